@@ -18,15 +18,16 @@ use clap::{Parser, ValueEnum, ValueHint};
 use rpc::admin_cli::OutputFormat;
 
 use crate::{
-    attestation, bmc_machine, boot_override, component_manager, compute_allocation, credential,
-    devenv, domain, dpa, dpu, dpu_remediation, expected_machines, expected_power_shelf,
-    expected_rack, expected_switch, extension_service, firmware, generate_shell_complete, host,
-    ib_partition, instance, instance_type, inventory, ip, ipxe_template, jump, machine,
-    machine_interfaces, machine_validation, managed_host, managed_switch, mlx, network_devices,
-    network_security_group, network_segment, nvl_logical_partition, nvl_partition,
-    operating_system, os_image, ping, power_shelf, rack, rack_firmware, redfish, resource_pool,
-    rms, route_server, scout_stream, set, site_explorer, sku, ssh, switch, tenant, tenant_keyset,
-    tpm_ca, trim_table, version, vpc, vpc_peering, vpc_prefix,
+    attestation, bmc_machine, boot_override, component_manager, compute_allocation,
+    console_recording, credential, devenv, domain, dpa, dpu, dpu_remediation, expected_machines,
+    expected_power_shelf, expected_rack, expected_switch, extension_service, firmware,
+    generate_shell_complete, host, ib_partition, instance, instance_type, inventory, ip,
+    ipxe_template, jump, machine, machine_interfaces, machine_validation, managed_host,
+    managed_switch, mlx, network_devices, network_security_group, network_segment,
+    nvl_logical_partition, nvl_partition, operating_system, os_image, ping, power_shelf, rack,
+    rack_firmware, redfish, resource_pool, rms, route_server, scout_stream, set, site_explorer,
+    sku, ssh, switch, tenant, tenant_keyset, tpm_ca, trim_table, version, vpc, vpc_peering,
+    vpc_prefix,
 };
 
 #[derive(Parser, Debug)]
@@ -293,6 +294,13 @@ pub enum CliCommand {
     #[clap(about = "SSH Util functions", subcommand)]
     Ssh(ssh::Cmd),
 
+    #[clap(
+        about = "ssh-console session recordings",
+        visible_alias = "cr",
+        subcommand
+    )]
+    ConsoleRecording(console_recording::Cmd),
+
     #[clap(about = "Power Shelf management", subcommand, visible_alias = "ps")]
     PowerShelf(power_shelf::Cmd),
 
@@ -16,6 +16,7 @@
  */
 
 use rpc::admin_cli::OutputFormat;
+use rpc::forge_tls_client::ForgeClientConfig;
 
 use crate::cfg::cli_options::SortField;
 use crate::rpc::ApiClient;
@@ -38,4 +39,7 @@ pub struct RuntimeConfig {
     pub extended: bool,
     pub cloud_unsafe_op_enabled: bool,
     pub sort_by: SortField,
+    // The TLS config used for carbide-api, which also authenticates
+    // us to other site services (e.g. ssh-console recordings).
+    pub client_config: ForgeClientConfig,
 }
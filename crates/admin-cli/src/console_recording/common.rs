@@ -0,0 +1,112 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use ::rpc::admin_cli::{CarbideCliError, CarbideCliResult};
+use ::rpc::forge_tls_client::ForgeClientConfig;
+use carbide_uuid::machine::MachineId;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+#[derive(Parser, Debug, Clone)]
+pub struct SshConsoleArgs {
+    #[clap(
+        long,
+        env = "CARBIDE_SSH_CONSOLE_URL",
+        help = "ssh-console recordings URL (e.g. https://ssh-console:8082). Requests are authenticated with the admin client certificate"
+    )]
+    pub ssh_console_url: String,
+}
+
+// RecordingMetadata mirrors the metadata ssh-console stores
+// alongside each asciicast recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingMetadata {
+    pub machine_id: String,
+    pub user: Option<String>,
+    pub peer_addr: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub file_name: String,
+}
+
+pub struct SshConsoleClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl SshConsoleClient {
+    // new builds a client which trusts the forge root CA and
+    // presents the same client certificate used for carbide-api,
+    // since ssh-console only serves recordings to admin certs.
+    pub fn new(args: SshConsoleArgs, client_config: &ForgeClientConfig) -> CarbideCliResult<Self> {
+        let read = |path: &str| {
+            std::fs::read(path)
+                .map_err(|e| CarbideCliError::GenericError(format!("failed to read {path}: {e}")))
+        };
+        let tls_error = |e: reqwest::Error| {
+            CarbideCliError::GenericError(format!("invalid TLS config for ssh-console: {e}"))
+        };
+
+        let root_ca = reqwest::Certificate::from_pem(&read(&client_config.root_ca_path)?)
+            .map_err(tls_error)?;
+        let mut builder = reqwest::Client::builder().tls_certs_only([root_ca]);
+        if let Some(client_cert) = &client_config.client_cert {
+            let mut identity = read(&client_cert.cert_path)?;
+            identity.push(b'\n');
+            identity.extend(read(&client_cert.key_path)?);
+            builder = builder.identity(reqwest::Identity::from_pem(&identity).map_err(tls_error)?);
+        }
+
+        Ok(Self {
+            client: builder.build().map_err(tls_error)?,
+            base_url: args.ssh_console_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    pub async fn list(&self, machine_id: &MachineId) -> CarbideCliResult<Vec<RecordingMetadata>> {
+        let body = self.get(&format!("recordings/{machine_id}")).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    pub async fn fetch(&self, machine_id: &MachineId, file_name: &str) -> CarbideCliResult<String> {
+        let body = self
+            .get(&format!("recordings/{machine_id}/{file_name}"))
+            .await?;
+        String::from_utf8(body).map_err(|e| {
+            CarbideCliError::GenericError(format!("recording {file_name} is not valid UTF-8: {e}"))
+        })
+    }
+
+    async fn get(&self, path: &str) -> CarbideCliResult<Vec<u8>> {
+        let url = format!("{}/{path}", self.base_url);
+        let response =
+            self.client.get(&url).send().await.map_err(|e| {
+                CarbideCliError::GenericError(format!("failed to fetch {url}: {e}"))
+            })?;
+        let status = response.status();
+        let body = response.bytes().await.map_err(|e| {
+            CarbideCliError::GenericError(format!("failed to read response from {url}: {e}"))
+        })?;
+        if !status.is_success() {
+            return Err(CarbideCliError::GenericError(format!(
+                "ssh-console returned {status} for {url}: {}",
+                String::from_utf8_lossy(&body)
+            )));
+        }
+        Ok(body.to_vec())
+    }
+}
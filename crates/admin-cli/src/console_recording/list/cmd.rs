@@ -0,0 +1,86 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use ::rpc::admin_cli::{CarbideCliResult, OutputFormat};
+use ::rpc::forge_tls_client::ForgeClientConfig;
+use prettytable::{Cell, Row, Table};
+
+use super::super::common::{RecordingMetadata, SshConsoleClient};
+use super::args::Args;
+
+pub async fn list(
+    args: Args,
+    format: OutputFormat,
+    client_config: &ForgeClientConfig,
+) -> CarbideCliResult<()> {
+    let recordings = SshConsoleClient::new(args.ssh_console, client_config)?
+        .list(&args.machine_id)
+        .await?;
+
+    match format {
+        OutputFormat::AsciiTable => {
+            recordings_to_table(&recordings).printstd();
+        }
+        OutputFormat::Csv => {
+            println!("started_at,ended_at,user,peer_addr,recording");
+            for recording in &recordings {
+                println!(
+                    "{},{},{},{},{}",
+                    recording.started_at,
+                    recording.ended_at.as_deref().unwrap_or_default(),
+                    recording.user.as_deref().unwrap_or_default(),
+                    recording.peer_addr,
+                    recording.file_name,
+                )
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&recordings)?)
+        }
+        OutputFormat::Yaml => {
+            println!("{}", serde_yaml::to_string(&recordings)?)
+        }
+    }
+
+    Ok(())
+}
+
+// recordings_to_table converts the recording metadata
+// returned by ssh-console into a pretty ASCII table.
+fn recordings_to_table(recordings: &[RecordingMetadata]) -> Table {
+    let mut table = Table::new();
+
+    table.add_row(Row::new(vec![
+        Cell::new("Started"),
+        Cell::new("Ended"),
+        Cell::new("User"),
+        Cell::new("Peer Address"),
+        Cell::new("Recording"),
+    ]));
+
+    for recording in recordings {
+        table.add_row(Row::new(vec![
+            Cell::new(&recording.started_at),
+            Cell::new(recording.ended_at.as_deref().unwrap_or("(in progress)")),
+            Cell::new(recording.user.as_deref().unwrap_or("(unknown)")),
+            Cell::new(&recording.peer_addr),
+            Cell::new(&recording.file_name),
+        ]));
+    }
+
+    table
+}
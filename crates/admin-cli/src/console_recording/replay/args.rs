@@ -0,0 +1,46 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use carbide_uuid::machine::MachineId;
+use clap::Parser;
+
+use super::super::common::SshConsoleArgs;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub ssh_console: SshConsoleArgs,
+    #[clap(help = "The machine the recording belongs to")]
+    pub machine_id: MachineId,
+    #[clap(
+        help = "The recording to replay, as shown by `console-recording list`. Defaults to the most recent recording."
+    )]
+    pub recording: Option<String>,
+    #[clap(long, default_value_t = 1.0, help = "Playback speed multiplier")]
+    pub speed: f64,
+    #[clap(
+        long,
+        default_value_t = 2.0,
+        help = "Cap pauses between output to this many seconds"
+    )]
+    pub idle_time_limit: f64,
+    #[clap(
+        long,
+        help = "Print what the user typed, with timestamps, instead of replaying the output"
+    )]
+    pub input: bool,
+}
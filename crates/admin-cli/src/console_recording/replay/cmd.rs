@@ -0,0 +1,95 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::Duration;
+
+use ::rpc::admin_cli::{CarbideCliError, CarbideCliResult};
+use ::rpc::forge_tls_client::ForgeClientConfig;
+use tokio::io::AsyncWriteExt;
+
+use super::super::common::SshConsoleClient;
+use super::args::Args;
+
+pub async fn replay(args: Args, client_config: &ForgeClientConfig) -> CarbideCliResult<()> {
+    if args.speed <= 0.0 {
+        return Err(CarbideCliError::GenericError(
+            "--speed must be greater than 0".to_string(),
+        ));
+    }
+
+    let client = SshConsoleClient::new(args.ssh_console, client_config)?;
+    let file_name = match args.recording {
+        Some(file_name) => file_name,
+        None => client
+            .list(&args.machine_id)
+            .await?
+            .pop()
+            .map(|recording| recording.file_name)
+            .ok_or_else(|| {
+                CarbideCliError::GenericError(format!(
+                    "no recordings found for machine {}",
+                    args.machine_id
+                ))
+            })?,
+    };
+    let events = parse_events(&client.fetch(&args.machine_id, &file_name).await?)?;
+
+    if args.input {
+        for event in events.iter().filter(|event| event.code == "i") {
+            println!("[{:>10.3}s] {:?}", event.time, event.data);
+        }
+        return Ok(());
+    }
+
+    let mut stdout = tokio::io::stdout();
+    let mut last_time = 0.0;
+    for event in events.iter().filter(|event| event.code == "o") {
+        let pause = (event.time - last_time).clamp(0.0, args.idle_time_limit.max(0.0));
+        last_time = event.time;
+        tokio::time::sleep(Duration::from_secs_f64(pause / args.speed)).await;
+        stdout.write_all(event.data.as_bytes()).await?;
+        stdout.flush().await?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Event {
+    pub time: f64,
+    pub code: String,
+    pub data: String,
+}
+
+// parse_events parses the events of an asciicast v2 recording,
+// skipping the header line.
+pub fn parse_events(cast: &str) -> CarbideCliResult<Vec<Event>> {
+    let mut lines = cast.lines().filter(|line| !line.trim().is_empty());
+    let header: serde_json::Value = serde_json::from_str(lines.next().unwrap_or("{}"))?;
+    if header.get("version").and_then(serde_json::Value::as_u64) != Some(2) {
+        return Err(CarbideCliError::GenericError(
+            "recording is not in asciicast v2 format".to_string(),
+        ));
+    }
+
+    lines
+        .map(|line| {
+            let (time, code, data) = serde_json::from_str::<(f64, String, String)>(line)?;
+            Ok(Event { time, code, data })
+        })
+        .collect()
+}
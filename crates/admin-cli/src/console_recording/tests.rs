@@ -0,0 +1,173 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// The intent of the tests.rs file is to test the integrity of the
+// command, including things like basic structure parsing, enum
+// translations, and any external input validators that are
+// configured. Specific "categories" are:
+//
+// Command Structure - Baseline debug_assert() of the entire command.
+// Argument Parsing  - Ensure required/optional arg combinations parse correctly.
+// Recording Parsing - Ensure asciicast recordings are read correctly.
+
+use clap::{CommandFactory, Parser};
+
+use super::*;
+
+const MACHINE_ID: &str = "fm100ht038bg3qsho433vkg684heguv282qaggmrsh2ugn1qk096n2c6hcg";
+
+// verify_cmd_structure runs a baseline clap debug_assert()
+// to do basic command configuration checking and validation,
+// ensuring things like unique argument definitions, group
+// configurations, argument references, etc. Things that would
+// otherwise be missed until runtime.
+#[test]
+fn verify_cmd_structure() {
+    Cmd::command().debug_assert();
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Argument Parsing
+//
+// This section contains tests specific to argument parsing,
+// including testing required arguments, as well as optional
+// flag-specific checking.
+
+// parse_list ensures list parses with the ssh-console URL
+// and a machine ID.
+#[test]
+fn parse_list() {
+    let cmd = Cmd::try_parse_from([
+        "console-recording",
+        "list",
+        "--ssh-console-url",
+        "https://ssh-console:8082",
+        MACHINE_ID,
+    ])
+    .expect("should parse list");
+
+    match cmd {
+        Cmd::List(args) => {
+            assert_eq!(args.ssh_console.ssh_console_url, "https://ssh-console:8082");
+            assert_eq!(args.machine_id.to_string(), MACHINE_ID);
+        }
+        _ => panic!("expected List variant"),
+    }
+}
+
+// parse_replay_defaults ensures replay parses without a
+// recording name, and uses the default playback options.
+#[test]
+fn parse_replay_defaults() {
+    let cmd = Cmd::try_parse_from([
+        "console-recording",
+        "replay",
+        "--ssh-console-url",
+        "https://ssh-console:8082",
+        MACHINE_ID,
+    ])
+    .expect("should parse replay");
+
+    match cmd {
+        Cmd::Replay(args) => {
+            assert!(args.recording.is_none());
+            assert_eq!(args.speed, 1.0);
+            assert_eq!(args.idle_time_limit, 2.0);
+            assert!(!args.input);
+        }
+        _ => panic!("expected Replay variant"),
+    }
+}
+
+// parse_replay_with_options ensures replay parses with a
+// recording name and playback options.
+#[test]
+fn parse_replay_with_options() {
+    let cmd = Cmd::try_parse_from([
+        "console-recording",
+        "replay",
+        "--ssh-console-url",
+        "https://ssh-console:8082",
+        MACHINE_ID,
+        "20260101T000000.000Z_session.cast",
+        "--speed",
+        "4",
+        "--input",
+    ])
+    .expect("should parse replay with options");
+
+    match cmd {
+        Cmd::Replay(args) => {
+            assert_eq!(
+                args.recording.as_deref(),
+                Some("20260101T000000.000Z_session.cast")
+            );
+            assert_eq!(args.speed, 4.0);
+            assert!(args.input);
+        }
+        _ => panic!("expected Replay variant"),
+    }
+}
+
+// parse_missing_machine_id_fails ensures a machine ID is required.
+#[test]
+fn parse_missing_machine_id_fails() {
+    let result = Cmd::try_parse_from([
+        "console-recording",
+        "list",
+        "--ssh-console-url",
+        "https://ssh-console:8082",
+    ]);
+    assert!(result.is_err(), "should fail without machine ID");
+}
+
+/////////////////////////////////////////////////////////////////////////////
+// Recording Parsing
+
+// parse_asciicast_events ensures events are read from an
+// asciicast v2 recording, skipping the header.
+#[test]
+fn parse_asciicast_events() {
+    let cast = concat!(
+        "{\"version\":2,\"width\":80,\"height\":24,\"timestamp\":1767225600}\n",
+        "[0.5,\"o\",\"login: \"]\n",
+        "[1.25,\"i\",\"root\\r\"]\n",
+    );
+    let events = replay::cmd::parse_events(cast).expect("should parse recording");
+    assert_eq!(
+        events,
+        vec![
+            replay::cmd::Event {
+                time: 0.5,
+                code: "o".to_string(),
+                data: "login: ".to_string(),
+            },
+            replay::cmd::Event {
+                time: 1.25,
+                code: "i".to_string(),
+                data: "root\r".to_string(),
+            },
+        ]
+    );
+}
+
+// parse_asciicast_rejects_other_versions ensures recordings
+// in other formats are rejected.
+#[test]
+fn parse_asciicast_rejects_other_versions() {
+    assert!(replay::cmd::parse_events("{\"version\":1}\n").is_err());
+}
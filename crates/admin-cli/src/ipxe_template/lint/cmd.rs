@@ -0,0 +1,143 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use ::rpc::admin_cli::{CarbideCliError, OutputFormat};
+use carbide_ipxe_renderer::{IpxeTemplate, IpxeTemplateScope, LintSeverity, lint_template};
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+
+use super::args::Args;
+use crate::rpc::ApiClient;
+
+#[derive(Serialize)]
+struct Finding {
+    template: String,
+    severity: LintSeverity,
+    message: String,
+}
+
+pub async fn handle_lint(
+    opts: Args,
+    format: OutputFormat,
+    api_client: &ApiClient,
+) -> Result<(), CarbideCliError> {
+    let templates = match (&opts.file, opts.id.as_deref()) {
+        (Some(path), _) => {
+            let yaml = std::fs::read_to_string(path).map_err(|e| {
+                CarbideCliError::GenericError(format!(
+                    "Failed to read file {}: {e}",
+                    path.display()
+                ))
+            })?;
+            carbide_ipxe_renderer::parse_templates(&yaml).map_err(|e| {
+                CarbideCliError::GenericError(format!(
+                    "Invalid templates file {}: {e}",
+                    path.display()
+                ))
+            })?
+        }
+        (None, Some(id)) if !id.is_empty() => vec![fetch_one(id, api_client).await?],
+        (None, _) => api_client
+            .0
+            .list_ipxe_templates()
+            .await?
+            .templates
+            .into_iter()
+            .map(from_rpc)
+            .collect(),
+    };
+
+    let findings: Vec<Finding> = templates
+        .iter()
+        .flat_map(|template| {
+            lint_template(template)
+                .into_iter()
+                .map(move |issue| Finding {
+                    template: template.name.clone(),
+                    severity: issue.severity,
+                    message: issue.message,
+                })
+        })
+        .collect();
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&findings)?);
+    } else if findings.is_empty() {
+        println!("No issues found in {} iPXE template(s).", templates.len());
+    } else {
+        let mut table = Table::new();
+        table.set_titles(Row::new(vec![
+            Cell::new("Template"),
+            Cell::new("Severity"),
+            Cell::new("Message"),
+        ]));
+        for finding in &findings {
+            table.add_row(Row::new(vec![
+                Cell::new(&finding.template),
+                Cell::new(&finding.severity.to_string()),
+                Cell::new(&finding.message),
+            ]));
+        }
+        table.printstd();
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == LintSeverity::Error)
+        .count();
+    if errors > 0 {
+        return Err(CarbideCliError::GenericError(format!(
+            "{errors} iPXE template lint error(s)"
+        )));
+    }
+    Ok(())
+}
+
+async fn fetch_one(id_str: &str, api_client: &ApiClient) -> Result<IpxeTemplate, CarbideCliError> {
+    let id: carbide_uuid::ipxe_template::IpxeTemplateId = id_str
+        .parse()
+        .map_err(|_| CarbideCliError::GenericError(format!("invalid template ID: {}", id_str)))?;
+
+    match api_client
+        .0
+        .get_ipxe_template(rpc::forge::GetIpxeTemplateRequest { id: Some(id) })
+        .await
+    {
+        Ok(template) => Ok(from_rpc(template)),
+        Err(status) if status.code() == tonic::Code::NotFound => Err(
+            CarbideCliError::GenericError(format!("iPXE template not found: {}", id_str)),
+        ),
+        Err(err) => Err(CarbideCliError::from(err)),
+    }
+}
+
+fn from_rpc(template: rpc::forge::IpxeTemplate) -> IpxeTemplate {
+    let scope = match rpc::forge::IpxeTemplateScope::try_from(template.scope) {
+        Ok(rpc::forge::IpxeTemplateScope::Public) => IpxeTemplateScope::Public,
+        _ => IpxeTemplateScope::Internal,
+    };
+    IpxeTemplate {
+        id: template.id.map(|id| id.to_string()).unwrap_or_default(),
+        name: template.name,
+        description: template.description,
+        template: template.template,
+        reserved_params: template.reserved_params,
+        required_params: template.required_params,
+        required_artifacts: template.required_artifacts,
+        scope,
+    }
+}
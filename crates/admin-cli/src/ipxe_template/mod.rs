@@ -15,6 +15,7 @@
  * limitations under the License.
  */
 
+mod lint;
 mod show;
 
 use clap::Parser;
@@ -29,4 +30,6 @@ pub enum Cmd {
         visible_alias = "s"
     )]
     Show(show::Args),
+    #[clap(about = "Check iPXE templates for syntax errors and undeclared parameters.")]
+    Lint(lint::Args),
 }
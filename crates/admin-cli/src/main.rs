@@ -43,6 +43,7 @@ mod boot_override;
 mod cfg;
 mod component_manager;
 mod compute_allocation;
+mod console_recording;
 mod credential;
 mod debug_bundle;
 mod devenv;
@@ -198,6 +199,7 @@ async fn main() -> color_eyre::Result<()> {
             extended: config.extended,
             cloud_unsafe_op_enabled: config.cloud_unsafe_op.is_some(),
             sort_by: config.sort_by,
+            client_config,
         },
         output_file: get_output_file_or_stdout(config.output.as_deref()).await?,
     };
@@ -210,6 +212,7 @@ async fn main() -> color_eyre::Result<()> {
         CliCommand::Credential(cmd) => cmd.dispatch(ctx).await?,
         CliCommand::ComponentManager(cmd) => cmd.dispatch(ctx).await?,
         CliCommand::ComputeAllocation(cmd) => cmd.dispatch(ctx).await?,
+        CliCommand::ConsoleRecording(cmd) => cmd.dispatch(ctx).await?,
         CliCommand::DevEnv(cmd) => cmd.dispatch(ctx).await?,
         CliCommand::Domain(cmd) => cmd.dispatch(ctx).await?,
         CliCommand::Dpa(cmd) => cmd.dispatch(ctx).await?,
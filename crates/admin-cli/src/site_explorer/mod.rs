@@ -25,6 +25,8 @@ mod have_credentials;
 mod is_bmc_in_managed_host;
 mod re_explore;
 mod remediation;
+mod report_history;
+mod swept_endpoints;
 
 #[cfg(test)]
 mod tests;
@@ -54,6 +56,16 @@ pub enum Cmd {
     Delete(delete::Args),
     #[clap(about = "Control remediation actions for an explored endpoint.")]
     Remediation(remediation::Args),
+    #[clap(
+        about = "Manage BMC endpoints found by the subnet sweep that are not known to carbide.",
+        subcommand
+    )]
+    SweptEndpoints(swept_endpoints::Args),
+    #[clap(
+        about = "Show or acknowledge hardware changes between exploration reports of an endpoint.",
+        subcommand
+    )]
+    ReportHistory(report_history::Args),
     IsBmcInManagedHost(is_bmc_in_managed_host::Args),
     HaveCredentials(have_credentials::Args),
     CopyBfbToDpuRshim(copy_bfb_to_dpu_rshim::Args),
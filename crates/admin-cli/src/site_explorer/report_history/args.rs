@@ -0,0 +1,42 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use clap::Parser;
+
+#[derive(Parser, Debug, PartialEq)]
+pub enum Args {
+    #[clap(
+        about = "Show the stored exploration reports of an endpoint and what changed between them."
+    )]
+    Show(ShowArgs),
+    #[clap(
+        about = "Acknowledge all hardware changes of an endpoint. This clears the HardwareDrift health alert."
+    )]
+    Acknowledge(AcknowledgeArgs),
+}
+
+#[derive(Parser, Debug, PartialEq)]
+pub struct ShowArgs {
+    #[clap(long, help = "IP address of the explored endpoint")]
+    pub address: String,
+}
+
+#[derive(Parser, Debug, PartialEq)]
+pub struct AcknowledgeArgs {
+    #[clap(long, help = "IP address of the explored endpoint")]
+    pub address: String,
+}
@@ -0,0 +1,108 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use ::rpc::admin_cli::{CarbideCliResult, OutputFormat};
+use ::rpc::site_explorer::{ExplorationReportChange, ExploredEndpointReportHistoryRecord};
+use prettytable::{Table, row};
+
+use super::args::Args;
+use crate::rpc::ApiClient;
+use crate::{async_write, async_writeln};
+
+pub async fn report_history(
+    api_client: &ApiClient,
+    output_file: &mut Box<dyn tokio::io::AsyncWrite + Unpin>,
+    output_format: OutputFormat,
+    mode: Args,
+) -> CarbideCliResult<()> {
+    match mode {
+        Args::Show(args) => {
+            let records = api_client
+                .0
+                .find_explored_endpoint_report_history(args.address.clone())
+                .await?
+                .records;
+            if output_format == OutputFormat::Json {
+                async_writeln!(output_file, "{}", serde_json::to_string_pretty(&records)?)?;
+                return Ok(());
+            }
+            if records.is_empty() {
+                async_writeln!(
+                    output_file,
+                    "No hardware changes recorded for {}.",
+                    args.address
+                )?;
+                return Ok(());
+            }
+            async_write!(output_file, "{}", convert_records_to_table(&records))?;
+        }
+        Args::Acknowledge(args) => {
+            let acknowledged = api_client
+                .0
+                .acknowledge_explored_endpoint_drift(args.address.clone())
+                .await?
+                .acknowledged;
+            async_writeln!(
+                output_file,
+                "Acknowledged {acknowledged} hardware changes of {}",
+                args.address
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn convert_records_to_table(records: &[ExploredEndpointReportHistoryRecord]) -> Box<Table> {
+    let mut table = Table::new();
+    table.set_titles(row![
+        "Time",
+        "Report Version",
+        "Machine State",
+        "Unexpected",
+        "Acknowledged",
+        "Changes"
+    ]);
+    for record in records {
+        table.add_row(row![
+            record
+                .time
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            record.report_version,
+            record.machine_state.as_deref().unwrap_or("-"),
+            record.unexpected,
+            record.acknowledged,
+            record
+                .changes
+                .iter()
+                .map(format_change)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ]);
+    }
+    Box::new(table)
+}
+
+fn format_change(change: &ExplorationReportChange) -> String {
+    let before = change.before.as_deref().unwrap_or("<none>");
+    let after = change.after.as_deref().unwrap_or("<none>");
+    format!(
+        "{} {}: {before} -> {after}",
+        change.category, change.component
+    )
+}
@@ -0,0 +1,34 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use clap::Parser;
+
+#[derive(Parser, Debug, PartialEq)]
+pub enum Args {
+    #[clap(about = "Show BMC endpoints found by the subnet sweep that are not known to carbide.")]
+    Show,
+    #[clap(
+        about = "Dismiss a swept BMC endpoint. It is recorded again if it still answers the next sweep."
+    )]
+    Dismiss(DismissArgs),
+}
+
+#[derive(Parser, Debug, PartialEq)]
+pub struct DismissArgs {
+    #[clap(long, help = "IP address of the swept endpoint to dismiss")]
+    pub address: String,
+}
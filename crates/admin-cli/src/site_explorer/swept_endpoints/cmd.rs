@@ -0,0 +1,77 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use ::rpc::admin_cli::{CarbideCliResult, OutputFormat};
+use ::rpc::site_explorer::SweptBmcEndpoint;
+use prettytable::{Table, row};
+
+use super::args::Args;
+use crate::rpc::ApiClient;
+use crate::{async_write, async_writeln};
+
+pub async fn swept_endpoints(
+    api_client: &ApiClient,
+    output_file: &mut Box<dyn tokio::io::AsyncWrite + Unpin>,
+    output_format: OutputFormat,
+    mode: Args,
+) -> CarbideCliResult<()> {
+    match mode {
+        Args::Show => {
+            let endpoints = api_client.0.find_swept_bmc_endpoints().await?.endpoints;
+            if output_format == OutputFormat::Json {
+                async_writeln!(output_file, "{}", serde_json::to_string_pretty(&endpoints)?)?;
+                return Ok(());
+            }
+            if endpoints.is_empty() {
+                async_writeln!(output_file, "No unclaimed swept BMC endpoints.")?;
+                return Ok(());
+            }
+            async_write!(output_file, "{}", convert_endpoints_to_table(&endpoints))?;
+        }
+        Args::Dismiss(args) => {
+            api_client
+                .0
+                .delete_swept_bmc_endpoint(args.address.clone())
+                .await?;
+            async_writeln!(output_file, "Dismissed swept endpoint {}", args.address)?;
+        }
+    }
+    Ok(())
+}
+
+fn convert_endpoints_to_table(endpoints: &[SweptBmcEndpoint]) -> Box<Table> {
+    let mut table = Table::new();
+    table.set_titles(row!["Address", "Port", "Vendor", "First Seen", "Last Seen"]);
+    for endpoint in endpoints {
+        table.add_row(row![
+            endpoint.address,
+            endpoint.port,
+            endpoint.vendor.as_deref().unwrap_or("Unknown"),
+            endpoint
+                .first_seen
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            endpoint
+                .last_seen
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        ]);
+    }
+    Box::new(table)
+}
@@ -183,6 +183,68 @@ fn parse_remediation() {
     }
 }
 
+// parse_swept_endpoints_show ensures swept-endpoints show parses.
+#[test]
+fn parse_swept_endpoints_show() {
+    let cmd = Cmd::try_parse_from(["site-explorer", "swept-endpoints", "show"])
+        .expect("should parse swept-endpoints show");
+
+    assert!(matches!(
+        cmd,
+        Cmd::SweptEndpoints(swept_endpoints::Args::Show)
+    ));
+}
+
+// parse_swept_endpoints_dismiss ensures swept-endpoints
+// dismiss parses with address.
+#[test]
+fn parse_swept_endpoints_dismiss() {
+    let cmd = Cmd::try_parse_from([
+        "site-explorer",
+        "swept-endpoints",
+        "dismiss",
+        "--address",
+        "192.168.1.100",
+    ])
+    .expect("should parse swept-endpoints dismiss");
+
+    match cmd {
+        Cmd::SweptEndpoints(swept_endpoints::Args::Dismiss(args)) => {
+            assert_eq!(args.address, "192.168.1.100");
+        }
+        _ => panic!("expected SweptEndpoints Dismiss variant"),
+    }
+}
+
+// parse_report_history_show ensures report-history show
+// parses with address.
+#[test]
+fn parse_report_history_show() {
+    let cmd = Cmd::try_parse_from([
+        "site-explorer",
+        "report-history",
+        "show",
+        "--address",
+        "192.168.1.100",
+    ])
+    .expect("should parse report-history show");
+
+    match cmd {
+        Cmd::ReportHistory(report_history::Args::Show(args)) => {
+            assert_eq!(args.address, "192.168.1.100");
+        }
+        _ => panic!("expected ReportHistory Show variant"),
+    }
+}
+
+// parse_report_history_acknowledge_missing_address_fails ensures
+// report-history acknowledge fails without address.
+#[test]
+fn parse_report_history_acknowledge_missing_address_fails() {
+    let result = Cmd::try_parse_from(["site-explorer", "report-history", "acknowledge"]);
+    assert!(result.is_err(), "should fail without --address");
+}
+
 // parse_explore_missing_address_fails ensures explore
 // fails without address.
 #[test]
@@ -21,7 +21,7 @@ pub mod proto {
 
 use carbide_rpc_utils::dhcp::{
     DhcpConfig as ModelDhcpConfig, HostConfig as ModelHostConfig,
-    InterfaceInfo as ModelInterfaceInfo,
+    InterfaceInfo as ModelInterfaceInfo, InterfaceIpv6Info as ModelInterfaceIpv6Info,
 };
 use carbide_uuid::machine::MachineInterfaceId;
 use proto::dhcp_server_control_client::DhcpServerControlClient;
@@ -47,6 +47,11 @@ impl From<ModelDhcpConfig> for proto::DhcpConfig {
                 .collect(),
             carbide_provisioning_server_ipv4: c.carbide_provisioning_server_ipv4.to_string(),
             carbide_dhcp_server: c.carbide_dhcp_server.to_string(),
+            carbide_nameservers_ipv6: c
+                .carbide_nameservers_ipv6
+                .iter()
+                .map(|ip| ip.to_string())
+                .collect(),
         }
     }
 }
@@ -60,6 +65,16 @@ impl From<ModelInterfaceInfo> for proto::InterfaceInfo {
             fqdn: i.fqdn,
             booturl: i.booturl,
             mtu: i.mtu,
+            ipv6: i.ipv6.map(Into::into),
+        }
+    }
+}
+
+impl From<ModelInterfaceIpv6Info> for proto::InterfaceIpv6Info {
+    fn from(i: ModelInterfaceIpv6Info) -> Self {
+        proto::InterfaceIpv6Info {
+            address: i.address.to_string(),
+            prefix: i.prefix,
         }
     }
 }
@@ -875,12 +875,20 @@ async fn update_dhcp_via_grpc(
             )
         })?;
 
-    let dhcp_config = carbide_rpc_utils::dhcp::DhcpConfig::from_forge_dhcp_config(
+    let mut dhcp_config = carbide_rpc_utils::dhcp::DhcpConfig::from_forge_dhcp_config(
         pxe_ip_v4,
         ntpservers_v4,
         nameservers_v4,
         loopback_ip,
     )?;
+    dhcp_config.carbide_nameservers_ipv6 = service_addrs
+        .nameservers
+        .iter()
+        .filter_map(|x| match x {
+            IpAddr::V6(x) => Some(*x),
+            _ => None,
+        })
+        .collect();
     let mut host_config = carbide_rpc_utils::dhcp::HostConfig::try_from(
         network_config.clone(),
         hbn_device_names.reps[0],
@@ -3053,6 +3061,7 @@ mod tests {
             rebinding_time_secs: 432000,
             carbide_api_url: None,
             carbide_dhcp_server: Ipv4Addr::from([10, 217, 5, 39]),
+            carbide_nameservers_ipv6: vec![],
         };
 
         let mut network_config = rpc::ManagedHostNetworkConfigResponse {
@@ -3242,6 +3251,7 @@ mod tests {
             rebinding_time_secs: 432000,
             carbide_api_url: None,
             carbide_dhcp_server: Ipv4Addr::from([10, 217, 5, 39]),
+            carbide_nameservers_ipv6: vec![],
         };
         let dhcp_contents = super::read_limited(g.path())?;
         assert!(dhcp_contents.contains("vlan196"));
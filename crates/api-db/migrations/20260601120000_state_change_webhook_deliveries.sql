@@ -0,0 +1,20 @@
+-- Durable retry queue for the state change webhook hook.
+-- Each row is a pending delivery of a state change payload to a configured webhook endpoint.
+-- Rows are deleted once delivered, or once the maximum number of attempts has been exhausted.
+CREATE TABLE state_change_webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    endpoint TEXT NOT NULL,
+    object_type TEXT NOT NULL,
+    object_id TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX state_change_webhook_deliveries_next_attempt_at_idx
+    ON state_change_webhook_deliveries (next_attempt_at);
+
+CREATE INDEX state_change_webhook_deliveries_endpoint_idx
+    ON state_change_webhook_deliveries (endpoint);
@@ -0,0 +1,33 @@
+-- Introduces scheduling priority and deadline columns for all queued object tables
+
+ALTER TABLE machine_state_controller_queued_objects
+    ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN deadline timestamptz NULL DEFAULT NULL;
+
+ALTER TABLE network_segments_controller_queued_objects
+    ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN deadline timestamptz NULL DEFAULT NULL;
+
+ALTER TABLE ib_partition_controller_queued_objects
+    ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN deadline timestamptz NULL DEFAULT NULL;
+
+ALTER TABLE dpa_interfaces_controller_queued_objects
+    ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN deadline timestamptz NULL DEFAULT NULL;
+
+ALTER TABLE power_shelf_controller_queued_objects
+    ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN deadline timestamptz NULL DEFAULT NULL;
+
+ALTER TABLE switch_controller_queued_objects
+    ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN deadline timestamptz NULL DEFAULT NULL;
+
+ALTER TABLE rack_controller_queued_objects
+    ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN deadline timestamptz NULL DEFAULT NULL;
+
+ALTER TABLE attestation_controller_queued_objects
+    ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN deadline timestamptz NULL DEFAULT NULL;
@@ -0,0 +1,12 @@
+-- BMC endpoints found by the site-explorer subnet sweep.
+-- Each row is an address that answered a Redfish service root probe but is not
+-- known to carbide through a machine interface or an explored endpoint.
+-- Rows are removed once the address is claimed by creating a machine interface for it,
+-- or when an operator dismisses them.
+CREATE TABLE swept_bmc_endpoints (
+    address INET PRIMARY KEY,
+    port INTEGER NOT NULL,
+    vendor TEXT,
+    first_seen TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
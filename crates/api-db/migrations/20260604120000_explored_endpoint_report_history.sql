@@ -0,0 +1,27 @@
+CREATE TABLE explored_endpoint_report_history (
+    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    address INET NOT NULL,
+    report_version VARCHAR(64) NOT NULL,
+    report jsonb NOT NULL,
+    diff jsonb NOT NULL,
+    machine_state TEXT,
+    unexpected BOOLEAN NOT NULL DEFAULT false,
+    acknowledged BOOLEAN NOT NULL DEFAULT false,
+    time TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_explored_endpoint_report_history_address ON explored_endpoint_report_history (address);
+
+CREATE OR REPLACE FUNCTION explored_endpoint_report_history_keep_limit()
+RETURNS TRIGGER AS
+$body$
+BEGIN
+    DELETE FROM explored_endpoint_report_history WHERE address=NEW.address AND id NOT IN (SELECT id from explored_endpoint_report_history where address=NEW.address ORDER BY id DESC LIMIT 50);
+    RETURN NULL;
+END;
+$body$
+LANGUAGE plpgsql;
+
+CREATE TRIGGER t_explored_endpoint_report_history_keep_limit
+  AFTER INSERT ON explored_endpoint_report_history
+  FOR EACH ROW EXECUTE PROCEDURE explored_endpoint_report_history_keep_limit();
@@ -0,0 +1,45 @@
+-- Version of the DNS snapshot carbide-dns caches: bumped by every statement which can change
+-- the zones or records DNS serves, so that carbide-dns can tell whether its copy is outdated
+-- without reading all of them.
+CREATE SEQUENCE dns_snapshot_version;
+
+CREATE OR REPLACE FUNCTION bump_dns_snapshot_version()
+RETURNS TRIGGER AS
+$body$
+BEGIN
+    PERFORM nextval('dns_snapshot_version');
+    RETURN NULL;
+END;
+$body$
+LANGUAGE plpgsql;
+
+CREATE TRIGGER t_domains_dns_snapshot_version
+  AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON domains
+  FOR EACH STATEMENT EXECUTE PROCEDURE bump_dns_snapshot_version();
+
+CREATE TRIGGER t_machine_interfaces_dns_snapshot_version
+  AFTER INSERT OR DELETE OR TRUNCATE
+     OR UPDATE OF hostname, domain_id, machine_id, primary_interface, interface_type
+  ON machine_interfaces
+  FOR EACH STATEMENT EXECUTE PROCEDURE bump_dns_snapshot_version();
+
+CREATE TRIGGER t_machine_interface_addresses_dns_snapshot_version
+  AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON machine_interface_addresses
+  FOR EACH STATEMENT EXECUTE PROCEDURE bump_dns_snapshot_version();
+
+CREATE TRIGGER t_dns_record_metadata_dns_snapshot_version
+  AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON dns_record_metadata
+  FOR EACH STATEMENT EXECUTE PROCEDURE bump_dns_snapshot_version();
+
+CREATE TRIGGER t_dns_record_types_dns_snapshot_version
+  AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON dns_record_types
+  FOR EACH STATEMENT EXECUTE PROCEDURE bump_dns_snapshot_version();
+
+-- Reverse zones are synthesized from the prefixes of live segments.
+CREATE TRIGGER t_network_prefixes_dns_snapshot_version
+  AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON network_prefixes
+  FOR EACH STATEMENT EXECUTE PROCEDURE bump_dns_snapshot_version();
+
+CREATE TRIGGER t_network_segments_dns_snapshot_version
+  AFTER INSERT OR DELETE OR TRUNCATE OR UPDATE OF subdomain_id, deleted ON network_segments
+  FOR EACH STATEMENT EXECUTE PROCEDURE bump_dns_snapshot_version();
@@ -15,10 +15,24 @@
  * limitations under the License.
  */
 
+use crate::DatabaseError;
+use crate::db_read::DbReader;
+
 pub mod domain;
 pub mod domain_metadata;
 pub mod resource_record;
 
+/// Version of the zones and records served by DNS. Every statement which can change them bumps
+/// it, possibly before the change commits.
+pub async fn snapshot_version(txn: impl DbReader<'_>) -> Result<i64, DatabaseError> {
+    // Zero until the sequence is first used, its last_value is the start value before that
+    let query = "SELECT CASE WHEN is_called THEN last_value ELSE 0 END FROM dns_snapshot_version";
+    sqlx::query_scalar(query)
+        .fetch_one(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))
+}
+
 pub fn normalize_domain(name: &str) -> String {
     let normalize_domain = name.trim_end_matches('.').to_lowercase();
     tracing::debug!("Normalized domain name: {} to: {}", name, normalize_domain);
@@ -18,6 +18,7 @@ use std::net::IpAddr;
 
 use carbide_uuid::domain::DomainId;
 use dns_record::SoaRecord;
+use ipnetwork::IpNetwork;
 use sqlx::postgres::PgRow;
 use sqlx::{Error, FromRow, Row};
 
@@ -54,6 +55,24 @@ impl<'r> FromRow<'r, PgRow> for DbSoaRecord {
     }
 }
 
+/// A network segment prefix and the SOA of the segment's domain, if it has one.
+/// Reverse zones are synthesized from these.
+pub struct DbReverseZonePrefix {
+    pub prefix: IpNetwork,
+    pub soa: Option<SoaRecord>,
+}
+
+impl<'r> FromRow<'r, PgRow> for DbReverseZonePrefix {
+    fn from_row(row: &'r PgRow) -> Result<Self, Error> {
+        let prefix: IpNetwork = row.try_get("prefix")?;
+        let soa: Option<sqlx::types::Json<SoaRecord>> = row.try_get("soa")?;
+        Ok(DbReverseZonePrefix {
+            prefix,
+            soa: soa.map(|soa| soa.0),
+        })
+    }
+}
+
 impl<'r> FromRow<'r, PgRow> for DbResourceRecord {
     fn from_row(row: &'r PgRow) -> Result<Self, Error> {
         // Stored as IP address in the database
@@ -111,6 +130,50 @@ pub async fn find_record(
 
     Ok(result)
 }
+
+/// Forward records which resolve to `address`. They are the targets of its PTR records.
+pub async fn find_records_by_address(
+    txn: impl DbReader<'_>,
+    address: IpAddr,
+) -> Result<Vec<DbResourceRecord>, DatabaseError> {
+    let query = r#"
+    SELECT
+     q_name,
+     resource_record,
+     domain_id,
+     COALESCE(ttl, 300) as ttl,
+     COALESCE(q_type, CASE WHEN family(resource_record) = 6 THEN 'AAAA' ELSE 'A' END) as q_type
+     from dns_records WHERE resource_record=$1
+     ORDER BY q_name"#;
+
+    sqlx::query_as::<_, DbResourceRecord>(query)
+        .bind(address)
+        .fetch_all(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))
+}
+
+/// Prefixes of live network segments which overlap `network`, or all of them if None.
+pub async fn find_reverse_zone_prefixes(
+    txn: impl DbReader<'_>,
+    network: Option<IpNetwork>,
+) -> Result<Vec<DbReverseZonePrefix>, DatabaseError> {
+    let query = r#"
+        SELECT np.prefix, d.soa
+        FROM network_prefixes np
+        JOIN network_segments ns ON ns.id = np.segment_id
+        LEFT JOIN domains d ON d.id = ns.subdomain_id AND d.deleted IS NULL
+        WHERE ns.deleted IS NULL AND ($1::inet IS NULL OR np.prefix && $1)
+        ORDER BY np.prefix
+    "#;
+
+    sqlx::query_as::<_, DbReverseZonePrefix>(query)
+        .bind(network)
+        .fetch_all(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))
+}
+
 pub async fn get_all_records_all_domains(
     txn: impl DbReader<'_>,
 ) -> Result<Vec<DbResourceRecord>, DatabaseError> {
@@ -130,6 +193,33 @@ pub async fn get_all_records_all_domains(
         .map_err(|e| DatabaseError::query(query, e))
 }
 
+/// A page of the records of all domains, in a stable order.
+pub async fn get_all_records_all_domains_page(
+    txn: impl DbReader<'_>,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<DbResourceRecord>, DatabaseError> {
+    let query = r#"
+        SELECT * FROM (
+            SELECT dr.q_name, dr.resource_record, dr.domain_id,
+                   COALESCE(dr.ttl, 300) as ttl,
+                   COALESCE(dr.q_type, CASE WHEN family(dr.resource_record) = 6 THEN 'AAAA' ELSE 'A' END) as q_type
+            FROM dns_records dr
+            JOIN domains d ON d.id = dr.domain_id
+            WHERE d.deleted IS NULL
+        ) r
+        ORDER BY r.q_name, r.q_type, r.resource_record, r.domain_id, r.ttl
+        OFFSET $1 LIMIT $2
+    "#;
+
+    sqlx::query_as::<_, DbResourceRecord>(query)
+        .bind(offset)
+        .bind(limit)
+        .fetch_all(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))
+}
+
 pub async fn get_all_records(
     txn: impl DbReader<'_>,
     query_name: &str,
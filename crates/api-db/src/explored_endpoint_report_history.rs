@@ -0,0 +1,128 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Versioned exploration reports, together with the hardware drift between them.
+//!
+//! Site-explorer stores a record whenever a successful exploration finds that
+//! the firmware inventory, NICs, PCIe devices, boot order or serial numbers of
+//! an endpoint changed. The newest 50 records per endpoint are retained.
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use config_version::ConfigVersion;
+use model::site_explorer::EndpointExplorationReport;
+use model::site_explorer::report_history::{
+    ExplorationReportDiff, ExploredEndpointReportHistoryRecord,
+};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, PgConnection, Row};
+
+use crate::DatabaseError;
+
+#[derive(Debug, Clone)]
+struct DbReportHistoryRecord(ExploredEndpointReportHistoryRecord);
+
+impl<'r> FromRow<'r, PgRow> for DbReportHistoryRecord {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let report: sqlx::types::Json<EndpointExplorationReport> = row.try_get("report")?;
+        let diff: sqlx::types::Json<ExplorationReportDiff> = row.try_get("diff")?;
+        let time: DateTime<Utc> = row.try_get("time")?;
+        Ok(DbReportHistoryRecord(ExploredEndpointReportHistoryRecord {
+            id: row.try_get("id")?,
+            address: row.try_get("address")?,
+            report_version: row.try_get("report_version")?,
+            report: report.0,
+            diff: diff.0,
+            machine_state: row.try_get("machine_state")?,
+            unexpected: row.try_get("unexpected")?,
+            acknowledged: row.try_get("acknowledged")?,
+            time,
+        }))
+    }
+}
+
+/// Stores a new version of the report of an endpoint, together with the diff to
+/// the previously stored version
+pub async fn persist(
+    txn: &mut PgConnection,
+    address: IpAddr,
+    report_version: ConfigVersion,
+    report: &EndpointExplorationReport,
+    diff: &ExplorationReportDiff,
+    machine_state: Option<&str>,
+    unexpected: bool,
+) -> Result<(), DatabaseError> {
+    let query = "INSERT INTO explored_endpoint_report_history
+        (address, report_version, report, diff, machine_state, unexpected)
+        VALUES ($1, $2, $3, $4, $5, $6)";
+    sqlx::query(query)
+        .bind(address)
+        .bind(report_version)
+        .bind(sqlx::types::Json(report))
+        .bind(sqlx::types::Json(diff))
+        .bind(machine_state)
+        .bind(unexpected)
+        .execute(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+    Ok(())
+}
+
+/// Returns the report history of an endpoint, starting with the newest record
+pub async fn find_by_address(
+    txn: &mut PgConnection,
+    address: IpAddr,
+) -> Result<Vec<ExploredEndpointReportHistoryRecord>, DatabaseError> {
+    let query = "SELECT * FROM explored_endpoint_report_history
+        WHERE address = $1
+        ORDER BY id DESC";
+    let records: Vec<DbReportHistoryRecord> = sqlx::query_as(query)
+        .bind(address)
+        .fetch_all(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+    Ok(records.into_iter().map(|r| r.0).collect())
+}
+
+/// Returns all unexpected changes which were not acknowledged by an operator yet,
+/// starting with the newest record
+pub async fn find_unacknowledged_unexpected(
+    txn: &mut PgConnection,
+) -> Result<Vec<ExploredEndpointReportHistoryRecord>, DatabaseError> {
+    let query = "SELECT * FROM explored_endpoint_report_history
+        WHERE unexpected AND NOT acknowledged
+        ORDER BY id DESC";
+    let records: Vec<DbReportHistoryRecord> = sqlx::query_as(query)
+        .fetch_all(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+    Ok(records.into_iter().map(|r| r.0).collect())
+}
+
+/// Acknowledges all changes of an endpoint. Returns the number of records which
+/// were not acknowledged before.
+pub async fn acknowledge(txn: &mut PgConnection, address: IpAddr) -> Result<u64, DatabaseError> {
+    let query = "UPDATE explored_endpoint_report_history SET acknowledged = true
+        WHERE address = $1 AND NOT acknowledged";
+    let result = sqlx::query(query)
+        .bind(address)
+        .execute(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+    Ok(result.rows_affected())
+}
@@ -36,6 +36,7 @@ pub mod expected_machine;
 pub mod expected_power_shelf;
 pub mod expected_rack;
 pub mod expected_switch;
+pub mod explored_endpoint_report_history;
 pub mod explored_endpoints;
 pub mod explored_managed_host;
 pub mod extension_service;
@@ -79,7 +80,9 @@ pub mod resource_pool;
 pub mod route_servers;
 pub mod site_exploration_report;
 pub mod sku;
+pub mod state_change_webhook;
 pub mod state_history;
+pub mod swept_bmc_endpoints;
 pub mod switch;
 pub mod tenant;
 pub mod tenant_identity_config;
@@ -0,0 +1,246 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Durable delivery queue for state change webhooks.
+//!
+//! Deliveries are claimed with a lease: claiming pushes `next_attempt_at` into
+//! the future, so a delivery whose worker crashed mid-request becomes due again
+//! once the lease expires, and multiple carbide-api instances never deliver
+//! the same row concurrently.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgTransaction};
+
+use crate::DatabaseError;
+
+/// A state change payload that should be delivered to a webhook endpoint.
+#[derive(Debug, Clone)]
+pub struct NewStateChangeWebhookDelivery<'a> {
+    pub endpoint: &'a str,
+    pub object_type: &'a str,
+    pub object_id: &'a str,
+    pub payload: &'a serde_json::Value,
+}
+
+/// A queued delivery, as returned by [`claim_due`].
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StateChangeWebhookDelivery {
+    pub id: i64,
+    pub endpoint: String,
+    pub object_type: String,
+    pub object_id: String,
+    pub payload: serde_json::Value,
+    /// Number of delivery attempts, including the attempt this row was claimed for.
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Queues a delivery, unless `max_pending` deliveries are already queued for the endpoint.
+///
+/// Returns whether the delivery was queued.
+pub async fn enqueue(
+    // Note: Must be a transaction, since concurrent enqueues for the same endpoint
+    // are serialized with an advisory lock to keep the bound exact
+    txn: &mut PgTransaction<'_>,
+    delivery: &NewStateChangeWebhookDelivery<'_>,
+    max_pending: usize,
+) -> Result<bool, DatabaseError> {
+    let query = "SELECT pg_advisory_xact_lock(hashtextextended($1::text, 0))";
+    sqlx::query(query)
+        .bind(format!(
+            "state_change_webhook_deliveries.{}",
+            delivery.endpoint
+        ))
+        .execute(txn.as_mut())
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+
+    let query = "INSERT INTO state_change_webhook_deliveries
+            (endpoint, object_type, object_id, payload)
+        SELECT $1, $2, $3, $4
+        WHERE (SELECT COUNT(*) FROM state_change_webhook_deliveries WHERE endpoint = $1) < $5";
+
+    let result = sqlx::query(query)
+        .bind(delivery.endpoint)
+        .bind(delivery.object_type)
+        .bind(delivery.object_id)
+        .bind(delivery.payload)
+        .bind(max_pending as i64)
+        .execute(txn.as_mut())
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Claims up to `limit` due deliveries, oldest first.
+///
+/// Claimed deliveries have their attempt counter incremented and are not due
+/// again until `lease` has passed.
+pub async fn claim_due(
+    txn: &mut PgConnection,
+    limit: usize,
+    lease: Duration,
+) -> Result<Vec<StateChangeWebhookDelivery>, DatabaseError> {
+    let query = "UPDATE state_change_webhook_deliveries
+        SET attempts = attempts + 1,
+            next_attempt_at = NOW() + make_interval(secs => $2)
+        WHERE id IN (
+            SELECT id FROM state_change_webhook_deliveries
+            WHERE next_attempt_at <= NOW()
+            ORDER BY id
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, endpoint, object_type, object_id, payload, attempts, created_at";
+
+    let mut deliveries: Vec<StateChangeWebhookDelivery> = sqlx::query_as(query)
+        .bind(limit as i64)
+        .bind(lease.as_secs_f64())
+        .fetch_all(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+
+    // RETURNING does not preserve the order of the subquery
+    deliveries.sort_by_key(|delivery| delivery.id);
+    Ok(deliveries)
+}
+
+/// Removes a delivery from the queue after it was delivered or abandoned.
+pub async fn delete(txn: &mut PgConnection, id: i64) -> Result<(), DatabaseError> {
+    let query = "DELETE FROM state_change_webhook_deliveries WHERE id = $1";
+
+    sqlx::query(query)
+        .bind(id)
+        .execute(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+
+    Ok(())
+}
+
+/// Schedules the next attempt of a failed delivery `delay` from now.
+pub async fn reschedule(
+    txn: &mut PgConnection,
+    id: i64,
+    delay: Duration,
+    error: &str,
+) -> Result<(), DatabaseError> {
+    let query = "UPDATE state_change_webhook_deliveries
+        SET next_attempt_at = NOW() + make_interval(secs => $2), last_error = $3
+        WHERE id = $1";
+
+    sqlx::query(query)
+        .bind(id)
+        .bind(delay.as_secs_f64())
+        .bind(error)
+        .execute(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_delivery<'a>(
+        endpoint: &'a str,
+        payload: &'a serde_json::Value,
+    ) -> NewStateChangeWebhookDelivery<'a> {
+        NewStateChangeWebhookDelivery {
+            endpoint,
+            object_type: "machine",
+            object_id: "fm100htest",
+            payload,
+        }
+    }
+
+    #[crate::sqlx_test]
+    async fn test_enqueue_is_bounded_per_endpoint(pool: sqlx::PgPool) {
+        let mut txn = pool.begin().await.unwrap();
+        let payload = serde_json::json!({"state": "ready"});
+
+        assert!(
+            enqueue(&mut txn, &new_delivery("a", &payload), 2)
+                .await
+                .unwrap()
+        );
+        assert!(
+            enqueue(&mut txn, &new_delivery("a", &payload), 2)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !enqueue(&mut txn, &new_delivery("a", &payload), 2)
+                .await
+                .unwrap()
+        );
+        // The bound applies per endpoint
+        assert!(
+            enqueue(&mut txn, &new_delivery("b", &payload), 2)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[crate::sqlx_test]
+    async fn test_claim_leases_deliveries(pool: sqlx::PgPool) {
+        let mut txn = pool.begin().await.unwrap();
+        let payload = serde_json::json!({"state": "ready"});
+        for _ in 0..3 {
+            enqueue(&mut txn, &new_delivery("a", &payload), 10)
+                .await
+                .unwrap();
+        }
+
+        let claimed = claim_due(&mut txn, 2, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(claimed.len(), 2);
+        assert!(claimed[0].id < claimed[1].id);
+        assert!(claimed.iter().all(|delivery| delivery.attempts == 1));
+        assert_eq!(claimed[0].payload, payload);
+
+        // Leased deliveries are not handed out again
+        let claimed_again = claim_due(&mut txn, 10, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(claimed_again.len(), 1);
+        assert!(claimed.iter().all(|d| d.id != claimed_again[0].id));
+
+        // A rescheduled delivery becomes due again once its delay has passed
+        reschedule(
+            &mut txn,
+            claimed[0].id,
+            Duration::ZERO,
+            "connection refused",
+        )
+        .await
+        .unwrap();
+        delete(&mut txn, claimed[1].id).await.unwrap();
+        let retried = claim_due(&mut txn, 10, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].id, claimed[0].id);
+        assert_eq!(retried[0].attempts, 2);
+    }
+}
@@ -0,0 +1,156 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! BMC endpoints discovered by the site-explorer subnet sweep.
+//!
+//! These are addresses which answered a Redfish service root probe, but which
+//! carbide has no machine interface for. They stay here until an operator
+//! claims them (by creating a machine interface for the address) or dismisses them.
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+
+use crate::{BIND_LIMIT, DatabaseError};
+
+/// A swept endpoint, as returned by [`find_all`].
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SweptBmcEndpoint {
+    pub address: IpAddr,
+    /// The port the Redfish service root was found on
+    pub port: i32,
+    /// The `Vendor` property of the Redfish service root, if the BMC reported one
+    pub vendor: Option<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Records that `address` answered a sweep probe.
+///
+/// Refreshes `last_seen` and `vendor` if the endpoint was already recorded.
+pub async fn upsert(
+    txn: &mut PgConnection,
+    address: IpAddr,
+    port: u16,
+    vendor: Option<&str>,
+) -> Result<(), DatabaseError> {
+    let query = "INSERT INTO swept_bmc_endpoints (address, port, vendor)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (address) DO UPDATE
+        SET port = EXCLUDED.port, vendor = EXCLUDED.vendor, last_seen = NOW()";
+
+    sqlx::query(query)
+        .bind(address)
+        .bind(i32::from(port))
+        .bind(vendor)
+        .execute(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+
+    Ok(())
+}
+
+/// Returns all swept endpoints, ordered by address.
+pub async fn find_all(txn: &mut PgConnection) -> Result<Vec<SweptBmcEndpoint>, DatabaseError> {
+    let query = "SELECT address, port, vendor, first_seen, last_seen
+        FROM swept_bmc_endpoints
+        ORDER BY address";
+
+    sqlx::query_as(query)
+        .fetch_all(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))
+}
+
+/// Removes a swept endpoint. Returns whether a record existed.
+pub async fn delete(txn: &mut PgConnection, address: IpAddr) -> Result<bool, DatabaseError> {
+    let query = "DELETE FROM swept_bmc_endpoints WHERE address = $1";
+
+    let result = sqlx::query(query)
+        .bind(address)
+        .execute(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Removes all swept endpoints with the given addresses.
+pub async fn delete_many(
+    txn: &mut PgConnection,
+    addresses: &[IpAddr],
+) -> Result<(), DatabaseError> {
+    for chunk in addresses.chunks(BIND_LIMIT) {
+        let query = "DELETE FROM swept_bmc_endpoints WHERE address = ANY($1)";
+        sqlx::query(query)
+            .bind(chunk)
+            .execute(&mut *txn)
+            .await
+            .map_err(|e| DatabaseError::query(query, e))?;
+    }
+    Ok(())
+}
+
+/// Removes all swept endpoints which did not answer a probe since `cutoff`.
+/// Returns the addresses of the removed endpoints.
+pub async fn delete_not_seen_since(
+    txn: &mut PgConnection,
+    cutoff: DateTime<Utc>,
+) -> Result<Vec<IpAddr>, DatabaseError> {
+    let query = "DELETE FROM swept_bmc_endpoints WHERE last_seen < $1 RETURNING address";
+
+    sqlx::query_scalar(query)
+        .bind(cutoff)
+        .fetch_all(txn)
+        .await
+        .map_err(|e| DatabaseError::query(query, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[crate::sqlx_test]
+    async fn test_delete_not_seen_since(pool: sqlx::PgPool) {
+        let mut txn = pool.begin().await.unwrap();
+        let stale: IpAddr = "10.20.0.2".parse().unwrap();
+        let fresh: IpAddr = "10.20.0.3".parse().unwrap();
+        upsert(&mut txn, stale, 443, Some("Dell")).await.unwrap();
+        upsert(&mut txn, fresh, 443, None).await.unwrap();
+        sqlx::query(
+            "UPDATE swept_bmc_endpoints SET last_seen = NOW() - INTERVAL '2 days' WHERE address = $1",
+        )
+        .bind(stale)
+        .execute(txn.as_mut())
+        .await
+        .unwrap();
+
+        let cutoff = Utc::now() - chrono::Duration::days(1);
+        assert_eq!(
+            delete_not_seen_since(&mut txn, cutoff).await.unwrap(),
+            vec![stale]
+        );
+        let remaining: Vec<IpAddr> = find_all(&mut txn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|endpoint| endpoint.address)
+            .collect();
+        assert_eq!(remaining, vec![fresh]);
+    }
+}
@@ -15,6 +15,7 @@
  * limitations under the License.
  */
 
+use crate::site_explorer::report_history::{ExploredEndpointReportHistoryRecord, ReportChange};
 use crate::site_explorer::{
     BootOption, BootOrder, Chassis, ComputerSystem, ComputerSystemAttributes, EthernetInterface,
     ExploredDpu, ExploredEndpoint, ExploredEndpointSearchFilter, ExploredManagedHost,
@@ -319,3 +320,31 @@ impl From<BootOption> for rpc::site_explorer::BootOption {
         }
     }
 }
+
+impl From<ReportChange> for rpc::site_explorer::ExplorationReportChange {
+    fn from(change: ReportChange) -> Self {
+        rpc::site_explorer::ExplorationReportChange {
+            category: change.category.to_string(),
+            component: change.component,
+            before: change.before,
+            after: change.after,
+        }
+    }
+}
+
+impl From<ExploredEndpointReportHistoryRecord>
+    for rpc::site_explorer::ExploredEndpointReportHistoryRecord
+{
+    fn from(record: ExploredEndpointReportHistoryRecord) -> Self {
+        rpc::site_explorer::ExploredEndpointReportHistoryRecord {
+            address: record.address.to_string(),
+            report_version: record.report_version.to_string(),
+            report: Some(record.report.into()),
+            changes: record.diff.changes.into_iter().map(Into::into).collect(),
+            machine_state: record.machine_state,
+            unexpected: record.unexpected,
+            acknowledged: record.acknowledged,
+            time: Some(record.time.into()),
+        }
+    }
+}
@@ -43,6 +43,8 @@ use crate::machine::machine_id::{MissingHardwareInfo, from_hardware_info_with_ty
 use crate::power_shelf::power_shelf_id;
 use crate::switch::switch_id;
 
+pub mod report_history;
+
 #[derive(Clone, Debug, Default)]
 pub struct ExploredEndpointSearchFilter {}
 
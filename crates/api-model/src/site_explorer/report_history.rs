@@ -0,0 +1,472 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! History of exploration reports, and the hardware drift between them.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use config_version::ConfigVersion;
+use serde::{Deserialize, Serialize};
+
+use super::{EndpointExplorationReport, EndpointType, PCIeDevice};
+
+/// The kind of component a [`ReportChange`] refers to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ReportChangeCategory {
+    Firmware,
+    Nic,
+    PcieDevice,
+    BootOrder,
+    SerialNumber,
+}
+
+impl Display for ReportChangeCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A single component that was added, removed or modified between two exploration reports
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReportChange {
+    pub category: ReportChangeCategory,
+    /// Redfish-style path of the component, e.g. `Systems/Self/PCIeDevices/0-1`
+    pub component: String,
+    /// The value in the previous report. `None` if the component was added.
+    pub before: Option<String>,
+    /// The value in the new report. `None` if the component was removed.
+    pub after: Option<String>,
+}
+
+impl Display for ReportChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.before, &self.after) {
+            (None, Some(after)) => write!(f, "{} {} added: {after}", self.category, self.component),
+            (Some(before), None) => {
+                write!(f, "{} {} removed: {before}", self.category, self.component)
+            }
+            (before, after) => write!(
+                f,
+                "{} {} changed: {} -> {}",
+                self.category,
+                self.component,
+                before.as_deref().unwrap_or_default(),
+                after.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// The structured difference between two exploration reports of the same endpoint
+///
+/// This data is stored as JSON in the Database. Therefore the format can
+/// only be adjusted in a backward compatible fashion.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExplorationReportDiff {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<ReportChange>,
+}
+
+impl ExplorationReportDiff {
+    /// Compares the firmware inventory, NICs, PCIe devices, boot order and
+    /// serial numbers of two reports.
+    ///
+    /// Returns `None` if either report does not contain usable data, i.e. if it
+    /// is not a BMC report, or if `current` is the result of a failed exploration.
+    /// The previous report is still compared if its latest exploration failed,
+    /// since a failed exploration keeps the data of the last successful one.
+    pub fn between(
+        previous: &EndpointExplorationReport,
+        current: &EndpointExplorationReport,
+    ) -> Option<Self> {
+        if previous.endpoint_type != EndpointType::Bmc
+            || current.endpoint_type != EndpointType::Bmc
+            || current.last_exploration_error.is_some()
+        {
+            return None;
+        }
+
+        let before = tracked_components(previous);
+        let mut after = tracked_components(current);
+
+        let mut changes = Vec::new();
+        for (key, before_value) in before {
+            match after.remove(&key) {
+                Some(after_value) if after_value == before_value => {}
+                after_value => changes.push(ReportChange {
+                    category: key.0,
+                    component: key.1,
+                    before: Some(before_value),
+                    after: after_value,
+                }),
+            }
+        }
+        changes.extend(after.into_iter().map(|(key, after_value)| ReportChange {
+            category: key.0,
+            component: key.1,
+            before: None,
+            after: Some(after_value),
+        }));
+        changes.sort_by(|a, b| (a.category, &a.component).cmp(&(b.category, &b.component)));
+
+        Some(Self { changes })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The categories of all changes, without duplicates
+    pub fn categories(&self) -> Vec<ReportChangeCategory> {
+        let mut categories: Vec<_> = self.changes.iter().map(|c| c.category).collect();
+        categories.sort();
+        categories.dedup();
+        categories
+    }
+}
+
+/// A stored version of an exploration report, together with how it differs
+/// from the version that was stored before
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExploredEndpointReportHistoryRecord {
+    pub id: i64,
+    /// The IP address of the endpoint
+    pub address: IpAddr,
+    /// The version of the explored endpoint report this record was created for
+    pub report_version: ConfigVersion,
+    /// The full report at that version
+    pub report: EndpointExplorationReport,
+    /// What changed compared to the previous report
+    pub diff: ExplorationReportDiff,
+    /// The state of the Machine that owns the endpoint at the time of the change.
+    /// `None` if the endpoint did not belong to a Machine.
+    pub machine_state: Option<String>,
+    /// Whether the change happened while the Machine was not supposed to change,
+    /// e.g. while it was `Ready` or `Assigned`
+    pub unexpected: bool,
+    /// Whether an operator acknowledged the change
+    pub acknowledged: bool,
+    pub time: DateTime<Utc>,
+}
+
+type ComponentKey = (ReportChangeCategory, String);
+
+fn tracked_components(report: &EndpointExplorationReport) -> BTreeMap<ComponentKey, String> {
+    let mut components = BTreeMap::new();
+    let mut track = |category, component: String, value: Option<String>| {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            components.insert((category, component), value);
+        }
+    };
+
+    for service in report.service.iter() {
+        for inventory in service.inventories.iter() {
+            track(
+                ReportChangeCategory::Firmware,
+                format!("{}/FirmwareInventory/{}", service.id, inventory.id),
+                inventory.version.clone(),
+            );
+        }
+    }
+
+    for manager in report.managers.iter() {
+        for (i, iface) in manager.ethernet_interfaces.iter().enumerate() {
+            let iface_id = iface.id.clone().unwrap_or_else(|| i.to_string());
+            track(
+                ReportChangeCategory::Nic,
+                format!("Managers/{}/EthernetInterfaces/{iface_id}", manager.id),
+                iface.mac_address.map(|mac| mac.to_string()),
+            );
+        }
+    }
+
+    for system in report.systems.iter() {
+        let path = format!("Systems/{}", system.id);
+        track(
+            ReportChangeCategory::SerialNumber,
+            path.clone(),
+            system.serial_number.clone(),
+        );
+
+        for (i, iface) in system.ethernet_interfaces.iter().enumerate() {
+            let iface_id = iface.id.clone().unwrap_or_else(|| i.to_string());
+            track(
+                ReportChangeCategory::Nic,
+                format!("{path}/EthernetInterfaces/{iface_id}"),
+                iface.mac_address.map(|mac| mac.to_string()),
+            );
+        }
+
+        for (i, device) in system.pcie_devices.iter().enumerate() {
+            let device_id = device
+                .id
+                .clone()
+                .or_else(|| device.name.clone())
+                .unwrap_or_else(|| i.to_string());
+            let device_path = format!("{path}/PCIeDevices/{device_id}");
+            track(
+                ReportChangeCategory::PcieDevice,
+                device_path.clone(),
+                Some(describe_pcie_device(device)),
+            );
+            track(
+                ReportChangeCategory::Firmware,
+                device_path,
+                device.firmware_version.clone(),
+            );
+        }
+
+        if let Some(boot_order) = system.boot_order.as_ref() {
+            track(
+                ReportChangeCategory::BootOrder,
+                format!("{path}/BootOrder"),
+                Some(
+                    boot_order
+                        .boot_order
+                        .iter()
+                        .map(|option| option.display_name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+            );
+        }
+    }
+
+    for chassis in report.chassis.iter() {
+        let path = format!("Chassis/{}", chassis.id);
+        track(
+            ReportChangeCategory::SerialNumber,
+            path.clone(),
+            chassis.serial_number.clone(),
+        );
+
+        for adapter in chassis.network_adapters.iter() {
+            track(
+                ReportChangeCategory::Nic,
+                format!("{path}/NetworkAdapters/{}", adapter.id),
+                Some(describe_part(
+                    [&adapter.manufacturer, &adapter.model, &adapter.part_number],
+                    &adapter.serial_number,
+                )),
+            );
+        }
+    }
+
+    components
+}
+
+fn describe_pcie_device(device: &PCIeDevice) -> String {
+    describe_part(
+        [&device.manufacturer, &device.name, &device.part_number],
+        &device.serial_number,
+    )
+}
+
+/// Formats a part as e.g. `Mellanox ConnectX-7 MCX75310AAS (SN: MT2243X00001)`
+fn describe_part(names: [&Option<String>; 3], serial_number: &Option<String>) -> String {
+    let mut description = names
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(serial_number) = serial_number {
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        description.push_str(&format!("(SN: {serial_number})"));
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::site_explorer::{
+        BootOption, BootOrder, Chassis, ComputerSystem, EndpointExplorationError, Inventory,
+        NetworkAdapter, Service,
+    };
+
+    fn pcie_device(id: &str, serial_number: &str) -> PCIeDevice {
+        PCIeDevice {
+            description: None,
+            firmware_version: Some("1.0".to_string()),
+            gpu_vendor: None,
+            id: Some(id.to_string()),
+            manufacturer: Some("NVIDIA".to_string()),
+            name: Some("GPU".to_string()),
+            part_number: None,
+            serial_number: Some(serial_number.to_string()),
+            status: None,
+        }
+    }
+
+    fn boot_option(name: &str) -> BootOption {
+        BootOption {
+            display_name: name.to_string(),
+            id: name.to_string(),
+            boot_option_enabled: Some(true),
+            uefi_device_path: None,
+        }
+    }
+
+    fn report() -> EndpointExplorationReport {
+        EndpointExplorationReport {
+            endpoint_type: EndpointType::Bmc,
+            systems: vec![ComputerSystem {
+                id: "Self".to_string(),
+                serial_number: Some("SN1".to_string()),
+                pcie_devices: vec![pcie_device("0-1", "GPU1"), pcie_device("0-2", "GPU2")],
+                boot_order: Some(BootOrder {
+                    boot_order: vec![boot_option("Network"), boot_option("Disk")],
+                }),
+                ..Default::default()
+            }],
+            chassis: vec![Chassis {
+                id: "Card1".to_string(),
+                network_adapters: vec![NetworkAdapter {
+                    id: "NIC1".to_string(),
+                    model: Some("ConnectX-7".to_string()),
+                    serial_number: Some("MT1".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            service: vec![Service {
+                id: "UpdateService".to_string(),
+                inventories: vec![Inventory {
+                    id: "BMC_Firmware".to_string(),
+                    version: Some("1.2.3".to_string()),
+                    ..Default::default()
+                }],
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn identical_reports_have_no_changes() {
+        let diff = ExplorationReportDiff::between(&report(), &report()).unwrap();
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn detects_added_removed_and_modified_components() {
+        let previous = report();
+        let mut current = report();
+        current.systems[0].pcie_devices.remove(1);
+        current.systems[0].boot_order = Some(BootOrder {
+            boot_order: vec![boot_option("Disk"), boot_option("Network")],
+        });
+        current.chassis[0].network_adapters[0].serial_number = Some("MT2".to_string());
+        current.service[0].inventories.push(Inventory {
+            id: "UEFI".to_string(),
+            version: Some("2.0".to_string()),
+            ..Default::default()
+        });
+
+        let diff = ExplorationReportDiff::between(&previous, &current).unwrap();
+        assert_eq!(
+            diff.changes,
+            vec![
+                ReportChange {
+                    category: ReportChangeCategory::Firmware,
+                    component: "Systems/Self/PCIeDevices/0-2".to_string(),
+                    before: Some("1.0".to_string()),
+                    after: None,
+                },
+                ReportChange {
+                    category: ReportChangeCategory::Firmware,
+                    component: "UpdateService/FirmwareInventory/UEFI".to_string(),
+                    before: None,
+                    after: Some("2.0".to_string()),
+                },
+                ReportChange {
+                    category: ReportChangeCategory::Nic,
+                    component: "Chassis/Card1/NetworkAdapters/NIC1".to_string(),
+                    before: Some("ConnectX-7 (SN: MT1)".to_string()),
+                    after: Some("ConnectX-7 (SN: MT2)".to_string()),
+                },
+                ReportChange {
+                    category: ReportChangeCategory::PcieDevice,
+                    component: "Systems/Self/PCIeDevices/0-2".to_string(),
+                    before: Some("NVIDIA GPU (SN: GPU2)".to_string()),
+                    after: None,
+                },
+                ReportChange {
+                    category: ReportChangeCategory::BootOrder,
+                    component: "Systems/Self/BootOrder".to_string(),
+                    before: Some("Network, Disk".to_string()),
+                    after: Some("Disk, Network".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            diff.categories(),
+            vec![
+                ReportChangeCategory::Firmware,
+                ReportChangeCategory::Nic,
+                ReportChangeCategory::PcieDevice,
+                ReportChangeCategory::BootOrder,
+            ]
+        );
+        assert_eq!(
+            diff.changes[3].to_string(),
+            "PcieDevice Systems/Self/PCIeDevices/0-2 removed: NVIDIA GPU (SN: GPU2)"
+        );
+    }
+
+    #[test]
+    fn failed_explorations_are_not_compared() {
+        let failed = EndpointExplorationReport::new_with_error(EndpointExplorationError::Other {
+            details: "test".to_string(),
+        });
+        assert!(ExplorationReportDiff::between(&report(), &failed).is_none());
+        assert!(ExplorationReportDiff::between(&failed, &report()).is_none());
+
+        // A failed re-exploration keeps the previous data around
+        let mut previous = report();
+        previous.last_exploration_error = failed.last_exploration_error.clone();
+        let diff = ExplorationReportDiff::between(&previous, &report()).unwrap();
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn diff_serializes_backward_compatible() {
+        let diff: ExplorationReportDiff = serde_json::from_str("{}").unwrap();
+        assert!(diff.is_empty());
+
+        let diff = ExplorationReportDiff::between(
+            &report(),
+            &EndpointExplorationReport {
+                endpoint_type: EndpointType::Bmc,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let serialized = serde_json::to_string(&diff).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ExplorationReportDiff>(&serialized).unwrap(),
+            diff
+        );
+    }
+}
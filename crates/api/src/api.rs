@@ -24,10 +24,11 @@ use std::sync::{Arc, OnceLock};
 pub use ::rpc::forge as rpc;
 use ::rpc::forge::{RemoveSkuRequest, SkuIdList};
 use ::rpc::protos::dns::{
-    CreateDomainRequest, DnsResourceRecordLookupRequest, DnsResourceRecordLookupResponse, Domain,
-    DomainDeletionRequest, DomainDeletionResult, DomainList, DomainMetadataRequest,
-    DomainMetadataResponse, DomainSearchQuery, GetAllDomainsRequest, GetAllDomainsResponse,
-    UpdateDomainRequest,
+    CreateDomainRequest, DnsResourceRecordLookupRequest, DnsResourceRecordLookupResponse,
+    DnsSnapshotRequest, DnsSnapshotResponse, Domain, DomainDeletionRequest, DomainDeletionResult,
+    DomainList, DomainMetadataRequest, DomainMetadataResponse, DomainSearchQuery,
+    GetAllDomainsRequest, GetAllDomainsResponse, GetAllRecordsForDomainRequest,
+    GetAllRecordsForDomainResponse, UpdateDomainRequest,
 };
 use ::rpc::protos::{measured_boot as measured_boot_pb, mlx_device as mlx_device_pb};
 use carbide_ib_fabric::ib::IBFabricManager;
@@ -629,6 +630,20 @@ impl Forge for Api {
         crate::handlers::dns::get_all_domains(self, request).await
     }
 
+    async fn get_all_records_for_domain(
+        &self,
+        request: Request<GetAllRecordsForDomainRequest>,
+    ) -> Result<Response<GetAllRecordsForDomainResponse>, tonic::Status> {
+        crate::handlers::dns::get_all_records_for_domain(self, request).await
+    }
+
+    async fn get_dns_snapshot(
+        &self,
+        request: Request<DnsSnapshotRequest>,
+    ) -> Result<Response<DnsSnapshotResponse>, tonic::Status> {
+        crate::handlers::dns::get_dns_snapshot(self, request).await
+    }
+
     async fn lookup_record(
         &self,
         request: Request<DnsResourceRecordLookupRequest>,
@@ -735,6 +750,13 @@ impl Forge for Api {
         crate::handlers::tenant_keyset::validate_public_key(self, request).await
     }
 
+    async fn create_console_token(
+        &self,
+        request: Request<rpc::CreateConsoleTokenRequest>,
+    ) -> Result<Response<rpc::CreateConsoleTokenResponse>, Status> {
+        crate::handlers::console_token::create_console_token(self, request).await
+    }
+
     async fn renew_machine_certificate(
         &self,
         request: Request<rpc::MachineCertificateRenewRequest>,
@@ -1010,6 +1032,34 @@ impl Forge for Api {
         crate::handlers::site_explorer::find_explored_managed_hosts_by_ids(self, request).await
     }
 
+    async fn find_swept_bmc_endpoints(
+        &self,
+        request: Request<::rpc::site_explorer::SweptBmcEndpointSearchFilter>,
+    ) -> Result<Response<::rpc::site_explorer::SweptBmcEndpointList>, Status> {
+        crate::handlers::site_explorer::find_swept_bmc_endpoints(self, request).await
+    }
+
+    async fn delete_swept_bmc_endpoint(
+        &self,
+        request: Request<rpc::DeleteSweptBmcEndpointRequest>,
+    ) -> Result<Response<()>, Status> {
+        crate::handlers::site_explorer::delete_swept_bmc_endpoint(self, request).await
+    }
+
+    async fn find_explored_endpoint_report_history(
+        &self,
+        request: Request<::rpc::site_explorer::ExploredEndpointReportHistoryRequest>,
+    ) -> Result<Response<::rpc::site_explorer::ExploredEndpointReportHistory>, Status> {
+        crate::handlers::site_explorer::find_explored_endpoint_report_history(self, request).await
+    }
+
+    async fn acknowledge_explored_endpoint_drift(
+        &self,
+        request: Request<rpc::AcknowledgeExploredEndpointDriftRequest>,
+    ) -> Result<Response<rpc::AcknowledgeExploredEndpointDriftResponse>, Status> {
+        crate::handlers::site_explorer::acknowledge_explored_endpoint_drift(self, request).await
+    }
+
     async fn update_machine_hardware_info(
         &self,
         request: Request<::rpc::forge::UpdateMachineHardwareInfoRequest>,
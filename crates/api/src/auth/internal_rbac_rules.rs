@@ -182,6 +182,8 @@ impl InternalRBACRules {
         x.perm("LookupRecordLegacy", vec![Dns]);
         x.perm("GetAllDomainMetadata", vec![Dns]);
         x.perm("GetAllDomains", vec![Dns]);
+        x.perm("GetAllRecordsForDomain", vec![Dns]);
+        x.perm("GetDnsSnapshot", vec![Dns]);
         x.perm("InvokeInstancePower", vec![ForgeAdminCLI, SiteAgent]);
         x.perm("ForgeAgentControl", vec![Machineatron, Scout]);
         x.perm("DiscoverMachine", vec![Anonymous]);
@@ -244,6 +246,7 @@ impl InternalRBACRules {
         x.perm("UpdateTenantKeyset", vec![SiteAgent]);
         x.perm("DeleteTenantKeyset", vec![SiteAgent]);
         x.perm("ValidateTenantPublicKey", vec![SiteAgent, Ssh, SshRs]);
+        x.perm("CreateConsoleToken", vec![ForgeAdminCLI, SiteAgent]);
         x.perm("GetBmcCredentials", vec![Health]);
         x.perm("GetAllManagedHostNetworkStatus", vec![ForgeAdminCLI]);
         x.perm(
@@ -260,6 +263,10 @@ impl InternalRBACRules {
         x.perm("FindExploredEndpointsByIds", vec![ForgeAdminCLI, Flow]);
         x.perm("FindExploredManagedHostIds", vec![ForgeAdminCLI, Flow]);
         x.perm("FindExploredManagedHostsByIds", vec![ForgeAdminCLI, Flow]);
+        x.perm("FindSweptBmcEndpoints", vec![ForgeAdminCLI]);
+        x.perm("DeleteSweptBmcEndpoint", vec![ForgeAdminCLI]);
+        x.perm("FindExploredEndpointReportHistory", vec![ForgeAdminCLI]);
+        x.perm("AcknowledgeExploredEndpointDrift", vec![ForgeAdminCLI]);
         x.perm("AdminForceDeleteMachine", vec![ForgeAdminCLI, Machineatron]);
         x.perm("AdminForceDeleteSwitch", vec![ForgeAdminCLI, Machineatron]);
         x.perm(
@@ -430,15 +437,18 @@ impl InternalRBACRules {
         x.perm("GetOperatingSystem", vec![ForgeAdminCLI, SiteAgent]);
         x.perm("UpdateOperatingSystem", vec![ForgeAdminCLI, SiteAgent]);
         x.perm("DeleteOperatingSystem", vec![ForgeAdminCLI, SiteAgent]);
-        x.perm("FindOperatingSystemIds", vec![ForgeAdminCLI, SiteAgent]);
+        x.perm(
+            "FindOperatingSystemIds",
+            vec![ForgeAdminCLI, SiteAgent, Pxe],
+        );
         x.perm("FindOperatingSystemsByIds", vec![ForgeAdminCLI, SiteAgent]);
         x.perm(
             "GetOperatingSystemCachableIpxeTemplateArtifacts",
-            vec![ForgeAdminCLI],
+            vec![ForgeAdminCLI, Pxe],
         );
         x.perm(
             "UpdateOperatingSystemCachableIpxeTemplateArtifacts",
-            vec![ForgeAdminCLI],
+            vec![ForgeAdminCLI, Pxe],
         );
         x.perm("GetIpxeTemplate", vec![ForgeAdminCLI, SiteAgent]);
         x.perm("ListIpxeTemplates", vec![ForgeAdminCLI, SiteAgent]);
@@ -51,7 +51,7 @@ use model::network_segment::NetworkDefinition;
 use model::resource_pool::define::ResourcePoolDef;
 use model::tenant::identity_config::SigningAlgorithm;
 use regex::Regex;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::state_controller::config::IterationConfig;
 
@@ -359,6 +359,12 @@ pub struct CarbideConfig {
     #[serde(default)]
     pub machine_identity: MachineIdentityConfig,
 
+    /// Tokens for ssh-console's websocket console, issued by
+    /// `CreateConsoleToken`. If unset, no tokens are issued.
+    /// Section `[console_token]`.
+    #[serde(default)]
+    pub console_token: Option<ConsoleTokenConfig>,
+
     /// Disables role-based access control enforcement.
     /// Intended for testing and development only.
     #[serde(default)]
@@ -425,6 +431,12 @@ pub struct CarbideConfig {
     #[serde(default)]
     pub dsx_exchange_event_bus: Option<DsxExchangeEventBusConfig>,
 
+    /// State change webhooks. Pushes state transitions of machines,
+    /// switches, racks and power shelves to HTTPS endpoints as
+    /// HMAC-signed JSON, retrying failed deliveries from a durable queue.
+    #[serde(default)]
+    pub state_change_webhooks: Option<StateChangeWebhooksConfig>,
+
     /// Datacenter ASN used by FNN to build DC-specific
     /// route targets for VRF import and export.
     /// Default is 11414.
@@ -839,6 +851,40 @@ fn machine_identity_default_signing_key_overlap_max_sec() -> u32 {
     604800
 }
 
+/// Signing of console tokens, which ssh-console's websocket console accepts
+/// instead of SSH keys.
+/// Loaded from `[console_token]` section in config.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConsoleTokenConfig {
+    /// PEM-encoded EC P-256 private key tokens are signed with (ES256). ssh-console
+    /// verifies them with the matching `websocket_token_public_key_path`.
+    pub signing_key_path: PathBuf,
+    /// Audience of issued tokens, ssh-console's `websocket_token_audience`.
+    /// Defaults to "ssh-console".
+    #[serde(default = "ConsoleTokenConfig::default_audience")]
+    pub audience: String,
+    /// How long issued tokens are valid. Defaults to 5 minutes.
+    #[serde(
+        default = "ConsoleTokenConfig::default_lifetime",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub lifetime: std::time::Duration,
+    /// Role added to tokens issued to admins, ssh-console's `admin_certificate_role`.
+    /// ssh-console only grants machine consoles to tokens with this role.
+    pub admin_role: String,
+}
+
+impl ConsoleTokenConfig {
+    pub fn default_audience() -> String {
+        "ssh-console".to_string()
+    }
+
+    pub const fn default_lifetime() -> std::time::Duration {
+        std::time::Duration::from_secs(5 * 60)
+    }
+}
+
 impl Default for MachineIdentityConfig {
     fn default() -> Self {
         Self {
@@ -1106,6 +1152,39 @@ impl CarbideConfig {
         Ok(())
     }
 
+    /// Returns an error when two state change webhook endpoints share a
+    /// `name` (the name selects the endpoint's HMAC secret), or when an
+    /// endpoint `url` is unparsable or doesn't use the `https` scheme.
+    pub fn validate_state_change_webhooks(&self) -> eyre::Result<()> {
+        let Some(webhooks) = &self.state_change_webhooks else {
+            return Ok(());
+        };
+        let mut seen = std::collections::HashSet::new();
+        for endpoint in &webhooks.endpoints {
+            if !seen.insert(endpoint.name.as_str()) {
+                return Err(eyre::eyre!(
+                    "duplicate state change webhook endpoint with name = {:?}; endpoint names must be unique",
+                    endpoint.name
+                ));
+            }
+            let parsed = url::Url::parse(&endpoint.url).map_err(|e| {
+                eyre::eyre!(
+                    "state change webhook endpoint {:?}: invalid url {:?}: {e}",
+                    endpoint.name,
+                    endpoint.url
+                )
+            })?;
+            if parsed.scheme() != "https" {
+                return Err(eyre::eyre!(
+                    "state change webhook endpoint {:?}: url {:?} must use https scheme",
+                    endpoint.name,
+                    endpoint.url
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// validate_supernic_firmware_profiles checks that each profile's inner
     /// part_number and psid match the HashMap keys they are nested under.
     /// Logs a warning for any mismatches (the inner values are authoritative
@@ -1523,6 +1602,59 @@ pub struct StateControllerConfig {
         serialize_with = "as_std_duration"
     )]
     pub metric_hold_time: std::time::Duration,
+
+    /// Configures the maximum time objects are expected to stay in a given state.
+    /// Objects which stay in a state for longer get a health alert attached.
+    ///
+    /// Keys are the state names that are used in state controller metrics, either
+    /// as `state` or as `state.substate` (e.g. `hostnotready` or
+    /// `measuring.waitingformeasurements`).
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        deserialize_with = "deserialize_duration_map",
+        serialize_with = "as_std_duration_map"
+    )]
+    pub state_dwell_time_limits: HashMap<String, std::time::Duration>,
+
+    /// Configures how quickly queued objects gain priority while they are waiting
+    /// to get processed. For every elapsed interval, the effective priority of a
+    /// queued object is raised by one class, which prevents starvation of objects
+    /// that are only periodically enqueued.
+    #[serde(
+        default = "StateControllerConfig::priority_aging_interval_default",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub priority_aging_interval: std::time::Duration,
+}
+
+/// Deserializes a map of durations, where each duration uses the same format
+/// as other durations in the config (e.g. `"30m"`)
+fn deserialize_duration_map<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, std::time::Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct DurationValue(#[serde(deserialize_with = "deserialize_duration")] std::time::Duration);
+
+    let map = HashMap::<String, DurationValue>::deserialize(deserializer)?;
+    Ok(map.into_iter().map(|(key, value)| (key, value.0)).collect())
+}
+
+fn as_std_duration_map<S>(
+    map: &HashMap<String, std::time::Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    #[derive(Serialize)]
+    struct DurationValue(#[serde(serialize_with = "as_std_duration")] std::time::Duration);
+
+    serializer.collect_map(map.iter().map(|(key, value)| (key, DurationValue(*value))))
 }
 
 impl StateControllerConfig {
@@ -1553,6 +1685,10 @@ impl StateControllerConfig {
     pub const fn max_concurrency_default() -> usize {
         10
     }
+
+    pub const fn priority_aging_interval_default() -> std::time::Duration {
+        std::time::Duration::from_secs(5 * 60)
+    }
 }
 
 impl Default for StateControllerConfig {
@@ -1565,6 +1701,8 @@ impl Default for StateControllerConfig {
             max_concurrency: Self::max_concurrency_default(),
             metric_emission_interval: Self::metric_emission_interval(),
             metric_hold_time: Self::metric_hold_time(),
+            state_dwell_time_limits: HashMap::new(),
+            priority_aging_interval: Self::priority_aging_interval_default(),
         }
     }
 }
@@ -1579,6 +1717,8 @@ impl From<&StateControllerConfig> for IterationConfig {
             processor_log_interval: config.processor_log_interval,
             metric_emission_interval: config.metric_emission_interval,
             metric_hold_time: config.metric_hold_time,
+            state_dwell_time_limits: config.state_dwell_time_limits.clone(),
+            priority_aging_interval: config.priority_aging_interval,
         }
     }
 }
@@ -2606,6 +2746,150 @@ impl DsxExchangeEventBusConfig {
     }
 }
 
+/// State change webhook configuration.
+///
+/// Every state transition of a machine, switch, rack or power shelf which
+/// matches an endpoint's filters is queued in the database and POSTed to the
+/// endpoint as JSON. The body is signed together with the `X-Carbide-Timestamp`
+/// header with HMAC-SHA256 using the endpoint's secret
+/// (`state_change_webhooks/{name}/hmac` in the credential store), and the
+/// signature is sent in the `X-Carbide-Signature: sha256=<hex>` header.
+/// Failed deliveries are retried with exponential backoff until
+/// `max_attempts` is reached.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct StateChangeWebhooksConfig {
+    /// Enable/disable state change webhooks.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Endpoints which state changes are delivered to.
+    #[serde(default)]
+    pub endpoints: Vec<StateChangeWebhookEndpointConfig>,
+
+    /// Timeout for a single delivery request. Defaults to 5 seconds.
+    #[serde(
+        default = "StateChangeWebhooksConfig::default_request_timeout",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub request_timeout: std::time::Duration,
+
+    /// Capacity of the in-memory queue which buffers state changes until they
+    /// are written to the delivery queue in the database. Events are dropped if
+    /// the queue is full. Defaults to 1024.
+    #[serde(default = "StateChangeWebhooksConfig::default_queue_capacity")]
+    pub queue_capacity: usize,
+
+    /// Maximum number of deliveries which can be pending for a single endpoint
+    /// in the database. New events for the endpoint are dropped while the limit
+    /// is reached. Defaults to 10000.
+    #[serde(default = "StateChangeWebhooksConfig::default_max_pending_deliveries")]
+    pub max_pending_deliveries: usize,
+
+    /// Number of attempts after which a delivery is abandoned. Defaults to 10.
+    #[serde(default = "StateChangeWebhooksConfig::default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry of a failed delivery. The delay doubles
+    /// with every further attempt. Defaults to 5 seconds.
+    #[serde(
+        default = "StateChangeWebhooksConfig::default_initial_retry_backoff",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub initial_retry_backoff: std::time::Duration,
+
+    /// Upper bound for the delay between retries. Defaults to 10 minutes.
+    #[serde(
+        default = "StateChangeWebhooksConfig::default_max_retry_backoff",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub max_retry_backoff: std::time::Duration,
+
+    /// How often the delivery queue is polled for due deliveries. Defaults to 1 second.
+    #[serde(
+        default = "StateChangeWebhooksConfig::default_poll_interval",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub poll_interval: std::time::Duration,
+}
+
+impl StateChangeWebhooksConfig {
+    pub const fn default_request_timeout() -> std::time::Duration {
+        std::time::Duration::from_secs(5)
+    }
+
+    pub const fn default_queue_capacity() -> usize {
+        1024
+    }
+
+    pub const fn default_max_pending_deliveries() -> usize {
+        10000
+    }
+
+    pub const fn default_max_attempts() -> u32 {
+        10
+    }
+
+    pub const fn default_initial_retry_backoff() -> std::time::Duration {
+        std::time::Duration::from_secs(5)
+    }
+
+    pub const fn default_max_retry_backoff() -> std::time::Duration {
+        std::time::Duration::from_secs(10 * 60)
+    }
+
+    pub const fn default_poll_interval() -> std::time::Duration {
+        std::time::Duration::from_secs(1)
+    }
+}
+
+/// A single state change webhook endpoint.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct StateChangeWebhookEndpointConfig {
+    /// Unique name of the endpoint. Selects the HMAC secret, and is used as
+    /// metric label.
+    pub name: String,
+
+    /// HTTPS URL which state changes are POSTed to.
+    pub url: String,
+
+    /// Object types whose state changes are delivered. All object types are
+    /// delivered if empty.
+    #[serde(default)]
+    pub object_types: Vec<StateChangeWebhookObjectType>,
+
+    /// Target states whose transitions are delivered, using the state names of
+    /// the state controller metrics. An entry either names a state (`ready`),
+    /// or a state and substate (`assigned.ready`). All transitions are
+    /// delivered if empty.
+    #[serde(default)]
+    pub target_states: Vec<String>,
+}
+
+/// Object types which state change webhooks can be filtered on.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StateChangeWebhookObjectType {
+    Machine,
+    Switch,
+    Rack,
+    PowerShelf,
+}
+
+impl StateChangeWebhookObjectType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Machine => "machine",
+            Self::Switch => "switch",
+            Self::Rack => "rack",
+            Self::PowerShelf => "power_shelf",
+        }
+    }
+}
+
 /// MachineValidation related configuration
 #[derive(Default, Clone, Copy, Debug, Deserialize, Serialize)]
 pub struct BomValidationConfig {
@@ -2758,7 +3042,7 @@ mod tests {
     use std::sync::atomic::Ordering as AtomicOrdering;
 
     use carbide_authn::config::CertComponent;
-    use carbide_site_explorer::config::SiteExplorerExploreMode;
+    use carbide_site_explorer::config::{SiteExplorerExploreMode, SubnetSweepConfig};
     use chrono::Datelike;
     use figment::Figment;
     use figment::providers::{Env, Format, Toml};
@@ -2782,6 +3066,8 @@ mod tests {
                 processor_log_interval: std::time::Duration::from_secs(60),
                 metric_emission_interval: std::time::Duration::from_secs(60),
                 metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                state_dwell_time_limits: HashMap::new(),
+                priority_aging_interval: std::time::Duration::from_secs(5 * 60),
             },
             dpu_wait_time: Duration::minutes(20),
             power_down_wait: Duration::seconds(10),
@@ -2824,6 +3110,8 @@ mod tests {
                         processor_log_interval: std::time::Duration::from_secs(60),
                         metric_emission_interval: std::time::Duration::from_secs(60),
                         metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                        state_dwell_time_limits: HashMap::new(),
+                        priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                     }
                 },
                 dpu_wait_time: Duration::minutes(20),
@@ -2874,6 +3162,8 @@ mod tests {
                         processor_log_interval: std::time::Duration::from_secs(60),
                         metric_emission_interval: std::time::Duration::from_secs(60),
                         metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                        state_dwell_time_limits: HashMap::new(),
+                        priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                     }
                 },
                 network_segment_drain_time: Duration::minutes(21),
@@ -2889,13 +3179,51 @@ mod tests {
         assert_eq!(config, NetworkSegmentStateControllerConfig::default());
     }
 
+    #[test]
+    fn deserialize_state_controller_config_with_dwell_time_limits() {
+        let config = r#"{"state_dwell_time_limits": {"hostnotready": "6h", "measuring.waitingformeasurements": "30m"}}"#;
+        let config: StateControllerConfig = serde_json::from_str(config).unwrap();
+
+        assert_eq!(
+            config.state_dwell_time_limits,
+            HashMap::from([
+                (
+                    "hostnotready".to_string(),
+                    std::time::Duration::from_secs(6 * 60 * 60)
+                ),
+                (
+                    "measuring.waitingformeasurements".to_string(),
+                    std::time::Duration::from_secs(30 * 60)
+                ),
+            ])
+        );
+
+        let config_str = serde_json::to_string(&config).unwrap();
+        let roundtrip: StateControllerConfig = serde_json::from_str(&config_str).unwrap();
+        assert_eq!(roundtrip, config);
+
+        let iteration_config = IterationConfig::from(&config);
+        assert_eq!(
+            iteration_config.dwell_time_limit("measuring", "waitingformeasurements"),
+            Some(std::time::Duration::from_secs(30 * 60))
+        );
+        assert_eq!(
+            iteration_config.dwell_time_limit("measuring", "pendingbundle"),
+            None
+        );
+        assert_eq!(
+            iteration_config.dwell_time_limit("hostnotready", "discovered"),
+            Some(std::time::Duration::from_secs(6 * 60 * 60))
+        );
+    }
+
     #[test]
     fn serialize_empty_state_controller_config() {
         let input = StateControllerConfig::default();
         let config_str = serde_json::to_string(&input).unwrap();
         assert_eq!(
             config_str,
-            r#"{"iteration_time":"30s","max_object_handling_time":"180s","max_concurrency":10,"processor_dispatch_interval":"2s","processor_log_interval":"60s","metric_emission_interval":"60s","metric_hold_time":"300s"}"#
+            r#"{"iteration_time":"30s","max_object_handling_time":"180s","max_concurrency":10,"processor_dispatch_interval":"2s","processor_log_interval":"60s","metric_emission_interval":"60s","metric_hold_time":"300s","priority_aging_interval":"300s"}"#
         );
         let config: StateControllerConfig = serde_json::from_str(&config_str).unwrap();
         assert_eq!(config, input);
@@ -2949,6 +3277,110 @@ mod tests {
         assert!(config.validate_web_ui_sidebar_tools().is_err());
     }
 
+    #[test]
+    fn deserialize_state_change_webhooks_config() {
+        let config = r#"{
+            "enabled": true,
+            "endpoints": [
+                {
+                    "name": "tenant-automation",
+                    "url": "https://hooks.example.com/carbide",
+                    "object_types": ["machine", "power_shelf"],
+                    "target_states": ["ready", "assigned.ready"]
+                },
+                {
+                    "name": "audit",
+                    "url": "https://audit.example.com"
+                }
+            ],
+            "max_attempts": 3,
+            "initial_retry_backoff": "10s"
+        }"#;
+        let config: StateChangeWebhooksConfig = serde_json::from_str(config).unwrap();
+
+        assert!(config.enabled);
+        assert_eq!(config.max_attempts, 3);
+        assert_eq!(
+            config.initial_retry_backoff,
+            std::time::Duration::from_secs(10)
+        );
+        assert_eq!(
+            config.max_retry_backoff,
+            StateChangeWebhooksConfig::default_max_retry_backoff()
+        );
+        assert_eq!(
+            config.queue_capacity,
+            StateChangeWebhooksConfig::default_queue_capacity()
+        );
+        assert_eq!(
+            config.endpoints,
+            vec![
+                StateChangeWebhookEndpointConfig {
+                    name: "tenant-automation".to_string(),
+                    url: "https://hooks.example.com/carbide".to_string(),
+                    object_types: vec![
+                        StateChangeWebhookObjectType::Machine,
+                        StateChangeWebhookObjectType::PowerShelf
+                    ],
+                    target_states: vec!["ready".to_string(), "assigned.ready".to_string()],
+                },
+                StateChangeWebhookEndpointConfig {
+                    name: "audit".to_string(),
+                    url: "https://audit.example.com".to_string(),
+                    object_types: vec![],
+                    target_states: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_state_change_webhooks() {
+        let mut config: CarbideConfig = Figment::new()
+            .merge(Toml::file(format!("{TEST_DATA_DIR}/min_config.toml")))
+            .extract()
+            .unwrap();
+        config.validate_state_change_webhooks().unwrap();
+
+        let endpoint = |name: &str, url: &str| StateChangeWebhookEndpointConfig {
+            name: name.to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        };
+
+        config.state_change_webhooks = Some(StateChangeWebhooksConfig {
+            endpoints: vec![
+                endpoint("a", "https://a.example.com"),
+                endpoint("b", "https://b.example.com/hook"),
+            ],
+            ..Default::default()
+        });
+        config.validate_state_change_webhooks().unwrap();
+
+        config.state_change_webhooks = Some(StateChangeWebhooksConfig {
+            endpoints: vec![endpoint("a", "http://a.example.com")],
+            ..Default::default()
+        });
+        let err = config
+            .validate_state_change_webhooks()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("must use https"), "unexpected error: {err}");
+
+        config.state_change_webhooks = Some(StateChangeWebhooksConfig {
+            endpoints: vec![
+                endpoint("a", "https://a.example.com"),
+                endpoint("a", "https://b.example.com"),
+            ],
+            ..Default::default()
+        });
+        let err = config
+            .validate_state_change_webhooks()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("duplicate"), "unexpected error: {err}");
+    }
+
     #[test]
     fn serialize_configured_state_controller_config() {
         let input = StateControllerConfig {
@@ -2959,11 +3391,13 @@ mod tests {
             processor_log_interval: std::time::Duration::from_secs(60),
             metric_emission_interval: std::time::Duration::from_secs(60),
             metric_hold_time: std::time::Duration::from_secs(5 * 60),
+            state_dwell_time_limits: HashMap::new(),
+            priority_aging_interval: std::time::Duration::from_secs(5 * 60),
         };
         let config_str = serde_json::to_string(&input).unwrap();
         assert_eq!(
             config_str,
-            r#"{"iteration_time":"11s","max_object_handling_time":"22s","max_concurrency":33,"processor_dispatch_interval":"2s","processor_log_interval":"60s","metric_emission_interval":"60s","metric_hold_time":"300s"}"#
+            r#"{"iteration_time":"11s","max_object_handling_time":"22s","max_concurrency":33,"processor_dispatch_interval":"2s","processor_log_interval":"60s","metric_emission_interval":"60s","metric_hold_time":"300s","priority_aging_interval":"300s"}"#
         );
         let config: StateControllerConfig = serde_json::from_str(&config_str).unwrap();
         assert_eq!(config, input);
@@ -3124,6 +3558,7 @@ mod tests {
                 rotate_switch_nvos_credentials: Arc::new(false.into()),
                 force_dpu_nic_mode: Arc::new(false.into()),
                 explore_mode: SiteExplorerExploreMode::LibRedfish,
+                subnet_sweep: Default::default(),
             }
         );
         assert_eq!(
@@ -3137,6 +3572,8 @@ mod tests {
                     processor_log_interval: std::time::Duration::from_secs(60),
                     metric_emission_interval: std::time::Duration::from_secs(60),
                     metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                    state_dwell_time_limits: HashMap::new(),
+                    priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                 },
                 dpu_wait_time: Duration::minutes(7),
                 power_down_wait: Duration::seconds(17),
@@ -3158,6 +3595,8 @@ mod tests {
                     processor_log_interval: std::time::Duration::from_secs(60),
                     metric_emission_interval: std::time::Duration::from_secs(60),
                     metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                    state_dwell_time_limits: HashMap::new(),
+                    priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                 },
             }
         );
@@ -3172,6 +3611,8 @@ mod tests {
                     processor_log_interval: std::time::Duration::from_secs(60),
                     metric_emission_interval: std::time::Duration::from_secs(60),
                     metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                    state_dwell_time_limits: HashMap::new(),
+                    priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                 },
             }
         );
@@ -3297,6 +3738,26 @@ mod tests {
                 rotate_switch_nvos_credentials: Arc::new(false.into()),
                 force_dpu_nic_mode: Arc::new(false.into()),
                 explore_mode: SiteExplorerExploreMode::LibRedfish,
+                // Compared separately below, SiteExplorerConfig equality does not cover it
+                subnet_sweep: Default::default(),
+            }
+        );
+        assert_eq!(
+            config.site_explorer.subnet_sweep,
+            SubnetSweepConfig {
+                enabled: true,
+                ranges: vec![
+                    "10.20.0.0/24".parse().unwrap(),
+                    "10.20.1.0/25".parse().unwrap(),
+                ],
+                exclude: vec!["10.20.0.1/32".parse().unwrap()],
+                port: 443,
+                run_interval: std::time::Duration::from_secs(30),
+                probes_per_run: 32,
+                concurrent_probes: 4,
+                probe_interval: std::time::Duration::from_millis(250),
+                probe_timeout: std::time::Duration::from_secs(5),
+                stale_after: std::time::Duration::from_secs(2 * 24 * 60 * 60),
             }
         );
 
@@ -3321,6 +3782,8 @@ mod tests {
                     processor_log_interval: std::time::Duration::from_secs(60),
                     metric_emission_interval: std::time::Duration::from_secs(60),
                     metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                    state_dwell_time_limits: HashMap::new(),
+                    priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                 },
                 dpu_wait_time: Duration::minutes(3),
                 power_down_wait: Duration::seconds(13),
@@ -3342,6 +3805,8 @@ mod tests {
                     processor_log_interval: std::time::Duration::from_secs(60),
                     metric_emission_interval: std::time::Duration::from_secs(60),
                     metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                    state_dwell_time_limits: HashMap::new(),
+                    priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                 },
             }
         );
@@ -3356,6 +3821,8 @@ mod tests {
                     processor_log_interval: std::time::Duration::from_secs(60),
                     metric_emission_interval: std::time::Duration::from_secs(60),
                     metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                    state_dwell_time_limits: HashMap::new(),
+                    priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                 },
             }
         );
@@ -3606,6 +4073,7 @@ mod tests {
                 rotate_switch_nvos_credentials: Arc::new(false.into()),
                 force_dpu_nic_mode: Arc::new(false.into()),
                 explore_mode: SiteExplorerExploreMode::LibRedfish,
+                subnet_sweep: Default::default(),
             }
         );
 
@@ -3630,6 +4098,8 @@ mod tests {
                     processor_log_interval: std::time::Duration::from_secs(60),
                     metric_emission_interval: std::time::Duration::from_secs(60),
                     metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                    state_dwell_time_limits: HashMap::new(),
+                    priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                 },
                 dpu_wait_time: Duration::minutes(7),
                 power_down_wait: Duration::seconds(17),
@@ -3651,6 +4121,8 @@ mod tests {
                     processor_log_interval: std::time::Duration::from_secs(60),
                     metric_emission_interval: std::time::Duration::from_secs(60),
                     metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                    state_dwell_time_limits: HashMap::new(),
+                    priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                 },
             }
         );
@@ -3665,6 +4137,8 @@ mod tests {
                     processor_log_interval: std::time::Duration::from_secs(60),
                     metric_emission_interval: std::time::Duration::from_secs(60),
                     metric_hold_time: std::time::Duration::from_secs(5 * 60),
+                    state_dwell_time_limits: HashMap::new(),
+                    priority_aging_interval: std::time::Duration::from_secs(5 * 60),
                 },
             }
         );
@@ -0,0 +1,141 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Tokens for ssh-console's websocket console.
+//!
+//! ssh-console verifies them with the public key matching `[console_token].signing_key_path`,
+//! and checks with carbide-api that instances still belong to the organization in the token.
+
+use std::str::FromStr;
+
+use ::rpc::forge as rpc;
+use carbide_uuid::instance::InstanceId;
+use carbide_uuid::machine::MachineId;
+use chrono::Utc;
+use tonic::{Request, Response, Status};
+
+use crate::CarbideError;
+use crate::api::{Api, log_request_data, log_tenant_organization_id};
+use crate::auth::AuthContext;
+use crate::machine_identity::{Es256Signer, SignOptions, Signer};
+
+/// `kid` of console tokens. ssh-console only trusts a single key, so it is not used to select one.
+const CONSOLE_TOKEN_KEY_ID: &str = "console";
+
+pub(crate) async fn create_console_token(
+    api: &Api,
+    request: Request<rpc::CreateConsoleTokenRequest>,
+) -> Result<Response<rpc::CreateConsoleTokenResponse>, Status> {
+    log_request_data(&request);
+
+    let Some(config) = api.runtime_config.console_token.as_ref() else {
+        return Err(CarbideError::UnavailableError(
+            "Console tokens are not configured in site config".into(),
+        )
+        .into());
+    };
+
+    let admin_user = request
+        .extensions()
+        .get::<AuthContext>()
+        .and_then(|ctx| ctx.get_external_user_name())
+        .map(String::from);
+    let rpc::CreateConsoleTokenRequest {
+        console,
+        user,
+        tenant_organization_id,
+    } = request.into_inner();
+
+    let (sub, roles) = match (admin_user, &tenant_organization_id) {
+        // Admins asking for their own access get the admin role, which grants any console.
+        (Some(admin_user), None) => {
+            if InstanceId::from_str(&console).is_err() && MachineId::from_str(&console).is_err() {
+                return Err(CarbideError::InvalidArgument(format!(
+                    "{console} is neither a machine ID nor an instance ID"
+                ))
+                .into());
+            }
+            (admin_user, vec![config.admin_role.clone()])
+        }
+        (_, Some(tenant_organization_id)) => {
+            log_tenant_organization_id(tenant_organization_id);
+            let instance_id = InstanceId::from_str(&console).map_err(|_| {
+                CarbideError::PermissionDeniedError(format!(
+                    "{console} is not an instance ID, only admins can access machine consoles"
+                ))
+            })?;
+            let instance = db::instance::find_by_id(&mut api.db_reader(), instance_id)
+                .await?
+                .ok_or_else(|| CarbideError::NotFoundError {
+                    kind: "instance",
+                    id: instance_id.to_string(),
+                })?;
+            if instance.config.tenant.tenant_organization_id.as_str()
+                != tenant_organization_id.as_str()
+            {
+                return Err(CarbideError::PermissionDeniedError(format!(
+                    "instance {instance_id} does not belong to {tenant_organization_id}"
+                ))
+                .into());
+            }
+            if user.is_empty() {
+                return Err(CarbideError::MissingArgument("user").into());
+            }
+            (user, vec![])
+        }
+        (None, None) => {
+            return Err(CarbideError::MissingArgument("tenant_organization_id").into());
+        }
+    };
+
+    let signing_key = tokio::fs::read(&config.signing_key_path)
+        .await
+        .map_err(|e| {
+            CarbideError::internal(format!(
+                "Error reading console token signing key at {}: {e}",
+                config.signing_key_path.display()
+            ))
+        })?;
+    let signer = Es256Signer::new(&signing_key, CONSOLE_TOKEN_KEY_ID)
+        .map_err(|e| CarbideError::internal(format!("Invalid console token signing key: {e}")))?;
+
+    let now = Utc::now();
+    let expires_at = now
+        + chrono::Duration::from_std(config.lifetime)
+            .map_err(|e| CarbideError::internal(format!("Invalid console token lifetime: {e}")))?;
+    let mut claims = serde_json::json!({
+        "sub": sub,
+        "aud": config.audience,
+        "iat": now.timestamp(),
+        "exp": expires_at.timestamp(),
+        "console": console,
+        "roles": roles,
+    });
+    if let Some(tenant_organization_id) = tenant_organization_id {
+        claims["tenant_organization_id"] = tenant_organization_id.into();
+    }
+    let token = signer
+        .sign(&claims, &SignOptions::default())
+        .map_err(|e| CarbideError::internal(format!("Error signing console token: {e}")))?;
+
+    tracing::info!(console, user = sub, "issued console token");
+
+    Ok(Response::new(rpc::CreateConsoleTokenResponse {
+        token,
+        expires_at: Some(expires_at.into()),
+    }))
+}
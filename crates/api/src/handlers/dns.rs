@@ -14,10 +14,14 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::collections::HashSet;
+use std::net::IpAddr;
+
 use ::rpc::protos;
 use db::dns::resource_record;
 use dns_record::constants::*;
-use dns_record::{DnsResourceRecordReply, DnsResourceRecordType};
+use dns_record::{DnsResourceRecordReply, DnsResourceRecordType, SoaRecord, reverse};
+use serde::{Deserialize, Serialize};
 use tonic::{Request, Response, Status};
 
 use crate::CarbideError;
@@ -36,6 +40,33 @@ impl From<DnsResourceRecordLookupResponse> for protos::dns::DnsResourceRecordLoo
     }
 }
 
+fn soa_reply(zone_name: String, soa: &SoaRecord) -> DnsResourceRecordReply {
+    DnsResourceRecordReply {
+        qtype: DnsResourceRecordType::SOA.to_string(),
+        qname: zone_name,
+        ttl: soa.ttl.0 as u32,
+        content: soa.to_string(),
+        domain_id: None,
+        scope_mask: None,
+        auth: None,
+    }
+}
+
+fn ptr_reply(
+    reverse_name: String,
+    forward: resource_record::DbResourceRecord,
+) -> DnsResourceRecordReply {
+    DnsResourceRecordReply {
+        qtype: DnsResourceRecordType::PTR.to_string(),
+        qname: reverse_name,
+        ttl: forward.ttl as u32,
+        content: forward.q_name,
+        domain_id: None,
+        scope_mask: None,
+        auth: None,
+    }
+}
+
 async fn lookup_soa_record(
     db: impl DbReader<'_>,
     query_name: &str,
@@ -48,15 +79,52 @@ async fn lookup_soa_record(
             kind: "soa_record",
             id: query_name.to_string(),
         })?;
-    Ok(DnsResourceRecordReply {
-        qtype: DnsResourceRecordType::SOA.to_string(),
-        qname: query_name.to_string(),
-        ttl: record.0.ttl.0 as u32,
-        content: record.0.to_string(),
-        domain_id: None,
-        scope_mask: None,
-        auth: None,
-    })
+    Ok(soa_reply(query_name.to_string(), &record.0))
+}
+
+/// SOA of a reverse zone synthesized for the network segment prefixes. The zone borrows
+/// the SOA of the segment's domain, so its serial moves along with the domain.
+///
+/// Returns None if `query_name` isn't one of the synthesized reverse zones.
+async fn lookup_reverse_soa_record(
+    db: impl DbReader<'_>,
+    query_name: &str,
+) -> Result<Option<DnsResourceRecordReply>, tonic::Status> {
+    let Some(zone) = reverse::parse_reverse_name(query_name) else {
+        return Ok(None);
+    };
+    tracing::debug!("Looking up reverse zone SOA record for {}", query_name);
+
+    let prefix = resource_record::find_reverse_zone_prefixes(db, Some(zone))
+        .await
+        .map_err(CarbideError::from)?
+        .into_iter()
+        .find(|p| reverse::reverse_zones(p.prefix).contains(&zone));
+    let Some(prefix) = prefix else {
+        return Ok(None);
+    };
+
+    let zone_name = reverse::reverse_zone_name(zone);
+    let soa = prefix.soa.unwrap_or_else(|| SoaRecord::new(&zone_name));
+    Ok(Some(soa_reply(zone_name, &soa)))
+}
+
+/// PTR records of an address, pointing at every forward name that resolves to it.
+async fn lookup_ptr_records(
+    txn: impl DbReader<'_>,
+    query_name: &str,
+    address: IpAddr,
+) -> Result<Vec<DnsResourceRecordReply>, tonic::Status> {
+    tracing::debug!("Looking up PTR records for {}", address);
+
+    let result = resource_record::find_records_by_address(txn, address)
+        .await
+        .map_err(CarbideError::from)?
+        .into_iter()
+        .map(|db_record| ptr_reply(query_name.to_string(), db_record))
+        .collect();
+
+    Ok(result)
 }
 
 /// Returns ALL record types (A, AAAA, CNAME, etc.) - PowerDNS filters to requested type
@@ -73,6 +141,11 @@ async fn lookup_records_by_qname(
         query_name.to_string()
     };
 
+    // Reverse names of addresses are answered with PTR records, they have no others
+    if let Some(address) = reverse::parse_reverse_address(query_name) {
+        return lookup_ptr_records(txn, &qname_with_dot, address).await;
+    }
+
     let result = resource_record::find_record(txn, &qname_with_dot)
         .await
         .map_err(CarbideError::from)?
@@ -157,12 +230,28 @@ where
                 }
             }
         }
-        Ok(_) => {
-            tracing::debug!(
-                query_name = %query_name,
-                "No authoritative domain found for query - SOA not included"
-            );
-        }
+        Ok(_) => match lookup_reverse_soa_record(&mut *txn, &domain_name).await {
+            Ok(Some(soa)) => {
+                tracing::debug!(
+                    zone = %soa.qname,
+                    "Including reverse zone SOA record in ANY response"
+                );
+                dns_records.push(soa);
+            }
+            Ok(None) => {
+                tracing::debug!(
+                    query_name = %query_name,
+                    "No authoritative domain found for query - SOA not included"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(
+                    query_name = %query_name,
+                    error = %e,
+                    "Failed to lookup SOA record for reverse zone"
+                );
+            }
+        },
         Err(e) => {
             tracing::warn!(
                 query_name = %query_name,
@@ -255,6 +344,192 @@ pub async fn get_all_domain_metadata(
         result: proto_metadata,
     }))
 }
+/// Returns every record of a domain, for carbide-dns to build the zone it transfers to
+/// secondaries. The SOA is not included, it is looked up separately.
+pub async fn get_all_records_for_domain(
+    api: &Api,
+    request: Request<protos::dns::GetAllRecordsForDomainRequest>,
+) -> Result<Response<protos::dns::GetAllRecordsForDomainResponse>, Status> {
+    log_request_data(&request);
+
+    let domain_name = db::dns::normalize_domain(&request.into_inner().name);
+
+    let result = resource_record::get_all_records(&api.database_connection, &domain_name)
+        .await
+        .map_err(CarbideError::from)?
+        .into_iter()
+        .map(|db_record| {
+            let model_record: model::dns::ResourceRecord = db_record.into();
+            DnsResourceRecordReply::from(model_record).into()
+        })
+        .collect::<Vec<_>>();
+
+    tracing::debug!(
+        domain = %domain_name,
+        count = result.len(),
+        "Found records for domain"
+    );
+
+    Ok(Response::new(protos::dns::GetAllRecordsForDomainResponse {
+        result,
+    }))
+}
+
+/// Records per page of a DNS snapshot, which keeps pages well under the gRPC message size limit.
+const DNS_SNAPSHOT_PAGE_SIZE: u32 = 5000;
+
+/// Where the next page of a DNS snapshot starts. Opaque to carbide-dns.
+#[derive(Serialize, Deserialize)]
+struct DnsSnapshotPageToken {
+    version: String,
+    offset: i64,
+}
+
+/// Every zone and record carbide-dns answers queries from, mirroring what lookup_record
+/// would answer. carbide-dns polls with the version it holds, and the zones and records
+/// are only sent again, in pages, once that version is outdated.
+pub async fn get_dns_snapshot(
+    api: &Api,
+    request: Request<protos::dns::DnsSnapshotRequest>,
+) -> Result<Response<protos::dns::DnsSnapshotResponse>, Status> {
+    log_request_data(&request);
+
+    let request = request.into_inner();
+    let page_size = request
+        .page_size
+        .unwrap_or(DNS_SNAPSHOT_PAGE_SIZE)
+        .clamp(1, DNS_SNAPSHOT_PAGE_SIZE) as i64;
+
+    // Read before the zones and records, so that changes made while they are read show up
+    // as a new version next time.
+    let version = db::dns::snapshot_version(&api.database_connection)
+        .await?
+        .to_string();
+
+    let offset = match request.page_token {
+        Some(page_token) => {
+            let page_token: DnsSnapshotPageToken = serde_json::from_str(&page_token)
+                .map_err(|e| CarbideError::InvalidArgument(format!("Invalid page_token: {e}")))?;
+            if page_token.version != version {
+                return Err(CarbideError::FailedPrecondition(format!(
+                    "DNS snapshot changed from version {} to {version}, start over",
+                    page_token.version
+                ))
+                .into());
+            }
+            page_token.offset
+        }
+        None if request.version.as_ref() == Some(&version) => {
+            return Ok(Response::new(protos::dns::DnsSnapshotResponse {
+                version,
+                unchanged: true,
+                ..Default::default()
+            }));
+        }
+        None => 0,
+    };
+
+    let zones = if offset == 0 {
+        snapshot_zones(api).await?
+    } else {
+        vec![]
+    };
+
+    // One more than the page size, to know if there is a next page
+    let mut db_records = resource_record::get_all_records_all_domains_page(
+        &api.database_connection,
+        offset,
+        page_size + 1,
+    )
+    .await
+    .map_err(CarbideError::from)?;
+    let next_page_token = if db_records.len() as i64 > page_size {
+        db_records.truncate(page_size as usize);
+        let page_token = DnsSnapshotPageToken {
+            version: version.clone(),
+            offset: offset + page_size,
+        };
+        Some(serde_json::to_string(&page_token).map_err(CarbideError::from)?)
+    } else {
+        None
+    };
+
+    let mut records: Vec<protos::dns::DnsResourceRecord> = Vec::new();
+    for db_record in db_records {
+        if let Ok(address) = db_record.record.parse::<IpAddr>() {
+            let reverse_name = format!("{}.", reverse::reverse_name(address));
+            records.push(ptr_reply(reverse_name, db_record.clone()).into());
+        }
+        let model_record: model::dns::ResourceRecord = db_record.into();
+        records.push(DnsResourceRecordReply::from(model_record).into());
+    }
+
+    tracing::debug!(
+        %version,
+        offset,
+        zones = zones.len(),
+        records = records.len(),
+        last_page = next_page_token.is_none(),
+        "Built DNS snapshot page"
+    );
+
+    Ok(Response::new(protos::dns::DnsSnapshotResponse {
+        version,
+        unchanged: false,
+        zones,
+        records,
+        next_page_token,
+    }))
+}
+
+/// The zones of a DNS snapshot: domains and the reverse zones synthesized from segment prefixes.
+async fn snapshot_zones(api: &Api) -> Result<Vec<protos::dns::DnsSnapshotZone>, CarbideError> {
+    let domains = db::dns::domain::find_by(
+        &api.database_connection,
+        db::ObjectColumnFilter::<db::dns::domain::IdColumn>::All,
+    )
+    .await?;
+
+    // Explicit domains take precedence over synthesized reverse zones, as in lookup_record
+    let mut zone_names = HashSet::new();
+    let mut zones = Vec::new();
+    for domain in domains {
+        zone_names.insert(domain.name.clone());
+        zones.push(protos::dns::DnsSnapshotZone {
+            name: domain.name.clone(),
+            soa: domain
+                .soa
+                .as_ref()
+                .map(|soa| soa_reply(domain.name.clone(), &soa.0).into()),
+            metadata: domain.metadata.clone().map(Into::into),
+            domain: Some(model::dns::DomainInfo::from(domain).into()),
+        });
+    }
+
+    let prefixes =
+        resource_record::find_reverse_zone_prefixes(&api.database_connection, None).await?;
+    for prefix in prefixes {
+        for zone in reverse::reverse_zones(prefix.prefix) {
+            let zone_name = reverse::reverse_zone_name(zone);
+            if !zone_names.insert(zone_name.clone()) {
+                continue;
+            }
+            let soa = prefix
+                .soa
+                .clone()
+                .unwrap_or_else(|| SoaRecord::new(&zone_name));
+            zones.push(protos::dns::DnsSnapshotZone {
+                name: zone_name.clone(),
+                soa: Some(soa_reply(zone_name, &soa).into()),
+                domain: None,
+                metadata: None,
+            });
+        }
+    }
+
+    Ok(zones)
+}
+
 pub async fn lookup_record(
     api: &Api,
     request: Request<protos::dns::DnsResourceRecordLookupRequest>,
@@ -287,9 +562,17 @@ pub async fn lookup_record(
             lookup_any_record(&mut api.db_reader(), &normalized).await?
         }
         DnsResourceRecordType::SOA => {
-            // SOA queries: only return SOA record for the domain
+            // SOA queries: only return SOA record for the domain, or the reverse zone
+            // of a segment prefix if there's no such domain
             let normalized = db::dns::normalize_domain(&qname);
-            let record = lookup_soa_record(&api.database_connection, &normalized).await?;
+            let record = match lookup_soa_record(&api.database_connection, &normalized).await {
+                Err(status) if status.code() == tonic::Code::NotFound => {
+                    lookup_reverse_soa_record(&api.database_connection, &normalized)
+                        .await?
+                        .ok_or(status)?
+                }
+                result => result?,
+            };
             vec![record]
         }
         _ => {
@@ -379,28 +662,34 @@ pub async fn lookup_record_legacy_compat(
         &qname
     };
 
-    // Try to find the domain this record belongs to
-    // Find all domains and match the longest suffix
-    let domains = db::dns::domain::find_by(
-        &api.database_connection,
-        db::ObjectColumnFilter::<db::dns::domain::IdColumn>::All,
-    )
-    .await?;
-
-    let domain = domains
-        .iter()
-        .filter(|d| domain_name.ends_with(&d.name))
-        .max_by_key(|d| d.name.len())
-        .ok_or_else(|| CarbideError::NotFoundError {
-            kind: "domain",
-            id: format!("No domain found for qname: {}", qname),
-        })?;
+    // Reverse zones are synthesized from segment prefixes and have no domain
+    let zone_id = if reverse::parse_reverse_name(domain_name).is_some() {
+        "-1".to_string()
+    } else {
+        // Try to find the domain this record belongs to
+        // Find all domains and match the longest suffix
+        let domains = db::dns::domain::find_by(
+            &api.database_connection,
+            db::ObjectColumnFilter::<db::dns::domain::IdColumn>::All,
+        )
+        .await?;
+
+        let domain = domains
+            .iter()
+            .filter(|d| domain_name.ends_with(&d.name))
+            .max_by_key(|d| d.name.len())
+            .ok_or_else(|| CarbideError::NotFoundError {
+                kind: "domain",
+                id: format!("No domain found for qname: {}", qname),
+            })?;
+        domain.id.to_string()
+    };
 
     // Convert to new request format
     let lookup_request = protos::dns::DnsResourceRecordLookupRequest {
         qtype,
         qname: qname.clone(),
-        zone_id: domain.id.to_string(),
+        zone_id,
         local: None,
         remote: None,
         real_remote: None,
@@ -54,6 +54,7 @@ use crate::instance::{
     InstanceAllocationRequest, allocate_ib_port_guid, allocate_instance, allocate_network,
     validate_ib_partition_ownership, validate_os_definition_usable,
 };
+use crate::state_controller::controller::QueuePriority;
 use crate::{CarbideError, CarbideResult};
 
 /// Refuses `ReleaseInstance` when aggregate host health includes [`HealthAlertClassification::prevent_instance_deletion`].
@@ -143,9 +144,32 @@ pub(crate) async fn allocate(
     // Row-locking on Machine records happens in allocate_instance
     let mh_snapshot = allocate_instance(api, request, api.runtime_config.host_health).await?;
 
+    enqueue_allocated_hosts(api, std::slice::from_ref(&mh_snapshot)).await;
+
     Ok(Response::new(snapshot_to_instance(mh_snapshot)?))
 }
 
+/// How long the state controller may take to pick up a host after an instance
+/// got allocated on it, before it gets handled ahead of all other hosts
+const INSTANCE_ALLOCATION_HANDLING_DEADLINE: chrono::Duration = chrono::Duration::seconds(30);
+
+/// Wakes up the state handler for hosts with freshly allocated instances.
+/// Since the tenant is waiting for the instance, the hosts are handled ahead of
+/// hosts which are only checked periodically.
+async fn enqueue_allocated_hosts(api: &Api, snapshots: &[ManagedHostStateSnapshot]) {
+    let deadline = chrono::Utc::now() + INSTANCE_ALLOCATION_HANDLING_DEADLINE;
+    for mh_snapshot in snapshots {
+        let machine_id = mh_snapshot.host_snapshot.id;
+        if let Err(err) = api
+            .machine_state_handler_enqueuer
+            .enqueue_object_with_priority(&machine_id, QueuePriority::Urgent, Some(deadline))
+            .await
+        {
+            tracing::warn!(%err, %machine_id, "Failed to wake up state handler for machine");
+        }
+    }
+}
+
 pub(crate) async fn batch_allocate(
     api: &Api,
     request: Request<rpc::BatchInstanceAllocationRequest>,
@@ -190,6 +214,8 @@ pub(crate) async fn batch_allocate(
                 tracing::error!(error = %e, "Batch instance allocation failed");
             })?;
 
+    enqueue_allocated_hosts(api, &snapshots).await;
+
     // Convert all snapshots to Instance responses
     let instances = snapshots
         .into_iter()
@@ -23,6 +23,7 @@ pub mod boot_override;
 pub mod client_resolution;
 pub mod component_manager;
 pub mod compute_allocation;
+pub mod console_token;
 pub mod credential;
 pub mod db;
 pub mod dns;
@@ -18,6 +18,7 @@
 use std::net::IpAddr;
 use std::str::FromStr;
 
+use ::rpc::Timestamp;
 use ::rpc::forge::{self as rpc, IsBmcInManagedHostResponse};
 use config_version::ConfigVersion;
 use tokio::net::lookup_host;
@@ -337,3 +338,89 @@ pub(crate) async fn delete_explored_endpoint(
         )),
     }))
 }
+
+pub(crate) async fn find_swept_bmc_endpoints(
+    api: &Api,
+    request: Request<::rpc::site_explorer::SweptBmcEndpointSearchFilter>,
+) -> Result<Response<::rpc::site_explorer::SweptBmcEndpointList>, Status> {
+    log_request_data(&request);
+
+    let mut txn = api.txn_begin().await?;
+    let endpoints = db::swept_bmc_endpoints::find_all(&mut txn).await?;
+    txn.commit().await?;
+
+    Ok(Response::new(::rpc::site_explorer::SweptBmcEndpointList {
+        endpoints: endpoints
+            .into_iter()
+            .map(|endpoint| ::rpc::site_explorer::SweptBmcEndpoint {
+                address: endpoint.address.to_string(),
+                port: endpoint.port as u32,
+                vendor: endpoint.vendor,
+                first_seen: Some(Timestamp::from(endpoint.first_seen)),
+                last_seen: Some(Timestamp::from(endpoint.last_seen)),
+            })
+            .collect(),
+    }))
+}
+
+pub(crate) async fn delete_swept_bmc_endpoint(
+    api: &Api,
+    request: Request<rpc::DeleteSweptBmcEndpointRequest>,
+) -> Result<Response<()>, tonic::Status> {
+    log_request_data(&request);
+    let req = request.into_inner();
+
+    let bmc_ip = IpAddr::from_str(&req.ip_address).map_err(CarbideError::from)?;
+
+    let mut txn = api.txn_begin().await?;
+
+    if !db::swept_bmc_endpoints::delete(&mut txn, bmc_ip).await? {
+        return Err(CarbideError::NotFoundError {
+            kind: "swept_bmc_endpoint",
+            id: bmc_ip.to_string(),
+        }
+        .into());
+    }
+
+    txn.commit().await?;
+
+    Ok(Response::new(()))
+}
+
+pub(crate) async fn find_explored_endpoint_report_history(
+    api: &Api,
+    request: Request<::rpc::site_explorer::ExploredEndpointReportHistoryRequest>,
+) -> Result<Response<::rpc::site_explorer::ExploredEndpointReportHistory>, Status> {
+    log_request_data(&request);
+    let req = request.into_inner();
+
+    let bmc_ip = IpAddr::from_str(&req.address).map_err(CarbideError::from)?;
+
+    let mut txn = api.txn_begin().await?;
+    let records = db::explored_endpoint_report_history::find_by_address(&mut txn, bmc_ip).await?;
+    txn.commit().await?;
+
+    Ok(Response::new(
+        ::rpc::site_explorer::ExploredEndpointReportHistory {
+            records: records.into_iter().map(Into::into).collect(),
+        },
+    ))
+}
+
+pub(crate) async fn acknowledge_explored_endpoint_drift(
+    api: &Api,
+    request: Request<rpc::AcknowledgeExploredEndpointDriftRequest>,
+) -> Result<Response<rpc::AcknowledgeExploredEndpointDriftResponse>, Status> {
+    log_request_data(&request);
+    let req = request.into_inner();
+
+    let bmc_ip = IpAddr::from_str(&req.ip_address).map_err(CarbideError::from)?;
+
+    let mut txn = api.txn_begin().await?;
+    let acknowledged = db::explored_endpoint_report_history::acknowledge(&mut txn, bmc_ip).await?;
+    txn.commit().await?;
+
+    Ok(Response::new(
+        rpc::AcknowledgeExploredEndpointDriftResponse { acknowledged },
+    ))
+}
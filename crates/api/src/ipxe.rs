@@ -194,6 +194,7 @@ impl PxeInstructions {
         ipxeos: &IpxeScript,
         base_url: &str,
         console: &str,
+        arch: rpc::MachineArchitecture,
     ) -> Result<String, CarbideError> {
         let renderer = DefaultIpxeScriptRenderer::new();
 
@@ -221,6 +222,22 @@ impl PxeInstructions {
                     value: console.to_string(),
                 });
             }
+
+            // Lets a template branch per architecture with {% if arch == "aarch64" %}
+            if template
+                .reserved_params
+                .iter()
+                .any(|p| p.to_lowercase() == "arch")
+            {
+                let arch = match arch {
+                    rpc::MachineArchitecture::Arm => "aarch64",
+                    rpc::MachineArchitecture::X86 => "x86_64",
+                };
+                reserved_params.push(IpxeTemplateParameter {
+                    name: "arch".to_string(),
+                    value: arch.to_string(),
+                });
+            }
         }
 
         renderer
@@ -508,7 +525,12 @@ exit ||
                                     == model::operating_system_definition::OS_TYPE_TEMPLATED_IPXE
                                 {
                                     let ipxeos = operating_system_row_to_ipxe_script(&row)?;
-                                    Self::render_ipxe_script(&ipxeos, "${base-url}", console)?
+                                    Self::render_ipxe_script(
+                                        &ipxeos,
+                                        "${base-url}",
+                                        console,
+                                        target.arch,
+                                    )?
                                 } else {
                                     row.ipxe_script.unwrap_or_default()
                                 }
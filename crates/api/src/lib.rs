@@ -67,6 +67,7 @@ mod storage;
 #[cfg(test)]
 mod tests;
 mod web;
+mod webhook_state_change_hook;
 
 // Allow carbide_macros::sqlx_test to be referred as #[crate::sqlx_test]
 #[cfg(test)]
@@ -121,6 +121,7 @@ impl StateChangeHook<MachineId, ManagedHostState> for MqttStateChangeHook {
         let message = ManagedHostStateChangeMessage {
             machine_id: event.object_id,
             managed_host_state: event.new_state,
+            previous_managed_host_state: event.previous_state,
             timestamp: event.timestamp,
         };
         let topic = self.build_topic(event.object_id);
@@ -34,6 +34,9 @@ pub struct ManagedHostStateChangeMessage<'a> {
     pub timestamp: DateTime<Utc>,
     /// The managed host state.
     pub managed_host_state: &'a ManagedHostState,
+    /// The managed host state before the transition. Omitted if unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_managed_host_state: Option<&'a ManagedHostState>,
 }
 
 impl<'a> ManagedHostStateChangeMessage<'a> {
@@ -63,6 +66,7 @@ mod tests {
         let message = ManagedHostStateChangeMessage {
             machine_id: &machine_id,
             managed_host_state: &state,
+            previous_managed_host_state: None,
             timestamp,
         };
         let json = message.to_json_bytes().unwrap();
@@ -85,6 +89,7 @@ mod tests {
         let message = ManagedHostStateChangeMessage {
             machine_id: &machine_id,
             managed_host_state: &state,
+            previous_managed_host_state: None,
             timestamp,
         };
         let json = message.to_json_bytes().unwrap();
@@ -95,6 +100,38 @@ mod tests {
         assert!(state_obj.get("instance_state").is_some());
     }
 
+    #[test]
+    fn test_previous_state_serialization() {
+        let machine_id = test_machine_id();
+        let previous_state = ManagedHostState::Ready;
+        let state = ManagedHostState::Assigned {
+            instance_state: InstanceState::Ready,
+        };
+
+        let message = ManagedHostStateChangeMessage {
+            machine_id: &machine_id,
+            managed_host_state: &state,
+            previous_managed_host_state: Some(&previous_state),
+            timestamp: Utc::now(),
+        };
+        let json = message.to_json_bytes().unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&json).unwrap();
+
+        let previous_obj = parsed.get("previous_managed_host_state").unwrap();
+        assert_eq!(previous_obj.get("state").unwrap(), "ready");
+        let state_obj = parsed.get("managed_host_state").unwrap();
+        assert_eq!(state_obj.get("state").unwrap(), "assigned");
+
+        // The previous state is omitted if unknown
+        let message = ManagedHostStateChangeMessage {
+            previous_managed_host_state: None,
+            ..message
+        };
+        let json = message.to_json_bytes().unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert!(parsed.get("previous_managed_host_state").is_none());
+    }
+
     #[test]
     fn test_timestamp_is_rfc3339() {
         let machine_id = test_machine_id();
@@ -104,6 +141,7 @@ mod tests {
         let message = ManagedHostStateChangeMessage {
             machine_id: &machine_id,
             managed_host_state: &state,
+            previous_managed_host_state: None,
             timestamp,
         };
         let json = message.to_json_bytes().unwrap();
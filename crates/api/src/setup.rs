@@ -62,7 +62,9 @@ use tracing_log::AsLog as _;
 
 use crate::api::Api;
 use crate::api::metrics::ApiMetricsEmitter;
-use crate::cfg::file::{CarbideConfig, InitialObjectsConfig, ListenMode};
+use crate::cfg::file::{
+    CarbideConfig, InitialObjectsConfig, ListenMode, StateChangeWebhookObjectType,
+};
 use crate::dpa::handler::{DpaInfo, start_dpa_handler};
 use crate::dynamic_settings::DynamicSettings;
 use crate::errors::CarbideError;
@@ -83,6 +85,7 @@ use crate::state_controller::dpa_interface::handler::DpaInterfaceStateHandler;
 use crate::state_controller::dpa_interface::io::DpaInterfaceStateControllerIO;
 use crate::state_controller::ib_partition::handler::IBPartitionStateHandler;
 use crate::state_controller::ib_partition::io::IBPartitionStateControllerIO;
+use crate::state_controller::io::StateControllerIO;
 use crate::state_controller::machine::handler::MachineStateHandlerBuilder;
 use crate::state_controller::machine::io::MachineStateControllerIO;
 use crate::state_controller::network_segment::handler::NetworkSegmentStateHandler;
@@ -93,9 +96,12 @@ use crate::state_controller::rack::handler::RackStateHandler;
 use crate::state_controller::rack::io::RackStateControllerIO;
 use crate::state_controller::spdm::handler::SpdmAttestationStateHandler;
 use crate::state_controller::spdm::io::SpdmStateControllerIO;
-use crate::state_controller::state_change_emitter::StateChangeEmitterBuilder;
+use crate::state_controller::state_change_emitter::{
+    StateChangeEmitter, StateChangeEmitterBuilder,
+};
 use crate::state_controller::switch::handler::SwitchStateHandler;
 use crate::state_controller::switch::io::SwitchStateControllerIO;
+use crate::webhook_state_change_hook::hook::StateChangeWebhooks;
 use crate::{attestation, db_init, ethernet_virtualization, listener};
 
 /// The resolved set of network declarations passed from `start_api` into
@@ -376,6 +382,16 @@ pub fn parse_carbide_config(
     // Validate that admin-UI tool entries have unique names.
     config.validate_web_ui_sidebar_tools()?;
 
+    // Validate that state change webhook endpoints have unique names and HTTPS URLs.
+    config.validate_state_change_webhooks()?;
+
+    // Validate that the site-explorer subnet sweep has ranges and stays within safe limits.
+    config
+        .site_explorer
+        .subnet_sweep
+        .validate()
+        .map_err(|e| eyre::eyre!(e))?;
+
     // Publish the configured tool list to the web layer so the
     // admin-UI sidebar and per-machine "Logs" deep link can read it.
     crate::web::init_tools(config.web_ui_sidebar_tools.clone());
@@ -772,6 +788,23 @@ pub async fn start_api(
     Ok(())
 }
 
+/// Builds the state change emitter for a state controller whose state changes
+/// are only published to webhooks.
+fn webhook_state_change_emitter<IO>(
+    webhooks: Option<&StateChangeWebhooks>,
+    object_type: StateChangeWebhookObjectType,
+) -> StateChangeEmitter<IO::ObjectId, IO::ControllerState>
+where
+    IO: StateControllerIO,
+    IO::ControllerState: serde::Serialize,
+{
+    let mut emitter_builder = StateChangeEmitterBuilder::default();
+    if let Some(webhooks) = webhooks {
+        emitter_builder = emitter_builder.hook(Box::new(webhooks.hook::<IO>(object_type)));
+    }
+    emitter_builder.build()
+}
+
 /// Initialize and spawn all controllers and background tasks.
 ///
 /// All background tasks will be spawned into `join_set`, which can be awaited with
@@ -928,6 +961,25 @@ pub async fn initialize_and_start_controllers<'a>(
         dpa_info = Some(Arc::new(info));
     }
 
+    // Create state change webhooks if enabled
+    let state_change_webhooks = match carbide_config.state_change_webhooks {
+        Some(ref config) if config.enabled => {
+            tracing::info!(
+                "State change webhooks enabled for {} endpoints",
+                config.endpoints.len()
+            );
+            Some(StateChangeWebhooks::new(
+                config,
+                db_pool.clone(),
+                api_service.credential_manager.clone(),
+                join_set,
+                &meter,
+                cancel_token.clone(),
+            )?)
+        }
+        _ => None,
+    };
+
     // Create state change emitter with DSX Exchange Event Bus hook if enabled
     let state_change_emitter = {
         let mut emitter_builder = StateChangeEmitterBuilder::default();
@@ -1001,6 +1053,14 @@ pub async fn initialize_and_start_controllers<'a>(
             )));
         }
 
+        if let Some(ref webhooks) = state_change_webhooks {
+            emitter_builder =
+                emitter_builder
+                    .hook(Box::new(webhooks.hook::<MachineStateControllerIO>(
+                        StateChangeWebhookObjectType::Machine,
+                    )));
+        }
+
         emitter_builder.build()
     };
 
@@ -1184,6 +1244,10 @@ pub async fn initialize_and_start_controllers<'a>(
         .services(handler_services.clone())
         .iteration_config((&carbide_config.power_shelf_state_controller.controller).into())
         .state_handler(Arc::new(PowerShelfStateHandler::default()))
+        .state_change_emitter(webhook_state_change_emitter::<PowerShelfStateControllerIO>(
+            state_change_webhooks.as_ref(),
+            StateChangeWebhookObjectType::PowerShelf,
+        ))
         .build_and_spawn(join_set, cancel_token.clone())
         .expect("Unable to build PowerShelfStateController");
 
@@ -1193,6 +1257,10 @@ pub async fn initialize_and_start_controllers<'a>(
         .processor_id(state_controller_id.clone())
         .services(handler_services.clone())
         .state_handler(Arc::new(RackStateHandler::default()))
+        .state_change_emitter(webhook_state_change_emitter::<RackStateControllerIO>(
+            state_change_webhooks.as_ref(),
+            StateChangeWebhookObjectType::Rack,
+        ))
         .build_and_spawn(join_set, cancel_token.clone())
         .expect("Unable to build RackStateController");
 
@@ -1203,6 +1271,10 @@ pub async fn initialize_and_start_controllers<'a>(
         .services(handler_services.clone())
         .iteration_config((&carbide_config.switch_state_controller.controller).into())
         .state_handler(Arc::new(SwitchStateHandler::default()))
+        .state_change_emitter(webhook_state_change_emitter::<SwitchStateControllerIO>(
+            state_change_webhooks.as_ref(),
+            StateChangeWebhookObjectType::Switch,
+        ))
         .build_and_spawn(join_set, cancel_token.clone())
         .expect("Unable to build SwitchStateController");
 
@@ -0,0 +1,179 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Side-effect free services for evaluating carbide-core's state handlers with
+//! [`super::controller::dry_run`].
+//!
+//! - Database access outside of the state controller's transaction is read-only
+//! - Redfish clients and IB fabrics can't be created, so handlers which need them
+//!   report an error instead of their outcome
+//! - IPMI commands and credential writes succeed without doing anything
+//! - The rack manager clients are not configured
+
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use carbide_ib_fabric::errors::IbError;
+use carbide_ib_fabric::ib::{IBFabric, IBFabricManager, IBFabricManagerConfig};
+use carbide_ipmi::IPMITool;
+use carbide_redfish::libredfish::{RedfishAuth, RedfishClientCreationError, RedfishClientPool};
+use carbide_uuid::machine::MachineId;
+use forge_secrets::SecretsError;
+use forge_secrets::credentials::{
+    CompositeCredentialManager, CredentialKey, CredentialManager, CredentialReader,
+    CredentialWriter, Credentials,
+};
+use libredfish::model::service_root::RedfishVendor;
+use libredfish::{Redfish, RedfishError};
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+
+use crate::state_controller::common_services::CommonStateHandlerServices;
+use crate::state_controller::state_handler::DryRunServices;
+
+impl DryRunServices for CommonStateHandlerServices {
+    fn for_dry_run(&self) -> Self {
+        let db_pool = read_only_pool(&self.db_pool);
+        let credential_manager: Arc<dyn CredentialManager> =
+            Arc::new(CompositeCredentialManager::new(
+                self.credential_manager.clone(),
+                DryRunCredentialWriter,
+            ));
+
+        Self {
+            db_reader: db_pool.clone().into(),
+            db_pool,
+            redfish_client_pool: Arc::new(DryRunRedfishClientPool {
+                credential_reader: self.credential_manager.clone(),
+            }),
+            ib_fabric_manager: Arc::new(DryRunIBFabricManager {
+                config: self.ib_fabric_manager.get_config(),
+            }),
+            ib_pools: self.ib_pools.clone(),
+            ipmi_tool: Arc::new(DryRunIPMITool),
+            site_config: self.site_config.clone(),
+            dpa_info: self.dpa_info.clone(),
+            rms_client: None,
+            switch_system_image_rms_client: None,
+            credential_manager,
+        }
+    }
+}
+
+/// A pool for the same database in which every transaction is read-only,
+/// so that handlers which write through their own transactions fail instead
+fn read_only_pool(pool: &PgPool) -> PgPool {
+    let options = pool
+        .connect_options()
+        .as_ref()
+        .clone()
+        .options([("default_transaction_read_only", "on")]);
+    PgPoolOptions::new()
+        .max_connections(pool.options().get_max_connections())
+        .connect_lazy_with(options)
+}
+
+struct DryRunRedfishClientPool {
+    credential_reader: Arc<dyn CredentialManager>,
+}
+
+#[async_trait]
+impl RedfishClientPool for DryRunRedfishClientPool {
+    async fn create_client(
+        &self,
+        host: &str,
+        _port: Option<u16>,
+        _auth: RedfishAuth,
+        _vendor: Option<RedfishVendor>,
+    ) -> Result<Box<dyn Redfish>, RedfishClientCreationError> {
+        Err(RedfishClientCreationError::RedfishError(
+            RedfishError::NotSupported(format!(
+                "Redfish calls to {host} are disabled in dry-run mode"
+            )),
+        ))
+    }
+
+    fn credential_reader(&self) -> &dyn CredentialReader {
+        &self.credential_reader
+    }
+}
+
+struct DryRunIBFabricManager {
+    config: IBFabricManagerConfig,
+}
+
+#[async_trait]
+impl IBFabricManager for DryRunIBFabricManager {
+    async fn new_client(&self, fabric_name: &str) -> Result<Arc<dyn IBFabric>, IbError> {
+        Err(IbError::IBFabricError(format!(
+            "IB fabric {fabric_name} is disabled in dry-run mode"
+        )))
+    }
+
+    fn get_config(&self) -> IBFabricManagerConfig {
+        self.config.clone()
+    }
+}
+
+struct DryRunIPMITool;
+
+#[async_trait]
+impl IPMITool for DryRunIPMITool {
+    async fn bmc_cold_reset(
+        &self,
+        _bmc_ip: IpAddr,
+        _credential_key: &CredentialKey,
+    ) -> Result<(), eyre::Report> {
+        Ok(())
+    }
+
+    async fn restart(
+        &self,
+        _machine_id: &MachineId,
+        _bmc_ip: IpAddr,
+        _legacy_boot: bool,
+        _credential_key: &CredentialKey,
+    ) -> Result<(), eyre::Report> {
+        Ok(())
+    }
+}
+
+struct DryRunCredentialWriter;
+
+#[async_trait]
+impl CredentialWriter for DryRunCredentialWriter {
+    async fn set_credentials(
+        &self,
+        _key: &CredentialKey,
+        _credentials: &Credentials,
+    ) -> Result<(), SecretsError> {
+        Ok(())
+    }
+
+    async fn create_credentials(
+        &self,
+        _key: &CredentialKey,
+        _credentials: &Credentials,
+    ) -> Result<(), SecretsError> {
+        Ok(())
+    }
+
+    async fn delete_credentials(&self, _key: &CredentialKey) -> Result<(), SecretsError> {
+        Ok(())
+    }
+}
@@ -20,6 +20,7 @@
 use carbide_uuid::machine::MachineId;
 use config_version::{ConfigVersion, Versioned};
 use db::{self, DatabaseError};
+use health_report::{HealthReport, HealthReportApplyMode};
 use model::StateSla;
 use model::controller_outcome::PersistentStateHandlerOutcome;
 use model::machine::machine_search_config::MachineSearchConfig;
@@ -153,6 +154,46 @@ impl StateControllerIO for MachineStateControllerIO {
         db::machine::update_controller_state_outcome(txn, object_id, outcome).await
     }
 
+    async fn persist_dwell_time_health_report(
+        &self,
+        txn: &mut PgConnection,
+        machine_id: &Self::ObjectId,
+        report: Option<&HealthReport>,
+    ) -> Result<(), DatabaseError> {
+        match report {
+            Some(report) => {
+                db::machine::insert_health_report(
+                    txn,
+                    machine_id,
+                    HealthReportApplyMode::Merge,
+                    report,
+                    false,
+                )
+                .await
+            }
+            None => {
+                db::machine::remove_health_report(
+                    txn,
+                    machine_id,
+                    HealthReportApplyMode::Merge,
+                    HealthReport::STATE_CONTROLLER_SOURCE,
+                )
+                .await
+            }
+        }
+    }
+
+    fn dwell_time_health_report<'a>(
+        &self,
+        object_state: &'a Self::State,
+    ) -> Option<&'a HealthReport> {
+        object_state
+            .host_snapshot
+            .health_reports
+            .merges
+            .get(HealthReport::STATE_CONTROLLER_SOURCE)
+    }
+
     fn metric_state_names(state: &ManagedHostState) -> (&'static str, &'static str) {
         use model::machine::{CleanupState, InstanceState, MachineState};
 
@@ -17,6 +17,7 @@
 
 pub mod common_services;
 pub mod dpa_interface;
+pub mod dry_run;
 pub(crate) mod external_service_error;
 pub mod health_metrics;
 pub mod ib_partition;
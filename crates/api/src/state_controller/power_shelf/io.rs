@@ -20,6 +20,7 @@
 use carbide_uuid::power_shelf::PowerShelfId;
 use config_version::{ConfigVersion, Versioned};
 use db::{DatabaseError, ObjectColumnFilter, power_shelf as db_power_shelf};
+use health_report::{HealthReport, HealthReportApplyMode};
 use model::controller_outcome::PersistentStateHandlerOutcome;
 use model::power_shelf::{
     PowerShelf, PowerShelfControllerState, PowerShelfSearchFilter, state_sla,
@@ -147,6 +148,44 @@ impl StateControllerIO for PowerShelfStateControllerIO {
         db_power_shelf::update_controller_state_outcome(txn, *object_id, outcome).await
     }
 
+    async fn persist_dwell_time_health_report(
+        &self,
+        txn: &mut PgConnection,
+        object_id: &Self::ObjectId,
+        report: Option<&HealthReport>,
+    ) -> Result<(), DatabaseError> {
+        match report {
+            Some(report) => {
+                db_power_shelf::insert_health_report(
+                    txn,
+                    object_id,
+                    HealthReportApplyMode::Merge,
+                    report,
+                )
+                .await
+            }
+            None => {
+                db_power_shelf::remove_health_report(
+                    txn,
+                    object_id,
+                    HealthReportApplyMode::Merge,
+                    HealthReport::STATE_CONTROLLER_SOURCE,
+                )
+                .await
+            }
+        }
+    }
+
+    fn dwell_time_health_report<'a>(
+        &self,
+        object_state: &'a Self::State,
+    ) -> Option<&'a HealthReport> {
+        object_state
+            .health_reports
+            .merges
+            .get(HealthReport::STATE_CONTROLLER_SOURCE)
+    }
+
     fn metric_state_names(state: &PowerShelfControllerState) -> (&'static str, &'static str) {
         match state {
             PowerShelfControllerState::Initializing => ("initializing", ""),
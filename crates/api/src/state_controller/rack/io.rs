@@ -21,6 +21,7 @@ use carbide_uuid::rack::RackId;
 use config_version::{ConfigVersion, Versioned};
 use db::rack::IdColumn;
 use db::{DatabaseError, ObjectColumnFilter, rack as db_rack};
+use health_report::{HealthReport, HealthReportApplyMode};
 use model::StateSla;
 use model::controller_outcome::PersistentStateHandlerOutcome;
 use model::rack::{
@@ -125,6 +126,39 @@ impl StateControllerIO for RackStateControllerIO {
         db_rack::update_controller_state_outcome(txn, rack_id, outcome).await
     }
 
+    async fn persist_dwell_time_health_report(
+        &self,
+        txn: &mut PgConnection,
+        rack_id: &Self::ObjectId,
+        report: Option<&HealthReport>,
+    ) -> Result<(), DatabaseError> {
+        match report {
+            Some(report) => {
+                db_rack::insert_health_report(txn, rack_id, HealthReportApplyMode::Merge, report)
+                    .await
+            }
+            None => {
+                db_rack::remove_health_report(
+                    txn,
+                    rack_id,
+                    HealthReportApplyMode::Merge,
+                    HealthReport::STATE_CONTROLLER_SOURCE,
+                )
+                .await
+            }
+        }
+    }
+
+    fn dwell_time_health_report<'a>(
+        &self,
+        object_state: &'a Self::State,
+    ) -> Option<&'a HealthReport> {
+        object_state
+            .health_reports
+            .merges
+            .get(HealthReport::STATE_CONTROLLER_SOURCE)
+    }
+
     fn metric_state_names(state: &RackState) -> (&'static str, &'static str) {
         match state {
             RackState::Created => ("created", ""),
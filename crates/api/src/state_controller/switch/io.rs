@@ -20,6 +20,7 @@
 use carbide_uuid::switch::SwitchId;
 use config_version::{ConfigVersion, Versioned};
 use db::{DatabaseError, ObjectColumnFilter, switch as db_switch};
+use health_report::{HealthReport, HealthReportApplyMode};
 use model::StateSla;
 use model::controller_outcome::PersistentStateHandlerOutcome;
 use model::switch::{Switch, SwitchControllerState, SwitchSearchFilter, state_sla};
@@ -140,6 +141,44 @@ impl StateControllerIO for SwitchStateControllerIO {
         db_switch::update_controller_state_outcome(txn, *object_id, outcome).await
     }
 
+    async fn persist_dwell_time_health_report(
+        &self,
+        txn: &mut PgConnection,
+        object_id: &Self::ObjectId,
+        report: Option<&HealthReport>,
+    ) -> Result<(), DatabaseError> {
+        match report {
+            Some(report) => {
+                db_switch::insert_health_report(
+                    txn,
+                    object_id,
+                    HealthReportApplyMode::Merge,
+                    report,
+                )
+                .await
+            }
+            None => {
+                db_switch::remove_health_report(
+                    txn,
+                    object_id,
+                    HealthReportApplyMode::Merge,
+                    HealthReport::STATE_CONTROLLER_SOURCE,
+                )
+                .await
+            }
+        }
+    }
+
+    fn dwell_time_health_report<'a>(
+        &self,
+        object_state: &'a Self::State,
+    ) -> Option<&'a HealthReport> {
+        object_state
+            .health_reports
+            .merges
+            .get(HealthReport::STATE_CONTROLLER_SOURCE)
+    }
+
     fn metric_state_names(state: &SwitchControllerState) -> (&'static str, &'static str) {
         match state {
             SwitchControllerState::Created => ("created", ""),
@@ -39,6 +39,11 @@ pub struct MockEndpointExplorer {
     /// mode) so tests can assert the auto-correct path fired with the
     /// right arguments. Cleared on each `insert_endpoints` reset.
     pub set_nic_mode_calls: Arc<Mutex<Vec<(SocketAddr, NicMode)>>>,
+    /// Addresses which answer `probe_service_root`, with the vendor they report.
+    /// All other addresses are treated as unreachable.
+    pub service_roots: Arc<Mutex<HashMap<IpAddr, Option<String>>>>,
+    /// Records every address passed to `probe_service_root`.
+    pub probe_service_root_calls: Arc<Mutex<Vec<SocketAddr>>>,
 }
 
 impl MockEndpointExplorer {
@@ -81,6 +86,19 @@ impl EndpointExplorer for MockEndpointExplorer {
     ) -> Result<(), EndpointExplorationError> {
         Ok(())
     }
+    async fn probe_service_root(
+        &self,
+        address: SocketAddr,
+    ) -> Result<Option<String>, EndpointExplorationError> {
+        self.probe_service_root_calls.lock().unwrap().push(address);
+        self.service_roots
+            .lock()
+            .unwrap()
+            .get(&address.ip())
+            .cloned()
+            .ok_or(EndpointExplorationError::Unreachable { details: None })
+    }
+
     async fn explore_endpoint(
         &self,
         bmc_ip_address: SocketAddr,
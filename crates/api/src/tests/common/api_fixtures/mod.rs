@@ -1298,6 +1298,8 @@ pub fn get_config() -> CarbideConfig {
             ..Default::default()
         },
         dsx_exchange_event_bus: None,
+        state_change_webhooks: None,
+        console_token: None,
         force_dpu_nic_mode: Arc::new(false.into()),
         dpf: crate::cfg::file::DpfConfig::default(),
         x86_pxe_boot_url_override: None,
@@ -1765,6 +1767,8 @@ pub async fn create_test_env_with_overrides(
         power_states: Arc::new(std::sync::Mutex::new(Default::default())),
         redfish_power_control_calls: Arc::new(std::sync::Mutex::new(Default::default())),
         set_nic_mode_calls: Arc::new(std::sync::Mutex::new(Default::default())),
+        service_roots: Arc::new(std::sync::Mutex::new(Default::default())),
+        probe_service_root_calls: Arc::new(std::sync::Mutex::new(Default::default())),
     };
 
     // The API server is launched with a disabled site-explorer config so that it doesn't launch one
@@ -1798,6 +1802,7 @@ pub async fn create_test_env_with_overrides(
             force_dpu_nic_mode: Arc::new(false.into()),
             // Tests use MockEndpointExplorer. So this doesn't affect anything.
             explore_mode: SiteExplorerExploreMode::NvRedfish,
+            subnet_sweep: Default::default(),
         },
         test_meter.meter(),
         Arc::new(fake_endpoint_explorer.clone()),
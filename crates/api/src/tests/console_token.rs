@@ -0,0 +1,174 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::io::Write;
+
+use ::rpc::forge as rpc;
+use carbide_authn::middleware::{ExternalUserInfo, Principal};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use rpc::forge_server::Forge;
+use serde_json::Value;
+use tonic::Code;
+
+use crate::auth::AuthContext;
+use crate::cfg::file::ConsoleTokenConfig;
+use crate::tests::common::api_fixtures::{
+    TestEnvOverrides, create_managed_host, create_test_env, create_test_env_with_overrides,
+    get_config,
+};
+
+fn request_as_admin<T>(request: T) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(request);
+
+    let mut auth_context = AuthContext::default();
+    auth_context
+        .principals
+        .push(Principal::ExternalUser(ExternalUserInfo {
+            org: Some("test_org".to_string()),
+            group: "test_group".to_string(),
+            user: Some("admin".to_string()),
+        }));
+
+    request.extensions_mut().insert(auth_context);
+
+    request
+}
+
+#[crate::sqlx_test]
+async fn test_create_console_token(pool: sqlx::PgPool) -> Result<(), eyre::Report> {
+    let key_pair = rcgen::KeyPair::generate()?;
+    let mut signing_key = tempfile::NamedTempFile::new()?;
+    signing_key.write_all(key_pair.serialize_pem().as_bytes())?;
+
+    let env = {
+        let mut config = get_config();
+        config.console_token = Some(ConsoleTokenConfig {
+            signing_key_path: signing_key.path().to_path_buf(),
+            audience: ConsoleTokenConfig::default_audience(),
+            lifetime: ConsoleTokenConfig::default_lifetime(),
+            admin_role: "console-admin".to_string(),
+        });
+        create_test_env_with_overrides(pool, TestEnvOverrides::with_config(config)).await
+    };
+    let segment_id = env.create_vpc_and_tenant_segment().await;
+    let mh = create_managed_host(&env).await;
+    let instance = mh
+        .instance_builer(&env)
+        .single_interface_network_config(segment_id)
+        .tenant_org("Tenant1")
+        .build()
+        .await;
+    let instance_id = instance.id.to_string();
+
+    let decoding_key = DecodingKey::from_ec_pem(key_pair.public_key_pem().as_bytes())?;
+    let mut validation = Validation::new(Algorithm::ES256);
+    validation.set_audience(&["ssh-console"]);
+    let claims = |token: &str| {
+        jsonwebtoken::decode::<Value>(token, &decoding_key, &validation)
+            .unwrap()
+            .claims
+    };
+
+    // The owning tenant gets a token for its instance, without the admin role.
+    let token = env
+        .api
+        .create_console_token(tonic::Request::new(rpc::CreateConsoleTokenRequest {
+            console: instance_id.clone(),
+            user: "tenant-user".to_string(),
+            tenant_organization_id: Some("Tenant1".to_string()),
+        }))
+        .await?
+        .into_inner();
+    assert!(token.expires_at.is_some());
+    let tenant_claims = claims(&token.token);
+    assert_eq!(tenant_claims["sub"], "tenant-user");
+    assert_eq!(tenant_claims["console"], instance_id.as_str());
+    assert_eq!(tenant_claims["tenant_organization_id"], "Tenant1");
+    assert_eq!(tenant_claims["roles"], serde_json::json!([]));
+
+    // Other tenants don't.
+    let err = env
+        .api
+        .create_console_token(tonic::Request::new(rpc::CreateConsoleTokenRequest {
+            console: instance_id.clone(),
+            user: "tenant-user".to_string(),
+            tenant_organization_id: Some("Tenant2".to_string()),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+
+    // Tenants can't access machine consoles.
+    let err = env
+        .api
+        .create_console_token(tonic::Request::new(rpc::CreateConsoleTokenRequest {
+            console: mh.id.to_string(),
+            user: "tenant-user".to_string(),
+            tenant_organization_id: Some("Tenant1".to_string()),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+
+    // Without an admin principal, the organization is required.
+    let err = env
+        .api
+        .create_console_token(tonic::Request::new(rpc::CreateConsoleTokenRequest {
+            console: mh.id.to_string(),
+            user: "someone".to_string(),
+            tenant_organization_id: None,
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    // Admins get the admin role, issued to the user in their certificate.
+    let token = env
+        .api
+        .create_console_token(request_as_admin(rpc::CreateConsoleTokenRequest {
+            console: mh.id.to_string(),
+            user: "someone-else".to_string(),
+            tenant_organization_id: None,
+        }))
+        .await?
+        .into_inner();
+    let admin_claims = claims(&token.token);
+    assert_eq!(admin_claims["sub"], "admin");
+    assert_eq!(admin_claims["console"], mh.id.to_string().as_str());
+    assert_eq!(admin_claims["roles"], serde_json::json!(["console-admin"]));
+    assert!(admin_claims.get("tenant_organization_id").is_none());
+
+    Ok(())
+}
+
+#[crate::sqlx_test]
+async fn test_create_console_token_unconfigured(pool: sqlx::PgPool) -> Result<(), eyre::Report> {
+    let env = create_test_env(pool).await;
+    let mh = create_managed_host(&env).await;
+
+    let err = env
+        .api
+        .create_console_token(request_as_admin(rpc::CreateConsoleTokenRequest {
+            console: mh.id.to_string(),
+            user: String::new(),
+            tenant_organization_id: None,
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unavailable);
+
+    Ok(())
+}
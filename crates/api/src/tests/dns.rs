@@ -191,6 +191,36 @@ async fn test_dns(pool: sqlx::PgPool) {
     //      - 2x bmc machine ID names
     assert_eq!(10, get_dns_record_count(&env.pool).await);
 
+    // Zone transfers fetch the whole domain at once.
+    let records = api
+        .get_all_records_for_domain(tonic::Request::new(
+            rpc::protos::dns::GetAllRecordsForDomainRequest {
+                name: format!("{DOMAIN_NAME}."),
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner()
+        .result;
+    assert!(
+        records
+            .iter()
+            .any(|r| r.qtype == "A" && r.content == ip2.split('/').collect::<Vec<&str>>()[0])
+    );
+    assert!(records.iter().all(|r| r.domain_id.is_some()));
+
+    let records = api
+        .get_all_records_for_domain(tonic::Request::new(
+            rpc::protos::dns::GetAllRecordsForDomainRequest {
+                name: "unknown.com".to_string(),
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner()
+        .result;
+    assert!(records.is_empty());
+
     let status = api
         .lookup_record(tonic::Request::new(
             rpc::protos::dns::DnsResourceRecordLookupRequest {
@@ -424,6 +454,212 @@ async fn test_dns_aaaa_legacy(pool: sqlx::PgPool) {
     );
 }
 
+// test_dns_ptr verifies that reverse zones are synthesized for network segment
+// prefixes, and that PTR records point back at the forward names of interface
+// addresses, through both the lookup_record and the legacy RPC.
+#[crate::sqlx_test]
+async fn test_dns_ptr(pool: sqlx::PgPool) {
+    let env = create_test_env(pool).await;
+    env.create_vpc_and_tenant_segment().await;
+    let api = &env.api;
+
+    let interface = api
+        .discover_dhcp(DhcpDiscovery::builder("FF:FF:FF:FF:FF:FF", "192.0.2.1").tonic_request())
+        .await
+        .unwrap()
+        .into_inner();
+    let fqdn = format!("{}.", interface.fqdn);
+    let ip: IpAddr = interface
+        .address
+        .split('/')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let ptr_name = format!("{}.", dns_record::reverse::reverse_name(ip));
+
+    let lookup = |qname: &str, qtype: &str| {
+        api.lookup_record(tonic::Request::new(
+            rpc::protos::dns::DnsResourceRecordLookupRequest {
+                qname: qname.to_string(),
+                zone_id: "-1".to_string(),
+                local: None,
+                remote: None,
+                qtype: qtype.to_string(),
+                real_remote: None,
+            },
+        ))
+    };
+
+    let records = lookup(&ptr_name, "PTR").await.unwrap().into_inner().records;
+    assert!(
+        records
+            .iter()
+            .any(|r| r.qtype == "PTR" && r.qname == ptr_name && r.content == fqdn),
+        "PTR records should point at {fqdn}: {records:?}"
+    );
+
+    // The admin segment is 192.0.2.0/24, which is served as one reverse zone.
+    let records = lookup("2.0.192.in-addr.arpa.", "SOA")
+        .await
+        .unwrap()
+        .into_inner()
+        .records;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].qtype, "SOA");
+    assert_eq!(records[0].qname, "2.0.192.in-addr.arpa");
+
+    let records = lookup("2.0.192.in-addr.arpa.", "ANY")
+        .await
+        .unwrap()
+        .into_inner()
+        .records;
+    assert!(records.iter().any(|r| r.qtype == "SOA"));
+
+    // No segment covers these, so there is no zone to be authoritative for.
+    for zone in ["0.192.in-addr.arpa.", "3.0.10.in-addr.arpa."] {
+        let status = lookup(zone, "SOA")
+            .await
+            .expect_err("SOA lookup outside of segment prefixes should fail");
+        assert_eq!(status.code(), tonic::Code::NotFound, "{zone}");
+    }
+
+    let legacy_response = api
+        .lookup_record_legacy(tonic::Request::new(rpc::forge::dns_message::DnsQuestion {
+            q_name: Some(ptr_name.clone()),
+            q_class: Some(1),
+            q_type: Some(12), // PTR
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(
+        legacy_response
+            .rrs
+            .iter()
+            .any(|rr| rr.rdata.as_deref() == Some(fqdn.as_str())),
+        "legacy PTR query should point at {fqdn}"
+    );
+}
+
+// test_dns_snapshot verifies that the snapshot carbide-dns answers queries from
+// holds domains, synthesized reverse zones and their records, that it is paged,
+// and that it is only sent again once its version is outdated.
+#[crate::sqlx_test]
+async fn test_dns_snapshot(pool: sqlx::PgPool) {
+    let env = create_test_env(pool).await;
+    env.create_vpc_and_tenant_segment().await;
+    let api = &env.api;
+
+    let interface = api
+        .discover_dhcp(DhcpDiscovery::builder("FF:FF:FF:FF:FF:FF", "192.0.2.1").tonic_request())
+        .await
+        .unwrap()
+        .into_inner();
+    let fqdn = format!("{}.", interface.fqdn);
+    let ip: IpAddr = interface
+        .address
+        .split('/')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let get_snapshot = |version: Option<String>, page_token: Option<String>, page_size| {
+        api.get_dns_snapshot(tonic::Request::new(rpc::protos::dns::DnsSnapshotRequest {
+            version,
+            page_token,
+            page_size,
+        }))
+    };
+
+    let snapshot = get_snapshot(None, None, None).await.unwrap().into_inner();
+    assert!(!snapshot.unchanged);
+    assert!(!snapshot.version.is_empty());
+    assert!(snapshot.next_page_token.is_none());
+
+    let domain = snapshot
+        .zones
+        .iter()
+        .find(|z| z.name == DOMAIN_NAME)
+        .expect("snapshot should hold the domain");
+    assert!(domain.domain.is_some());
+    assert_eq!(domain.soa.as_ref().unwrap().qtype, "SOA");
+
+    let reverse_zone = snapshot
+        .zones
+        .iter()
+        .find(|z| z.name == "2.0.192.in-addr.arpa")
+        .expect("snapshot should hold the reverse zone of the admin segment");
+    assert!(reverse_zone.domain.is_none());
+    assert!(reverse_zone.soa.is_some());
+
+    assert!(
+        snapshot
+            .records
+            .iter()
+            .any(|r| r.qname == fqdn && r.qtype == "A" && r.content == ip.to_string())
+    );
+    let ptr_name = format!("{}.", dns_record::reverse::reverse_name(ip));
+    assert!(
+        snapshot
+            .records
+            .iter()
+            .any(|r| r.qname == ptr_name && r.qtype == "PTR" && r.content == fqdn)
+    );
+
+    // Paged one database record (and its PTR record) at a time, the pages add up to the
+    // whole snapshot. Zones only come with the first page.
+    let mut page = get_snapshot(None, None, Some(1))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(page.version, snapshot.version);
+    assert_eq!(page.zones, snapshot.zones);
+    let mut records = page.records.clone();
+    while let Some(page_token) = page.next_page_token.take() {
+        page = get_snapshot(None, Some(page_token), Some(1))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(page.version, snapshot.version);
+        assert!(page.zones.is_empty());
+        assert!(!page.records.is_empty());
+        records.extend(page.records.clone());
+    }
+    assert_eq!(records, snapshot.records);
+
+    let unchanged = get_snapshot(Some(snapshot.version.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(unchanged.unchanged);
+    assert_eq!(unchanged.version, snapshot.version);
+    assert!(unchanged.zones.is_empty() && unchanged.records.is_empty());
+
+    let first_page = get_snapshot(None, None, Some(1))
+        .await
+        .unwrap()
+        .into_inner();
+    api.discover_dhcp(DhcpDiscovery::builder("F1:FF:FF:FF:FF:FF", "192.0.2.1").tonic_request())
+        .await
+        .unwrap();
+
+    // The snapshot changed while it was being paged through.
+    let err = get_snapshot(None, first_page.next_page_token, Some(1))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+
+    let changed = get_snapshot(Some(snapshot.version.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!changed.unchanged);
+    assert_ne!(changed.version, snapshot.version);
+    assert!(changed.records.len() > snapshot.records.len());
+}
+
 // Get the current number of rows in the dns_records view,
 // which is expected to start at 0, and then progress, as
 // the test continues.
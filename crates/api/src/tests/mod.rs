@@ -19,6 +19,7 @@ mod client_resolution;
 pub(crate) mod common;
 mod compute_allocation;
 mod connected_device;
+mod console_token;
 mod create_domain;
 mod credential;
 mod dhcp_lease_expiration;
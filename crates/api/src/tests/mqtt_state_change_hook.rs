@@ -44,6 +44,7 @@ fn test_message_json_structure() {
     let message = ManagedHostStateChangeMessage {
         machine_id: &machine_id,
         managed_host_state: &state,
+        previous_managed_host_state: None,
         timestamp,
     };
     let json = message
@@ -75,6 +76,7 @@ fn test_complex_state_has_nested_fields() {
     let message = ManagedHostStateChangeMessage {
         machine_id: &machine_id,
         managed_host_state: &state,
+        previous_managed_host_state: None,
         timestamp,
     };
     let json = message.to_json_bytes().unwrap();
@@ -95,6 +97,7 @@ fn test_timestamp_format() {
     let message = ManagedHostStateChangeMessage {
         machine_id: &machine_id,
         managed_host_state: &state,
+        previous_managed_host_state: None,
         timestamp,
     };
     let json = message.to_json_bytes().unwrap();
@@ -21,7 +21,9 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use carbide_site_explorer::SiteExplorer;
-use carbide_site_explorer::config::{SiteExplorerConfig, SiteExplorerExploreMode};
+use carbide_site_explorer::config::{
+    SiteExplorerConfig, SiteExplorerExploreMode, SubnetSweepConfig,
+};
 use carbide_utils::test_support::test_meter::TestMeter;
 use carbide_uuid::network::NetworkSegmentId;
 use common::api_fixtures::TestEnv;
@@ -29,7 +31,7 @@ use common::api_fixtures::endpoint_explorer::MockEndpointExplorer;
 use config_version::ConfigVersion;
 use db::sku::CURRENT_SKU_VERSION;
 use db::{self, ObjectColumnFilter, ObjectFilter, explored_endpoints as db_explored_endpoints};
-use ipnetwork::IpNetwork;
+use ipnetwork::{IpNetwork, Ipv4Network};
 use itertools::Itertools;
 use mac_address::MacAddress;
 use model::expected_machine::{ExpectedMachine, ExpectedMachineData};
@@ -39,8 +41,9 @@ use model::machine::{LoadSnapshotOptions, Machine, ManagedHostStateSnapshot};
 use model::metadata::Metadata;
 use model::power_shelf::PowerShelfControllerState;
 use model::site_explorer::{
-    Chassis, ComputerSystem, EndpointExplorationError, EndpointExplorationReport, EndpointType,
-    ExploredDpu, ExploredEndpoint, ExploredManagedHost, PreingestionState, UefiDevicePath,
+    BootOption, BootOrder, Chassis, ComputerSystem, EndpointExplorationError,
+    EndpointExplorationReport, EndpointType, ExploredDpu, ExploredEndpoint, ExploredManagedHost,
+    PreingestionState, UefiDevicePath,
 };
 use model::switch::SwitchSearchFilter;
 use rpc::forge::GetSiteExplorationRequest;
@@ -1037,6 +1040,7 @@ async fn test_site_explorer_audit_exploration_results(
         force_dpu_nic_mode: Arc::new(false.into()),
         // Tests use MockEndpointExplorer. So this doesn't affect anything.
         explore_mode: SiteExplorerExploreMode::NvRedfish,
+        subnet_sweep: Default::default(),
     };
     let test_meter = TestMeter::default();
     let explorer = SiteExplorer::new(
@@ -1772,6 +1776,20 @@ async fn test_site_explorer_health_report(
     // Run site explorer and check the health state of the Machine
     explorer.run_single_iteration().await.unwrap();
 
+    // The mocked reports use different MACs and DPU serials than the ones the
+    // host was ingested with, which is detected as hardware drift
+    for ip in [
+        bmc_ip.to_string(),
+        dpu_machine.bmc_info.as_ref().unwrap().ip().to_string(),
+    ] {
+        env.api
+            .acknowledge_explored_endpoint_drift(tonic::Request::new(
+                rpc::forge::AcknowledgeExploredEndpointDriftRequest { ip_address: ip },
+            ))
+            .await?;
+    }
+    explorer.run_single_iteration().await.unwrap();
+
     let host_machine = env.find_machine(host_machine_id).await.remove(0);
 
     let alerts = &host_machine.health.as_ref().unwrap().alerts;
@@ -1817,6 +1835,203 @@ async fn test_site_explorer_health_report(
     Ok(())
 }
 
+#[crate::sqlx_test]
+async fn test_site_explorer_hardware_drift(
+    pool: sqlx::PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let env = common::api_fixtures::create_test_env(pool.clone()).await;
+    let (host_machine_id, dpu_machine_id) =
+        common::api_fixtures::create_managed_host(&env).await.into();
+    let segment_id = env.create_vpc_and_tenant_segment().await;
+    let host_machine = env.find_machine(host_machine_id).await.remove(0);
+    let dpu_machine = env.find_machine(dpu_machine_id).await.remove(0);
+    let bmc_ip: IpAddr = host_machine
+        .bmc_info
+        .as_ref()
+        .unwrap()
+        .ip()
+        .parse()
+        .unwrap();
+    let dpu_bmc_ip: IpAddr = dpu_machine.bmc_info.as_ref().unwrap().ip().parse().unwrap();
+    let chassis_serial = host_machine
+        .discovery_info
+        .as_ref()
+        .unwrap()
+        .dmi_data
+        .as_ref()
+        .unwrap()
+        .chassis_serial
+        .clone();
+    let dpu_serial = dpu_machine
+        .discovery_info
+        .as_ref()
+        .unwrap()
+        .dmi_data
+        .as_ref()
+        .unwrap()
+        .product_serial
+        .clone();
+
+    let host_report: EndpointExplorationReport =
+        ManagedHostConfig::with_serial(chassis_serial).into();
+    let endpoint_explorer = Arc::new(MockEndpointExplorer::default());
+    endpoint_explorer.insert_endpoint_results(vec![
+        (bmc_ip, Ok(host_report.clone())),
+        (dpu_bmc_ip, Ok(DpuConfig::with_serial(dpu_serial).into())),
+    ]);
+
+    // Allow site explorer to run against the ingested BMC IPs,
+    // see test_site_explorer_health_report
+    let mut txn = env.pool.begin().await?;
+    let query = format!(
+        "UPDATE network_segments SET network_segment_type='underlay' WHERE id='{segment_id}'",
+    );
+    sqlx::query::<_>(&query).execute(&mut *txn).await.unwrap();
+    txn.commit().await.unwrap();
+
+    let explorer_config = SiteExplorerConfig {
+        enabled: Arc::new(true.into()),
+        explorations_per_run: 10,
+        concurrent_explorations: 1,
+        run_interval: std::time::Duration::from_secs(1),
+        create_machines: Arc::new(true.into()),
+        ..Default::default()
+    };
+    let explorer = SiteExplorer::new(
+        env.pool.clone(),
+        explorer_config,
+        env.test_meter.meter(),
+        endpoint_explorer.clone(),
+        Arc::new(env.config.get_firmware_config()),
+        env.common_pools.clone(),
+        env.api.work_lock_manager_handle.clone(),
+        env.rms_sim.as_rms_client(),
+        env.test_credential_manager.clone(),
+    );
+
+    let find_history = |address: IpAddr| {
+        let api = env.api.clone();
+        async move {
+            api.find_explored_endpoint_report_history(Request::new(
+                rpc::site_explorer::ExploredEndpointReportHistoryRequest {
+                    address: address.to_string(),
+                },
+            ))
+            .await
+            .unwrap()
+            .into_inner()
+            .records
+        }
+    };
+    let has_drift_alert = |machine: &rpc::forge::Machine| {
+        machine
+            .health
+            .as_ref()
+            .unwrap()
+            .alerts
+            .iter()
+            .any(|alert| alert.id == "HardwareDrift")
+    };
+
+    // The mocked report uses different NICs than the host was ingested with.
+    // Since the host is Ready, this is unexpected.
+    explorer.run_single_iteration().await.unwrap();
+    let history = find_history(bmc_ip).await;
+    assert_eq!(history.len(), 1);
+    assert!(history[0].unexpected);
+    assert!(!history[0].acknowledged);
+    assert_eq!(history[0].machine_state.as_deref(), Some("Ready"));
+    assert!(history[0].changes.iter().any(|c| c.category == "Nic"));
+    let host_machine = env.find_machine(host_machine_id).await.remove(0);
+    assert!(has_drift_alert(&host_machine));
+
+    // Acknowledging the drift clears the alert
+    for address in [bmc_ip, dpu_bmc_ip] {
+        env.api
+            .acknowledge_explored_endpoint_drift(Request::new(
+                rpc::forge::AcknowledgeExploredEndpointDriftRequest {
+                    ip_address: address.to_string(),
+                },
+            ))
+            .await?;
+    }
+    explorer.run_single_iteration().await.unwrap();
+    let history = find_history(bmc_ip).await;
+    assert_eq!(history.len(), 1, "unchanged reports are not stored");
+    assert!(history[0].acknowledged);
+    let host_machine = env.find_machine(host_machine_id).await.remove(0);
+    assert!(!has_drift_alert(&host_machine));
+
+    // A changed boot order alone raises the alert, but doesn't block allocations
+    let mut reordered_report = host_report.clone();
+    reordered_report.systems[0].boot_order = Some(BootOrder {
+        boot_order: vec![BootOption {
+            display_name: "Disk".to_string(),
+            id: "Boot0001".to_string(),
+            boot_option_enabled: Some(true),
+            uefi_device_path: None,
+        }],
+    });
+    endpoint_explorer.insert_endpoint_result(bmc_ip, Ok(reordered_report.clone()));
+    explorer.run_single_iteration().await.unwrap();
+    let history = find_history(bmc_ip).await;
+    assert_eq!(history.len(), 2);
+    assert!(history[0].unexpected);
+    let host_machine = env.find_machine(host_machine_id).await.remove(0);
+    let drift_alert = host_machine
+        .health
+        .as_ref()
+        .unwrap()
+        .alerts
+        .iter()
+        .find(|alert| alert.id == "HardwareDrift")
+        .unwrap();
+    assert!(drift_alert.classifications.is_empty());
+    env.api
+        .acknowledge_explored_endpoint_drift(Request::new(
+            rpc::forge::AcknowledgeExploredEndpointDriftRequest {
+                ip_address: bmc_ip.to_string(),
+            },
+        ))
+        .await?;
+
+    // Losing a PCIe device raises the alert again
+    let mut degraded_report = reordered_report.clone();
+    let lost_device = degraded_report.systems[0].pcie_devices.remove(0);
+    endpoint_explorer.insert_endpoint_result(bmc_ip, Ok(degraded_report));
+    explorer.run_single_iteration().await.unwrap();
+
+    let history = find_history(bmc_ip).await;
+    assert_eq!(history.len(), 3);
+    assert!(history[0].unexpected && !history[0].acknowledged);
+    let removed = history[0]
+        .changes
+        .iter()
+        .find(|c| c.category == "PcieDevice")
+        .unwrap();
+    assert!(
+        removed
+            .before
+            .as_ref()
+            .unwrap()
+            .contains(lost_device.serial_number.as_ref().unwrap())
+    );
+    assert_eq!(removed.after, None);
+    let host_machine = env.find_machine(host_machine_id).await.remove(0);
+    assert!(has_drift_alert(&host_machine));
+    let drift_alert = host_machine
+        .health
+        .as_ref()
+        .unwrap()
+        .alerts
+        .iter()
+        .find(|alert| alert.id == "HardwareDrift")
+        .unwrap();
+    assert_eq!(drift_alert.classifications, vec!["PreventAllocations"]);
+
+    Ok(())
+}
+
 async fn fetch_exploration_report(env: &TestEnv) -> rpc::site_explorer::SiteExplorationReport {
     env.api
         .get_site_exploration_report(tonic::Request::new(GetSiteExplorationRequest::default()))
@@ -5438,3 +5653,139 @@ async fn power_shelf_skips_creation_when_bmc_mac_already_used(
 
     Ok(())
 }
+
+#[crate::sqlx_test]
+async fn test_site_explorer_subnet_sweep(
+    pool: sqlx::PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let env = common::api_fixtures::create_test_env(pool.clone()).await;
+
+    let mut machines = vec![FakeMachine::new(
+        "B8:3F:D2:90:97:A6",
+        "Vendor1",
+        env.underlay_segment.unwrap(),
+    )];
+    machines.discover_dhcp(&env).await?;
+    let known_ip: IpAddr = machines[0].ip.parse().unwrap();
+
+    // Sweep the /29 around the BMC which DHCPed through carbide
+    let IpAddr::V4(known_v4) = known_ip else {
+        panic!("underlay addresses are IPv4");
+    };
+    let range = Ipv4Network::new(Ipv4Network::new(known_v4, 29)?.network(), 29)?;
+    let static_ips: Vec<IpAddr> = range
+        .iter()
+        .filter(|ip| *ip != range.network() && *ip != range.broadcast() && *ip != known_v4)
+        .map(IpAddr::V4)
+        .collect();
+    let static_bmc_ip = static_ips[0];
+    let excluded_bmc_ip = static_ips[1];
+
+    let endpoint_explorer = Arc::new(MockEndpointExplorer::default());
+    endpoint_explorer.insert_endpoint_results(vec![(
+        known_ip,
+        Err(EndpointExplorationError::Unreachable { details: None }),
+    )]);
+    {
+        let mut service_roots = endpoint_explorer.service_roots.lock().unwrap();
+        service_roots.insert(known_ip, Some("Dell".to_string()));
+        service_roots.insert(static_bmc_ip, Some("Dell".to_string()));
+        service_roots.insert(excluded_bmc_ip, Some("Lenovo".to_string()));
+    }
+
+    let explorer_config = SiteExplorerConfig {
+        enabled: Arc::new(true.into()),
+        explorations_per_run: 1,
+        concurrent_explorations: 1,
+        run_interval: std::time::Duration::from_secs(1),
+        create_machines: Arc::new(false.into()),
+        subnet_sweep: SubnetSweepConfig {
+            enabled: true,
+            ranges: vec![range.into()],
+            exclude: vec![IpNetwork::new(excluded_bmc_ip, 32)?],
+            probe_interval: std::time::Duration::ZERO,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let test_meter = TestMeter::default();
+    let explorer = SiteExplorer::new(
+        env.pool.clone(),
+        explorer_config,
+        test_meter.meter(),
+        endpoint_explorer.clone(),
+        Arc::new(env.config.get_firmware_config()),
+        env.common_pools.clone(),
+        env.api.work_lock_manager_handle.clone(),
+        env.rms_sim.as_rms_client(),
+        env.test_credential_manager.clone(),
+    );
+    explorer.run_single_subnet_sweep().await.unwrap();
+    // Exploration runs report the results of the latest sweep
+    explorer.run_single_iteration().await.unwrap();
+
+    // Neither the known nor the excluded address may be probed
+    let probed: Vec<IpAddr> = endpoint_explorer
+        .probe_service_root_calls
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|addr| addr.ip())
+        .collect();
+    assert_eq!(probed.len(), static_ips.len() - 1);
+    assert!(probed.contains(&static_bmc_ip));
+    assert!(!probed.contains(&known_ip));
+    assert!(!probed.contains(&excluded_bmc_ip));
+
+    let swept = env
+        .api
+        .find_swept_bmc_endpoints(Request::new(
+            rpc::site_explorer::SweptBmcEndpointSearchFilter {},
+        ))
+        .await?
+        .into_inner()
+        .endpoints;
+    assert_eq!(swept.len(), 1);
+    assert_eq!(swept[0].address, static_bmc_ip.to_string());
+    assert_eq!(swept[0].port, 443);
+    assert_eq!(swept[0].vendor.as_deref(), Some("Dell"));
+
+    assert_eq!(
+        test_meter
+            .formatted_metric("carbide_site_explorer_swept_bmc_endpoints_unclaimed_count")
+            .unwrap(),
+        "1"
+    );
+    assert_eq!(
+        test_meter
+            .formatted_metric("carbide_site_explorer_subnet_sweep_responses_count")
+            .unwrap(),
+        "1"
+    );
+
+    // Dismissing the endpoint removes it until it is swept again
+    env.api
+        .delete_swept_bmc_endpoint(Request::new(rpc::forge::DeleteSweptBmcEndpointRequest {
+            ip_address: static_bmc_ip.to_string(),
+        }))
+        .await?;
+    let err = env
+        .api
+        .delete_swept_bmc_endpoint(Request::new(rpc::forge::DeleteSweptBmcEndpointRequest {
+            ip_address: static_bmc_ip.to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::NotFound);
+
+    let mut txn = env.pool.begin().await?;
+    assert!(
+        db::swept_bmc_endpoints::find_all(txn.as_mut())
+            .await?
+            .is_empty()
+    );
+    txn.commit().await?;
+
+    Ok(())
+}
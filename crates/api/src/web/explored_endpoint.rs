@@ -27,8 +27,8 @@ use hyper::http::StatusCode;
 use rpc::forge::forge_server::Forge;
 use rpc::forge::{self as forgerpc, BmcEndpointRequest, admin_power_control_request};
 use rpc::site_explorer::{
-    ExploredEndpoint, InternalLockdownStatus, LockdownStatus, MachineSetupStatus, SecureBootStatus,
-    SiteExplorationReport,
+    ExploredEndpoint, ExploredEndpointReportHistoryRecord, InternalLockdownStatus, LockdownStatus,
+    MachineSetupStatus, SecureBootStatus, SiteExplorationReport,
 };
 use serde::Deserialize;
 
@@ -379,6 +379,8 @@ struct ExploredEndpointDetail<'a> {
     is_dell_endpoint: bool,
     report_age: String,
     pause_remediation: bool,
+    report_history: Vec<ExploredEndpointReportHistoryRecord>,
+    has_unacknowledged_drift: bool,
     bmc_action_redirect_to: Option<String>,
     action_status: Option<ActionStatus<'a>>,
 }
@@ -387,6 +389,7 @@ struct ExploredEndpointInfo {
     endpoint: ExploredEndpoint,
     credentials_set: String,
     has_machine: bool,
+    report_history: Vec<ExploredEndpointReportHistoryRecord>,
 }
 
 impl From<ExploredEndpointInfo> for ExploredEndpointDetail<'_> {
@@ -406,6 +409,10 @@ impl From<ExploredEndpointInfo> for ExploredEndpointDetail<'_> {
                 .unwrap_or_else(|| "unknown".to_string());
 
         let pause_remediation = endpoint_info.endpoint.pause_remediation;
+        let has_unacknowledged_drift = endpoint_info
+            .report_history
+            .iter()
+            .any(|record| record.unexpected && !record.acknowledged);
 
         Self {
             last_exploration_error: report_ref
@@ -429,6 +436,8 @@ impl From<ExploredEndpointInfo> for ExploredEndpointDetail<'_> {
             is_dell_endpoint,
             report_age,
             pause_remediation,
+            report_history: endpoint_info.report_history,
+            has_unacknowledged_drift,
             bmc_action_redirect_to: None,
             action_status: None,
         }
@@ -565,10 +574,26 @@ pub async fn detail(
         }
     };
 
+    let report_history = match state
+        .find_explored_endpoint_report_history(tonic::Request::new(
+            rpc::site_explorer::ExploredEndpointReportHistoryRequest {
+                address: endpoint_ip.clone(),
+            },
+        ))
+        .await
+    {
+        Ok(response) => response.into_inner().records,
+        Err(err) => {
+            tracing::error!(%err, %endpoint_ip, "find_explored_endpoint_report_history");
+            Vec::new()
+        }
+    };
+
     let endpoint_info = ExploredEndpointInfo {
         endpoint,
         credentials_set,
         has_machine,
+        report_history,
     };
 
     let mut display = ExploredEndpointDetail::from(endpoint_info);
@@ -624,6 +649,28 @@ pub async fn pause_remediation(
     Redirect::to(&view_url)
 }
 
+pub async fn acknowledge_drift(
+    AxumState(state): AxumState<Arc<Api>>,
+    AxumPath(endpoint_ip): AxumPath<String>,
+) -> impl IntoResponse {
+    let view_url = format!("/admin/explored-endpoint/{endpoint_ip}");
+
+    if let Err(err) = state
+        .acknowledge_explored_endpoint_drift(tonic::Request::new(
+            rpc::forge::AcknowledgeExploredEndpointDriftRequest {
+                ip_address: endpoint_ip.clone(),
+            },
+        ))
+        .await
+        .map(|response| response.into_inner())
+    {
+        tracing::error!(%err, endpoint_ip, "acknowledge_explored_endpoint_drift");
+        return Redirect::to(&view_url);
+    }
+
+    Redirect::to(&view_url)
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ReExploreEndpointAction {
     if_version_match: Option<String>,
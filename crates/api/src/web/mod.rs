@@ -464,6 +464,10 @@ pub fn routes(api: Arc<Api>) -> eyre::Result<NormalizePath<Router>> {
                 "/explored-endpoint/{endpoint_ip}/pause-remediation",
                 post(explored_endpoint::pause_remediation),
             )
+            .route(
+                "/explored-endpoint/{endpoint_ip}/acknowledge-drift",
+                post(explored_endpoint::acknowledge_drift),
+            )
             .route(
                 "/explored-endpoint/{endpoint_ip}/machine-setup",
                 post(explored_endpoint::machine_setup),
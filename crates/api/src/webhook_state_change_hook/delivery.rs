@@ -0,0 +1,474 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Delivery worker for the webhook state change hook.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use db::DatabaseError;
+use db::state_change_webhook::StateChangeWebhookDelivery;
+use forge_secrets::credentials::{CredentialKey, CredentialReader, Credentials};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::PgPool;
+use tokio_util::sync::CancellationToken;
+
+use crate::cfg::file::StateChangeWebhooksConfig;
+use crate::webhook_state_change_hook::metrics::WebhookHookMetrics;
+
+/// Header carrying the HMAC-SHA256 signature of `<timestamp>.<body>`, as `sha256=<hex>`.
+pub const SIGNATURE_HEADER: &str = "X-Carbide-Signature";
+
+/// Header carrying the time the request was signed, in seconds since the Unix epoch.
+/// It is part of the signed message, so receivers can reject replayed requests
+/// whose timestamp is too old.
+pub const TIMESTAMP_HEADER: &str = "X-Carbide-Timestamp";
+
+/// Header carrying the ID of the delivery. The ID stays the same across
+/// retries, so receivers can use it to discard duplicates.
+pub const DELIVERY_ID_HEADER: &str = "X-Carbide-Delivery";
+
+/// Maximum number of deliveries claimed per poll of the delivery queue.
+const CLAIM_BATCH_SIZE: usize = 16;
+
+/// Trait for sending webhook requests, enabling test mocks.
+#[async_trait::async_trait]
+pub trait WebhookTransport: Send + Sync + 'static {
+    /// POST a signed JSON body to the given URL.
+    ///
+    /// Returns a description of the failure if the endpoint did not accept the delivery.
+    async fn post(
+        &self,
+        url: &str,
+        delivery_id: i64,
+        timestamp: i64,
+        signature: &str,
+        body: Vec<u8>,
+    ) -> Result<(), String>;
+}
+
+#[async_trait::async_trait]
+impl WebhookTransport for reqwest::Client {
+    async fn post(
+        &self,
+        url: &str,
+        delivery_id: i64,
+        timestamp: i64,
+        signature: &str,
+        body: Vec<u8>,
+    ) -> Result<(), String> {
+        let response = reqwest::Client::post(self, url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(DELIVERY_ID_HEADER, delivery_id.to_string())
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {e}"))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(format!("endpoint responded with status {status}"))
+        }
+    }
+}
+
+/// Settings of the delivery worker.
+#[derive(Debug, Clone)]
+pub struct DeliverySettings {
+    /// URLs of the configured endpoints, by endpoint name.
+    pub endpoints: HashMap<String, String>,
+    pub max_attempts: u32,
+    pub initial_retry_backoff: Duration,
+    pub max_retry_backoff: Duration,
+    pub poll_interval: Duration,
+    /// How long claimed deliveries are reserved for this worker. Must cover
+    /// the time required to attempt a full batch of deliveries.
+    pub lease: Duration,
+}
+
+impl From<&StateChangeWebhooksConfig> for DeliverySettings {
+    fn from(config: &StateChangeWebhooksConfig) -> Self {
+        Self {
+            endpoints: config
+                .endpoints
+                .iter()
+                .map(|endpoint| (endpoint.name.clone(), endpoint.url.clone()))
+                .collect(),
+            max_attempts: config.max_attempts,
+            initial_retry_backoff: config.initial_retry_backoff,
+            max_retry_backoff: config.max_retry_backoff,
+            poll_interval: config.poll_interval,
+            lease: config
+                .request_timeout
+                .saturating_mul(CLAIM_BATCH_SIZE as u32 + 1),
+        }
+    }
+}
+
+/// Signs a request body and the [`TIMESTAMP_HEADER`] value with HMAC-SHA256,
+/// in the format of [`SIGNATURE_HEADER`].
+pub fn sign_payload(secret: &[u8], timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delay before the next attempt of a delivery which failed `attempts` times.
+pub fn retry_backoff(attempts: u32, initial: Duration, max: Duration) -> Duration {
+    let exponent = attempts.saturating_sub(1).min(31);
+    initial.saturating_mul(1 << exponent).min(max)
+}
+
+/// Background task that delivers due deliveries from the delivery queue.
+///
+/// Deliveries are claimed in batches. Deliveries which are claimed but not
+/// attempted before shutdown become due again once their lease expires.
+pub async fn run_delivery_worker<C: CredentialReader + 'static, T: WebhookTransport>(
+    db_pool: PgPool,
+    credentials: C,
+    transport: T,
+    settings: DeliverySettings,
+    metrics: WebhookHookMetrics,
+    cancel_token: CancellationToken,
+) {
+    while !cancel_token.is_cancelled() {
+        let claimed = match claim_due(&db_pool, settings.lease).await {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to claim state change webhook deliveries");
+                Vec::new()
+            }
+        };
+        let batch_full = claimed.len() == CLAIM_BATCH_SIZE;
+
+        // Secrets are cached per batch, so rotated secrets are picked up on the next poll
+        let mut secrets = HashMap::new();
+        for delivery in claimed {
+            if cancel_token.is_cancelled() {
+                break;
+            }
+            let id = delivery.id;
+            if let Err(e) = deliver(
+                &db_pool,
+                &credentials,
+                &transport,
+                &settings,
+                &metrics,
+                &mut secrets,
+                delivery,
+            )
+            .await
+            {
+                tracing::warn!(id, error = %e, "Failed to update state change webhook delivery");
+            }
+        }
+
+        if !batch_full {
+            cancel_token
+                .run_until_cancelled(tokio::time::sleep(settings.poll_interval))
+                .await;
+        }
+    }
+    tracing::debug!("State change webhook delivery task stopped");
+}
+
+async fn claim_due(
+    db_pool: &PgPool,
+    lease: Duration,
+) -> Result<Vec<StateChangeWebhookDelivery>, DatabaseError> {
+    let mut conn = db_pool.acquire().await.map_err(DatabaseError::acquire)?;
+    db::state_change_webhook::claim_due(&mut conn, CLAIM_BATCH_SIZE, lease).await
+}
+
+/// Attempts a single delivery, and removes or reschedules it based on the outcome.
+async fn deliver<C: CredentialReader, T: WebhookTransport>(
+    db_pool: &PgPool,
+    credentials: &C,
+    transport: &T,
+    settings: &DeliverySettings,
+    metrics: &WebhookHookMetrics,
+    secrets: &mut HashMap<String, String>,
+    delivery: StateChangeWebhookDelivery,
+) -> Result<(), DatabaseError> {
+    let Some(url) = settings.endpoints.get(&delivery.endpoint) else {
+        tracing::warn!(
+            id = delivery.id,
+            endpoint = %delivery.endpoint,
+            "Dropping state change webhook delivery for endpoint which is no longer configured"
+        );
+        metrics.record_delivery_abandoned(&delivery.endpoint);
+        let mut conn = db_pool.acquire().await.map_err(DatabaseError::acquire)?;
+        return db::state_change_webhook::delete(&mut conn, delivery.id).await;
+    };
+
+    let result = match endpoint_secret(credentials, secrets, &delivery.endpoint).await {
+        Ok(secret) => {
+            let body = delivery.payload.to_string().into_bytes();
+            let timestamp = chrono::Utc::now().timestamp();
+            let signature = sign_payload(secret.as_bytes(), timestamp, &body);
+            transport
+                .post(url, delivery.id, timestamp, &signature, body)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+
+    let mut conn = db_pool.acquire().await.map_err(DatabaseError::acquire)?;
+    match result {
+        Ok(()) => {
+            tracing::debug!(
+                id = delivery.id,
+                endpoint = %delivery.endpoint,
+                object_id = %delivery.object_id,
+                "Delivered state change webhook"
+            );
+            metrics.record_delivered(&delivery.endpoint);
+            db::state_change_webhook::delete(&mut conn, delivery.id).await
+        }
+        Err(error) if delivery.attempts.max(0) as u32 >= settings.max_attempts => {
+            tracing::warn!(
+                id = delivery.id,
+                endpoint = %delivery.endpoint,
+                object_id = %delivery.object_id,
+                attempts = delivery.attempts,
+                %error,
+                "Abandoning state change webhook delivery"
+            );
+            metrics.record_delivery_abandoned(&delivery.endpoint);
+            db::state_change_webhook::delete(&mut conn, delivery.id).await
+        }
+        Err(error) => {
+            let delay = retry_backoff(
+                delivery.attempts.max(0) as u32,
+                settings.initial_retry_backoff,
+                settings.max_retry_backoff,
+            );
+            tracing::debug!(
+                id = delivery.id,
+                endpoint = %delivery.endpoint,
+                attempts = delivery.attempts,
+                %error,
+                ?delay,
+                "State change webhook delivery failed, retrying"
+            );
+            metrics.record_delivery_failed(&delivery.endpoint);
+            db::state_change_webhook::reschedule(&mut conn, delivery.id, delay, &error).await
+        }
+    }
+}
+
+/// Loads the HMAC secret of an endpoint, caching it in `secrets`.
+async fn endpoint_secret<C: CredentialReader>(
+    credentials: &C,
+    secrets: &mut HashMap<String, String>,
+    endpoint: &str,
+) -> Result<String, String> {
+    if let Some(secret) = secrets.get(endpoint) {
+        return Ok(secret.clone());
+    }
+
+    let key = CredentialKey::StateChangeWebhook {
+        endpoint: endpoint.to_string(),
+    };
+    let secret = match credentials.get_credentials(&key).await {
+        Ok(Some(Credentials::UsernamePassword { password, .. })) => password,
+        Ok(None) => return Err(format!("HMAC secret {} not found", key.to_key_str())),
+        Err(e) => return Err(format!("failed to load HMAC secret: {e}")),
+    };
+    secrets.insert(endpoint.to_string(), secret.clone());
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use db::state_change_webhook::NewStateChangeWebhookDelivery;
+    use forge_secrets::local_credentials::CredentialSnapshot;
+    use opentelemetry::global;
+    use tokio::sync::mpsc;
+    use tokio::task::JoinSet;
+
+    use super::*;
+
+    #[test]
+    fn test_sign_payload() {
+        assert_eq!(
+            sign_payload(
+                b"key",
+                1700000000,
+                b"The quick brown fox jumps over the lazy dog"
+            ),
+            "sha256=2f658d6aef4f246e91cd741bbcded7479e9605f9d41c9e248122a117e0e1765b"
+        );
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let initial = Duration::from_secs(5);
+        let max = Duration::from_secs(60);
+        assert_eq!(retry_backoff(1, initial, max), Duration::from_secs(5));
+        assert_eq!(retry_backoff(2, initial, max), Duration::from_secs(10));
+        assert_eq!(retry_backoff(4, initial, max), Duration::from_secs(40));
+        assert_eq!(retry_backoff(5, initial, max), max);
+        assert_eq!(retry_backoff(u32::MAX, initial, max), max);
+    }
+
+    /// Transport that fails the first `failures` requests, and reports every request.
+    struct RecordingTransport {
+        failures: Mutex<usize>,
+        sender: mpsc::UnboundedSender<(String, i64, String, Vec<u8>)>,
+    }
+
+    #[async_trait::async_trait]
+    impl WebhookTransport for RecordingTransport {
+        async fn post(
+            &self,
+            url: &str,
+            _delivery_id: i64,
+            timestamp: i64,
+            signature: &str,
+            body: Vec<u8>,
+        ) -> Result<(), String> {
+            let _ = self
+                .sender
+                .send((url.to_string(), timestamp, signature.to_string(), body));
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err("endpoint responded with status 503".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    async fn pending_deliveries(pool: &PgPool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM state_change_webhook_deliveries")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn run_worker(
+        pool: PgPool,
+        failures: usize,
+        max_attempts: u32,
+    ) -> Vec<(String, i64, String, Vec<u8>)> {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let transport = RecordingTransport {
+            failures: Mutex::new(failures),
+            sender,
+        };
+        let credentials = CredentialSnapshot {
+            state_change_webhook_secrets: HashMap::from([(
+                "hook".to_string(),
+                "secret".to_string(),
+            )]),
+            ..Default::default()
+        };
+        let settings = DeliverySettings {
+            endpoints: HashMap::from([(
+                "hook".to_string(),
+                "https://hooks.example.com".to_string(),
+            )]),
+            max_attempts,
+            initial_retry_backoff: Duration::ZERO,
+            max_retry_backoff: Duration::ZERO,
+            poll_interval: Duration::from_millis(10),
+            lease: Duration::from_secs(60),
+        };
+        let (metrics_sender, _metrics_receiver) = mpsc::channel::<()>(1);
+        let metrics = WebhookHookMetrics::new(&global::meter("test"), metrics_sender.downgrade());
+
+        let mut join_set = JoinSet::new();
+        let cancel_token = CancellationToken::new();
+        join_set.spawn(run_delivery_worker(
+            pool.clone(),
+            credentials,
+            transport,
+            settings,
+            metrics,
+            cancel_token.clone(),
+        ));
+
+        let expected_requests = failures.saturating_add(1).min(max_attempts as usize);
+        let mut requests = Vec::new();
+        while requests.len() < expected_requests {
+            requests.push(receiver.recv().await.expect("should receive request"));
+        }
+        // Wait until the worker processed the outcome of the last request
+        while pending_deliveries(&pool).await > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        cancel_token.cancel();
+        join_set.join_all().await;
+        requests
+    }
+
+    async fn enqueue_delivery(pool: &PgPool, payload: &serde_json::Value) {
+        let mut txn = pool.begin().await.unwrap();
+        let delivery = NewStateChangeWebhookDelivery {
+            endpoint: "hook",
+            object_type: "machine",
+            object_id: "fm100htest",
+            payload,
+        };
+        assert!(
+            db::state_change_webhook::enqueue(&mut txn, &delivery, 10)
+                .await
+                .unwrap()
+        );
+        txn.commit().await.unwrap();
+    }
+
+    #[crate::sqlx_test]
+    async fn test_failed_deliveries_are_retried(pool: PgPool) {
+        let payload = serde_json::json!({"object_type": "machine", "state": {"state": "ready"}});
+        enqueue_delivery(&pool, &payload).await;
+
+        let requests = run_worker(pool.clone(), 2, 10).await;
+
+        assert_eq!(requests.len(), 3);
+        for (url, timestamp, signature, body) in requests {
+            assert_eq!(url, "https://hooks.example.com");
+            assert_eq!(signature, sign_payload(b"secret", timestamp, &body));
+            let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(parsed, payload);
+        }
+        assert_eq!(pending_deliveries(&pool).await, 0);
+    }
+
+    #[crate::sqlx_test]
+    async fn test_deliveries_are_abandoned_after_max_attempts(pool: PgPool) {
+        let payload = serde_json::json!({"object_type": "machine"});
+        enqueue_delivery(&pool, &payload).await;
+
+        let requests = run_worker(pool.clone(), usize::MAX, 3).await;
+
+        assert_eq!(requests.len(), 3);
+        assert_eq!(pending_deliveries(&pool).await, 0);
+    }
+}
@@ -0,0 +1,428 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Webhook hook implementation for queueing state changes.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use db::DatabaseError;
+use db::state_change_webhook::NewStateChangeWebhookDelivery;
+use forge_secrets::credentials::CredentialReader;
+use opentelemetry::metrics::Meter;
+use serde::Serialize;
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+use crate::cfg::file::{
+    StateChangeWebhookEndpointConfig, StateChangeWebhookObjectType, StateChangeWebhooksConfig,
+};
+use crate::state_controller::io::StateControllerIO;
+use crate::state_controller::state_change_emitter::{StateChangeEvent, StateChangeHook};
+use crate::webhook_state_change_hook::delivery::{DeliverySettings, run_delivery_worker};
+use crate::webhook_state_change_hook::message::StateChangeWebhookMessage;
+use crate::webhook_state_change_hook::metrics::WebhookHookMetrics;
+
+/// Object type and target state filter of a webhook endpoint.
+#[derive(Debug, Clone)]
+pub struct EndpointFilter {
+    name: String,
+    object_types: Vec<StateChangeWebhookObjectType>,
+    target_states: Vec<String>,
+}
+
+impl EndpointFilter {
+    /// Returns whether a transition of an object of `object_type` into the
+    /// given state should be delivered to the endpoint.
+    ///
+    /// `state` and `substate` are the names returned by
+    /// `StateControllerIO::metric_state_names`.
+    pub fn matches(
+        &self,
+        object_type: StateChangeWebhookObjectType,
+        state: &str,
+        substate: &str,
+    ) -> bool {
+        let object_type_matches =
+            self.object_types.is_empty() || self.object_types.contains(&object_type);
+        let state_matches = self.target_states.is_empty()
+            || self
+                .target_states
+                .iter()
+                .any(|target| match target.split_once('.') {
+                    Some((target_state, target_substate)) => {
+                        target_state.eq_ignore_ascii_case(state)
+                            && target_substate.eq_ignore_ascii_case(substate)
+                    }
+                    None => target.eq_ignore_ascii_case(state),
+                });
+        object_type_matches && state_matches
+    }
+}
+
+impl From<&StateChangeWebhookEndpointConfig> for EndpointFilter {
+    fn from(config: &StateChangeWebhookEndpointConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            object_types: config.object_types.clone(),
+            target_states: config.target_states.clone(),
+        }
+    }
+}
+
+/// State change event waiting to be written to the delivery queue.
+struct QueuedEvent {
+    /// Names of the endpoints whose filters matched the event.
+    endpoints: Vec<String>,
+    object_type: StateChangeWebhookObjectType,
+    object_id: String,
+    payload: serde_json::Value,
+}
+
+/// Shared state of the webhook hooks of all state controllers.
+///
+/// Owns the background tasks which write matching events to the durable
+/// delivery queue and deliver them. Use [`StateChangeWebhooks::hook`] to create
+/// the hook for a specific state controller.
+pub struct StateChangeWebhooks {
+    sender: mpsc::Sender<QueuedEvent>,
+    filters: Arc<[EndpointFilter]>,
+    metrics: WebhookHookMetrics,
+}
+
+impl StateChangeWebhooks {
+    /// Create the webhook hooks from the given configuration.
+    ///
+    /// Spawns a background task which writes queued events to the database,
+    /// and the delivery worker.
+    /// Emits metrics:
+    /// - `carbide_state_change_webhook_event_count`: Events matching an endpoint
+    /// - `carbide_state_change_webhook_delivery_count`: Delivery attempts
+    /// - `carbide_state_change_webhook_queue_depth`: Current in-memory queue depth
+    pub fn new<C: CredentialReader + 'static>(
+        config: &StateChangeWebhooksConfig,
+        db_pool: PgPool,
+        credentials: C,
+        join_set: &mut JoinSet<()>,
+        meter: &Meter,
+        cancel_token: CancellationToken,
+    ) -> Result<Self, eyre::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| eyre::eyre!("Failed to create state change webhook HTTP client: {e}"))?;
+
+        let (sender, receiver) = mpsc::channel(config.queue_capacity);
+        let metrics = WebhookHookMetrics::new(meter, sender.downgrade());
+
+        join_set.spawn(persist_events(
+            receiver,
+            db_pool.clone(),
+            config.max_pending_deliveries,
+            metrics.clone(),
+            cancel_token.clone(),
+        ));
+        join_set.spawn(run_delivery_worker(
+            db_pool,
+            credentials,
+            client,
+            DeliverySettings::from(config),
+            metrics.clone(),
+            cancel_token,
+        ));
+
+        Ok(Self {
+            sender,
+            filters: config.endpoints.iter().map(EndpointFilter::from).collect(),
+            metrics,
+        })
+    }
+
+    /// Create the hook for the state controller of the given object type.
+    pub fn hook<IO: StateControllerIO>(
+        &self,
+        object_type: StateChangeWebhookObjectType,
+    ) -> WebhookStateChangeHook<IO> {
+        WebhookStateChangeHook {
+            object_type,
+            sender: self.sender.clone(),
+            filters: self.filters.clone(),
+            metrics: self.metrics.clone(),
+            _io: PhantomData,
+        }
+    }
+}
+
+/// Webhook hook that queues state changes of one object type for delivery to
+/// the webhook endpoints whose filters match.
+///
+/// Events are buffered in a bounded in-memory queue until they are written to
+/// the database. If the queue is full, events are dropped and a warning is logged.
+pub struct WebhookStateChangeHook<IO: StateControllerIO> {
+    object_type: StateChangeWebhookObjectType,
+    sender: mpsc::Sender<QueuedEvent>,
+    filters: Arc<[EndpointFilter]>,
+    metrics: WebhookHookMetrics,
+    _io: PhantomData<fn() -> IO>,
+}
+
+impl<IO> StateChangeHook<IO::ObjectId, IO::ControllerState> for WebhookStateChangeHook<IO>
+where
+    IO: StateControllerIO,
+    IO::ControllerState: Serialize,
+{
+    fn on_state_changed(&self, event: &StateChangeEvent<'_, IO::ObjectId, IO::ControllerState>) {
+        let (state, substate) = IO::metric_state_names(event.new_state);
+        let endpoints: Vec<String> = self
+            .filters
+            .iter()
+            .filter(|filter| filter.matches(self.object_type, state, substate))
+            .map(|filter| filter.name.clone())
+            .collect();
+        if endpoints.is_empty() {
+            return;
+        }
+
+        let message = StateChangeWebhookMessage {
+            object_type: self.object_type.as_str(),
+            object_id: event.object_id.to_string(),
+            timestamp: event.timestamp,
+            state: event.new_state,
+            previous_state: event.previous_state,
+        };
+
+        match message.to_json_value() {
+            Ok(payload) => {
+                let queued = QueuedEvent {
+                    endpoints,
+                    object_type: self.object_type,
+                    object_id: message.object_id,
+                    payload,
+                };
+                if let Err(e) = self.sender.try_send(queued) {
+                    tracing::warn!("State change webhook event dropped (queue full): {e}");
+                    self.metrics.record_overflow(self.object_type.as_str());
+                }
+            }
+            Err(e) => {
+                tracing::error!(
+                    object_id = %event.object_id,
+                    error = %e,
+                    "Failed to serialize state change webhook message"
+                );
+                self.metrics
+                    .record_serialization_error(self.object_type.as_str());
+            }
+        }
+    }
+}
+
+/// Background task that writes queued events to the delivery queue.
+async fn persist_events(
+    mut receiver: mpsc::Receiver<QueuedEvent>,
+    db_pool: PgPool,
+    max_pending_deliveries: usize,
+    metrics: WebhookHookMetrics,
+    cancel_token: CancellationToken,
+) {
+    while let Some(Some(event)) = cancel_token.run_until_cancelled(receiver.recv()).await {
+        let object_type = event.object_type.as_str();
+        for endpoint in &event.endpoints {
+            let delivery = NewStateChangeWebhookDelivery {
+                endpoint,
+                object_type,
+                object_id: &event.object_id,
+                payload: &event.payload,
+            };
+            match enqueue(&db_pool, &delivery, max_pending_deliveries).await {
+                Ok(true) => metrics.record_queued(object_type),
+                Ok(false) => {
+                    tracing::warn!(
+                        %endpoint,
+                        object_id = %event.object_id,
+                        "State change webhook event dropped (too many pending deliveries)"
+                    );
+                    metrics.record_pending_limit_reached(object_type);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        %endpoint,
+                        object_id = %event.object_id,
+                        error = %e,
+                        "Failed to queue state change webhook delivery"
+                    );
+                    metrics.record_database_error(object_type);
+                }
+            }
+        }
+    }
+    tracing::debug!("State change webhook queue task stopped");
+}
+
+async fn enqueue(
+    db_pool: &PgPool,
+    delivery: &NewStateChangeWebhookDelivery<'_>,
+    max_pending_deliveries: usize,
+) -> Result<bool, DatabaseError> {
+    let mut txn = db::Transaction::begin(db_pool).await?;
+    let queued =
+        db::state_change_webhook::enqueue(&mut txn, delivery, max_pending_deliveries).await?;
+    txn.commit().await?;
+    Ok(queued)
+}
+
+#[cfg(test)]
+mod tests {
+    use model::machine::ManagedHostState;
+    use opentelemetry::global;
+
+    use super::*;
+    use crate::state_controller::machine::io::MachineStateControllerIO;
+
+    fn filter(
+        name: &str,
+        object_types: &[StateChangeWebhookObjectType],
+        target_states: &[&str],
+    ) -> EndpointFilter {
+        EndpointFilter {
+            name: name.to_string(),
+            object_types: object_types.to_vec(),
+            target_states: target_states.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn test_hook(
+        filters: Vec<EndpointFilter>,
+        queue_capacity: usize,
+    ) -> (
+        WebhookStateChangeHook<MachineStateControllerIO>,
+        mpsc::Receiver<QueuedEvent>,
+    ) {
+        let (sender, receiver) = mpsc::channel(queue_capacity);
+        let metrics = WebhookHookMetrics::new(&global::meter("test"), sender.downgrade());
+        let hook = WebhookStateChangeHook {
+            object_type: StateChangeWebhookObjectType::Machine,
+            sender,
+            filters: filters.into(),
+            metrics,
+            _io: PhantomData,
+        };
+        (hook, receiver)
+    }
+
+    fn emit(hook: &WebhookStateChangeHook<MachineStateControllerIO>, state: &ManagedHostState) {
+        #[allow(deprecated)]
+        let machine_id = carbide_uuid::machine::MachineId::default();
+        hook.on_state_changed(&StateChangeEvent {
+            object_id: &machine_id,
+            previous_state: None,
+            new_state: state,
+            timestamp: chrono::Utc::now(),
+        });
+    }
+
+    #[test]
+    fn test_filter_matching() {
+        use StateChangeWebhookObjectType::*;
+
+        let all = filter("all", &[], &[]);
+        assert!(all.matches(Machine, "ready", ""));
+        assert!(all.matches(PowerShelf, "error", ""));
+
+        let switches = filter("switches", &[Switch], &[]);
+        assert!(switches.matches(Switch, "ready", ""));
+        assert!(!switches.matches(Rack, "ready", ""));
+
+        let states = filter("states", &[], &["ready", "Assigned.Ready"]);
+        assert!(states.matches(Machine, "ready", ""));
+        assert!(states.matches(Machine, "assigned", "ready"));
+        assert!(!states.matches(Machine, "assigned", "bootingwithdiscoveryimage"));
+        assert!(!states.matches(Machine, "hostinit", "ready"));
+
+        let both = filter("both", &[Machine], &["ready"]);
+        assert!(both.matches(Machine, "ready", ""));
+        assert!(!both.matches(Switch, "ready", ""));
+        assert!(!both.matches(Machine, "hostinit", ""));
+    }
+
+    #[tokio::test]
+    async fn test_matching_events_are_queued() {
+        let (hook, mut receiver) = test_hook(
+            vec![
+                filter(
+                    "ready",
+                    &[StateChangeWebhookObjectType::Machine],
+                    &["ready"],
+                ),
+                filter("switches", &[StateChangeWebhookObjectType::Switch], &[]),
+                filter("all", &[], &[]),
+            ],
+            16,
+        );
+
+        emit(&hook, &ManagedHostState::Ready);
+
+        let queued = receiver.try_recv().expect("event should be queued");
+        assert_eq!(
+            queued.endpoints,
+            vec!["ready".to_string(), "all".to_string()]
+        );
+        assert_eq!(queued.object_type, StateChangeWebhookObjectType::Machine);
+        assert_eq!(queued.payload.get("object_type").unwrap(), "machine");
+        assert_eq!(
+            queued.payload.get("state").unwrap().get("state").unwrap(),
+            "ready"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_events_are_not_queued() {
+        let (hook, mut receiver) = test_hook(
+            vec![filter(
+                "switches",
+                &[StateChangeWebhookObjectType::Switch],
+                &[],
+            )],
+            16,
+        );
+
+        emit(&hook, &ManagedHostState::Ready);
+
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_queue_overflow_drops_events() {
+        const QUEUE_SIZE: usize = 4;
+
+        let (hook, mut receiver) = test_hook(vec![filter("all", &[], &[])], QUEUE_SIZE);
+
+        for _ in 0..(QUEUE_SIZE + 10) {
+            emit(&hook, &ManagedHostState::Ready);
+        }
+        drop(hook);
+
+        let mut count = 0;
+        while receiver.recv().await.is_some() {
+            count += 1;
+        }
+        assert_eq!(count, QUEUE_SIZE);
+    }
+}
@@ -0,0 +1,82 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Message types for the webhook state change hook.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Webhook body for a state change of any state controlled object.
+///
+/// The state uses the native serde serialization of the object's controller
+/// state, so the payload matches what the API returns for the object.
+#[derive(Debug, Clone, Serialize)]
+pub struct StateChangeWebhookMessage<'a, S> {
+    /// Type of the object, e.g. `machine` or `power_shelf`.
+    pub object_type: &'static str,
+    /// Unique identifier of the object.
+    pub object_id: String,
+    /// ISO 8601 timestamp of the state change.
+    pub timestamp: DateTime<Utc>,
+    /// The state the object transitioned into.
+    pub state: &'a S,
+    /// The state the object transitioned from. Omitted if unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_state: Option<&'a S>,
+}
+
+impl<S: Serialize> StateChangeWebhookMessage<'_, S> {
+    /// Serialize the message into the JSON value stored in the delivery queue.
+    pub fn to_json_value(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use model::machine::{InstanceState, ManagedHostState};
+
+    use super::*;
+
+    #[test]
+    fn test_managed_host_state_serialization() {
+        let state = ManagedHostState::Assigned {
+            instance_state: InstanceState::Ready,
+        };
+        let timestamp = Utc::now();
+
+        let message = StateChangeWebhookMessage {
+            object_type: "machine",
+            object_id: "fm100htest".to_string(),
+            timestamp,
+            state: &state,
+            previous_state: Some(&ManagedHostState::Ready),
+        };
+        let parsed = message.to_json_value().unwrap();
+
+        assert_eq!(parsed.get("object_type").unwrap(), "machine");
+        assert_eq!(parsed.get("object_id").unwrap(), "fm100htest");
+        let state_obj = parsed.get("state").unwrap();
+        assert_eq!(state_obj.get("state").unwrap(), "assigned");
+        assert!(state_obj.get("instance_state").is_some());
+        let previous_state_obj = parsed.get("previous_state").unwrap();
+        assert_eq!(previous_state_obj.get("state").unwrap(), "ready");
+
+        let ts = parsed.get("timestamp").unwrap().as_str().unwrap();
+        chrono::DateTime::parse_from_rfc3339(ts).expect("timestamp should be RFC 3339");
+    }
+}
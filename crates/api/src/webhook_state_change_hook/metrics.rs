@@ -0,0 +1,132 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Metrics for the webhook state change hook.
+
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{Counter, Meter};
+use tokio::sync::mpsc::WeakSender;
+
+/// Metrics for the webhook state change hook.
+#[derive(Clone)]
+pub struct WebhookHookMetrics {
+    /// Counter for state change events, with status label for queued/dropped.
+    event_count: Counter<u64>,
+    /// Counter for delivery attempts, with status label for the outcome.
+    delivery_count: Counter<u64>,
+}
+
+impl WebhookHookMetrics {
+    /// Create new metrics instruments from the given meter.
+    ///
+    /// Uses a weak reference to the sender to observe the depth of the in-memory
+    /// queue without preventing shutdown (when the sender is dropped, queue depth
+    /// reports 0).
+    pub fn new<T: Send + 'static>(meter: &Meter, sender: WeakSender<T>) -> Self {
+        let max_capacity = sender.upgrade().map(|s| s.max_capacity()).unwrap_or(0);
+
+        meter
+            .u64_observable_gauge("carbide_state_change_webhook_queue_depth")
+            .with_description(
+                "Number of state change events waiting to be written to the webhook delivery queue",
+            )
+            .with_callback(move |observer| {
+                let depth = sender
+                    .upgrade()
+                    .map(|s| max_capacity - s.capacity())
+                    .unwrap_or(0);
+                observer.observe(depth as u64, &[]);
+            })
+            .build();
+
+        let event_count = meter
+            .u64_counter("carbide_state_change_webhook_event_count")
+            .with_description("Total number of state change events matching a webhook endpoint")
+            .build();
+
+        let delivery_count = meter
+            .u64_counter("carbide_state_change_webhook_delivery_count")
+            .with_description("Total number of webhook delivery attempts")
+            .build();
+
+        Self {
+            event_count,
+            delivery_count,
+        }
+    }
+
+    fn record_event(&self, object_type: &'static str, status: &'static str) {
+        self.event_count.add(
+            1,
+            &[
+                KeyValue::new("object_type", object_type),
+                KeyValue::new("status", status),
+            ],
+        );
+    }
+
+    fn record_delivery(&self, endpoint: &str, status: &'static str) {
+        self.delivery_count.add(
+            1,
+            &[
+                KeyValue::new("endpoint", endpoint.to_string()),
+                KeyValue::new("status", status),
+            ],
+        );
+    }
+
+    /// Record that an event was written to the delivery queue.
+    pub fn record_queued(&self, object_type: &'static str) {
+        self.record_event(object_type, "queued");
+    }
+
+    /// Record that an event was dropped because the in-memory queue was full.
+    pub fn record_overflow(&self, object_type: &'static str) {
+        self.record_event(object_type, "overflow");
+    }
+
+    /// Record that an event was dropped because the endpoint reached its
+    /// maximum number of pending deliveries.
+    pub fn record_pending_limit_reached(&self, object_type: &'static str) {
+        self.record_event(object_type, "pending_limit_reached");
+    }
+
+    /// Record that an event could not be written to the delivery queue.
+    pub fn record_database_error(&self, object_type: &'static str) {
+        self.record_event(object_type, "database_error");
+    }
+
+    /// Record a serialization failure.
+    pub fn record_serialization_error(&self, object_type: &'static str) {
+        self.record_event(object_type, "serialization_error");
+    }
+
+    /// Record a successful delivery.
+    pub fn record_delivered(&self, endpoint: &str) {
+        self.record_delivery(endpoint, "ok");
+    }
+
+    /// Record a failed delivery which will be retried.
+    pub fn record_delivery_failed(&self, endpoint: &str) {
+        self.record_delivery(endpoint, "failed");
+    }
+
+    /// Record a failed delivery which exhausted its attempts.
+    pub fn record_delivery_abandoned(&self, endpoint: &str) {
+        self.record_delivery(endpoint, "abandoned");
+    }
+}
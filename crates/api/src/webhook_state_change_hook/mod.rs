@@ -0,0 +1,32 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! HTTP webhook state change hook.
+//!
+//! Pushes state transitions of machines, switches, racks and power shelves to
+//! the HTTPS endpoints configured in `StateChangeWebhooksConfig`. Events which
+//! match an endpoint's object type and target state filters are written to the
+//! `state_change_webhook_deliveries` table, and a background worker POSTs them
+//! as JSON, signed with the endpoint's HMAC-SHA256 secret and timestamped so
+//! receivers can reject replays. Failed deliveries
+//! are retried with exponential backoff, so events survive endpoint outages
+//! and carbide-api restarts.
+
+pub mod delivery;
+pub mod hook;
+pub mod message;
+pub mod metrics;
@@ -17,12 +17,14 @@
 use std::sync::Arc;
 
 use crate::bug::InjectedBugs;
-use crate::redfish;
 use crate::redfish::account_service::AccountServiceState;
 use crate::redfish::chassis::ChassisState;
 use crate::redfish::computer_system::SystemState;
+use crate::redfish::event_service::{EventRecord, EventServiceState, Severity};
 use crate::redfish::manager::ManagerState;
 use crate::redfish::update_service::UpdateServiceState;
+use crate::simulation::{Simulation, Snapshot};
+use crate::{MockPowerState, redfish};
 
 #[derive(Clone)]
 pub struct BmcState {
@@ -35,7 +37,9 @@ pub struct BmcState {
     pub chassis_state: Arc<ChassisState>,
     pub update_service_state: Arc<UpdateServiceState>,
     pub account_service_state: Arc<AccountServiceState>,
+    pub event_service_state: Arc<EventServiceState>,
     pub injected_bugs: Arc<InjectedBugs>,
+    pub simulation: Arc<Simulation>,
     pub callbacks: Option<Arc<dyn crate::Callbacks>>,
 }
 
@@ -50,6 +54,11 @@ impl BmcState {
         match event {
             BmcEvent::PowerOn => {
                 self.complete_all_bios_jobs();
+                self.event_service_state.emit(EventRecord::new(
+                    "ResourceEvent.1.3.ResourcePoweredOn",
+                    Severity::Ok,
+                    "Machine is powered on",
+                ));
             }
             BmcEvent::BootCompleted => {
                 self.system_state.on_boot_completed();
@@ -57,6 +66,15 @@ impl BmcState {
         }
     }
 
+    /// Simulated machine state, or None if the simulation is disabled.
+    pub fn simulation_snapshot(&self) -> Option<Snapshot> {
+        let power_on = self
+            .callbacks
+            .as_ref()
+            .is_none_or(|callbacks| matches!(callbacks.get_power_state(), MockPowerState::On));
+        self.simulation.snapshot(power_on)
+    }
+
     pub fn complete_all_bios_jobs(&self) {
         if let redfish::oem::State::DellIdrac(v) = &self.oem_state {
             v.complete_all_bios_jobs()
@@ -29,6 +29,7 @@ use crate::redfish;
 #[derive(Clone, Default)]
 pub struct InjectedBugs {
     all_dpu_lost_on_host: Arc<AtomicBool>,
+    firmware_update_failure: Arc<AtomicBool>,
     long_response: Arc<ArcSwap<Option<LongResponse>>>,
     http_error: Arc<Mutex<Option<HttpErrorRule>>>,
 }
@@ -36,6 +37,7 @@ pub struct InjectedBugs {
 #[derive(Deserialize, Serialize, Default)]
 pub struct Args {
     pub all_dpu_lost_on_host: Option<bool>,
+    pub firmware_update_failure: Option<bool>,
     pub long_response: Option<LongResponse>,
     pub http_error: Option<HttpErrorRule>,
 }
@@ -61,6 +63,7 @@ impl InjectedBugs {
         let http_error = self.http_error.lock().unwrap();
         serde_json::json!(Args {
             all_dpu_lost_on_host: Some(self.all_dpu_lost_on_host().is_some()),
+            firmware_update_failure: Some(self.firmware_update_failure()),
             long_response: long_response.as_ref().clone(),
             http_error: http_error.clone()
         })
@@ -77,6 +80,10 @@ impl InjectedBugs {
             args.all_dpu_lost_on_host.unwrap_or(false),
             Ordering::Relaxed,
         );
+        self.firmware_update_failure.store(
+            args.firmware_update_failure.unwrap_or(false),
+            Ordering::Relaxed,
+        );
         self.long_response.store(args.long_response.into());
         *self.http_error.lock().unwrap() = args.http_error;
     }
@@ -87,6 +94,10 @@ impl InjectedBugs {
             .then_some(AllDpuLostOnHost {})
     }
 
+    pub fn firmware_update_failure(&self) -> bool {
+        self.firmware_update_failure.load(Ordering::Relaxed)
+    }
+
     pub fn long_response(&self, path: &str) -> Option<Duration> {
         self.long_response.load().as_ref().as_ref().and_then(|v| {
             if v.path.as_ref().is_none_or(|v| v == path) {
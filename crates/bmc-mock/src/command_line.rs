@@ -58,6 +58,18 @@ pub struct Args {
         help = "An ip_address and .tar.gz file pair (comma separated).\nThe file is an archive of redfish data when the request is forwarded to a specific IP address.\nRepeat for different machines"
     )]
     pub ip_router: Option<Vec<IpRouterPair>>,
+
+    #[clap(
+        long,
+        help = "Path to a recorded scenario (.jsonl) to replay as the default BMC. Record one with carbide-bmc-proxy"
+    )]
+    pub scenario: Option<std::path::PathBuf>,
+
+    #[clap(
+        long,
+        help = "Directory of recorded scenarios named <ip_address>.jsonl.\nEach file is replayed when the request is forwarded to that IP address"
+    )]
+    pub scenario_dir: Option<std::path::PathBuf>,
 }
 
 pub fn parse_args() -> Args {
@@ -34,6 +34,8 @@ mod machine_info;
 mod middleware_router;
 mod mock_machine_router;
 mod redfish;
+pub mod scenario;
+pub mod simulation;
 pub mod test_support;
 pub mod tls;
 
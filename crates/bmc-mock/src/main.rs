@@ -24,6 +24,7 @@ use std::process::Command;
 use std::sync::Arc;
 
 use axum::Router;
+use bmc_mock::scenario::{Scenario, scenario_router};
 use bmc_mock::{
     BmcCommand, Callbacks, DpuMachineInfo, HostHardwareType, HostMachineInfo, ListenerOrAddress,
     MachineInfo, MockPowerState, SetSystemPowerError, SystemPowerControl,
@@ -81,11 +82,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    if let Some(scenario_dir) = args.scenario_dir {
+        for (ip_address, scenario) in Scenario::load_dir(&scenario_dir)? {
+            info!(
+                "Replaying {} recorded exchanges for {ip_address}",
+                scenario.exchanges.len()
+            );
+            routers_by_ip.insert(ip_address, scenario_router(scenario));
+        }
+    }
+
     let listen_addr = args.port.map(|p| SocketAddr::from(([0, 0, 0, 0], p)));
     info!("Using cert_path: {:?}", args.cert_path);
     let router = if let Some(tar_path) = args.targz {
         info!("Using archive {} as default", tar_path.to_string_lossy());
         tar_router::tar_router(TarGzOption::Disk(&tar_path), Some(&mut tar_router_entries)).unwrap()
+    } else if let Some(scenario_path) = args.scenario {
+        info!(
+            "Replaying scenario {} as default",
+            scenario_path.to_string_lossy()
+        );
+        scenario_router(Scenario::from_path(&scenario_path)?)
     } else {
         info!("Using default BMC mock");
         default_host_mock()
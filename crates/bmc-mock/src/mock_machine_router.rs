@@ -27,6 +27,7 @@ use crate::auth_router::Authorizer;
 use crate::bmc_state::BmcState;
 use crate::bug::InjectedBugs;
 use crate::json::JsonExt;
+use crate::redfish::event_service::{EventRecord, EventServiceState, Severity};
 use crate::redfish::manager::ManagerState;
 use crate::{Callbacks, MachineInfo, SystemPowerControl, auth_router, middleware_router, redfish};
 
@@ -83,12 +84,15 @@ pub fn machine_router(
             "/InjectedBugs",
             get(get_injected_bugs).post(post_injected_bugs),
         )
+        // Sensor and failure simulation control.
+        .route("/Simulation", get(get_simulation).post(post_simulation))
         .add_routes(crate::redfish::service_root::add_routes)
         .add_routes(crate::redfish::chassis::add_routes)
         .add_routes(crate::redfish::manager::add_routes)
         .add_routes(crate::redfish::update_service::add_routes)
         .add_routes(crate::redfish::task_service::add_routes)
         .add_routes(crate::redfish::account_service::add_routes)
+        .add_routes(crate::redfish::event_service::add_routes)
         .add_routes(|routes| crate::redfish::computer_system::add_routes(routes, bmc_vendor))
         .add_routes(crate::ipmi::add_routes);
     let router = match &machine_info {
@@ -97,7 +101,11 @@ pub fn machine_router(
         }
         MachineInfo::Host(_) => router.add_routes(crate::redfish::oem::dell::idrac::add_routes),
     };
-    let manager = Arc::new(ManagerState::new(&machine_info.manager_config()));
+    let manager_config = machine_info.manager_config();
+    let manager = Arc::new(ManagerState::new(&manager_config));
+    let event_service_state = Arc::new(EventServiceState::new(
+        manager_config.managers.first().map(|manager| manager.id),
+    ));
     let system_state = Arc::new(crate::redfish::computer_system::SystemState::from_config(
         system_config,
     ));
@@ -121,7 +129,9 @@ pub fn machine_router(
         chassis_state,
         update_service_state,
         account_service_state,
+        event_service_state,
         injected_bugs: injected_bugs.clone(),
+        simulation: Arc::default(),
         callbacks: Some(callbacks.clone()),
     };
     let account_service_state = state.account_service_state.clone();
@@ -154,7 +164,32 @@ async fn post_injected_bugs(
     state
         .injected_bugs
         .update(bug_args)
-        .map(|_| state.injected_bugs.get().into_ok_response())
+        .map(|_| {
+            let bugs = state.injected_bugs.get();
+            state.event_service_state.emit(EventRecord::new(
+                "ResourceEvent.1.0.ResourceErrorsDetected",
+                Severity::Warning,
+                format!("Injected bugs changed: {bugs}"),
+            ));
+            bugs.into_ok_response()
+        })
+        .unwrap_or_else(|err| {
+            serde_json::json!({"error": format!("{err:?}")}).into_response(StatusCode::BAD_REQUEST)
+        })
+}
+
+async fn get_simulation(State(state): State<BmcState>) -> Response {
+    state.simulation.get().into_ok_response()
+}
+
+async fn post_simulation(
+    State(state): State<BmcState>,
+    Json(args): Json<serde_json::Value>,
+) -> Response {
+    state
+        .simulation
+        .update(args)
+        .map(|_| state.simulation.get().into_ok_response())
         .unwrap_or_else(|err| {
             serde_json::json!({"error": format!("{err:?}")}).into_response(StatusCode::BAD_REQUEST)
         })
@@ -480,7 +480,11 @@ async fn get_chassis_sensor(
         .chassis_state
         .find(&chassis_id)
         .and_then(|chassis_state| chassis_state.find_sensor(&sensor_id))
-        .map(|sensor| sensor.to_json().into_ok_response())
+        .map(|sensor| match state.simulation_snapshot() {
+            Some(snapshot) => sensor.to_simulated_json(&snapshot),
+            None => sensor.to_json(),
+        })
+        .map(|sensor| sensor.into_ok_response())
         .unwrap_or_else(http::not_found)
 }
 
@@ -545,7 +549,11 @@ async fn get_chassis_power_supply(
     };
     chassis_state
         .find_power_supply(&power_supply_id)
-        .map(|v| v.to_json().into_ok_response())
+        .map(|v| match state.simulation_snapshot() {
+            Some(snapshot) => v.to_simulated_json(&snapshot),
+            None => v.to_json(),
+        })
+        .map(|v| v.into_ok_response())
         .unwrap_or_else(http::not_found)
 }
 
@@ -617,7 +625,16 @@ async fn get_chassis_leak_detector(
         .chassis_state
         .find(&chassis_id)
         .and_then(|chassis_state| chassis_state.find_leak_detector(&leak_detector_id))
-        .map(|detector| detector.to_json(&chassis_id).into_ok_response())
+        .cloned()
+        .map(|mut detector| {
+            if let Some(detector_state) = state
+                .simulation_snapshot()
+                .and_then(|snapshot| snapshot.leak_detector_state(&detector.id))
+            {
+                detector.detector_state = detector_state;
+            }
+            detector.to_json(&chassis_id).into_ok_response()
+        })
         .unwrap_or_else(http::not_found)
 }
 
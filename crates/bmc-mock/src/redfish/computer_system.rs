@@ -29,6 +29,7 @@ use serde_json::json;
 use crate::bmc_state::BmcState;
 use crate::json::{JsonExt, JsonPatch, json_patch};
 use crate::redfish::Builder;
+use crate::redfish::event_service::EventRecord;
 use crate::{
     BootOptionKind, Callbacks, LogServices, MockPowerState, POWER_CYCLE_DELAY, SetSystemPowerError,
     http, redfish,
@@ -540,7 +541,12 @@ async fn post_reset_system(
     // while issuing a redfish call, and MachineStateMachine is blocked waiting for the row lock
     // to be released.
     match callbacks.set_power_state(reset_type) {
-        Ok(_) => json!({}).into_ok_response(),
+        Ok(_) => {
+            state
+                .event_service_state
+                .emit(EventRecord::system_reset(&system_id, reset_type));
+            json!({}).into_ok_response()
+        }
         Err(SetSystemPowerError::BadRequest(_)) => StatusCode::BAD_REQUEST.into_response(),
         Err(SetSystemPowerError::CommandSendError(_)) => {
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
@@ -0,0 +1,678 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::Router;
+use axum::extract::{Json, Path, State};
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::bmc_state::BmcState;
+use crate::json::{JsonExt, JsonPatch};
+use crate::redfish::Builder;
+use crate::redfish::log_service::EntryBuilder;
+use crate::{LogService, SystemPowerControl, http, redfish};
+
+/// Id of the manager log service which keeps emitted events.
+pub const EVENT_LOG_ID: &str = "EventLog";
+
+/// Number of emitted events kept in the event log.
+const EVENT_LOG_CAPACITY: usize = 1000;
+
+pub fn resource<'a>() -> redfish::Resource<'a> {
+    redfish::Resource {
+        odata_id: Cow::Borrowed("/redfish/v1/EventService"),
+        odata_type: Cow::Borrowed("#EventService.v1_10_0.EventService"),
+        id: Cow::Borrowed("EventService"),
+        name: Cow::Borrowed("Event Service"),
+    }
+}
+
+pub fn subscriptions_collection() -> redfish::Collection<'static> {
+    redfish::Collection {
+        odata_id: Cow::Borrowed("/redfish/v1/EventService/Subscriptions"),
+        odata_type: Cow::Borrowed("#EventDestinationCollection.EventDestinationCollection"),
+        name: Cow::Borrowed("Event Subscriptions Collection"),
+    }
+}
+
+pub fn subscription_resource(subscription_id: &str) -> redfish::Resource<'_> {
+    let odata_id = format!("{}/{subscription_id}", subscriptions_collection().odata_id);
+    redfish::Resource {
+        odata_id: Cow::Owned(odata_id),
+        odata_type: Cow::Borrowed("#EventDestination.v1_13_0.EventDestination"),
+        id: Cow::Borrowed(subscription_id),
+        name: Cow::Borrowed("Event Subscription"),
+    }
+}
+
+pub fn sse_uri() -> String {
+    format!("{}/SSE", resource().odata_id)
+}
+
+pub fn submit_test_event_target() -> String {
+    format!(
+        "{}/Actions/EventService.SubmitTestEvent",
+        resource().odata_id
+    )
+}
+
+pub fn add_routes(r: Router<BmcState>) -> Router<BmcState> {
+    const SUBSCRIPTION_ID: &str = "{subscription_id}";
+    r.route(&resource().odata_id, get(get_event_service))
+        .route(
+            &subscriptions_collection().odata_id,
+            get(get_subscriptions).post(post_subscription),
+        )
+        .route(
+            &subscription_resource(SUBSCRIPTION_ID).odata_id,
+            get(get_subscription).delete(delete_subscription),
+        )
+        .route(&sse_uri(), get(get_sse))
+        .route(&submit_test_event_target(), post(post_submit_test_event))
+}
+
+pub fn builder(resource: &redfish::Resource) -> EventServiceBuilder {
+    EventServiceBuilder {
+        value: resource.json_patch(),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Warning => "Warning",
+            Self::Critical => "Critical",
+        }
+    }
+}
+
+/// Single record of the Redfish Event delivered to subscribers.
+#[derive(Clone, Debug)]
+pub struct EventRecord {
+    pub message_id: Cow<'static, str>,
+    pub message: String,
+    pub message_args: Vec<String>,
+    pub severity: Severity,
+    pub origin_of_condition: Option<String>,
+}
+
+impl EventRecord {
+    pub fn new(message_id: &'static str, severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            message_id: Cow::Borrowed(message_id),
+            message: message.into(),
+            message_args: vec![],
+            severity,
+            origin_of_condition: None,
+        }
+    }
+
+    pub fn origin_of_condition(self, odata_id: impl Into<String>) -> Self {
+        Self {
+            origin_of_condition: Some(odata_id.into()),
+            ..self
+        }
+    }
+
+    /// Event emitted when a reset of the system is accepted.
+    pub fn system_reset(system_id: &str, reset_type: SystemPowerControl) -> Self {
+        type C = SystemPowerControl;
+        let message_id = match reset_type {
+            C::On | C::ForceOn | C::PushPowerButton | C::Resume => {
+                "ResourceEvent.1.3.ResourcePoweringOn"
+            }
+            _ => "ResourceEvent.1.3.ResourcePoweringOff",
+        };
+        Self {
+            message_args: vec![system_id.to_string()],
+            ..Self::new(
+                message_id,
+                Severity::Ok,
+                format!("System {system_id} is being reset with {reset_type:?}"),
+            )
+        }
+        .origin_of_condition(redfish::computer_system::resource(system_id).odata_id)
+    }
+
+    fn to_json(
+        &self,
+        event_id: u64,
+        timestamp: DateTime<Utc>,
+        log_entry: Option<&str>,
+    ) -> serde_json::Value {
+        json!({
+            "@odata.id": format!("{}/Events/{event_id}#/Events/0", resource().odata_id),
+            "EventId": event_id.to_string(),
+            "EventType": "Alert",
+            "EventTimestamp": timestamp.to_rfc3339(),
+            "MemberId": "0",
+            "Message": self.message,
+            "MessageArgs": self.message_args,
+            "MessageId": self.message_id,
+            "MessageSeverity": self.severity.as_str(),
+            "Severity": self.severity.as_str(),
+        })
+        .patch(
+            self.origin_of_condition
+                .as_ref()
+                .map(|odata_id| json!({"OriginOfCondition": {"@odata.id": odata_id}}))
+                .unwrap_or_else(|| json!({})),
+        )
+        .patch(
+            log_entry
+                .map(|odata_id| json!({"LogEntry": {"@odata.id": odata_id}}))
+                .unwrap_or_else(|| json!({})),
+        )
+    }
+}
+
+struct LoggedEvent {
+    id: u64,
+    created: DateTime<Utc>,
+    record: EventRecord,
+}
+
+/// Events are delivered only to clients connected to the SSE stream;
+/// subscriptions are stored and listed but nothing is pushed to their
+/// destinations. The last events are also kept as entries of the
+/// `EventLog` log service of the BMC manager.
+pub struct EventServiceState {
+    sender: broadcast::Sender<serde_json::Value>,
+    next_event_id: AtomicU64,
+    next_subscription_id: AtomicU64,
+    subscriptions: Mutex<BTreeMap<u64, serde_json::Value>>,
+    log_manager_id: Option<&'static str>,
+    log: Mutex<VecDeque<LoggedEvent>>,
+}
+
+impl EventServiceState {
+    pub fn new(log_manager_id: Option<&'static str>) -> Self {
+        Self {
+            sender: broadcast::channel(256).0,
+            next_event_id: AtomicU64::new(1),
+            next_subscription_id: AtomicU64::new(1),
+            subscriptions: Mutex::default(),
+            log_manager_id,
+            log: Mutex::default(),
+        }
+    }
+
+    /// Manager which exposes the event log.
+    pub fn log_manager_id(&self) -> Option<&str> {
+        self.log_manager_id
+    }
+
+    pub fn emit(&self, record: EventRecord) {
+        let event_id = self.next_event_id.fetch_add(1, Ordering::Relaxed);
+        let created = Utc::now();
+        let log_entry = self.log_manager_id.map(|manager_id| {
+            let entries =
+                redfish::log_service::manager_entries_collection(manager_id, EVENT_LOG_ID);
+            format!("{}/{event_id}", entries.odata_id)
+        });
+        tracing::debug!(
+            event_id,
+            message_id = %record.message_id,
+            "Emitting Redfish event: {}",
+            record.message
+        );
+        let event = json!({
+            "@odata.type": "#Event.v1_7_0.Event",
+            "Id": event_id.to_string(),
+            "Name": "Event Array",
+            "Context": "bmc-mock",
+            "Events": [record.to_json(event_id, created, log_entry.as_deref())],
+        });
+        // Error only means that nobody listens to the stream right now.
+        let _ = self.sender.send(event);
+        if log_entry.is_some() {
+            let mut log = self.log.lock().unwrap();
+            if log.len() == EVENT_LOG_CAPACITY {
+                log.pop_front();
+            }
+            log.push_back(LoggedEvent {
+                id: event_id,
+                created,
+                record,
+            });
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<serde_json::Value> {
+        self.sender.subscribe()
+    }
+}
+
+impl LogService for EventServiceState {
+    fn id(&self) -> &str {
+        EVENT_LOG_ID
+    }
+
+    fn entries(&self, collection: &redfish::Collection<'_>) -> Vec<serde_json::Value> {
+        self.log
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|logged| {
+                redfish::log_service::event_entry(collection, &logged.id.to_string())
+                    .message(&logged.record.message)
+                    .message_id(&logged.record.message_id)
+                    .severity(logged.record.severity.as_str())
+                    .created(&logged.created.to_rfc3339())
+                    .maybe_with(
+                        EntryBuilder::origin_of_condition,
+                        &logged.record.origin_of_condition,
+                    )
+                    .build()
+            })
+            .collect()
+    }
+}
+
+async fn get_event_service() -> Response {
+    builder(&resource())
+        .service_enabled(true)
+        .server_sent_event_uri(&sse_uri())
+        .subscriptions(&subscriptions_collection())
+        .submit_test_event_target(&submit_test_event_target())
+        .build()
+        .into_ok_response()
+}
+
+async fn get_subscriptions(State(state): State<BmcState>) -> Response {
+    let members = state
+        .event_service_state
+        .subscriptions
+        .lock()
+        .unwrap()
+        .keys()
+        .map(|id| subscription_resource(&id.to_string()).entity_ref())
+        .collect::<Vec<_>>();
+    subscriptions_collection()
+        .with_members(&members)
+        .into_ok_response()
+}
+
+async fn post_subscription(
+    State(state): State<BmcState>,
+    Json(request): Json<serde_json::Value>,
+) -> Response {
+    let Some(destination) = request.get("Destination").and_then(|v| v.as_str()) else {
+        return json!("Destination is expected field in subscription")
+            .into_response(StatusCode::BAD_REQUEST);
+    };
+    let event_service = &state.event_service_state;
+    let id = event_service
+        .next_subscription_id
+        .fetch_add(1, Ordering::Relaxed);
+    let id_str = id.to_string();
+    let resource = subscription_resource(&id_str);
+    let subscription = resource.json_patch().patch(json!({
+        "Destination": destination,
+        "Context": request.get("Context").cloned().unwrap_or_else(|| json!("")),
+        "Protocol": request.get("Protocol").cloned().unwrap_or_else(|| json!("Redfish")),
+        "EventFormatType": "Event",
+        "SubscriptionType": "RedfishEvent",
+    }));
+    let location = HeaderValue::from_str(&resource.odata_id).unwrap();
+    event_service
+        .subscriptions
+        .lock()
+        .unwrap()
+        .insert(id, subscription.clone());
+    let mut response = subscription.into_response(StatusCode::CREATED);
+    response.headers_mut().insert("Location", location);
+    response
+}
+
+async fn get_subscription(
+    State(state): State<BmcState>,
+    Path(subscription_id): Path<u64>,
+) -> Response {
+    state
+        .event_service_state
+        .subscriptions
+        .lock()
+        .unwrap()
+        .get(&subscription_id)
+        .map(|subscription| subscription.clone().into_ok_response())
+        .unwrap_or_else(http::not_found)
+}
+
+async fn delete_subscription(
+    State(state): State<BmcState>,
+    Path(subscription_id): Path<u64>,
+) -> Response {
+    state
+        .event_service_state
+        .subscriptions
+        .lock()
+        .unwrap()
+        .remove(&subscription_id)
+        .map(|_| http::ok_no_content())
+        .unwrap_or_else(http::not_found)
+}
+
+async fn post_submit_test_event(
+    State(state): State<BmcState>,
+    Json(request): Json<serde_json::Value>,
+) -> Response {
+    let field = |name: &str| request.get(name).and_then(|v| v.as_str());
+    let severity = match field("MessageSeverity").or(field("Severity")) {
+        Some("Critical") => Severity::Critical,
+        Some("Warning") => Severity::Warning,
+        _ => Severity::Ok,
+    };
+    let record = EventRecord {
+        message_id: Cow::Owned(
+            field("MessageId")
+                .unwrap_or("TestEvent.1.0.TestEvent")
+                .to_string(),
+        ),
+        message: field("Message").unwrap_or("Test event").to_string(),
+        message_args: vec![],
+        severity,
+        origin_of_condition: field("OriginOfCondition").map(str::to_string),
+    };
+    state.event_service_state.emit(record);
+    http::ok_no_content()
+}
+
+async fn get_sse(State(state): State<BmcState>) -> Response {
+    let receiver = state.event_service_state.subscribe();
+    let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    let id = event["Id"].as_str().unwrap_or_default().to_string();
+                    let sse = SseEvent::default().id(id).data(event.to_string());
+                    return Some((Ok::<_, Infallible>(sse), receiver));
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "SSE client is too slow, events were dropped");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+pub struct EventServiceBuilder {
+    value: serde_json::Value,
+}
+
+impl Builder for EventServiceBuilder {
+    fn apply_patch(self, patch: serde_json::Value) -> Self {
+        Self {
+            value: self.value.patch(patch),
+        }
+    }
+}
+
+impl EventServiceBuilder {
+    pub fn build(self) -> serde_json::Value {
+        self.value
+    }
+
+    pub fn service_enabled(self, v: bool) -> Self {
+        self.apply_patch(json!({ "ServiceEnabled": v }))
+    }
+
+    pub fn server_sent_event_uri(self, v: &str) -> Self {
+        self.add_str_field("ServerSentEventUri", v)
+    }
+
+    pub fn subscriptions(self, v: &redfish::Collection<'_>) -> Self {
+        self.apply_patch(v.nav_property("Subscriptions"))
+    }
+
+    pub fn submit_test_event_target(self, v: &str) -> Self {
+        self.apply_patch(json!({
+            "Actions": {
+                "#EventService.SubmitTestEvent": {
+                    "target": v
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::body::Body;
+    use axum::http::{Method, Request};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        Callbacks, HostHardwareType, HostMachineInfo, MachineInfo, MockPowerState,
+        SetSystemPowerError, machine_router,
+    };
+
+    #[derive(Debug)]
+    struct PoweredOn;
+
+    impl Callbacks for PoweredOn {
+        fn get_power_state(&self) -> MockPowerState {
+            MockPowerState::On
+        }
+
+        fn send_power_command(&self, _: SystemPowerControl) -> Result<(), SetSystemPowerError> {
+            Ok(())
+        }
+
+        fn state_refresh_indication(&self) {}
+    }
+
+    fn dell_router() -> (Router, BmcState) {
+        machine_router(
+            MachineInfo::Host(HostMachineInfo::new(
+                HostHardwareType::DellPowerEdgeR750,
+                vec![],
+            )),
+            Arc::new(PoweredOn),
+            "test-host-id".to_string(),
+            false,
+        )
+    }
+
+    async fn call(
+        router: &Router,
+        method: Method,
+        uri: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn subscriptions_can_be_created_and_deleted() {
+        let (router, _) = dell_router();
+        let subscriptions = subscriptions_collection().odata_id.to_string();
+
+        let (status, created) = call(
+            &router,
+            Method::POST,
+            &subscriptions,
+            json!({"Destination": "https://collector.local/events", "Protocol": "Redfish"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let odata_id = created["@odata.id"].as_str().unwrap().to_string();
+
+        let (_, collection) = call(&router, Method::GET, &subscriptions, json!({})).await;
+        assert_eq!(collection["Members@odata.count"], 1);
+
+        let (status, _) = call(&router, Method::DELETE, &odata_id, json!({})).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&router, Method::GET, &odata_id, json!({})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn power_and_firmware_failures_emit_events() {
+        let (router, state) = dell_router();
+        let mut events = state.event_service_state.subscribe();
+
+        let reset = redfish::computer_system::reset_target("System.Embedded.1");
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            &reset,
+            json!({"ResetType": "ForceRestart"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let event = events.recv().await.unwrap();
+        assert_eq!(
+            event["Events"][0]["MessageId"],
+            "ResourceEvent.1.3.ResourcePoweringOff"
+        );
+        assert_eq!(
+            event["Events"][0]["OriginOfCondition"]["@odata.id"],
+            "/redfish/v1/Systems/System.Embedded.1"
+        );
+
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            "/InjectedBugs",
+            json!({"firmware_update_failure": true}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let event = events.recv().await.unwrap();
+        assert_eq!(event["Events"][0]["MessageSeverity"], "Warning");
+
+        call(
+            &router,
+            Method::POST,
+            &redfish::update_service::simple_update_target(),
+            json!({"ImageURI": "http://images.local/fw.bin"}),
+        )
+        .await;
+        let event = events.recv().await.unwrap();
+        assert_eq!(event["Events"][0]["MessageId"], "Update.1.0.ApplyFailed");
+        assert_eq!(event["Events"][0]["MessageSeverity"], "Critical");
+    }
+
+    #[tokio::test]
+    async fn emitted_events_are_kept_in_manager_event_log() {
+        let (router, state) = dell_router();
+        let mut events = state.event_service_state.subscribe();
+        let manager_id = state.event_service_state.log_manager_id().unwrap();
+
+        call(
+            &router,
+            Method::POST,
+            &redfish::computer_system::reset_target("System.Embedded.1"),
+            json!({"ResetType": "ForceRestart"}),
+        )
+        .await;
+        call(
+            &router,
+            Method::POST,
+            "/InjectedBugs",
+            json!({"firmware_update_failure": true}),
+        )
+        .await;
+        call(
+            &router,
+            Method::POST,
+            &redfish::update_service::simple_update_target(),
+            json!({"ImageURI": "http://images.local/fw.bin"}),
+        )
+        .await;
+
+        let (status, services) = call(
+            &router,
+            Method::GET,
+            &redfish::log_service::manager_collection(manager_id).odata_id,
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            services["Members"][0]["@odata.id"],
+            redfish::log_service::manager_resource(manager_id, EVENT_LOG_ID)
+                .odata_id
+                .as_ref()
+        );
+
+        let entries = redfish::log_service::manager_entries_collection(manager_id, EVENT_LOG_ID);
+        let (status, entries) = call(&router, Method::GET, &entries.odata_id, json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        let members = entries["Members"].as_array().unwrap();
+        assert_eq!(
+            members
+                .iter()
+                .map(|entry| entry["MessageId"].as_str().unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                "ResourceEvent.1.3.ResourcePoweringOff",
+                "ResourceEvent.1.0.ResourceErrorsDetected",
+                "Update.1.0.ApplyFailed",
+            ]
+        );
+        assert_eq!(members[2]["Severity"], "Critical");
+        assert_eq!(
+            members[0]["Links"]["OriginOfCondition"]["@odata.id"],
+            "/redfish/v1/Systems/System.Embedded.1"
+        );
+
+        let event = events.recv().await.unwrap();
+        assert_eq!(
+            event["Events"][0]["LogEntry"]["@odata.id"],
+            members[0]["@odata.id"]
+        );
+    }
+}
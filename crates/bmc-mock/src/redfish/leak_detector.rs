@@ -17,6 +17,7 @@
 
 use std::borrow::Cow;
 
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::json::{JsonExt, JsonPatch};
@@ -64,7 +65,7 @@ impl LeakDetector {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub enum DetectorState {
     Ok,
     Warning,
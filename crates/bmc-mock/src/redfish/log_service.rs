@@ -17,6 +17,8 @@
 
 use std::borrow::Cow;
 
+use serde_json::json;
+
 use crate::json::{JsonExt, JsonPatch};
 use crate::redfish;
 use crate::redfish::Builder;
@@ -30,6 +32,28 @@ pub fn manager_collection(manager_id: &str) -> redfish::Collection<'static> {
     }
 }
 
+pub fn manager_resource<'a>(manager_id: &str, service_id: &'a str) -> redfish::Resource<'a> {
+    let odata_id = format!("/redfish/v1/Managers/{manager_id}/LogServices/{service_id}");
+    redfish::Resource {
+        odata_id: Cow::Owned(odata_id),
+        odata_type: Cow::Borrowed("#LogService.v1_2_0.LogService"),
+        name: Cow::Borrowed("Log Service"),
+        id: Cow::Borrowed(service_id),
+    }
+}
+
+pub fn manager_entries_collection<'a>(
+    manager_id: &str,
+    service_id: &'a str,
+) -> redfish::Collection<'a> {
+    let odata_id = format!("/redfish/v1/Managers/{manager_id}/LogServices/{service_id}/Entries");
+    redfish::Collection {
+        odata_id: Cow::Owned(odata_id),
+        odata_type: Cow::Borrowed("#LogEntryCollection.LogEntryCollection"),
+        name: Cow::Borrowed("Log Entries"),
+    }
+}
+
 pub fn system_collection(system_id: &str) -> redfish::Collection<'static> {
     let odata_id = format!("/redfish/v1/Systems/{system_id}/LogServices");
     redfish::Collection {
@@ -124,10 +148,18 @@ impl EntryBuilder {
         self.add_str_field("Message", v)
     }
 
+    pub fn message_id(self, v: &str) -> Self {
+        self.add_str_field("MessageId", v)
+    }
+
     pub fn severity(self, v: &str) -> Self {
         self.add_str_field("Severity", v)
     }
 
+    pub fn origin_of_condition(self, odata_id: &str) -> Self {
+        self.apply_patch(json!({"Links": {"OriginOfCondition": {"@odata.id": odata_id}}}))
+    }
+
     pub fn created(self, v: &str) -> Self {
         self.add_str_field("Created", v)
     }
@@ -19,7 +19,6 @@ use std::borrow::Cow;
 use std::sync::{Arc, atomic};
 
 use axum::extract::{Path, State};
-use axum::http::StatusCode;
 use axum::response::Response;
 use axum::routing::{get, post};
 use axum::{Json, Router};
@@ -29,7 +28,8 @@ use serde_json::json;
 use crate::bmc_state::BmcState;
 use crate::json::{JsonExt, JsonPatch};
 use crate::redfish::Builder;
-use crate::{http, redfish};
+use crate::redfish::event_service::{EVENT_LOG_ID, EventServiceState};
+use crate::{LogService, http, redfish};
 
 pub fn collection() -> redfish::Collection<'static> {
     redfish::Collection {
@@ -158,6 +158,7 @@ impl ManagerBuilder {
 
 pub fn add_routes(r: Router<BmcState>) -> Router<BmcState> {
     const MGR_ID: &str = "{manager_id}";
+    const LOG_SERVICE_ID: &str = "{log_service_id}";
     const ETH_ID: &str = "{ethernet_id}";
     const HOST_IF_ID: &str = "{hostif_id}";
     r.route(&collection().odata_id, get(get_manager_collection))
@@ -187,6 +188,14 @@ pub fn add_routes(r: Router<BmcState>) -> Router<BmcState> {
             &redfish::log_service::manager_collection(MGR_ID).odata_id,
             get(get_log_services),
         )
+        .route(
+            &redfish::log_service::manager_resource(MGR_ID, LOG_SERVICE_ID).odata_id,
+            get(get_log_service),
+        )
+        .route(
+            &redfish::log_service::manager_entries_collection(MGR_ID, LOG_SERVICE_ID).odata_id,
+            get(get_log_service_entries),
+        )
 }
 
 #[derive(Clone, Copy)]
@@ -440,10 +449,69 @@ async fn post_reset_manager(
         .unwrap_or_else(http::not_found)
 }
 
-async fn get_log_services() -> Response {
-    not_implemented()
+async fn get_log_services(
+    State(state): State<BmcState>,
+    Path(manager_id): Path<String>,
+) -> Response {
+    if state.manager.find(&manager_id).is_none() {
+        return http::not_found();
+    }
+    let members = event_log(&state, &manager_id, EVENT_LOG_ID)
+        .map(|log_service| {
+            redfish::log_service::manager_resource(&manager_id, log_service.id()).entity_ref()
+        })
+        .into_iter()
+        .collect::<Vec<_>>();
+    redfish::log_service::manager_collection(&manager_id)
+        .with_members(&members)
+        .into_ok_response()
+}
+
+async fn get_log_service(
+    State(state): State<BmcState>,
+    Path((manager_id, log_service_id)): Path<(String, String)>,
+) -> Response {
+    event_log(&state, &manager_id, &log_service_id)
+        .map(|_| {
+            redfish::log_service::builder(&redfish::log_service::manager_resource(
+                &manager_id,
+                &log_service_id,
+            ))
+            .entries(&redfish::log_service::manager_entries_collection(
+                &manager_id,
+                &log_service_id,
+            ))
+            .build()
+            .into_ok_response()
+        })
+        .unwrap_or_else(http::not_found)
+}
+
+async fn get_log_service_entries(
+    State(state): State<BmcState>,
+    Path((manager_id, log_service_id)): Path<(String, String)>,
+) -> Response {
+    event_log(&state, &manager_id, &log_service_id)
+        .map(|log_service| {
+            let collection =
+                redfish::log_service::manager_entries_collection(&manager_id, &log_service_id);
+            let members = log_service.entries(&collection);
+            collection
+                .with_members(&members)
+                .patch(json!({"Description": "Log services collection"})) // Required by libredfish
+                .into_ok_response()
+        })
+        .unwrap_or_else(http::not_found)
 }
 
-fn not_implemented() -> Response {
-    json!("").into_response(StatusCode::NOT_IMPLEMENTED)
+/// Events emitted by the BMC are logged only by the manager which owns
+/// the event log.
+fn event_log<'a>(
+    state: &'a BmcState,
+    manager_id: &str,
+    log_service_id: &str,
+) -> Option<&'a EventServiceState> {
+    let event_service = state.event_service_state.as_ref();
+    (event_service.log_manager_id() == Some(manager_id) && log_service_id == EVENT_LOG_ID)
+        .then_some(event_service)
 }
@@ -23,6 +23,7 @@ pub mod chassis;
 pub mod collection;
 pub mod computer_system;
 pub mod ethernet_interface;
+pub mod event_service;
 pub mod host_interface;
 pub mod leak_detector;
 pub mod log_service;
@@ -20,8 +20,8 @@ use std::borrow::Cow;
 use serde_json::json;
 
 use crate::json::{JsonExt, JsonPatch};
-use crate::redfish;
 use crate::redfish::Builder;
+use crate::{redfish, simulation};
 
 pub fn resource<'a>(chassis_id: &str, supply_id: &'a str) -> redfish::Resource<'a> {
     let odata_id = format!(
@@ -57,6 +57,16 @@ impl PowerSupply {
     pub fn to_json(&self) -> serde_json::Value {
         self.value.clone()
     }
+
+    pub fn to_simulated_json(&self, snapshot: &simulation::Snapshot) -> serde_json::Value {
+        if snapshot.power_supply_failed(&self.id) {
+            self.value.clone().patch(json!({
+                "Status": {"State": "UnavailableOffline", "Health": "Critical"},
+            }))
+        } else {
+            self.to_json()
+        }
+    }
 }
 
 pub fn builder(resource: &redfish::Resource) -> PowerSupplyBuilder {
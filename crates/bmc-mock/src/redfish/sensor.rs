@@ -21,8 +21,8 @@ use rand::{Rng, RngExt};
 use serde_json::json;
 
 use crate::json::{JsonExt, JsonPatch};
-use crate::redfish;
 use crate::redfish::Builder;
+use crate::{redfish, simulation};
 
 pub fn chassis_collection(chassis_id: &str) -> redfish::Collection<'static> {
     let odata_id = format!("/redfish/v1/Chassis/{chassis_id}/Sensors");
@@ -75,6 +75,17 @@ pub struct Sensor {
 
 impl Sensor {
     pub fn to_json(&self) -> serde_json::Value {
+        let mut rng = rand::rng();
+        self.with_reading(|kind| kind.random_reading(&mut rng))
+    }
+
+    /// Same as `to_json`, but the reading comes from the simulated machine
+    /// state instead of being random.
+    pub fn to_simulated_json(&self, snapshot: &simulation::Snapshot) -> serde_json::Value {
+        self.with_reading(|kind| kind.simulated_reading(&self.id, snapshot))
+    }
+
+    fn with_reading(&self, reading: impl FnOnce(SensorKind) -> SensorReading) -> serde_json::Value {
         let Some(reading_type) = self
             .value
             .get("ReadingType")
@@ -86,15 +97,12 @@ impl Sensor {
             return self.value.clone();
         };
 
-        let mut rng = rand::rng();
-
-        let reading = kind.random_reading(&mut rng);
         let refreshed_builder = SensorBuilder {
             id: self.id.clone(),
             value: self
                 .value
                 .clone()
-                .patch(json!({ "Reading": reading.into_json() })),
+                .patch(json!({ "Reading": reading(kind).into_json() })),
         };
 
         refreshed_builder.build().value
@@ -403,6 +411,16 @@ impl SensorKind {
         }
     }
 
+    fn simulated_reading(self, sensor_id: &str, snapshot: &simulation::Snapshot) -> SensorReading {
+        match self {
+            Self::Temperature => SensorReading::Float(tenths(snapshot.temperature())),
+            Self::Fan => SensorReading::Unsigned(snapshot.fan_rpm(sensor_id)),
+            Self::Power => SensorReading::Float(tenths(snapshot.power())),
+            Self::Current => SensorReading::Float(tenths(snapshot.current())),
+            Self::Voltage => SensorReading::Float(tenths(snapshot.voltage())),
+        }
+    }
+
     fn default_thresholds(self) -> Thresholds {
         match self {
             Self::Temperature => Thresholds {
@@ -498,7 +516,10 @@ fn append_sensors(
 }
 
 fn random_tenths(rng: &mut impl Rng, range: std::ops::RangeInclusive<f64>) -> f64 {
-    let value = rng.random_range(range);
+    tenths(rng.random_range(range))
+}
+
+fn tenths(value: f64) -> f64 {
     (value * 10.0).round() / 10.0
 }
 
@@ -506,7 +527,10 @@ fn random_tenths(rng: &mut impl Rng, range: std::ops::RangeInclusive<f64>) -> f6
 mod tests {
     use std::collections::HashMap;
 
+    use serde_json::json;
+
     use super::{Layout, Thresholds, builder, chassis_resource, generate_chassis_sensors};
+    use crate::simulation::Simulation;
 
     #[test]
     fn generated_sensors_follow_layout_and_ranges() {
@@ -605,4 +629,37 @@ mod tests {
         assert_eq!(json["Status"]["Health"], "Critical");
         assert_eq!(json["Thresholds"]["UpperFatal"]["Reading"], 44.0);
     }
+
+    #[test]
+    fn simulated_readings_follow_machine_state() {
+        let sensors = generate_chassis_sensors(
+            "System.Embedded.1",
+            Layout {
+                temperature: 1,
+                fan: 2,
+                ..Default::default()
+            },
+        );
+        let simulation = Simulation::default();
+        simulation
+            .update(json!({
+                "enabled": true,
+                "load": 1.0,
+                "fan_degradation": [{"sensor_id": "Fan_1", "rate": 1.0}],
+            }))
+            .unwrap();
+        simulation.update(json!({"advance": "30m"})).unwrap();
+        let snapshot = simulation.snapshot(true).unwrap();
+
+        let readings = sensors
+            .iter()
+            .map(|sensor| (sensor.id.to_string(), sensor.to_simulated_json(&snapshot)))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(readings["Temp_1"]["Reading"], 46.0);
+        assert_eq!(readings["Temp_1"]["Status"]["Health"], "Critical");
+        assert_eq!(readings["Fan_1"]["Reading"], 0);
+        assert_eq!(readings["Fan_1"]["Status"]["Health"], "Critical");
+        assert_eq!(readings["Fan_2"]["Reading"], 9000);
+        assert_eq!(readings["Fan_2"]["Status"]["Health"], "OK");
+    }
 }
@@ -16,6 +16,7 @@
  */
 
 use axum::Router;
+use axum::extract::State;
 use axum::response::Response;
 use axum::routing::get;
 use serde_json::json;
@@ -27,7 +28,12 @@ pub fn add_routes(r: Router<BmcState>) -> Router<BmcState> {
     r.route("/redfish/v1/TaskService/Tasks/{task_id}", get(get_task))
 }
 
-async fn get_task() -> Response {
+async fn get_task(State(state): State<BmcState>) -> Response {
+    let (task_state, task_status) = if state.injected_bugs.firmware_update_failure() {
+        ("Exception", "Critical")
+    } else {
+        ("Completed", "OK")
+    };
     json!({
         "@odata.id": "/redfish/v1/TaskService/Tasks/0",
         "@odata.type": "#Task.v1_4_3.Task",
@@ -35,8 +41,8 @@ async fn get_task() -> Response {
         "PercentComplete": 100,
         "StartTime": "2024-01-30T09:00:52+00:00",
         "TaskMonitor": "/redfish/v1/TaskService/Tasks/0/Monitor",
-        "TaskState": "Completed",
-        "TaskStatus": "OK"
+        "TaskState": task_state,
+        "TaskStatus": task_status
     })
     .into_ok_response()
 }
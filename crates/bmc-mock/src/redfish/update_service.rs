@@ -25,6 +25,7 @@ use axum::routing::{get, post};
 use crate::bmc_state::BmcState;
 use crate::json::{JsonExt, JsonPatch};
 use crate::redfish::Builder;
+use crate::redfish::event_service::{EventRecord, Severity};
 use crate::{http, redfish};
 
 pub fn resource<'a>() -> redfish::Resource<'a> {
@@ -90,7 +91,17 @@ async fn get_update_service() -> Response {
         .into_ok_response()
 }
 
-async fn update_firmware_simple_update() -> Response {
+async fn update_firmware_simple_update(State(state): State<BmcState>) -> Response {
+    if state.injected_bugs.firmware_update_failure() {
+        state.event_service_state.emit(
+            EventRecord::new(
+                "Update.1.0.ApplyFailed",
+                Severity::Critical,
+                "Installation of the firmware image failed",
+            )
+            .origin_of_condition(resource().odata_id),
+        );
+    }
     redfish::task_service::update_firmware_simple_update_task()
 }
 
@@ -0,0 +1,329 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Replay of Redfish exchanges recorded against a real BMC.
+//!
+//! A scenario is a JSON Lines file where every line is a
+//! [`RecordedExchange`]. carbide-bmc-proxy writes these files when
+//! recording is enabled, one file per BMC IP address.
+//!
+//! Replay is stateful: every mutating request (POST, PATCH, PUT,
+//! DELETE) that matches a recorded one moves the scenario forward, and
+//! subsequent GET requests are answered with the responses that were
+//! recorded after that mutation. This way a BIOS PATCH followed by a
+//! GET of the pending settings returns what the real BMC returned at
+//! that point of the recording.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderName, HeaderValue, Method, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use bmc_scenario::{RecordedExchange, is_mutating_method};
+use eyre::Context;
+
+#[derive(Clone, Debug, Default)]
+pub struct Scenario {
+    pub exchanges: Vec<RecordedExchange>,
+}
+
+impl Scenario {
+    /// Load a scenario from a JSON Lines file.
+    pub fn from_path(path: &Path) -> eyre::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err(format!("cannot read scenario file: {path:?}"))?;
+        Self::parse(&content).wrap_err(format!("cannot parse scenario file: {path:?}"))
+    }
+
+    pub fn parse(content: &str) -> eyre::Result<Self> {
+        let exchanges = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line).wrap_err(format!("invalid exchange at line {}", n + 1))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        Ok(Self { exchanges })
+    }
+
+    /// Load every `<ip>.jsonl` file in a directory, keyed by the IP
+    /// address in the file name.
+    pub fn load_dir(dir: &Path) -> eyre::Result<HashMap<String, Self>> {
+        let mut result = HashMap::new();
+        for entry in
+            std::fs::read_dir(dir).wrap_err(format!("cannot read scenario dir: {dir:?}"))?
+        {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "jsonl") {
+                continue;
+            }
+            let Some(ip) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            result.insert(ip.to_string(), Self::from_path(&path)?);
+        }
+        Ok(result)
+    }
+}
+
+/// Create a router that replays a recorded scenario.
+pub fn scenario_router(scenario: Scenario) -> Router {
+    Router::new()
+        .fallback(replay)
+        .with_state(Arc::new(Mutex::new(ReplayState::new(scenario))))
+}
+
+struct ReplayState {
+    exchanges: Vec<RecordedExchange>,
+    /// Number of recorded mutations preceding each exchange.
+    epochs: Vec<usize>,
+    consumed: Vec<bool>,
+    epoch: usize,
+    /// How many times a (path, epoch) pair was already served by GET.
+    served: HashMap<(String, usize), usize>,
+}
+
+impl ReplayState {
+    fn new(scenario: Scenario) -> Self {
+        let mut epoch = 0;
+        let epochs = scenario
+            .exchanges
+            .iter()
+            .map(|exchange| {
+                let current = epoch;
+                if exchange.is_mutation() {
+                    epoch += 1;
+                }
+                current
+            })
+            .collect();
+        Self {
+            consumed: vec![false; scenario.exchanges.len()],
+            exchanges: scenario.exchanges,
+            epochs,
+            epoch: 0,
+            served: HashMap::new(),
+        }
+    }
+
+    fn find(&mut self, method: &Method, path: &str) -> Option<&RecordedExchange> {
+        let matching = |exchange: &RecordedExchange| {
+            exchange.method.eq_ignore_ascii_case(method.as_str())
+                && normalize_path(&exchange.path) == path
+        };
+        let candidates = (0..self.exchanges.len())
+            .filter(|&i| matching(&self.exchanges[i]))
+            .collect::<Vec<_>>();
+        let last = *candidates.last()?;
+
+        if is_mutating_method(method.as_str()) {
+            // Prefer the next mutation of the recording, but accept
+            // mutations that were issued in a different order.
+            let next = candidates
+                .iter()
+                .copied()
+                .filter(|&i| !self.consumed[i])
+                .min_by_key(|&i| (self.epochs[i] < self.epoch, i));
+            return Some(match next {
+                Some(i) => {
+                    self.consumed[i] = true;
+                    self.epoch = self.epochs[i] + 1;
+                    &self.exchanges[i]
+                }
+                // Everything was replayed already; repeat the last answer.
+                None => &self.exchanges[last],
+            });
+        }
+
+        let current = candidates
+            .iter()
+            .copied()
+            .filter(|&i| self.epochs[i] == self.epoch)
+            .collect::<Vec<_>>();
+        let index = if current.is_empty() {
+            candidates
+                .iter()
+                .copied()
+                .rfind(|&i| self.epochs[i] < self.epoch)
+                .unwrap_or(candidates[0])
+        } else {
+            let served = self
+                .served
+                .entry((path.to_string(), self.epoch))
+                .or_default();
+            let index = current[(*served).min(current.len() - 1)];
+            *served += 1;
+            index
+        };
+        Some(&self.exchanges[index])
+    }
+}
+
+fn normalize_path(path: &str) -> &str {
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+    let trimmed = path.trim_end_matches('/');
+    if query.is_empty() && !trimmed.is_empty() {
+        trimmed
+    } else {
+        path
+    }
+}
+
+async fn replay(State(state): State<Arc<Mutex<ReplayState>>>, request: Request<Body>) -> Response {
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str())
+        .unwrap_or("/");
+    let path = normalize_path(path);
+    match state.lock().unwrap().find(request.method(), path) {
+        Some(exchange) => recorded_response(exchange),
+        None => {
+            tracing::warn!(
+                "scenario: no recorded exchange for {} {path}",
+                request.method()
+            );
+            (
+                StatusCode::NOT_FOUND,
+                format!("No recorded exchange for {} {path}", request.method()),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn recorded_response(exchange: &RecordedExchange) -> Response {
+    let status = StatusCode::from_u16(exchange.status).unwrap_or(StatusCode::OK);
+    let body = match (&exchange.response_body, &exchange.response_text) {
+        (Some(value), _) => value.to_string(),
+        (None, Some(text)) => text.clone(),
+        (None, None) => String::new(),
+    };
+    let mut response = (status, body).into_response();
+    for (name, value) in &exchange.response_headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use http_body_util::BodyExt;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn exchange(method: &str, path: &str, body: serde_json::Value) -> RecordedExchange {
+        RecordedExchange {
+            method: method.to_string(),
+            path: path.to_string(),
+            status: 200,
+            response_body: Some(body),
+            ..Default::default()
+        }
+    }
+
+    async fn call(router: &Router, method: Method, path: &str) -> (StatusCode, String) {
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(path)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    #[test]
+    fn parse_json_lines() {
+        let scenario = Scenario::parse(concat!(
+            r#"{"method":"GET","path":"/redfish/v1","status":200,"response_body":{"Id":"1"}}"#,
+            "\n\n",
+            r#"{"method":"POST","path":"/redfish/v1/Actions","status":204}"#,
+            "\n",
+        ))
+        .unwrap();
+        assert_eq!(scenario.exchanges.len(), 2);
+        assert!(scenario.exchanges[1].is_mutation());
+        assert!(Scenario::parse("{not json}").is_err());
+    }
+
+    #[tokio::test]
+    async fn replay_follows_mutations() {
+        let bios = "/redfish/v1/Systems/1/Bios";
+        let router = scenario_router(Scenario {
+            exchanges: vec![
+                exchange("GET", bios, json!({"v": 1})),
+                exchange("GET", bios, json!({"v": 2})),
+                exchange("PATCH", bios, json!({})),
+                exchange("GET", bios, json!({"v": 3})),
+            ],
+        });
+
+        // Repeated GETs step through the recording and stick at the last one.
+        assert_eq!(call(&router, Method::GET, bios).await.1, r#"{"v":1}"#);
+        assert_eq!(call(&router, Method::GET, bios).await.1, r#"{"v":2}"#);
+        assert_eq!(call(&router, Method::GET, bios).await.1, r#"{"v":2}"#);
+
+        assert_eq!(call(&router, Method::PATCH, bios).await.0, StatusCode::OK);
+        assert_eq!(
+            call(&router, Method::GET, "/redfish/v1/Systems/1/Bios/")
+                .await
+                .1,
+            r#"{"v":3}"#
+        );
+
+        let (status, _) = call(&router, Method::GET, "/redfish/v1/Chassis").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn replay_falls_back_to_earlier_epoch() {
+        let router = scenario_router(Scenario {
+            exchanges: vec![
+                exchange("GET", "/redfish/v1", json!({"root": true})),
+                exchange("POST", "/redfish/v1/Actions/Reset", json!({})),
+                exchange("GET", "/redfish/v1/Systems", json!({"after": true})),
+            ],
+        });
+
+        // Resource only recorded after the POST is still served before it.
+        let (_, body) = call(&router, Method::GET, "/redfish/v1/Systems").await;
+        assert_eq!(body, r#"{"after":true}"#);
+
+        call(&router, Method::POST, "/redfish/v1/Actions/Reset").await;
+        let (_, body) = call(&router, Method::GET, "/redfish/v1").await;
+        assert_eq!(body, r#"{"root":true}"#);
+    }
+}
@@ -0,0 +1,431 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Physics-lite simulation of chassis sensors, power supplies and leak
+//! detectors.
+//!
+//! The simulation is disabled by default and sensors return random
+//! readings. Once enabled through the `/Simulation` control endpoint,
+//! readings are derived from the machine power state, the configured
+//! load and scheduled faults. The simulation clock only moves when
+//! `advance` is posted (or with `realtime` set), which keeps integration
+//! tests deterministic.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use duration_str::deserialize_duration;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::redfish::leak_detector::DetectorState;
+
+/// Time constant of the temperature response to a new target.
+const THERMAL_TIME_CONSTANT: Duration = Duration::from_secs(60);
+const DEFAULT_AMBIENT_TEMPERATURE: f64 = 25.0;
+/// Temperature rise of a powered on idle machine.
+const IDLE_TEMPERATURE_RISE: f64 = 5.0;
+/// Additional temperature rise at full load.
+const LOAD_TEMPERATURE_RISE: f64 = 8.0;
+/// Temperature rise caused by each completely stopped fan.
+const FAN_LOSS_TEMPERATURE_RISE: f64 = 8.0;
+const STANDBY_FAN_RPM: f64 = 800.0;
+const IDLE_FAN_RPM: f64 = 3000.0;
+const MAX_FAN_RPM: f64 = 9000.0;
+const STANDBY_POWER: f64 = 10.0;
+const IDLE_POWER: f64 = 200.0;
+const MAX_POWER: f64 = 700.0;
+const RAIL_VOLTAGE: f64 = 12.0;
+const CORE_VOLTAGE: f64 = 1.8;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Args {
+    pub enabled: Option<bool>,
+    /// Advance the simulation clock together with the wall clock.
+    pub realtime: Option<bool>,
+    /// Move the simulation clock forward by this amount.
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub advance: Option<Duration>,
+    /// Machine load between 0.0 and 1.0.
+    pub load: Option<f64>,
+    pub ambient_temperature: Option<f64>,
+    pub fan_degradation: Option<Vec<FanDegradation>>,
+    pub power_supply_failures: Option<Vec<PowerSupplyFailure>>,
+    pub leak_detector_trips: Option<Vec<LeakDetectorTrip>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FanDegradation {
+    pub sensor_id: String,
+    /// Simulation time when the fan starts to degrade.
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub after: Option<Duration>,
+    /// Fraction of the nominal speed lost per second. 1.0 or more
+    /// stops the fan within a second.
+    pub rate: f64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PowerSupplyFailure {
+    pub power_supply_id: String,
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub after: Option<Duration>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LeakDetectorTrip {
+    pub leak_detector_id: String,
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub after: Option<Duration>,
+    /// Critical if not specified.
+    pub state: Option<DetectorState>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Config {
+    enabled: bool,
+    realtime: bool,
+    load: f64,
+    ambient_temperature: f64,
+    fan_degradation: Vec<FanDegradation>,
+    power_supply_failures: Vec<PowerSupplyFailure>,
+    leak_detector_trips: Vec<LeakDetectorTrip>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            realtime: false,
+            load: 0.0,
+            ambient_temperature: DEFAULT_AMBIENT_TEMPERATURE,
+            fan_degradation: vec![],
+            power_supply_failures: vec![],
+            leak_detector_trips: vec![],
+        }
+    }
+}
+
+struct Clock {
+    advanced: Duration,
+    realtime_since: Option<Instant>,
+}
+
+impl Clock {
+    fn now(&self) -> Duration {
+        self.advanced + self.realtime_since.map(|t| t.elapsed()).unwrap_or_default()
+    }
+
+    fn set_realtime(&mut self, realtime: bool) {
+        self.advanced = self.now();
+        self.realtime_since = realtime.then(Instant::now);
+    }
+}
+
+struct State {
+    config: Config,
+    clock: Clock,
+    /// Last computed temperature and the simulation time it was computed at.
+    temperature: Option<(f64, Duration)>,
+    /// Power state seen by the last snapshot.
+    power_on: bool,
+}
+
+pub struct Simulation {
+    state: Mutex<State>,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(State {
+                config: Config::default(),
+                clock: Clock {
+                    advanced: Duration::ZERO,
+                    realtime_since: None,
+                },
+                temperature: None,
+                power_on: true,
+            }),
+        }
+    }
+}
+
+impl Simulation {
+    pub fn get(&self) -> serde_json::Value {
+        let state = self.state.lock().unwrap();
+        serde_json::json!({
+            "config": state.config,
+            "time": state.clock.now().as_secs_f64(),
+            "temperature": state.temperature.map(|(t, _)| t),
+        })
+    }
+
+    pub fn update(&self, v: serde_json::Value) -> Result<(), serde_json::Error> {
+        let args = serde_json::from_value::<Args>(v)?;
+        self.update_args(args);
+        Ok(())
+    }
+
+    pub fn update_args(&self, args: Args) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(realtime) = args.realtime {
+            state.config.realtime = realtime;
+            state.clock.set_realtime(realtime);
+        }
+        if let Some(advance) = args.advance {
+            state.clock.advanced += advance;
+        }
+        // Settle temperature under the old conditions before they change.
+        let power_on = state.power_on;
+        state.refresh_temperature(power_on);
+        let config = &mut state.config;
+        if let Some(enabled) = args.enabled {
+            config.enabled = enabled;
+        }
+        if let Some(load) = args.load {
+            config.load = load.clamp(0.0, 1.0);
+        }
+        if let Some(ambient_temperature) = args.ambient_temperature {
+            config.ambient_temperature = ambient_temperature;
+        }
+        if let Some(fan_degradation) = args.fan_degradation {
+            config.fan_degradation = fan_degradation;
+        }
+        if let Some(power_supply_failures) = args.power_supply_failures {
+            config.power_supply_failures = power_supply_failures;
+        }
+        if let Some(leak_detector_trips) = args.leak_detector_trips {
+            config.leak_detector_trips = leak_detector_trips;
+        }
+    }
+
+    /// Current state of the simulated machine, or None if the
+    /// simulation is disabled.
+    pub fn snapshot(&self, power_on: bool) -> Option<Snapshot> {
+        let mut state = self.state.lock().unwrap();
+        if !state.config.enabled {
+            return None;
+        }
+        state.power_on = power_on;
+        let temperature = state.refresh_temperature(power_on);
+        Some(Snapshot {
+            now: state.clock.now(),
+            power_on,
+            temperature,
+            config: state.config.clone(),
+        })
+    }
+}
+
+impl State {
+    fn refresh_temperature(&mut self, power_on: bool) -> f64 {
+        let now = self.clock.now();
+        let target = target_temperature(&self.config, now, power_on);
+        let temperature = match self.temperature {
+            None => target,
+            Some((previous, at)) => {
+                let elapsed = now.saturating_sub(at).as_secs_f64();
+                let decay = (-elapsed / THERMAL_TIME_CONSTANT.as_secs_f64()).exp();
+                target + (previous - target) * decay
+            }
+        };
+        self.temperature = Some((temperature, now));
+        temperature
+    }
+}
+
+fn target_temperature(config: &Config, now: Duration, power_on: bool) -> f64 {
+    let heat = if power_on {
+        IDLE_TEMPERATURE_RISE + LOAD_TEMPERATURE_RISE * config.load
+    } else {
+        0.0
+    };
+    let lost_cooling = config
+        .fan_degradation
+        .iter()
+        .map(|fan| 1.0 - fan_health(fan, now))
+        .sum::<f64>();
+    config.ambient_temperature + heat + FAN_LOSS_TEMPERATURE_RISE * lost_cooling
+}
+
+fn fan_health(fan: &FanDegradation, now: Duration) -> f64 {
+    let degrading_for = now.saturating_sub(fan.after.unwrap_or_default());
+    if degrading_for.is_zero() {
+        return 1.0;
+    }
+    (1.0 - fan.rate * degrading_for.as_secs_f64()).max(0.0)
+}
+
+fn has_happened(after: Option<Duration>, now: Duration) -> bool {
+    after.is_none_or(|after| now >= after)
+}
+
+pub struct Snapshot {
+    now: Duration,
+    power_on: bool,
+    temperature: f64,
+    config: Config,
+}
+
+impl Snapshot {
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    pub fn fan_rpm(&self, sensor_id: &str) -> u32 {
+        let nominal = if self.power_on {
+            IDLE_FAN_RPM + (MAX_FAN_RPM - IDLE_FAN_RPM) * self.config.load
+        } else {
+            STANDBY_FAN_RPM
+        };
+        let health = self
+            .config
+            .fan_degradation
+            .iter()
+            .filter(|fan| fan.sensor_id == sensor_id)
+            .map(|fan| fan_health(fan, self.now))
+            .fold(1.0, f64::min);
+        (nominal * health).round() as u32
+    }
+
+    pub fn power(&self) -> f64 {
+        if self.power_on {
+            IDLE_POWER + (MAX_POWER - IDLE_POWER) * self.config.load
+        } else {
+            STANDBY_POWER
+        }
+    }
+
+    pub fn current(&self) -> f64 {
+        self.power() / RAIL_VOLTAGE
+    }
+
+    pub fn voltage(&self) -> f64 {
+        CORE_VOLTAGE
+    }
+
+    pub fn power_supply_failed(&self, power_supply_id: &str) -> bool {
+        self.config
+            .power_supply_failures
+            .iter()
+            .any(|f| f.power_supply_id == power_supply_id && has_happened(f.after, self.now))
+    }
+
+    pub fn leak_detector_state(&self, leak_detector_id: &str) -> Option<DetectorState> {
+        self.config
+            .leak_detector_trips
+            .iter()
+            .filter(|trip| {
+                trip.leak_detector_id == leak_detector_id && has_happened(trip.after, self.now)
+            })
+            .map(|trip| trip.state.unwrap_or(DetectorState::Critical))
+            .next_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn enabled() -> Simulation {
+        let simulation = Simulation::default();
+        simulation.update(json!({"enabled": true})).unwrap();
+        simulation
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(Simulation::default().snapshot(true).is_none());
+    }
+
+    #[test]
+    fn temperature_follows_power_and_load() {
+        let simulation = enabled();
+        let idle = simulation.snapshot(true).unwrap().temperature();
+        assert_eq!(idle, DEFAULT_AMBIENT_TEMPERATURE + IDLE_TEMPERATURE_RISE);
+
+        // Load change takes a while to heat the machine up.
+        simulation.update(json!({"load": 1.0})).unwrap();
+        assert_eq!(simulation.snapshot(true).unwrap().temperature(), idle);
+        simulation.update(json!({"advance": "10m"})).unwrap();
+        let loaded = simulation.snapshot(true).unwrap().temperature();
+        assert!((loaded - (idle + LOAD_TEMPERATURE_RISE)).abs() < 0.01);
+
+        assert_eq!(simulation.snapshot(false).unwrap().temperature(), loaded);
+        simulation.update(json!({"advance": "10m"})).unwrap();
+        let off = simulation.snapshot(false).unwrap();
+        assert!((off.temperature() - DEFAULT_AMBIENT_TEMPERATURE).abs() < 0.01);
+        assert_eq!(off.fan_rpm("Fan_1"), STANDBY_FAN_RPM as u32);
+        assert_eq!(off.power(), STANDBY_POWER);
+    }
+
+    #[test]
+    fn fans_degrade_over_time() {
+        let simulation = enabled();
+        simulation
+            .update(json!({
+                "fan_degradation": [{"sensor_id": "Fan_1", "after": "1m", "rate": 0.01}],
+            }))
+            .unwrap();
+        let snapshot = simulation.snapshot(true).unwrap();
+        assert_eq!(snapshot.fan_rpm("Fan_1"), IDLE_FAN_RPM as u32);
+
+        simulation.update(json!({"advance": "110s"})).unwrap();
+        let snapshot = simulation.snapshot(true).unwrap();
+        assert_eq!(snapshot.fan_rpm("Fan_1"), (IDLE_FAN_RPM / 2.0) as u32);
+        assert_eq!(snapshot.fan_rpm("Fan_2"), IDLE_FAN_RPM as u32);
+
+        simulation.update(json!({"advance": "1h"})).unwrap();
+        let snapshot = simulation.snapshot(true).unwrap();
+        assert_eq!(snapshot.fan_rpm("Fan_1"), 0);
+        let expected = DEFAULT_AMBIENT_TEMPERATURE + IDLE_TEMPERATURE_RISE + 8.0;
+        assert!((snapshot.temperature() - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn scheduled_faults() {
+        let simulation = enabled();
+        simulation
+            .update(json!({
+                "power_supply_failures": [{"power_supply_id": "PSU_1", "after": "30s"}],
+                "leak_detector_trips": [
+                    {"leak_detector_id": "LeakDetector_1"},
+                    {"leak_detector_id": "LeakDetector_2", "after": "1m", "state": "Warning"},
+                ],
+            }))
+            .unwrap();
+        let snapshot = simulation.snapshot(true).unwrap();
+        assert!(!snapshot.power_supply_failed("PSU_1"));
+        assert!(matches!(
+            snapshot.leak_detector_state("LeakDetector_1"),
+            Some(DetectorState::Critical)
+        ));
+        assert!(snapshot.leak_detector_state("LeakDetector_2").is_none());
+
+        simulation.update(json!({"advance": "1m"})).unwrap();
+        let snapshot = simulation.snapshot(true).unwrap();
+        assert!(snapshot.power_supply_failed("PSU_1"));
+        assert!(!snapshot.power_supply_failed("PSU_2"));
+        assert!(matches!(
+            snapshot.leak_detector_state("LeakDetector_2"),
+            Some(DetectorState::Warning)
+        ));
+    }
+}
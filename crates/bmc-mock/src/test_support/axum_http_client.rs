@@ -20,6 +20,7 @@ use std::fmt;
 use axum::Router;
 use axum::body::Body;
 use axum::http::{HeaderMap, Method, Request, StatusCode};
+use futures::StreamExt;
 use http_body_util::BodyExt;
 use nv_redfish::bmc_http::{BmcCredentials, CacheableError, HttpClient};
 use nv_redfish::core::{BoxTryStream, ModificationResponse, ODataETag, SessionCreateResponse};
@@ -154,16 +155,34 @@ impl HttpClient for AxumRouterHttpClient {
 
     async fn post<B, T>(
         &self,
-        _: Url,
-        _: &B,
-        _: &BmcCredentials,
-        _: &HeaderMap,
+        url: Url,
+        body: &B,
+        credentials: &BmcCredentials,
+        custom_headers: &HeaderMap,
     ) -> Result<ModificationResponse<T>, Self::Error>
     where
         B: Serialize + Send + Sync,
         T: DeserializeOwned + Send + Sync,
     {
-        Err(Error::NotSupported("POST is not supported yet"))
+        let builder = Self::request_builder(Method::POST, &url, credentials, custom_headers)
+            .header("Content-Type", "application/json");
+        let body = serde_json::to_vec(body).map_err(Error::Json)?;
+        let request = builder.body(Body::from(body)).map_err(Error::Http)?;
+        let response = self.call(request).await?;
+        let (status, _, bytes) = Self::response_bytes(response).await?;
+        if !status.is_success() {
+            return Err(Error::InvalidResponse {
+                url,
+                status,
+                text: String::from_utf8_lossy(&bytes).to_string(),
+            });
+        }
+        if bytes.is_empty() {
+            return Ok(ModificationResponse::Empty);
+        }
+        serde_json::from_slice(&bytes)
+            .map(ModificationResponse::Entity)
+            .map_err(Error::Json)
     }
 
     async fn patch<B, T>(
@@ -195,11 +214,54 @@ impl HttpClient for AxumRouterHttpClient {
 
     async fn sse<T: Send + Sized + for<'a> serde::Deserialize<'a>>(
         &self,
-        _url: Url,
-        _credentials: &BmcCredentials,
-        _custom_headers: &HeaderMap,
+        url: Url,
+        credentials: &BmcCredentials,
+        custom_headers: &HeaderMap,
     ) -> Result<BoxTryStream<T, Self::Error>, Self::Error> {
-        Err(Error::NotSupported("SSE stream is not supported yet"))
+        let builder = Self::request_builder(Method::GET, &url, credentials, custom_headers);
+        let request = builder.body(Body::empty()).map_err(Error::Http)?;
+        let response = self.call(request).await?;
+        if !response.status().is_success() {
+            let (status, _, bytes) = Self::response_bytes(response).await?;
+            return Err(Error::InvalidResponse {
+                url,
+                status,
+                text: String::from_utf8_lossy(&bytes).to_string(),
+            });
+        }
+        let body = response.into_body().into_data_stream();
+        let stream = futures::stream::unfold((Some(body), String::new()), |state| {
+            let (mut body, mut buffer) = state;
+            async move {
+                loop {
+                    // Events are separated by an empty line; only `data:`
+                    // fields are used, comments (keep-alive) are skipped.
+                    if let Some(end) = buffer.find("\n\n") {
+                        let event = buffer[..end].to_string();
+                        buffer.drain(..end + 2);
+                        let data = event
+                            .lines()
+                            .filter_map(|line| line.strip_prefix("data:"))
+                            .map(str::trim_start)
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        if data.is_empty() {
+                            continue;
+                        }
+                        let item = serde_json::from_str::<T>(&data).map_err(Error::Json);
+                        return Some((item, (body, buffer)));
+                    }
+                    match body.as_mut()?.next().await? {
+                        Ok(chunk) => buffer.push_str(&String::from_utf8_lossy(&chunk)),
+                        Err(_) => {
+                            let err = Error::NotSupported("SSE body read error");
+                            return Some((Err(err), (None, buffer)));
+                        }
+                    }
+                }
+            }
+        });
+        Ok(Box::pin(stream))
     }
 
     async fn post_session<B, T>(
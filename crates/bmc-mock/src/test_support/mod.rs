@@ -51,6 +51,7 @@ pub type TestBmc = HttpBmc<AxumRouterHttpClient>;
 
 #[derive(Clone)]
 pub struct TestBmcHandle {
+    pub bmc: Arc<TestBmc>,
     pub service_root: Arc<nv_redfish::ServiceRoot<TestBmc>>,
     pub state: BmcState,
 }
@@ -66,7 +67,11 @@ async fn test_bmc((router, state): (axum::Router, BmcState)) -> TestBmcHandle {
         CacheSettings::with_capacity(32),
     ));
     TestBmcHandle {
-        service_root: nv_redfish::ServiceRoot::new(bmc).await.unwrap().into(),
+        service_root: nv_redfish::ServiceRoot::new(Arc::clone(&bmc))
+            .await
+            .unwrap()
+            .into(),
+        bmc,
         state,
     }
 }
@@ -153,10 +158,13 @@ pub async fn generic_ami_bmc() -> TestBmcHandle {
 mod test {
 
     use axum::Router;
+    use futures::TryStreamExt;
     use nv_redfish::bmc_http::{BmcCredentials, HttpClient};
+    use nv_redfish::event_service::EventStreamPayload;
     use url::Url;
 
     use super::*;
+    use crate::redfish::event_service::{EventRecord, Severity};
     use crate::test_support::axum_http_client::Error;
 
     #[tokio::test]
@@ -197,6 +205,29 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn event_service_streams_emitted_events() {
+        let h = dell_poweredge_r750_bmc().await;
+        let event_service = h
+            .service_root
+            .event_service()
+            .await
+            .unwrap()
+            .expect("mock should expose EventService");
+        let mut stream = event_service.events().await.unwrap();
+
+        h.state.event_service_state.emit(EventRecord::new(
+            "TestEvent.1.0.TestEvent",
+            Severity::Warning,
+            "Test event",
+        ));
+
+        match stream.try_next().await.unwrap() {
+            Some(EventStreamPayload::Event(event)) => assert_eq!(event.events.len(), 1),
+            _ => panic!("expected an event payload"),
+        }
+    }
+
     #[tokio::test]
     async fn unroutable_request_returns_404_from_transport() {
         let client = AxumRouterHttpClient::new(Router::new());
@@ -54,6 +54,7 @@ use tokio_util::sync::CancellationToken;
 use tower_http::add_extension::AddExtensionLayer;
 
 use crate::config::{AuthConfig, TlsConfig};
+use crate::recorder::Recorder;
 
 const TLS_REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
 const MAX_BODY_SIZE: usize = 8 * 1024 * 1024; // 8MiB body size limit (matches nginx ingress controller defaults)
@@ -87,6 +88,7 @@ struct BmcProxyState {
     meter: Meter,
     pg_pool: PgPool,
     credential_manager: Arc<dyn CredentialManager>,
+    recorder: Option<Arc<Recorder>>,
 }
 
 impl BmcProxyState {
@@ -135,8 +137,17 @@ pub async fn start(
         .await
         .map_err(BmcProxyError::Listen)?;
 
+    let recorder = config.recording.as_ref().map(|recording| {
+        tracing::info!(
+            directory = %recording.directory.display(),
+            "Recording proxied BMC exchanges"
+        );
+        Arc::new(Recorder::new(recording))
+    });
+
     let state = BmcProxyState {
         config,
+        recorder,
         pg_pool,
         credential_manager,
         meter,
@@ -498,14 +509,14 @@ async fn proxy_request(
 
     copy_request_headers(&parts.headers, &mut bmc_client_info.header_map);
 
-    let body = axum::body::to_bytes(body, MAX_BODY_SIZE)
+    let request_body = axum::body::to_bytes(body, MAX_BODY_SIZE)
         .await
         .map_err(|e| error_response((StatusCode::BAD_REQUEST, e.to_string()).into()))?;
 
     let Credentials::UsernamePassword { username, password } = bmc_client_info.credentials;
 
     let mut upstream_uri_parts = bmc_client_info.base_upstream_uri.into_parts();
-    upstream_uri_parts.path_and_query = Some(path_and_query);
+    upstream_uri_parts.path_and_query = Some(path_and_query.clone());
     let upstream_uri = Uri::from_parts(upstream_uri_parts)
         .map_err(|e| error_response((StatusCode::BAD_REQUEST, e.to_string()).into()))?;
 
@@ -516,7 +527,7 @@ async fn proxy_request(
         .headers(bmc_client_info.header_map);
 
     if method_supports_body(&parts.method) {
-        upstream_request = upstream_request.body(body);
+        upstream_request = upstream_request.body(request_body.clone());
     }
 
     let upstream_response = upstream_request
@@ -526,12 +537,28 @@ async fn proxy_request(
 
     let status = upstream_response.status();
     let headers = upstream_response.headers().clone();
-    let body = upstream_response
+    let response_body = upstream_response
         .bytes()
         .await
         .map_err(|e| error_response((StatusCode::BAD_GATEWAY, e.to_string()).into()))?;
 
-    Ok(build_response(status, &headers, body))
+    if let Some(recorder) = state.recorder.as_ref()
+        && recorder.records(target_ip)
+    {
+        recorder
+            .record(
+                target_ip,
+                &parts.method,
+                path_and_query.as_str(),
+                &request_body,
+                status,
+                &headers,
+                &response_body,
+            )
+            .await;
+    }
+
+    Ok(build_response(status, &headers, response_body))
 }
 
 async fn authorize_proxy_request(
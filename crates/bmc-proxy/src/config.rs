@@ -16,7 +16,8 @@
  */
 
 use std::collections::HashSet;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use carbide_authn::config::{AllowedCertCriteria, TrustConfig};
@@ -57,6 +58,8 @@ pub struct Config {
     pub tls: TlsConfig,
     pub auth: AuthConfig,
     pub bmc_proxy: Option<HostPortPair>,
+    #[serde(default)]
+    pub recording: Option<RecordingConfig>,
 }
 
 struct Defaults;
@@ -122,6 +125,21 @@ pub struct AuthConfig {
     pub acls: AclConfig,
 }
 
+/// Record proxied Redfish exchanges as replayable bmc-mock scenarios
+#[derive(Clone, Deserialize)]
+pub struct RecordingConfig {
+    /// Directory where one `<bmc_ip>.jsonl` scenario per BMC is written
+    pub directory: PathBuf,
+
+    /// BMC IP addresses to record. Records every BMC if empty.
+    #[serde(default)]
+    pub addresses: Vec<IpAddr>,
+
+    /// Additional body fields to scrub, on top of passwords, tokens and keys
+    #[serde(default)]
+    pub scrub_fields: Vec<String>,
+}
+
 impl Config {
     pub fn parse(s: &str) -> Result<Config, ConfigError> {
         Figment::new()
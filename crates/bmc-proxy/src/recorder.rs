@@ -0,0 +1,343 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Recording of proxied Redfish exchanges as bmc-mock scenarios.
+//!
+//! Every exchange with a recorded BMC is appended to
+//! `<directory>/<bmc_ip>.jsonl`, which `bmc-mock --scenario-dir` can
+//! replay. Credentials and other sensitive fields are replaced before
+//! anything is written to disk.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use bmc_scenario::{RECORDED_RESPONSE_HEADERS, RecordedExchange};
+use http::{HeaderMap, Method, StatusCode};
+use regex::Regex;
+use serde_json::Value;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::config::RecordingConfig;
+
+/// Fields which are always scrubbed from recorded bodies, compared
+/// case-insensitively.
+const DEFAULT_SCRUB_FIELDS: &[&str] = &[
+    "Password",
+    "NewPassword",
+    "OldPassword",
+    "Token",
+    "SessionToken",
+    "CertificateString",
+    "PrivateKey",
+    "SecretKey",
+    "SnmpCommunity",
+];
+
+pub const REDACTED: &str = "REDACTED";
+
+pub struct Recorder {
+    directory: PathBuf,
+    addresses: HashSet<IpAddr>,
+    scrub_fields: HashSet<String>,
+    /// Scrub fields in bodies which are not JSON, as `key=value`,
+    /// `"key": "value"` or `<key>value</key>`.
+    text_scrub_patterns: [Regex; 2],
+    files: Mutex<HashMap<IpAddr, File>>,
+}
+
+impl Recorder {
+    pub fn new(config: &RecordingConfig) -> Self {
+        let scrub_fields: HashSet<String> = DEFAULT_SCRUB_FIELDS
+            .iter()
+            .map(|s| s.to_string())
+            .chain(config.scrub_fields.iter().cloned())
+            .map(|s| s.to_ascii_lowercase())
+            .collect();
+        let names = scrub_fields
+            .iter()
+            .map(|field| regex::escape(field))
+            .collect::<Vec<_>>()
+            .join("|");
+        let text_scrub_patterns = [
+            format!(r#"(?i)(["']?\b(?:{names})\b["']?\s*[:=]\s*)("[^"]*"|'[^']*'|[^\s&,;"'<>]*)"#),
+            format!(r"(?i)(<(?:{names})>)[^<]*(</)"),
+        ]
+        .map(|pattern| Regex::new(&pattern).expect("scrub fields are escaped"));
+        Self {
+            directory: config.directory.clone(),
+            addresses: config.addresses.iter().copied().collect(),
+            scrub_fields,
+            text_scrub_patterns,
+            files: Mutex::default(),
+        }
+    }
+
+    /// Whether exchanges with `ip` are recorded. An empty address list
+    /// records every BMC.
+    pub fn records(&self, ip: IpAddr) -> bool {
+        self.addresses.is_empty() || self.addresses.contains(&ip)
+    }
+
+    /// Append an exchange to the scenario of `ip`. Failures are logged
+    /// and never affect the proxied request.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record(
+        &self,
+        ip: IpAddr,
+        method: &Method,
+        path: &str,
+        request_body: &[u8],
+        status: StatusCode,
+        response_headers: &HeaderMap,
+        response_body: &[u8],
+    ) {
+        let exchange = self.exchange(
+            method,
+            path,
+            request_body,
+            status,
+            response_headers,
+            response_body,
+        );
+        if let Err(error) = self.append(ip, &exchange).await {
+            tracing::warn!(%error, %ip, "Error recording BMC exchange");
+        }
+    }
+
+    fn exchange(
+        &self,
+        method: &Method,
+        path: &str,
+        request_body: &[u8],
+        status: StatusCode,
+        response_headers: &HeaderMap,
+        response_body: &[u8],
+    ) -> RecordedExchange {
+        let mut exchange = RecordedExchange {
+            method: method.to_string(),
+            path: path.to_string(),
+            status: status.as_u16(),
+            response_headers: RECORDED_RESPONSE_HEADERS
+                .iter()
+                .filter_map(|name| {
+                    let value = response_headers.get(*name)?.to_str().ok()?;
+                    Some((name.to_string(), value.to_string()))
+                })
+                .collect(),
+            ..Default::default()
+        };
+        exchange.set_request_body(request_body);
+        exchange.set_response_body(response_body);
+        for body in [&mut exchange.request_body, &mut exchange.response_body]
+            .into_iter()
+            .flatten()
+        {
+            self.scrub(body);
+        }
+        for text in [&mut exchange.request_text, &mut exchange.response_text]
+            .into_iter()
+            .flatten()
+        {
+            *text = self.scrub_text(text);
+        }
+        exchange
+    }
+
+    fn scrub(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.scrub_fields.contains(&key.to_ascii_lowercase()) {
+                        if !value.is_null() {
+                            *value = Value::String(REDACTED.to_string());
+                        }
+                    } else {
+                        self.scrub(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|v| self.scrub(v)),
+            _ => {}
+        }
+    }
+
+    fn scrub_text(&self, text: &str) -> String {
+        let [assignment, element] = &self.text_scrub_patterns;
+        let text = assignment.replace_all(text, |captures: &regex::Captures| {
+            let value = &captures[2];
+            let quote = if value.starts_with(['"', '\'']) {
+                &value[..1]
+            } else {
+                ""
+            };
+            format!("{}{quote}{REDACTED}{quote}", &captures[1])
+        });
+        element
+            .replace_all(&text, format!("${{1}}{REDACTED}${{2}}"))
+            .into_owned()
+    }
+
+    async fn append(&self, ip: IpAddr, exchange: &RecordedExchange) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(exchange)?;
+        line.push(b'\n');
+
+        let mut files = self.files.lock().await;
+        let file = match files.entry(ip) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                tokio::fs::create_dir_all(&self.directory).await?;
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.directory.join(format!("{ip}.jsonl")))
+                    .await?;
+                entry.insert(file)
+            }
+        };
+        file.write_all(&line).await?;
+        file.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use http::HeaderValue;
+    use serde_json::json;
+
+    use super::*;
+
+    fn recorder(directory: PathBuf, addresses: Vec<IpAddr>) -> Recorder {
+        Recorder::new(&RecordingConfig {
+            directory,
+            addresses,
+            scrub_fields: vec!["SerialNumber".to_string()],
+        })
+    }
+
+    #[test]
+    fn scrubs_nested_sensitive_fields() {
+        let recorder = recorder(PathBuf::new(), vec![]);
+        let mut value = json!({
+            "UserName": "root",
+            "password": "hunter2",
+            "Accounts": [{"Password": "secret", "Token": null}],
+            "Oem": {"Vendor": {"SerialNumber": "SN123", "Model": "X"}},
+        });
+        recorder.scrub(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "UserName": "root",
+                "password": REDACTED,
+                "Accounts": [{"Password": REDACTED, "Token": null}],
+                "Oem": {"Vendor": {"SerialNumber": REDACTED, "Model": "X"}},
+            })
+        );
+    }
+
+    #[test]
+    fn scrubs_bodies_which_are_not_json() {
+        let recorder = recorder(PathBuf::new(), vec![]);
+        let exchange = recorder.exchange(
+            &Method::POST,
+            "/login",
+            b"user=root&password=hunter2&lang=en",
+            StatusCode::OK,
+            &HeaderMap::new(),
+            br#"<Session><Token>abc</Token></Session> {"SessionToken": "def", 'secretkey': 'ghi'"#,
+        );
+        assert_eq!(
+            exchange.request_text.as_deref(),
+            Some("user=root&password=REDACTED&lang=en")
+        );
+        assert_eq!(
+            exchange.response_text.as_deref(),
+            Some(
+                r#"<Session><Token>REDACTED</Token></Session> {"SessionToken": "REDACTED", 'secretkey': 'REDACTED'"#
+            )
+        );
+    }
+
+    #[test]
+    fn records_only_configured_addresses() {
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        assert!(recorder(PathBuf::new(), vec![]).records(ip));
+        assert!(recorder(PathBuf::new(), vec![ip]).records(ip));
+        assert!(!recorder(PathBuf::new(), vec![ip]).records(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    }
+
+    #[tokio::test]
+    async fn writes_replayable_scenario() {
+        let directory = std::env::temp_dir().join(format!("bmc-proxy-{}", uuid::Uuid::new_v4()));
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let recorder = recorder(directory.clone(), vec![]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("x-auth-token", HeaderValue::from_static("abc"));
+        recorder
+            .record(
+                ip,
+                &Method::POST,
+                "/redfish/v1/SessionService/Sessions",
+                br#"{"UserName":"root","Password":"hunter2"}"#,
+                StatusCode::CREATED,
+                &headers,
+                br#"{"Id":"1"}"#,
+            )
+            .await;
+        recorder
+            .record(
+                ip,
+                &Method::GET,
+                "/redfish/v1",
+                b"",
+                StatusCode::OK,
+                &HeaderMap::new(),
+                b"plain",
+            )
+            .await;
+
+        let exchanges: Vec<RecordedExchange> =
+            std::fs::read_to_string(directory.join("10.0.0.1.jsonl"))
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(exchanges.len(), 2);
+        let session = &exchanges[0];
+        assert_eq!(
+            session.request_body,
+            Some(json!({"UserName": "root", "Password": REDACTED}))
+        );
+        assert_eq!(session.status, 201);
+        assert_eq!(
+            session.response_headers.keys().collect::<Vec<_>>(),
+            vec!["content-type"]
+        );
+        assert_eq!(exchanges[1].request_body, None);
+        assert_eq!(exchanges[1].response_text.as_deref(), Some("plain"));
+    }
+}
@@ -0,0 +1,109 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Redfish exchanges recorded against a real BMC.
+//!
+//! carbide-bmc-proxy records exchanges as JSON Lines files, one
+//! [`RecordedExchange`] per line, and bmc-mock replays them. This crate
+//! only holds the file format, so that the proxy does not depend on
+//! bmc-mock.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Single HTTP request/response pair captured from a BMC.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub method: String,
+    /// Path including the query string.
+    pub path: String,
+    /// Request body if the client sent JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<serde_json::Value>,
+    /// Request body if the client sent something that is not JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_text: Option<String>,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub response_headers: BTreeMap<String, String>,
+    /// Response body if the BMC returned JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<serde_json::Value>,
+    /// Response body if the BMC returned something that is not JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_text: Option<String>,
+}
+
+/// Response headers worth keeping in a recording. Everything else is
+/// either connection specific or not used by Redfish clients.
+pub const RECORDED_RESPONSE_HEADERS: &[&str] = &["content-type", "etag", "location"];
+
+impl RecordedExchange {
+    pub fn is_mutation(&self) -> bool {
+        is_mutating_method(&self.method)
+    }
+
+    /// Store a request body, as JSON if possible.
+    pub fn set_request_body(&mut self, body: &[u8]) {
+        (self.request_body, self.request_text) = split_body(body);
+    }
+
+    /// Store a response body, as JSON if possible.
+    pub fn set_response_body(&mut self, body: &[u8]) {
+        (self.response_body, self.response_text) = split_body(body);
+    }
+}
+
+pub fn is_mutating_method(method: &str) -> bool {
+    ["POST", "PATCH", "PUT", "DELETE"]
+        .iter()
+        .any(|m| m.eq_ignore_ascii_case(method))
+}
+
+fn split_body(body: &[u8]) -> (Option<serde_json::Value>, Option<String>) {
+    if body.is_empty() {
+        return (None, None);
+    }
+    match serde_json::from_slice(body) {
+        Ok(value) => (Some(value), None),
+        Err(_) => (None, Some(String::from_utf8_lossy(body).into_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn bodies_fall_back_to_text() {
+        let mut exchange = RecordedExchange::default();
+        exchange.set_request_body(b"user=root");
+        exchange.set_response_body(br#"{"a":1}"#);
+        assert_eq!(exchange.request_text.as_deref(), Some("user=root"));
+        assert_eq!(exchange.request_body, None);
+        assert_eq!(exchange.response_body, Some(json!({"a": 1})));
+        assert_eq!(exchange.response_text, None);
+
+        exchange.set_request_body(b"");
+        exchange.set_response_body(b"not json");
+        assert_eq!(exchange.request_text, None);
+        assert_eq!(exchange.response_text.as_deref(), Some("not json"));
+    }
+}
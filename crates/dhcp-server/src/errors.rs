@@ -39,6 +39,12 @@ pub enum DhcpError {
     #[error("Missing Message Type: {0:?}")]
     UnhandledMessageType(MessageType),
 
+    #[error("Missing DHCPv6 Option: {0:?}")]
+    MissingDhcpv6Option(dhcproto::v6::OptionCode),
+
+    #[error("Unhandled DHCPv6 Message Type: {0:?}")]
+    UnhandledDhcpv6MessageType(dhcproto::v6::MessageType),
+
     #[error("DhcpDecline message received for IP: {0}, mac: {1:?}")]
     DhcpDeclineMessage(String, String),
 
@@ -69,6 +75,9 @@ pub enum DhcpError {
     #[error("Non relayed packet received: {0}. Dropping!")]
     NonRelayedPacket(Ipv4Addr),
 
+    #[error("Non relayed DHCPv6 packet received: {0:?}. Dropping!")]
+    NonRelayedDhcpv6Packet(dhcproto::v6::MessageType),
+
     #[error("Unknown Packet: {0}")]
     UnknownPacket(u8),
 
@@ -20,6 +20,7 @@ use std::net::SocketAddr;
 use carbide_rpc_utils::dhcp::{
     DhcpConfig as ModelDhcpConfig, DhcpTimestamps, DhcpTimestampsFilePath,
     HostConfig as ModelHostConfig, InterfaceInfo as ModelInterfaceInfo,
+    InterfaceIpv6Info as ModelInterfaceIpv6Info,
 };
 use carbide_uuid::machine::MachineInterfaceId;
 use tokio::sync::mpsc;
@@ -76,6 +77,11 @@ impl TryFrom<proto::DhcpConfig> for ModelDhcpConfig {
                 .collect::<Result<Vec<_>, _>>()?,
             carbide_provisioning_server_ipv4: c.carbide_provisioning_server_ipv4.parse()?,
             carbide_dhcp_server: c.carbide_dhcp_server.parse()?,
+            carbide_nameservers_ipv6: c
+                .carbide_nameservers_ipv6
+                .iter()
+                .map(|s| s.parse())
+                .collect::<Result<Vec<_>, _>>()?,
         })
     }
 }
@@ -91,6 +97,18 @@ impl TryFrom<proto::InterfaceInfo> for ModelInterfaceInfo {
             fqdn: i.fqdn,
             booturl: i.booturl,
             mtu: i.mtu,
+            ipv6: i.ipv6.map(ModelInterfaceIpv6Info::try_from).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<proto::InterfaceIpv6Info> for ModelInterfaceIpv6Info {
+    type Error = DhcpError;
+
+    fn try_from(i: proto::InterfaceIpv6Info) -> Result<Self, Self::Error> {
+        Ok(ModelInterfaceIpv6Info {
+            address: i.address.parse()?,
+            prefix: i.prefix,
         })
     }
 }
@@ -21,12 +21,14 @@ mod errors;
 mod grpc_server;
 mod modes;
 mod packet_handler;
+mod packet_handler_v6;
+mod router_advertisement;
 mod rpc;
 mod util;
 mod vendor_class;
 
 use std::error::Error;
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 use std::sync::Arc;
 
 use ::rpc::forge::{DhcpDiscovery, DhcpRecord};
@@ -40,6 +42,8 @@ use lru::LruCache;
 use modes::DhcpMode;
 use modes::controller::Controller;
 use modes::dpu::{Dpu, get_host_config};
+use packet_handler_v6::{Dhcpv6Lease, MINIMUM_DHCPV6_PKT_SIZE};
+use router_advertisement::RouterAdvertisement;
 use tokio::net::UdpSocket;
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
@@ -48,7 +52,7 @@ use tracing::level_filters::LevelFilter;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::prelude::*;
 
-use crate::util::get_socket;
+use crate::util::{get_dhcpv6_socket, get_socket};
 
 pub struct Server {
     socket: Arc<UdpSocket>,
@@ -100,6 +104,26 @@ async fn run_dhcp_server(args: Args, cancel_token: CancellationToken) {
     // Create a new socket for each interface.
     // In case of Controller, there will be only 1 interface.
     for interface in args.interfaces {
+        join_handles.push(tokio::spawn(run_dhcpv6_listener(
+            interface.clone(),
+            config__.clone(),
+            get_mode(&args.mode),
+            dhcp_timestamps.clone(),
+            rate_limiter_.clone(),
+            cancel_token.clone(),
+        )));
+
+        // In DPU mode we are the host's first hop router, so hosts need to hear it from us.
+        if let ServerMode::Dpu = args.mode
+            && let Some(ra) = RouterAdvertisement::from_config(&interface, &config__)
+        {
+            join_handles.push(tokio::spawn(router_advertisement::run(
+                interface.clone(),
+                ra,
+                cancel_token.clone(),
+            )));
+        }
+
         let config_ = config__.clone();
         let args_mode = args.mode.clone();
         let dhcp_timestamps_ = dhcp_timestamps.clone();
@@ -205,6 +229,96 @@ async fn run_dhcp_server(args: Args, cancel_token: CancellationToken) {
     futures::future::join_all(join_handles).await;
 }
 
+/// DHCPv6 counterpart of the per-interface loop in `run_dhcp_server`. Both share the rate
+/// limiter and the timestamps file.
+async fn run_dhcpv6_listener(
+    interface: String,
+    config: Config,
+    handler: Box<dyn DhcpMode>,
+    dhcp_timestamps: Arc<Mutex<DhcpTimestamps>>,
+    rate_limiter: Arc<tokio::sync::Semaphore>,
+    cancel: CancellationToken,
+) {
+    let handler: Arc<Box<dyn DhcpMode>> = Arc::new(handler);
+    let listen_address = SocketAddr::new(
+        IpAddr::from(Ipv6Addr::UNSPECIFIED),
+        dhcproto::v6::SERVER_PORT,
+    );
+
+    let mut socket = Arc::new(get_dhcpv6_socket(listen_address, interface.clone()).await);
+    tracing::info!(
+        "Listening on {:?} on interface: {}, mode: {:?}",
+        listen_address,
+        interface,
+        handler
+    );
+
+    let machine_cache_ = Arc::new(Mutex::new(LruCache::new(
+        std::num::NonZeroUsize::new(cache::MACHINE_CACHE_SIZE).unwrap(),
+    )));
+
+    loop {
+        let mut buf = [0; 1500];
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                tracing::info!(
+                    "DHCPv6 server on interface {} received cancellation, shutting down",
+                    interface
+                );
+                break;
+            }
+            result = socket.recv_from(&mut buf) => {
+                let (len, addr) = match result {
+                    Ok((len, addr)) => (len, addr),
+                    Err(err) => {
+                        // Same as DHCPv4, the socket is mostly unusable after a failed read.
+                        tracing::error!("DHCPv6 socket recv failed with error: {err}");
+                        drop(socket);
+                        tracing::info!("Recreating the socket on {listen_address}, {interface}");
+                        socket =
+                            Arc::new(get_dhcpv6_socket(listen_address, interface.clone()).await);
+                        continue;
+                    }
+                };
+
+                let Ok(permit) = rate_limiter.clone().try_acquire_owned() else {
+                    // drop packet.
+                    tracing::error!("Dropping DHCPv6 packet because of rate limiting.");
+                    continue;
+                };
+
+                if len < MINIMUM_DHCPV6_PKT_SIZE {
+                    tracing::error!("Dropping DHCPv6 packet smaller than min length.");
+                    continue;
+                }
+
+                let config = config.clone();
+                let mut machine_cache = machine_cache_.clone();
+                let iface = interface.clone();
+                let handler_ = handler.clone();
+                let dhcp_timestamps = dhcp_timestamps.clone();
+                let socket = socket.clone();
+
+                tokio::spawn(async move {
+                    // Unlike DHCPv4, trailing bytes would be decoded as options.
+                    process_v6(
+                        addr,
+                        socket,
+                        &buf[..len],
+                        config,
+                        &**handler_,
+                        &iface,
+                        &mut machine_cache,
+                        dhcp_timestamps,
+                    )
+                    .await;
+                    drop(permit);
+                });
+            }
+        }
+    }
+}
+
 /// Initialises the tracing subscriber with per-crate log-level overrides.
 fn setup_tracing() -> Result<(), Box<dyn Error>> {
     let env_filter = EnvFilter::builder()
@@ -521,6 +635,15 @@ impl DhcpMode for TestArm {
         Test::dhcp_record()
     }
 
+    async fn discover_dhcpv6(
+        &self,
+        _discovery_request: DhcpDiscovery,
+        _config: &Config,
+        _machine_cache: &mut Arc<Mutex<LruCache<String, CacheEntry>>>,
+    ) -> Result<Dhcpv6Lease, DhcpError> {
+        Test::dhcpv6_lease()
+    }
+
     // Packets received from DPU to API must be relayed.
     fn should_be_relayed(&self) -> bool {
         true
@@ -551,6 +674,13 @@ impl Test {
             last_invalidation_time: None,
         })
     }
+
+    pub fn dhcpv6_lease() -> Result<Dhcpv6Lease, DhcpError> {
+        Ok(Dhcpv6Lease {
+            address: "2001:db8:d9:84::cc".parse().unwrap(),
+            prefix: "2001:db8:d9:84::/64".parse().unwrap(),
+        })
+    }
 }
 
 #[async_trait]
@@ -564,6 +694,15 @@ impl DhcpMode for Test {
         Test::dhcp_record()
     }
 
+    async fn discover_dhcpv6(
+        &self,
+        _discovery_request: DhcpDiscovery,
+        _config: &Config,
+        _machine_cache: &mut Arc<Mutex<LruCache<String, CacheEntry>>>,
+    ) -> Result<Dhcpv6Lease, DhcpError> {
+        Test::dhcpv6_lease()
+    }
+
     fn should_be_relayed(&self) -> bool {
         false
     }
@@ -614,8 +753,44 @@ async fn process(
         }
     }
 
-    // Tell forge-dpu-agent that an IP has been requested for this interface.
-    if let Some(host_config) = config.host_config {
+    record_dhcp_timestamp(&config, &dhcp_timestamps).await;
+}
+
+#[tracing::instrument(skip_all)]
+#[allow(clippy::too_many_arguments)]
+async fn process_v6(
+    addr: SocketAddr,
+    socket: Arc<UdpSocket>,
+    buf: &[u8],
+    config: Config,
+    handler: &dyn DhcpMode,
+    circuit_id: &str, // interface name
+    machine_cache: &mut Arc<Mutex<LruCache<String, CacheEntry>>>,
+    dhcp_timestamps: Arc<Mutex<DhcpTimestamps>>,
+) {
+    tracing::info!("Received DHCPv6 packet [{}] from {}", buf[0], addr);
+
+    let packet =
+        match packet_handler_v6::process_packet(buf, &config, circuit_id, handler, machine_cache)
+            .await
+        {
+            Ok(packet) => packet,
+            Err(err) => {
+                tracing::error!("Dropping DHCPv6 packet because of error: {}", err);
+                return;
+            }
+        };
+
+    if let Err(err) = packet.send(addr, socket).await {
+        tracing::error!("DHCPv6 packet sending failed because of error: {}", err);
+    }
+
+    record_dhcp_timestamp(&config, &dhcp_timestamps).await;
+}
+
+/// Tell forge-dpu-agent that an IP has been requested for this interface.
+async fn record_dhcp_timestamp(config: &Config, dhcp_timestamps: &Mutex<DhcpTimestamps>) {
+    if let Some(host_config) = &config.host_config {
         let mut dhcp_timestamps = dhcp_timestamps.lock().await;
         dhcp_timestamps.add_timestamp(host_config.host_interface_id, Utc::now().to_rfc3339());
         if let Err(e) = dhcp_timestamps.write() {
@@ -630,7 +805,7 @@ async fn process(
 #[cfg(test)]
 mod test {
     use std::env;
-    use std::net::{Ipv4Addr, SocketAddrV4};
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4};
     use std::path::PathBuf;
     use std::str::FromStr;
     use std::sync::Arc;
@@ -638,7 +813,7 @@ mod test {
     use carbide_rpc_utils::dhcp::{DhcpTimestamps, DhcpTimestampsFilePath};
     use chrono::{DateTime, Utc};
     use dhcproto::v4::{DhcpOption, Message, MessageType, OptionCode};
-    use dhcproto::{Decodable, Decoder, Encodable};
+    use dhcproto::{Decodable, Decoder, Encodable, v6};
     use lru::LruCache;
     use tempfile::TempDir;
     use tokio::net::UdpSocket;
@@ -647,7 +822,11 @@ mod test {
 
     use crate::command_line::{Args, ServerMode};
     use crate::errors::DhcpError;
-    use crate::{DhcpMode, Test, TestArm, cache, handle_reload, init, packet_handler, process};
+    use crate::modes::dpu::Dpu;
+    use crate::{
+        Config, DhcpMode, Test, TestArm, cache, handle_reload, init, packet_handler,
+        packet_handler_v6, process,
+    };
 
     fn make_reload_args(td: &TempDir, interfaces: Vec<String>) -> Args {
         Args {
@@ -1096,4 +1275,301 @@ mod test {
             DhcpOption::MessageType(MessageType::Nak)
         );
     }
+
+    const TEST_CLIENT_DUID: [u8; 10] = [0x00, 0x03, 0x00, 0x01, 0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6];
+
+    /// RELAY-FORW from the DPU's relay agent, wrapping a Solicit with an IA_NA.
+    const RELAYED_SOLICIT: [u8; 95] = [
+        0x0c, 0x00, // msg-type, hop-count
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0xd9, 0x00, 0x84, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, // link-address 2001:db8:d9:84::1
+        0xfe, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x1b, 0x63, 0xff, 0xfe, 0x84, 0x45,
+        0xe6, // peer-address fe80::21b:63ff:fe84:45e6
+        0x00, 0x12, 0x00, 0x07, b'v', b'l', b'a', b'n', b'2', b'0', b'0', // Interface-Id
+        0x00, 0x4f, 0x00, 0x08, 0x00, 0x01, 0x00, 0x1b, 0x63, 0x84, 0x45,
+        0xe6, // Client Link-Layer Address
+        0x00, 0x09, 0x00, 0x22, // Relay Message
+        0x01, 0x12, 0x34, 0x56, // Solicit, transaction-id
+        0x00, 0x01, 0x00, 0x0a, 0x00, 0x03, 0x00, 0x01, 0x00, 0x1b, 0x63, 0x84, 0x45,
+        0xe6, // Client Identifier
+        0x00, 0x03, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // IA_NA
+    ];
+
+    fn get_v6_byte_stream(message_type: v6::MessageType, opts: Vec<v6::DhcpOption>) -> Vec<u8> {
+        let mut msg = v6::Message::new_with_id(message_type, [0x12, 0x34, 0x56]);
+        msg.opts_mut()
+            .insert(v6::DhcpOption::ClientId(TEST_CLIENT_DUID.to_vec()));
+        for opt in opts {
+            msg.opts_mut().insert(opt);
+        }
+
+        let mut encoded_packet = Vec::new();
+        let mut e = dhcproto::Encoder::new(&mut encoded_packet);
+        msg.encode(&mut e).unwrap();
+        encoded_packet
+    }
+
+    fn ia_na(addresses: &[&str]) -> v6::DhcpOption {
+        let mut opts = v6::DhcpOptions::new();
+        for address in addresses {
+            opts.insert(v6::DhcpOption::IAAddr(v6::IAAddr {
+                addr: address.parse().unwrap(),
+                preferred_life: 0,
+                valid_life: 0,
+                opts: v6::DhcpOptions::new(),
+            }));
+        }
+        v6::DhcpOption::IANA(v6::IANA {
+            id: 1,
+            t1: 0,
+            t2: 0,
+            opts,
+        })
+    }
+
+    fn ia_pd() -> v6::DhcpOption {
+        v6::DhcpOption::IAPD(v6::IAPD {
+            id: 2,
+            t1: 0,
+            t2: 0,
+            opts: v6::DhcpOptions::new(),
+        })
+    }
+
+    async fn process_v6_packet(
+        packet: &[u8],
+        config: &Config,
+        circuit_id: &str,
+        handler: &dyn DhcpMode,
+    ) -> Result<Vec<u8>, DhcpError> {
+        let mut machine_cache = Arc::new(Mutex::new(LruCache::new(
+            std::num::NonZeroUsize::new(cache::MACHINE_CACHE_SIZE).unwrap(),
+        )));
+        packet_handler_v6::process_packet(packet, config, circuit_id, handler, &mut machine_cache)
+            .await
+            .map(|packet| packet.encoded_packet().clone())
+    }
+
+    fn decode_v6(packet: &[u8]) -> v6::Message {
+        v6::Message::decode(&mut Decoder::new(packet)).unwrap()
+    }
+
+    fn reply_addresses(packet: &v6::Message) -> Vec<v6::IAAddr> {
+        let Some(v6::DhcpOption::IANA(iana)) = packet.opts().get(v6::OptionCode::IANA) else {
+            panic!("No IA_NA in {packet:?}");
+        };
+        iana.opts
+            .iter()
+            .filter_map(|opt| match opt {
+                v6::DhcpOption::IAAddr(addr) => Some(addr.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Prefix delegation isn't supported, so the IA_PD must come back without prefixes.
+    fn assert_no_prefix_available(packet: &v6::Message) {
+        let Some(v6::DhcpOption::IAPD(iapd)) = packet.opts().get(v6::OptionCode::IAPD) else {
+            panic!("No IA_PD in {packet:?}");
+        };
+        assert_eq!(iapd.id, 2);
+        assert!(iapd.opts.get(v6::OptionCode::IAPrefix).is_none());
+        let Some(v6::DhcpOption::StatusCode(status)) = iapd.opts.get(v6::OptionCode::StatusCode)
+        else {
+            panic!("No status code in {iapd:?}");
+        };
+        assert_eq!(status.status, v6::Status::NoPrefixAvail);
+    }
+
+    #[tokio::test]
+    async fn test_dhcpv6_solicit() {
+        let packet = get_v6_byte_stream(v6::MessageType::Solicit, vec![ia_na(&[]), ia_pd()]);
+        let config = init(get_test_args()).await.unwrap();
+
+        let packet = decode_v6(
+            &process_v6_packet(&packet, &config, "vlan200", &Test {})
+                .await
+                .unwrap(),
+        );
+
+        assert_eq!(packet.msg_type(), v6::MessageType::Advertise);
+        assert_eq!(packet.xid(), [0x12, 0x34, 0x56]);
+        assert_eq!(
+            packet.opts().get(v6::OptionCode::ClientId),
+            Some(&v6::DhcpOption::ClientId(TEST_CLIENT_DUID.to_vec()))
+        );
+        assert_eq!(
+            packet.opts().get(v6::OptionCode::ServerId),
+            Some(&v6::DhcpOption::ServerId(packet_handler_v6::server_duid(
+                &config
+            )))
+        );
+        assert_eq!(
+            packet.opts().get(v6::OptionCode::Preference),
+            Some(&v6::DhcpOption::Preference(255))
+        );
+        assert_eq!(
+            packet.opts().get(v6::OptionCode::DomainNameServers),
+            Some(&v6::DhcpOption::DomainNameServers(vec![
+                "2001:db8:126::20".parse().unwrap()
+            ]))
+        );
+
+        let addresses = reply_addresses(&packet);
+        assert_eq!(addresses.len(), 1);
+        assert_eq!(
+            addresses[0].addr,
+            "2001:db8:d9:84::cc".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(addresses[0].valid_life, 604800);
+
+        assert_no_prefix_available(&packet);
+    }
+
+    #[tokio::test]
+    async fn test_dhcpv6_solicit_rapid_commit() {
+        let packet = get_v6_byte_stream(
+            v6::MessageType::Solicit,
+            vec![ia_na(&[]), v6::DhcpOption::RapidCommit],
+        );
+        let config = init(get_test_args()).await.unwrap();
+
+        let packet = decode_v6(
+            &process_v6_packet(&packet, &config, "vlan200", &Test {})
+                .await
+                .unwrap(),
+        );
+
+        assert_eq!(packet.msg_type(), v6::MessageType::Reply);
+        assert!(packet.opts().get(v6::OptionCode::RapidCommit).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_dhcpv6_renew_with_stale_address() {
+        let config = init(get_test_args()).await.unwrap();
+        let packet = get_v6_byte_stream(
+            v6::MessageType::Renew,
+            vec![
+                ia_na(&["2001:db8:d9:85::cc"]),
+                v6::DhcpOption::ServerId(packet_handler_v6::server_duid(&config)),
+            ],
+        );
+
+        let packet = decode_v6(
+            &process_v6_packet(&packet, &config, "vlan200", &Test {})
+                .await
+                .unwrap(),
+        );
+
+        assert_eq!(packet.msg_type(), v6::MessageType::Reply);
+        let addresses = reply_addresses(&packet);
+        assert_eq!(addresses.len(), 2);
+        let stale = addresses
+            .iter()
+            .find(|a| a.addr == "2001:db8:d9:85::cc".parse::<Ipv6Addr>().unwrap())
+            .unwrap();
+        assert_eq!((stale.preferred_life, stale.valid_life), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_dhcpv6_request_for_other_server() {
+        let packet = get_v6_byte_stream(
+            v6::MessageType::Request,
+            vec![
+                ia_na(&[]),
+                v6::DhcpOption::ServerId(vec![0x00, 0x03, 0x00, 0x01, 1, 2, 3, 4, 5, 6]),
+            ],
+        );
+        let config = init(get_test_args()).await.unwrap();
+
+        assert!(matches!(
+            process_v6_packet(&packet, &config, "vlan200", &Test {}).await,
+            Err(DhcpError::NotMyPacket(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dhcpv6_confirm_not_on_link() {
+        let packet = get_v6_byte_stream(
+            v6::MessageType::Confirm,
+            vec![ia_na(&["2001:db8:ffff::cc"])],
+        );
+        let config = init(get_test_args()).await.unwrap();
+
+        let packet = decode_v6(
+            &process_v6_packet(&packet, &config, "vlan200", &Test {})
+                .await
+                .unwrap(),
+        );
+
+        assert_eq!(packet.msg_type(), v6::MessageType::Reply);
+        let Some(v6::DhcpOption::StatusCode(status)) =
+            packet.opts().get(v6::OptionCode::StatusCode)
+        else {
+            panic!("No status code in {packet:?}");
+        };
+        assert_eq!(status.status, v6::Status::NotOnLink);
+    }
+
+    #[tokio::test]
+    async fn test_dhcpv6_arm_non_relayed_packet() {
+        let packet = get_v6_byte_stream(v6::MessageType::Solicit, vec![ia_na(&[])]);
+        let config = init(get_test_args()).await.unwrap();
+
+        assert!(matches!(
+            process_v6_packet(&packet, &config, "vlan200", &TestArm {}).await,
+            Err(DhcpError::NonRelayedDhcpv6Packet(v6::MessageType::Solicit))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dhcpv6_arm_relayed_packet() {
+        let config = init(get_test_args()).await.unwrap();
+
+        let packet = process_v6_packet(&RELAYED_SOLICIT, &config, "vlan200", &TestArm {})
+            .await
+            .unwrap();
+
+        // RELAY-REPL with the same hop-count, link-address, peer-address and Interface-Id.
+        assert_eq!(packet[0], 13);
+        assert_eq!(packet[1..34], RELAYED_SOLICIT[1..34]);
+        assert_eq!(packet[34..45], RELAYED_SOLICIT[34..45]);
+        assert_eq!(packet[45..47], [0x00, 0x09]);
+        let len = u16::from_be_bytes([packet[47], packet[48]]) as usize;
+        assert_eq!(packet.len(), 49 + len);
+
+        let packet = decode_v6(&packet[49..]);
+        assert_eq!(packet.msg_type(), v6::MessageType::Advertise);
+        assert_eq!(packet.xid(), [0x12, 0x34, 0x56]);
+        assert_eq!(
+            reply_addresses(&packet)[0].addr,
+            "2001:db8:d9:84::cc".parse::<Ipv6Addr>().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dhcpv6_dpu_host_config() {
+        let packet = get_v6_byte_stream(v6::MessageType::Request, vec![ia_na(&[]), ia_pd()]);
+        let config = init(get_test_args()).await.unwrap();
+
+        let reply = decode_v6(
+            &process_v6_packet(&packet, &config, "vlan200", &Dpu {})
+                .await
+                .unwrap(),
+        );
+
+        assert_eq!(reply.msg_type(), v6::MessageType::Reply);
+        assert_eq!(
+            reply_addresses(&reply)[0].addr,
+            "2001:db8:10::2".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_no_prefix_available(&reply);
+
+        // vlan123 has no IPv6 configuration.
+        assert!(matches!(
+            process_v6_packet(&packet, &config, "vlan123", &Dpu {}).await,
+            Err(DhcpError::MissingArgument(..))
+        ));
+    }
 }
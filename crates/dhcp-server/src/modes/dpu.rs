@@ -24,6 +24,7 @@ use super::DhcpMode;
 use crate::cache::CacheEntry;
 use crate::errors::DhcpError;
 use crate::packet_handler::DecodedPacket;
+use crate::packet_handler_v6::Dhcpv6Lease;
 use crate::{Config, HostConfig};
 
 #[derive(Debug)]
@@ -55,21 +56,7 @@ impl DhcpMode for Dpu {
         config: &Config,
         _machine_cache: &mut std::sync::Arc<tokio::sync::Mutex<LruCache<String, CacheEntry>>>,
     ) -> Result<DhcpRecord, DhcpError> {
-        let Some(circuit_id) = discovery_request.circuit_id else {
-            return Err(DhcpError::MissingArgument(
-                "Missing circuit id.".to_string(),
-            ));
-        };
-
-        let ip_details = config
-            .host_config
-            .as_ref()
-            .ok_or_else(|| DhcpError::InvalidInput("host input is invalid.".to_string()))?
-            .host_ip_addresses
-            .get(&circuit_id)
-            .ok_or_else(|| {
-                DhcpError::MissingArgument(format!("Could not find IP details for {circuit_id}"))
-            })?;
+        let ip_details = get_ip_details(&discovery_request, config)?;
 
         let Some(host_config) = &config.host_config else {
             return Err(DhcpError::MissingArgument(
@@ -80,6 +67,24 @@ impl DhcpMode for Dpu {
         Ok(from_host_conf(ip_details, host_config.host_interface_id))
     }
 
+    async fn discover_dhcpv6(
+        &self,
+        discovery_request: DhcpDiscovery,
+        config: &Config,
+        _machine_cache: &mut std::sync::Arc<tokio::sync::Mutex<LruCache<String, CacheEntry>>>,
+    ) -> Result<Dhcpv6Lease, DhcpError> {
+        let ip_details = get_ip_details(&discovery_request, config)?;
+
+        let Some(ipv6_details) = &ip_details.ipv6 else {
+            return Err(DhcpError::MissingArgument(format!(
+                "Could not find IPv6 details for {:?}",
+                discovery_request.circuit_id
+            )));
+        };
+
+        Dhcpv6Lease::try_from(ipv6_details)
+    }
+
     /// Here circuit is interface name. This is what dhcp-relay used to fill.
     fn get_circuit_id(&self, _packet: &DecodedPacket, circuit_id: &str) -> Option<String> {
         Some(circuit_id.to_string())
@@ -90,6 +95,27 @@ impl DhcpMode for Dpu {
     }
 }
 
+fn get_ip_details<'a>(
+    discovery_request: &DhcpDiscovery,
+    config: &'a Config,
+) -> Result<&'a InterfaceInfo, DhcpError> {
+    let Some(circuit_id) = &discovery_request.circuit_id else {
+        return Err(DhcpError::MissingArgument(
+            "Missing circuit id.".to_string(),
+        ));
+    };
+
+    config
+        .host_config
+        .as_ref()
+        .ok_or_else(|| DhcpError::InvalidInput("host input is invalid.".to_string()))?
+        .host_ip_addresses
+        .get(circuit_id)
+        .ok_or_else(|| {
+            DhcpError::MissingArgument(format!("Could not find IP details for {circuit_id}"))
+        })
+}
+
 /// This config is fetched by dpu-agent from controller periodically. In case of any change in
 /// this configuration, dpu-agent MUST restart dhcp-server.
 pub async fn get_host_config(
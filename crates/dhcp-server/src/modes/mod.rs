@@ -26,6 +26,7 @@ use crate::Config;
 use crate::cache::CacheEntry;
 use crate::errors::DhcpError;
 use crate::packet_handler::{DecodedPacket, Packet};
+use crate::packet_handler_v6::Dhcpv6Lease;
 
 pub mod controller;
 pub mod dpu;
@@ -39,6 +40,19 @@ pub trait DhcpMode: Send + Sync + std::fmt::Debug {
         config: &Config,
         machine_cache: &mut Arc<Mutex<LruCache<String, CacheEntry>>>,
     ) -> Result<DhcpRecord, DhcpError>;
+    /// Method to determine the IPv6 address to be returned to a DHCPv6 client.
+    /// carbide answers IPv6 relay addresses with an IPv6 record, so by default ask it the same way.
+    async fn discover_dhcpv6(
+        &self,
+        discovery_request: DhcpDiscovery,
+        config: &Config,
+        machine_cache: &mut Arc<Mutex<LruCache<String, CacheEntry>>>,
+    ) -> Result<Dhcpv6Lease, DhcpError> {
+        Dhcpv6Lease::try_from(
+            self.discover_dhcp(discovery_request, config, machine_cache)
+                .await?,
+        )
+    }
     /// And at what address?
     fn get_destination_address(&self, packet: &Packet) -> SocketAddrV4 {
         packet.dst_address()
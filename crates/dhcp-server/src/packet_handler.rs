@@ -517,7 +517,7 @@ fn nak_packet(
     Ok(msg)
 }
 
-fn get_mtu(circuit_id: &str, host_config: Option<&HostConfig>) -> u16 {
+pub fn get_mtu(circuit_id: &str, host_config: Option<&HostConfig>) -> u16 {
     host_config
         .map(|x| x.host_ip_addresses.clone())
         .unwrap_or_default()
@@ -542,6 +542,7 @@ mod test {
             fqdn: "fqdn1".to_string(),
             booturl: None,
             mtu: None,
+            ipv6: None,
         };
         let interface_mtu_9000 = crate::packet_handler::InterfaceInfo {
             address: <std::net::Ipv4Addr as std::str::FromStr>::from_str("20.22.2.2")
@@ -554,6 +555,7 @@ mod test {
             fqdn: "fqdn2".to_string(),
             booturl: None,
             mtu: Some(9000),
+            ipv6: None,
         };
         let mut interface_mtu_65537 = interface_mtu_none.clone();
         interface_mtu_65537.mtu = Some(65537);
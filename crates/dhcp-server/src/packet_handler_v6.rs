@@ -0,0 +1,524 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::net::{Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use carbide_rpc_utils::dhcp::InterfaceIpv6Info;
+use dhcproto::v6::{
+    DhcpOption, DhcpOptions, IAAddr, IANA, IAPD, Message, MessageType, OptionCode, Status,
+    StatusCode,
+};
+use dhcproto::{Decodable, Decoder, Encodable, Encoder};
+use ipnetwork::{IpNetwork, Ipv6Network};
+use lru::LruCache;
+use rpc::forge::{DhcpDiscovery, DhcpRecord};
+use tokio::net::UdpSocket;
+use tokio::sync::Mutex;
+
+use crate::cache::CacheEntry;
+use crate::errors::DhcpError;
+use crate::{Config, DhcpMode, util};
+
+/// Smallest valid DHCPv6 message: msg-type and transaction-id.
+pub const MINIMUM_DHCPV6_PKT_SIZE: usize = 4;
+
+/// We are the only DHCPv6 server on the link, so tell clients not to wait for other offers.
+const SERVER_PREFERENCE: u8 = 255;
+
+// https://www.rfc-editor.org/rfc/rfc8415#section-11
+const DUID_TYPE_LLT: u16 = 1;
+const DUID_TYPE_LL: u16 = 3;
+const HTYPE_ETHERNET: u16 = 1;
+
+// Relay options which dhcproto doesn't know about.
+// https://www.rfc-editor.org/rfc/rfc6939
+const OPTION_CLIENT_LINKLAYER_ADDR: u16 = 79;
+
+/// Address and prefix handed out to a DHCPv6 client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dhcpv6Lease {
+    pub address: Ipv6Addr,
+    pub prefix: Ipv6Network,
+}
+
+impl TryFrom<DhcpRecord> for Dhcpv6Lease {
+    type Error = DhcpError;
+
+    fn try_from(record: DhcpRecord) -> Result<Self, Self::Error> {
+        Ok(Dhcpv6Lease {
+            address: Ipv6Addr::from_str(&record.address)?,
+            prefix: parse_ipv6_network(&record.prefix)?,
+        })
+    }
+}
+
+impl TryFrom<&InterfaceIpv6Info> for Dhcpv6Lease {
+    type Error = DhcpError;
+
+    fn try_from(info: &InterfaceIpv6Info) -> Result<Self, Self::Error> {
+        Ok(Dhcpv6Lease {
+            address: info.address,
+            prefix: parse_ipv6_network(&info.prefix)?,
+        })
+    }
+}
+
+fn parse_ipv6_network(prefix: &str) -> Result<Ipv6Network, DhcpError> {
+    match prefix.parse::<IpNetwork>() {
+        Ok(IpNetwork::V6(prefix)) => Ok(prefix),
+        Ok(IpNetwork::V4(prefix)) => Err(DhcpError::GenericError(format!(
+            "Prefix ({prefix}) is an IPv4 network, which can't be served over DHCPv6."
+        ))),
+        Err(error) => Err(DhcpError::GenericError(format!(
+            "prefix value is not an IP Network: {error}"
+        ))),
+    }
+}
+
+/// One RELAY-FORW encapsulation around the client message. Relays are stored outermost first.
+#[derive(Debug)]
+struct RelayHop {
+    hop_count: u8,
+    link_addr: Ipv6Addr,
+    peer_addr: Ipv6Addr,
+    interface_id: Option<Vec<u8>>,
+    remote_id: Option<Vec<u8>>,
+    client_link_layer_addr: Option<Vec<u8>>,
+}
+
+/// dhcproto decodes the Relay Message option as another relay message, which breaks as soon as
+/// it wraps a client message, so relay headers are walked by hand.
+fn strip_relay_hops(buf: &[u8]) -> Result<(Vec<RelayHop>, &[u8]), DhcpError> {
+    let mut hops = vec![];
+    let mut buf = buf;
+
+    while buf[0] == u8::from(MessageType::RelayForw) {
+        let mut decoder = Decoder::new(buf);
+        let _msg_type = decoder.read_u8()?;
+        let mut hop = RelayHop {
+            hop_count: decoder.read_u8()?,
+            link_addr: decoder.read::<16>()?.into(),
+            peer_addr: decoder.read::<16>()?.into(),
+            interface_id: None,
+            remote_id: None,
+            client_link_layer_addr: None,
+        };
+
+        let mut relay_msg = None;
+        while !decoder.buffer().is_empty() {
+            let code = decoder.read_u16()?;
+            let len = decoder.read_u16()? as usize;
+            let data = decoder.read_slice(len)?;
+            match OptionCode::from(code) {
+                OptionCode::RelayMsg => relay_msg = Some(data),
+                OptionCode::InterfaceId => hop.interface_id = Some(data.to_vec()),
+                // enterprise-number followed by the remote-id itself.
+                OptionCode::RemoteId if len > 4 => hop.remote_id = Some(data[4..].to_vec()),
+                _ if code == OPTION_CLIENT_LINKLAYER_ADDR => {
+                    hop.client_link_layer_addr = Some(data.to_vec())
+                }
+                _ => {}
+            }
+        }
+
+        let Some(relay_msg) = relay_msg.filter(|m| !m.is_empty()) else {
+            return Err(DhcpError::MissingDhcpv6Option(OptionCode::RelayMsg));
+        };
+        hops.push(hop);
+        buf = relay_msg;
+    }
+
+    Ok((hops, buf))
+}
+
+/// Wrap the reply in one RELAY-REPL per RELAY-FORW we received, innermost first.
+fn add_relay_hops(hops: &[RelayHop], reply: Vec<u8>) -> Result<Vec<u8>, DhcpError> {
+    let mut encoded_packet = reply;
+    for hop in hops.iter().rev() {
+        let mut relay_repl = Vec::new();
+        let mut e = Encoder::new(&mut relay_repl);
+        e.write_u8(MessageType::RelayRepl.into())?;
+        e.write_u8(hop.hop_count)?;
+        e.write_slice(&hop.link_addr.octets())?;
+        e.write_slice(&hop.peer_addr.octets())?;
+        // Relays rely on the Interface-Id they sent us to pick the outgoing interface.
+        if let Some(interface_id) = &hop.interface_id {
+            e.write_u16(OptionCode::InterfaceId.into())?;
+            e.write_u16(interface_id.len() as u16)?;
+            e.write_slice(interface_id)?;
+        }
+        e.write_u16(OptionCode::RelayMsg.into())?;
+        e.write_u16(encoded_packet.len() as u16)?;
+        e.write_slice(&encoded_packet)?;
+        encoded_packet = relay_repl;
+    }
+    Ok(encoded_packet)
+}
+
+pub struct DecodedPacketV6 {
+    packet: Message,
+    relay_hops: Vec<RelayHop>,
+}
+
+impl DecodedPacketV6 {
+    fn is_relayed(&self) -> Result<(), DhcpError> {
+        if self.relay_hops.is_empty() {
+            return Err(DhcpError::NonRelayedDhcpv6Packet(self.packet.msg_type()));
+        }
+        Ok(())
+    }
+
+    fn is_this_for_us(&self, config: &Config) -> Result<(), DhcpError> {
+        if let Some(DhcpOption::ServerId(server_id)) = self.packet.opts().get(OptionCode::ServerId)
+        {
+            if *server_id == server_duid(config) {
+                return Ok(());
+            }
+            return Err(DhcpError::NotMyPacket(hex_string(server_id)));
+        }
+
+        // No identifier sent by client. It can be for us
+        Ok(())
+    }
+
+    fn client_id(&self) -> Option<&Vec<u8>> {
+        match self.packet.opts().get(OptionCode::ClientId) {
+            Some(DhcpOption::ClientId(duid)) => Some(duid),
+            _ => None,
+        }
+    }
+
+    /// The relay closest to the client is the one which knows the client's link.
+    fn first_relay(&self) -> Option<&RelayHop> {
+        self.relay_hops.last()
+    }
+
+    /// Prefer the Client Link-Layer Address the first relay saw, fall back to the MAC embedded
+    /// in a DUID-LL/DUID-LLT.
+    fn get_mac_address(&self) -> Result<String, DhcpError> {
+        if let Some(lladdr) = self
+            .first_relay()
+            .and_then(|hop| hop.client_link_layer_addr.as_ref())
+            && lladdr.len() == 8
+            && u16::from_be_bytes([lladdr[0], lladdr[1]]) == HTYPE_ETHERNET
+        {
+            return Ok(util::u8_to_mac(&lladdr[2..]));
+        }
+
+        let duid = self
+            .client_id()
+            .ok_or(DhcpError::MissingDhcpv6Option(OptionCode::ClientId))?;
+        let mac = match duid.get(0..4).map(|h| {
+            (
+                u16::from_be_bytes([h[0], h[1]]),
+                u16::from_be_bytes([h[2], h[3]]),
+            )
+        }) {
+            Some((DUID_TYPE_LLT, HTYPE_ETHERNET)) if duid.len() == 14 => &duid[8..],
+            Some((DUID_TYPE_LL, HTYPE_ETHERNET)) if duid.len() == 10 => &duid[4..],
+            _ => {
+                return Err(DhcpError::MissingArgument(format!(
+                    "Can not find a MAC address for DUID {}",
+                    hex_string(duid)
+                )));
+            }
+        };
+        Ok(util::u8_to_mac(mac))
+    }
+
+    fn get_discovery_request(
+        &self,
+        handler: &dyn DhcpMode,
+        circuit_id: &str,
+    ) -> Result<DhcpDiscovery, DhcpError> {
+        let relay = self.first_relay();
+        // The relay's link-address identifies the client's link, like giaddr does for DHCPv4.
+        let relay_address = relay.map_or(Ipv6Addr::UNSPECIFIED, |hop| hop.link_addr);
+        let circuit_id = if handler.should_be_relayed() {
+            relay
+                .and_then(|hop| hop.interface_id.as_ref())
+                .map(|id| String::from_utf8_lossy(id).to_string())
+        } else {
+            // Same as the DHCPv4 dpu mode, the interface name is the circuit id.
+            Some(circuit_id.to_string())
+        };
+
+        Ok(DhcpDiscovery {
+            mac_address: self.get_mac_address()?,
+            relay_address: relay_address.to_string(),
+            vendor_string: None,
+            link_address: None,
+            circuit_id,
+            remote_id: relay
+                .and_then(|hop| hop.remote_id.as_ref())
+                .map(|id| String::from_utf8_lossy(id).to_string()),
+            desired_address: None,
+        })
+    }
+}
+
+pub struct Dhcpv6Packet {
+    encoded_packet: Vec<u8>,
+}
+
+impl Dhcpv6Packet {
+    #[cfg(test)]
+    pub fn encoded_packet(&self) -> &Vec<u8> {
+        &self.encoded_packet
+    }
+
+    /// Replies go back where the request came from: the client's link-local address when it
+    /// talks to us directly, the relay otherwise.
+    pub async fn send(
+        &self,
+        dst_address: SocketAddr,
+        socket: Arc<UdpSocket>,
+    ) -> Result<(), String> {
+        tracing::info!("Sending DHCPv6 packet to {:?}", dst_address);
+        socket
+            .send_to(&self.encoded_packet, dst_address)
+            .await
+            .map_err(|x| x.to_string())?;
+
+        Ok(())
+    }
+}
+
+pub async fn process_packet(
+    buf: &[u8],
+    config: &Config,
+    circuit_id: &str,
+    handler: &dyn DhcpMode,
+    machine_cache: &mut Arc<Mutex<LruCache<String, CacheEntry>>>,
+) -> Result<Dhcpv6Packet, DhcpError> {
+    let (relay_hops, buf) = strip_relay_hops(buf)?;
+    let packet = Message::decode(&mut Decoder::new(buf))?;
+    tracing::info!(packet.received=%packet, "Received DHCPv6 Packet");
+    let decoded_packet = DecodedPacketV6 { packet, relay_hops };
+
+    if handler.should_be_relayed() {
+        decoded_packet.is_relayed()?;
+    }
+    decoded_packet.is_this_for_us(config)?;
+
+    let lease = match decoded_packet.packet.msg_type() {
+        // Nothing to look up, the client only wants the other configuration options.
+        MessageType::InformationRequest => None,
+        _ => Some(
+            handler
+                .discover_dhcpv6(
+                    decoded_packet.get_discovery_request(handler, circuit_id)?,
+                    config,
+                    machine_cache,
+                )
+                .await?,
+        ),
+    };
+
+    let packet = create_dhcpv6_reply_packet(&decoded_packet, lease.as_ref(), config)?;
+    tracing::info!(packet.send=%packet, "Sending DHCPv6 Packet");
+
+    let mut encoded_packet = Vec::new();
+    let mut e = Encoder::new(&mut encoded_packet);
+    packet.encode(&mut e)?;
+
+    Ok(Dhcpv6Packet {
+        encoded_packet: add_relay_hops(&decoded_packet.relay_hops, encoded_packet)?,
+    })
+}
+
+fn create_dhcpv6_reply_packet(
+    src: &DecodedPacketV6,
+    lease: Option<&Dhcpv6Lease>,
+    config: &Config,
+) -> Result<Message, DhcpError> {
+    let rapid_commit = src.packet.opts().get(OptionCode::RapidCommit).is_some();
+    let reply_message_type = match src.packet.msg_type() {
+        MessageType::Solicit if rapid_commit => MessageType::Reply,
+        MessageType::Solicit => MessageType::Advertise,
+        MessageType::Request
+        | MessageType::Renew
+        | MessageType::Rebind
+        | MessageType::Confirm
+        | MessageType::Release
+        | MessageType::InformationRequest => MessageType::Reply,
+        MessageType::Decline => {
+            let declined = ia_addresses(&src.packet)
+                .map(|addr| addr.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            return Err(DhcpError::DhcpDeclineMessage(
+                declined,
+                src.client_id()
+                    .map(|duid| hex_string(duid))
+                    .unwrap_or_default(),
+            ));
+        }
+        msg_type => {
+            return Err(DhcpError::UnhandledDhcpv6MessageType(msg_type));
+        }
+    };
+
+    // https://www.rfc-editor.org/rfc/rfc8415#section-18.3
+    let mut msg = Message::new_with_id(reply_message_type, src.packet.xid());
+    match src.client_id() {
+        Some(duid) => msg.opts_mut().insert(DhcpOption::ClientId(duid.clone())),
+        // Information-request is the only message a client may send anonymously.
+        None if src.packet.msg_type() == MessageType::InformationRequest => {}
+        None => return Err(DhcpError::MissingDhcpv6Option(OptionCode::ClientId)),
+    }
+    msg.opts_mut()
+        .insert(DhcpOption::ServerId(server_duid(config)));
+
+    if src.packet.msg_type() == MessageType::Solicit {
+        msg.opts_mut()
+            .insert(DhcpOption::Preference(SERVER_PREFERENCE));
+        if rapid_commit {
+            msg.opts_mut().insert(DhcpOption::RapidCommit);
+        }
+    }
+
+    if !config.dhcp_config.carbide_nameservers_ipv6.is_empty() {
+        msg.opts_mut().insert(DhcpOption::DomainNameServers(
+            config.dhcp_config.carbide_nameservers_ipv6.clone(),
+        ));
+    }
+
+    let Some(lease) = lease else {
+        return Ok(msg);
+    };
+
+    match src.packet.msg_type() {
+        MessageType::Confirm => {
+            // The client only wants to know whether its addresses are still on-link.
+            let status = if ia_addresses(&src.packet).all(|addr| lease.prefix.contains(addr)) {
+                Status::Success
+            } else {
+                Status::NotOnLink
+            };
+            msg.opts_mut().insert(DhcpOption::StatusCode(StatusCode {
+                status,
+                msg: String::new(),
+            }));
+        }
+        MessageType::Release => {
+            // Leases are owned by carbide, there is nothing for us to free.
+            msg.opts_mut().insert(DhcpOption::StatusCode(StatusCode {
+                status: Status::Success,
+                msg: String::new(),
+            }));
+        }
+        _ => {
+            for opt in src.packet.opts().iter() {
+                match opt {
+                    DhcpOption::IANA(iana) => {
+                        msg.opts_mut()
+                            .insert(DhcpOption::IANA(ia_na_reply(iana, lease, config)));
+                    }
+                    DhcpOption::IAPD(iapd) => {
+                        msg.opts_mut().insert(DhcpOption::IAPD(ia_pd_reply(iapd)));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(msg)
+}
+
+/// https://www.rfc-editor.org/rfc/rfc8415#section-21.4
+fn ia_na_reply(requested: &IANA, lease: &Dhcpv6Lease, config: &Config) -> IANA {
+    let mut opts = DhcpOptions::new();
+    opts.insert(DhcpOption::IAAddr(IAAddr {
+        addr: lease.address,
+        preferred_life: config.dhcp_config.lease_time_secs,
+        valid_life: config.dhcp_config.lease_time_secs,
+        opts: DhcpOptions::new(),
+    }));
+
+    // Addresses the client holds but may no longer use, same as a DHCPv4 NAK.
+    for opt in requested.opts.iter() {
+        if let DhcpOption::IAAddr(held) = opt
+            && held.addr != lease.address
+        {
+            opts.insert(DhcpOption::IAAddr(IAAddr {
+                addr: held.addr,
+                preferred_life: 0,
+                valid_life: 0,
+                opts: DhcpOptions::new(),
+            }));
+        }
+    }
+
+    IANA {
+        id: requested.id,
+        t1: config.dhcp_config.renewal_time_secs,
+        t2: config.dhcp_config.rebinding_time_secs,
+        opts,
+    }
+}
+
+/// Carbide doesn't delegate prefixes, so every IA_PD is returned without prefixes and with a
+/// NoPrefixAvail status.
+/// https://www.rfc-editor.org/rfc/rfc8415#section-18.3.2
+fn ia_pd_reply(requested: &IAPD) -> IAPD {
+    let mut opts = DhcpOptions::new();
+    opts.insert(DhcpOption::StatusCode(StatusCode {
+        status: Status::NoPrefixAvail,
+        msg: "Prefix delegation is not supported".to_string(),
+    }));
+    IAPD {
+        id: requested.id,
+        t1: 0,
+        t2: 0,
+        opts,
+    }
+}
+
+/// All addresses in the IA_NAs of a client message.
+fn ia_addresses(packet: &Message) -> impl Iterator<Item = Ipv6Addr> + '_ {
+    packet
+        .opts()
+        .iter()
+        .filter_map(|opt| match opt {
+            DhcpOption::IANA(iana) => Some(iana.opts.iter()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|opt| match opt {
+            DhcpOption::IAAddr(addr) => Some(addr.addr),
+            _ => None,
+        })
+}
+
+/// DUID-LL built from a locally administered MAC which embeds `carbide_dhcp_server`. That
+/// address is unique per DPU (its loopback) and per site in controller mode, and keeping it
+/// derived from config means the DUID survives restarts without persisting anything.
+pub fn server_duid(config: &Config) -> Vec<u8> {
+    let mut duid = Vec::with_capacity(10);
+    duid.extend_from_slice(&DUID_TYPE_LL.to_be_bytes());
+    duid.extend_from_slice(&HTYPE_ETHERNET.to_be_bytes());
+    duid.extend_from_slice(&[0x02, 0x00]);
+    duid.extend_from_slice(&config.dhcp_config.carbide_dhcp_server.octets());
+    duid
+}
+
+fn hex_string(data: &[u8]) -> String {
+    data.iter().map(|x| format!("{x:02x}")).collect()
+}
@@ -0,0 +1,277 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::io::Read;
+use std::net::{Ipv6Addr, SocketAddrV6};
+use std::time::{Duration, Instant};
+
+use ipnetwork::{IpNetwork, Ipv6Network};
+use tokio::io::unix::AsyncFd;
+use tokio_util::sync::CancellationToken;
+
+use crate::errors::DhcpError;
+use crate::{Config, packet_handler, util};
+
+// https://www.rfc-editor.org/rfc/rfc4861#section-4.2
+const ICMPV6_ROUTER_SOLICITATION: u8 = 133;
+const ICMPV6_ROUTER_ADVERTISEMENT: u8 = 134;
+
+const OPTION_SOURCE_LINK_LAYER_ADDRESS: u8 = 1;
+const OPTION_PREFIX_INFORMATION: u8 = 3;
+const OPTION_MTU: u8 = 5;
+// https://www.rfc-editor.org/rfc/rfc8106#section-5.1
+const OPTION_RDNSS: u8 = 25;
+
+/// Addresses (M) and everything else (O) come from our DHCPv6 server.
+const FLAG_MANAGED: u8 = 0x80;
+const FLAG_OTHER_CONFIG: u8 = 0x40;
+const PREFIX_FLAG_ON_LINK: u8 = 0x80;
+const PREFIX_FLAG_AUTONOMOUS: u8 = 0x40;
+
+const CUR_HOP_LIMIT: u8 = 64;
+/// AdvDefaultLifetime default, 3 * MaxRtrAdvInterval.
+const ROUTER_LIFETIME_SECS: u16 = 1800;
+/// Between the MinRtrAdvInterval and MaxRtrAdvInterval defaults.
+const UNSOLICITED_INTERVAL: Duration = Duration::from_secs(200);
+/// MIN_DELAY_BETWEEN_RAS, so a chatty host can't make us flood the link.
+const MIN_DELAY_BETWEEN_RAS: Duration = Duration::from_secs(3);
+/// Receivers drop neighbor discovery packets with any other hop limit.
+const ND_HOP_LIMIT: u32 = 255;
+
+const ALL_NODES: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1);
+const ALL_ROUTERS: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 2);
+
+/// Router advertisement sent on a dual-stack DPU tenant interface. The DPU is the host's
+/// first hop router, so hosts learn their default route from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouterAdvertisement {
+    pub prefix: Ipv6Network,
+    pub mtu: u16,
+    pub source_mac: Option<[u8; 6]>,
+    pub nameservers: Vec<Ipv6Addr>,
+    pub lifetime_secs: u32,
+}
+
+impl RouterAdvertisement {
+    /// None if host_config has no IPv6 details for this interface.
+    pub fn from_config(interface: &str, config: &Config) -> Option<Self> {
+        let host_config = config.host_config.as_ref()?;
+        let ipv6 = host_config
+            .host_ip_addresses
+            .get(interface)?
+            .ipv6
+            .as_ref()?;
+        let prefix = match ipv6.prefix.parse::<IpNetwork>() {
+            Ok(IpNetwork::V6(prefix)) => prefix,
+            _ => {
+                tracing::error!(
+                    "Not sending router advertisements on {interface}, invalid IPv6 prefix: {}",
+                    ipv6.prefix
+                );
+                return None;
+            }
+        };
+
+        Some(RouterAdvertisement {
+            prefix,
+            mtu: packet_handler::get_mtu(interface, Some(host_config)),
+            source_mac: util::get_interface_mac(interface)
+                .inspect_err(|e| {
+                    tracing::warn!("No source link-layer address for {interface}: {e}")
+                })
+                .ok(),
+            nameservers: config.dhcp_config.carbide_nameservers_ipv6.clone(),
+            lifetime_secs: config.dhcp_config.lease_time_secs,
+        })
+    }
+
+    /// https://www.rfc-editor.org/rfc/rfc4861#section-4.2
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(128);
+        buf.push(ICMPV6_ROUTER_ADVERTISEMENT);
+        buf.push(0); // code
+        buf.extend_from_slice(&[0, 0]); // checksum, filled in by the kernel
+        buf.push(CUR_HOP_LIMIT);
+        buf.push(FLAG_MANAGED | FLAG_OTHER_CONFIG);
+        buf.extend_from_slice(&ROUTER_LIFETIME_SECS.to_be_bytes());
+        buf.extend_from_slice(&0u32.to_be_bytes()); // reachable time, unspecified
+        buf.extend_from_slice(&0u32.to_be_bytes()); // retrans timer, unspecified
+
+        // Option lengths are in units of 8 octets.
+        if let Some(mac) = self.source_mac {
+            buf.extend_from_slice(&[OPTION_SOURCE_LINK_LAYER_ADDRESS, 1]);
+            buf.extend_from_slice(&mac);
+        }
+
+        buf.extend_from_slice(&[OPTION_MTU, 1, 0, 0]);
+        buf.extend_from_slice(&u32::from(self.mtu).to_be_bytes());
+
+        // Nothing else shares a /128 with the host, so there is nothing to put on-link.
+        if self.prefix.prefix() < 128 {
+            let mut flags = PREFIX_FLAG_ON_LINK;
+            // SLAAC needs a 64 bit interface identifier.
+            if self.prefix.prefix() == 64 {
+                flags |= PREFIX_FLAG_AUTONOMOUS;
+            }
+            buf.extend_from_slice(&[OPTION_PREFIX_INFORMATION, 4, self.prefix.prefix(), flags]);
+            buf.extend_from_slice(&self.lifetime_secs.to_be_bytes()); // valid
+            buf.extend_from_slice(&self.lifetime_secs.to_be_bytes()); // preferred
+            buf.extend_from_slice(&0u32.to_be_bytes()); // reserved
+            buf.extend_from_slice(&self.prefix.network().octets());
+        }
+
+        if !self.nameservers.is_empty() {
+            let len = 1 + 2 * self.nameservers.len() as u8;
+            buf.extend_from_slice(&[OPTION_RDNSS, len, 0, 0]);
+            buf.extend_from_slice(&self.lifetime_secs.to_be_bytes());
+            for nameserver in &self.nameservers {
+                buf.extend_from_slice(&nameserver.octets());
+            }
+        }
+
+        buf
+    }
+}
+
+/// Advertise on `interface` until `cancel` fires: once at startup, every
+/// `UNSOLICITED_INTERVAL` and in answer to router solicitations.
+pub async fn run(interface: String, ra: RouterAdvertisement, cancel: CancellationToken) {
+    let (socket, ifindex) = match get_icmpv6_socket(&interface) {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::error!("Not sending router advertisements on {interface}: {e}");
+            return;
+        }
+    };
+    tracing::info!("Sending router advertisements on {interface}: {ra:?}");
+
+    let packet = ra.encode();
+    let destination = SocketAddrV6::new(ALL_NODES, 0, 0, ifindex).into();
+    let mut interval = tokio::time::interval(UNSOLICITED_INTERVAL);
+    let mut last_sent: Option<Instant> = None;
+    let mut buf = [0; 1500];
+
+    loop {
+        let solicited = tokio::select! {
+            _ = cancel.cancelled() => {
+                tracing::info!("Router advertisements on {interface} received cancellation");
+                break;
+            }
+            _ = interval.tick() => false,
+            result = socket.readable() => {
+                let mut guard = match result {
+                    Ok(guard) => guard,
+                    Err(e) => {
+                        tracing::error!("Router solicitation socket on {interface} failed: {e}");
+                        break;
+                    }
+                };
+                match guard.try_io(|inner| inner.get_ref().read(&mut buf)) {
+                    Ok(Ok(len)) => {
+                        len >= 4 && buf[0] == ICMPV6_ROUTER_SOLICITATION && buf[1] == 0
+                    }
+                    Ok(Err(e)) => {
+                        tracing::error!("Reading router solicitation on {interface} failed: {e}");
+                        continue;
+                    }
+                    // Spurious wakeup.
+                    Err(_would_block) => continue,
+                }
+            }
+        };
+
+        if solicited && last_sent.is_some_and(|t| t.elapsed() < MIN_DELAY_BETWEEN_RAS) {
+            continue;
+        }
+
+        match socket.get_ref().send_to(&packet, &destination) {
+            Ok(_) => last_sent = Some(Instant::now()),
+            Err(e) => tracing::error!("Sending router advertisement on {interface} failed: {e}"),
+        }
+    }
+}
+
+fn get_icmpv6_socket(interface: &str) -> Result<(AsyncFd<socket2::Socket>, u32), DhcpError> {
+    let ifindex = util::get_interface_index(interface)?;
+    let socket = socket2::Socket::new(
+        socket2::Domain::IPV6,
+        socket2::Type::RAW,
+        Some(socket2::Protocol::ICMPV6),
+    )?;
+    socket.bind_device(Some(interface.as_bytes()))?;
+    socket.set_nonblocking(true)?;
+    socket.set_multicast_hops_v6(ND_HOP_LIMIT)?;
+    socket.set_unicast_hops_v6(ND_HOP_LIMIT)?;
+    socket.set_multicast_loop_v6(false)?;
+    socket.set_multicast_if_v6(ifindex)?;
+    socket.join_multicast_v6(&ALL_ROUTERS, ifindex)?;
+    Ok((AsyncFd::new(socket)?, ifindex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_slaac_prefix() {
+        let ra = RouterAdvertisement {
+            prefix: "2001:db8:1::/64".parse().unwrap(),
+            mtu: 9000,
+            source_mac: Some([0x02, 0x00, 0x0a, 0x00, 0x00, 0x01]),
+            nameservers: vec!["2001:db8::53".parse().unwrap()],
+            lifetime_secs: 3600,
+        };
+
+        #[rustfmt::skip]
+        let expected: Vec<u8> = vec![
+            // header
+            134, 0, 0, 0, 64, 0xc0, 0x07, 0x08, 0, 0, 0, 0, 0, 0, 0, 0,
+            // source link-layer address
+            1, 1, 0x02, 0x00, 0x0a, 0x00, 0x00, 0x01,
+            // mtu
+            5, 1, 0, 0, 0, 0, 0x23, 0x28,
+            // prefix information, on-link and autonomous
+            3, 4, 64, 0xc0, 0, 0, 0x0e, 0x10, 0, 0, 0x0e, 0x10, 0, 0, 0, 0,
+            0x20, 0x01, 0x0d, 0xb8, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            // rdnss
+            25, 3, 0, 0, 0, 0, 0x0e, 0x10,
+            0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x53,
+        ];
+        assert_eq!(ra.encode(), expected);
+    }
+
+    #[test]
+    fn test_encode_fnn_prefix() {
+        let ra = RouterAdvertisement {
+            prefix: "2001:db8::/127".parse().unwrap(),
+            mtu: 1500,
+            source_mac: None,
+            nameservers: vec![],
+            lifetime_secs: 3600,
+        };
+        let encoded = ra.encode();
+
+        // header, mtu, prefix information
+        assert_eq!(encoded.len(), 16 + 8 + 32);
+        assert_eq!(encoded[24..28], [3, 4, 127, PREFIX_FLAG_ON_LINK]);
+
+        let host_route = RouterAdvertisement {
+            prefix: "2001:db8::1/128".parse().unwrap(),
+            ..ra
+        };
+        assert_eq!(host_route.encode().len(), 16 + 8);
+    }
+}
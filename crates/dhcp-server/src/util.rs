@@ -14,6 +14,8 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::net::Ipv6Addr;
+
 use rpc::forge::DhcpRecord;
 use tokio::net::UdpSocket;
 
@@ -21,6 +23,9 @@ use crate::Config;
 use crate::errors::DhcpError;
 use crate::vendor_class::{MachineArchitecture, VendorClass};
 
+// https://www.rfc-editor.org/rfc/rfc8415#section-7.1
+const ALL_DHCP_RELAY_AGENTS_AND_SERVERS: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 1, 2);
+
 macro_rules! socket_opr {
     ($socket:expr, $statement:expr, $retry:expr) => {
         if let Err(e) = $statement {
@@ -88,7 +93,7 @@ pub async fn get_socket(listen_address: core::net::SocketAddr, interface: String
         // Create a socket2.socket. std and tokio sockets do not support advance options like
         // reuseaddr to be set.
         let socket = match socket2::Socket::new(
-            socket2::Domain::IPV4,
+            socket2::Domain::for_address(listen_address),
             socket2::Type::DGRAM,
             Some(socket2::Protocol::UDP),
         ) {
@@ -102,9 +107,15 @@ pub async fn get_socket(listen_address: core::net::SocketAddr, interface: String
 
         socket_opr!(socket, socket.set_reuse_address(true), retry);
         socket_opr!(socket, socket.set_nonblocking(true), retry);
+        if listen_address.is_ipv6() {
+            // DHCPv4 has its own socket.
+            socket_opr!(socket, socket.set_only_v6(true), retry);
+        }
         socket_opr!(socket, socket.bind(&listen_address.into()), retry);
-        // Not for listening, but allowed for sending.
-        socket_opr!(socket, socket.set_broadcast(true), retry);
+        if listen_address.is_ipv4() {
+            // Not for listening, but allowed for sending.
+            socket_opr!(socket, socket.set_broadcast(true), retry);
+        }
 
         let mut retries_left = 10;
         while retries_left > 0 && socket.bind_device(Some(interface.as_bytes())).is_err() {
@@ -121,3 +132,41 @@ pub async fn get_socket(listen_address: core::net::SocketAddr, interface: String
     }
     panic!("Could not create socket successfully.");
 }
+
+/// Like `get_socket`, and also join All_DHCP_Relay_Agents_and_Servers, which is where clients on
+/// the link send to. Relays send to our unicast address, so failing to join is not fatal.
+pub async fn get_dhcpv6_socket(
+    listen_address: core::net::SocketAddr,
+    interface: String,
+) -> UdpSocket {
+    let socket = get_socket(listen_address, interface.clone()).await;
+    if let Err(e) = get_interface_index(&interface).and_then(|ifindex| {
+        Ok(socket.join_multicast_v6(&ALL_DHCP_RELAY_AGENTS_AND_SERVERS, ifindex)?)
+    }) {
+        tracing::warn!("Could not join DHCPv6 multicast group on {interface}: {e}");
+    }
+    socket
+}
+
+/// Index of the interface, needed for IPv6 multicast and link-local scope ids.
+pub fn get_interface_index(interface: &str) -> Result<u32, DhcpError> {
+    let index = std::fs::read_to_string(format!("/sys/class/net/{interface}/ifindex"))?;
+    index.trim().parse().map_err(|e| {
+        DhcpError::GenericError(format!("Invalid ifindex for interface {interface}: {e}"))
+    })
+}
+
+/// MAC address of the interface, advertised as source link-layer address in router
+/// advertisements.
+pub fn get_interface_mac(interface: &str) -> Result<[u8; 6], DhcpError> {
+    let address = std::fs::read_to_string(format!("/sys/class/net/{interface}/address"))?;
+    let octets = address
+        .trim()
+        .split(':')
+        .map(|x| u8::from_str_radix(x, 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|e| DhcpError::GenericError(format!("Invalid MAC for {interface}: {e}")))?;
+    octets.try_into().map_err(|_| {
+        DhcpError::GenericError(format!("Invalid MAC for {interface}: {}", address.trim()))
+    })
+}
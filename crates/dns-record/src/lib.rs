@@ -22,6 +22,7 @@ use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 pub mod constants;
+pub mod reverse;
 
 /// Wrapper type for time intervals in seconds
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
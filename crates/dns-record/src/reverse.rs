@@ -0,0 +1,248 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Reverse (PTR) zone names
+//!
+//! Carbide synthesizes `in-addr.arpa` and `ip6.arpa` zones for the prefixes of its
+//! network segments. Reverse zones can only be cut on label boundaries, which are
+//! octets for IPv4 and nibbles for IPv6, so a prefix that doesn't end on one is
+//! served as several zones of the next longer boundary.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use ipnetwork::IpNetwork;
+
+pub const IPV4_REVERSE_SUFFIX: &str = "in-addr.arpa";
+pub const IPV6_REVERSE_SUFFIX: &str = "ip6.arpa";
+
+/// Name of the PTR record of an address, without the trailing dot.
+///
+/// `192.0.2.10` becomes `10.2.0.192.in-addr.arpa`.
+pub fn reverse_name(address: IpAddr) -> String {
+    reverse_zone_name(IpNetwork::from(address))
+}
+
+/// Name of the reverse zone of a label aligned network, without the trailing dot.
+///
+/// `192.0.2.0/24` becomes `2.0.192.in-addr.arpa`. Bits after the last full label
+/// are ignored.
+pub fn reverse_zone_name(network: IpNetwork) -> String {
+    let (mut labels, suffix): (Vec<String>, _) = match network.network() {
+        IpAddr::V4(v4) => (
+            v4.octets()
+                .iter()
+                .take(network.prefix() as usize / 8)
+                .map(u8::to_string)
+                .collect(),
+            IPV4_REVERSE_SUFFIX,
+        ),
+        IpAddr::V6(v6) => (
+            v6.octets()
+                .iter()
+                .flat_map(|octet| [octet >> 4, octet & 0xf])
+                .take(network.prefix() as usize / 4)
+                .map(|nibble| format!("{nibble:x}"))
+                .collect(),
+            IPV6_REVERSE_SUFFIX,
+        ),
+    };
+    labels.reverse();
+    labels.push(suffix.to_string());
+    labels.join(".")
+}
+
+/// Network a reverse name stands for. A name with all labels present is a host
+/// network (`/32` or `/128`), i.e. the name of a PTR record.
+///
+/// Returns None for names outside of `in-addr.arpa` and `ip6.arpa`, and for the
+/// suffixes themselves.
+pub fn parse_reverse_name(name: &str) -> Option<IpNetwork> {
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+
+    if let Some(labels) = reverse_labels(&name, IPV4_REVERSE_SUFFIX) {
+        if labels.len() > 4 {
+            return None;
+        }
+        let mut octets = [0u8; 4];
+        for (octet, label) in octets.iter_mut().zip(&labels) {
+            // Reject `01` and friends, they would make two names for one address
+            *octet = label
+                .parse()
+                .ok()
+                .filter(|o: &u8| o.to_string() == *label)?;
+        }
+        return IpNetwork::new(Ipv4Addr::from(octets).into(), 8 * labels.len() as u8).ok();
+    }
+
+    if let Some(labels) = reverse_labels(&name, IPV6_REVERSE_SUFFIX) {
+        if labels.len() > 32 {
+            return None;
+        }
+        let mut address = 0u128;
+        for (i, label) in labels.iter().enumerate() {
+            if label.len() != 1 {
+                return None;
+            }
+            let nibble = u128::from_str_radix(label, 16).ok()?;
+            address |= nibble << (124 - 4 * i);
+        }
+        return IpNetwork::new(Ipv6Addr::from(address).into(), 4 * labels.len() as u8).ok();
+    }
+
+    None
+}
+
+/// Address a PTR record name stands for. Returns None for names of zones.
+pub fn parse_reverse_address(name: &str) -> Option<IpAddr> {
+    let network = parse_reverse_name(name)?;
+    let host_prefix = if network.is_ipv4() { 32 } else { 128 };
+    (network.prefix() == host_prefix).then(|| network.ip())
+}
+
+/// Labels in front of `suffix`, most significant first.
+fn reverse_labels<'a>(name: &'a str, suffix: &str) -> Option<Vec<&'a str>> {
+    let labels = name.strip_suffix(suffix)?.strip_suffix('.')?;
+    Some(labels.split('.').rev().collect())
+}
+
+/// Reverse zones that together cover `prefix`.
+///
+/// The prefix length is rounded up to the next label boundary, so a `/24` is one zone
+/// and a `/22` is four `/24` zones. That is at most 128 zones for IPv4 and 8 for IPv6.
+/// Rounding down instead would claim authority over addresses carbide doesn't own.
+pub fn reverse_zones(prefix: IpNetwork) -> Vec<IpNetwork> {
+    let label_bits = match prefix {
+        IpNetwork::V4(_) => 8,
+        IpNetwork::V6(_) => 4,
+    };
+    let zone_prefix = prefix.prefix().div_ceil(label_bits).max(1) * label_bits;
+    let split_bits = zone_prefix - prefix.prefix();
+
+    match prefix.network() {
+        IpAddr::V4(network) => {
+            let network = u32::from(network);
+            (0..1u32 << split_bits)
+                .filter_map(|i| {
+                    let zone = network | (i << (32 - zone_prefix));
+                    IpNetwork::new(Ipv4Addr::from(zone).into(), zone_prefix).ok()
+                })
+                .collect()
+        }
+        IpAddr::V6(network) => {
+            let network = u128::from(network);
+            (0..1u128 << split_bits)
+                .filter_map(|i| {
+                    let zone = network | (i << (128 - zone_prefix));
+                    IpNetwork::new(Ipv6Addr::from(zone).into(), zone_prefix).ok()
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_name() {
+        assert_eq!(
+            reverse_name("192.0.2.10".parse().unwrap()),
+            "10.2.0.192.in-addr.arpa"
+        );
+        assert_eq!(
+            reverse_name("2001:db8::1".parse().unwrap()),
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+        );
+        assert_eq!(
+            reverse_zone_name("192.0.2.0/24".parse().unwrap()),
+            "2.0.192.in-addr.arpa"
+        );
+        assert_eq!(
+            reverse_zone_name("2001:db8:0:10::/64".parse().unwrap()),
+            "0.1.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+        );
+    }
+
+    #[test]
+    fn test_parse_reverse_name() {
+        for network in [
+            "192.0.2.10/32",
+            "192.0.2.0/24",
+            "10.0.0.0/8",
+            "2001:db8::1/128",
+            "2001:db8:0:10::/64",
+        ] {
+            let network: IpNetwork = network.parse().unwrap();
+            let name = format!("{}.", reverse_zone_name(network).to_uppercase());
+            assert_eq!(parse_reverse_name(&name), Some(network), "{name}");
+        }
+
+        for name in [
+            "in-addr.arpa",
+            "ip6.arpa.",
+            "example.com",
+            "5.4.3.2.1.in-addr.arpa",
+            "256.2.0.192.in-addr.arpa",
+            "01.2.0.192.in-addr.arpa",
+            "10.2.0.192.xin-addr.arpa",
+            "10.8.b.d.0.1.0.0.2.ip6.arpa",
+            "g.8.b.d.0.1.0.0.2.ip6.arpa",
+        ] {
+            assert_eq!(parse_reverse_name(name), None, "{name}");
+        }
+
+        assert_eq!(
+            parse_reverse_address("10.2.0.192.in-addr.arpa."),
+            Some("192.0.2.10".parse().unwrap())
+        );
+        assert_eq!(parse_reverse_address("2.0.192.in-addr.arpa."), None);
+    }
+
+    #[test]
+    fn test_reverse_zones() {
+        let zones = |prefix: &str| {
+            reverse_zones(prefix.parse().unwrap())
+                .into_iter()
+                .map(|zone| zone.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(zones("192.0.2.0/24"), ["192.0.2.0/24"]);
+        assert_eq!(
+            zones("192.0.4.0/22"),
+            [
+                "192.0.4.0/24",
+                "192.0.5.0/24",
+                "192.0.6.0/24",
+                "192.0.7.0/24"
+            ]
+        );
+        assert_eq!(zones("192.0.2.64/26").len(), 64);
+        assert_eq!(zones("192.0.2.64/26")[0], "192.0.2.64/32");
+        assert_eq!(zones("2001:db8:0:10::/64"), ["2001:db8:0:10::/64"]);
+        assert_eq!(
+            zones("2001:db8:0:10::/62"),
+            [
+                "2001:db8:0:10::/64",
+                "2001:db8:0:11::/64",
+                "2001:db8:0:12::/64",
+                "2001:db8:0:13::/64"
+            ]
+        );
+    }
+}
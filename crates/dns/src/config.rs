@@ -14,6 +14,7 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 
 use forge_tls::client_config::ClientCert;
@@ -52,6 +53,60 @@ pub struct Config {
         deserialize_with = "deserialize_uri"
     )]
     pub otlp_endpoint: http::Uri,
+    /// If set, also act as hidden primary for carbide's zones: answer SOA, AXFR and IXFR
+    /// queries and NOTIFY secondaries when zones change.
+    #[serde(default)]
+    pub primary: Option<PrimaryConfig>,
+    /// Answer queries from a local copy of the zones instead of asking carbide-api for each
+    #[serde(default)]
+    pub record_cache: RecordCacheConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordCacheConfig {
+    /// If disabled (the default), every query is sent to carbide-api
+    #[serde(default = "Defaults::record_cache_enabled")]
+    pub enabled: bool,
+    /// How often the local copy is synced with carbide-api. It is kept when carbide-api
+    /// can't be reached.
+    #[serde(default = "Defaults::record_cache_sync_interval_secs")]
+    pub sync_interval_secs: u64,
+}
+
+impl Default for RecordCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: Defaults::record_cache_enabled(),
+            sync_interval_secs: Defaults::record_cache_sync_interval_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PrimaryConfig {
+    /// Address to serve zone transfers on, over both UDP and TCP
+    pub listen: SocketAddr,
+    /// Secondaries to NOTIFY. They are always allowed to transfer zones, in addition to the
+    /// domain's `allow_axfr_from` metadata.
+    #[serde(default)]
+    pub secondaries: Vec<SocketAddr>,
+    /// How often zones are rebuilt from carbide-api
+    #[serde(default = "Defaults::zone_refresh_interval_secs")]
+    pub zone_refresh_interval_secs: u64,
+    /// How many changes per zone are kept to answer IXFR. Older serials get a full transfer.
+    #[serde(default = "Defaults::ixfr_history")]
+    pub ixfr_history: usize,
+}
+
+impl PrimaryConfig {
+    pub fn new(listen: SocketAddr) -> Self {
+        Self {
+            listen,
+            secondaries: vec![],
+            zone_refresh_interval_secs: Defaults::zone_refresh_interval_secs(),
+            ixfr_history: Defaults::ixfr_history(),
+        }
+    }
 }
 
 pub struct Defaults;
@@ -83,6 +138,18 @@ impl Defaults {
     pub fn client_key() -> PathBuf {
         "/var/run/secrets/spiffe.io/tls.key".into()
     }
+    pub fn zone_refresh_interval_secs() -> u64 {
+        30
+    }
+    pub fn ixfr_history() -> usize {
+        64
+    }
+    pub fn record_cache_enabled() -> bool {
+        false
+    }
+    pub fn record_cache_sync_interval_secs() -> u64 {
+        10
+    }
     pub fn ns_ip_address() -> String {
         let address = local_ip().expect("Failed to get local IP address");
         tracing::debug!(
@@ -114,6 +181,8 @@ impl Default for Config {
             client_key_path: Defaults::client_key(),
             otlp_endpoint: Defaults::otlp_endpoint(),
             legacy_listen: None,
+            primary: None,
+            record_cache: RecordCacheConfig::default(),
         }
     }
 }
@@ -23,21 +23,22 @@
 
 use std::collections::HashMap;
 use std::iter;
-use std::net::{IpAddr, SocketAddr};
+use std::net::IpAddr;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use eyre::Report;
-use metrics_endpoint::{MetricsEndpointConfig, new_metrics_setup, run_metrics_endpoint};
 use opentelemetry::KeyValue;
 use opentelemetry::metrics::{Counter, Meter};
 use rpc::forge_tls_client::{ApiConfig, ForgeClientT, ForgeTlsClient};
+use rpc::protos::dns::DnsResourceRecord;
 use rpc::protos::forge;
 use tokio::net::{TcpListener, UdpSocket};
 use tokio::sync::{Mutex, RwLock};
 use tracing::{error, info, warn};
 use trust_dns_resolver::proto::op::{Header, ResponseCode};
+use trust_dns_resolver::proto::rr::rdata::PTR;
 use trust_dns_resolver::proto::rr::{DNSClass, Name, RData};
 use trust_dns_server::ServerFuture;
 use trust_dns_server::authority::MessageResponseBuilder;
@@ -46,6 +47,7 @@ use trust_dns_server::proto::rr::{Record, RecordType};
 use trust_dns_server::server::{Request, RequestHandler, ResponseHandler, ResponseInfo};
 
 use crate::config::Config;
+use crate::record_cache::RecordCache;
 
 /// Metrics for the legacy DNS server, created from an OpenTelemetry `Meter`.
 struct LegacyDnsMetrics {
@@ -83,6 +85,7 @@ pub struct LegacyDnsServer {
     negative_cache: Arc<RwLock<HashMap<CacheKey, NegativeEntry>>>,
     negative_ttl: Duration,
     metrics: LegacyDnsMetrics,
+    record_cache: Option<Arc<RecordCache>>,
 }
 
 #[derive(Debug)]
@@ -119,9 +122,10 @@ impl RequestHandler for LegacyDnsServer {
         };
 
         match qtype {
-            A | AAAA => {
+            A | AAAA | RecordType::PTR => {
                 let q_type_num = match qtype {
                     AAAA => 28,
+                    RecordType::PTR => 12,
                     _ => 1,
                 };
 
@@ -133,7 +137,25 @@ impl RequestHandler for LegacyDnsServer {
                         .map(|e| e.reason_code)
                 };
 
-                let (response_code, record) = if let Some(code) = cached {
+                let from_record_cache = match &self.record_cache {
+                    Some(record_cache) => record_cache.lookup(&qname, &qtype.to_string()).await,
+                    None => None,
+                };
+
+                let answer = |rdata: RData| {
+                    Record::new()
+                        .set_ttl(30)
+                        .set_name(Name::from(request_info.query.name()))
+                        .set_record_type(rdata.record_type())
+                        .set_dns_class(DNSClass::IN)
+                        .set_data(Some(rdata))
+                        .clone()
+                };
+
+                let (response_code, record) = if let Some(response) = from_record_cache {
+                    let (code, rdata) = Self::answer_from_records(qtype, &response.records);
+                    (code, rdata.map(answer))
+                } else if let Some(code) = cached {
                     self.metrics
                         .negative_cache_hit
                         .add(1, &[KeyValue::new("response_code", format!("{code:?}"))]);
@@ -155,23 +177,14 @@ impl RequestHandler for LegacyDnsServer {
 
                     info!("Sending {} to api server", request_info.query.original());
 
-                    match Self::retrieve_record(self.forge_client.clone(), carbide_dns_request)
-                        .await
+                    match Self::retrieve_record(
+                        self.forge_client.clone(),
+                        carbide_dns_request,
+                        qtype,
+                    )
+                    .await
                     {
-                        Ok(ip) => {
-                            let (rtype, rdata) = match ip {
-                                IpAddr::V4(v4) => (A, RData::A(v4.into())),
-                                IpAddr::V6(v6) => (AAAA, RData::AAAA(v6.into())),
-                            };
-                            let dns_record = Record::new()
-                                .set_ttl(30)
-                                .set_name(Name::from(request_info.query.name()))
-                                .set_record_type(rtype)
-                                .set_dns_class(DNSClass::IN)
-                                .set_data(Some(rdata))
-                                .clone();
-                            (ResponseCode::NoError, Some(dns_record))
-                        }
+                        Ok(rdata) => (ResponseCode::NoError, Some(answer(rdata))),
                         Err(e) => {
                             warn!(
                                 "Unable to find record: {} error was {}",
@@ -238,19 +251,22 @@ impl LegacyDnsServer {
         forge_client: Arc<Mutex<ForgeClientT>>,
         negative_ttl: Duration,
         meter: &Meter,
+        record_cache: Option<Arc<RecordCache>>,
     ) -> Self {
         Self {
             forge_client,
             negative_cache: Arc::new(RwLock::new(HashMap::new())),
             negative_ttl,
             metrics: LegacyDnsMetrics::new(meter),
+            record_cache,
         }
     }
 
     async fn retrieve_record(
         forge_client: Arc<Mutex<ForgeClientT>>,
         request: tonic::Request<forge::dns_message::DnsQuestion>,
-    ) -> Result<IpAddr, tonic::Status> {
+        qtype: RecordType,
+    ) -> Result<RData, tonic::Status> {
         let mut client = forge_client.lock().await;
         #[allow(deprecated)]
         let response = client.lookup_record_legacy(request).await?.into_inner();
@@ -261,12 +277,42 @@ impl LegacyDnsServer {
             .rrs
             .first()
             .ok_or_else(|| tonic::Status::internal("Resource Record list is empty".to_string()))?;
-        let rdata = record.rdata.as_deref().unwrap_or("");
+        Self::parse_rdata(qtype, record.rdata.as_deref().unwrap_or(""))
+    }
+
+    /// Answer from the records the record cache holds for the queried name. carbide-api
+    /// returns every record of a name, so only the ones of the queried type are used.
+    fn answer_from_records(
+        qtype: RecordType,
+        records: &[DnsResourceRecord],
+    ) -> (ResponseCode, Option<RData>) {
+        if records.is_empty() {
+            return (ResponseCode::NXDomain, None);
+        }
+        let rdata = records
+            .iter()
+            .filter(|record| record.qtype == qtype.to_string())
+            .find_map(|record| Self::parse_rdata(qtype, &record.content).ok());
+        (ResponseCode::NoError, rdata)
+    }
+
+    fn parse_rdata(qtype: RecordType, rdata: &str) -> Result<RData, tonic::Status> {
+        // PTR records point at the forward name of the address
+        if qtype == RecordType::PTR {
+            let name = Name::from_str(rdata).map_err(|_e| {
+                tonic::Status::internal(format!("Can not parse record data \"{rdata}\" as name"))
+            })?;
+            return Ok(RData::PTR(PTR(name)));
+        }
+
         let ip = IpAddr::from_str(rdata).map_err(|_e| {
             tonic::Status::internal(format!("Can not parse record data \"{rdata}\" as IP"))
         })?;
 
-        Ok(ip)
+        Ok(match ip {
+            IpAddr::V4(v4) => RData::A(v4.into()),
+            IpAddr::V6(v6) => RData::AAAA(v6.into()),
+        })
     }
 
     pub async fn run(config: Config, listen: std::net::SocketAddr) -> Result<(), Report> {
@@ -286,26 +332,19 @@ impl LegacyDnsServer {
         // TODO: make negative_cache_ttl configurable via Config
         let negative_ttl = Duration::from_secs(120);
 
-        let metrics_setup = new_metrics_setup("carbide-dns", "carbide", true)?;
-
-        // Must keep meter_provider alive for the lifetime of the server,
+        // Must keep metrics_setup alive for the lifetime of the server,
         // otherwise SdkMeterProvider::drop() shuts down the Prometheus exporter.
-        let _metrics_guard = metrics_setup.meter_provider;
-
-        let metrics_config = MetricsEndpointConfig {
-            address: SocketAddr::from_str("0.0.0.0:8844").expect("Invalid address socket address"),
-            registry: metrics_setup.registry,
-            health_controller: Some(metrics_setup.health_controller),
-        };
-
-        tokio::spawn(async move {
-            tracing::info!("Spawning metrics endpoint on {}", metrics_config.address);
-            if let Err(e) = run_metrics_endpoint(&metrics_config).await {
-                tracing::error!("Metrics endpoint error: {}", e);
-            }
+        let metrics_setup = crate::spawn_metrics_endpoint()?;
+
+        let record_cache = config.record_cache.enabled.then(|| {
+            RecordCache::spawn(
+                client.clone(),
+                Duration::from_secs(config.record_cache.sync_interval_secs),
+                &metrics_setup.meter,
+            )
         });
 
-        let api = LegacyDnsServer::new(client, negative_ttl, &metrics_setup.meter);
+        let api = LegacyDnsServer::new(client, negative_ttl, &metrics_setup.meter, record_cache);
 
         let cache = api.negative_cache.clone();
 
@@ -15,13 +15,20 @@
  * limitations under the License.
  */
 
+use std::net::SocketAddr;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use config::Config;
 use eyre::{Report, WrapErr};
+use metrics_endpoint::{
+    MetricsEndpointConfig, MetricsSetup, new_metrics_setup, run_metrics_endpoint,
+};
 use pdns::request::PdnsRequest;
 use pdns::response::PdnsResponse;
 use pdns::socket::PdnsSocket;
+use record_cache::RecordCache;
 use rpc::JsonDnsResourceRecord;
 use rpc::forge_tls_client::{ApiConfig, ForgeClientT, ForgeTlsClient};
 use rpc::protos::dns::{
@@ -36,6 +43,8 @@ use uuid::Uuid;
 pub mod config;
 pub mod legacy;
 pub mod pdns;
+pub mod primary;
+pub mod record_cache;
 
 #[derive(Debug, Clone)]
 pub struct MethodParseError;
@@ -49,6 +58,16 @@ pub async fn start(config: Config) -> Result<(), eyre::Report> {
 
     let client = Arc::new(Mutex::new(ForgeTlsClient::retry_build(&api_config).await?));
 
+    // Must stay alive for as long as metrics are recorded
+    let metrics_setup = spawn_metrics_endpoint()?;
+    let record_cache = config.record_cache.enabled.then(|| {
+        RecordCache::spawn(
+            client.clone(),
+            Duration::from_secs(config.record_cache.sync_interval_secs),
+            &metrics_setup.meter,
+        )
+    });
+
     let socket = PdnsSocket::new_socket(config.clone())?;
     let listener = socket.socket.clone();
 
@@ -57,13 +76,14 @@ pub async fn start(config: Config) -> Result<(), eyre::Report> {
 
         if let Ok((stream, _)) = listener.accept().await {
             let client = client.clone();
+            let record_cache = record_cache.clone();
             let conn_id = Uuid::new_v4();
             tokio::spawn(async move {
                 let span = tracing::info_span!("connection", %conn_id);
                 let _guard = span.enter();
 
                 tracing::info!("Connection accepted");
-                if let Err(err) = handle_connection(stream, client).await {
+                if let Err(err) = handle_connection(stream, client, record_cache).await {
                     tracing::error!(
                         error = ?err,
                         "Connection handling failed"
@@ -73,10 +93,33 @@ pub async fn start(config: Config) -> Result<(), eyre::Report> {
         }
     }
 }
+/// Serve metrics on port 8844. The returned setup must be kept alive, dropping it shuts the
+/// Prometheus exporter down.
+pub fn spawn_metrics_endpoint() -> Result<MetricsSetup, Report> {
+    let metrics_setup = new_metrics_setup("carbide-dns", "carbide", true)?;
+
+    let metrics_config = MetricsEndpointConfig {
+        address: SocketAddr::from_str("0.0.0.0:8844").expect("Invalid address socket address"),
+        registry: metrics_setup.registry.clone(),
+        health_controller: Some(metrics_setup.health_controller.clone()),
+    };
+
+    tokio::spawn(async move {
+        tracing::info!("Spawning metrics endpoint on {}", metrics_config.address);
+        if let Err(e) = run_metrics_endpoint(&metrics_config).await {
+            tracing::error!("Metrics endpoint error: {}", e);
+        }
+    });
+
+    Ok(metrics_setup)
+}
+
 async fn handle_connection(
     mut stream: UnixStream,
     client: Arc<Mutex<ForgeClientT>>,
+    record_cache: Option<Arc<RecordCache>>,
 ) -> Result<(), Report> {
+    let record_cache = record_cache.as_deref();
     let (reader, mut writer) = stream.split();
 
     let mut reader = BufReader::new(reader);
@@ -105,7 +148,7 @@ async fn handle_connection(
         let start = std::time::Instant::now();
         let response = match req.method.as_str() {
             "getAllDomains" => {
-                match handle_get_all_domains(&req, &client).await {
+                match handle_get_all_domains(&req, &client, record_cache).await {
                     Ok(response) => response,
                     Err(e) => {
                         tracing::error!(
@@ -120,7 +163,7 @@ async fn handle_connection(
             }
 
             "getAllDomainMetadata" => {
-                match handle_get_all_domain_metadata(&req, &client).await {
+                match handle_get_all_domain_metadata(&req, &client, record_cache).await {
                     Ok(response) => response,
                     Err(e) => {
                         tracing::error!(
@@ -135,7 +178,7 @@ async fn handle_connection(
             }
 
             "lookup" => {
-                match handle_lookup(&req, &client).await {
+                match handle_lookup(&req, &client, record_cache).await {
                     Ok(response) => response,
                     Err(e) => {
                         tracing::error!(
@@ -187,6 +230,7 @@ async fn handle_connection(
 async fn handle_get_all_domains(
     req: &PdnsRequest,
     client: &Arc<Mutex<ForgeClientT>>,
+    record_cache: Option<&RecordCache>,
 ) -> Result<PdnsResponse, Report> {
     let query: GetAllDomainsRequest = req.try_into()?;
     let span = tracing::info_span!("get_all_domains");
@@ -195,8 +239,19 @@ async fn handle_get_all_domains(
     tracing::info!(method = "getAllDomains", "Processing getAllDomains request");
 
     let api_start = std::time::Instant::now();
-    let mut client = client.lock().await;
-    let domains = client.get_all_domains(query).await?.into_inner();
+    let cached = match record_cache {
+        Some(cache) => cache.get_all_domains().await,
+        None => None,
+    };
+    let domains = match cached {
+        Some(domains) => domains,
+        None => client
+            .lock()
+            .await
+            .get_all_domains(query)
+            .await?
+            .into_inner(),
+    };
     let api_duration = api_start.elapsed();
 
     let res = domains
@@ -224,6 +279,7 @@ async fn handle_get_all_domains(
 async fn handle_get_all_domain_metadata(
     req: &PdnsRequest,
     client: &Arc<Mutex<ForgeClientT>>,
+    record_cache: Option<&RecordCache>,
 ) -> Result<PdnsResponse, Report> {
     let query: DomainMetadataRequest = req.try_into()?;
     let span = tracing::info_span!("get_all_domain_metadata", domain = %query.domain);
@@ -236,8 +292,19 @@ async fn handle_get_all_domain_metadata(
     );
 
     let api_start = std::time::Instant::now();
-    let mut client = client.lock().await;
-    let metadata = client.get_all_domain_metadata(query).await?.into_inner();
+    let cached = match record_cache {
+        Some(cache) => cache.get_domain_metadata(&query.domain).await,
+        None => None,
+    };
+    let metadata = match cached {
+        Some(metadata) => metadata,
+        None => client
+            .lock()
+            .await
+            .get_all_domain_metadata(query)
+            .await?
+            .into_inner(),
+    };
     let api_duration = api_start.elapsed();
 
     let res = metadata
@@ -265,6 +332,7 @@ async fn handle_get_all_domain_metadata(
 async fn handle_lookup(
     req: &PdnsRequest,
     client: &Arc<Mutex<ForgeClientT>>,
+    record_cache: Option<&RecordCache>,
 ) -> Result<PdnsResponse, Report> {
     let query: DnsResourceRecordLookupRequest = req.try_into()?;
 
@@ -284,8 +352,14 @@ async fn handle_lookup(
     tracing::info!(method = "lookup", "Processing DNS lookup request");
 
     let lookup_start = std::time::Instant::now();
-    let mut client = client.lock().await;
-    let record_lookup_response = client.lookup_record(query.clone()).await?.into_inner();
+    let cached = match record_cache {
+        Some(cache) => cache.lookup(&query.qname, &query.qtype).await,
+        None => None,
+    };
+    let record_lookup_response = match cached {
+        Some(response) => response,
+        None => client.lock().await.lookup_record(query).await?.into_inner(),
+    };
     let lookup_duration = lookup_start.elapsed();
 
     tracing::info!(
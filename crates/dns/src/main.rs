@@ -16,7 +16,7 @@
  */
 use std::path::PathBuf;
 
-use carbide_dns::config::{Config, ConfigError};
+use carbide_dns::config::{Config, ConfigError, PrimaryConfig};
 use carbide_dns::start;
 use clap::{CommandFactory, Parser};
 use eyre::WrapErr;
@@ -95,6 +95,17 @@ async fn main() -> Result<(), eyre::Report> {
                 .with(otel_layer)
                 .try_init()?;
 
+            if let Some(primary_config) = config.primary.clone() {
+                let config = config.clone();
+                tokio::spawn(async move {
+                    if let Err(e) =
+                        carbide_dns::primary::PrimaryServer::run(config, primary_config).await
+                    {
+                        tracing::error!(error = %e, "Hidden primary failed");
+                    }
+                });
+            }
+
             // Check if legacy mode is configured
             // TODO: Remove this after migration to PowerDNS backend
             if let Some(listen_addr) = config.legacy_listen {
@@ -166,6 +177,12 @@ pub struct RunCommand {
     )]
     pub listen: Option<std::net::SocketAddr>,
 
+    #[clap(
+        long,
+        help = "Address to serve zone transfers to secondary DNS servers on (e.g., [::]:5353). Secondaries to NOTIFY are set in the configuration file."
+    )]
+    pub primary_listen: Option<std::net::SocketAddr>,
+
     // Backward compatibility alias
     #[clap(
         long,
@@ -237,6 +254,13 @@ impl TryInto<Config> for RunCommand {
             config.legacy_listen = Some(listen);
         }
 
+        if let Some(primary_listen) = self.primary_listen {
+            match &mut config.primary {
+                Some(primary) => primary.listen = primary_listen,
+                None => config.primary = Some(PrimaryConfig::new(primary_listen)),
+            }
+        }
+
         Ok(config)
     }
 }
@@ -0,0 +1,669 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Hidden primary for carbide's zones
+//!
+//! Zones are rebuilt from carbide-api periodically and served to secondary DNS servers through
+//! AXFR and IXFR. Secondaries get a NOTIFY whenever a zone's serial changes. Regular lookups are
+//! left to the PowerDNS backend (or legacy server), this server only answers what secondaries
+//! need: SOA queries and zone transfers.
+
+use std::collections::{BTreeSet, HashMap};
+use std::iter;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use eyre::Report;
+use ipnetwork::IpNetwork;
+use rpc::forge_tls_client::{ApiConfig, ForgeClientT, ForgeTlsClient};
+use rpc::protos::dns::{
+    DnsResourceRecordLookupRequest, DomainMetadataRequest, GetAllDomainsRequest,
+    GetAllRecordsForDomainRequest,
+};
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::sync::{Mutex, RwLock};
+use trust_dns_server::ServerFuture;
+use trust_dns_server::authority::MessageResponseBuilder;
+use trust_dns_server::proto::op::{Header, OpCode, ResponseCode};
+use trust_dns_server::proto::rr::{Name, RData, Record, RecordType};
+use trust_dns_server::server::{Protocol, Request, RequestHandler, ResponseHandler, ResponseInfo};
+use zone::Zone;
+
+use crate::config::{Config, PrimaryConfig};
+
+pub mod notify;
+pub mod zone;
+
+/// Records per message of a zone transfer, which keeps each message well under the 64KiB a TCP
+/// DNS message can hold.
+const TRANSFER_RECORDS_PER_MESSAGE: usize = 256;
+
+#[derive(Debug)]
+struct PrimaryZone {
+    zone: Zone,
+    /// From the domain's `allow_axfr_from` metadata
+    allow_transfer: Vec<IpNetwork>,
+}
+
+/// A zone as carbide-api has it right now
+struct ZoneContent {
+    origin: Name,
+    soa: Record,
+    records: BTreeSet<Record>,
+    allow_transfer: Vec<IpNetwork>,
+}
+
+#[derive(Debug)]
+pub struct PrimaryServer {
+    zones: Arc<RwLock<HashMap<Name, PrimaryZone>>>,
+    secondaries: Vec<IpAddr>,
+}
+
+#[async_trait::async_trait]
+impl RequestHandler for PrimaryServer {
+    async fn handle_request<R: ResponseHandler>(
+        &self,
+        request: &Request,
+        mut response_handle: R,
+    ) -> ResponseInfo {
+        let query = request.query();
+        let qtype = query.query_type();
+        let origin = Name::from(query.name()).to_lowercase();
+
+        let records = match (request.op_code(), qtype) {
+            (OpCode::Query, RecordType::SOA | RecordType::AXFR | RecordType::IXFR) => {
+                self.answer(request, &origin, qtype).await
+            }
+            _ => {
+                tracing::debug!(%origin, %qtype, op_code = ?request.op_code(), "Refusing query");
+                Err(ResponseCode::Refused)
+            }
+        };
+
+        let records = match records {
+            Ok(records) => records,
+            Err(response_code) => {
+                let response = MessageResponseBuilder::from_message_request(request);
+                return send(
+                    &mut response_handle,
+                    response.error_msg(request.header(), response_code),
+                )
+                .await;
+            }
+        };
+
+        let mut header = Header::response_from_request(request.header());
+        header.set_authoritative(true);
+
+        let mut response_info = None;
+        for chunk in records.chunks(TRANSFER_RECORDS_PER_MESSAGE) {
+            let message = MessageResponseBuilder::from_message_request(request).build(
+                header,
+                chunk.iter(),
+                iter::empty(),
+                iter::empty(),
+                iter::empty(),
+            );
+            response_info = Some(send(&mut response_handle, message).await);
+        }
+        response_info.unwrap_or_else(|| header.into())
+    }
+}
+
+async fn send<'a, R: ResponseHandler>(
+    response_handle: &mut R,
+    message: trust_dns_server::authority::MessageResponse<
+        '_,
+        'a,
+        impl Iterator<Item = &'a Record> + Send + 'a,
+        impl Iterator<Item = &'a Record> + Send + 'a,
+        impl Iterator<Item = &'a Record> + Send + 'a,
+        impl Iterator<Item = &'a Record> + Send + 'a,
+    >,
+) -> ResponseInfo {
+    let mut header = *message.header();
+    match response_handle.send_response(message).await {
+        Ok(info) => info,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to send response");
+            header.set_response_code(ResponseCode::ServFail);
+            header.into()
+        }
+    }
+}
+
+impl PrimaryServer {
+    /// Records to answer with, in order. Zone transfers are only served over TCP to secondaries
+    /// which are allowed to.
+    async fn answer(
+        &self,
+        request: &Request,
+        origin: &Name,
+        qtype: RecordType,
+    ) -> Result<Vec<Record>, ResponseCode> {
+        let zones = self.zones.read().await;
+        let Some(primary_zone) = zones.get(origin) else {
+            tracing::debug!(%origin, %qtype, "Query for a zone we don't serve");
+            return Err(ResponseCode::Refused);
+        };
+
+        if qtype == RecordType::SOA {
+            return Ok(vec![primary_zone.zone.soa().clone()]);
+        }
+
+        let src = request.src().ip();
+        if !self.secondaries.contains(&src)
+            && !primary_zone
+                .allow_transfer
+                .iter()
+                .any(|network| network.contains(src))
+        {
+            tracing::warn!(%origin, %qtype, %src, "Zone transfer not allowed");
+            return Err(ResponseCode::Refused);
+        }
+
+        let over_tcp = matches!(request.protocol(), Protocol::Tcp);
+        let client_serial = match qtype {
+            RecordType::AXFR if !over_tcp => {
+                return Err(ResponseCode::FormErr);
+            }
+            RecordType::AXFR => None,
+            // The client's SOA is in the authority section.
+            _ => request
+                .name_servers()
+                .iter()
+                .find_map(|record| match record.data() {
+                    Some(RData::SOA(soa)) => Some(soa.serial()),
+                    _ => None,
+                }),
+        };
+
+        // An IXFR over UDP which needs more than the SOA tells the client to retry over TCP.
+        // https://www.rfc-editor.org/rfc/rfc1995#section-2
+        if qtype == RecordType::IXFR && !over_tcp {
+            return Ok(vec![primary_zone.zone.soa().clone()]);
+        }
+
+        let records = primary_zone.zone.transfer(client_serial);
+        tracing::info!(
+            %origin,
+            %qtype,
+            %src,
+            ?client_serial,
+            serial = primary_zone.zone.serial(),
+            record_count = records.len(),
+            "Serving zone transfer"
+        );
+        Ok(records)
+    }
+
+    pub async fn run(config: Config, primary_config: PrimaryConfig) -> Result<(), Report> {
+        tracing::info!(
+            "Starting hidden primary on {}, secondaries: {:?}",
+            primary_config.listen,
+            primary_config.secondaries
+        );
+
+        let forge_client_config = config.forge_client_config();
+        let api_uri = config.carbide_uri.to_string();
+        let api_config = ApiConfig::new(api_uri.as_str(), &forge_client_config);
+        let client = Arc::new(Mutex::new(ForgeTlsClient::retry_build(&api_config).await?));
+
+        let zones = Arc::new(RwLock::new(HashMap::new()));
+        let server = PrimaryServer {
+            zones: zones.clone(),
+            secondaries: primary_config
+                .secondaries
+                .iter()
+                .map(|secondary| secondary.ip())
+                .collect(),
+        };
+
+        let refresh_config = primary_config.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(
+                refresh_config.zone_refresh_interval_secs,
+            ));
+            loop {
+                interval.tick().await;
+                match refresh_zones(&client, &zones, &refresh_config).await {
+                    Ok(changed) => {
+                        for soa in changed {
+                            notify::notify_secondaries(&refresh_config.secondaries, &soa);
+                        }
+                    }
+                    Err(e) => tracing::error!(error = %e, "Failed to refresh zones"),
+                }
+            }
+        });
+
+        let mut server = ServerFuture::new(server);
+        let udp_socket = UdpSocket::bind(&primary_config.listen).await?;
+        server.register_socket(udp_socket);
+
+        let tcp_socket = TcpListener::bind(&primary_config.listen).await?;
+        server.register_listener(tcp_socket, Duration::new(30, 0));
+
+        server
+            .block_until_done()
+            .await
+            .map_err(|e| eyre::eyre!("Hidden primary has encountered an error: {e}"))
+    }
+}
+
+/// Rebuild every zone from carbide-api. Returns the SOA of the zones which got a new serial.
+async fn refresh_zones(
+    client: &Mutex<ForgeClientT>,
+    zones: &RwLock<HashMap<Name, PrimaryZone>>,
+    primary_config: &PrimaryConfig,
+) -> Result<Vec<Record>, Report> {
+    let domains = client
+        .lock()
+        .await
+        .get_all_domains(GetAllDomainsRequest {})
+        .await?
+        .into_inner()
+        .result;
+
+    let mut changed = vec![];
+    let mut origins = vec![];
+    for domain in domains {
+        let ZoneContent {
+            origin,
+            soa,
+            records,
+            allow_transfer,
+        } = match fetch_zone(client, &domain.zone).await {
+            Ok(content) => content,
+            Err(e) => {
+                // Keep serving what we have, the next refresh will try again.
+                tracing::error!(zone = %domain.zone, error = %e, "Failed to fetch zone");
+                if let Ok(origin) = zone::parse_name(&domain.zone) {
+                    origins.push(origin);
+                }
+                continue;
+            }
+        };
+        origins.push(origin.clone());
+
+        let mut zones_ = zones.write().await;
+        if let Some(primary_zone) = zones_.get_mut(&origin) {
+            primary_zone.allow_transfer = allow_transfer;
+            if primary_zone.zone.update(soa, records) {
+                changed.push(primary_zone.zone.soa().clone());
+            }
+            continue;
+        }
+        drop(zones_);
+
+        let floor_serial = secondaries_serial(&primary_config.secondaries, &origin).await;
+        let zone = Zone::new(
+            origin.clone(),
+            soa,
+            records,
+            floor_serial,
+            primary_config.ixfr_history,
+        );
+        tracing::info!(zone = %origin, serial = zone.serial(), "Serving new zone");
+        changed.push(zone.soa().clone());
+        zones.write().await.insert(
+            origin,
+            PrimaryZone {
+                zone,
+                allow_transfer,
+            },
+        );
+    }
+
+    zones.write().await.retain(|origin, _| {
+        let keep = origins.contains(origin);
+        if !keep {
+            tracing::info!(zone = %origin, "Zone was deleted, no longer serving it");
+        }
+        keep
+    });
+
+    Ok(changed)
+}
+
+async fn fetch_zone(client: &Mutex<ForgeClientT>, zone_name: &str) -> Result<ZoneContent, Report> {
+    let origin = zone::parse_name(zone_name).map_err(|e| eyre::eyre!(e))?;
+    let mut client = client.lock().await;
+
+    let soa = client
+        .lookup_record(DnsResourceRecordLookupRequest {
+            qtype: "SOA".to_string(),
+            qname: zone_name.to_string(),
+            zone_id: "-1".to_string(),
+            local: None,
+            remote: None,
+            real_remote: None,
+        })
+        .await?
+        .into_inner()
+        .records
+        .into_iter()
+        .next()
+        .ok_or_else(|| eyre::eyre!("No SOA record"))?;
+    let soa = zone::parse_soa(&origin, &soa).map_err(|e| eyre::eyre!(e))?;
+
+    let records = client
+        .get_all_records_for_domain(GetAllRecordsForDomainRequest {
+            name: zone_name.to_string(),
+        })
+        .await?
+        .into_inner()
+        .result;
+    let records = zone::zone_records(&origin, &soa, &records);
+
+    let allow_transfer = match client
+        .get_all_domain_metadata(DomainMetadataRequest {
+            domain: zone_name.to_string(),
+        })
+        .await
+    {
+        Ok(metadata) => metadata
+            .into_inner()
+            .result
+            .map(|metadata| parse_allow_transfer(&metadata.allow_axfr_from))
+            .unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!(zone = %zone_name, error = %e, "Failed to get domain metadata");
+            vec![]
+        }
+    };
+
+    Ok(ZoneContent {
+        origin,
+        soa,
+        records,
+        allow_transfer,
+    })
+}
+
+/// `allow_axfr_from` holds addresses or networks, possibly comma separated in a single entry.
+fn parse_allow_transfer(allow_axfr_from: &[String]) -> Vec<IpNetwork> {
+    allow_axfr_from
+        .iter()
+        .flat_map(|entry| entry.split(','))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| match entry.parse::<IpNetwork>() {
+            Ok(network) => Some(network),
+            Err(e) => {
+                tracing::warn!(entry, error = %e, "Ignoring invalid allow_axfr_from entry");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Highest serial the secondaries have for a zone.
+async fn secondaries_serial(secondaries: &[SocketAddr], origin: &Name) -> Option<u32> {
+    let mut highest: Option<u32> = None;
+    for secondary in secondaries {
+        match notify::query_serial(*secondary, origin).await {
+            Ok(Some(serial)) => {
+                if highest.is_none_or(|highest| zone::serial_gt(serial, highest)) {
+                    highest = Some(serial);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!(%secondary, zone = %origin, error = %e, "Failed to query serial");
+            }
+        }
+    }
+    highest
+}
+
+#[cfg(test)]
+mod tests {
+    use rpc::protos::dns::DnsResourceRecord;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use trust_dns_server::proto::op::{Message, MessageType, Query};
+
+    use super::*;
+
+    fn api_record(qname: &str, qtype: &str, content: &str) -> DnsResourceRecord {
+        DnsResourceRecord {
+            qname: qname.to_string(),
+            qtype: qtype.to_string(),
+            ttl: 300,
+            content: content.to_string(),
+            domain_id: None,
+            scope_mask: None,
+            auth: None,
+        }
+    }
+
+    fn origin() -> Name {
+        zone::parse_name("example.com").unwrap()
+    }
+
+    fn soa(serial: u32) -> Record {
+        zone::parse_soa(
+            &origin(),
+            &api_record(
+                "example.com",
+                "SOA",
+                &format!("ns1.example.com. hostmaster.example.com. {serial} 3600 600 604800 3600"),
+            ),
+        )
+        .unwrap()
+    }
+
+    fn records(addresses: &[&str]) -> BTreeSet<Record> {
+        let records = addresses
+            .iter()
+            .enumerate()
+            .map(|(i, address)| api_record(&format!("host{i}.example.com."), "A", address))
+            .collect::<Vec<_>>();
+        zone::zone_records(&origin(), &soa(1), &records)
+    }
+
+    /// Serve example.com, which went from serial 10 to 11 when host1 was added, on localhost.
+    async fn start_server(secondaries: Vec<IpAddr>, allow_transfer: Vec<IpNetwork>) -> SocketAddr {
+        let mut zone = Zone::new(origin(), soa(10), records(&["192.0.2.1"]), None, 8);
+        zone.update(soa(10), records(&["192.0.2.1", "192.0.2.2"]));
+        let server = PrimaryServer {
+            zones: Arc::new(RwLock::new(HashMap::from([(
+                origin(),
+                PrimaryZone {
+                    zone,
+                    allow_transfer,
+                },
+            )]))),
+            secondaries,
+        };
+
+        let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = tcp_listener.local_addr().unwrap();
+        let udp_socket = UdpSocket::bind(address).await.unwrap();
+        let mut server = ServerFuture::new(server);
+        server.register_socket(udp_socket);
+        server.register_listener(tcp_listener, Duration::from_secs(5));
+        tokio::spawn(async move { server.block_until_done().await });
+        address
+    }
+
+    fn request(op_code: OpCode, qtype: RecordType, client_serial: Option<u32>) -> Message {
+        let mut message = Message::new();
+        message
+            .set_id(1234)
+            .set_message_type(MessageType::Query)
+            .set_op_code(op_code)
+            .add_query(Query::query(origin(), qtype));
+        if let Some(client_serial) = client_serial {
+            message.add_name_server(soa(client_serial));
+        }
+        message
+    }
+
+    async fn exchange_udp(server: SocketAddr, request: &Message) -> Message {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        socket
+            .send_to(&request.to_vec().unwrap(), server)
+            .await
+            .unwrap();
+        let mut buf = [0; 4096];
+        let received = tokio::time::timeout(Duration::from_secs(5), socket.recv(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        Message::from_vec(&buf[..received]).unwrap()
+    }
+
+    /// Send a request over TCP and read responses until they hold a complete answer: an error, a
+    /// single SOA, or records ending with the SOA they started with.
+    async fn exchange_tcp(server: SocketAddr, request: &Message) -> (ResponseCode, Vec<Record>) {
+        let mut stream = TcpStream::connect(server).await.unwrap();
+        let request = request.to_vec().unwrap();
+        stream
+            .write_all(&(request.len() as u16).to_be_bytes())
+            .await
+            .unwrap();
+        stream.write_all(&request).await.unwrap();
+
+        let mut answers: Vec<Record> = vec![];
+        loop {
+            let len = tokio::time::timeout(Duration::from_secs(5), stream.read_u16())
+                .await
+                .unwrap()
+                .unwrap();
+            let mut buf = vec![0; len as usize];
+            stream.read_exact(&mut buf).await.unwrap();
+            let response = Message::from_vec(&buf).unwrap();
+            assert_eq!(response.id(), 1234);
+            if response.response_code() != ResponseCode::NoError {
+                return (response.response_code(), vec![]);
+            }
+            answers.extend(response.answers().iter().cloned());
+            if answers.len() == 1 || (answers.len() > 1 && answers.first() == answers.last()) {
+                return (ResponseCode::NoError, answers);
+            }
+        }
+    }
+
+    fn describe(records: &[Record]) -> Vec<String> {
+        records
+            .iter()
+            .map(|r| match r.data() {
+                Some(RData::SOA(soa)) => format!("SOA {}", soa.serial()),
+                Some(RData::A(a)) => format!("{} A {}", r.name(), a.0),
+                _ => format!("{} {}", r.name(), r.record_type()),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_transfer_acl() {
+        let localhost = IpAddr::from([127, 0, 0, 1]);
+        let axfr = request(OpCode::Query, RecordType::AXFR, None);
+
+        // Neither a secondary nor in allow_axfr_from: only the SOA is served.
+        let server = start_server(vec![], vec!["192.0.2.0/24".parse().unwrap()]).await;
+        assert_eq!(exchange_tcp(server, &axfr).await.0, ResponseCode::Refused);
+        let (response_code, answers) =
+            exchange_tcp(server, &request(OpCode::Query, RecordType::SOA, None)).await;
+        assert_eq!(response_code, ResponseCode::NoError);
+        assert_eq!(describe(&answers), vec!["SOA 11"]);
+
+        // Configured secondaries can transfer every zone.
+        let server = start_server(vec![localhost], vec![]).await;
+        assert_eq!(exchange_tcp(server, &axfr).await.0, ResponseCode::NoError);
+
+        // So can the networks in the zone's allow_axfr_from.
+        let server = start_server(vec![], parse_allow_transfer(&["127.0.0.0/8".into()])).await;
+        assert_eq!(exchange_tcp(server, &axfr).await.0, ResponseCode::NoError);
+
+        // Zones we don't serve and other kinds of queries are refused.
+        let mut other_zone = axfr.clone();
+        other_zone.take_queries();
+        other_zone.add_query(Query::query(
+            zone::parse_name("example.org").unwrap(),
+            RecordType::AXFR,
+        ));
+        assert_eq!(
+            exchange_tcp(server, &other_zone).await.0,
+            ResponseCode::Refused
+        );
+        assert_eq!(
+            exchange_udp(server, &request(OpCode::Query, RecordType::A, None))
+                .await
+                .response_code(),
+            ResponseCode::Refused
+        );
+    }
+
+    #[tokio::test]
+    async fn test_axfr() {
+        let server = start_server(vec![IpAddr::from([127, 0, 0, 1])], vec![]).await;
+        let axfr = request(OpCode::Query, RecordType::AXFR, None);
+
+        // AXFR is TCP only.
+        assert_eq!(
+            exchange_udp(server, &axfr).await.response_code(),
+            ResponseCode::FormErr
+        );
+
+        let (response_code, answers) = exchange_tcp(server, &axfr).await;
+        assert_eq!(response_code, ResponseCode::NoError);
+        assert_eq!(
+            describe(&answers),
+            vec![
+                "SOA 11",
+                "example.com. NS",
+                "host0.example.com. A 192.0.2.1",
+                "host1.example.com. A 192.0.2.2",
+                "SOA 11",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ixfr() {
+        let server = start_server(vec![IpAddr::from([127, 0, 0, 1])], vec![]).await;
+        let ixfr = request(OpCode::Query, RecordType::IXFR, Some(10));
+
+        // Over UDP, the current SOA tells the secondary to retry over TCP.
+        let response = exchange_udp(server, &ixfr).await;
+        assert_eq!(response.response_code(), ResponseCode::NoError);
+        assert_eq!(describe(response.answers()), vec!["SOA 11"]);
+
+        let (response_code, answers) = exchange_tcp(server, &ixfr).await;
+        assert_eq!(response_code, ResponseCode::NoError);
+        assert_eq!(
+            describe(&answers),
+            vec![
+                "SOA 11",
+                "SOA 10",
+                "SOA 11",
+                "host1.example.com. A 192.0.2.2",
+                "SOA 11",
+            ]
+        );
+
+        // Up to date.
+        let (response_code, answers) =
+            exchange_tcp(server, &request(OpCode::Query, RecordType::IXFR, Some(11))).await;
+        assert_eq!(response_code, ResponseCode::NoError);
+        assert_eq!(describe(&answers), vec!["SOA 11"]);
+    }
+}
@@ -0,0 +1,241 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Messages the primary sends to secondaries: NOTIFY and SOA queries.
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use eyre::Report;
+use tokio::net::UdpSocket;
+use trust_dns_server::proto::op::{Message, MessageType, OpCode, Query, ResponseCode};
+use trust_dns_server::proto::rr::{Name, RData, Record, RecordType};
+use uuid::Uuid;
+
+// https://www.rfc-editor.org/rfc/rfc1996#section-3.6
+const NOTIFY_RETRIES: usize = 5;
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Tell every secondary that the zone has a new serial. Secondaries which don't answer will
+/// still pick the change up at their next SOA refresh.
+pub fn notify_secondaries(secondaries: &[SocketAddr], soa: &Record) {
+    for secondary in secondaries.iter().copied() {
+        let soa = soa.clone();
+        tokio::spawn(async move {
+            let zone = soa.name().clone();
+            match notify(secondary, soa).await {
+                Ok(()) => tracing::info!(%secondary, %zone, "Secondary acknowledged NOTIFY"),
+                Err(e) => tracing::warn!(%secondary, %zone, error = %e, "NOTIFY failed"),
+            }
+        });
+    }
+}
+
+async fn notify(secondary: SocketAddr, soa: Record) -> Result<(), Report> {
+    let mut message = new_message(OpCode::Notify, soa.name().clone());
+    message.set_authoritative(true);
+    message.add_answer(soa);
+
+    let response = exchange(secondary, &message, NOTIFY_RETRIES).await?;
+    if response.response_code() != ResponseCode::NoError {
+        return Err(eyre::eyre!(
+            "secondary answered {}",
+            response.response_code()
+        ));
+    }
+    Ok(())
+}
+
+/// Serial of the zone on a secondary, None if it doesn't have the zone.
+pub async fn query_serial(secondary: SocketAddr, zone: &Name) -> Result<Option<u32>, Report> {
+    let message = new_message(OpCode::Query, zone.clone());
+    let response = exchange(secondary, &message, 1).await?;
+
+    Ok(response
+        .answers()
+        .iter()
+        .find_map(|record| match record.data() {
+            Some(RData::SOA(soa)) if record.name() == zone => Some(soa.serial()),
+            _ => None,
+        }))
+}
+
+fn new_message(op_code: OpCode, zone: Name) -> Message {
+    let mut message = Message::new();
+    message
+        .set_id(Uuid::new_v4().as_u128() as u16)
+        .set_message_type(MessageType::Query)
+        .set_op_code(op_code)
+        .add_query(Query::query(zone, RecordType::SOA));
+    message
+}
+
+/// Send `message` over UDP until the secondary answers it or we run out of attempts.
+async fn exchange(
+    secondary: SocketAddr,
+    message: &Message,
+    attempts: usize,
+) -> Result<Message, Report> {
+    let bind_address: SocketAddr = match secondary {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(bind_address).await?;
+    socket.connect(secondary).await?;
+    let request = message.to_vec()?;
+
+    let mut buf = [0; 4096];
+    for _ in 0..attempts {
+        socket.send(&request).await?;
+        let deadline = tokio::time::Instant::now() + RESPONSE_TIMEOUT;
+        while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv(&mut buf)).await {
+            let Ok(response) = Message::from_vec(&buf[..received?]) else {
+                continue;
+            };
+            if response.id() == message.id() && response.message_type() == MessageType::Response {
+                return Ok(response);
+            }
+        }
+    }
+
+    Err(eyre::eyre!(
+        "no response after {attempts} attempt(s) to {secondary}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use rpc::protos::dns::DnsResourceRecord;
+
+    use super::*;
+    use crate::primary::zone;
+
+    fn soa(serial: u32) -> Record {
+        let origin = zone::parse_name("example.com").unwrap();
+        zone::parse_soa(
+            &origin,
+            &DnsResourceRecord {
+                qname: "example.com".to_string(),
+                qtype: "SOA".to_string(),
+                ttl: 300,
+                content: format!(
+                    "ns1.example.com. hostmaster.example.com. {serial} 3600 600 604800 3600"
+                ),
+                domain_id: None,
+                scope_mask: None,
+                auth: None,
+            },
+        )
+        .unwrap()
+    }
+
+    /// A secondary which answers each request it gets with `respond`, or not at all if it
+    /// returns None.
+    async fn start_secondary(
+        respond: impl Fn(usize, &Message) -> Option<Message> + Send + 'static,
+    ) -> (SocketAddr, tokio::task::JoinHandle<Vec<Message>>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let mut requests = vec![];
+            let mut buf = [0; 4096];
+            while let Ok(Ok((received, src))) =
+                tokio::time::timeout(Duration::from_secs(5), socket.recv_from(&mut buf)).await
+            {
+                let request = Message::from_vec(&buf[..received]).unwrap();
+                let response = respond(requests.len(), &request);
+                requests.push(request);
+                let Some(response) = response else {
+                    continue;
+                };
+                socket
+                    .send_to(&response.to_vec().unwrap(), src)
+                    .await
+                    .unwrap();
+                return requests;
+            }
+            requests
+        });
+        (address, handle)
+    }
+
+    fn response_to(request: &Message, response_code: ResponseCode) -> Message {
+        let mut response = Message::new();
+        response
+            .set_id(request.id())
+            .set_message_type(MessageType::Response)
+            .set_op_code(request.op_code())
+            .set_response_code(response_code)
+            .add_queries(request.queries().to_vec());
+        response
+    }
+
+    #[tokio::test]
+    async fn test_notify() {
+        let (secondary, requests) =
+            start_secondary(|_, request| Some(response_to(request, ResponseCode::NoError))).await;
+        notify(secondary, soa(11)).await.unwrap();
+
+        let requests = requests.await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        assert_eq!(request.op_code(), OpCode::Notify);
+        assert_eq!(request.message_type(), MessageType::Query);
+        assert!(request.authoritative());
+        assert_eq!(request.queries()[0].query_type(), RecordType::SOA);
+        assert_eq!(request.queries()[0].name().to_string(), "example.com.");
+        assert_eq!(request.answers(), &[soa(11)]);
+    }
+
+    #[tokio::test]
+    async fn test_notify_is_retried() {
+        // The first NOTIFY is lost, the retry is acknowledged.
+        let (secondary, requests) = start_secondary(|attempt, request| {
+            (attempt > 0).then(|| response_to(request, ResponseCode::NoError))
+        })
+        .await;
+        notify(secondary, soa(11)).await.unwrap();
+
+        let requests = requests.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].id(), requests[1].id());
+    }
+
+    #[tokio::test]
+    async fn test_notify_refused() {
+        let (secondary, _) =
+            start_secondary(|_, request| Some(response_to(request, ResponseCode::Refused))).await;
+        assert!(notify(secondary, soa(11)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_serial() {
+        let (secondary, _) = start_secondary(|_, request| {
+            let mut response = response_to(request, ResponseCode::NoError);
+            response.add_answer(soa(42));
+            Some(response)
+        })
+        .await;
+        let origin = zone::parse_name("example.com").unwrap();
+        assert_eq!(query_serial(secondary, &origin).await.unwrap(), Some(42));
+
+        // The secondary doesn't have the zone.
+        let (secondary, _) =
+            start_secondary(|_, request| Some(response_to(request, ResponseCode::NXDomain))).await;
+        assert_eq!(query_serial(secondary, &origin).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,472 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! In-memory snapshots of carbide's zones, versioned by SOA serial.
+//!
+//! carbide-api only bumps a domain's serial when the domain itself is updated, not when machine
+//! interface records come and go, so the serial served to secondaries is owned here: any change
+//! in the zone's content gets a new serial, and the changes are kept to answer IXFR.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::net::{AddrParseError, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use dns_record::DnsResourceRecordType;
+use rpc::protos::dns::DnsResourceRecord;
+use trust_dns_server::proto::rr::rdata::{A, AAAA, CNAME, MX, NS, PTR, SOA, TXT};
+use trust_dns_server::proto::rr::{Name, RData, Record};
+
+/// One change of the zone, as sent in an IXFR response.
+#[derive(Debug, Clone)]
+struct ZoneChange {
+    from_soa: Record,
+    to_soa: Record,
+    removed: Vec<Record>,
+    added: Vec<Record>,
+}
+
+#[derive(Debug)]
+pub struct Zone {
+    origin: Name,
+    soa: Record,
+    records: BTreeSet<Record>,
+    /// Oldest change first.
+    history: VecDeque<ZoneChange>,
+    history_limit: usize,
+}
+
+impl Zone {
+    /// `floor_serial` is the highest serial secondaries already have for this zone. carbide-dns
+    /// doesn't remember the serials it handed out before a restart, so it starts above them.
+    pub fn new(
+        origin: Name,
+        soa: Record,
+        records: BTreeSet<Record>,
+        floor_serial: Option<u32>,
+        history_limit: usize,
+    ) -> Self {
+        let mut soa = soa;
+        let serial = soa_serial(&soa);
+        if let Some(floor_serial) = floor_serial
+            && !serial_gt(serial, floor_serial)
+        {
+            set_soa_serial(&mut soa, floor_serial.wrapping_add(1));
+        }
+
+        Self {
+            origin,
+            soa,
+            records,
+            history: VecDeque::new(),
+            history_limit,
+        }
+    }
+
+    pub fn origin(&self) -> &Name {
+        &self.origin
+    }
+
+    pub fn soa(&self) -> &Record {
+        &self.soa
+    }
+
+    pub fn serial(&self) -> u32 {
+        soa_serial(&self.soa)
+    }
+
+    /// Replace the zone's content with what carbide-api currently has. Returns whether the zone
+    /// changed, in which case it has a new serial.
+    pub fn update(&mut self, soa: Record, records: BTreeSet<Record>) -> bool {
+        let api_serial = soa_serial(&soa);
+        let mut soa = soa;
+        let mut current_soa = self.soa.clone();
+        // Compare everything but the serial, which is ours.
+        set_soa_serial(&mut current_soa, api_serial);
+        if current_soa == soa && records == self.records && !serial_gt(api_serial, self.serial()) {
+            return false;
+        }
+
+        if !serial_gt(api_serial, self.serial()) {
+            set_soa_serial(&mut soa, self.serial().wrapping_add(1));
+        }
+
+        let change = ZoneChange {
+            from_soa: self.soa.clone(),
+            to_soa: soa.clone(),
+            removed: self.records.difference(&records).cloned().collect(),
+            added: records.difference(&self.records).cloned().collect(),
+        };
+        tracing::info!(
+            zone = %self.origin,
+            from_serial = soa_serial(&change.from_soa),
+            to_serial = soa_serial(&change.to_soa),
+            removed = change.removed.len(),
+            added = change.added.len(),
+            "Zone changed"
+        );
+
+        self.history.push_back(change);
+        while self.history.len() > self.history_limit {
+            self.history.pop_front();
+        }
+        self.soa = soa;
+        self.records = records;
+        true
+    }
+
+    /// Records of an AXFR response (`client_serial` is None), or of an IXFR response for a
+    /// secondary at `client_serial`. Falls back to the full zone when that serial is too old.
+    pub fn transfer(&self, client_serial: Option<u32>) -> Vec<Record> {
+        let Some(client_serial) = client_serial else {
+            return self.full_transfer();
+        };
+
+        // https://www.rfc-editor.org/rfc/rfc1995#section-2
+        if !serial_gt(self.serial(), client_serial) {
+            return vec![self.soa.clone()];
+        }
+
+        let Some(start) = self
+            .history
+            .iter()
+            .position(|change| soa_serial(&change.from_soa) == client_serial)
+        else {
+            return self.full_transfer();
+        };
+
+        let mut records = vec![self.soa.clone()];
+        for change in self.history.iter().skip(start) {
+            records.push(change.from_soa.clone());
+            records.extend(change.removed.iter().cloned());
+            records.push(change.to_soa.clone());
+            records.extend(change.added.iter().cloned());
+        }
+        records.push(self.soa.clone());
+        records
+    }
+
+    fn full_transfer(&self) -> Vec<Record> {
+        let mut records = Vec::with_capacity(self.records.len() + 2);
+        records.push(self.soa.clone());
+        records.extend(self.records.iter().cloned());
+        records.push(self.soa.clone());
+        records
+    }
+}
+
+/// Serial number comparison: https://www.rfc-editor.org/rfc/rfc1982#section-3.2
+pub fn serial_gt(a: u32, b: u32) -> bool {
+    a != b && a.wrapping_sub(b) < 0x8000_0000
+}
+
+pub fn soa_serial(soa: &Record) -> u32 {
+    match soa.data() {
+        Some(RData::SOA(soa)) => soa.serial(),
+        _ => 0,
+    }
+}
+
+fn set_soa_serial(record: &mut Record, serial: u32) {
+    if let Some(RData::SOA(soa)) = record.data() {
+        let soa = SOA::new(
+            soa.mname().clone(),
+            soa.rname().clone(),
+            serial,
+            soa.refresh(),
+            soa.retry(),
+            soa.expire(),
+            soa.minimum(),
+        );
+        record.set_data(Some(RData::SOA(soa)));
+    }
+}
+
+/// Parse the SOA as carbide-api returns it from a lookup:
+/// `ns1.example.com. hostmaster.example.com. 2024110401 3600 600 604800 3600`
+pub fn parse_soa(origin: &Name, record: &DnsResourceRecord) -> Result<Record, String> {
+    let fields = record.content.split_whitespace().collect::<Vec<_>>();
+    let [mname, rname, serial, refresh, retry, expire, minimum] = fields[..] else {
+        return Err(format!("Invalid SOA content: {}", record.content));
+    };
+    let number_error = |e| format!("Invalid SOA content {}: {e}", record.content);
+
+    let soa = SOA::new(
+        parse_name(mname)?,
+        parse_name(rname)?,
+        serial.parse().map_err(number_error)?,
+        refresh.parse().map_err(number_error)?,
+        retry.parse().map_err(number_error)?,
+        expire.parse().map_err(number_error)?,
+        minimum.parse().map_err(number_error)?,
+    );
+    Ok(Record::from_rdata(
+        origin.clone(),
+        record.ttl,
+        RData::SOA(soa),
+    ))
+}
+
+/// Build the zone's records from carbide-api's. Records which can't be served are skipped, so
+/// one bad record doesn't hold back the whole zone.
+pub fn zone_records(
+    origin: &Name,
+    soa: &Record,
+    records: &[DnsResourceRecord],
+) -> BTreeSet<Record> {
+    let mut zone_records = records
+        .iter()
+        .filter_map(|record| match to_record(origin, record) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                tracing::warn!(
+                    zone = %origin,
+                    qname = %record.qname,
+                    qtype = %record.qtype,
+                    error = %e,
+                    "Skipping record in zone"
+                );
+                None
+            }
+        })
+        .collect::<BTreeSet<_>>();
+
+    // Secondaries refuse zones without NS records at the apex, and carbide doesn't store any, so
+    // the SOA's primary nameserver stands in.
+    let has_apex_ns = zone_records
+        .iter()
+        .any(|r| matches!(r.data(), Some(RData::NS(_))) && r.name() == origin);
+    if !has_apex_ns && let Some(RData::SOA(soa_data)) = soa.data() {
+        zone_records.insert(Record::from_rdata(
+            origin.clone(),
+            soa.ttl(),
+            RData::NS(NS(soa_data.mname().clone())),
+        ));
+    }
+
+    zone_records
+}
+
+fn to_record(origin: &Name, record: &DnsResourceRecord) -> Result<Record, String> {
+    let name = parse_name(&record.qname)?;
+    if !origin.zone_of(&name) {
+        return Err(format!("{name} is not in zone {origin}"));
+    }
+
+    let content = record.content.as_str();
+    let rdata = match DnsResourceRecordType::try_from(record.qtype.as_str())? {
+        DnsResourceRecordType::A => RData::A(A(parse_ip::<Ipv4Addr>(content)?)),
+        DnsResourceRecordType::AAAA => RData::AAAA(AAAA(parse_ip::<Ipv6Addr>(content)?)),
+        DnsResourceRecordType::CNAME => RData::CNAME(CNAME(parse_name(content)?)),
+        DnsResourceRecordType::NS => RData::NS(NS(parse_name(content)?)),
+        DnsResourceRecordType::PTR => RData::PTR(PTR(parse_name(content)?)),
+        DnsResourceRecordType::TXT => RData::TXT(TXT::new(vec![content.to_string()])),
+        DnsResourceRecordType::MX => {
+            let (preference, exchange) = content
+                .split_once(' ')
+                .ok_or_else(|| format!("Invalid MX content: {content}"))?;
+            let preference = preference
+                .parse()
+                .map_err(|e| format!("Invalid MX preference {preference}: {e}"))?;
+            RData::MX(MX::new(preference, parse_name(exchange)?))
+        }
+        qtype @ (DnsResourceRecordType::SOA | DnsResourceRecordType::ANY) => {
+            return Err(format!("Unexpected {qtype} record"));
+        }
+    };
+
+    Ok(Record::from_rdata(name, record.ttl, rdata))
+}
+
+fn parse_ip<T: FromStr<Err = AddrParseError>>(content: &str) -> Result<T, String> {
+    content
+        .parse()
+        .map_err(|e| format!("Invalid IP address {content}: {e}"))
+}
+
+/// Names from carbide-api may or may not have the trailing dot, all of them are absolute.
+pub fn parse_name(name: &str) -> Result<Name, String> {
+    let mut name = Name::from_str(name).map_err(|e| format!("Invalid name {name}: {e}"))?;
+    name.set_fqdn(true);
+    Ok(name.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use trust_dns_server::proto::rr::RecordType;
+
+    use super::*;
+
+    fn api_record(qname: &str, qtype: &str, content: &str) -> DnsResourceRecord {
+        DnsResourceRecord {
+            qname: qname.to_string(),
+            qtype: qtype.to_string(),
+            ttl: 300,
+            content: content.to_string(),
+            domain_id: None,
+            scope_mask: None,
+            auth: None,
+        }
+    }
+
+    fn origin() -> Name {
+        parse_name("example.com").unwrap()
+    }
+
+    fn soa(serial: u32) -> Record {
+        parse_soa(
+            &origin(),
+            &api_record(
+                "example.com",
+                "SOA",
+                &format!("ns1.example.com. hostmaster.example.com. {serial} 3600 600 604800 3600"),
+            ),
+        )
+        .unwrap()
+    }
+
+    fn records(addresses: &[&str]) -> BTreeSet<Record> {
+        let records = addresses
+            .iter()
+            .enumerate()
+            .map(|(i, address)| api_record(&format!("host{i}.example.com."), "A", address))
+            .collect::<Vec<_>>();
+        zone_records(&origin(), &soa(1), &records)
+    }
+
+    #[test]
+    fn test_serial_gt() {
+        assert!(serial_gt(2, 1));
+        assert!(!serial_gt(1, 2));
+        assert!(!serial_gt(1, 1));
+        // Wraps around.
+        assert!(serial_gt(0, u32::MAX));
+        assert!(!serial_gt(u32::MAX, 0));
+    }
+
+    #[test]
+    fn test_zone_records() {
+        let records = zone_records(
+            &origin(),
+            &soa(1),
+            &[
+                api_record("host.example.com.", "A", "192.0.2.1"),
+                api_record("HOST.example.com", "AAAA", "2001:db8::1"),
+                api_record("mail.example.com.", "MX", "10 host.example.com."),
+                api_record("other.org.", "A", "192.0.2.2"),
+                api_record("bad.example.com.", "A", "not-an-ip"),
+            ],
+        );
+
+        let types = records
+            .iter()
+            .map(|r| (r.name().to_string(), r.record_type()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                ("example.com.".to_string(), RecordType::NS),
+                ("host.example.com.".to_string(), RecordType::A),
+                ("host.example.com.".to_string(), RecordType::AAAA),
+                ("mail.example.com.".to_string(), RecordType::MX),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_update() {
+        let mut zone = Zone::new(origin(), soa(2026101801), records(&["192.0.2.1"]), None, 8);
+        assert_eq!(zone.serial(), 2026101801);
+
+        // Nothing changed.
+        assert!(!zone.update(soa(2026101801), records(&["192.0.2.1"])));
+        assert_eq!(zone.serial(), 2026101801);
+
+        // Records changed, carbide-api's serial didn't.
+        assert!(zone.update(soa(2026101801), records(&["192.0.2.2"])));
+        assert_eq!(zone.serial(), 2026101802);
+
+        // The domain was updated in carbide-api, its serial wins if higher.
+        assert!(zone.update(soa(2026101901), records(&["192.0.2.2"])));
+        assert_eq!(zone.serial(), 2026101901);
+    }
+
+    #[test]
+    fn test_new_zone_starts_above_secondaries() {
+        let zone = Zone::new(origin(), soa(2026101801), records(&[]), Some(2026101805), 8);
+        assert_eq!(zone.serial(), 2026101806);
+
+        let zone = Zone::new(origin(), soa(2026101801), records(&[]), Some(2026101701), 8);
+        assert_eq!(zone.serial(), 2026101801);
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut zone = Zone::new(origin(), soa(10), records(&["192.0.2.1"]), None, 2);
+        zone.update(soa(10), records(&["192.0.2.2"]));
+        zone.update(soa(10), records(&["192.0.2.2", "192.0.2.3"]));
+        assert_eq!(zone.serial(), 12);
+
+        let serials = |records: &[Record]| {
+            records
+                .iter()
+                .map(|r| match r.data() {
+                    Some(RData::SOA(soa)) => format!("SOA {}", soa.serial()),
+                    Some(RData::A(a)) => format!("+{}", a.0),
+                    data => format!("{data:?}"),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // AXFR
+        assert_eq!(
+            serials(&zone.transfer(None)),
+            vec![
+                "SOA 12",
+                &format!(
+                    "{:?}",
+                    Some(RData::NS(NS(parse_name("ns1.example.com").unwrap())))
+                ),
+                "+192.0.2.2",
+                "+192.0.2.3",
+                "SOA 12"
+            ]
+        );
+
+        // IXFR from serial 10: host0 changed address, then host1 was added.
+        assert_eq!(
+            serials(&zone.transfer(Some(10))),
+            vec![
+                "SOA 12",
+                "SOA 10",
+                "+192.0.2.1",
+                "SOA 11",
+                "+192.0.2.2",
+                "SOA 11",
+                "SOA 12",
+                "+192.0.2.3",
+                "SOA 12"
+            ]
+        );
+
+        // Up to date.
+        assert_eq!(serials(&zone.transfer(Some(12))), vec!["SOA 12"]);
+
+        // Older than the history, full zone.
+        zone.update(soa(10), records(&["192.0.2.4"]));
+        assert_eq!(zone.transfer(Some(10)), zone.transfer(None));
+    }
+}
@@ -0,0 +1,458 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Local copy of carbide's zones which queries are answered from.
+//!
+//! The copy is synced from carbide-api's DNS snapshot and kept when carbide-api can't be
+//! reached, so names keep resolving through carbide-api restarts and database outages.
+//! Queries go to carbide-api until the first sync succeeds, and for names the copy doesn't
+//! have.
+//!
+//! carbide-api bumps the snapshot's version when the zones or records may have changed, the
+//! snapshot is only fetched again (in pages) then. A change committed while a snapshot is
+//! being read can leave the version ahead of the records, so the whole snapshot is fetched
+//! again every FULL_SYNC_INTERVAL regardless.
+//!
+//! Lookups answered from the copy count as hits and lookups sent to carbide-api as
+//! misses, their ratio is the hit ratio. The age of the copy is exported as well.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dns_record::DnsResourceRecordType;
+use opentelemetry::metrics::{Counter, Meter};
+use rpc::forge_tls_client::ForgeClientT;
+use rpc::protos::dns::{
+    DnsResourceRecord, DnsResourceRecordLookupResponse, DnsSnapshotRequest, DnsSnapshotResponse,
+    DnsSnapshotZone, DomainMetadataResponse, GetAllDomainsResponse,
+};
+use tokio::sync::{Mutex, RwLock};
+
+/// How often the whole snapshot is fetched, even if its version didn't change
+const FULL_SYNC_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Metrics for the record cache, created from an OpenTelemetry `Meter`.
+struct RecordCacheMetrics {
+    hit: Counter<u64>,
+    miss: Counter<u64>,
+}
+
+impl RecordCacheMetrics {
+    fn new(meter: &Meter, last_sync: Arc<AtomicU64>) -> Self {
+        meter
+            .u64_observable_gauge("carbide_dns_record_cache_age_seconds")
+            .with_description("Time since the record cache was last synced with carbide-api")
+            .with_callback(move |observer| {
+                let last_sync = last_sync.load(Ordering::Relaxed);
+                if last_sync > 0 {
+                    observer.observe(unix_time().saturating_sub(last_sync), &[]);
+                }
+            })
+            .build();
+
+        Self {
+            hit: meter
+                .u64_counter("carbide_dns_record_cache_hit_count")
+                .with_description("Lookups answered from the record cache")
+                .build(),
+            miss: meter
+                .u64_counter("carbide_dns_record_cache_miss_count")
+                .with_description(
+                    "Lookups sent to carbide-api because the cache isn't synced yet or doesn't \
+                     have the name",
+                )
+                .build(),
+        }
+    }
+}
+
+// RecordCacheMetrics contains OpenTelemetry instrument types which don't implement Debug.
+impl std::fmt::Debug for RecordCacheMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordCacheMetrics").finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct RecordCache {
+    snapshot: RwLock<Option<Snapshot>>,
+    /// Unix time of the last successful sync, 0 before the first one
+    last_sync: Arc<AtomicU64>,
+    /// Unix time the whole snapshot was last fetched, 0 before the first time
+    last_full_sync: AtomicU64,
+    metrics: RecordCacheMetrics,
+}
+
+impl RecordCache {
+    pub fn new(meter: &Meter) -> Self {
+        let last_sync = Arc::new(AtomicU64::new(0));
+        Self {
+            snapshot: RwLock::new(None),
+            metrics: RecordCacheMetrics::new(meter, last_sync.clone()),
+            last_sync,
+            last_full_sync: AtomicU64::new(0),
+        }
+    }
+
+    /// Create the cache and keep it synced with carbide-api in the background.
+    pub fn spawn(
+        forge_client: Arc<Mutex<ForgeClientT>>,
+        sync_interval: Duration,
+        meter: &Meter,
+    ) -> Arc<Self> {
+        let cache = Arc::new(Self::new(meter));
+
+        let sync_cache = cache.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(sync_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = sync_cache.sync(&forge_client).await {
+                    tracing::warn!(
+                        error = %e,
+                        "Failed to sync record cache, answering from the last copy"
+                    );
+                }
+            }
+        });
+
+        cache
+    }
+
+    async fn sync(&self, forge_client: &Mutex<ForgeClientT>) -> Result<(), tonic::Status> {
+        let version = if self.full_sync_due() {
+            None
+        } else {
+            self.snapshot
+                .read()
+                .await
+                .as_ref()
+                .map(|snapshot| snapshot.version.clone())
+        };
+
+        let mut response = forge_client
+            .lock()
+            .await
+            .get_dns_snapshot(DnsSnapshotRequest {
+                version,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        // If the version changes midway, the next page fails and the next sync starts over.
+        while let Some(page_token) = response.next_page_token.take() {
+            let page = forge_client
+                .lock()
+                .await
+                .get_dns_snapshot(DnsSnapshotRequest {
+                    page_token: Some(page_token),
+                    ..Default::default()
+                })
+                .await?
+                .into_inner();
+            response.zones.extend(page.zones);
+            response.records.extend(page.records);
+            response.next_page_token = page.next_page_token;
+        }
+        self.update(response).await;
+        Ok(())
+    }
+
+    fn full_sync_due(&self) -> bool {
+        let last_full_sync = self.last_full_sync.load(Ordering::Relaxed);
+        unix_time().saturating_sub(last_full_sync) >= FULL_SYNC_INTERVAL.as_secs()
+    }
+
+    async fn update(&self, response: DnsSnapshotResponse) {
+        if !response.unchanged {
+            self.last_full_sync.store(unix_time(), Ordering::Relaxed);
+            let snapshot = Snapshot::from(response);
+            tracing::info!(
+                version = %snapshot.version,
+                zones = snapshot.zones.len(),
+                names = snapshot.records.len(),
+                "Record cache updated"
+            );
+            *self.snapshot.write().await = Some(snapshot);
+        }
+        self.last_sync.store(unix_time(), Ordering::Relaxed);
+    }
+
+    /// Answer a lookup the way carbide-api's LookupRecord would. None if the cache hasn't
+    /// been synced yet or doesn't have the name, and the lookup has to go to carbide-api.
+    pub async fn lookup(
+        &self,
+        qname: &str,
+        qtype: &str,
+    ) -> Option<DnsResourceRecordLookupResponse> {
+        let records = self.read(|snapshot| snapshot.lookup(qname, qtype)).await?;
+        Some(DnsResourceRecordLookupResponse { records })
+    }
+
+    /// Answer GetAllDomains. None if the cache hasn't been synced yet.
+    pub async fn get_all_domains(&self) -> Option<GetAllDomainsResponse> {
+        let result = self
+            .read(|snapshot| {
+                let mut domains = snapshot
+                    .zones
+                    .values()
+                    .filter_map(|zone| zone.domain.clone())
+                    .collect::<Vec<_>>();
+                domains.sort_by(|a, b| a.zone.cmp(&b.zone));
+                Some(domains)
+            })
+            .await?;
+        Some(GetAllDomainsResponse { result })
+    }
+
+    /// Answer GetAllDomainMetadata. None if the cache hasn't been synced yet or doesn't have
+    /// the domain.
+    pub async fn get_domain_metadata(&self, domain: &str) -> Option<DomainMetadataResponse> {
+        let zone = self
+            .read(|snapshot| snapshot.zones.get(&normalize(domain)).cloned())
+            .await?;
+        Some(DomainMetadataResponse {
+            result: zone.metadata,
+        })
+    }
+
+    async fn read<T>(&self, f: impl FnOnce(&Snapshot) -> Option<T>) -> Option<T> {
+        let snapshot = self.snapshot.read().await;
+        match snapshot.as_ref().and_then(f) {
+            Some(result) => {
+                self.metrics.hit.add(1, &[]);
+                Some(result)
+            }
+            None => {
+                self.metrics.miss.add(1, &[]);
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Snapshot {
+    version: String,
+    /// Zones by lowercase name, without trailing dot
+    zones: HashMap<String, DnsSnapshotZone>,
+    /// Records by lowercase name, without trailing dot
+    records: HashMap<String, Vec<DnsResourceRecord>>,
+}
+
+impl From<DnsSnapshotResponse> for Snapshot {
+    fn from(response: DnsSnapshotResponse) -> Self {
+        let zones = response
+            .zones
+            .into_iter()
+            .map(|zone| (normalize(&zone.name), zone))
+            .collect();
+
+        let mut records: HashMap<String, Vec<DnsResourceRecord>> = HashMap::new();
+        for record in response.records {
+            records
+                .entry(normalize(&record.qname))
+                .or_default()
+                .push(record);
+        }
+
+        Self {
+            version: response.version,
+            zones,
+            records,
+        }
+    }
+}
+
+impl Snapshot {
+    /// Like LookupRecord, SOA queries get the SOA of the zone, ANY queries all records and
+    /// the SOA if the name is a zone, and other queries all records. PowerDNS picks the
+    /// ones it needs. None for names which are neither a zone nor have records.
+    fn lookup(&self, qname: &str, qtype: &str) -> Option<Vec<DnsResourceRecord>> {
+        let name = normalize(qname);
+        let zone = self.zones.get(&name);
+        let records = self.records.get(&name);
+        if zone.is_none() && records.is_none() {
+            return None;
+        }
+        let soa = zone.and_then(|zone| zone.soa.clone());
+        let records = records.cloned().unwrap_or_default();
+
+        Some(match DnsResourceRecordType::try_from(qtype) {
+            Ok(DnsResourceRecordType::SOA) => soa.into_iter().collect(),
+            Ok(DnsResourceRecordType::ANY) => records.into_iter().chain(soa).collect(),
+            _ => records,
+        })
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use rpc::protos::dns::{DomainInfo, Metadata};
+
+    use super::*;
+
+    fn record(qname: &str, qtype: &str, content: &str) -> DnsResourceRecord {
+        DnsResourceRecord {
+            qname: qname.to_string(),
+            qtype: qtype.to_string(),
+            ttl: 300,
+            content: content.to_string(),
+            domain_id: None,
+            scope_mask: None,
+            auth: None,
+        }
+    }
+
+    fn snapshot(version: &str) -> DnsSnapshotResponse {
+        DnsSnapshotResponse {
+            version: version.to_string(),
+            unchanged: false,
+            next_page_token: None,
+            zones: vec![
+                DnsSnapshotZone {
+                    name: "example.com".to_string(),
+                    soa: Some(record("example.com", "SOA", "ns1.example.com. ...")),
+                    domain: Some(DomainInfo {
+                        zone: "example.com.".to_string(),
+                        kind: "native".to_string(),
+                        ..Default::default()
+                    }),
+                    metadata: Some(Metadata {
+                        allow_axfr_from: vec!["192.0.2.53".to_string()],
+                    }),
+                },
+                DnsSnapshotZone {
+                    name: "2.0.192.in-addr.arpa".to_string(),
+                    soa: Some(record(
+                        "2.0.192.in-addr.arpa",
+                        "SOA",
+                        "ns1.example.com. ...",
+                    )),
+                    domain: None,
+                    metadata: None,
+                },
+            ],
+            records: vec![
+                record("host-1.example.com.", "A", "192.0.2.10"),
+                record("host-1.example.com.", "AAAA", "2001:db8::10"),
+                record("10.2.0.192.in-addr.arpa.", "PTR", "host-1.example.com."),
+            ],
+        }
+    }
+
+    fn contents(response: Option<DnsResourceRecordLookupResponse>) -> Vec<String> {
+        response
+            .expect("cache should be synced")
+            .records
+            .into_iter()
+            .map(|record| format!("{} {}", record.qtype, record.content))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_lookup() {
+        let cache = RecordCache::new(&opentelemetry::global::meter("test"));
+        assert!(cache.lookup("host-1.example.com.", "A").await.is_none());
+
+        cache.update(snapshot("1")).await;
+
+        assert_eq!(
+            contents(cache.lookup("HOST-1.example.com.", "A").await),
+            ["A 192.0.2.10", "AAAA 2001:db8::10"]
+        );
+        assert_eq!(
+            contents(cache.lookup("example.com.", "SOA").await),
+            ["SOA ns1.example.com. ..."]
+        );
+        assert_eq!(
+            contents(cache.lookup("2.0.192.in-addr.arpa", "ANY").await),
+            ["SOA ns1.example.com. ..."]
+        );
+        assert_eq!(
+            contents(cache.lookup("10.2.0.192.in-addr.arpa.", "PTR").await),
+            ["PTR host-1.example.com."]
+        );
+        assert!(contents(cache.lookup("host-1.example.com.", "SOA").await).is_empty());
+
+        // Names the cache doesn't have are looked up in carbide-api.
+        assert!(cache.lookup("host-2.example.com.", "ANY").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_domains() {
+        let cache = RecordCache::new(&opentelemetry::global::meter("test"));
+        assert!(cache.get_all_domains().await.is_none());
+
+        cache.update(snapshot("1")).await;
+
+        let domains = cache.get_all_domains().await.unwrap().result;
+        assert_eq!(domains.len(), 1, "reverse zones aren't domains");
+        assert_eq!(domains[0].zone, "example.com.");
+
+        let metadata = cache.get_domain_metadata("example.com.").await.unwrap();
+        assert_eq!(metadata.result.unwrap().allow_axfr_from, ["192.0.2.53"]);
+        assert!(cache.get_domain_metadata("unknown.com").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_snapshot_is_kept() {
+        let cache = RecordCache::new(&opentelemetry::global::meter("test"));
+        cache.update(snapshot("1")).await;
+        cache
+            .update(DnsSnapshotResponse {
+                version: "1".to_string(),
+                unchanged: true,
+                ..Default::default()
+            })
+            .await;
+
+        assert_eq!(
+            contents(cache.lookup("host-1.example.com", "A").await).len(),
+            2
+        );
+        assert!(cache.last_sync.load(Ordering::Relaxed) > 0);
+    }
+
+    #[tokio::test]
+    async fn test_full_sync_due() {
+        let cache = RecordCache::new(&opentelemetry::global::meter("test"));
+        assert!(cache.full_sync_due());
+
+        cache.update(snapshot("1")).await;
+        assert!(!cache.full_sync_due());
+
+        cache.last_full_sync.store(
+            unix_time() - FULL_SYNC_INTERVAL.as_secs(),
+            Ordering::Relaxed,
+        );
+        assert!(cache.full_sync_due());
+    }
+}
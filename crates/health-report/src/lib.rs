@@ -61,6 +61,7 @@ impl HealthReport {
     pub const SITE_EXPLORER_SOURCE: &str = "site-explorer";
     pub const SKU_VALIDATION_SOURCE: &str = "sku-validation";
     pub const QUARANTINE_SOURCE: &str = "quarantine";
+    pub const STATE_CONTROLLER_SOURCE: &str = "state-controller";
 
     /// Returns a health report with no successes or errors reported
     pub fn empty(source: String) -> Self {
@@ -187,6 +188,26 @@ impl HealthReport {
         }
     }
 
+    /// Returns a health report which indicates that an object stayed in a
+    /// state for longer than the configured dwell time limit
+    pub fn state_dwell_time_exceeded(
+        state: String,
+        message: String,
+        in_alert_since: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        Self {
+            source: Self::STATE_CONTROLLER_SOURCE.to_string(),
+            observed_at: Some(chrono::Utc::now()),
+            successes: vec![],
+            alerts: vec![HealthProbeAlert::state_dwell_time_exceeded(
+                state,
+                message,
+                in_alert_since,
+            )],
+            triggered_by: None,
+        }
+    }
+
     /// Returns a health report which indicates that a machine failed SKU validation
     pub fn sku_mismatch(mismatches: Vec<String>) -> Self {
         Self {
@@ -428,6 +449,25 @@ impl HealthProbeAlert {
         }
     }
 
+    /// Creates a StateDwellTimeExceeded alert
+    ///
+    /// The alert is informational and carries no classifications, since the
+    /// object being stuck is the symptom of another problem.
+    pub fn state_dwell_time_exceeded(
+        state: String,
+        message: String,
+        in_alert_since: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        Self {
+            id: HealthProbeId::state_dwell_time_exceeded(),
+            target: Some(state),
+            in_alert_since: Some(in_alert_since),
+            message,
+            tenant_message: None,
+            classifications: vec![],
+        }
+    }
+
     /// Creates a MissingReport alert
     pub fn missing_report() -> Self {
         Self {
@@ -567,6 +607,12 @@ impl HealthProbeId {
         HealthProbeId("StaleAgentVersion".to_string())
     }
 
+    /// Returns the ID of the HealthProbe that indicates that an object stayed in
+    /// a state for longer than the configured dwell time limit
+    pub fn state_dwell_time_exceeded() -> Self {
+        HealthProbeId("StateDwellTimeExceeded".to_string())
+    }
+
     /// The alert indicates that no health report was received, where health report
     /// was expected. It is different from `heartbeat_timeout` in the following sense
     /// - HeartbeatTimeout alerts can be emitted if data is available, but stale.
@@ -704,6 +750,11 @@ impl HealthAlertClassification {
         Self("SensorCritical".to_string())
     }
 
+    // Issues detected in the output of a machine's serial console
+    pub fn serial_console() -> Self {
+        Self("SerialConsole".to_string())
+    }
+
     /// Excludes the host from state machine SLA tracking.
     /// When this classification is present on any alert in the aggregate health report,
     /// the host will not be counted as violating its state machine SLA.
@@ -170,3 +170,86 @@ fn event_to_logs<B: Bmc>(event: &Event, bmc: &B) -> Vec<Result<CollectorEvent, H
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use bmc_mock::test_support::dell_poweredge_r750_bmc;
+    use nv_redfish::core::ODataId;
+    use serde_json::json;
+
+    use super::*;
+
+    fn attribute<'a>(record: &'a LogRecord, name: &str) -> Option<&'a str> {
+        record
+            .attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[tokio::test]
+    async fn collects_events_emitted_by_bmc_mock() {
+        let h = dell_poweredge_r750_bmc().await;
+        let mut collector = SseLogCollector {
+            bmc: Arc::clone(&h.bmc),
+        };
+        let mut events = collector.connect().await.unwrap();
+
+        for (uri, body) in [
+            (
+                "/redfish/v1/Systems/System.Embedded.1/Actions/ComputerSystem.Reset",
+                json!({"ResetType": "ForceRestart"}),
+            ),
+            ("/InjectedBugs", json!({"firmware_update_failure": true})),
+            (
+                "/redfish/v1/UpdateService/Actions/UpdateService.SimpleUpdate",
+                json!({"ImageURI": "http://images.local/fw.bin"}),
+            ),
+        ] {
+            h.bmc
+                .create::<_, serde_json::Value>(&ODataId::from(uri.to_string()), &body)
+                .await
+                .unwrap();
+        }
+
+        let mut records = Vec::new();
+        for _ in 0..3 {
+            match events.next().await {
+                Some(Ok(CollectorEvent::Log(record))) => records.push(record),
+                _ => panic!("expected a log record"),
+            }
+        }
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| (
+                    attribute(record, "message_id").unwrap(),
+                    record.severity.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("ResourceEvent.1.3.ResourcePoweringOff", "OK"),
+                ("ResourceEvent.1.0.ResourceErrorsDetected", "Warning"),
+                ("Update.1.0.ApplyFailed", "Critical"),
+            ]
+        );
+        assert_eq!(
+            attribute(&records[0], "origin_of_condition"),
+            Some("/redfish/v1/Systems/System.Embedded.1")
+        );
+
+        // Every streamed event is also readable from the manager event log.
+        let managers = h.service_root.managers().await.unwrap().unwrap();
+        let manager = managers.members().await.unwrap().remove(0);
+        let log_service = manager.log_services().await.unwrap().unwrap().remove(0);
+        let entries = log_service.entries().await.unwrap().unwrap();
+        for record in &records {
+            let log_entry_id = attribute(record, "log_entry_id").unwrap();
+            let entry = entries
+                .iter()
+                .find(|entry| entry.odata_id().to_string() == log_entry_id)
+                .expect("streamed event should be logged");
+            assert_eq!(entry.message.clone().flatten(), Some(record.body.clone()));
+        }
+    }
+}
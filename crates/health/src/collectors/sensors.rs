@@ -188,6 +188,9 @@ impl<B: Bmc> SensorRecordable<B> for MonitoredEntity<B> {
                         processor_type.to_snake_case().to_string(),
                     ));
                 }
+                if let Some(manufacturer) = entity.raw().manufacturer.clone().flatten() {
+                    attrs.push((Cow::Borrowed("manufacturer"), manufacturer));
+                }
                 if let Some(model) = entity.raw().model.clone().flatten() {
                     attrs.push((Cow::Borrowed("model"), model));
                 }
@@ -199,21 +202,33 @@ impl<B: Bmc> SensorRecordable<B> for MonitoredEntity<B> {
                         device_type.to_snake_case().to_string(),
                     ));
                 }
+                if let Some(manufacturer) = entity.raw().manufacturer.clone().flatten() {
+                    attrs.push((Cow::Borrowed("manufacturer"), manufacturer));
+                }
                 if let Some(model) = entity.raw().model.clone().flatten() {
                     attrs.push((Cow::Borrowed("model"), model));
                 }
             }
             MonitoredEntity::Drive { entity, .. } => {
+                if let Some(manufacturer) = entity.raw().manufacturer.clone().flatten() {
+                    attrs.push((Cow::Borrowed("manufacturer"), manufacturer));
+                }
                 if let Some(model) = entity.raw().model.clone().flatten() {
                     attrs.push((Cow::Borrowed("model"), model));
                 }
             }
             MonitoredEntity::PowerSupply { entity, .. } => {
+                if let Some(manufacturer) = entity.raw().manufacturer.clone().flatten() {
+                    attrs.push((Cow::Borrowed("manufacturer"), manufacturer));
+                }
                 if let Some(model) = entity.raw().model.clone().flatten() {
                     attrs.push((Cow::Borrowed("model"), model));
                 }
             }
             MonitoredEntity::Chassis { entity, .. } => {
+                if let Some(manufacturer) = entity.raw().manufacturer.clone().flatten() {
+                    attrs.push((Cow::Borrowed("manufacturer"), manufacturer));
+                }
                 if let Some(model) = entity.raw().model.clone().flatten() {
                     attrs.push((Cow::Borrowed("model"), model));
                 }
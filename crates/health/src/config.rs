@@ -15,8 +15,9 @@
  * limitations under the License.
  */
 
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::path::Path;
 use std::time::Duration;
 
@@ -25,6 +26,8 @@ use figment::providers::{Env, Format, Serialized, Toml};
 use serde::{Deserialize, Deserializer, Serialize};
 use url::Url;
 
+use crate::sink::Classification;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
@@ -46,6 +49,9 @@ pub struct Config {
     /// Total number of shards in the StatefulSet
     pub shards_count: usize,
 
+    /// Dynamic shard membership (if present, `shard` and `shards_count` are ignored)
+    pub sharding: ShardingConfig,
+
     /// Maximum cache size per BMC, uses etags
     pub cache_size: usize,
 
@@ -64,6 +70,7 @@ impl Default for Config {
             metrics: MetricsConfig::default(),
             shard: 0,
             shards_count: 1,
+            sharding: ShardingConfig::default(),
             cache_size: 100,
             bmc_proxy_url: None,
         }
@@ -208,6 +215,9 @@ pub struct SinksConfig {
 
     /// OTLP log export sink: streams events to an OpenTelemetry collector via gRPC.
     pub otlp: Configurable<OtlpSinkConfig>,
+
+    /// MQTT sink: publishes collector events to an MQTT broker.
+    pub mqtt: Configurable<MqttSinkConfig>,
 }
 
 impl Default for SinksConfig {
@@ -223,6 +233,7 @@ impl Default for SinksConfig {
             ),
             log_file: Configurable::Disabled,
             otlp: Configurable::Disabled,
+            mqtt: Configurable::Disabled,
         }
     }
 }
@@ -260,6 +271,10 @@ pub struct OtlpSinkConfig {
     pub batch_size: usize,
     #[serde(with = "humantime_serde")]
     pub flush_interval: std::time::Duration,
+
+    /// On-disk buffer for batches which could not be exported while the
+    /// collector is unreachable.
+    pub disk_buffer: Configurable<DiskBufferConfig>,
 }
 
 impl Default for OtlpSinkConfig {
@@ -268,6 +283,123 @@ impl Default for OtlpSinkConfig {
             endpoint: "http://localhost:4317".to_string(),
             batch_size: 512,
             flush_interval: std::time::Duration::from_secs(2),
+            disk_buffer: Configurable::Disabled,
+        }
+    }
+}
+
+/// Placeholders which may be used in `sinks.mqtt.topic_template`.
+pub const MQTT_TOPIC_PLACEHOLDERS: &[&str] = &["rack_id", "endpoint", "kind", "collector"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSinkConfig {
+    pub broker_host: String,
+    pub broker_port: u16,
+    pub client_id: String,
+
+    /// Topic events are published to. Supports the `{rack_id}`, `{endpoint}`,
+    /// `{kind}` and `{collector}` placeholders.
+    pub topic_template: String,
+
+    pub qos: MqttQos,
+    pub retain: bool,
+
+    /// Event kinds which are published, all kinds by default.
+    pub event_kinds: Vec<MqttEventKind>,
+
+    /// Maximum number of events published as one JSON array per topic.
+    pub batch_size: usize,
+    #[serde(with = "humantime_serde")]
+    pub flush_interval: Duration,
+
+    /// Events waiting to be published. Events are dropped once it is full.
+    pub queue_capacity: usize,
+
+    /// Rate limit applied to publishes.
+    pub rate_limit: Configurable<RateLimitConfig>,
+
+    pub username: Option<String>,
+    /// File containing the password used together with `username`.
+    pub password_file: Option<String>,
+}
+
+impl Default for MqttSinkConfig {
+    fn default() -> Self {
+        Self {
+            broker_host: "localhost".to_string(),
+            broker_port: 1883,
+            client_id: "carbide-hardware-health".to_string(),
+            topic_template: "health/{rack_id}/{endpoint}/{kind}".to_string(),
+            qos: MqttQos::AtLeastOnce,
+            retain: false,
+            event_kinds: vec![
+                MqttEventKind::Metric,
+                MqttEventKind::Firmware,
+                MqttEventKind::Log,
+                MqttEventKind::HealthReport,
+            ],
+            batch_size: 100,
+            flush_interval: Duration::from_secs(1),
+            queue_capacity: 10_000,
+            rate_limit: Configurable::Enabled(RateLimitConfig::default()),
+            username: None,
+            password_file: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)] // Named after the MQTT QoS levels
+pub enum MqttQos {
+    AtMostOnce,
+    AtLeastOnce,
+    ExactlyOnce,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttEventKind {
+    Metric,
+    Firmware,
+    Log,
+    HealthReport,
+}
+
+impl MqttEventKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Metric => "metric",
+            Self::Firmware => "firmware",
+            Self::Log => "log",
+            Self::HealthReport => "health_report",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiskBufferConfig {
+    /// Directory holding buffered events. Every sink needs its own directory.
+    pub directory: String,
+
+    /// Maximum size of all buffered events. The oldest events are dropped
+    /// once the limit is reached.
+    pub max_bytes: u64,
+
+    /// Interval at which delivery of buffered events is retried while the
+    /// destination is unreachable. The OTLP sink retries on every flush instead.
+    #[serde(with = "humantime_serde")]
+    pub replay_interval: Duration,
+}
+
+impl Default for DiskBufferConfig {
+    fn default() -> Self {
+        Self {
+            directory: String::new(),
+            max_bytes: 268_435_456, // 256MB
+            replay_interval: Duration::from_secs(10),
         }
     }
 }
@@ -308,6 +440,10 @@ pub struct HealthReportSinkConfig {
 
     /// Number of concurrent workers submitting reports to Carbide API.
     pub workers: usize,
+
+    /// On-disk buffer for reports which could not be submitted while
+    /// Carbide API is unreachable.
+    pub disk_buffer: Configurable<DiskBufferConfig>,
 }
 
 impl Default for HealthReportSinkConfig {
@@ -315,6 +451,7 @@ impl Default for HealthReportSinkConfig {
         Self {
             connection: CarbideApiConnectionConfig::default(),
             workers: 4,
+            disk_buffer: Configurable::Disabled,
         }
     }
 }
@@ -327,6 +464,10 @@ pub struct RackHealthReportSinkConfig {
 
     /// Number of concurrent workers submitting rack-level reports to Carbide API.
     pub workers: usize,
+
+    /// On-disk buffer for rack-level reports which could not be submitted while
+    /// Carbide API is unreachable.
+    pub disk_buffer: Configurable<DiskBufferConfig>,
 }
 
 impl Default for RackHealthReportSinkConfig {
@@ -334,6 +475,7 @@ impl Default for RackHealthReportSinkConfig {
         Self {
             connection: CarbideApiConnectionConfig::default(),
             workers: 2,
+            disk_buffer: Configurable::Disabled,
         }
     }
 }
@@ -346,6 +488,10 @@ pub struct SwitchHealthReportSinkConfig {
 
     /// Number of concurrent workers submitting switch-level reports to Carbide API.
     pub workers: usize,
+
+    /// On-disk buffer for switch-level reports which could not be submitted while
+    /// Carbide API is unreachable.
+    pub disk_buffer: Configurable<DiskBufferConfig>,
 }
 
 impl Default for SwitchHealthReportSinkConfig {
@@ -353,6 +499,7 @@ impl Default for SwitchHealthReportSinkConfig {
         Self {
             connection: CarbideApiConnectionConfig::default(),
             workers: 2,
+            disk_buffer: Configurable::Disabled,
         }
     }
 }
@@ -365,6 +512,10 @@ pub struct PowerShelfHealthReportSinkConfig {
 
     /// Number of concurrent workers submitting power-shelf-level reports to Carbide API.
     pub workers: usize,
+
+    /// On-disk buffer for power-shelf-level reports which could not be submitted while
+    /// Carbide API is unreachable.
+    pub disk_buffer: Configurable<DiskBufferConfig>,
 }
 
 impl Default for PowerShelfHealthReportSinkConfig {
@@ -372,6 +523,56 @@ impl Default for PowerShelfHealthReportSinkConfig {
         Self {
             connection: CarbideApiConnectionConfig::default(),
             workers: 2,
+            disk_buffer: Configurable::Disabled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShardingConfig {
+    /// Peer discovery configuration (if present, endpoints are distributed across
+    /// all discovered replicas instead of the static shard ordinals)
+    pub peer_discovery: Configurable<PeerDiscoveryConfig>,
+
+    /// Time a replica waits before it starts monitoring endpoints which it took over
+    /// from another replica. Needs to be longer than the peer refresh interval, so
+    /// that the previous owner stops monitoring first.
+    #[serde(with = "humantime_serde")]
+    pub handoff_delay: Duration,
+}
+
+impl Default for ShardingConfig {
+    fn default() -> Self {
+        Self {
+            peer_discovery: Configurable::Disabled,
+            handoff_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeerDiscoveryConfig {
+    /// DNS name of the headless service which resolves to the addresses of all
+    /// ready replicas (e.g. `carbide-hw-health.forge-system.svc.cluster.local`)
+    pub service: String,
+
+    /// Address of this replica, as returned when resolving `service`.
+    /// Typically injected via `CARBIDE_HEALTH__SHARDING__PEER_DISCOVERY__SELF_ADDRESS`
+    pub self_address: Option<IpAddr>,
+
+    /// Interval between peer list refreshes
+    #[serde(with = "humantime_serde")]
+    pub refresh_interval: Duration,
+}
+
+impl Default for PeerDiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            service: String::new(),
+            self_address: None,
+            refresh_interval: Duration::from_secs(15),
         }
     }
 }
@@ -436,6 +637,13 @@ pub struct ProcessorsConfig {
 
     /// Rack-level leak processor: aggregates tray leak reports per rack.
     pub rack_leak: Configurable<RackLeakProcessorConfig>,
+
+    /// Threshold rules processor: evaluates operator-defined rules over sensor readings.
+    pub threshold_rules: Configurable<ThresholdRulesProcessorConfig>,
+
+    /// Anomaly detection processor: compares sensor readings against their own
+    /// baseline and against the same sensor on other trays in the rack.
+    pub anomaly_detection: Configurable<AnomalyDetectionProcessorConfig>,
 }
 
 impl Default for ProcessorsConfig {
@@ -443,6 +651,8 @@ impl Default for ProcessorsConfig {
         Self {
             leak_detection: Configurable::Enabled(LeakDetectionProcessorConfig::default()),
             rack_leak: Configurable::Enabled(RackLeakProcessorConfig::default()),
+            threshold_rules: Configurable::Disabled,
+            anomaly_detection: Configurable::Disabled,
         }
     }
 }
@@ -478,6 +688,120 @@ impl Default for RackLeakProcessorConfig {
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThresholdRulesProcessorConfig {
+    /// Rules which are evaluated for every sensor reading
+    pub rules: Vec<ThresholdRuleConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThresholdRuleConfig {
+    /// Unique name of the rule. Used in alert targets and messages.
+    pub name: String,
+
+    /// Only sensors with this reading type (e.g. `temperature`) are evaluated
+    #[serde(default)]
+    pub metric_type: Option<String>,
+
+    /// Regular expression which the sensor ID needs to match (e.g. `(?i).*inlet.*`)
+    #[serde(default)]
+    pub sensor: Option<String>,
+
+    /// Regular expressions which metric labels need to match, e.g. to restrict
+    /// the rule to a vendor or model via the `manufacturer` and `model` labels
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+
+    /// The condition which raises an alert
+    pub condition: ThresholdCondition,
+
+    /// Number of consecutive samples which need to violate a threshold
+    /// before an alert is raised
+    #[serde(default = "ThresholdRuleConfig::default_consecutive_samples")]
+    pub consecutive_samples: usize,
+
+    /// Classifications which are attached to the alert
+    #[serde(default = "ThresholdRuleConfig::default_classifications")]
+    pub classifications: Vec<Classification>,
+}
+
+impl ThresholdRuleConfig {
+    fn default_consecutive_samples() -> usize {
+        1
+    }
+
+    fn default_classifications() -> Vec<Classification> {
+        vec![Classification::SensorCritical]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdCondition {
+    /// The reading is greater than the value
+    Above(f64),
+    /// The reading is less than the value
+    Below(f64),
+    /// No reading has been received for the duration
+    MissingFor(#[serde(with = "humantime_serde")] Duration),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnomalyDetectionProcessorConfig {
+    /// Reading types (e.g. `temperature`, `rotational`) which are evaluated.
+    /// All reading types are evaluated if empty.
+    pub metric_types: Vec<String>,
+
+    /// Weight of the newest sample in the moving average and variance
+    pub smoothing_factor: f64,
+
+    /// Number of samples collected for a sensor before it is evaluated.
+    /// The baseline is rebuilt from this many samples after a restart or
+    /// shard reassignment.
+    pub warmup_samples: u32,
+
+    /// Number of standard deviations a reading or its rate of change needs
+    /// to deviate from the sensor's own baseline to raise an alert
+    pub baseline_deviation: f64,
+
+    /// Number of (median absolute) deviations a sensor needs to deviate from
+    /// the same sensor on other trays in the rack to raise an alert
+    pub peer_deviation: f64,
+
+    /// Minimum number of other trays in the rack reporting the same sensor
+    /// before peer comparison is performed
+    pub min_peers: usize,
+
+    /// Deviations smaller than this fraction of the baseline are never reported,
+    /// which avoids alerts for sensors with very stable readings
+    pub min_relative_deviation: f64,
+
+    /// Maximum number of sensors which are tracked across all endpoints
+    pub max_tracked_sensors: usize,
+
+    /// Classifications which are attached to anomaly alerts
+    pub classifications: Vec<Classification>,
+}
+
+impl Default for AnomalyDetectionProcessorConfig {
+    fn default() -> Self {
+        Self {
+            metric_types: Vec::new(),
+            smoothing_factor: 0.1,
+            warmup_samples: 30,
+            baseline_deviation: 4.0,
+            peer_deviation: 4.0,
+            min_peers: 4,
+            min_relative_deviation: 0.05,
+            max_tracked_sensors: 500_000,
+            classifications: vec![Classification::SensorWarning],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct SensorCollectorConfig {
@@ -757,6 +1081,21 @@ impl Config {
             ));
         }
 
+        if let Configurable::Enabled(peer_discovery) = &self.sharding.peer_discovery {
+            if peer_discovery.service.is_empty() {
+                return Err("sharding.peer_discovery.service must not be empty".to_string());
+            }
+            if peer_discovery.self_address.is_none() {
+                return Err("sharding.peer_discovery.self_address must be set".to_string());
+            }
+            if self.sharding.handoff_delay <= peer_discovery.refresh_interval {
+                return Err(format!(
+                    "sharding.handoff_delay ({:?}) must be greater than sharding.peer_discovery.refresh_interval ({:?})",
+                    self.sharding.handoff_delay, peer_discovery.refresh_interval
+                ));
+            }
+        }
+
         if let Configurable::Enabled(rate_limit) = &self.rate_limit
             && rate_limit.bucket_replenish.is_zero()
         {
@@ -765,6 +1104,61 @@ impl Config {
             );
         }
 
+        if let Configurable::Enabled(threshold_rules) = &self.processors.threshold_rules {
+            let mut names = HashSet::new();
+            for rule in &threshold_rules.rules {
+                if rule.name.is_empty() {
+                    return Err("threshold rule name must not be empty".to_string());
+                }
+                if !names.insert(rule.name.as_str()) {
+                    return Err(format!("duplicate threshold rule name: {}", rule.name));
+                }
+                if rule.consecutive_samples == 0 {
+                    return Err(format!(
+                        "threshold rule {}: consecutive_samples must be greater than 0",
+                        rule.name
+                    ));
+                }
+                for pattern in rule.sensor.iter().chain(rule.labels.values()) {
+                    regex::Regex::new(pattern).map_err(|e| {
+                        format!(
+                            "threshold rule {}: invalid pattern {pattern}: {e}",
+                            rule.name
+                        )
+                    })?;
+                }
+            }
+        }
+
+        if let Configurable::Enabled(anomaly_detection) = &self.processors.anomaly_detection {
+            if !(anomaly_detection.smoothing_factor > 0.0
+                && anomaly_detection.smoothing_factor <= 1.0)
+            {
+                return Err(
+                    "processors.anomaly_detection.smoothing_factor must be in (0, 1]".to_string(),
+                );
+            }
+            if anomaly_detection.warmup_samples == 0 {
+                return Err(
+                    "processors.anomaly_detection.warmup_samples must be greater than 0"
+                        .to_string(),
+                );
+            }
+            if anomaly_detection.baseline_deviation <= 0.0
+                || anomaly_detection.peer_deviation <= 0.0
+            {
+                return Err(
+                    "processors.anomaly_detection deviation thresholds must be greater than 0"
+                        .to_string(),
+                );
+            }
+            if anomaly_detection.min_peers == 0 {
+                return Err(
+                    "processors.anomaly_detection.min_peers must be greater than 0".to_string(),
+                );
+            }
+        }
+
         if let Configurable::Enabled(leak_detection) = &self.processors.leak_detection
             && leak_detection.minimum_alerts_per_report == 0
         {
@@ -774,6 +1168,55 @@ impl Config {
             );
         }
 
+        let disk_buffers = [
+            (
+                "health_report",
+                self.sinks.health_report.as_option().map(|c| &c.disk_buffer),
+            ),
+            (
+                "rack_health_report",
+                self.sinks
+                    .rack_health_report
+                    .as_option()
+                    .map(|c| &c.disk_buffer),
+            ),
+            (
+                "switch_health_report",
+                self.sinks
+                    .switch_health_report
+                    .as_option()
+                    .map(|c| &c.disk_buffer),
+            ),
+            (
+                "power_shelf_health_report",
+                self.sinks
+                    .power_shelf_health_report
+                    .as_option()
+                    .map(|c| &c.disk_buffer),
+            ),
+            ("otlp", self.sinks.otlp.as_option().map(|c| &c.disk_buffer)),
+        ];
+        let mut disk_buffer_directories = HashSet::new();
+        for (sink, disk_buffer) in disk_buffers {
+            let Some(Configurable::Enabled(disk_buffer)) = disk_buffer else {
+                continue;
+            };
+            if disk_buffer.directory.is_empty() {
+                return Err(format!("sinks.{sink}.disk_buffer.directory must be set"));
+            }
+            if disk_buffer.max_bytes == 0 {
+                return Err(format!(
+                    "sinks.{sink}.disk_buffer.max_bytes must be greater than 0"
+                ));
+            }
+            if !disk_buffer_directories.insert(disk_buffer.directory.as_str()) {
+                return Err(format!(
+                    "sinks.{sink}.disk_buffer.directory {} is used by another sink",
+                    disk_buffer.directory
+                ));
+            }
+        }
+
         for (index, endpoint) in self
             .endpoint_sources
             .static_bmc_endpoints
@@ -814,6 +1257,29 @@ impl Config {
             logs.validate()?;
         }
 
+        if let Configurable::Enabled(mqtt) = &self.sinks.mqtt {
+            if mqtt.client_id.is_empty() {
+                return Err("sinks.mqtt.client_id must not be empty".to_string());
+            }
+            if mqtt.batch_size == 0 {
+                return Err("sinks.mqtt.batch_size must be greater than 0".to_string());
+            }
+            if mqtt.queue_capacity == 0 {
+                return Err("sinks.mqtt.queue_capacity must be greater than 0".to_string());
+            }
+            if let Configurable::Enabled(rate_limit) = &mqtt.rate_limit
+                && rate_limit.bucket_replenish.is_zero()
+            {
+                return Err(
+                    "sinks.mqtt.rate_limit.bucket_replenish must be greater than 0".to_string(),
+                );
+            }
+            if mqtt.password_file.is_some() && mqtt.username.is_none() {
+                return Err("sinks.mqtt.password_file requires sinks.mqtt.username".to_string());
+            }
+            validate_topic_template(&mqtt.topic_template)?;
+        }
+
         if let Configurable::Enabled(ref otlp) = self.sinks.otlp {
             tonic::transport::Channel::from_shared(otlp.endpoint.clone())
                 .map_err(|_| format!("invalid sinks.otlp.endpoint: {}", otlp.endpoint))?;
@@ -825,6 +1291,36 @@ impl Config {
     }
 }
 
+fn validate_topic_template(template: &str) -> Result<(), String> {
+    if template.is_empty() {
+        return Err("sinks.mqtt.topic_template must not be empty".to_string());
+    }
+    if template.contains(['+', '#']) {
+        return Err(format!(
+            "sinks.mqtt.topic_template must not contain wildcards: {template}"
+        ));
+    }
+
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Err(format!(
+                "sinks.mqtt.topic_template has an unclosed placeholder: {template}"
+            ));
+        };
+        let placeholder = &rest[start + 1..start + len];
+        if !MQTT_TOPIC_PLACEHOLDERS.contains(&placeholder) {
+            return Err(format!(
+                "sinks.mqtt.topic_template has unknown placeholder {{{placeholder}}}, expected one of {}",
+                MQTT_TOPIC_PLACEHOLDERS.join(", ")
+            ));
+        }
+        rest = &rest[start + len + 1..];
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum Configurable<T> {
@@ -917,6 +1413,15 @@ mod tests {
                 "/var/run/secrets/spiffe.io/ca.crt"
             );
             assert_eq!(health_report.workers, 8);
+            let Configurable::Enabled(ref disk_buffer) = health_report.disk_buffer else {
+                panic!("health report disk buffer is disabled")
+            };
+            assert_eq!(
+                disk_buffer.directory,
+                "/var/lib/carbide-hardware-health/buffer/health_report"
+            );
+            assert_eq!(disk_buffer.max_bytes, 268_435_456);
+            assert_eq!(disk_buffer.replay_interval, Duration::from_secs(10));
         } else {
             panic!("health report sink is disabled")
         }
@@ -936,6 +1441,7 @@ mod tests {
         assert!(config.collectors.nvue.is_enabled());
         assert!(!config.sinks.tracing.is_enabled());
         assert!(config.sinks.prometheus.is_enabled());
+        assert!(!config.sinks.mqtt.is_enabled());
 
         if let Configurable::Enabled(ref sensors) = config.collectors.sensors {
             assert_eq!(sensors.rediscover_interval, Duration::from_secs(300));
@@ -971,10 +1477,51 @@ mod tests {
             panic!("leak detection processor is disabled")
         }
 
+        if let Configurable::Enabled(ref threshold_rules) = config.processors.threshold_rules {
+            assert_eq!(threshold_rules.rules.len(), 2);
+            assert_eq!(threshold_rules.rules[0].name, "inlet-temperature-high");
+            assert_eq!(
+                threshold_rules.rules[0].condition,
+                ThresholdCondition::Above(45.0)
+            );
+            assert_eq!(threshold_rules.rules[0].consecutive_samples, 3);
+            assert_eq!(
+                threshold_rules.rules[0].classifications,
+                vec![Classification::SensorCritical]
+            );
+            assert_eq!(
+                threshold_rules.rules[1].condition,
+                ThresholdCondition::MissingFor(Duration::from_secs(300))
+            );
+            assert_eq!(
+                threshold_rules.rules[1].classifications,
+                vec![
+                    Classification::SensorFailure,
+                    Classification::PreventAllocations
+                ]
+            );
+        } else {
+            panic!("threshold rules processor is disabled")
+        }
+
+        if let Configurable::Enabled(ref anomaly_detection) = config.processors.anomaly_detection {
+            assert_eq!(
+                anomaly_detection.metric_types,
+                vec!["rotational".to_string(), "temperature".to_string()]
+            );
+            assert_eq!(anomaly_detection.warmup_samples, 30);
+            assert_eq!(anomaly_detection.min_peers, 4);
+            assert_eq!(anomaly_detection.max_tracked_sensors, 500_000);
+        } else {
+            panic!("anomaly detection processor is disabled")
+        }
+
         assert_eq!(config.metrics.endpoint, "0.0.0.0:9009");
 
         assert_eq!(config.shard, 0);
         assert_eq!(config.shards_count, 1);
+        assert!(!config.sharding.peer_discovery.is_enabled());
+        assert_eq!(config.sharding.handoff_delay, Duration::from_secs(60));
 
         assert_eq!(config.cache_size, 100);
 
@@ -1066,6 +1613,49 @@ cache_size = 50
         config.validate().expect("config should be valid");
     }
 
+    #[test]
+    fn test_sharding_peer_discovery_config() {
+        let toml_content = r#"
+[sharding]
+handoff_delay = "45s"
+
+[sharding.peer_discovery]
+service = "carbide-hw-health.forge-system.svc.cluster.local"
+self_address = "10.1.2.3"
+"#;
+
+        let mut config: Config = Figment::new()
+            .merge(Serialized::defaults(Config::default()))
+            .merge(Toml::string(toml_content))
+            .extract()
+            .expect("failed to parse sharding config");
+
+        let Configurable::Enabled(peer_discovery) = config.sharding.peer_discovery.clone() else {
+            panic!("peer discovery should be enabled");
+        };
+        assert_eq!(
+            peer_discovery.service,
+            "carbide-hw-health.forge-system.svc.cluster.local"
+        );
+        assert_eq!(
+            peer_discovery.self_address,
+            Some("10.1.2.3".parse().unwrap())
+        );
+        assert_eq!(peer_discovery.refresh_interval, Duration::from_secs(15));
+        assert_eq!(config.sharding.handoff_delay, Duration::from_secs(45));
+        config.validate().expect("config should be valid");
+
+        config.sharding.handoff_delay = Duration::from_secs(10);
+        assert!(config.validate().is_err());
+
+        config.sharding.handoff_delay = Duration::from_secs(60);
+        config.sharding.peer_discovery = Configurable::Enabled(PeerDiscoveryConfig {
+            self_address: None,
+            ..peer_discovery
+        });
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_config_validation() {
         let mut config = Config::default();
@@ -1095,6 +1685,53 @@ cache_size = 50
 
         config.processors.leak_detection =
             Configurable::Enabled(LeakDetectionProcessorConfig::default());
+        let threshold_rule = ThresholdRuleConfig {
+            name: "inlet-temperature-high".to_string(),
+            metric_type: None,
+            sensor: Some("(?i).*inlet.*".to_string()),
+            labels: HashMap::new(),
+            condition: ThresholdCondition::Above(45.0),
+            consecutive_samples: 1,
+            classifications: vec![Classification::SensorCritical],
+        };
+        config.processors.threshold_rules = Configurable::Enabled(ThresholdRulesProcessorConfig {
+            rules: vec![threshold_rule.clone(), threshold_rule.clone()],
+        });
+        assert!(config.validate().is_err());
+
+        config.processors.threshold_rules = Configurable::Enabled(ThresholdRulesProcessorConfig {
+            rules: vec![ThresholdRuleConfig {
+                sensor: Some("(unclosed".to_string()),
+                ..threshold_rule.clone()
+            }],
+        });
+        assert!(config.validate().is_err());
+
+        config.processors.threshold_rules = Configurable::Enabled(ThresholdRulesProcessorConfig {
+            rules: vec![threshold_rule],
+        });
+        assert!(config.validate().is_ok());
+
+        config.processors.threshold_rules = Configurable::Disabled;
+        config.processors.anomaly_detection =
+            Configurable::Enabled(AnomalyDetectionProcessorConfig {
+                smoothing_factor: 1.5,
+                ..AnomalyDetectionProcessorConfig::default()
+            });
+        assert!(config.validate().is_err());
+
+        config.processors.anomaly_detection =
+            Configurable::Enabled(AnomalyDetectionProcessorConfig {
+                warmup_samples: 0,
+                ..AnomalyDetectionProcessorConfig::default()
+            });
+        assert!(config.validate().is_err());
+
+        config.processors.anomaly_detection =
+            Configurable::Enabled(AnomalyDetectionProcessorConfig::default());
+        assert!(config.validate().is_ok());
+
+        config.processors.anomaly_detection = Configurable::Disabled;
         config.sinks.health_report = Configurable::Enabled(HealthReportSinkConfig {
             workers: 0,
             ..HealthReportSinkConfig::default()
@@ -1132,6 +1769,104 @@ cache_size = 50
 
         config.sinks.otlp = Configurable::Enabled(OtlpSinkConfig::default());
         assert!(config.validate().is_ok());
+
+        config.sinks.otlp = Configurable::Enabled(OtlpSinkConfig {
+            disk_buffer: Configurable::Enabled(DiskBufferConfig::default()),
+            ..OtlpSinkConfig::default()
+        });
+        assert!(config.validate().is_err());
+
+        let disk_buffer = DiskBufferConfig {
+            directory: "/var/lib/carbide-hardware-health/buffer/otlp".to_string(),
+            ..DiskBufferConfig::default()
+        };
+        config.sinks.otlp = Configurable::Enabled(OtlpSinkConfig {
+            disk_buffer: Configurable::Enabled(disk_buffer.clone()),
+            ..OtlpSinkConfig::default()
+        });
+        assert!(config.validate().is_ok());
+
+        config.sinks.health_report = Configurable::Enabled(HealthReportSinkConfig {
+            disk_buffer: Configurable::Enabled(disk_buffer),
+            ..HealthReportSinkConfig::default()
+        });
+        assert!(config.validate().is_err());
+
+        config.sinks.health_report = Configurable::Enabled(HealthReportSinkConfig::default());
+        config.sinks.mqtt = Configurable::Enabled(MqttSinkConfig::default());
+        assert!(config.validate().is_ok());
+
+        config.sinks.mqtt = Configurable::Enabled(MqttSinkConfig {
+            topic_template: "health/{rack}/{endpoint}".to_string(),
+            ..MqttSinkConfig::default()
+        });
+        assert!(config.validate().is_err());
+
+        config.sinks.mqtt = Configurable::Enabled(MqttSinkConfig {
+            topic_template: "health/{endpoint".to_string(),
+            ..MqttSinkConfig::default()
+        });
+        assert!(config.validate().is_err());
+
+        config.sinks.mqtt = Configurable::Enabled(MqttSinkConfig {
+            topic_template: "health/+/{endpoint}".to_string(),
+            ..MqttSinkConfig::default()
+        });
+        assert!(config.validate().is_err());
+
+        config.sinks.mqtt = Configurable::Enabled(MqttSinkConfig {
+            topic_template: "bmc/{collector}/{endpoint}".to_string(),
+            ..MqttSinkConfig::default()
+        });
+        assert!(config.validate().is_ok());
+
+        config.sinks.mqtt = Configurable::Enabled(MqttSinkConfig {
+            batch_size: 0,
+            ..MqttSinkConfig::default()
+        });
+        assert!(config.validate().is_err());
+
+        config.sinks.mqtt = Configurable::Enabled(MqttSinkConfig {
+            password_file: Some("/run/secrets/mqtt".to_string()),
+            ..MqttSinkConfig::default()
+        });
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_mqtt_sink_config() {
+        let toml_content = r#"
+[sinks.mqtt]
+enabled = true
+broker_host = "mqtt.example.com"
+topic_template = "bmc/{endpoint}/{kind}"
+qos = "exactly_once"
+event_kinds = ["log", "health_report"]
+
+[sinks.mqtt.rate_limit]
+enabled = false
+"#;
+
+        let config: Config = Figment::new()
+            .merge(Serialized::defaults(Config::default()))
+            .merge(Toml::string(toml_content))
+            .extract()
+            .expect("failed to parse");
+        config.validate().expect("config should be valid");
+
+        let Configurable::Enabled(ref mqtt) = config.sinks.mqtt else {
+            panic!("mqtt sink should be enabled");
+        };
+        assert_eq!(mqtt.broker_host, "mqtt.example.com");
+        assert_eq!(mqtt.broker_port, 1883);
+        assert_eq!(mqtt.topic_template, "bmc/{endpoint}/{kind}");
+        assert_eq!(mqtt.qos, MqttQos::ExactlyOnce);
+        assert_eq!(
+            mqtt.event_kinds,
+            vec![MqttEventKind::Log, MqttEventKind::HealthReport]
+        );
+        assert_eq!(mqtt.batch_size, 100);
+        assert!(!mqtt.rate_limit.is_enabled());
     }
 
     #[test]
@@ -20,6 +20,7 @@ use std::time::Duration;
 
 use nv_redfish::bmc_http::reqwest::BmcError;
 use prometheus::{Gauge, GaugeVec, Opts};
+use tokio::sync::watch;
 
 pub mod api_client;
 pub mod bmc;
@@ -43,13 +44,13 @@ use crate::endpoint::{CompositeEndpointSource, EndpointSource, StaticEndpointSou
 use crate::limiter::{BucketLimiter, NoopLimiter, RateLimiter};
 use crate::metrics::{MetricsManager, run_metrics_server};
 use crate::processor::{
-    EventProcessingPipeline, EventProcessor, HealthReportProcessor, LeakEventProcessor,
-    RackLeakProcessor,
+    AnomalyDetectionProcessor, EventProcessingPipeline, EventProcessor, HealthReportProcessor,
+    LeakEventProcessor, RackLeakProcessor, ThresholdRulesProcessor,
 };
-use crate::sharding::ShardManager;
+use crate::sharding::{DnsPeerDiscovery, ShardManager, ShardMembers};
 use crate::sink::event_mapper::{OpenBmcEventMapper, RedfishEventMapper};
 use crate::sink::{
-    CompositeDataSink, DataSink, HealthReportSink, LogFileSink, OtlpSink,
+    CompositeDataSink, DataSink, HealthReportSink, LogFileSink, MqttSink, OtlpSink,
     PowerShelfHealthReportSink, PrometheusSink, RackHealthReportSink, SwitchHealthReportSink,
     TracingSink,
 };
@@ -181,6 +182,16 @@ fn build_data_sink(
         )));
     }
 
+    if let Configurable::Enabled(ref threshold_rules_cfg) = config.processors.threshold_rules {
+        processors.push(Arc::new(ThresholdRulesProcessor::new(threshold_rules_cfg)?));
+    }
+
+    if let Configurable::Enabled(ref anomaly_detection_cfg) = config.processors.anomaly_detection {
+        processors.push(Arc::new(AnomalyDetectionProcessor::new(
+            anomaly_detection_cfg,
+        )));
+    }
+
     if let Configurable::Enabled(ref sink_cfg) = config.sinks.log_file {
         sinks.push(Arc::new(
             LogFileSink::new(sink_cfg).map_err(HealthError::GenericError)?,
@@ -188,19 +199,35 @@ fn build_data_sink(
     }
 
     if let Configurable::Enabled(ref sink_cfg) = config.sinks.health_report {
-        sinks.push(Arc::new(HealthReportSink::new(sink_cfg)?));
+        sinks.push(Arc::new(HealthReportSink::new(
+            sink_cfg,
+            &metrics_manager,
+            &config.metrics.prefix,
+        )?));
     }
 
     if let Configurable::Enabled(ref sink_cfg) = config.sinks.rack_health_report {
-        sinks.push(Arc::new(RackHealthReportSink::new(sink_cfg)?));
+        sinks.push(Arc::new(RackHealthReportSink::new(
+            sink_cfg,
+            &metrics_manager,
+            &config.metrics.prefix,
+        )?));
     }
 
     if let Configurable::Enabled(ref sink_cfg) = config.sinks.switch_health_report {
-        sinks.push(Arc::new(SwitchHealthReportSink::new(sink_cfg)?));
+        sinks.push(Arc::new(SwitchHealthReportSink::new(
+            sink_cfg,
+            &metrics_manager,
+            &config.metrics.prefix,
+        )?));
     }
 
     if let Configurable::Enabled(ref sink_cfg) = config.sinks.power_shelf_health_report {
-        sinks.push(Arc::new(PowerShelfHealthReportSink::new(sink_cfg)?));
+        sinks.push(Arc::new(PowerShelfHealthReportSink::new(
+            sink_cfg,
+            &metrics_manager,
+            &config.metrics.prefix,
+        )?));
     }
 
     if let Configurable::Enabled(ref otlp_cfg) = config.sinks.otlp {
@@ -213,6 +240,14 @@ fn build_data_sink(
         )?));
     }
 
+    if let Configurable::Enabled(ref mqtt_cfg) = config.sinks.mqtt {
+        sinks.push(Arc::new(MqttSink::new(
+            mqtt_cfg,
+            &metrics_manager,
+            &config.metrics.prefix,
+        )?));
+    }
+
     if sinks.is_empty() {
         return Ok(None);
     }
@@ -231,6 +266,58 @@ fn build_data_sink(
     ))))
 }
 
+/// Builds the `ShardManager`. If peer discovery is enabled, the returned receiver
+/// reports changes of the shard membership.
+async fn build_shard_manager(
+    config: &Config,
+) -> Result<(ShardManager, Option<watch::Receiver<ShardMembers>>), HealthError> {
+    let Configurable::Enabled(peer_discovery) = &config.sharding.peer_discovery else {
+        return Ok((
+            ShardManager::with_static_ordinals(config.shard, config.shards_count),
+            None,
+        ));
+    };
+
+    let self_address = peer_discovery.self_address.ok_or_else(|| {
+        HealthError::GenericError("sharding.peer_discovery.self_address must be set".to_string())
+    })?;
+    let discovery = DnsPeerDiscovery::new(peer_discovery.service.clone(), self_address);
+    let members = match discovery.fetch_members().await {
+        Ok(members) => members,
+        Err(error) => {
+            // Without knowing its peers, this replica does not monitor any endpoint
+            // until the next successful refresh
+            tracing::warn!(%error, "Failed to fetch initial shard membership");
+            ShardMembers::new(self_address.to_string(), Vec::new())
+        }
+    };
+    tracing::info!(
+        members = ?members.members(),
+        self_id = members.self_id(),
+        "Initial shard membership"
+    );
+
+    let (members_tx, members_rx) = watch::channel(members.clone());
+    tokio::spawn(discovery.run(peer_discovery.refresh_interval, members_tx));
+
+    Ok((
+        ShardManager::with_dynamic_members(members, config.sharding.handoff_delay),
+        Some(members_rx),
+    ))
+}
+
+/// Resolves once the shard membership changed. Never resolves if peer discovery is disabled.
+async fn shard_membership_changed(members_rx: &mut Option<watch::Receiver<ShardMembers>>) {
+    match members_rx {
+        Some(members_rx) => {
+            if members_rx.changed().await.is_err() {
+                std::future::pending().await
+            }
+        }
+        None => std::future::pending().await,
+    }
+}
+
 pub async fn run_service(config: Config) -> Result<(), HealthError> {
     let metrics_endpoint = config.metrics_addr()?;
     let metrics_manager = Arc::new(MetricsManager::new(&config.metrics.prefix)?);
@@ -262,20 +349,27 @@ pub async fn run_service(config: Config) -> Result<(), HealthError> {
     )?;
     registry.register(Box::new(discovery_endpoints_gauge.clone()))?;
 
+    let shard_members_gauge = Gauge::new(
+        format!(
+            "{metrics_prefix}_shard_members",
+            metrics_prefix = &config.metrics.prefix
+        ),
+        "Number of replicas which share the monitoring of endpoints",
+    )?;
+    registry.register(Box::new(shard_members_gauge.clone()))?;
+
     let EndpointWiring {
         source: endpoint_source,
     } = build_endpoint_wiring(&config)?;
 
     let data_sink = build_data_sink(&config, metrics_manager.clone())?;
 
+    let (mut shard_manager, mut shard_members_rx) = build_shard_manager(&config).await?;
+
     let config_arc = Arc::new(config);
 
     let join_discovery: tokio::task::JoinHandle<Result<(), HealthError>> = tokio::spawn({
         let config = config_arc.clone();
-        let shard_manager = ShardManager {
-            shard: config.shard,
-            shards_count: config.shards_count,
-        };
         let limiter: Arc<dyn RateLimiter> =
             if let Configurable::Enabled(rate_limit) = &config.rate_limit {
                 Arc::new(BucketLimiter::new(
@@ -296,6 +390,16 @@ pub async fn run_service(config: Config) -> Result<(), HealthError> {
 
         async move {
             loop {
+                if let Some(members_rx) = &mut shard_members_rx {
+                    let members = members_rx.borrow_and_update().clone();
+                    if shard_manager.update_members(members) {
+                        tracing::info!(
+                            members = ?shard_manager.members().members(),
+                            "Rebalancing endpoints after shard membership change"
+                        );
+                    }
+                }
+
                 let stats = discovery::run_discovery_iteration(
                     endpoint_source.clone(),
                     &shard_manager,
@@ -312,16 +416,24 @@ pub async fn run_service(config: Config) -> Result<(), HealthError> {
                     .get_metric_with_label_values(&["sharded"])?
                     .set(stats.sharded_endpoints as f64);
                 active_endpoints_gauge.set(stats.active_monitors as f64);
+                shard_members_gauge.set(shard_manager.members().members().len() as f64);
+
+                let mut rediscover_interval = config
+                    .collectors
+                    .sensors
+                    .as_option()
+                    .map(|s| s.rediscover_interval)
+                    .unwrap_or(Duration::from_secs(300));
+                // Pick up endpoints which are handed over from other replicas
+                // as soon as the handoff is finished
+                if let Some(pending_handoff) = shard_manager.pending_handoff() {
+                    rediscover_interval = rediscover_interval.min(pending_handoff);
+                }
 
-                tokio::time::sleep(
-                    config
-                        .collectors
-                        .sensors
-                        .as_option()
-                        .map(|s| s.rediscover_interval)
-                        .unwrap_or(Duration::from_secs(300)),
-                )
-                .await;
+                tokio::select! {
+                    _ = tokio::time::sleep(rediscover_interval) => {}
+                    _ = shard_membership_changed(&mut shard_members_rx) => {}
+                }
             }
         }
     });
@@ -18,11 +18,14 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use prost::Message;
 use tonic::transport::Channel;
 
+use super::collector_logs::ExportLogsServiceRequest;
 use super::collector_logs::logs_service_client::LogsServiceClient;
 use super::convert::build_export_request;
 use crate::collectors::{BackoffConfig, ExponentialBackoff};
+use crate::sink::disk_buffer::DiskBuffer;
 use crate::sink::otlp::OtlpQueue;
 use crate::sink::{CollectorEvent, EventContext};
 
@@ -31,6 +34,7 @@ pub(crate) struct OtlpDrainTask {
     endpoint: String,
     batch_size: usize,
     flush_interval: Duration,
+    buffer: Option<Arc<DiskBuffer>>,
 }
 
 impl OtlpDrainTask {
@@ -39,12 +43,14 @@ impl OtlpDrainTask {
         endpoint: String,
         batch_size: usize,
         flush_interval: Duration,
+        buffer: Option<Arc<DiskBuffer>>,
     ) -> Self {
         Self {
             queue,
             endpoint,
             batch_size,
             flush_interval,
+            buffer,
         }
     }
 
@@ -77,6 +83,7 @@ impl OtlpDrainTask {
                     }
                 }
                 _ = interval.tick() => {
+                    self.replay_buffered(&mut client).await;
                     self.drain_batch(&mut batch);
                     if !batch.is_empty() {
                         self.flush(&mut client, &mut batch).await;
@@ -147,6 +154,14 @@ impl OtlpDrainTask {
             return;
         }
 
+        if let Some(buffer) = &self.buffer
+            && !buffer.is_empty()
+        {
+            // Keep batches ordered behind the ones waiting for replay
+            buffer.push(request.encode_to_vec()).await;
+            return;
+        }
+
         const MAX_RETRIES: usize = 5;
 
         let mut backoff = ExponentialBackoff::new(&BackoffConfig {
@@ -172,21 +187,80 @@ impl OtlpDrainTask {
                     tokio::time::sleep(delay).await;
                 }
                 Err(status) => {
-                    tracing::error!(
+                    if is_retryable(&status)
+                        && let Some(buffer) = &self.buffer
+                    {
+                        tracing::warn!(
+                            code = ?status.code(),
+                            message = status.message(),
+                            record_count,
+                            attempt,
+                            "otlp export failed, buffering batch on disk"
+                        );
+                        buffer.push(request.encode_to_vec()).await;
+                    } else {
+                        tracing::error!(
+                            code = ?status.code(),
+                            message = status.message(),
+                            record_count,
+                            attempt,
+                            "otlp export failed, dropping batch"
+                        );
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Exports buffered batches oldest-first, until the buffer is empty or the
+    /// collector is unavailable again
+    async fn replay_buffered(&self, client: &mut LogsServiceClient<Channel>) {
+        let Some(buffer) = &self.buffer else {
+            return;
+        };
+
+        while let Some((seq, payload)) = buffer.front().await {
+            let request = match ExportLogsServiceRequest::decode(payload.as_slice()) {
+                Ok(request) => request,
+                Err(error) => {
+                    tracing::warn!(
+                        ?error,
+                        seq,
+                        "failed to decode buffered otlp batch, dropping"
+                    );
+                    buffer.discard(seq).await;
+                    continue;
+                }
+            };
+
+            match client.export(request).await {
+                Ok(_) => buffer.remove(seq).await,
+                Err(status) if is_retryable(&status) => {
+                    tracing::debug!(
                         code = ?status.code(),
                         message = status.message(),
-                        record_count,
-                        attempt,
-                        "otlp export failed, dropping batch"
+                        "otlp collector unavailable, keeping buffered batches"
                     );
                     break;
                 }
+                Err(status) => {
+                    tracing::error!(
+                        code = ?status.code(),
+                        message = status.message(),
+                        seq,
+                        "buffered otlp batch rejected, dropping"
+                    );
+                    buffer.discard(seq).await;
+                }
             }
         }
+
+        buffer.update_metrics();
     }
 }
 
-fn is_retryable(status: &tonic::Status) -> bool {
+pub(crate) fn is_retryable(status: &tonic::Status) -> bool {
     matches!(
         status.code(),
         tonic::Code::Unavailable
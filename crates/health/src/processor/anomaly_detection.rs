@@ -0,0 +1,568 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Statistical anomaly detection for sensor readings.
+//!
+//! Every sensor keeps an exponentially weighted moving average and variance of
+//! its readings and of their rate of change. A reading is anomalous if it deviates
+//! sharply from the sensor's own baseline, or if the baseline deviates from the
+//! same sensor on the other trays in the rack. The latter catches slowly degrading
+//! fans and pumps, which never leave their own baseline.
+//!
+//! State is only kept in memory. After a restart or shard reassignment the baseline
+//! is rebuilt from the first `warmup_samples` readings, during which the sensor is
+//! not evaluated.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use dashmap::DashMap;
+
+use super::{CollectorEvent, EventContext, EventProcessor};
+use crate::config::AnomalyDetectionProcessorConfig;
+use crate::sink::{
+    HealthReport, HealthReportAlert, HealthReportSuccess, Probe, ReportSource, SensorHealthData,
+};
+
+/// Scales the median absolute deviation to the standard deviation of normally
+/// distributed values
+const MAD_SCALE: f64 = 1.4826;
+
+/// Exponentially weighted moving average and variance
+#[derive(Clone, Copy, Debug, Default)]
+struct Ewma {
+    mean: f64,
+    variance: f64,
+    samples: u32,
+}
+
+impl Ewma {
+    /// While fewer than `1 / smoothing_factor` samples have been seen, all samples
+    /// are weighted equally, so that the baseline starts out as their plain mean.
+    fn update(&mut self, value: f64, smoothing_factor: f64) {
+        let weight = smoothing_factor.max(1.0 / f64::from(self.samples.saturating_add(1)));
+        let diff = value - self.mean;
+        let increment = weight * diff;
+        self.mean += increment;
+        self.variance = (1.0 - weight) * (self.variance + diff * increment);
+        self.samples = self.samples.saturating_add(1);
+    }
+
+    fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+}
+
+struct SensorBaseline {
+    sensor_id: String,
+    unit: String,
+    /// Set if the endpoint belongs to a rack
+    peer_group: Option<String>,
+    value: Ewma,
+    rate: Ewma,
+    last_value: f64,
+    last_seen: Instant,
+    /// Deviation from the sensor's own baseline, found for the latest reading
+    anomaly: Option<String>,
+}
+
+#[derive(Default)]
+struct EndpointState {
+    window_started_at: Option<Instant>,
+    /// Keyed by sensor key
+    sensors: HashMap<String, SensorBaseline>,
+}
+
+/// Detects sensor readings which deviate from their own baseline or from
+/// their rack peers, and emits a health report for them at the end of each
+/// collection cycle
+pub struct AnomalyDetectionProcessor {
+    config: AnomalyDetectionProcessorConfig,
+    endpoints: DashMap<String, EndpointState>,
+    /// Baseline mean of every sensor, grouped by rack, reading type and sensor ID,
+    /// and keyed by endpoint
+    peers: DashMap<String, HashMap<String, f64>>,
+    tracked_sensors: AtomicUsize,
+}
+
+impl AnomalyDetectionProcessor {
+    pub fn new(config: &AnomalyDetectionProcessorConfig) -> Self {
+        Self {
+            config: config.clone(),
+            endpoints: DashMap::new(),
+            peers: DashMap::new(),
+            tracked_sensors: AtomicUsize::new(0),
+        }
+    }
+
+    fn stream_key(context: &EventContext) -> String {
+        format!("{}::{}", context.endpoint_key(), context.collector_type)
+    }
+
+    fn is_evaluated(&self, metric: &SensorHealthData) -> bool {
+        self.config.metric_types.is_empty()
+            || self
+                .config
+                .metric_types
+                .iter()
+                .any(|metric_type| metric_type == &metric.metric_type)
+    }
+
+    fn is_warmed_up(&self, ewma: &Ewma) -> bool {
+        ewma.samples >= self.config.warmup_samples
+    }
+
+    /// Returns whether `deviation` from `baseline` is significant given the spread
+    /// of the values, and not negligible relative to the baseline
+    fn is_significant(&self, deviation: f64, spread: f64, limit: f64, baseline: f64) -> bool {
+        deviation.abs() > limit * spread
+            && deviation.abs() > self.config.min_relative_deviation * baseline.abs()
+    }
+
+    fn record_metric(&self, context: &EventContext, metric: &SensorHealthData, now: Instant) {
+        if !self.is_evaluated(metric) || !metric.value.is_finite() {
+            return;
+        }
+
+        let mut endpoint = self.endpoints.entry(Self::stream_key(context)).or_default();
+
+        let Some(baseline) = endpoint.sensors.get_mut(&metric.key) else {
+            if self.tracked_sensors.load(Ordering::Relaxed) >= self.config.max_tracked_sensors {
+                tracing::debug!(
+                    endpoint_key = context.endpoint_key(),
+                    sensor = %metric.key,
+                    "anomaly detection sensor limit reached, not tracking sensor"
+                );
+                return;
+            }
+            self.tracked_sensors.fetch_add(1, Ordering::Relaxed);
+
+            let sensor_id = metric
+                .context
+                .as_ref()
+                .map(|context| context.sensor_id.clone())
+                .unwrap_or_else(|| metric.key.clone());
+            let mut value = Ewma::default();
+            value.update(metric.value, self.config.smoothing_factor);
+            endpoint.sensors.insert(
+                metric.key.clone(),
+                SensorBaseline {
+                    peer_group: context
+                        .rack_id()
+                        .map(|rack_id| format!("{rack_id}::{}::{sensor_id}", metric.metric_type)),
+                    sensor_id,
+                    unit: metric.unit.clone(),
+                    value,
+                    rate: Ewma::default(),
+                    last_value: metric.value,
+                    last_seen: now,
+                    anomaly: None,
+                },
+            );
+            return;
+        };
+
+        let elapsed = now
+            .saturating_duration_since(baseline.last_seen)
+            .as_secs_f64();
+        let rate = (elapsed > 0.0).then(|| (metric.value - baseline.last_value) / elapsed);
+
+        // Evaluate the reading before the baseline absorbs it
+        baseline.anomaly = None;
+        if self.is_warmed_up(&baseline.value) {
+            let deviation = metric.value - baseline.value.mean;
+            if self.is_significant(
+                deviation,
+                baseline.value.std_dev(),
+                self.config.baseline_deviation,
+                baseline.value.mean,
+            ) {
+                baseline.anomaly = Some(format!(
+                    "Sensor '{}' reading {:.2} {} deviates from its baseline {:.2} {} by {:.1} standard deviations",
+                    baseline.sensor_id,
+                    metric.value,
+                    baseline.unit,
+                    baseline.value.mean,
+                    baseline.unit,
+                    deviation.abs() / baseline.value.std_dev().max(f64::EPSILON),
+                ));
+            } else if let Some(rate) = rate
+                && self.is_warmed_up(&baseline.rate)
+                && self.is_significant(
+                    rate - baseline.rate.mean,
+                    baseline.rate.std_dev(),
+                    self.config.baseline_deviation,
+                    0.0,
+                )
+                && (metric.value - baseline.last_value).abs()
+                    > self.config.min_relative_deviation * baseline.value.mean.abs()
+            {
+                baseline.anomaly = Some(format!(
+                    "Sensor '{}' reading changes by {:.3} {}/s, while its baseline rate of change is {:.3} {}/s",
+                    baseline.sensor_id, rate, baseline.unit, baseline.rate.mean, baseline.unit,
+                ));
+            }
+        }
+
+        baseline
+            .value
+            .update(metric.value, self.config.smoothing_factor);
+        if let Some(rate) = rate {
+            baseline.rate.update(rate, self.config.smoothing_factor);
+        }
+        baseline.last_value = metric.value;
+        baseline.last_seen = now;
+    }
+
+    fn remove_from_peer_group(&self, endpoint_key: &str, baseline: &SensorBaseline) {
+        if let Some(peer_group) = &baseline.peer_group {
+            self.peers.remove_if_mut(peer_group, |_, members| {
+                members.remove(endpoint_key);
+                members.is_empty()
+            });
+        }
+    }
+
+    /// Compares a sensor baseline against the same sensor on the other trays in the rack
+    fn peer_anomaly(&self, endpoint_key: &str, baseline: &SensorBaseline) -> Option<String> {
+        let peer_group = baseline.peer_group.as_ref()?;
+        let mut peer_values: Vec<f64> = self
+            .peers
+            .get(peer_group)?
+            .iter()
+            .filter(|(peer, _)| peer.as_str() != endpoint_key)
+            .map(|(_, value)| *value)
+            .collect();
+        if peer_values.len() < self.config.min_peers {
+            return None;
+        }
+
+        let peer_median = median(&mut peer_values);
+        let mut absolute_deviations: Vec<f64> = peer_values
+            .iter()
+            .map(|value| (value - peer_median).abs())
+            .collect();
+        let spread = MAD_SCALE * median(&mut absolute_deviations);
+
+        let deviation = baseline.value.mean - peer_median;
+        self.is_significant(deviation, spread, self.config.peer_deviation, peer_median)
+            .then(|| {
+                format!(
+                    "Sensor '{}' baseline {:.2} {} deviates from the median {:.2} {} of {} peers in the rack",
+                    baseline.sensor_id,
+                    baseline.value.mean,
+                    baseline.unit,
+                    peer_median,
+                    baseline.unit,
+                    peer_values.len(),
+                )
+            })
+    }
+
+    fn build_report(&self, context: &EventContext) -> Option<HealthReport> {
+        let endpoint_key = Self::stream_key(context);
+        let mut endpoint = self.endpoints.get_mut(&endpoint_key)?;
+        let window_started_at = endpoint.window_started_at.take();
+
+        // Drop sensors which were not reported in this cycle, so that the state
+        // only covers sensors which still exist
+        let mut removed = 0;
+        endpoint.sensors.retain(|_, baseline| {
+            let keep = window_started_at.is_none_or(|started_at| baseline.last_seen >= started_at);
+            if !keep {
+                self.remove_from_peer_group(&endpoint_key, baseline);
+                removed += 1;
+            }
+            keep
+        });
+        self.tracked_sensors.fetch_sub(removed, Ordering::Relaxed);
+        if endpoint.sensors.is_empty() {
+            return None;
+        }
+
+        for baseline in endpoint.sensors.values() {
+            if let Some(peer_group) = &baseline.peer_group
+                && self.is_warmed_up(&baseline.value)
+            {
+                self.peers
+                    .entry(peer_group.clone())
+                    .or_default()
+                    .insert(endpoint_key.clone(), baseline.value.mean);
+            }
+        }
+
+        let mut successes = Vec::new();
+        let mut alerts = Vec::new();
+        for baseline in endpoint.sensors.values() {
+            if !self.is_warmed_up(&baseline.value) {
+                continue;
+            }
+
+            let messages: Vec<String> = baseline
+                .anomaly
+                .iter()
+                .cloned()
+                .chain(self.peer_anomaly(&endpoint_key, baseline))
+                .collect();
+            let target = Some(baseline.sensor_id.clone());
+            if messages.is_empty() {
+                successes.push(HealthReportSuccess {
+                    probe_id: Probe::SensorAnomaly,
+                    target,
+                });
+            } else {
+                alerts.push(HealthReportAlert {
+                    probe_id: Probe::SensorAnomaly,
+                    target,
+                    message: messages.join("; "),
+                    classifications: self.config.classifications.clone(),
+                });
+            }
+        }
+
+        Some(HealthReport {
+            source: ReportSource::AnomalyDetection,
+            target: context.health_report_target(),
+            observed_at: Some(chrono::Utc::now()),
+            successes,
+            alerts,
+        })
+    }
+
+    fn remove_endpoint(&self, context: &EventContext) {
+        let endpoint_key = Self::stream_key(context);
+        if let Some((_, endpoint)) = self.endpoints.remove(&endpoint_key) {
+            for baseline in endpoint.sensors.values() {
+                self.remove_from_peer_group(&endpoint_key, baseline);
+            }
+            self.tracked_sensors
+                .fetch_sub(endpoint.sensors.len(), Ordering::Relaxed);
+        }
+    }
+}
+
+/// Returns the median of the values, which must not be empty
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+impl EventProcessor for AnomalyDetectionProcessor {
+    fn processor_type(&self) -> &'static str {
+        "anomaly_detection_processor"
+    }
+
+    fn process_event(&self, context: &EventContext, event: &CollectorEvent) -> Vec<CollectorEvent> {
+        match event {
+            CollectorEvent::MetricCollectionStart => {
+                if let Some(mut endpoint) = self.endpoints.get_mut(&Self::stream_key(context)) {
+                    endpoint.window_started_at = Some(Instant::now());
+                }
+            }
+            CollectorEvent::Metric(metric) => {
+                self.record_metric(context, metric, Instant::now());
+            }
+            CollectorEvent::MetricCollectionEnd => {
+                if let Some(report) = self.build_report(context) {
+                    return vec![CollectorEvent::HealthReport(Arc::new(report))];
+                }
+            }
+            CollectorEvent::CollectorRemoved => {
+                self.remove_endpoint(context);
+            }
+            CollectorEvent::Log(_)
+            | CollectorEvent::Firmware(_)
+            | CollectorEvent::HealthReport(_) => {}
+        }
+
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use carbide_uuid::rack::RackId;
+    use mac_address::MacAddress;
+
+    use super::*;
+    use crate::endpoint::BmcAddr;
+    use crate::sink::Classification;
+
+    fn context(tray: u8) -> EventContext {
+        let mac = format!("42:9e:b1:bd:9d:{tray:02x}");
+        EventContext {
+            endpoint_key: mac.clone(),
+            addr: BmcAddr {
+                ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, tray)),
+                port: Some(443),
+                mac: MacAddress::from_str(&mac).expect("valid mac"),
+            },
+            collector_type: "sensor_collector",
+            metadata: None,
+            rack_id: Some(RackId::new("rack-1")),
+        }
+    }
+
+    fn metric(value: f64) -> SensorHealthData {
+        SensorHealthData {
+            key: "sensor::Fan_3".to_string(),
+            name: "hw_sensor".to_string(),
+            metric_type: "rotational".to_string(),
+            unit: "rpm".to_string(),
+            value,
+            labels: vec![],
+            context: None,
+        }
+    }
+
+    fn config() -> AnomalyDetectionProcessorConfig {
+        AnomalyDetectionProcessorConfig {
+            warmup_samples: 5,
+            min_peers: 3,
+            ..AnomalyDetectionProcessorConfig::default()
+        }
+    }
+
+    /// Records one sample per endpoint and cycle, 60s apart
+    fn run_cycle(
+        processor: &AnomalyDetectionProcessor,
+        context: &EventContext,
+        start: Instant,
+        cycle: u64,
+        value: f64,
+    ) -> Option<HealthReport> {
+        let now = start + Duration::from_secs(60 * cycle);
+        if let Some(mut endpoint) = processor
+            .endpoints
+            .get_mut(&AnomalyDetectionProcessor::stream_key(context))
+        {
+            endpoint.window_started_at = Some(now);
+        }
+        processor.record_metric(context, &metric(value), now);
+        processor.build_report(context)
+    }
+
+    #[test]
+    fn ewma_starts_with_plain_mean() {
+        let mut ewma = Ewma::default();
+        for value in [10.0, 20.0, 30.0] {
+            ewma.update(value, 0.1);
+        }
+        assert!((ewma.mean - 20.0).abs() < 1e-9);
+        assert_eq!(ewma.samples, 3);
+    }
+
+    #[test]
+    fn alerts_on_deviation_from_own_baseline() {
+        let processor = AnomalyDetectionProcessor::new(&config());
+        let context = context(1);
+        let start = Instant::now();
+
+        for (cycle, value) in [5000.0, 5010.0, 4990.0, 5005.0, 4995.0]
+            .into_iter()
+            .enumerate()
+        {
+            let report = run_cycle(&processor, &context, start, cycle as u64, value)
+                .expect("report for tracked sensor");
+            assert!(report.alerts.is_empty(), "no alerts during warmup");
+        }
+
+        let report =
+            run_cycle(&processor, &context, start, 5, 5002.0).expect("report for tracked sensor");
+        assert_eq!(report.source, ReportSource::AnomalyDetection);
+        assert!(report.alerts.is_empty());
+        assert_eq!(report.successes.len(), 1);
+
+        let report =
+            run_cycle(&processor, &context, start, 6, 2000.0).expect("report for tracked sensor");
+        assert_eq!(report.alerts.len(), 1);
+        assert_eq!(report.alerts[0].probe_id, Probe::SensorAnomaly);
+        assert_eq!(report.alerts[0].target.as_deref(), Some("sensor::Fan_3"));
+        assert_eq!(
+            report.alerts[0].classifications,
+            vec![Classification::SensorWarning]
+        );
+    }
+
+    #[test]
+    fn alerts_on_deviation_from_rack_peers() {
+        let processor = AnomalyDetectionProcessor::new(&config());
+        let start = Instant::now();
+        let contexts: Vec<_> = (1..=5).map(context).collect();
+
+        let mut last_reports = Vec::new();
+        for cycle in 0..6 {
+            last_reports.clear();
+            for (tray, context) in contexts.iter().enumerate() {
+                // The last tray's fan has been running slow all along
+                let value = if tray == 4 {
+                    3000.0
+                } else {
+                    5000.0 + tray as f64 * 20.0
+                };
+                last_reports.push(run_cycle(&processor, context, start, cycle, value));
+            }
+        }
+
+        let healthy = last_reports[0].as_ref().expect("report for tracked sensor");
+        assert!(healthy.alerts.is_empty());
+        let degraded = last_reports[4].as_ref().expect("report for tracked sensor");
+        assert_eq!(degraded.alerts.len(), 1);
+        assert!(degraded.alerts[0].message.contains("4 peers"));
+    }
+
+    #[test]
+    fn limits_tracked_sensors() {
+        let processor = AnomalyDetectionProcessor::new(&AnomalyDetectionProcessorConfig {
+            max_tracked_sensors: 1,
+            ..config()
+        });
+        let start = Instant::now();
+
+        assert!(run_cycle(&processor, &context(1), start, 0, 5000.0).is_some());
+        assert!(run_cycle(&processor, &context(2), start, 0, 5000.0).is_none());
+        assert_eq!(processor.tracked_sensors.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn collector_removed_clears_state() {
+        let processor = AnomalyDetectionProcessor::new(&config());
+        let context = context(1);
+        let start = Instant::now();
+        for cycle in 0..5 {
+            let _ = run_cycle(&processor, &context, start, cycle, 5000.0);
+        }
+        assert_eq!(processor.peers.len(), 1);
+
+        let emitted = processor.process_event(&context, &CollectorEvent::CollectorRemoved);
+        assert!(emitted.is_empty());
+        assert!(processor.endpoints.is_empty());
+        assert!(processor.peers.is_empty());
+        assert_eq!(processor.tracked_sensors.load(Ordering::Relaxed), 0);
+    }
+}
@@ -20,12 +20,16 @@ use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Instant;
 
+mod anomaly_detection;
 mod health_report;
 mod leak_events;
 mod rack_leak;
+mod threshold_rules;
+pub use anomaly_detection::AnomalyDetectionProcessor;
 pub use health_report::HealthReportProcessor;
 pub use leak_events::LeakEventProcessor;
 pub use rack_leak::RackLeakProcessor;
+pub use threshold_rules::ThresholdRulesProcessor;
 
 use crate::metrics::{ComponentMetrics, MetricsManager};
 use crate::sink::{CollectorEvent, DataSink, EventContext};
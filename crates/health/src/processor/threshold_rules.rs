@@ -0,0 +1,558 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::Instant;
+
+use dashmap::DashMap;
+use regex::Regex;
+
+use super::{CollectorEvent, EventContext, EventProcessor};
+use crate::HealthError;
+use crate::config::{ThresholdCondition, ThresholdRuleConfig, ThresholdRulesProcessorConfig};
+use crate::sink::{
+    Classification, HealthReport, HealthReportAlert, HealthReportSuccess, Probe, ReportSource,
+    SensorHealthData,
+};
+
+struct ThresholdRule {
+    name: String,
+    metric_type: Option<String>,
+    sensor: Option<Regex>,
+    labels: Vec<(String, Regex)>,
+    condition: ThresholdCondition,
+    consecutive_samples: usize,
+    classifications: Vec<Classification>,
+}
+
+/// Compiles a pattern which needs to match the full value
+fn full_match_regex(pattern: &str) -> Result<Regex, HealthError> {
+    Regex::new(&format!("^(?:{pattern})$"))
+        .map_err(|e| HealthError::GenericError(format!("invalid pattern {pattern}: {e}")))
+}
+
+impl ThresholdRule {
+    fn new(config: &ThresholdRuleConfig) -> Result<Self, HealthError> {
+        Ok(Self {
+            name: config.name.clone(),
+            metric_type: config.metric_type.clone(),
+            sensor: config.sensor.as_deref().map(full_match_regex).transpose()?,
+            labels: config
+                .labels
+                .iter()
+                .map(|(label, pattern)| Ok((label.clone(), full_match_regex(pattern)?)))
+                .collect::<Result<_, HealthError>>()?,
+            condition: config.condition,
+            consecutive_samples: config.consecutive_samples,
+            classifications: config.classifications.clone(),
+        })
+    }
+
+    fn matches(&self, metric: &SensorHealthData) -> bool {
+        if self
+            .metric_type
+            .as_ref()
+            .is_some_and(|metric_type| metric_type != &metric.metric_type)
+        {
+            return false;
+        }
+
+        if let Some(sensor) = &self.sensor
+            && !sensor.is_match(sensor_id(metric))
+        {
+            return false;
+        }
+
+        self.matches_labels(metric)
+    }
+
+    /// Whether the metric comes from an endpoint the rule applies to, e.g. a
+    /// matching vendor or model. The sensor itself does not need to match.
+    fn matches_labels(&self, metric: &SensorHealthData) -> bool {
+        self.labels.iter().all(|(label, pattern)| {
+            metric
+                .labels
+                .iter()
+                .any(|(name, value)| name == label && pattern.is_match(value))
+        })
+    }
+
+    fn is_missing_for(&self) -> bool {
+        matches!(self.condition, ThresholdCondition::MissingFor(_))
+    }
+
+    fn is_violated(&self, value: f64) -> bool {
+        match self.condition {
+            ThresholdCondition::Above(threshold) => value > threshold,
+            ThresholdCondition::Below(threshold) => value < threshold,
+            ThresholdCondition::MissingFor(_) => false,
+        }
+    }
+}
+
+fn sensor_id(metric: &SensorHealthData) -> &str {
+    metric
+        .context
+        .as_ref()
+        .map(|context| context.sensor_id.as_str())
+        .unwrap_or(metric.key.as_str())
+}
+
+struct SensorState {
+    sensor_id: String,
+    unit: String,
+    last_value: f64,
+    last_seen: Instant,
+    consecutive_violations: usize,
+}
+
+struct EndpointState {
+    /// When the processor started to receive readings for the endpoint
+    monitoring_started_at: Instant,
+    window_started_at: Option<Instant>,
+    /// Keyed by rule index and sensor key
+    sensors: BTreeMap<(usize, String), SensorState>,
+    /// Indices of `MissingFor` rules whose labels matched a reading of the endpoint.
+    /// These alert if no matching sensor reported at all since monitoring started.
+    expecting_rules: BTreeSet<usize>,
+}
+
+impl EndpointState {
+    fn new(now: Instant) -> Self {
+        Self {
+            monitoring_started_at: now,
+            window_started_at: None,
+            sensors: BTreeMap::new(),
+            expecting_rules: BTreeSet::new(),
+        }
+    }
+}
+
+/// Evaluates operator-defined rules over sensor readings, and emits a health report
+/// with all rule violations at the end of each collection cycle
+pub struct ThresholdRulesProcessor {
+    rules: Vec<ThresholdRule>,
+    endpoints: DashMap<String, EndpointState>,
+}
+
+impl ThresholdRulesProcessor {
+    pub fn new(config: &ThresholdRulesProcessorConfig) -> Result<Self, HealthError> {
+        Ok(Self {
+            rules: config
+                .rules
+                .iter()
+                .map(ThresholdRule::new)
+                .collect::<Result<_, _>>()?,
+            endpoints: DashMap::new(),
+        })
+    }
+
+    fn stream_key(context: &EventContext) -> String {
+        format!("{}::{}", context.endpoint_key(), context.collector_type)
+    }
+
+    fn record_metric(&self, context: &EventContext, metric: &SensorHealthData, now: Instant) {
+        let mut endpoint = None;
+        for (rule_idx, rule) in self.rules.iter().enumerate() {
+            if rule.is_missing_for() && rule.matches_labels(metric) {
+                endpoint
+                    .get_or_insert_with(|| {
+                        self.endpoints
+                            .entry(Self::stream_key(context))
+                            .or_insert_with(|| EndpointState::new(now))
+                    })
+                    .expecting_rules
+                    .insert(rule_idx);
+            }
+            if !rule.matches(metric) {
+                continue;
+            }
+
+            let endpoint = endpoint.get_or_insert_with(|| {
+                self.endpoints
+                    .entry(Self::stream_key(context))
+                    .or_insert_with(|| EndpointState::new(now))
+            });
+            let state = endpoint
+                .sensors
+                .entry((rule_idx, metric.key.clone()))
+                .or_insert_with(|| SensorState {
+                    sensor_id: sensor_id(metric).to_string(),
+                    unit: metric.unit.clone(),
+                    last_value: metric.value,
+                    last_seen: now,
+                    consecutive_violations: 0,
+                });
+            state.last_value = metric.value;
+            state.last_seen = now;
+            if rule.is_violated(metric.value) {
+                state.consecutive_violations = state.consecutive_violations.saturating_add(1);
+            } else {
+                state.consecutive_violations = 0;
+            }
+        }
+    }
+
+    fn build_report(&self, context: &EventContext, now: Instant) -> Option<HealthReport> {
+        let mut endpoint = self.endpoints.get_mut(&Self::stream_key(context))?;
+        let window_started_at = endpoint.window_started_at.take();
+
+        // Sensors which are no longer reported can not violate thresholds anymore.
+        // Only rules which check for missing readings keep track of them.
+        endpoint.sensors.retain(|(rule_idx, _), state| {
+            self.rules[*rule_idx].is_missing_for()
+                || window_started_at.is_none_or(|started_at| state.last_seen >= started_at)
+        });
+
+        let mut successes = Vec::new();
+        let mut alerts = Vec::new();
+
+        // Rules which apply to the endpoint, but for which no sensor ever reported,
+        // e.g. a PSU which is absent since the processor started
+        let monitored_for = now.saturating_duration_since(endpoint.monitoring_started_at);
+        for rule_idx in endpoint.expecting_rules.iter() {
+            let rule = &self.rules[*rule_idx];
+            let ThresholdCondition::MissingFor(limit) = rule.condition else {
+                continue;
+            };
+            if monitored_for < limit
+                || endpoint
+                    .sensors
+                    .keys()
+                    .any(|(sensor_rule_idx, _)| sensor_rule_idx == rule_idx)
+            {
+                continue;
+            }
+            alerts.push(HealthReportAlert {
+                probe_id: Probe::ThresholdRule,
+                target: Some(rule.name.clone()),
+                message: format!(
+                    "{}: no matching sensor has reported a reading for {}s (limit {}s)",
+                    rule.name,
+                    monitored_for.as_secs(),
+                    limit.as_secs(),
+                ),
+                classifications: rule.classifications.clone(),
+            });
+        }
+        if endpoint.sensors.is_empty() && alerts.is_empty() {
+            return None;
+        }
+
+        for ((rule_idx, _), state) in endpoint.sensors.iter() {
+            let rule = &self.rules[*rule_idx];
+            let target = Some(format!("{}:{}", rule.name, state.sensor_id));
+            let message = match rule.condition {
+                ThresholdCondition::Above(threshold)
+                    if state.consecutive_violations >= rule.consecutive_samples =>
+                {
+                    Some(format!(
+                        "{}: sensor '{}' reading {:.2} {} above {threshold:.2} for {} consecutive samples",
+                        rule.name,
+                        state.sensor_id,
+                        state.last_value,
+                        state.unit,
+                        state.consecutive_violations,
+                    ))
+                }
+                ThresholdCondition::Below(threshold)
+                    if state.consecutive_violations >= rule.consecutive_samples =>
+                {
+                    Some(format!(
+                        "{}: sensor '{}' reading {:.2} {} below {threshold:.2} for {} consecutive samples",
+                        rule.name,
+                        state.sensor_id,
+                        state.last_value,
+                        state.unit,
+                        state.consecutive_violations,
+                    ))
+                }
+                ThresholdCondition::MissingFor(limit)
+                    if now.saturating_duration_since(state.last_seen) >= limit =>
+                {
+                    Some(format!(
+                        "{}: sensor '{}' has not reported a reading for {}s (limit {}s)",
+                        rule.name,
+                        state.sensor_id,
+                        now.saturating_duration_since(state.last_seen).as_secs(),
+                        limit.as_secs(),
+                    ))
+                }
+                _ => None,
+            };
+
+            match message {
+                Some(message) => alerts.push(HealthReportAlert {
+                    probe_id: Probe::ThresholdRule,
+                    target,
+                    message,
+                    classifications: rule.classifications.clone(),
+                }),
+                None => successes.push(HealthReportSuccess {
+                    probe_id: Probe::ThresholdRule,
+                    target,
+                }),
+            }
+        }
+
+        Some(HealthReport {
+            source: ReportSource::ThresholdRules,
+            target: context.health_report_target(),
+            observed_at: Some(chrono::Utc::now()),
+            successes,
+            alerts,
+        })
+    }
+}
+
+impl EventProcessor for ThresholdRulesProcessor {
+    fn processor_type(&self) -> &'static str {
+        "threshold_rules_processor"
+    }
+
+    fn process_event(&self, context: &EventContext, event: &CollectorEvent) -> Vec<CollectorEvent> {
+        match event {
+            CollectorEvent::MetricCollectionStart => {
+                if let Some(mut endpoint) = self.endpoints.get_mut(&Self::stream_key(context)) {
+                    endpoint.window_started_at = Some(Instant::now());
+                }
+            }
+            CollectorEvent::Metric(metric) => {
+                self.record_metric(context, metric, Instant::now());
+            }
+            CollectorEvent::MetricCollectionEnd => {
+                if let Some(report) = self.build_report(context, Instant::now()) {
+                    return vec![CollectorEvent::HealthReport(Arc::new(report))];
+                }
+            }
+            CollectorEvent::CollectorRemoved => {
+                self.endpoints.remove(&Self::stream_key(context));
+            }
+            CollectorEvent::Log(_)
+            | CollectorEvent::Firmware(_)
+            | CollectorEvent::HealthReport(_) => {}
+        }
+
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use mac_address::MacAddress;
+
+    use super::*;
+    use crate::endpoint::BmcAddr;
+
+    fn context() -> EventContext {
+        EventContext {
+            endpoint_key: "42:9e:b1:bd:9d:dd".to_string(),
+            addr: BmcAddr {
+                ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+                port: Some(443),
+                mac: MacAddress::from_str("42:9e:b1:bd:9d:dd").expect("valid mac"),
+            },
+            collector_type: "sensor_collector",
+            metadata: None,
+            rack_id: None,
+        }
+    }
+
+    fn rule(name: &str, condition: ThresholdCondition) -> ThresholdRuleConfig {
+        ThresholdRuleConfig {
+            name: name.to_string(),
+            metric_type: Some("temperature".to_string()),
+            sensor: Some("(?i).*inlet.*".to_string()),
+            labels: HashMap::from([("manufacturer".to_string(), "Dell.*".to_string())]),
+            condition,
+            consecutive_samples: 2,
+            classifications: vec![Classification::SensorCritical],
+        }
+    }
+
+    fn metric(sensor_id: &str, value: f64, manufacturer: &str) -> SensorHealthData {
+        SensorHealthData {
+            key: sensor_id.to_string(),
+            name: "hw_sensor".to_string(),
+            metric_type: "temperature".to_string(),
+            unit: "celsius".to_string(),
+            value,
+            labels: vec![("manufacturer".into(), manufacturer.to_string())],
+            context: None,
+        }
+    }
+
+    fn run_cycle(
+        processor: &ThresholdRulesProcessor,
+        metrics: Vec<SensorHealthData>,
+    ) -> Option<Arc<HealthReport>> {
+        let context = context();
+        let _ = processor.process_event(&context, &CollectorEvent::MetricCollectionStart);
+        for metric in metrics {
+            let _ = processor.process_event(&context, &CollectorEvent::Metric(metric.into()));
+        }
+        let emitted = processor.process_event(&context, &CollectorEvent::MetricCollectionEnd);
+        match emitted.last() {
+            Some(CollectorEvent::HealthReport(report)) => Some(report.clone()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn alerts_after_consecutive_violations() {
+        let processor = ThresholdRulesProcessor::new(&ThresholdRulesProcessorConfig {
+            rules: vec![rule("inlet-hot", ThresholdCondition::Above(40.0))],
+        })
+        .expect("valid rules");
+
+        let report = run_cycle(&processor, vec![metric("InletTemp", 45.0, "Dell Inc.")])
+            .expect("report for matching sensor");
+        assert_eq!(report.source, ReportSource::ThresholdRules);
+        assert!(report.alerts.is_empty());
+        assert_eq!(report.successes.len(), 1);
+        assert_eq!(
+            report.successes[0].target.as_deref(),
+            Some("inlet-hot:InletTemp")
+        );
+
+        let report = run_cycle(&processor, vec![metric("InletTemp", 46.0, "Dell Inc.")])
+            .expect("report for matching sensor");
+        assert!(report.successes.is_empty());
+        assert_eq!(report.alerts.len(), 1);
+        assert_eq!(report.alerts[0].probe_id, Probe::ThresholdRule);
+        assert_eq!(
+            report.alerts[0].classifications,
+            vec![Classification::SensorCritical]
+        );
+
+        let report = run_cycle(&processor, vec![metric("InletTemp", 30.0, "Dell Inc.")])
+            .expect("report for matching sensor");
+        assert!(report.alerts.is_empty());
+        assert_eq!(report.successes.len(), 1);
+    }
+
+    #[test]
+    fn ignores_sensors_not_matching_rule() {
+        let processor = ThresholdRulesProcessor::new(&ThresholdRulesProcessorConfig {
+            rules: vec![rule("inlet-hot", ThresholdCondition::Above(40.0))],
+        })
+        .expect("valid rules");
+
+        let report = run_cycle(
+            &processor,
+            vec![
+                metric("InletTemp", 45.0, "Lenovo"),
+                metric("CPU0Temp", 90.0, "Dell Inc."),
+            ],
+        );
+        assert!(report.is_none());
+        assert!(processor.endpoints.is_empty());
+    }
+
+    #[test]
+    fn alerts_when_sensor_missing_for_duration() {
+        let processor = ThresholdRulesProcessor::new(&ThresholdRulesProcessorConfig {
+            rules: vec![rule(
+                "inlet-missing",
+                ThresholdCondition::MissingFor(Duration::from_secs(300)),
+            )],
+        })
+        .expect("valid rules");
+        let context = context();
+        let now = Instant::now();
+
+        processor.record_metric(&context, &metric("InletTemp", 25.0, "Dell Inc."), now);
+        let report = processor
+            .build_report(&context, now + Duration::from_secs(60))
+            .expect("report for tracked sensor");
+        assert!(report.alerts.is_empty());
+        assert_eq!(report.successes.len(), 1);
+
+        let report = processor
+            .build_report(&context, now + Duration::from_secs(301))
+            .expect("report for tracked sensor");
+        assert_eq!(report.alerts.len(), 1);
+        assert_eq!(
+            report.alerts[0].target.as_deref(),
+            Some("inlet-missing:InletTemp")
+        );
+    }
+
+    #[test]
+    fn alerts_when_sensor_never_reported() {
+        let processor = ThresholdRulesProcessor::new(&ThresholdRulesProcessorConfig {
+            rules: vec![ThresholdRuleConfig {
+                metric_type: Some("power".to_string()),
+                sensor: Some("PSU.*".to_string()),
+                ..rule(
+                    "psu-missing",
+                    ThresholdCondition::MissingFor(Duration::from_secs(300)),
+                )
+            }],
+        })
+        .expect("valid rules");
+        let context = context();
+        let now = Instant::now();
+
+        // Readings of other sensors from a matching vendor start the clock
+        processor.record_metric(&context, &metric("InletTemp", 25.0, "Dell Inc."), now);
+        assert!(
+            processor
+                .build_report(&context, now + Duration::from_secs(60))
+                .is_none()
+        );
+
+        let report = processor
+            .build_report(&context, now + Duration::from_secs(301))
+            .expect("report for rule without readings");
+        assert_eq!(report.alerts.len(), 1);
+        assert_eq!(report.alerts[0].target.as_deref(), Some("psu-missing"));
+
+        // Endpoints of other vendors are not expected to report the sensor
+        let lenovo = EventContext {
+            endpoint_key: "42:9e:b1:bd:9d:de".to_string(),
+            ..context
+        };
+        processor.record_metric(&lenovo, &metric("InletTemp", 25.0, "Lenovo"), now);
+        assert!(
+            processor
+                .build_report(&lenovo, now + Duration::from_secs(301))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn collector_removed_clears_state() {
+        let processor = ThresholdRulesProcessor::new(&ThresholdRulesProcessorConfig {
+            rules: vec![rule("inlet-hot", ThresholdCondition::Above(40.0))],
+        })
+        .expect("valid rules");
+
+        let _ = run_cycle(&processor, vec![metric("InletTemp", 45.0, "Dell Inc.")]);
+        assert_eq!(processor.endpoints.len(), 1);
+
+        let emitted = processor.process_event(&context(), &CollectorEvent::CollectorRemoved);
+        assert!(emitted.is_empty());
+        assert!(processor.endpoints.is_empty());
+    }
+}
@@ -15,41 +15,297 @@
  * limitations under the License.
  */
 
+//! Assignment of BMC endpoints to carbide-health replicas
+//!
+//! Endpoints are assigned via rendezvous hashing: every endpoint is monitored by
+//! the member with the highest hash score for the endpoint key. When a member joins
+//! or leaves, only the endpoints that are assigned to that member move.
+//!
+//! Members are either the static shard ordinals of the StatefulSet, or are discovered
+//! dynamically by resolving a headless service which lists all ready replicas.
+
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+
+use crate::HealthError;
 use crate::endpoint::BmcEndpoint;
 
+/// The set of replicas which share the monitoring work
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardMembers {
+    self_id: String,
+    members: Vec<String>,
+}
+
+impl ShardMembers {
+    /// Creates a membership view from the perspective of the member `self_id`.
+    ///
+    /// `self_id` is not required to be part of `members`. A replica which is not
+    /// (yet) visible to its peers will not monitor any endpoint.
+    pub fn new(self_id: impl Into<String>, members: impl IntoIterator<Item = String>) -> Self {
+        let mut members: Vec<String> = members.into_iter().collect();
+        members.sort();
+        members.dedup();
+        Self {
+            self_id: self_id.into(),
+            members,
+        }
+    }
+
+    /// Creates a membership view for statically configured shard ordinals
+    pub fn from_ordinals(shard: usize, shards_count: usize) -> Self {
+        Self::new(
+            shard.to_string(),
+            (0..shards_count).map(|ordinal| ordinal.to_string()),
+        )
+    }
+
+    pub fn self_id(&self) -> &str {
+        &self.self_id
+    }
+
+    pub fn members(&self) -> &[String] {
+        &self.members
+    }
+
+    pub fn contains(&self, member: &str) -> bool {
+        self.members.iter().any(|m| m == member)
+    }
+
+    /// Returns the membership as seen by peers which don't know about this replica
+    fn without_self(&self) -> Self {
+        Self {
+            self_id: self.self_id.clone(),
+            members: self
+                .members
+                .iter()
+                .filter(|member| **member != self.self_id)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns the member which is responsible for the given key
+    pub fn owner(&self, key: &str) -> Option<&str> {
+        self.members
+            .iter()
+            .max_by_key(|member| rendezvous_score(member, key))
+            .map(String::as_str)
+    }
+}
+
+/// A change of membership whose handoff period has not yet finished
+#[derive(Debug, Clone)]
+struct PendingHandoff {
+    /// The membership before the change
+    previous: ShardMembers,
+    started_at: Instant,
+}
+
 pub struct ShardManager {
-    pub shard: usize,
-    pub shards_count: usize,
+    members: ShardMembers,
+    /// Endpoints which are newly assigned to this replica are only monitored once
+    /// this duration elapsed since the membership change. This gives the previous
+    /// owner time to observe the change and to stop its collectors.
+    handoff_delay: Duration,
+    pending_handoffs: Vec<PendingHandoff>,
 }
 
 impl ShardManager {
+    /// Creates a `ShardManager` for statically configured shard ordinals
+    pub fn with_static_ordinals(shard: usize, shards_count: usize) -> Self {
+        Self {
+            members: ShardMembers::from_ordinals(shard, shards_count),
+            handoff_delay: Duration::ZERO,
+            pending_handoffs: Vec::new(),
+        }
+    }
+
+    /// Creates a `ShardManager` for dynamically discovered members.
+    ///
+    /// Peers which did not yet observe this replica might still be monitoring
+    /// endpoints that are assigned to it. Those endpoints are only monitored once
+    /// `handoff_delay` elapsed. Endpoints without another owner, e.g. if this is
+    /// the only replica, are monitored immediately.
+    pub fn with_dynamic_members(members: ShardMembers, handoff_delay: Duration) -> Self {
+        let previous = members.without_self();
+        let pending_handoffs = if handoff_delay.is_zero() || previous.members().is_empty() {
+            Vec::new()
+        } else {
+            vec![PendingHandoff {
+                previous,
+                started_at: Instant::now(),
+            }]
+        };
+        Self {
+            members,
+            handoff_delay,
+            pending_handoffs,
+        }
+    }
+
+    pub fn members(&self) -> &ShardMembers {
+        &self.members
+    }
+
+    /// Updates the set of members. Returns `true` if the membership changed.
+    pub fn update_members(&mut self, members: ShardMembers) -> bool {
+        self.update_members_at(members, Instant::now())
+    }
+
+    fn update_members_at(&mut self, members: ShardMembers, now: Instant) -> bool {
+        if members == self.members {
+            return false;
+        }
+
+        self.prune_handoffs(now);
+        let previous = std::mem::replace(&mut self.members, members);
+        if !self.handoff_delay.is_zero() {
+            self.pending_handoffs.push(PendingHandoff {
+                previous,
+                started_at: now,
+            });
+        }
+        true
+    }
+
+    /// Returns the remaining time until all pending handoffs are finished
+    pub fn pending_handoff(&self) -> Option<Duration> {
+        let now = Instant::now();
+        self.pending_handoffs
+            .iter()
+            .map(|handoff| (handoff.started_at + self.handoff_delay).saturating_duration_since(now))
+            .filter(|remaining| !remaining.is_zero())
+            .max()
+    }
+
     /// Check if this shard should monitor a BMC endpoint.
     pub fn should_monitor(&self, endpoint: &BmcEndpoint) -> bool {
         self.should_monitor_key(&endpoint.hash_key())
     }
 
     pub fn should_monitor_key(&self, key: &str) -> bool {
-        if self.shards_count == 1 {
-            return true;
+        self.should_monitor_key_at(key, Instant::now())
+    }
+
+    fn should_monitor_key_at(&self, key: &str, now: Instant) -> bool {
+        let self_id = self.members.self_id();
+        if self.members.owner(key) != Some(self_id) {
+            return false;
         }
 
-        let hash = self.hash_key(key);
-        let assigned_shard = hash % self.shards_count;
-        assigned_shard == self.shard
+        // Endpoints which were owned by another member that is still active
+        // are only taken over after the handoff delay
+        !self
+            .pending_handoffs
+            .iter()
+            .filter(|handoff| now < handoff.started_at + self.handoff_delay)
+            .any(|handoff| {
+                handoff
+                    .previous
+                    .owner(key)
+                    .is_some_and(|owner| owner != self_id && self.members.contains(owner))
+            })
+    }
+
+    fn prune_handoffs(&mut self, now: Instant) {
+        let handoff_delay = self.handoff_delay;
+        self.pending_handoffs
+            .retain(|handoff| now < handoff.started_at + handoff_delay);
+    }
+}
+
+/// Rendezvous hash score of a member for a key
+fn rendezvous_score(member: &str, key: &str) -> u64 {
+    let mut hash = fnv1a(FNV_OFFSET_BASIS, member.as_bytes());
+    hash = fnv1a(hash, &[0]);
+    hash = fnv1a(hash, key.as_bytes());
+    mix64(hash)
+}
+
+const FNV_OFFSET_BASIS: u64 = 14695981039346656037;
+
+/// FNV-1a 64-bit
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    const FNV_PRIME: u64 = 1099511628211;
+
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
     }
 
-    /// FNV-1a 64-bit
-    fn hash_key(&self, key: &str) -> usize {
-        const FNV_PRIME: u64 = 1099511628211;
-        const FNV_OFFSET_BASIS: u64 = 14695981039346656037;
+    hash
+}
+
+/// Finalizer of splitmix64, which spreads similar FNV hashes of similar
+/// member names across the whole value range
+fn mix64(mut hash: u64) -> u64 {
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58476d1ce4e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+/// Discovers carbide-health replicas by resolving a headless service,
+/// which returns the addresses of all ready replicas
+pub struct DnsPeerDiscovery {
+    service: String,
+    self_address: IpAddr,
+}
 
-        let mut hash = FNV_OFFSET_BASIS;
-        for byte in key.as_bytes() {
-            hash = hash.wrapping_mul(FNV_PRIME);
-            hash ^= *byte as u64;
+impl DnsPeerDiscovery {
+    pub fn new(service: String, self_address: IpAddr) -> Self {
+        Self {
+            service,
+            self_address,
         }
+    }
 
-        hash as usize
+    pub async fn fetch_members(&self) -> Result<ShardMembers, HealthError> {
+        let addresses = tokio::net::lookup_host((self.service.as_str(), 0))
+            .await
+            .map_err(|e| {
+                HealthError::GenericError(format!(
+                    "failed to resolve shard peers via {}: {e}",
+                    self.service
+                ))
+            })?;
+
+        Ok(ShardMembers::new(
+            self.self_address.to_string(),
+            addresses.map(|addr| addr.ip().to_string()),
+        ))
+    }
+
+    /// Periodically refreshes the set of members and publishes changes to `members_tx`.
+    /// Returns once all receivers are dropped.
+    pub async fn run(self, refresh_interval: Duration, members_tx: watch::Sender<ShardMembers>) {
+        while !members_tx.is_closed() {
+            match self.fetch_members().await {
+                Ok(members) => {
+                    members_tx.send_if_modified(|current| {
+                        if *current == members {
+                            return false;
+                        }
+                        tracing::info!(
+                            members = ?members.members(),
+                            self_id = members.self_id(),
+                            "Shard membership changed"
+                        );
+                        *current = members;
+                        true
+                    });
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "Failed to refresh shard membership");
+                }
+            }
+
+            tokio::time::sleep(refresh_interval).await;
+        }
     }
 }
 
@@ -97,10 +353,7 @@ mod tests {
 
     #[test]
     fn test_single_shard() {
-        let manager = ShardManager {
-            shard: 0,
-            shards_count: 1,
-        };
+        let manager = ShardManager::with_static_ordinals(0, 1);
         assert!(manager.should_monitor(&endpoint("42:9e:b1:bd:9d:dd")));
     }
 
@@ -110,10 +363,7 @@ mod tests {
         let ep2 = endpoint("42:9e:b2:bd:9d:dd");
 
         let managers: Vec<_> = (0..3)
-            .map(|shard| ShardManager {
-                shard,
-                shards_count: 3,
-            })
+            .map(|shard| ShardManager::with_static_ordinals(shard, 3))
             .collect();
 
         for (label, ep) in [("ep1", &ep1), ("ep2", &ep2)] {
@@ -126,10 +376,7 @@ mod tests {
     fn test_should_monitor_key_distribution() {
         for key in ["AA:BB:CC:DD:EE:FF", "11:22:33:44:55:66"] {
             let count = (0..3)
-                .map(|shard| ShardManager {
-                    shard,
-                    shards_count: 3,
-                })
+                .map(|shard| ShardManager::with_static_ordinals(shard, 3))
                 .filter(|m| m.should_monitor_key(key))
                 .count();
             assert_eq!(
@@ -141,10 +388,7 @@ mod tests {
 
     #[test]
     fn test_should_monitor_key_consistency() {
-        let manager = ShardManager {
-            shard: 0,
-            shards_count: 3,
-        };
+        let manager = ShardManager::with_static_ordinals(0, 3);
         let key = "AA:BB:CC:DD:EE:FF";
         assert_eq!(
             manager.should_monitor_key(key),
@@ -158,10 +402,7 @@ mod tests {
         let ep_b = endpoint_with_rack("42:9e:b2:bd:9d:dd", "rack-7");
 
         let managers: Vec<_> = (0..3)
-            .map(|shard| ShardManager {
-                shard,
-                shards_count: 3,
-            })
+            .map(|shard| ShardManager::with_static_ordinals(shard, 3))
             .collect();
 
         let shard_a = managers
@@ -178,4 +419,152 @@ mod tests {
             "endpoints with the same rack_id should land on the same shard"
         );
     }
+
+    fn keys() -> Vec<String> {
+        (0..1000).map(|idx| format!("key-{idx}")).collect()
+    }
+
+    #[test]
+    fn test_scaling_moves_minimal_keys() {
+        let before = ShardMembers::from_ordinals(0, 4);
+        let after = ShardMembers::from_ordinals(0, 5);
+
+        let mut moved = 0;
+        for key in keys() {
+            let old_owner = before.owner(&key).unwrap();
+            let new_owner = after.owner(&key).unwrap();
+            if old_owner != new_owner {
+                // Keys only move to the new member
+                assert_eq!(new_owner, "4", "{key} moved between existing members");
+                moved += 1;
+            }
+        }
+        // Roughly 1/5th of all keys should move to the new member
+        assert!((100..300).contains(&moved), "{moved} keys moved");
+    }
+
+    #[test]
+    fn test_member_leaving_moves_only_its_keys() {
+        let before = ShardMembers::new(
+            "10.0.0.1",
+            ["10.0.0.1", "10.0.0.2", "10.0.0.3"].map(String::from),
+        );
+        let after = ShardMembers::new("10.0.0.1", ["10.0.0.1", "10.0.0.3"].map(String::from));
+
+        for key in keys() {
+            let old_owner = before.owner(&key).unwrap();
+            if old_owner != "10.0.0.2" {
+                assert_eq!(after.owner(&key).unwrap(), old_owner);
+            }
+        }
+    }
+
+    #[test]
+    fn test_not_a_member() {
+        let manager = ShardManager::with_dynamic_members(
+            ShardMembers::new("10.0.0.9", ["10.0.0.1".to_string()]),
+            Duration::ZERO,
+        );
+        assert!(keys().iter().all(|key| !manager.should_monitor_key(key)));
+    }
+
+    #[test]
+    fn test_single_member_starts_without_handoff() {
+        let manager = ShardManager::with_dynamic_members(
+            ShardMembers::new("a", ["a".to_string()]),
+            Duration::from_secs(60),
+        );
+        assert!(manager.pending_handoff().is_none());
+        assert!(keys().iter().all(|key| manager.should_monitor_key(key)));
+    }
+
+    #[test]
+    fn test_handoff_delay() {
+        let handoff_delay = Duration::from_secs(60);
+        let start = Instant::now();
+        let two_members = ShardMembers::new("a", ["a", "b"].map(String::from));
+        let mut manager = ShardManager::with_dynamic_members(two_members.clone(), handoff_delay);
+
+        // Nothing is monitored until the startup handoff is finished
+        assert!(
+            keys()
+                .iter()
+                .all(|key| !manager.should_monitor_key_at(key, start))
+        );
+        let after_startup = start + handoff_delay + Duration::from_secs(1);
+        let owned: Vec<String> = keys()
+            .into_iter()
+            .filter(|key| manager.should_monitor_key_at(key, after_startup))
+            .collect();
+        assert!(!owned.is_empty());
+
+        // A third member joins. Keys moving to it are released immediately.
+        let three_members = ShardMembers::new("a", ["a", "b", "c"].map(String::from));
+        assert!(manager.update_members_at(three_members.clone(), after_startup));
+        assert!(!manager.update_members_at(three_members, after_startup));
+        let still_owned: Vec<String> = owned
+            .iter()
+            .filter(|key| manager.should_monitor_key_at(key, after_startup))
+            .cloned()
+            .collect();
+        assert!(still_owned.len() < owned.len());
+
+        // "b" leaves. Its keys are taken over without a delay, since it
+        // is no longer active
+        let members = ShardMembers::new("a", ["a", "c"].map(String::from));
+        assert!(manager.update_members_at(members.clone(), after_startup));
+        let taken_over: Vec<String> = keys()
+            .into_iter()
+            .filter(|key| two_members.owner(key) == Some("b") && members.owner(key) == Some("a"))
+            .collect();
+        assert!(!taken_over.is_empty());
+        assert!(
+            taken_over
+                .iter()
+                .all(|key| manager.should_monitor_key_at(key, after_startup))
+        );
+
+        // "c" leaves while the handoff from "b" and "c" is pending.
+        // Keys that "a" takes over from "c" are monitored immediately.
+        let single_member = ShardMembers::new("a", ["a".to_string()]);
+        assert!(manager.update_members_at(single_member, after_startup));
+        assert!(
+            keys()
+                .iter()
+                .all(|key| manager.should_monitor_key_at(key, after_startup))
+        );
+    }
+
+    #[test]
+    fn test_handoff_from_active_member() {
+        let handoff_delay = Duration::from_secs(60);
+        let now = Instant::now();
+        // "a" is not yet visible to its peers, and thereby owns no keys
+        let mut manager = ShardManager {
+            members: ShardMembers::new("a", ["b".to_string()]),
+            handoff_delay,
+            pending_handoffs: Vec::new(),
+        };
+        assert!(manager.pending_handoff().is_none());
+
+        // Once "a" joins, it takes over keys from "b" after the handoff delay
+        let members = ShardMembers::new("a", ["a", "b"].map(String::from));
+        assert!(manager.update_members_at(members.clone(), now));
+        let moved: Vec<String> = keys()
+            .into_iter()
+            .filter(|key| members.owner(key) == Some("a"))
+            .collect();
+        assert!(!moved.is_empty());
+        assert!(
+            moved
+                .iter()
+                .all(|key| !manager.should_monitor_key_at(key, now))
+        );
+        assert!(manager.pending_handoff().is_some());
+        assert!(
+            moved
+                .iter()
+                .all(|key| manager.should_monitor_key_at(key, now + handoff_delay))
+        );
+    }
 }
@@ -0,0 +1,780 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Bounded on-disk buffer for sink events.
+//!
+//! Sinks spill events into the buffer when their destination is unreachable,
+//! and keep appending while older events are still buffered so that ordering
+//! is preserved. Buffered events are replayed oldest-first once the destination
+//! recovers. Every event is stored in its own file named after its sequence
+//! number, which keeps writes atomic (write + rename) and makes removing
+//! delivered events cheap. When the size limit is reached, the oldest events
+//! are dropped.
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::future::Future;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use prometheus::{Gauge, IntCounter, IntGauge, Registry};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::HealthError;
+use crate::collectors::{BackoffConfig, ExponentialBackoff};
+use crate::config::{Configurable, DiskBufferConfig};
+use crate::otlp::drain::is_retryable;
+
+const RECORD_EXTENSION: &str = "rec";
+const TEMP_EXTENSION: &str = "tmp";
+
+/// Every record starts with the time it was buffered, in milliseconds since the UNIX epoch
+const HEADER_LEN: usize = 8;
+
+/// Health report which is buffered by the Carbide API health report sinks
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct BufferedHealthReport<I> {
+    pub id: I,
+    pub report: health_report::HealthReport,
+}
+
+/// Returns whether an event which failed with the given error should be kept for replay
+pub(crate) fn is_retryable_error(error: &HealthError) -> bool {
+    match error {
+        HealthError::ApiConnectFailed(_) => true,
+        HealthError::ApiInvocationError(status) => is_retryable(status),
+        _ => false,
+    }
+}
+
+/// Submits health reports to carbide-api. If a disk buffer is configured, reports which
+/// can not be delivered because carbide-api is unavailable are buffered and replayed.
+pub(crate) struct HealthReportSubmitter<I> {
+    buffer: Option<Arc<DiskBuffer>>,
+    submit: SubmitFn<I>,
+}
+
+type SubmitFn<I> = Arc<
+    dyn Fn(I, health_report::HealthReport) -> BoxFuture<'static, Result<(), HealthError>>
+        + Send
+        + Sync,
+>;
+
+impl<I> HealthReportSubmitter<I>
+where
+    I: Clone + Serialize + DeserializeOwned + Send + 'static,
+{
+    /// Creates the submitter, and starts replaying reports left in the disk buffer.
+    /// Buffer metrics are registered as `{metric_prefix}_disk_buffer_*`.
+    pub fn new<F, Fut>(
+        disk_buffer: &Configurable<DiskBufferConfig>,
+        handle: &tokio::runtime::Handle,
+        registry: &Registry,
+        metric_prefix: &str,
+        submit: F,
+    ) -> Result<Self, HealthError>
+    where
+        F: Fn(I, health_report::HealthReport) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), HealthError>> + Send + 'static,
+    {
+        let submit: SubmitFn<I> = Arc::new(move |id, report| submit(id, report).boxed());
+        let buffer = match disk_buffer {
+            Configurable::Enabled(buffer_config) => {
+                let replay = Arc::clone(&submit);
+                Some(DiskBuffer::start(
+                    buffer_config,
+                    handle,
+                    registry,
+                    metric_prefix,
+                    move |buffered: BufferedHealthReport<I>| replay(buffered.id, buffered.report),
+                )?)
+            }
+            Configurable::Disabled => None,
+        };
+
+        Ok(Self { buffer, submit })
+    }
+
+    /// Submits a report. Reports are buffered instead while older reports wait for
+    /// replay, so that they stay ordered. Returns the error of a failed submission,
+    /// even if the report was buffered for replay.
+    pub async fn submit(
+        &self,
+        id: I,
+        report: health_report::HealthReport,
+    ) -> Result<(), HealthError> {
+        let Some(buffer) = &self.buffer else {
+            return (self.submit)(id, report).await;
+        };
+
+        if !buffer.is_empty() {
+            buffer.push_json(&BufferedHealthReport { id, report }).await;
+            return Ok(());
+        }
+
+        let retained = BufferedHealthReport {
+            id: id.clone(),
+            report: report.clone(),
+        };
+        let result = (self.submit)(id, report).await;
+        if let Err(error) = &result
+            && is_retryable_error(error)
+        {
+            buffer.push_json(&retained).await;
+        }
+        result
+    }
+}
+
+struct BufferedEntry {
+    seq: u64,
+    size: u64,
+    buffered_at: SystemTime,
+    /// Whether the record is completely written to disk. Events are replayed in
+    /// order, so replay waits while the oldest record is still being written.
+    written: bool,
+}
+
+#[derive(Default)]
+struct BufferState {
+    entries: VecDeque<BufferedEntry>,
+    next_seq: u64,
+    total_bytes: u64,
+}
+
+struct DiskBufferMetrics {
+    buffered_bytes: IntGauge,
+    buffered_events: IntGauge,
+    oldest_event_age_seconds: Gauge,
+    dropped_total: IntCounter,
+    replayed_total: IntCounter,
+}
+
+impl DiskBufferMetrics {
+    fn new(registry: &Registry, prefix: &str) -> Result<Self, prometheus::Error> {
+        let buffered_bytes = IntGauge::new(
+            format!("{prefix}_disk_buffer_bytes"),
+            "bytes of events held in the on-disk buffer",
+        )?;
+        registry.register(Box::new(buffered_bytes.clone()))?;
+
+        let buffered_events = IntGauge::new(
+            format!("{prefix}_disk_buffer_events"),
+            "number of events held in the on-disk buffer",
+        )?;
+        registry.register(Box::new(buffered_events.clone()))?;
+
+        let oldest_event_age_seconds = Gauge::new(
+            format!("{prefix}_disk_buffer_oldest_event_age_seconds"),
+            "age of the oldest event in the on-disk buffer",
+        )?;
+        registry.register(Box::new(oldest_event_age_seconds.clone()))?;
+
+        let dropped_total = IntCounter::new(
+            format!("{prefix}_disk_buffer_dropped_total"),
+            "total events dropped from the on-disk buffer because it was full, unreadable or rejected on replay",
+        )?;
+        registry.register(Box::new(dropped_total.clone()))?;
+
+        let replayed_total = IntCounter::new(
+            format!("{prefix}_disk_buffer_replayed_total"),
+            "total events delivered from the on-disk buffer",
+        )?;
+        registry.register(Box::new(replayed_total.clone()))?;
+
+        Ok(Self {
+            buffered_bytes,
+            buffered_events,
+            oldest_event_age_seconds,
+            dropped_total,
+            replayed_total,
+        })
+    }
+}
+
+pub(crate) struct DiskBuffer {
+    directory: PathBuf,
+    max_bytes: u64,
+    replay_interval: Duration,
+    state: Mutex<BufferState>,
+    metrics: DiskBufferMetrics,
+}
+
+impl DiskBuffer {
+    /// Opens the buffer directory and picks up events left over from a previous run.
+    /// Metrics are registered as `{metric_prefix}_disk_buffer_*`.
+    pub fn open(
+        config: &DiskBufferConfig,
+        registry: &Registry,
+        metric_prefix: &str,
+    ) -> Result<Self, HealthError> {
+        let directory = PathBuf::from(&config.directory);
+        fs::create_dir_all(&directory).map_err(|e| {
+            HealthError::GenericError(format!(
+                "failed to create disk buffer directory {}: {e}",
+                directory.display()
+            ))
+        })?;
+
+        let state = load_entries(&directory).map_err(|e| {
+            HealthError::GenericError(format!(
+                "failed to read disk buffer directory {}: {e}",
+                directory.display()
+            ))
+        })?;
+        if !state.entries.is_empty() {
+            tracing::info!(
+                directory = %directory.display(),
+                events = state.entries.len(),
+                bytes = state.total_bytes,
+                "found buffered events from previous run"
+            );
+        }
+
+        let buffer = Self {
+            directory,
+            max_bytes: config.max_bytes,
+            replay_interval: config.replay_interval,
+            state: Mutex::new(state),
+            metrics: DiskBufferMetrics::new(registry, metric_prefix)?,
+        };
+        buffer.update_metrics();
+        Ok(buffer)
+    }
+
+    /// Opens the buffer and spawns a task which replays JSON encoded events via `submit`
+    pub fn start<T, F, Fut>(
+        config: &DiskBufferConfig,
+        handle: &tokio::runtime::Handle,
+        registry: &Registry,
+        metric_prefix: &str,
+        submit: F,
+    ) -> Result<Arc<Self>, HealthError>
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: Fn(T) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), HealthError>> + Send + 'static,
+    {
+        let buffer = Arc::new(Self::open(config, registry, metric_prefix)?);
+        handle.spawn(Arc::clone(&buffer).replay(submit));
+        Ok(buffer)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().entries.is_empty()
+    }
+
+    pub async fn push_json<T: Serialize>(&self, value: &T) {
+        match serde_json::to_vec(value) {
+            Ok(payload) => self.push(payload).await,
+            Err(error) => {
+                tracing::error!(?error, "failed to serialize event for disk buffer");
+                self.metrics.dropped_total.inc();
+            }
+        }
+    }
+
+    /// Appends an event, dropping the oldest events if the buffer would exceed its size limit.
+    ///
+    /// The event is placed in the buffer before it is written, so that concurrent pushes
+    /// keep their order. The file system is only accessed on the blocking thread pool and
+    /// never while the buffer state is locked.
+    pub async fn push(&self, payload: Vec<u8>) {
+        let size = (HEADER_LEN + payload.len()) as u64;
+        if size > self.max_bytes {
+            tracing::warn!(
+                size,
+                max_bytes = self.max_bytes,
+                "event exceeds disk buffer size, dropping"
+            );
+            self.metrics.dropped_total.inc();
+            return;
+        }
+
+        let buffered_at = SystemTime::now();
+        let (seq, evicted) = {
+            let mut state = self.lock();
+            let mut evicted = Vec::new();
+            while state.total_bytes + size > self.max_bytes {
+                let Some(oldest) = state.entries.pop_front() else {
+                    break;
+                };
+                state.total_bytes -= oldest.size;
+                evicted.push(oldest.seq);
+            }
+
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.total_bytes += size;
+            state.entries.push_back(BufferedEntry {
+                seq,
+                size,
+                buffered_at,
+                written: false,
+            });
+            (seq, evicted)
+        };
+        self.metrics.dropped_total.inc_by(evicted.len() as u64);
+
+        let directory = self.directory.clone();
+        let result = run_blocking(move || {
+            for seq in evicted {
+                remove_record(&directory, seq);
+            }
+            write_record(&directory, seq, buffered_at, &payload)
+        })
+        .await;
+
+        let still_buffered = {
+            let mut state = self.lock();
+            match state.entries.iter().position(|entry| entry.seq == seq) {
+                Some(position) if result.is_ok() => {
+                    state.entries[position].written = true;
+                    true
+                }
+                Some(position) => {
+                    state.entries.remove(position);
+                    state.total_bytes -= size;
+                    false
+                }
+                None => false,
+            }
+        };
+
+        match result {
+            Ok(()) if !still_buffered => {
+                // Dropped by a concurrent push while the record was written
+                self.remove_file(seq).await;
+            }
+            Ok(()) => {}
+            Err(error) => {
+                tracing::error!(
+                    ?error,
+                    directory = %self.directory.display(),
+                    "failed to write event to disk buffer, dropping"
+                );
+                self.metrics.dropped_total.inc();
+            }
+        }
+
+        self.update_metrics();
+    }
+
+    /// Returns the oldest buffered event, or `None` while it is still being written.
+    /// Records which can not be read are dropped.
+    pub async fn front(&self) -> Option<(u64, Vec<u8>)> {
+        loop {
+            let seq = {
+                let state = self.lock();
+                let entry = state.entries.front()?;
+                if !entry.written {
+                    return None;
+                }
+                entry.seq
+            };
+
+            let path = record_path(&self.directory, seq);
+            match run_blocking(move || fs::read(path)).await {
+                Ok(mut data) if data.len() >= HEADER_LEN => {
+                    data.drain(..HEADER_LEN);
+                    return Some((seq, data));
+                }
+                Ok(_) => {
+                    tracing::warn!(seq, "truncated disk buffer record, dropping");
+                    self.discard(seq).await;
+                }
+                Err(error) => {
+                    tracing::warn!(?error, seq, "failed to read disk buffer record, dropping");
+                    self.discard(seq).await;
+                }
+            }
+        }
+    }
+
+    /// Removes an event which was delivered
+    pub async fn remove(&self, seq: u64) {
+        if self.remove_entry(seq).await {
+            self.metrics.replayed_total.inc();
+        }
+    }
+
+    /// Removes an event which can not be delivered
+    pub async fn discard(&self, seq: u64) {
+        if self.remove_entry(seq).await {
+            self.metrics.dropped_total.inc();
+        }
+    }
+
+    pub fn update_metrics(&self) {
+        let state = self.lock();
+        self.metrics
+            .buffered_bytes
+            .set(state.total_bytes.try_into().unwrap_or(i64::MAX));
+        self.metrics
+            .buffered_events
+            .set(state.entries.len().try_into().unwrap_or(i64::MAX));
+        let oldest_age = state
+            .entries
+            .front()
+            .and_then(|entry| entry.buffered_at.elapsed().ok())
+            .unwrap_or_default();
+        self.metrics
+            .oldest_event_age_seconds
+            .set(oldest_age.as_secs_f64());
+    }
+
+    /// Delivers buffered JSON encoded events oldest-first. Events which fail with a
+    /// retryable error stay in the buffer and are retried with backoff, events which
+    /// fail otherwise are dropped.
+    async fn replay<T, F, Fut>(self: Arc<Self>, submit: F)
+    where
+        T: DeserializeOwned,
+        F: Fn(T) -> Fut,
+        Fut: Future<Output = Result<(), HealthError>>,
+    {
+        let mut backoff = ExponentialBackoff::new(&BackoffConfig {
+            initial: Duration::from_secs(1),
+            max: self.replay_interval.max(Duration::from_secs(1)),
+        });
+
+        loop {
+            self.update_metrics();
+
+            let Some((seq, payload)) = self.front().await else {
+                tokio::time::sleep(self.replay_interval).await;
+                continue;
+            };
+
+            let event = match serde_json::from_slice::<T>(&payload) {
+                Ok(event) => event,
+                Err(error) => {
+                    tracing::warn!(?error, seq, "failed to decode disk buffer record, dropping");
+                    self.discard(seq).await;
+                    continue;
+                }
+            };
+
+            match submit(event).await {
+                Ok(()) => {
+                    self.remove(seq).await;
+                    backoff.reset();
+                }
+                Err(error) if is_retryable_error(&error) => {
+                    let delay = backoff.next_delay();
+                    tracing::debug!(?error, retry_in = ?delay, "destination unavailable, keeping buffered events");
+                    tokio::time::sleep(delay).await;
+                }
+                Err(error) => {
+                    tracing::warn!(?error, seq, "buffered event rejected, dropping");
+                    self.discard(seq).await;
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BufferState> {
+        self.state.lock().expect("disk buffer mutex poisoned")
+    }
+
+    async fn remove_entry(&self, seq: u64) -> bool {
+        {
+            let mut state = self.lock();
+            let Some(position) = state.entries.iter().position(|entry| entry.seq == seq) else {
+                return false;
+            };
+            if let Some(entry) = state.entries.remove(position) {
+                state.total_bytes -= entry.size;
+            }
+        }
+        self.remove_file(seq).await;
+        self.update_metrics();
+        true
+    }
+
+    async fn remove_file(&self, seq: u64) {
+        let directory = self.directory.clone();
+        let _ = run_blocking(move || {
+            remove_record(&directory, seq);
+            Ok(())
+        })
+        .await;
+    }
+}
+
+/// Runs file system operations on the blocking thread pool
+async fn run_blocking<T, F>(operation: F) -> io::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(operation)
+        .await
+        .unwrap_or_else(|error| Err(io::Error::other(error)))
+}
+
+fn record_path(directory: &Path, seq: u64) -> PathBuf {
+    directory.join(format!("{seq:020}.{RECORD_EXTENSION}"))
+}
+
+fn write_record(
+    directory: &Path,
+    seq: u64,
+    buffered_at: SystemTime,
+    payload: &[u8],
+) -> io::Result<()> {
+    let buffered_at_ms = buffered_at
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let mut data = Vec::with_capacity(HEADER_LEN + payload.len());
+    data.extend_from_slice(&buffered_at_ms.to_le_bytes());
+    data.extend_from_slice(payload);
+
+    let temp_path = directory.join(format!("{seq:020}.{TEMP_EXTENSION}"));
+    fs::write(&temp_path, &data)?;
+    fs::rename(&temp_path, record_path(directory, seq))
+}
+
+fn remove_record(directory: &Path, seq: u64) {
+    if let Err(error) = fs::remove_file(record_path(directory, seq))
+        && error.kind() != io::ErrorKind::NotFound
+    {
+        tracing::warn!(?error, seq, "failed to remove disk buffer record");
+    }
+}
+
+fn load_entries(directory: &Path) -> io::Result<BufferState> {
+    let mut entries = Vec::new();
+    for dir_entry in fs::read_dir(directory)? {
+        let path = dir_entry?.path();
+        match path.extension().and_then(|extension| extension.to_str()) {
+            // Left over from a write which did not complete
+            Some(TEMP_EXTENSION) => {
+                let _ = fs::remove_file(&path);
+            }
+            Some(RECORD_EXTENSION) => {
+                let Some(seq) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<u64>().ok())
+                else {
+                    continue;
+                };
+                match read_header(&path) {
+                    Ok((size, buffered_at)) => entries.push(BufferedEntry {
+                        seq,
+                        size,
+                        buffered_at,
+                        written: true,
+                    }),
+                    Err(error) => {
+                        tracing::warn!(?error, path = %path.display(), "dropping unreadable disk buffer record");
+                        let _ = fs::remove_file(&path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    entries.sort_by_key(|entry| entry.seq);
+
+    Ok(BufferState {
+        next_seq: entries.last().map_or(0, |entry| entry.seq + 1),
+        total_bytes: entries.iter().map(|entry| entry.size).sum(),
+        entries: entries.into(),
+    })
+}
+
+fn read_header(path: &Path) -> io::Result<(u64, SystemTime)> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut header = [0u8; HEADER_LEN];
+    file.read_exact(&mut header)?;
+    let buffered_at = UNIX_EPOCH + Duration::from_millis(u64::from_le_bytes(header));
+    Ok((size, buffered_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(directory: &Path, max_bytes: u64) -> DiskBufferConfig {
+        DiskBufferConfig {
+            directory: directory.to_string_lossy().into_owned(),
+            max_bytes,
+            replay_interval: Duration::from_secs(1),
+        }
+    }
+
+    fn open(directory: &Path, max_bytes: u64) -> DiskBuffer {
+        DiskBuffer::open(&config(directory, max_bytes), &Registry::new(), "test")
+            .expect("open disk buffer")
+    }
+
+    #[tokio::test]
+    async fn replays_events_in_order() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let buffer = open(dir.path(), 1024);
+
+        buffer.push(b"first".to_vec()).await;
+        buffer.push(b"second".to_vec()).await;
+        assert!(!buffer.is_empty());
+
+        let (seq, payload) = buffer.front().await.expect("buffered event");
+        assert_eq!(payload, b"first");
+        buffer.remove(seq).await;
+
+        let (seq, payload) = buffer.front().await.expect("buffered event");
+        assert_eq!(payload, b"second");
+        buffer.remove(seq).await;
+
+        assert!(buffer.front().await.is_none());
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.metrics.replayed_total.get(), 2);
+        assert_eq!(buffer.metrics.buffered_bytes.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn drops_oldest_events_when_full() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let record_size = (HEADER_LEN + 4) as u64;
+        let buffer = open(dir.path(), record_size * 2);
+
+        buffer.push(b"aaaa".to_vec()).await;
+        buffer.push(b"bbbb".to_vec()).await;
+        buffer.push(b"cccc".to_vec()).await;
+
+        assert_eq!(buffer.metrics.dropped_total.get(), 1);
+        assert_eq!(buffer.metrics.buffered_events.get(), 2);
+        assert_eq!(
+            buffer.metrics.buffered_bytes.get(),
+            (record_size * 2) as i64
+        );
+        assert_eq!(buffer.front().await.expect("buffered event").1, b"bbbb");
+    }
+
+    #[tokio::test]
+    async fn drops_events_larger_than_buffer() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let buffer = open(dir.path(), 4);
+
+        buffer.push(b"too large".to_vec()).await;
+
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.metrics.dropped_total.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn reopening_resumes_buffered_events() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        {
+            let buffer = open(dir.path(), 1024);
+            buffer.push(b"first".to_vec()).await;
+            buffer.push(b"second".to_vec()).await;
+            let (seq, _) = buffer.front().await.expect("buffered event");
+            buffer.remove(seq).await;
+        }
+        fs::write(dir.path().join("00000000000000000007.tmp"), b"partial").expect("write");
+
+        let buffer = open(dir.path(), 1024);
+        assert_eq!(buffer.metrics.buffered_events.get(), 1);
+        assert!(!dir.path().join("00000000000000000007.tmp").exists());
+
+        buffer.push(b"third".to_vec()).await;
+        let (seq, payload) = buffer.front().await.expect("buffered event");
+        assert_eq!(payload, b"second");
+        buffer.remove(seq).await;
+        assert_eq!(buffer.front().await.expect("buffered event").1, b"third");
+    }
+
+    #[tokio::test]
+    async fn buffered_health_report_round_trips() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let buffer = open(dir.path(), 4096);
+        let report = BufferedHealthReport {
+            id: "rack-1".to_string(),
+            report: health_report::HealthReport::empty("hardware-health".to_string()),
+        };
+
+        buffer.push_json(&report).await;
+
+        let (_, payload) = buffer.front().await.expect("buffered event");
+        let decoded: BufferedHealthReport<String> =
+            serde_json::from_slice(&payload).expect("decode buffered report");
+        assert_eq!(decoded.id, "rack-1");
+        assert_eq!(decoded.report, report.report);
+    }
+
+    #[tokio::test]
+    async fn concurrent_pushes_respect_size_limit() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let record_size = (HEADER_LEN + 4) as u64;
+        let buffer = open(dir.path(), record_size * 5);
+
+        futures::future::join_all((0..10u32).map(|i| buffer.push(i.to_le_bytes().to_vec()))).await;
+
+        assert_eq!(buffer.metrics.buffered_events.get(), 5);
+        assert_eq!(buffer.metrics.dropped_total.get(), 5);
+        let files = fs::read_dir(dir.path()).expect("read dir").count();
+        assert_eq!(files, 5);
+    }
+
+    #[tokio::test]
+    async fn submitter_buffers_reports_while_api_is_unavailable() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let submitter = HealthReportSubmitter::new(
+            &Configurable::Enabled(config(dir.path(), 4096)),
+            &tokio::runtime::Handle::current(),
+            &Registry::new(),
+            "test",
+            |_id: String, _report| async {
+                Err(HealthError::ApiInvocationError(tonic::Status::unavailable(
+                    "down",
+                )))
+            },
+        )
+        .expect("create submitter");
+        let report = health_report::HealthReport::empty("hardware-health".to_string());
+
+        let result = submitter.submit("rack-1".to_string(), report.clone()).await;
+        assert!(result.is_err());
+        // Queued behind the buffered report without contacting the API
+        let result = submitter.submit("rack-2".to_string(), report).await;
+        assert!(result.is_ok());
+
+        let buffer = submitter.buffer.as_ref().expect("disk buffer");
+        assert_eq!(buffer.metrics.buffered_events.get(), 2);
+    }
+
+    #[test]
+    fn only_connectivity_errors_are_retryable() {
+        assert!(is_retryable_error(&HealthError::ApiInvocationError(
+            tonic::Status::unavailable("down")
+        )));
+        assert!(!is_retryable_error(&HealthError::ApiInvocationError(
+            tonic::Status::invalid_argument("bad report")
+        )));
+        assert!(!is_retryable_error(&HealthError::GenericError(
+            "conversion".to_string()
+        )));
+    }
+}
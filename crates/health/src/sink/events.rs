@@ -26,6 +26,7 @@ use health_report::{
     HealthReport as CarbideHealthReport, HealthReportConversionError,
 };
 use nv_redfish::resource::Health as BmcHealth;
+use serde::{Deserialize, Serialize};
 
 use crate::endpoint::{BmcAddr, BmcEndpoint, EndpointMetadata};
 use crate::metrics::MetricLabel;
@@ -183,6 +184,8 @@ pub enum ReportSource {
     BmcLeakDetectors,
     TrayLeakDetection,
     RackLeakDetection,
+    ThresholdRules,
+    AnomalyDetection,
 }
 
 impl ReportSource {
@@ -192,6 +195,8 @@ impl ReportSource {
             Self::BmcLeakDetectors => "bmc-leak-detectors",
             Self::TrayLeakDetection => "tray-leak-detection",
             Self::RackLeakDetection => "rack-leak-detection",
+            Self::ThresholdRules => "bmc-threshold-rules",
+            Self::AnomalyDetection => "bmc-anomaly-detection",
         }
     }
 }
@@ -200,6 +205,8 @@ impl ReportSource {
 pub enum Probe {
     Sensor,
     LeakDetection,
+    ThresholdRule,
+    SensorAnomaly,
 }
 
 impl Probe {
@@ -207,11 +214,13 @@ impl Probe {
         match self {
             Self::Sensor => "BmcSensor",
             Self::LeakDetection => "BmcLeakDetection",
+            Self::ThresholdRule => "BmcThresholdRule",
+            Self::SensorAnomaly => "BmcSensorAnomaly",
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Classification {
     SensorOk,
     SensorWarning,
@@ -220,6 +229,9 @@ pub enum Classification {
     SensorFailure,
     Leak,
     LeakDetector,
+    PreventAllocations,
+    PreventHostStateChanges,
+    SuppressExternalAlerting,
 }
 
 impl Classification {
@@ -232,6 +244,9 @@ impl Classification {
             Self::SensorFailure => "SensorFailure",
             Self::Leak => "Leak",
             Self::LeakDetector => "LeakDetector",
+            Self::PreventAllocations => "PreventAllocations",
+            Self::PreventHostStateChanges => "PreventHostStateChanges",
+            Self::SuppressExternalAlerting => "SuppressExternalAlerting",
         }
     }
 }
@@ -17,6 +17,7 @@
 
 mod composite;
 mod dedup_queue;
+pub(crate) mod disk_buffer;
 #[cfg(not(feature = "bench-hooks"))]
 pub(crate) mod event_mapper;
 #[cfg(feature = "bench-hooks")]
@@ -24,6 +25,7 @@ pub mod event_mapper;
 mod events;
 mod health_report;
 mod log_file;
+mod mqtt;
 pub(crate) mod otlp;
 mod power_shelf_health_report;
 mod prometheus;
@@ -39,6 +41,7 @@ pub use events::{
 };
 pub use health_report::HealthReportSink;
 pub use log_file::LogFileSink;
+pub use mqtt::MqttSink;
 pub use power_shelf_health_report::PowerShelfHealthReportSink;
 pub use prometheus::PrometheusSink;
 pub use rack_health_report::RackHealthReportSink;
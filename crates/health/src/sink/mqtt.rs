@@ -0,0 +1,627 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use carbide_uuid::machine::MachineId;
+use carbide_uuid::rack::RackId;
+use chrono::{DateTime, Utc};
+use health_report::HealthReport as CarbideHealthReport;
+use mqttea::QoS;
+use mqttea::client::{ClientCredentials, ClientOptions, MqtteaClient};
+use prometheus::Counter;
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use super::{CollectorEvent, DataSink, EventContext};
+use crate::HealthError;
+use crate::collectors::{BackoffConfig, ExponentialBackoff};
+use crate::config::{Configurable, MqttEventKind, MqttQos, MqttSinkConfig};
+use crate::limiter::{BucketLimiter, NoopLimiter, RateLimiter};
+use crate::metrics::MetricsManager;
+
+/// Topic level used for `{rack_id}` when the endpoint is not assigned to a rack.
+const UNKNOWN_RACK: &str = "unknown";
+
+struct MqttMessage {
+    topic: String,
+    payload: Vec<u8>,
+}
+
+/// Publishes collector events as JSON to an MQTT broker. Events are queued
+/// by `handle_event` and published by a background task, which groups them
+/// per topic into JSON arrays of up to `batch_size` events.
+pub struct MqttSink {
+    sender: mpsc::Sender<MqttMessage>,
+    topic: TopicTemplate,
+    event_kinds: Vec<MqttEventKind>,
+    dropped_total: Counter,
+}
+
+impl MqttSink {
+    pub fn new(
+        config: &MqttSinkConfig,
+        metrics_manager: &MetricsManager,
+        prefix: &str,
+    ) -> Result<Self, HealthError> {
+        let handle = tokio::runtime::Handle::try_current().map_err(|e| {
+            HealthError::GenericError(format!("mqtt sink requires active tokio runtime: {e}"))
+        })?;
+
+        let dropped_total = Counter::new(
+            format!("{prefix}_mqtt_sink_dropped_total"),
+            "total events dropped because the mqtt publish queue was full",
+        )?;
+        let publish_failures_total = Counter::new(
+            format!("{prefix}_mqtt_sink_publish_failures_total"),
+            "total batches which could not be handed over to the mqtt client",
+        )?;
+        let registry = metrics_manager.global_registry();
+        registry.register(Box::new(dropped_total.clone()))?;
+        registry.register(Box::new(publish_failures_total.clone()))?;
+
+        let credentials = match (&config.username, &config.password_file) {
+            (Some(username), Some(password_file)) => Some(ClientCredentials {
+                username: username.clone(),
+                password: std::fs::read_to_string(password_file)
+                    .map_err(|e| {
+                        HealthError::GenericError(format!(
+                            "failed to read mqtt password file {password_file}: {e}"
+                        ))
+                    })?
+                    .trim_end()
+                    .to_string(),
+            }),
+            (Some(username), None) => Some(ClientCredentials {
+                username: username.clone(),
+                password: String::new(),
+            }),
+            (None, _) => None,
+        };
+
+        let limiter: Arc<dyn RateLimiter> = match &config.rate_limit {
+            Configurable::Enabled(rate_limit) => Arc::new(BucketLimiter::new(
+                rate_limit.bucket_burst,
+                rate_limit.bucket_replenish,
+                rate_limit.max_jitter,
+            )),
+            Configurable::Disabled => Arc::new(NoopLimiter),
+        };
+
+        let (sender, receiver) = mpsc::channel(config.queue_capacity);
+        let publisher = MqttPublisher {
+            broker_host: config.broker_host.clone(),
+            broker_port: config.broker_port,
+            client_id: config.client_id.clone(),
+            qos: config.qos,
+            retain: config.retain,
+            credentials,
+            batch_size: config.batch_size,
+            flush_interval: config.flush_interval,
+            limiter,
+            publish_failures_total,
+        };
+        handle.spawn(publisher.run(receiver));
+
+        Ok(Self {
+            sender,
+            topic: TopicTemplate::parse(&config.topic_template),
+            event_kinds: config.event_kinds.clone(),
+            dropped_total,
+        })
+    }
+}
+
+impl DataSink for MqttSink {
+    fn sink_type(&self) -> &'static str {
+        "mqtt_sink"
+    }
+
+    fn handle_event(&self, context: &EventContext, event: &CollectorEvent) {
+        let Some(kind) = event_kind(event) else {
+            return;
+        };
+        if !self.event_kinds.contains(&kind) {
+            return;
+        }
+
+        let Some(data) = MqttEventData::from_event(event) else {
+            return;
+        };
+        let payload = match serde_json::to_vec(&MqttEvent::new(context, kind, data)) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::error!(error = ?e, "failed to serialize mqtt event");
+                return;
+            }
+        };
+
+        let message = MqttMessage {
+            topic: self.topic.render(context, kind),
+            payload,
+        };
+        if self.sender.try_send(message).is_err() {
+            self.dropped_total.inc();
+        }
+    }
+}
+
+fn event_kind(event: &CollectorEvent) -> Option<MqttEventKind> {
+    match event {
+        CollectorEvent::Metric(_) => Some(MqttEventKind::Metric),
+        CollectorEvent::Firmware(_) => Some(MqttEventKind::Firmware),
+        CollectorEvent::Log(_) => Some(MqttEventKind::Log),
+        CollectorEvent::HealthReport(_) => Some(MqttEventKind::HealthReport),
+        CollectorEvent::MetricCollectionStart
+        | CollectorEvent::MetricCollectionEnd
+        | CollectorEvent::CollectorRemoved => None,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum TopicSegment {
+    Literal(String),
+    RackId,
+    Endpoint,
+    Kind,
+    Collector,
+}
+
+/// Topic template parsed once at startup. Placeholders are validated by
+/// `Config::validate`, anything unrecognized is kept as literal text.
+#[derive(Debug)]
+struct TopicTemplate {
+    segments: Vec<TopicSegment>,
+}
+
+impl TopicTemplate {
+    fn parse(template: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let segment = match &rest[start + 1..start + len] {
+                "rack_id" => TopicSegment::RackId,
+                "endpoint" => TopicSegment::Endpoint,
+                "kind" => TopicSegment::Kind,
+                "collector" => TopicSegment::Collector,
+                _ => {
+                    literal.push_str(&rest[..start + len + 1]);
+                    rest = &rest[start + len + 1..];
+                    continue;
+                }
+            };
+            literal.push_str(&rest[..start]);
+            if !literal.is_empty() {
+                segments.push(TopicSegment::Literal(std::mem::take(&mut literal)));
+            }
+            segments.push(segment);
+            rest = &rest[start + len + 1..];
+        }
+
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            segments.push(TopicSegment::Literal(literal));
+        }
+
+        Self { segments }
+    }
+
+    fn render(&self, context: &EventContext, kind: MqttEventKind) -> String {
+        let mut topic = String::new();
+        for segment in &self.segments {
+            let value = match segment {
+                TopicSegment::Literal(literal) => {
+                    topic.push_str(literal);
+                    continue;
+                }
+                TopicSegment::RackId => context.rack_id().map_or(UNKNOWN_RACK, RackId::as_str),
+                TopicSegment::Endpoint => context.endpoint_key(),
+                TopicSegment::Kind => kind.as_str(),
+                TopicSegment::Collector => context.collector_type,
+            };
+            // Values must stay within a single topic level and never act as wildcards.
+            topic.extend(value.chars().map(|c| match c {
+                '/' | '+' | '#' => '_',
+                c => c,
+            }));
+        }
+        topic
+    }
+}
+
+#[derive(Serialize)]
+struct MqttEvent<'a> {
+    kind: &'static str,
+    endpoint: &'a str,
+    collector: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rack_id: Option<&'a RackId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    machine_id: Option<MachineId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    serial_number: Option<&'a str>,
+    timestamp: DateTime<Utc>,
+    data: MqttEventData<'a>,
+}
+
+impl<'a> MqttEvent<'a> {
+    fn new(context: &'a EventContext, kind: MqttEventKind, data: MqttEventData<'a>) -> Self {
+        Self {
+            kind: kind.as_str(),
+            endpoint: context.endpoint_key(),
+            collector: context.collector_type,
+            rack_id: context.rack_id(),
+            machine_id: context.machine_id(),
+            serial_number: context.serial_number(),
+            timestamp: Utc::now(),
+            data,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum MqttEventData<'a> {
+    Metric {
+        name: &'a str,
+        metric_type: &'a str,
+        unit: &'a str,
+        value: f64,
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        labels: BTreeMap<&'a str, &'a str>,
+    },
+    Firmware {
+        component: &'a str,
+        version: &'a str,
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        attributes: BTreeMap<&'a str, &'a str>,
+    },
+    Log {
+        severity: &'a str,
+        body: &'a str,
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        attributes: BTreeMap<&'a str, &'a str>,
+    },
+    HealthReport(CarbideHealthReport),
+}
+
+impl<'a> MqttEventData<'a> {
+    fn from_event(event: &'a CollectorEvent) -> Option<Self> {
+        let data = match event {
+            CollectorEvent::Metric(metric) => Self::Metric {
+                name: &metric.name,
+                metric_type: &metric.metric_type,
+                unit: &metric.unit,
+                value: metric.value,
+                labels: metric
+                    .labels
+                    .iter()
+                    .map(|(k, v)| (k.as_ref(), v.as_str()))
+                    .collect(),
+            },
+            CollectorEvent::Firmware(info) => Self::Firmware {
+                component: &info.component,
+                version: &info.version,
+                attributes: info
+                    .attributes
+                    .iter()
+                    .map(|(k, v)| (k.as_ref(), v.as_str()))
+                    .collect(),
+            },
+            CollectorEvent::Log(record) => Self::Log {
+                severity: &record.severity,
+                body: &record.body,
+                attributes: record
+                    .attributes
+                    .iter()
+                    .map(|(k, v)| (k.as_ref(), v.as_str()))
+                    .collect(),
+            },
+            CollectorEvent::HealthReport(report) => {
+                match CarbideHealthReport::try_from(report.as_ref()) {
+                    Ok(report) => Self::HealthReport(report),
+                    Err(e) => {
+                        tracing::error!(error = ?e, "failed to convert health report for mqtt");
+                        return None;
+                    }
+                }
+            }
+            _ => return None,
+        };
+        Some(data)
+    }
+}
+
+struct MqttPublisher {
+    broker_host: String,
+    broker_port: u16,
+    client_id: String,
+    qos: MqttQos,
+    retain: bool,
+    credentials: Option<ClientCredentials>,
+    batch_size: usize,
+    flush_interval: Duration,
+    limiter: Arc<dyn RateLimiter>,
+    publish_failures_total: Counter,
+}
+
+impl MqttPublisher {
+    async fn run(self, mut receiver: mpsc::Receiver<MqttMessage>) {
+        let client = self.connect().await;
+
+        let mut pending: HashMap<String, Vec<Vec<u8>>> = HashMap::new();
+        let mut interval = tokio::time::interval(self.flush_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                message = receiver.recv() => {
+                    let Some(message) = message else {
+                        break;
+                    };
+                    let batch = pending.entry(message.topic.clone()).or_default();
+                    batch.push(message.payload);
+                    if batch.len() >= self.batch_size
+                        && let Some(batch) = pending.remove(&message.topic)
+                    {
+                        self.publish(&client, &message.topic, batch).await;
+                    }
+                }
+                _ = interval.tick() => {
+                    for (topic, batch) in pending.drain() {
+                        self.publish(&client, &topic, batch).await;
+                    }
+                }
+            }
+        }
+
+        for (topic, batch) in pending.drain() {
+            self.publish(&client, &topic, batch).await;
+        }
+    }
+
+    async fn connect(&self) -> Arc<MqtteaClient> {
+        let mut backoff = ExponentialBackoff::new(&BackoffConfig {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+        });
+
+        loop {
+            match self.try_connect().await {
+                Ok(client) => {
+                    tracing::info!(
+                        broker_host = %self.broker_host,
+                        broker_port = self.broker_port,
+                        "mqtt sink connected"
+                    );
+                    return client;
+                }
+                Err(e) => {
+                    let delay = backoff.next_delay();
+                    tracing::warn!(error = ?e, ?delay, "failed to start mqtt sink client");
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    async fn try_connect(&self) -> Result<Arc<MqtteaClient>, mqttea::MqtteaClientError> {
+        let qos = match self.qos {
+            MqttQos::AtMostOnce => QoS::AtMostOnce,
+            MqttQos::AtLeastOnce => QoS::AtLeastOnce,
+            MqttQos::ExactlyOnce => QoS::ExactlyOnce,
+        };
+        let mut options = ClientOptions::default()
+            .with_qos(qos)
+            .with_retain(self.retain);
+        if let Some(credentials) = &self.credentials {
+            options = options.with_credentials(credentials.clone());
+        }
+
+        let client = MqtteaClient::new(
+            &self.broker_host,
+            self.broker_port,
+            &self.client_id,
+            Some(options),
+        )
+        .await?;
+        client.connect().await?;
+        Ok(client)
+    }
+
+    async fn publish(&self, client: &MqtteaClient, topic: &str, batch: Vec<Vec<u8>>) {
+        self.limiter.acquire().await;
+
+        if let Err(e) = client.publish(topic, encode_batch(&batch)).await {
+            self.publish_failures_total.inc();
+            tracing::warn!(error = ?e, topic, events = batch.len(), "failed to publish mqtt batch");
+        }
+    }
+}
+
+/// Joins already serialized events into a single JSON array.
+fn encode_batch(batch: &[Vec<u8>]) -> Vec<u8> {
+    let len = batch.iter().map(|event| event.len() + 1).sum::<usize>() + 1;
+    let mut payload = Vec::with_capacity(len);
+    payload.push(b'[');
+    for (i, event) in batch.iter().enumerate() {
+        if i > 0 {
+            payload.push(b',');
+        }
+        payload.extend_from_slice(event);
+    }
+    payload.push(b']');
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::str::FromStr;
+
+    use mac_address::MacAddress;
+
+    use super::*;
+    use crate::endpoint::BmcAddr;
+    use crate::sink::{FirmwareInfo, LogRecord, SensorHealthData};
+
+    fn test_context(rack_id: Option<RackId>) -> EventContext {
+        EventContext {
+            endpoint_key: "42:9e:b1:bd:9d:dd".to_string(),
+            addr: BmcAddr {
+                ip: "10.0.0.1".parse().unwrap(),
+                port: Some(443),
+                mac: MacAddress::from_str("42:9e:b1:bd:9d:dd").unwrap(),
+            },
+            collector_type: "sensor_collector",
+            metadata: None,
+            rack_id,
+        }
+    }
+
+    fn test_sink(
+        event_kinds: Vec<MqttEventKind>,
+        capacity: usize,
+    ) -> (MqttSink, mpsc::Receiver<MqttMessage>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let sink = MqttSink {
+            sender,
+            topic: TopicTemplate::parse(&MqttSinkConfig::default().topic_template),
+            event_kinds,
+            dropped_total: Counter::new("test_dropped", "test").unwrap(),
+        };
+        (sink, receiver)
+    }
+
+    fn metric_event() -> CollectorEvent {
+        CollectorEvent::Metric(Box::new(SensorHealthData {
+            key: "k".to_string(),
+            name: "hw_sensor".to_string(),
+            metric_type: "temperature".to_string(),
+            unit: "celsius".to_string(),
+            value: 42.0,
+            labels: vec![(Cow::Borrowed("sensor"), "temp1".to_string())],
+            context: None,
+        }))
+    }
+
+    fn log_event() -> CollectorEvent {
+        CollectorEvent::Log(Box::new(LogRecord {
+            body: "fan failure".to_string(),
+            severity: "Critical".to_string(),
+            attributes: Vec::new(),
+        }))
+    }
+
+    #[test]
+    fn topic_template_renders_placeholders() {
+        let template = TopicTemplate::parse("health/{rack_id}/{endpoint}/{kind}/{collector}");
+        let context = test_context(Some(RackId::new("rack/1+a#")));
+
+        assert_eq!(
+            template.render(&context, MqttEventKind::HealthReport),
+            "health/rack_1_a_/42:9e:b1:bd:9d:dd/health_report/sensor_collector"
+        );
+        assert_eq!(
+            template.render(&test_context(None), MqttEventKind::Log),
+            "health/unknown/42:9e:b1:bd:9d:dd/log/sensor_collector"
+        );
+    }
+
+    #[test]
+    fn topic_template_keeps_unknown_placeholders() {
+        let template = TopicTemplate::parse("{endpoint}/{other}/x");
+        assert_eq!(
+            template.segments,
+            vec![
+                TopicSegment::Endpoint,
+                TopicSegment::Literal("/{other}/x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn metric_event_is_serialized_with_context() {
+        let (sink, mut receiver) = test_sink(vec![MqttEventKind::Metric], 8);
+        sink.handle_event(&test_context(Some(RackId::new("rack-1"))), &metric_event());
+
+        let message = receiver.try_recv().expect("metric should be queued");
+        assert_eq!(message.topic, "health/rack-1/42:9e:b1:bd:9d:dd/metric");
+
+        let json: serde_json::Value = serde_json::from_slice(&message.payload).unwrap();
+        assert_eq!(json["kind"], "metric");
+        assert_eq!(json["endpoint"], "42:9e:b1:bd:9d:dd");
+        assert_eq!(json["collector"], "sensor_collector");
+        assert_eq!(json["rack_id"], "rack-1");
+        assert_eq!(json["data"]["name"], "hw_sensor");
+        assert_eq!(json["data"]["value"], 42.0);
+        assert_eq!(json["data"]["labels"]["sensor"], "temp1");
+        assert!(json.get("machine_id").is_none());
+    }
+
+    #[test]
+    fn events_outside_configured_kinds_are_skipped() {
+        let (sink, mut receiver) = test_sink(vec![MqttEventKind::Log, MqttEventKind::Firmware], 8);
+        let context = test_context(None);
+
+        sink.handle_event(&context, &metric_event());
+        sink.handle_event(&context, &CollectorEvent::MetricCollectionEnd);
+        assert!(receiver.try_recv().is_err());
+
+        sink.handle_event(&context, &log_event());
+        sink.handle_event(
+            &context,
+            &CollectorEvent::Firmware(FirmwareInfo {
+                component: "BMC".to_string(),
+                version: "1.2.3".to_string(),
+                attributes: Vec::new(),
+            }),
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap().topic,
+            "health/unknown/42:9e:b1:bd:9d:dd/log"
+        );
+        let firmware = receiver.try_recv().unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&firmware.payload).unwrap();
+        assert_eq!(json["data"]["version"], "1.2.3");
+    }
+
+    #[test]
+    fn full_queue_drops_events() {
+        let (sink, _receiver) = test_sink(vec![MqttEventKind::Log], 1);
+        let context = test_context(None);
+
+        sink.handle_event(&context, &log_event());
+        sink.handle_event(&context, &log_event());
+
+        assert_eq!(sink.dropped_total.get(), 1.0);
+    }
+
+    #[test]
+    fn batch_is_encoded_as_json_array() {
+        let batch = vec![br#"{"a":1}"#.to_vec(), br#"{"b":2}"#.to_vec()];
+        let json: serde_json::Value = serde_json::from_slice(&encode_batch(&batch)).unwrap();
+        assert_eq!(json, serde_json::json!([{"a": 1}, {"b": 2}]));
+    }
+}
@@ -20,10 +20,11 @@ use std::sync::Arc;
 use prometheus::Counter;
 
 use super::dedup_queue::DedupQueue;
+use super::disk_buffer::DiskBuffer;
 use super::event_mapper::RedfishEventMapper;
 use super::{CollectorEvent, DataSink, EventContext};
 use crate::HealthError;
-use crate::config::OtlpSinkConfig;
+use crate::config::{Configurable, OtlpSinkConfig};
 use crate::metrics::MetricsManager;
 use crate::otlp::drain::OtlpDrainTask;
 
@@ -74,11 +75,21 @@ impl OtlpSink {
             .global_registry()
             .register(Box::new(replaced_total.clone()))?;
 
+        let buffer = match &config.disk_buffer {
+            Configurable::Enabled(buffer_config) => Some(Arc::new(DiskBuffer::open(
+                buffer_config,
+                metrics_manager.global_registry(),
+                &format!("{prefix}_otlp_sink"),
+            )?)),
+            Configurable::Disabled => None,
+        };
+
         let drain = OtlpDrainTask::new(
             queue.clone(),
             config.endpoint.clone(),
             config.batch_size,
             config.flush_interval,
+            buffer,
         );
         handle.spawn(drain.run());
 
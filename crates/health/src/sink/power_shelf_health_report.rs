@@ -20,12 +20,14 @@ use std::sync::Arc;
 use carbide_uuid::power_shelf::PowerShelfId;
 
 use super::dedup_queue::DedupQueue;
+use super::disk_buffer::HealthReportSubmitter;
 use super::{
     CollectorEvent, DataSink, EventContext, HealthReport, HealthReportTarget, ReportSource,
 };
 use crate::HealthError;
 use crate::api_client::ApiClientWrapper;
 use crate::config::PowerShelfHealthReportSinkConfig;
+use crate::metrics::MetricsManager;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct PowerShelfHealthReportKey {
@@ -38,7 +40,11 @@ pub struct PowerShelfHealthReportSink {
 }
 
 impl PowerShelfHealthReportSink {
-    pub fn new(config: &PowerShelfHealthReportSinkConfig) -> Result<Self, HealthError> {
+    pub fn new(
+        config: &PowerShelfHealthReportSinkConfig,
+        metrics_manager: &MetricsManager,
+        prefix: &str,
+    ) -> Result<Self, HealthError> {
         let handle = tokio::runtime::Handle::try_current().map_err(|error| {
             HealthError::GenericError(format!(
                 "power shelf health report sink requires active Tokio runtime: {error}"
@@ -52,22 +58,30 @@ impl PowerShelfHealthReportSink {
             &config.connection.api_url,
         ));
 
+        let submitter = Arc::new(HealthReportSubmitter::new(
+            &config.disk_buffer,
+            &handle,
+            metrics_manager.global_registry(),
+            &format!("{prefix}_power_shelf_health_report_sink"),
+            move |id: PowerShelfId, report| {
+                let client = Arc::clone(&client);
+                async move { client.submit_power_shelf_health_report(&id, report).await }
+            },
+        )?);
+
         let queue: Arc<DedupQueue<PowerShelfHealthReportKey, Arc<HealthReport>>> =
             Arc::new(DedupQueue::new());
 
         for worker_id in 0..config.workers {
-            let worker_client = Arc::clone(&client);
+            let worker_submitter = Arc::clone(&submitter);
             let worker_queue = Arc::clone(&queue);
             handle.spawn(async move {
                 loop {
                     let (key, report) = worker_queue.next().await;
 
-                    match report.as_ref().try_into() {
+                    match health_report::HealthReport::try_from(report.as_ref()) {
                         Ok(converted) => {
-                            if let Err(error) = worker_client
-                                .submit_power_shelf_health_report(&key.id, converted)
-                                .await
-                            {
+                            if let Err(error) = worker_submitter.submit(key.id, converted).await {
                                 tracing::warn!(
                                     ?error,
                                     worker_id,
@@ -20,6 +20,12 @@ use std::collections::HashMap;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
 
+mod lint;
+mod template;
+
+pub use lint::{LintIssue, LintSeverity, lint_template};
+use template::{EXTRA, Scope, Template};
+
 /// iPXE OS definition with template-based rendering support
 #[derive(Debug, Clone)]
 pub struct IpxeScript {
@@ -126,6 +132,14 @@ pub enum IpxeScriptError {
 
     #[error("Artifact '{0}' has cache_strategy CachedOnly but no cached_url is available")]
     CachedOnlyNotCached(String),
+
+    #[error("Invalid template: {0}")]
+    TemplateSyntax(String),
+
+    #[error(
+        "Value of {{{{{0}}}}} contains a line break or control character, use `| raw` to allow it"
+    )]
+    UnsafeValue(String),
 }
 
 pub type Result<T> = std::result::Result<T, IpxeScriptError>;
@@ -184,11 +198,8 @@ impl DefaultIpxeScriptRenderer {
         // Load templates from embedded YAML file at compile time
         const TEMPLATES_YAML: &str = include_str!("../templates.yaml");
 
-        let template_collection: TemplateCollection = serde_yaml::from_str(TEMPLATES_YAML)
-            .expect("Failed to parse templates.yaml - this is a compile-time error");
-
-        let templates = template_collection
-            .templates
+        let templates = parse_templates(TEMPLATES_YAML)
+            .expect("Failed to parse templates.yaml - this is a compile-time error")
             .into_iter()
             .map(|t| (t.name.clone(), t))
             .collect();
@@ -207,6 +218,11 @@ impl Default for DefaultIpxeScriptRenderer {
     }
 }
 
+/// Parse templates in the format of the embedded `templates.yaml`.
+pub fn parse_templates(yaml: &str) -> std::result::Result<Vec<IpxeTemplate>, serde_yaml::Error> {
+    serde_yaml::from_str::<TemplateCollection>(yaml).map(|c| c.templates)
+}
+
 /// Resolve the effective URL for an artifact respecting cache_strategy:
 /// - RemoteOnly: always use the remote url, ignore cached_url
 /// - LocalOnly: use url directly (it is already site-local, no caching applicable)
@@ -304,40 +320,55 @@ impl IpxeScriptRenderer for DefaultIpxeScriptRenderer {
             }
         }
 
-        // Step 4: Replace placeholders in template (lowercase keys)
-        let mut result = template.template.clone();
-        let mut processed_names: std::collections::HashSet<String> =
-            std::collections::HashSet::new();
-
-        for (param_name_lower, values) in &consumption_map {
-            if processed_names.contains(param_name_lower) {
+        // Step 4: Parameters and artifacts the template uses without requiring them, such as
+        // optional console arguments behind an {% if %}, get all their values
+        let parsed = Template::parse(&template.template)?;
+        for reference in parsed.references() {
+            let name = reference.name;
+            if name == EXTRA || consumption_map.contains_key(&name) {
                 continue;
             }
-            processed_names.insert(param_name_lower.clone());
-
-            // Placeholder should be lowercase
-            let placeholder = format!("{{{{{}}}}}", param_name_lower);
-            for value in values {
-                if let Some(pos) = result.find(&placeholder) {
-                    result.replace_range(pos..pos + placeholder.len(), value);
+            let mut values = Vec::new();
+            for (idx, param) in ipxeos.parameters.iter().enumerate() {
+                if param.name.to_lowercase() == name
+                    && !consumed_param_indices.contains(&idx)
+                    && !param.value.is_empty()
+                {
+                    values.push(param.value.clone());
+                    consumed_param_indices.insert(idx);
+                }
+            }
+            if values.is_empty() {
+                for (idx, artifact) in ipxeos.artifacts.iter().enumerate() {
+                    if artifact.name.to_lowercase() == name
+                        && !consumed_artifact_indices.contains(&idx)
+                    {
+                        values.push(resolve_artifact_url(artifact)?);
+                        consumed_artifact_indices.insert(idx);
+                    }
                 }
             }
+            if !values.is_empty() {
+                consumption_map.insert(name, values);
+            }
         }
 
-        // Step 5: Handle {{extra}} placeholder for unconsumed parameters
+        // Step 5: {{extra}} gets the unconsumed parameters
         // PRESERVE ORIGINAL CASE for parameter names in {{extra}}
-        if result.contains("{{extra}}") {
-            let mut extra_params: Vec<String> = Vec::new();
-
-            // Collect all unconsumed parameters (not artifacts for extra, preserve original case)
-            for (idx, param) in ipxeos.parameters.iter().enumerate() {
-                if !consumed_param_indices.contains(&idx) && !param.value.is_empty() {
-                    extra_params.push(format!("{}={}", param.name, param.value)); // Original case preserved
-                }
-            }
+        let extra_params: Vec<String> = ipxeos
+            .parameters
+            .iter()
+            .enumerate()
+            .filter(|(idx, param)| !consumed_param_indices.contains(idx) && !param.value.is_empty())
+            .map(|(_, param)| format!("{}={}", param.name, param.value)) // Original case preserved
+            .collect();
 
-            result = result.replace("{{extra}}", &extra_params.join(" "));
-        }
+        // Step 6: Evaluate the template, placeholders are replaced in order of occurrence
+        let mut result = parsed.render(&Scope {
+            values: &consumption_map,
+            extra: &extra_params.join(" "),
+            artifacts: &ipxeos.artifacts,
+        })?;
 
         // Post-processing: replace multiple spaces with single space
         while result.contains("  ") {
@@ -471,12 +502,19 @@ impl IpxeScriptRenderer for DefaultIpxeScriptRenderer {
             .map(|s| s.to_lowercase())
             .collect();
 
+        // Parameters the template refers to without requiring them are optional, not extra
+        let parsed = Template::parse(&template.template)?;
+        let used_params_lower: std::collections::HashSet<String> = used_params_lower
+            .into_iter()
+            .chain(parsed.references().into_iter().map(|r| r.name))
+            .collect();
+
         let has_extra_params = ipxeos
             .parameters
             .iter()
             .any(|p| !used_params_lower.contains(&p.name.to_lowercase()));
 
-        if has_extra_params && !template.template.contains("{{extra}}") {
+        if has_extra_params && !parsed.uses(EXTRA) {
             return Err(IpxeScriptError::ExtraParametersNotSupported);
         }
 
@@ -2178,4 +2216,148 @@ mod tests {
             result
         );
     }
+
+    fn remote_artifact(name: &str, url: &str) -> IpxeTemplateArtifact {
+        IpxeTemplateArtifact {
+            name: name.to_string(),
+            url: url.to_string(),
+            sha: None,
+            auth_type: None,
+            auth_token: None,
+            cache_strategy: IpxeTemplateArtifactCacheStrategy::RemoteOnly,
+            cached_url: None,
+        }
+    }
+
+    fn base_reserved_params() -> Vec<IpxeTemplateParameter> {
+        vec![
+            IpxeTemplateParameter {
+                name: "base_url".to_string(),
+                value: "http://pxe.local".to_string(),
+            },
+            IpxeTemplateParameter {
+                name: "console".to_string(),
+                value: "ttyS0,115200".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_kernel_initrd_with_multiple_initrds() {
+        let renderer = DefaultIpxeScriptRenderer::new();
+        let mut ipxeos = IpxeScript {
+            name: "Multiple initrds".to_string(),
+            description: None,
+            hash: String::new(),
+            tenant_id: None,
+            ipxe_template_id: "c4b1d4f6-69ba-5f55-90cd-ab2acd002475".to_string(),
+            parameters: vec![IpxeTemplateParameter {
+                name: "kernel_params".to_string(),
+                value: "quiet".to_string(),
+            }],
+            artifacts: vec![
+                remote_artifact("kernel", "http://a/vmlinuz"),
+                remote_artifact("initrd", "http://a/microcode.img"),
+                remote_artifact("initrd", "http://a/initrd.img"),
+            ],
+        };
+        ipxeos.hash = renderer.hash(&ipxeos);
+
+        let script = renderer.render(&ipxeos, &base_reserved_params()).unwrap();
+        assert!(script.ends_with(
+            "kernel http://a/vmlinuz quiet\n\
+             initrd http://a/microcode.img\n\
+             initrd http://a/initrd.img\n\
+             boot"
+        ));
+    }
+
+    #[test]
+    fn test_render_raw_ipxe_allows_multiple_lines() {
+        let renderer = DefaultIpxeScriptRenderer::new();
+        let mut ipxeos = IpxeScript {
+            name: "Raw".to_string(),
+            description: None,
+            hash: String::new(),
+            tenant_id: None,
+            ipxe_template_id: "ddbf83c0-a753-5fde-96c1-6b74e9c9db10".to_string(),
+            parameters: vec![IpxeTemplateParameter {
+                name: "ipxe".to_string(),
+                value: "kernel http://a/vmlinuz\nboot".to_string(),
+            }],
+            artifacts: vec![],
+        };
+        ipxeos.hash = renderer.hash(&ipxeos);
+
+        let script = renderer.render(&ipxeos, &base_reserved_params()).unwrap();
+        assert!(script.ends_with("kernel http://a/vmlinuz\nboot"));
+    }
+
+    #[test]
+    fn test_render_optional_parameters() {
+        let template = IpxeTemplate {
+            id: "00000000-0000-0000-0000-000000000001".to_string(),
+            name: "optional".to_string(),
+            description: String::new(),
+            template: "#!ipxe\n\
+                       {% if arch == \"aarch64\" %}\n\
+                       kernel {{kernel}} console=ttyAMA0\n\
+                       {% else %}\n\
+                       kernel {{kernel}}{% if serial %} console={{serial}}{% endif %}\n\
+                       {% endif %}\n\
+                       boot"
+                .to_string(),
+            reserved_params: vec!["arch".to_string()],
+            required_params: vec![],
+            required_artifacts: vec!["kernel".to_string()],
+            scope: IpxeTemplateScope::Public,
+        };
+        let renderer = DefaultIpxeScriptRenderer::with_templates(HashMap::from([(
+            template.name.clone(),
+            template.clone(),
+        )]));
+        let arch = |value: &str| {
+            vec![IpxeTemplateParameter {
+                name: "arch".to_string(),
+                value: value.to_string(),
+            }]
+        };
+
+        let mut ipxeos = IpxeScript {
+            name: "Optional".to_string(),
+            description: None,
+            hash: String::new(),
+            tenant_id: None,
+            ipxe_template_id: template.id.clone(),
+            parameters: vec![],
+            artifacts: vec![remote_artifact("kernel", "http://a/vmlinuz")],
+        };
+        ipxeos.hash = renderer.hash(&ipxeos);
+        assert_eq!(
+            renderer.render(&ipxeos, &arch("x86_64")).unwrap(),
+            "#!ipxe\nkernel http://a/vmlinuz\nboot"
+        );
+        assert_eq!(
+            renderer.render(&ipxeos, &arch("aarch64")).unwrap(),
+            "#!ipxe\nkernel http://a/vmlinuz console=ttyAMA0\nboot"
+        );
+
+        // Used by the template, so not an extra parameter even though there's no {{extra}}
+        ipxeos.parameters.push(IpxeTemplateParameter {
+            name: "serial".to_string(),
+            value: "ttyS1".to_string(),
+        });
+        ipxeos.hash = renderer.hash(&ipxeos);
+        assert_eq!(
+            renderer.render(&ipxeos, &arch("x86_64")).unwrap(),
+            "#!ipxe\nkernel http://a/vmlinuz console=ttyS1\nboot"
+        );
+
+        ipxeos.parameters[0].value = "ttyS1\nshell".to_string();
+        ipxeos.hash = renderer.hash(&ipxeos);
+        assert!(matches!(
+            renderer.render(&ipxeos, &arch("x86_64")),
+            Err(IpxeScriptError::UnsafeValue(_))
+        ));
+    }
 }
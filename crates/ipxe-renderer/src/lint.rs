@@ -0,0 +1,191 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Static checks for iPXE script templates, run before a template is shipped.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::IpxeTemplate;
+use crate::template::{EXTRA, Template};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// The template can't be rendered.
+    Error,
+    /// The template renders, but likely not as intended.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintIssue {
+    pub severity: LintSeverity,
+    pub message: String,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintSeverity::Error => write!(f, "error"),
+            LintSeverity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl LintIssue {
+    fn error(message: String) -> Self {
+        Self {
+            severity: LintSeverity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            severity: LintSeverity::Warning,
+            message,
+        }
+    }
+}
+
+/// Check that a template parses and that its declared parameters and artifacts match
+/// what it refers to.
+pub fn lint_template(template: &IpxeTemplate) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    if !template.template.starts_with("#!ipxe") {
+        issues.push(LintIssue::warning(
+            "template doesn't start with `#!ipxe`".to_string(),
+        ));
+    }
+
+    let lower = |names: &[String]| -> HashSet<String> {
+        names.iter().map(|name| name.to_lowercase()).collect()
+    };
+    let required = lower(&template.required_params);
+    let reserved = lower(&template.reserved_params);
+    let artifacts = lower(&template.required_artifacts);
+
+    for name in required.intersection(&reserved) {
+        issues.push(LintIssue::error(format!(
+            "`{name}` is both a required and a reserved parameter"
+        )));
+    }
+    for name in required.iter().chain(&reserved).chain(&artifacts) {
+        if name == EXTRA {
+            issues.push(LintIssue::error(format!(
+                "`{EXTRA}` is reserved for the unconsumed parameters and can't be declared"
+            )));
+        }
+    }
+
+    let parsed = match Template::parse(&template.template) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            issues.push(LintIssue::error(err.to_string()));
+            return issues;
+        }
+    };
+    let references = parsed.references();
+
+    // Reserved parameters are always provided, only unused requirements burden OS definitions
+    for (names, kind) in [
+        (&template.required_params, "required parameter"),
+        (&template.required_artifacts, "required artifact"),
+    ] {
+        for name in names {
+            if !parsed.uses(&name.to_lowercase()) {
+                issues.push(LintIssue::warning(format!("{kind} `{name}` is never used")));
+            }
+        }
+    }
+
+    let mut reported = HashSet::new();
+    for reference in references {
+        let declared = reference.name == EXTRA
+            || required.contains(&reference.name)
+            || reserved.contains(&reference.name)
+            || artifacts.contains(&reference.name);
+        if !declared && !reference.optional && reported.insert(reference.name.clone()) {
+            issues.push(LintIssue::warning(format!(
+                "line {}: `{}` isn't declared and has no default or `{{% if {} %}}` guard, \
+                 rendering fails unless the OS definition provides it",
+                reference.line, reference.name, reference.name
+            )));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DefaultIpxeScriptRenderer, IpxeScriptRenderer, IpxeTemplateScope};
+
+    fn template(text: &str, required_params: &[&str], reserved_params: &[&str]) -> IpxeTemplate {
+        IpxeTemplate {
+            id: "00000000-0000-0000-0000-000000000000".to_string(),
+            name: "test".to_string(),
+            description: String::new(),
+            template: text.to_string(),
+            reserved_params: reserved_params.iter().map(|s| s.to_string()).collect(),
+            required_params: required_params.iter().map(|s| s.to_string()).collect(),
+            required_artifacts: vec![],
+            scope: IpxeTemplateScope::Public,
+        }
+    }
+
+    #[test]
+    fn test_embedded_templates_are_clean() {
+        let renderer = DefaultIpxeScriptRenderer::new();
+        for name in renderer.list_templates() {
+            let template = renderer.get_template_by_name(&name).unwrap();
+            assert_eq!(lint_template(template), vec![], "template {name}");
+        }
+    }
+
+    #[test]
+    fn test_lint_findings() {
+        let issues = lint_template(&template(
+            "#!ipxe\n{% if console %}console={{console}}{% endif %} {{ image_url }}\n{{ unknown }}",
+            &["image_url", "Unused"],
+            &["image_url"],
+        ));
+        let messages: Vec<String> = issues
+            .iter()
+            .map(|issue| format!("{}: {}", issue.severity, issue.message))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "error: `image_url` is both a required and a reserved parameter",
+                "warning: required parameter `Unused` is never used",
+                "warning: line 3: `unknown` isn't declared and has no default or \
+                 `{% if unknown %}` guard, rendering fails unless the OS definition provides it",
+            ]
+        );
+
+        let issues = lint_template(&template("kernel {% if x %}", &[], &[]));
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[1].severity, LintSeverity::Error);
+        assert_eq!(issues[1].message, "line 1: missing `{% endif %}`");
+    }
+}
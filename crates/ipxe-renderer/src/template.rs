@@ -0,0 +1,945 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Sandboxed templating language for iPXE script templates.
+//!
+//! On top of plain `{{name}}` substitution templates support:
+//! - filters: `{{ console | default("ttyS0,115200") }}` and `{{ ipxe | raw }}`
+//! - conditionals: `{% if arch == "aarch64" %}`, `{% elif not console %}`, `{% else %}`,
+//!   `{% endif %}`
+//! - loops over the artifacts of the OS definition, all of them or those with a given name:
+//!   `{% for initrd in artifacts.initrd %}initrd {{ initrd.url }}{% endfor %}`
+//!
+//! Values are never parsed as templates themselves, there are no includes and loops only
+//! iterate over artifacts, so rendering always terminates. A value containing a line break
+//! or another control character is rejected unless marked `raw`, since it could start a new
+//! iPXE command. Block tags on a line of their own don't leave an empty line behind.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{IpxeScriptError, IpxeTemplateArtifact, Result, resolve_artifact_url};
+
+/// Reserved for the unconsumed parameters of the OS definition.
+pub(crate) const EXTRA: &str = "extra";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Expr(Expr),
+    If {
+        branches: Vec<Branch>,
+        otherwise: Vec<Node>,
+    },
+    For {
+        var: String,
+        /// Only artifacts with this name, all of them if None.
+        artifact_name: Option<String>,
+        line: usize,
+        body: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Branch {
+    condition: Condition,
+    line: usize,
+    body: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Expr {
+    path: VarPath,
+    default: Option<String>,
+    raw: bool,
+    line: usize,
+    /// What was written between the braces, kept to report unreplaced placeholders.
+    source: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum VarPath {
+    /// Parameter or artifact, lowercase.
+    Name(String),
+    /// Field of a loop variable.
+    Field(String, ArtifactField),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArtifactField {
+    Name,
+    Url,
+    Sha,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Defined(VarPath),
+    Not(Box<Condition>),
+    Eq(VarPath, String),
+    Ne(VarPath, String),
+}
+
+type ParseResult<T> = std::result::Result<T, TemplateError>;
+
+/// A block closing tag and its line.
+type Terminator<'a> = (&'a str, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TemplateError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl From<TemplateError> for IpxeScriptError {
+    fn from(err: TemplateError) -> Self {
+        IpxeScriptError::TemplateSyntax(err.to_string())
+    }
+}
+
+/// A parameter or artifact the template refers to by name.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Reference {
+    pub name: String,
+    pub line: usize,
+    /// Rendering doesn't fail if nothing is provided for the name: it's only used in
+    /// conditions, behind an `{% if name %}` or with a default.
+    pub optional: bool,
+}
+
+/// What a template is rendered with.
+pub(crate) struct Scope<'a> {
+    /// Values by lowercase name, substituted in order of occurrence.
+    pub values: &'a HashMap<String, Vec<String>>,
+    /// Substituted for every `{{extra}}`.
+    pub extra: &'a str,
+    pub artifacts: &'a [IpxeTemplateArtifact],
+}
+
+impl Template {
+    pub(crate) fn parse(src: &str) -> ParseResult<Self> {
+        let tokens = tokenize(src)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            loop_vars: Vec::new(),
+        };
+        let (nodes, end) = parser.parse_nodes(&[], 1)?;
+        debug_assert!(end.is_none());
+        Ok(Self { nodes })
+    }
+
+    pub(crate) fn render(&self, scope: &Scope) -> Result<String> {
+        let mut renderer = Renderer {
+            scope,
+            cursors: HashMap::new(),
+            loop_vars: Vec::new(),
+            out: String::new(),
+        };
+        renderer.render_nodes(&self.nodes)?;
+        Ok(renderer.out)
+    }
+
+    /// Every parameter or artifact name the template refers to, including the names
+    /// artifacts are looped over by.
+    pub(crate) fn references(&self) -> Vec<Reference> {
+        let mut references = Vec::new();
+        collect_references(&self.nodes, &mut Vec::new(), &mut references);
+        references
+    }
+
+    pub(crate) fn uses(&self, name: &str) -> bool {
+        self.references().iter().any(|r| r.name == name)
+    }
+}
+
+fn collect_references(nodes: &[Node], guards: &mut Vec<String>, out: &mut Vec<Reference>) {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Expr(expr) => {
+                if let VarPath::Name(name) = &expr.path {
+                    out.push(Reference {
+                        name: name.clone(),
+                        line: expr.line,
+                        optional: expr.default.is_some() || guards.contains(name),
+                    });
+                }
+            }
+            Node::If {
+                branches,
+                otherwise,
+            } => {
+                for branch in branches {
+                    for name in branch.condition.names() {
+                        out.push(Reference {
+                            name: name.to_string(),
+                            line: branch.line,
+                            optional: true,
+                        });
+                    }
+                    let guarded = branch.condition.guarded_name();
+                    if let Some(name) = guarded {
+                        guards.push(name.to_string());
+                    }
+                    collect_references(&branch.body, guards, out);
+                    if guarded.is_some() {
+                        guards.pop();
+                    }
+                }
+                collect_references(otherwise, guards, out);
+            }
+            Node::For {
+                artifact_name,
+                line,
+                body,
+                ..
+            } => {
+                if let Some(name) = artifact_name {
+                    out.push(Reference {
+                        name: name.clone(),
+                        line: *line,
+                        optional: true,
+                    });
+                }
+                collect_references(body, guards, out);
+            }
+        }
+    }
+}
+
+impl Condition {
+    fn names(&self) -> Vec<&str> {
+        match self {
+            Condition::Defined(path) | Condition::Eq(path, _) | Condition::Ne(path, _) => {
+                match path {
+                    VarPath::Name(name) => vec![name.as_str()],
+                    VarPath::Field(..) => vec![],
+                }
+            }
+            Condition::Not(inner) => inner.names(),
+        }
+    }
+
+    /// The name which is known to have a value inside a branch taken on this condition.
+    fn guarded_name(&self) -> Option<&str> {
+        match self {
+            Condition::Defined(VarPath::Name(name)) | Condition::Eq(VarPath::Name(name), _) => {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Expr { content: &'a str, line: usize },
+    Tag { content: &'a str, line: usize },
+}
+
+fn tokenize(src: &str) -> ParseResult<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while let Some((start, is_tag)) = next_delimiter(src, pos) {
+        let line = line_of(src, start);
+        let close = if is_tag { "%}" } else { "}}" };
+        let content_start = start + 2;
+        let content_end = src[content_start..]
+            .find(close)
+            .map(|i| content_start + i)
+            .ok_or_else(|| TemplateError {
+                line,
+                message: format!("unclosed `{}`", &src[start..content_start]),
+            })?;
+        let mut end = content_end + close.len();
+        let mut text_end = start;
+
+        if is_tag {
+            let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = src[end..].find('\n').map_or(src.len(), |i| end + i + 1);
+            if line_start >= pos
+                && src[line_start..start].trim().is_empty()
+                && src[end..line_end].trim().is_empty()
+            {
+                text_end = line_start;
+                end = line_end;
+            }
+        }
+
+        if text_end > pos {
+            tokens.push(Token::Text(&src[pos..text_end]));
+        }
+        let content = src[content_start..content_end].trim();
+        tokens.push(if is_tag {
+            Token::Tag { content, line }
+        } else {
+            Token::Expr { content, line }
+        });
+        pos = end;
+    }
+
+    if pos < src.len() {
+        tokens.push(Token::Text(&src[pos..]));
+    }
+    Ok(tokens)
+}
+
+fn next_delimiter(src: &str, from: usize) -> Option<(usize, bool)> {
+    let expr = src[from..].find("{{").map(|i| from + i);
+    let tag = src[from..].find("{%").map(|i| from + i);
+    match (expr, tag) {
+        (Some(e), Some(t)) if t < e => Some((t, true)),
+        (Some(e), _) => Some((e, false)),
+        (None, Some(t)) => Some((t, true)),
+        (None, None) => None,
+    }
+}
+
+fn line_of(src: &str, pos: usize) -> usize {
+    src[..pos].matches('\n').count() + 1
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+    loop_vars: Vec<String>,
+}
+
+impl<'a> Parser<'a> {
+    /// Parse until one of the `terminators` tags, which is returned along with its line.
+    /// `opened_at` is the line of the block being parsed.
+    fn parse_nodes(
+        &mut self,
+        terminators: &[&str],
+        opened_at: usize,
+    ) -> ParseResult<(Vec<Node>, Option<Terminator<'a>>)> {
+        let mut nodes = Vec::new();
+        while let Some(token) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            match *token {
+                Token::Text(text) => nodes.push(Node::Text(text.to_string())),
+                Token::Expr { content, line } => {
+                    nodes.push(Node::Expr(self.parse_expr(content, line)?))
+                }
+                Token::Tag { content, line } => {
+                    let keyword = content.split_whitespace().next().unwrap_or_default();
+                    if terminators.contains(&keyword) {
+                        return Ok((nodes, Some((content, line))));
+                    }
+                    nodes.push(match keyword {
+                        "if" => self.parse_if(content, line)?,
+                        "for" => self.parse_for(content, line)?,
+                        "elif" | "else" | "endif" | "endfor" => {
+                            return Err(TemplateError {
+                                line,
+                                message: format!("unexpected `{{% {keyword} %}}`"),
+                            });
+                        }
+                        _ => {
+                            return Err(TemplateError {
+                                line,
+                                message: format!("unknown tag `{{% {content} %}}`"),
+                            });
+                        }
+                    });
+                }
+            }
+        }
+
+        match terminators.last() {
+            Some(expected) => Err(TemplateError {
+                line: opened_at,
+                message: format!("missing `{{% {expected} %}}`"),
+            }),
+            None => Ok((nodes, None)),
+        }
+    }
+
+    fn parse_if(&mut self, content: &str, line: usize) -> ParseResult<Node> {
+        let mut branches = Vec::new();
+        let mut otherwise = Vec::new();
+        let mut condition = self.parse_condition(keyword_argument(content, "if"), line)?;
+        let mut condition_line = line;
+
+        loop {
+            let (body, end) = self.parse_nodes(&["elif", "else", "endif"], line)?;
+            branches.push(Branch {
+                condition,
+                line: condition_line,
+                body,
+            });
+            let (end, end_line) = end.expect("parse_nodes returns the terminator");
+            match end.split_whitespace().next() {
+                Some("elif") => {
+                    condition = self.parse_condition(keyword_argument(end, "elif"), end_line)?;
+                    condition_line = end_line;
+                }
+                Some("else") => {
+                    expect_bare(end, end_line)?;
+                    let (body, end) = self.parse_nodes(&["endif"], line)?;
+                    let (end, end_line) = end.expect("parse_nodes returns the terminator");
+                    expect_bare(end, end_line)?;
+                    otherwise = body;
+                    break;
+                }
+                _ => {
+                    expect_bare(end, end_line)?;
+                    break;
+                }
+            }
+        }
+
+        Ok(Node::If {
+            branches,
+            otherwise,
+        })
+    }
+
+    fn parse_for(&mut self, content: &str, line: usize) -> ParseResult<Node> {
+        let words: Vec<&str> = content.split_whitespace().collect();
+        let [_, var, "in", source] = words.as_slice() else {
+            return Err(TemplateError {
+                line,
+                message: format!(
+                    "expected `{{% for <name> in artifacts[.<artifact>] %}}`, got `{{% {content} %}}`"
+                ),
+            });
+        };
+        let var = parse_name(var, line)?;
+        let artifact_name = match source.split_once('.') {
+            None if *source == "artifacts" => None,
+            Some(("artifacts", name)) => Some(parse_name(name, line)?),
+            _ => {
+                return Err(TemplateError {
+                    line,
+                    message: format!("can only loop over `artifacts`, not `{source}`"),
+                });
+            }
+        };
+
+        self.loop_vars.push(var.clone());
+        let (body, end) = self.parse_nodes(&["endfor"], line)?;
+        self.loop_vars.pop();
+        let (end, end_line) = end.expect("parse_nodes returns the terminator");
+        expect_bare(end, end_line)?;
+
+        Ok(Node::For {
+            var,
+            artifact_name,
+            line,
+            body,
+        })
+    }
+
+    fn parse_expr(&self, content: &str, line: usize) -> ParseResult<Expr> {
+        let mut parts = split_filters(content).into_iter();
+        let path = self.parse_path(parts.next().unwrap_or_default(), line)?;
+        let mut default = None;
+        let mut raw = false;
+
+        for filter in parts {
+            if filter == "raw" {
+                raw = true;
+            } else if let Some(argument) = filter
+                .strip_prefix("default")
+                .map(str::trim_start)
+                .and_then(|f| f.strip_prefix('('))
+                .and_then(|f| f.strip_suffix(')'))
+            {
+                default = Some(parse_literal(argument, line)?);
+            } else {
+                return Err(TemplateError {
+                    line,
+                    message: format!("unknown filter `{filter}`"),
+                });
+            }
+        }
+
+        Ok(Expr {
+            path,
+            default,
+            raw,
+            line,
+            source: content.to_string(),
+        })
+    }
+
+    fn parse_condition(&self, content: &str, line: usize) -> ParseResult<Condition> {
+        if let Some(inner) = content.strip_prefix("not ") {
+            return Ok(Condition::Not(Box::new(
+                self.parse_condition(inner.trim(), line)?,
+            )));
+        }
+        if let Some((path, literal)) = content.split_once("==") {
+            return Ok(Condition::Eq(
+                self.parse_path(path.trim(), line)?,
+                parse_literal(literal, line)?,
+            ));
+        }
+        if let Some((path, literal)) = content.split_once("!=") {
+            return Ok(Condition::Ne(
+                self.parse_path(path.trim(), line)?,
+                parse_literal(literal, line)?,
+            ));
+        }
+        Ok(Condition::Defined(self.parse_path(content, line)?))
+    }
+
+    fn parse_path(&self, content: &str, line: usize) -> ParseResult<VarPath> {
+        match content.split_once('.') {
+            Some((var, field)) => {
+                let var = parse_name(var, line)?;
+                if !self.loop_vars.contains(&var) {
+                    return Err(TemplateError {
+                        line,
+                        message: format!("`{var}` is not a loop variable"),
+                    });
+                }
+                let field = match field {
+                    "name" => ArtifactField::Name,
+                    "url" => ArtifactField::Url,
+                    "sha" => ArtifactField::Sha,
+                    _ => {
+                        return Err(TemplateError {
+                            line,
+                            message: format!(
+                                "artifacts have a `name`, `url` and `sha`, not `{field}`"
+                            ),
+                        });
+                    }
+                };
+                Ok(VarPath::Field(var, field))
+            }
+            None => {
+                let name = parse_name(content, line)?;
+                if self.loop_vars.contains(&name) {
+                    return Err(TemplateError {
+                        line,
+                        message: format!("loop variable `{name}` needs a field, e.g. `{name}.url`"),
+                    });
+                }
+                Ok(VarPath::Name(name))
+            }
+        }
+    }
+}
+
+fn keyword_argument<'s>(content: &'s str, keyword: &str) -> &'s str {
+    content[keyword.len()..].trim()
+}
+
+fn expect_bare(content: &str, line: usize) -> ParseResult<()> {
+    if content.split_whitespace().count() > 1 {
+        return Err(TemplateError {
+            line,
+            message: format!("unexpected arguments in `{{% {content} %}}`"),
+        });
+    }
+    Ok(())
+}
+
+/// Names are case-insensitive, like parameter and artifact names.
+fn parse_name(name: &str, line: usize) -> ParseResult<String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(TemplateError {
+            line,
+            message: format!("invalid name `{name}`"),
+        });
+    }
+    Ok(name.to_lowercase())
+}
+
+/// A double quoted string, `\"` and `\\` are the only escapes.
+fn parse_literal(content: &str, line: usize) -> ParseResult<String> {
+    let invalid = || TemplateError {
+        line,
+        message: format!("expected a double quoted string, got `{}`", content.trim()),
+    };
+    let inner = content
+        .trim()
+        .strip_prefix('"')
+        .and_then(|c| c.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+
+    let mut literal = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped @ ('"' | '\\')) => literal.push(escaped),
+                _ => return Err(invalid()),
+            },
+            '"' => return Err(invalid()),
+            c => literal.push(c),
+        }
+    }
+    Ok(literal)
+}
+
+/// Split an expression on the `|` which aren't inside a string literal.
+fn split_filters(content: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_literal = false;
+    let mut escaped = false;
+    for (i, c) in content.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_literal => escaped = true,
+            '"' => in_literal = !in_literal,
+            '|' if !in_literal => {
+                parts.push(content[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(content[start..].trim());
+    parts
+}
+
+struct Renderer<'s, 'a> {
+    scope: &'s Scope<'a>,
+    cursors: HashMap<String, usize>,
+    loop_vars: Vec<(&'s str, &'a IpxeTemplateArtifact)>,
+    out: String,
+}
+
+impl<'s, 'a> Renderer<'s, 'a> {
+    fn render_nodes(&mut self, nodes: &'s [Node]) -> Result<()> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => self.out.push_str(text),
+                Node::Expr(expr) => self.render_expr(expr)?,
+                Node::If {
+                    branches,
+                    otherwise,
+                } => {
+                    let mut taken = None;
+                    for branch in branches {
+                        if self.evaluate(&branch.condition)? {
+                            taken = Some(&branch.body);
+                            break;
+                        }
+                    }
+                    self.render_nodes(taken.unwrap_or(otherwise))?;
+                }
+                Node::For {
+                    var,
+                    artifact_name,
+                    body,
+                    ..
+                } => {
+                    let artifacts: &'a [IpxeTemplateArtifact] = self.scope.artifacts;
+                    for artifact in artifacts.iter().filter(|a| {
+                        artifact_name
+                            .as_ref()
+                            .is_none_or(|name| a.name.to_lowercase() == *name)
+                    }) {
+                        self.loop_vars.push((var, artifact));
+                        self.render_nodes(body)?;
+                        self.loop_vars.pop();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render_expr(&mut self, expr: &Expr) -> Result<()> {
+        let value = match &expr.path {
+            VarPath::Name(name) if name == EXTRA => Some(self.scope.extra.to_string()),
+            VarPath::Name(name) => {
+                let cursor = self.cursors.entry(name.clone()).or_default();
+                let value = self
+                    .scope
+                    .values
+                    .get(name)
+                    .and_then(|values| values.get(*cursor))
+                    .cloned();
+                if value.is_some() {
+                    *cursor += 1;
+                }
+                value
+            }
+            VarPath::Field(..) => self.lookup(&expr.path)?,
+        };
+
+        let value = match (value, &expr.default) {
+            (Some(v), Some(default)) if v.is_empty() => default.clone(),
+            (None, Some(default)) => default.clone(),
+            (Some(v), _) => v,
+            // Left in place to be reported as unreplaced placeholder
+            (None, None) => format!("{{{{{}}}}}", expr.source),
+        };
+
+        if !expr.raw && value.chars().any(|c| c.is_control() && c != '\t') {
+            return Err(IpxeScriptError::UnsafeValue(expr.source.clone()));
+        }
+        self.out.push_str(&value);
+        Ok(())
+    }
+
+    fn evaluate(&self, condition: &Condition) -> Result<bool> {
+        Ok(match condition {
+            Condition::Defined(path) => self.lookup(path)?.is_some_and(|v| !v.is_empty()),
+            Condition::Not(inner) => !self.evaluate(inner)?,
+            Condition::Eq(path, literal) => self.lookup(path)?.as_ref() == Some(literal),
+            Condition::Ne(path, literal) => self.lookup(path)?.as_ref() != Some(literal),
+        })
+    }
+
+    /// The first value of a name, conditions don't consume values.
+    fn lookup(&self, path: &VarPath) -> Result<Option<String>> {
+        match path {
+            VarPath::Name(name) if name == EXTRA => Ok(Some(self.scope.extra.to_string())),
+            VarPath::Name(name) => Ok(self
+                .scope
+                .values
+                .get(name)
+                .and_then(|values| values.first())
+                .cloned()),
+            VarPath::Field(var, field) => {
+                let (_, artifact) = self
+                    .loop_vars
+                    .iter()
+                    .rev()
+                    .find(|(name, _)| name == var)
+                    .expect("loop variables are checked when parsing");
+                Ok(Some(match field {
+                    ArtifactField::Name => artifact.name.clone(),
+                    ArtifactField::Url => resolve_artifact_url(artifact)?,
+                    ArtifactField::Sha => artifact.sha.clone().unwrap_or_default(),
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IpxeTemplateArtifactCacheStrategy;
+
+    fn artifact(name: &str, url: &str) -> IpxeTemplateArtifact {
+        IpxeTemplateArtifact {
+            name: name.to_string(),
+            url: url.to_string(),
+            sha: None,
+            auth_type: None,
+            auth_token: None,
+            cache_strategy: IpxeTemplateArtifactCacheStrategy::RemoteOnly,
+            cached_url: None,
+        }
+    }
+
+    fn render(
+        src: &str,
+        values: &[(&str, &str)],
+        artifacts: &[IpxeTemplateArtifact],
+    ) -> Result<String> {
+        let mut map: HashMap<String, Vec<String>> = HashMap::new();
+        for (name, value) in values {
+            map.entry(name.to_string())
+                .or_default()
+                .push(value.to_string());
+        }
+        Template::parse(src)?.render(&Scope {
+            values: &map,
+            extra: "",
+            artifacts,
+        })
+    }
+
+    #[test]
+    fn test_substitution_is_occurrence_based() {
+        let rendered = render(
+            "console={{console}} console={{ console }} {{missing}}",
+            &[("console", "tty0"), ("console", "ttyS0")],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(rendered, "console=tty0 console=ttyS0 {{missing}}");
+    }
+
+    #[test]
+    fn test_conditionals_per_architecture() {
+        let src = "#!ipxe\n\
+                   {% if arch == \"aarch64\" %}\n\
+                   chain arm.efi\n\
+                   {% elif arch == \"x86_64\" %}\n\
+                   chain x86.efi\n\
+                   {% else %}\n\
+                   exit 1\n\
+                   {% endif %}\n\
+                   boot\n";
+        assert_eq!(
+            render(src, &[("arch", "aarch64")], &[]).unwrap(),
+            "#!ipxe\nchain arm.efi\nboot\n"
+        );
+        assert_eq!(
+            render(src, &[("arch", "x86_64")], &[]).unwrap(),
+            "#!ipxe\nchain x86.efi\nboot\n"
+        );
+        assert_eq!(render(src, &[], &[]).unwrap(), "#!ipxe\nexit 1\nboot\n");
+
+        let src = "kernel k{% if not console %} quiet{% endif %}";
+        assert_eq!(render(src, &[], &[]).unwrap(), "kernel k quiet");
+        assert_eq!(
+            render(src, &[("console", "ttyS0")], &[]).unwrap(),
+            "kernel k"
+        );
+    }
+
+    #[test]
+    fn test_default_filter() {
+        let src = r#"console={{ console | default("ttyS0,115200") }}"#;
+        assert_eq!(render(src, &[], &[]).unwrap(), "console=ttyS0,115200");
+        assert_eq!(
+            render(src, &[("console", "")], &[]).unwrap(),
+            "console=ttyS0,115200"
+        );
+        assert_eq!(
+            render(src, &[("console", "tty0")], &[]).unwrap(),
+            "console=tty0"
+        );
+
+        let src = r#"{{ x | default("a|\"b\"") }}"#;
+        assert_eq!(render(src, &[], &[]).unwrap(), r#"a|"b""#);
+    }
+
+    #[test]
+    fn test_artifact_loops() {
+        let artifacts = [
+            artifact("kernel", "http://a/vmlinuz"),
+            artifact("initrd", "http://a/microcode.img"),
+            artifact("InitRD", "http://a/initrd.img"),
+        ];
+        let src = "{% for initrd in artifacts.initrd %}\ninitrd {{ initrd.url }}\n{% endfor %}\n";
+        assert_eq!(
+            render(src, &[], &artifacts).unwrap(),
+            "initrd http://a/microcode.img\ninitrd http://a/initrd.img\n"
+        );
+
+        let src = "{% for a in artifacts %}{{a.name}} {% endfor %}";
+        assert_eq!(
+            render(src, &[], &artifacts).unwrap(),
+            "kernel initrd InitRD "
+        );
+
+        let mut cached_only = artifact("initrd", "http://a/initrd.img");
+        cached_only.cache_strategy = IpxeTemplateArtifactCacheStrategy::CachedOnly;
+        assert!(matches!(
+            render(
+                "{% for a in artifacts %}{{a.url}}{% endfor %}",
+                &[],
+                &[cached_only]
+            ),
+            Err(IpxeScriptError::CachedOnlyNotCached(_))
+        ));
+    }
+
+    #[test]
+    fn test_escaping() {
+        let script = "echo hello\nshell";
+        assert!(matches!(
+            render("kernel k {{params}}", &[("params", script)], &[]),
+            Err(IpxeScriptError::UnsafeValue(_))
+        ));
+        assert_eq!(
+            render("{{ ipxe | raw }}", &[("ipxe", script)], &[]).unwrap(),
+            script
+        );
+        // iPXE settings are expanded when the script runs, they are fine in values
+        assert_eq!(
+            render("{{params}}", &[("params", "console=${console}")], &[]).unwrap(),
+            "console=${console}"
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let error = |src: &str| Template::parse(src).unwrap_err();
+
+        assert_eq!(error("a\n{% if x %}\nb").line, 2);
+        assert_eq!(error("{% endif %}").message, "unexpected `{% endif %}`");
+        assert_eq!(error("{{ x").message, "unclosed `{{`");
+        assert_eq!(error("{% include \"x\" %}").line, 1);
+        assert!(error("{{ a.url }}").message.contains("not a loop variable"));
+        assert!(
+            error("{% for a in artifacts %}{{ a }}{% endfor %}")
+                .message
+                .contains("needs a field")
+        );
+        assert!(
+            error("{% for a in params %}{% endfor %}")
+                .message
+                .contains("only loop over")
+        );
+        assert!(error("{{ x | upper }}").message.contains("unknown filter"));
+        assert!(
+            error("{{ x | default(y) }}")
+                .message
+                .contains("double quoted")
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let template = Template::parse(
+            "{{a}} {{ b | default(\"\") }}\n\
+             {% if c %}{{c}} {{d}}{% endif %}\n\
+             {% for i in artifacts.initrd %}{{ i.url }}{% endfor %}",
+        )
+        .unwrap();
+        let references: Vec<String> = template
+            .references()
+            .iter()
+            .map(|r| format!("{}:{}", r.name, r.optional))
+            .collect();
+        assert_eq!(
+            references,
+            vec![
+                "a:false",
+                "b:true",
+                "c:true",
+                "c:true",
+                "d:false",
+                "initrd:true"
+            ]
+        );
+        assert!(template.uses("initrd"));
+        assert!(!template.uses("i"));
+    }
+}
@@ -0,0 +1,165 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::path::Path;
+
+use futures_util::StreamExt;
+use reqwest::Client;
+use rpc::forge::IpxeTemplateArtifact;
+use sha2::{Digest, Sha256};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+/// Download `artifact` into `destination` and verify its `sha` if it has one. Downloads larger
+/// than `max_bytes` are aborted. Returns the size of the download.
+pub(crate) async fn download(
+    client: &Client,
+    artifact: &IpxeTemplateArtifact,
+    destination: &Path,
+    max_bytes: u64,
+) -> Result<u64, String> {
+    let result = download_and_verify(client, artifact, destination, max_bytes).await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(destination).await;
+    }
+    result
+}
+
+async fn download_and_verify(
+    client: &Client,
+    artifact: &IpxeTemplateArtifact,
+    destination: &Path,
+    max_bytes: u64,
+) -> Result<u64, String> {
+    let url = &artifact.url;
+    // Artifacts with an auth_token are never cached, so there are no credentials to send
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Error requesting {url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("{url} returned {}", response.status()));
+    }
+    if let Some(length) = response.content_length()
+        && length > max_bytes
+    {
+        return Err(too_large(url, max_bytes));
+    }
+
+    let mut file = File::create(destination)
+        .await
+        .map_err(|e| format!("Unable to create {}: {e}", destination.display()))?;
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(|e| format!("Error downloading {url}: {e}"))?;
+        hasher.update(&chunk);
+        size += chunk.len() as u64;
+        // The Content-Length can't be relied on, it might be missing or wrong
+        if size > max_bytes {
+            return Err(too_large(url, max_bytes));
+        }
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Error writing {}: {e}", destination.display()))?;
+    }
+    file.sync_all()
+        .await
+        .map_err(|e| format!("Error writing {}: {e}", destination.display()))?;
+
+    if let Some(expected) = artifact.sha.as_deref().filter(|sha| !sha.is_empty()) {
+        let actual = hex::encode(hasher.finalize());
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(format!(
+                "Checksum mismatch for {url}: expected {expected}, downloaded {actual}"
+            ));
+        }
+    }
+
+    Ok(size)
+}
+
+fn too_large(url: &str, max_bytes: u64) -> String {
+    format!("{url} exceeds the cache size of {max_bytes} bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Serve a single request with `response` and return the artifact pointing at it.
+    async fn serve_once(response: Vec<u8>) -> IpxeTemplateArtifact {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/vmlinuz", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).await;
+            let _ = stream.write_all(&response).await;
+        });
+        IpxeTemplateArtifact {
+            name: "kernel".to_string(),
+            url,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_is_bounded_by_max_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("kernel");
+        let client = Client::new();
+        let body = vec![b'x'; 100];
+
+        let ok = [
+            b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\nConnection: close\r\n\r\n".as_slice(),
+            &body,
+        ]
+        .concat();
+        let artifact = serve_once(ok.clone()).await;
+        assert_eq!(
+            download(&client, &artifact, &destination, 100).await,
+            Ok(100)
+        );
+
+        // Rejected up front
+        let artifact = serve_once(ok).await;
+        let err = download(&client, &artifact, &destination, 64)
+            .await
+            .unwrap_err();
+        assert!(err.contains("exceeds the cache size"), "{err}");
+        assert!(!destination.exists());
+
+        // No Content-Length, the body ends when the connection is closed
+        let without_length = [
+            b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n".as_slice(),
+            &body,
+        ]
+        .concat();
+        let artifact = serve_once(without_length).await;
+        let err = download(&client, &artifact, &destination, 64)
+            .await
+            .unwrap_err();
+        assert!(err.contains("exceeds the cache size"), "{err}");
+        assert!(!destination.exists());
+    }
+}
@@ -0,0 +1,624 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Keeps tenant OS artifacts on local disk so that hosts don't pull large kernels and initrds
+//! over the WAN on every boot.
+//!
+//! Every sync pass lists the operating systems known to carbide-api, downloads the
+//! `CACHE_AS_NEEDED` and `CACHED_ONLY` artifacts which aren't on disk yet and writes the
+//! resulting `cached_url` back. Artifacts which are still referenced are never evicted;
+//! space is reclaimed from artifacts no operating system uses anymore, least recently used
+//! first.
+//!
+//! Artifacts with an `auth_token` are never cached: `/artifacts` is unauthenticated, so
+//! serving them from there would hand them to anyone who can reach carbide-pxe.
+//!
+//! All replicas share the `cached_url` prefix and thereby the `cached_url`s under it. A
+//! replica sets one once it has the artifact, but never clears one just because it doesn't:
+//! it downloads the artifact as well and redirects requests for it to the original URL
+//! meanwhile. A `cached_url` is only cleared once the artifact can't be cached anymore, which
+//! every replica agrees on, so replicas with different contents don't undo each other.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use ::rpc::forge as rpc;
+use ::rpc::forge_tls_client::{self, ApiConfig, ForgeClientConfig, ForgeClientT};
+use carbide_uuid::operating_system::OperatingSystemId;
+use metrics::{counter, gauge, histogram};
+use rpc::{IpxeTemplateArtifact, IpxeTemplateArtifactCacheStrategy};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::ArtifactCacheConfig;
+
+pub(crate) mod download;
+pub(crate) mod store;
+
+use store::{ArtifactStore, is_valid_key};
+
+#[derive(Debug)]
+pub(crate) struct ArtifactCache {
+    store: ArtifactStore,
+    url_prefix: String,
+    api_url: String,
+    client_config: ForgeClientConfig,
+    http_client: reqwest::Client,
+    /// Original URL of every cachable artifact by cache key, for requests this replica can't
+    /// serve from disk (yet).
+    origins: RwLock<HashMap<String, String>>,
+    status: RwLock<Vec<ArtifactStatus>>,
+}
+
+/// Download status of a single artifact of an operating system. Artifacts with an
+/// `auth_token` are never listed, and neither are URLs, which might embed credentials.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct ArtifactStatus {
+    pub operating_system_id: String,
+    pub name: String,
+    pub cache_key: String,
+    pub state: DownloadState,
+    pub size_bytes: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DownloadState {
+    Pending,
+    Downloading,
+    Cached,
+    Failed,
+}
+
+impl ArtifactCache {
+    pub(crate) fn new(
+        config: &ArtifactCacheConfig,
+        api_url: String,
+        client_config: ForgeClientConfig,
+    ) -> Result<Self, String> {
+        let store = ArtifactStore::open(&config.directory, config.max_bytes).map_err(|e| {
+            format!(
+                "Unable to open artifact cache directory {}: {e}",
+                config.directory
+            )
+        })?;
+
+        Ok(Self {
+            store,
+            url_prefix: config.url_prefix.trim_end_matches('/').to_string(),
+            api_url,
+            client_config,
+            http_client: reqwest::Client::new(),
+            origins: RwLock::new(HashMap::new()),
+            status: RwLock::new(Vec::new()),
+        })
+    }
+
+    pub(crate) fn store(&self) -> &ArtifactStore {
+        &self.store
+    }
+
+    /// Where an artifact which isn't on disk can be downloaded from instead.
+    pub(crate) fn origin(&self, key: &str) -> Option<String> {
+        self.origins.read().unwrap().get(key).cloned()
+    }
+
+    pub(crate) fn status(&self) -> Vec<ArtifactStatus> {
+        self.status.read().unwrap().clone()
+    }
+
+    /// Sync in the background every `config.sync_interval`.
+    pub(crate) fn spawn(self: Arc<Self>, config: &ArtifactCacheConfig) {
+        let sync_interval = config.sync_interval;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(sync_interval);
+            loop {
+                interval.tick().await;
+                if let Err(err) = self.sync().await {
+                    eprintln!("Artifact cache sync failed: {err}");
+                }
+            }
+        });
+    }
+
+    pub(crate) async fn sync(&self) -> Result<(), String> {
+        let mut client = self.api_client().await?;
+        let operating_systems = fetch_artifacts(&mut client).await?;
+
+        let cachable = self.managed_artifacts(&operating_systems);
+        let referenced: HashSet<String> = cachable.iter().map(|(_, a)| cache_key(a)).collect();
+
+        // Copies made before an artifact got an auth_token must not be served anymore
+        for (_, artifacts) in &operating_systems {
+            for artifact in artifacts.iter().filter(|a| has_auth_token(a)) {
+                let key = cache_key(artifact);
+                if !referenced.contains(&key) && self.store.remove(&key) {
+                    println!("Removed cached artifact {key}, it requires authentication");
+                }
+            }
+        }
+
+        *self.origins.write().unwrap() = cachable
+            .iter()
+            .map(|(_, artifact)| (cache_key(artifact), artifact.url.clone()))
+            .collect();
+        self.update_status(&cachable);
+
+        // Artifacts without which a host can't boot at all go first
+        let mut missing: Vec<&IpxeTemplateArtifact> = cachable
+            .iter()
+            .map(|(_, artifact)| *artifact)
+            .filter(|artifact| !self.store.contains(&cache_key(artifact)))
+            .collect();
+        missing.sort_by_key(|a| {
+            a.cache_strategy != IpxeTemplateArtifactCacheStrategy::CachedOnly as i32
+        });
+
+        let mut fetched = HashSet::new();
+        for artifact in missing {
+            let key = cache_key(artifact);
+            if fetched.insert(key.clone()) {
+                self.fetch(&key, artifact, &referenced).await;
+            }
+        }
+
+        for (id, artifacts) in &operating_systems {
+            self.write_back(&mut client, id, artifacts).await;
+        }
+
+        let cached = referenced
+            .iter()
+            .filter(|key| self.store.contains(key))
+            .count();
+        gauge!("carbide_pxe_artifact_cache_artifacts", "state" => "cached").set(cached as f64);
+        gauge!("carbide_pxe_artifact_cache_artifacts", "state" => "missing")
+            .set((referenced.len() - cached) as f64);
+        gauge!("carbide_pxe_artifact_cache_used_bytes").set(self.store.used_bytes() as f64);
+        gauge!("carbide_pxe_artifact_cache_capacity_bytes").set(self.store.max_bytes() as f64);
+        Ok(())
+    }
+
+    async fn fetch(
+        &self,
+        key: &str,
+        artifact: &IpxeTemplateArtifact,
+        referenced: &HashSet<String>,
+    ) {
+        self.set_state(key, DownloadState::Downloading, None, None);
+        let started = Instant::now();
+        let download_path = self.store.download_path(key);
+        let result = match download::download(
+            &self.http_client,
+            artifact,
+            &download_path,
+            self.store.max_bytes(),
+        )
+        .await
+        {
+            Ok(size) => self
+                .store
+                .insert(key, &download_path, referenced)
+                .map(|evicted| (size, evicted))
+                .map_err(|e| {
+                    let _ = std::fs::remove_file(&download_path);
+                    format!("Unable to store {}: {e}", artifact.url)
+                }),
+            Err(e) => Err(e),
+        };
+        histogram!("carbide_pxe_artifact_download_duration_seconds")
+            .record(started.elapsed().as_secs_f64());
+
+        match result {
+            Ok((size, evicted)) => {
+                println!("Cached artifact {} as {key} ({size} bytes)", artifact.url);
+                self.set_state(key, DownloadState::Cached, Some(size), None);
+                counter!("carbide_pxe_artifact_downloads_total", "result" => "success")
+                    .increment(1);
+                counter!("carbide_pxe_artifact_cache_evictions_total")
+                    .increment(evicted.len() as u64);
+            }
+            Err(err) => {
+                eprintln!("Failed to cache artifact {}: {err}", artifact.url);
+                self.set_state(key, DownloadState::Failed, None, Some(err));
+                counter!("carbide_pxe_artifact_downloads_total", "result" => "failure")
+                    .increment(1);
+            }
+        }
+    }
+
+    /// The artifacts of every operating system this cache is responsible for.
+    fn managed_artifacts<'a>(
+        &self,
+        operating_systems: &'a [(OperatingSystemId, Vec<IpxeTemplateArtifact>)],
+    ) -> Vec<(&'a OperatingSystemId, &'a IpxeTemplateArtifact)> {
+        operating_systems
+            .iter()
+            .flat_map(|(id, artifacts)| artifacts.iter().map(move |a| (id, a)))
+            .filter(|(_, artifact)| self.is_managed(artifact))
+            .collect()
+    }
+
+    /// Rebuild the status from the artifacts this cache is responsible for. Artifacts which
+    /// aren't on disk keep the error of their last failed download until they're retried.
+    fn update_status(&self, cachable: &[(&OperatingSystemId, &IpxeTemplateArtifact)]) {
+        let mut status = self.status.write().unwrap();
+        let errors: HashMap<String, String> = status
+            .iter()
+            .filter_map(|entry| Some((entry.cache_key.clone(), entry.error.clone()?)))
+            .collect();
+
+        *status = cachable
+            .iter()
+            .map(|(id, artifact)| {
+                let key = cache_key(artifact);
+                let size_bytes = self.store.size(&key);
+                let error = match size_bytes {
+                    Some(_) => None,
+                    None => errors.get(&key).cloned(),
+                };
+                ArtifactStatus {
+                    operating_system_id: id.to_string(),
+                    name: artifact.name.clone(),
+                    state: match (size_bytes, &error) {
+                        (Some(_), _) => DownloadState::Cached,
+                        (None, Some(_)) => DownloadState::Failed,
+                        (None, None) => DownloadState::Pending,
+                    },
+                    size_bytes,
+                    error,
+                    cache_key: key,
+                }
+            })
+            .collect();
+    }
+
+    fn set_state(
+        &self,
+        key: &str,
+        state: DownloadState,
+        size_bytes: Option<u64>,
+        error: Option<String>,
+    ) {
+        let mut status = self.status.write().unwrap();
+        for entry in status.iter_mut().filter(|entry| entry.cache_key == key) {
+            entry.state = state;
+            entry.size_bytes = size_bytes;
+            entry.error = error.clone();
+        }
+    }
+
+    /// Write the `cached_url` of every artifact of an operating system back if any changed.
+    /// An update is sent for every artifact, in stored order, since carbide-api matches
+    /// updates to artifacts by occurrence of their name.
+    async fn write_back(
+        &self,
+        client: &mut ForgeClientT,
+        id: &OperatingSystemId,
+        artifacts: &[IpxeTemplateArtifact],
+    ) {
+        let updates: Vec<rpc::IpxeTemplateArtifactUpdateRequest> = artifacts
+            .iter()
+            .map(|artifact| rpc::IpxeTemplateArtifactUpdateRequest {
+                name: artifact.name.clone(),
+                cached_url: self.cached_url(artifact),
+            })
+            .collect();
+        if updates
+            .iter()
+            .zip(artifacts)
+            .all(|(update, artifact)| update.cached_url == artifact.cached_url)
+        {
+            return;
+        }
+
+        let request = rpc::UpdateOperatingSystemIpxeTemplateArtifactRequest {
+            id: Some(*id),
+            updates,
+        };
+        if let Err(err) = client
+            .update_operating_system_cachable_ipxe_template_artifacts(request)
+            .await
+        {
+            eprintln!("Unable to update cached artifacts of operating system {id}: {err}");
+        }
+    }
+
+    /// The `cached_url` an artifact should have. This only depends on what's on disk when
+    /// the artifact has none yet, so that replicas with different contents agree on it.
+    fn cached_url(&self, artifact: &IpxeTemplateArtifact) -> Option<String> {
+        let ours = artifact
+            .cached_url
+            .as_deref()
+            .filter(|url| url.starts_with(&format!("{}/", self.url_prefix)));
+        if !is_cachable(artifact) {
+            return match ours {
+                Some(_) => None,
+                None => artifact.cached_url.clone(),
+            };
+        }
+        if !self.is_managed(artifact) {
+            return artifact.cached_url.clone();
+        }
+
+        let cached_url = format!("{}/{}", self.url_prefix, cache_key(artifact));
+        if ours == Some(cached_url.as_str()) {
+            return Some(cached_url);
+        }
+        // Unset, or a different version of the artifact was cached
+        self.store
+            .contains(&cache_key(artifact))
+            .then_some(cached_url)
+    }
+
+    /// Whether this cache is responsible for the artifact. A `cached_url` which doesn't
+    /// point at us was set by an operator and is left alone.
+    fn is_managed(&self, artifact: &IpxeTemplateArtifact) -> bool {
+        let overridden = artifact
+            .cached_url
+            .as_deref()
+            .is_some_and(|url| !url.starts_with(&format!("{}/", self.url_prefix)));
+
+        is_cachable(artifact) && !overridden
+    }
+
+    async fn api_client(&self) -> Result<ForgeClientT, String> {
+        let api_config = ApiConfig::new(&self.api_url, &self.client_config);
+        forge_tls_client::ForgeTlsClient::retry_build(&api_config)
+            .await
+            .map_err(|err| err.to_string())
+    }
+}
+
+async fn fetch_artifacts(
+    client: &mut ForgeClientT,
+) -> Result<Vec<(OperatingSystemId, Vec<IpxeTemplateArtifact>)>, String> {
+    let ids = client
+        .find_operating_system_ids(rpc::OperatingSystemSearchFilter::default())
+        .await
+        .map_err(|err| format!("Error listing operating systems: {err}"))?
+        .into_inner()
+        .ids;
+
+    let mut operating_systems = Vec::with_capacity(ids.len());
+    for id in ids {
+        let request = rpc::GetOperatingSystemCachableIpxeTemplateArtifactsRequest { id: Some(id) };
+        match client
+            .get_operating_system_cachable_ipxe_template_artifacts(request)
+            .await
+        {
+            Ok(response) => operating_systems.push((id, response.into_inner().artifacts)),
+            // Deleted since we listed it
+            Err(status) if status.code() == tonic::Code::NotFound => continue,
+            Err(err) => {
+                return Err(format!(
+                    "Error fetching artifacts of operating system {id}: {err}"
+                ));
+            }
+        }
+    }
+    Ok(operating_systems)
+}
+
+fn is_cachable(artifact: &IpxeTemplateArtifact) -> bool {
+    let strategy = [
+        IpxeTemplateArtifactCacheStrategy::CacheAsNeeded as i32,
+        IpxeTemplateArtifactCacheStrategy::CachedOnly as i32,
+    ]
+    .contains(&artifact.cache_strategy);
+
+    strategy && !artifact.url.is_empty() && !has_auth_token(artifact)
+}
+
+fn has_auth_token(artifact: &IpxeTemplateArtifact) -> bool {
+    artifact
+        .auth_token
+        .as_deref()
+        .is_some_and(|token| !token.is_empty())
+}
+
+/// Artifacts are keyed by their SHA256 so that operating systems sharing an artifact share
+/// the download. Artifacts without one are keyed by URL and never re-validated.
+fn cache_key(artifact: &IpxeTemplateArtifact) -> String {
+    if let Some(sha) = artifact.sha.as_deref() {
+        let sha = sha.trim().to_ascii_lowercase();
+        if is_valid_key(&sha) {
+            return sha;
+        }
+    }
+    hex::encode(Sha256::digest(artifact.url.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact(url: &str, sha: Option<&str>) -> IpxeTemplateArtifact {
+        IpxeTemplateArtifact {
+            name: "kernel".to_string(),
+            url: url.to_string(),
+            sha: sha.map(String::from),
+            cache_strategy: IpxeTemplateArtifactCacheStrategy::CacheAsNeeded as i32,
+            ..Default::default()
+        }
+    }
+
+    fn cache(dir: &std::path::Path) -> ArtifactCache {
+        let config = ArtifactCacheConfig {
+            directory: dir.to_string_lossy().to_string(),
+            max_bytes: 1024,
+            sync_interval: std::time::Duration::from_secs(60),
+            url_prefix: "[pxe_url]/artifacts/".to_string(),
+        };
+        ArtifactCache::new(
+            &config,
+            "https://carbide-api".to_string(),
+            ForgeClientConfig::new("/dev/null".to_string(), None),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let sha = "AB".repeat(32);
+        assert_eq!(
+            cache_key(&artifact("http://a/vmlinuz", Some(&sha))),
+            "ab".repeat(32)
+        );
+        // Anything but a SHA256 falls back to the URL
+        let by_url = cache_key(&artifact("http://a/vmlinuz", None));
+        assert!(is_valid_key(&by_url));
+        assert_eq!(
+            cache_key(&artifact("http://a/vmlinuz", Some("abc"))),
+            by_url
+        );
+        assert_ne!(cache_key(&artifact("http://b/vmlinuz", None)), by_url);
+    }
+
+    #[test]
+    fn test_cached_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = cache(dir.path());
+        let sha = "ab".repeat(32);
+
+        let mut kernel = artifact("http://a/vmlinuz", Some(&sha));
+        assert_eq!(cache.cached_url(&kernel), None);
+
+        let download = cache.store.download_path(&sha);
+        std::fs::write(&download, b"kernel").unwrap();
+        cache
+            .store
+            .insert(&sha, &download, &HashSet::new())
+            .unwrap();
+        assert_eq!(
+            cache.cached_url(&kernel),
+            Some(format!("[pxe_url]/artifacts/{sha}"))
+        );
+
+        // Set by an operator
+        kernel.cached_url = Some("http://mirror/vmlinuz".to_string());
+        assert_eq!(
+            cache.cached_url(&kernel).as_deref(),
+            Some("http://mirror/vmlinuz")
+        );
+
+        kernel.cached_url = None;
+        kernel.cache_strategy = IpxeTemplateArtifactCacheStrategy::RemoteOnly as i32;
+        assert_eq!(cache.cached_url(&kernel), None);
+    }
+
+    #[test]
+    fn test_cached_url_is_shared_between_replicas() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = cache(dir.path());
+        let sha = "ab".repeat(32);
+
+        // Set by another replica, this one will download the artifact as well
+        let mut kernel = artifact("http://a/vmlinuz", Some(&sha));
+        kernel.cached_url = Some(format!("[pxe_url]/artifacts/{sha}"));
+        assert_eq!(cache.cached_url(&kernel), kernel.cached_url);
+
+        // Points at a previous version of the artifact
+        kernel.cached_url = Some(format!("[pxe_url]/artifacts/{}", "cd".repeat(32)));
+        assert_eq!(cache.cached_url(&kernel), None);
+
+        // Can't be cached anymore
+        kernel.cached_url = Some(format!("[pxe_url]/artifacts/{sha}"));
+        kernel.cache_strategy = IpxeTemplateArtifactCacheStrategy::RemoteOnly as i32;
+        assert_eq!(cache.cached_url(&kernel), None);
+    }
+
+    #[test]
+    fn test_artifacts_with_auth_token_are_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = cache(dir.path());
+        let sha = "ab".repeat(32);
+
+        let download = cache.store.download_path(&sha);
+        std::fs::write(&download, b"kernel").unwrap();
+        cache
+            .store
+            .insert(&sha, &download, &HashSet::new())
+            .unwrap();
+
+        let mut kernel = artifact("http://a/vmlinuz", Some(&sha));
+        kernel.auth_type = Some("Bearer".to_string());
+        kernel.auth_token = Some("secret".to_string());
+        assert!(!cache.is_managed(&kernel));
+        assert_eq!(cache.cached_url(&kernel), None);
+
+        // Cached before it got the token
+        kernel.cached_url = Some(format!("[pxe_url]/artifacts/{sha}"));
+        assert_eq!(cache.cached_url(&kernel), None);
+    }
+
+    #[test]
+    fn test_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = cache(dir.path());
+        let kernel_sha = "ab".repeat(32);
+        let initrd_sha = "cd".repeat(32);
+
+        let download = cache.store.download_path(&kernel_sha);
+        std::fs::write(&download, b"kernel").unwrap();
+        cache
+            .store
+            .insert(&kernel_sha, &download, &HashSet::new())
+            .unwrap();
+
+        let mut initrd = artifact("http://a/initrd", Some(&initrd_sha));
+        initrd.name = "initrd".to_string();
+        let mut secret = artifact("http://a/secret", Some(&"ef".repeat(32)));
+        secret.name = "secret".to_string();
+        secret.auth_type = Some("Bearer".to_string());
+        secret.auth_token = Some("secret".to_string());
+        let operating_systems = vec![(
+            OperatingSystemId::new(),
+            vec![
+                artifact("http://a/vmlinuz", Some(&kernel_sha)),
+                initrd,
+                secret,
+            ],
+        )];
+
+        let cachable = cache.managed_artifacts(&operating_systems);
+        cache.update_status(&cachable);
+        cache.set_state(
+            &initrd_sha,
+            DownloadState::Failed,
+            None,
+            Some("http://a/initrd returned 404 Not Found".to_string()),
+        );
+        // The error is kept until the download is retried
+        cache.update_status(&cachable);
+
+        let status = cache.status();
+        let names: Vec<&str> = status.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["kernel", "initrd"]);
+        assert_eq!(status[0].cache_key, kernel_sha);
+        assert_eq!(status[0].state, DownloadState::Cached);
+        assert_eq!(status[0].size_bytes, Some(6));
+        assert_eq!(status[0].error, None);
+        assert_eq!(status[1].state, DownloadState::Failed);
+        assert_eq!(status[1].size_bytes, None);
+        assert_eq!(
+            status[1].error.as_deref(),
+            Some("http://a/initrd returned 404 Not Found")
+        );
+    }
+}
@@ -0,0 +1,284 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Artifacts on local disk, one file per cache key, evicted least recently used first.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use std::{fs, io};
+
+const DOWNLOAD_SUFFIX: &str = ".download";
+
+#[derive(Debug)]
+pub(crate) struct ArtifactStore {
+    directory: PathBuf,
+    max_bytes: u64,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    size: u64,
+    last_access: SystemTime,
+}
+
+impl ArtifactStore {
+    /// Open the store, picking up artifacts a previous run left behind. The file modification
+    /// time doubles as the last access time, so LRU order survives a restart.
+    pub(crate) fn open(directory: impl Into<PathBuf>, max_bytes: u64) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        let mut entries = HashMap::new();
+        for dir_entry in fs::read_dir(&directory)? {
+            let dir_entry = dir_entry?;
+            let file_name = dir_entry.file_name().to_string_lossy().to_string();
+            if file_name.ends_with(DOWNLOAD_SUFFIX) {
+                // Interrupted download
+                let _ = fs::remove_file(dir_entry.path());
+                continue;
+            }
+            if !is_valid_key(&file_name) {
+                continue;
+            }
+            let metadata = dir_entry.metadata()?;
+            entries.insert(
+                file_name,
+                Entry {
+                    size: metadata.len(),
+                    last_access: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                },
+            );
+        }
+
+        Ok(Self {
+            directory,
+            max_bytes,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    pub(crate) fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(key)
+    }
+
+    /// Where a download for `key` is written before it's verified and moved into place.
+    pub(crate) fn download_path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{key}{DOWNLOAD_SUFFIX}"))
+    }
+
+    pub(crate) fn contains(&self, key: &str) -> bool {
+        self.entries.lock().unwrap().contains_key(key)
+    }
+
+    pub(crate) fn size(&self, key: &str) -> Option<u64> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .map(|entry| entry.size)
+    }
+
+    pub(crate) fn used_bytes(&self) -> u64 {
+        self.entries.lock().unwrap().values().map(|e| e.size).sum()
+    }
+
+    pub(crate) fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Record that `key` was served. Returns false if the artifact isn't in the store.
+    pub(crate) fn touch(&self, key: &str) -> bool {
+        let now = SystemTime::now();
+        let mut entries = self.entries.lock().unwrap();
+        let Some(entry) = entries.get_mut(key) else {
+            return false;
+        };
+        entry.last_access = now;
+        // Best effort, this only matters for the LRU order after a restart
+        let _ = fs::File::options()
+            .write(true)
+            .open(self.path(key))
+            .and_then(|file| file.set_modified(now));
+        true
+    }
+
+    /// Remove an artifact. Returns false if it wasn't in the store.
+    pub(crate) fn remove(&self, key: &str) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.remove(key).is_none() {
+            return false;
+        }
+        let _ = fs::remove_file(self.path(key));
+        true
+    }
+
+    /// Move a finished download into the store, evicting least recently used artifacts that
+    /// aren't in `protected` until it fits. Returns the evicted keys.
+    pub(crate) fn insert(
+        &self,
+        key: &str,
+        download: &Path,
+        protected: &HashSet<String>,
+    ) -> io::Result<Vec<String>> {
+        let size = fs::metadata(download)?.len();
+        let mut entries = self.entries.lock().unwrap();
+
+        let mut used: u64 = entries
+            .iter()
+            .filter(|(k, _)| k.as_str() != key)
+            .map(|(_, e)| e.size)
+            .sum();
+        let mut candidates: Vec<(String, Entry)> = entries
+            .iter()
+            .filter(|(k, _)| k.as_str() != key && !protected.contains(k.as_str()))
+            .map(|(k, e)| (k.clone(), *e))
+            .collect();
+        candidates.sort_by_key(|(_, e)| e.last_access);
+
+        let mut evicted = Vec::new();
+        let mut candidates = candidates.into_iter();
+        while used + size > self.max_bytes {
+            let Some((candidate, entry)) = candidates.next() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::StorageFull,
+                    format!(
+                        "artifact of {size} bytes doesn't fit, {used} of {} bytes are in use \
+                         by artifacts that are still referenced",
+                        self.max_bytes
+                    ),
+                ));
+            };
+            used -= entry.size;
+            evicted.push(candidate);
+        }
+
+        for candidate in &evicted {
+            fs::remove_file(self.path(candidate)).or_else(|e| match e.kind() {
+                io::ErrorKind::NotFound => Ok(()),
+                _ => Err(e),
+            })?;
+            entries.remove(candidate);
+        }
+
+        fs::rename(download, self.path(key))?;
+        entries.insert(
+            key.to_string(),
+            Entry {
+                size,
+                last_access: SystemTime::now(),
+            },
+        );
+
+        Ok(evicted)
+    }
+}
+
+/// Keys are lowercase hex SHA256 digests, which also keeps them safe to use as file names.
+pub(crate) fn is_valid_key(key: &str) -> bool {
+    key.len() == 64 && key.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> String {
+        format!("{n:02x}").repeat(32)
+    }
+
+    fn stage(store: &ArtifactStore, key: &str, size: usize) -> PathBuf {
+        let path = store.download_path(key);
+        fs::write(&path, vec![0u8; size]).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ArtifactStore::open(dir.path(), 100).unwrap();
+
+        for n in 1..=3 {
+            let download = stage(&store, &key(n), 30);
+            assert!(
+                store
+                    .insert(&key(n), &download, &HashSet::new())
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+        assert!(store.touch(&key(1)));
+
+        let download = stage(&store, &key(4), 30);
+        let evicted = store.insert(&key(4), &download, &HashSet::new()).unwrap();
+        assert_eq!(evicted, vec![key(2)]);
+        assert!(!store.path(&key(2)).exists());
+        assert!(store.contains(&key(1)) && store.contains(&key(3)) && store.contains(&key(4)));
+        assert_eq!(store.used_bytes(), 90);
+    }
+
+    #[test]
+    fn test_protected_artifacts_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ArtifactStore::open(dir.path(), 100).unwrap();
+
+        let download = stage(&store, &key(1), 60);
+        store.insert(&key(1), &download, &HashSet::new()).unwrap();
+
+        let download = stage(&store, &key(2), 60);
+        let protected = HashSet::from([key(1)]);
+        let err = store.insert(&key(2), &download, &protected).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::StorageFull);
+        assert!(store.contains(&key(1)));
+        assert!(!store.contains(&key(2)));
+    }
+
+    #[test]
+    fn test_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ArtifactStore::open(dir.path(), 100).unwrap();
+
+        let download = stage(&store, &key(1), 10);
+        store.insert(&key(1), &download, &HashSet::new()).unwrap();
+        assert!(store.remove(&key(1)));
+        assert!(!store.contains(&key(1)));
+        assert!(!store.path(&key(1)).exists());
+        assert_eq!(store.used_bytes(), 0);
+        assert!(!store.remove(&key(1)));
+    }
+
+    #[test]
+    fn test_reopen_picks_up_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let store = ArtifactStore::open(dir.path(), 100).unwrap();
+            let download = stage(&store, &key(1), 10);
+            store.insert(&key(1), &download, &HashSet::new()).unwrap();
+            stage(&store, &key(2), 10);
+        }
+        fs::write(dir.path().join("unrelated"), b"x").unwrap();
+
+        let store = ArtifactStore::open(dir.path(), 100).unwrap();
+        assert_eq!(store.size(&key(1)), Some(10));
+        assert_eq!(store.used_bytes(), 10);
+        assert!(!store.contains(&key(2)));
+        assert!(!store.download_path(&key(2)).exists());
+        assert!(!is_valid_key("unrelated"));
+    }
+}
@@ -15,6 +15,7 @@
  * limitations under the License.
  */
 use std::net::IpAddr;
+use std::sync::Arc;
 
 use axum_template::engine::Engine;
 use metrics_exporter_prometheus::PrometheusHandle;
@@ -22,6 +23,7 @@ use rpc::forge::CloudInitInstructions;
 use serde::{Deserialize, Serialize};
 use tera::Tera;
 
+use crate::artifact_cache::ArtifactCache;
 use crate::config::RuntimeConfig;
 use crate::extractors::machine_architecture;
 // use crate::middleware::metrics::RequestMetrics;
@@ -52,4 +54,5 @@ pub(crate) struct AppState {
     // pub request_metrics: RequestMetrics,
     pub runtime_config: RuntimeConfig,
     pub prometheus_handle: PrometheusHandle,
+    pub artifact_cache: Option<Arc<ArtifactCache>>,
 }
@@ -15,6 +15,7 @@
  * limitations under the License.
  */
 use std::env;
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub(crate) struct RuntimeConfig {
@@ -28,6 +29,20 @@ pub(crate) struct RuntimeConfig {
     pub bind_address: String,
     pub bind_port: u16,
     pub template_directory: String,
+    pub artifact_cache: Option<ArtifactCacheConfig>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ArtifactCacheConfig {
+    /// Where downloaded artifacts are kept. The cache is disabled if this isn't set.
+    pub directory: String,
+    /// Least recently used artifacts are evicted once the cache would grow beyond this.
+    pub max_bytes: u64,
+    pub sync_interval: Duration,
+    /// Prefix of the `cached_url` written back to carbide-api. `[pxe_url]` is substituted
+    /// when the iPXE script is served, so the default works for external clients too.
+    /// It must be the same on every replica.
+    pub url_prefix: String,
 }
 
 impl RuntimeConfig {
@@ -58,8 +73,38 @@ impl RuntimeConfig {
                 .map_err(|_| "not a parsable bind port for runtime config?".to_string())?,
             template_directory: env::var("CARBIDE_PXE_TEMPLATE_DIRECTORY")
                 .unwrap_or_else(|_| "/opt/carbide/pxe/templates".to_string()),
+            artifact_cache: ArtifactCacheConfig::from_env()?,
         };
 
         Ok(this)
     }
 }
+
+impl ArtifactCacheConfig {
+    fn from_env() -> Result<Option<Self>, String> {
+        let Ok(directory) = env::var("CARBIDE_PXE_ARTIFACT_CACHE_DIRECTORY") else {
+            return Ok(None);
+        };
+
+        let this = Self {
+            directory,
+            max_bytes: env::var("CARBIDE_PXE_ARTIFACT_CACHE_MAX_BYTES")
+                .unwrap_or_else(|_| (100u64 << 30).to_string())
+                .parse::<u64>()
+                .map_err(|_| {
+                    "not a parsable artifact cache size for runtime config?".to_string()
+                })?,
+            sync_interval: env::var("CARBIDE_PXE_ARTIFACT_CACHE_SYNC_INTERVAL_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse::<u64>()
+                .map(Duration::from_secs)
+                .map_err(|_| {
+                    "not a parsable artifact cache sync interval for runtime config?".to_string()
+                })?,
+            url_prefix: env::var("CARBIDE_PXE_ARTIFACT_CACHE_URL")
+                .unwrap_or_else(|_| "[pxe_url]/artifacts".to_string()),
+        };
+
+        Ok(Some(this))
+    }
+}
@@ -17,17 +17,22 @@
 use std::fmt::Debug;
 use std::net::SocketAddr;
 use std::str::FromStr;
+use std::sync::Arc;
 
+use artifact_cache::ArtifactCache;
 use axum::middleware::{map_request, map_response};
 use axum::{Router, ServiceExt};
 use axum_client_ip::ClientIpSource;
 use axum_template::engine::Engine;
 use clap::Parser;
 use common::AppState;
+use forge_tls::client_config::ClientCert;
+use rpc::forge_tls_client::ForgeClientConfig;
 use tera::Tera;
 use tower_http::services::ServeDir;
 use tower_layer::Layer;
 
+mod artifact_cache;
 mod common;
 mod config;
 mod extractors;
@@ -83,10 +88,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     )
     .expect("unable to construct socket address from runtime config?");
 
+    let artifact_cache = match &runtime_config.artifact_cache {
+        Some(config) => {
+            let client_config = ForgeClientConfig::new(
+                runtime_config.forge_root_ca_path.clone(),
+                Some(ClientCert {
+                    cert_path: runtime_config.server_cert_path.clone(),
+                    key_path: runtime_config.server_key_path.clone(),
+                }),
+            );
+            let cache = Arc::new(ArtifactCache::new(
+                config,
+                runtime_config.internal_api_url.clone(),
+                client_config,
+            )?);
+            cache.clone().spawn(config);
+            println!("Caching OS artifacts in {}", config.directory);
+            Some(cache)
+        }
+        None => None,
+    };
+
     let app_state = AppState {
         engine: Engine::from(tera),
         runtime_config,
         prometheus_handle,
+        artifact_cache,
     };
 
     let app = Router::new()
@@ -104,6 +131,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .merge(routes::ipxe::get_router("/api/v0/pxe"))
         .merge(routes::cloud_init::get_router("/api/v0/cloud-init"))
         .merge(routes::tls::get_router("/api/v0/tls"))
+        .merge(routes::artifacts::get_router(
+            "/artifacts",
+            "/api/v0/artifacts",
+        ))
         .route_layer(axum::middleware::from_fn(middleware::logging::logger))
         .layer(map_response(middleware::fix_content_length_header))
         .layer(middleware::metrics::MetricLayer::default())
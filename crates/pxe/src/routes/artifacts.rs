@@ -0,0 +1,74 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::{Request, StatusCode};
+use axum::response::{IntoResponse, Json, Redirect, Response};
+use axum::routing::get;
+use tower::ServiceExt;
+use tower_http::services::ServeFile;
+
+use crate::artifact_cache::store::is_valid_key;
+use crate::common::AppState;
+
+async fn artifact(
+    Path(key): Path<String>,
+    state: State<AppState>,
+    request: Request<Body>,
+) -> Response {
+    let Some(cache) = state.artifact_cache.as_ref() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !is_valid_key(&key) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    if !cache.store().touch(&key) {
+        // Another replica cached it, or it's still being downloaded
+        return match cache.origin(&key) {
+            Some(url) => Redirect::temporary(&url).into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        };
+    }
+
+    match ServeFile::new(cache.store().path(&key))
+        .oneshot(request)
+        .await
+    {
+        Ok(response) => response.into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+async fn status(state: State<AppState>) -> impl IntoResponse {
+    Json(
+        state
+            .artifact_cache
+            .as_ref()
+            .map(|cache| cache.status())
+            .unwrap_or_default(),
+    )
+}
+
+/// Cached artifacts are served from `{path_prefix}/{key}`, the download status of every
+/// artifact from `{status_path}`.
+pub fn get_router(path_prefix: &str, status_path: &str) -> Router<AppState> {
+    Router::new()
+        .route(format!("{path_prefix}/{{key}}").as_str(), get(artifact))
+        .route(status_path, get(status))
+}
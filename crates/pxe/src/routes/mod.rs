@@ -19,6 +19,7 @@ use std::net::IpAddr;
 use ::rpc::forge as rpc;
 use ::rpc::forge_tls_client::{self, ApiConfig, ForgeClientConfig};
 
+pub(crate) mod artifacts;
 pub(crate) mod cloud_init;
 pub(crate) mod ipxe;
 pub(crate) mod metrics;
@@ -16,7 +16,7 @@
  */
 use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 
 use carbide_uuid::UuidConversionError;
@@ -40,6 +40,9 @@ pub struct DhcpConfig {
     pub carbide_ntpservers: Vec<Ipv4Addr>,
     pub carbide_provisioning_server_ipv4: Ipv4Addr,
     pub carbide_dhcp_server: Ipv4Addr,
+    // Advertised to DHCPv6 clients and in router advertisements. Older configs don't have it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub carbide_nameservers_ipv6: Vec<Ipv6Addr>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -72,6 +75,7 @@ impl Default for DhcpConfig {
             // These two must be updated with valid values.
             carbide_provisioning_server_ipv4: Ipv4Addr::from([127, 0, 0, 1]),
             carbide_dhcp_server: Ipv4Addr::from([127, 0, 0, 1]),
+            carbide_nameservers_ipv6: vec![],
         }
     }
 }
@@ -113,7 +117,20 @@ pub struct InterfaceInfo {
     pub booturl: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mtu: Option<u32>,
+    // Present only on dual-stack interfaces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv6: Option<InterfaceIpv6Info>,
 }
+
+/// IPv6 half of a dual-stack interface, served by dhcp-server over DHCPv6 and router
+/// advertisements.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceIpv6Info {
+    pub address: Ipv6Addr,
+    /// Interface prefix in CIDR notation. Advertised as on-link in router advertisements.
+    pub prefix: String,
+}
+
 impl Default for InterfaceInfo {
     fn default() -> Self {
         InterfaceInfo {
@@ -123,6 +140,7 @@ impl Default for InterfaceInfo {
             fqdn: Default::default(),
             booturl: None,
             mtu: None,
+            ipv6: None,
         }
     }
 }
@@ -185,6 +203,16 @@ impl TryFrom<::rpc::forge::FlatInterfaceConfig> for InterfaceInfo {
     fn try_from(value: ::rpc::forge::FlatInterfaceConfig) -> Result<Self, Self::Error> {
         let gateway = Ipv4Network::from_str(&value.gateway)?.ip();
 
+        let ipv6 = value
+            .ipv6_interface_config
+            .map(|v6| -> Result<_, DhcpDataError> {
+                Ok(InterfaceIpv6Info {
+                    address: v6.ip.parse()?,
+                    prefix: v6.interface_prefix,
+                })
+            })
+            .transpose()?;
+
         Ok(InterfaceInfo {
             address: value.ip.parse()?,
             gateway,
@@ -192,6 +220,7 @@ impl TryFrom<::rpc::forge::FlatInterfaceConfig> for InterfaceInfo {
             fqdn: value.fqdn,
             booturl: value.booturl,
             mtu: value.mtu,
+            ipv6,
         })
     }
 }
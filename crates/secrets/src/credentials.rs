@@ -399,6 +399,11 @@ pub enum CredentialKey {
     MachineIdentityEncryptionKey {
         key_id: String,
     },
+    /// HMAC signing secret of a state change webhook endpoint, by endpoint name.
+    /// Returns `UsernamePassword { username: endpoint, password: secret }`.
+    StateChangeWebhook {
+        endpoint: String,
+    },
 }
 
 /// CredentialPrefix identifies a category of
@@ -422,6 +427,7 @@ pub enum CredentialPrefix {
     SwitchNvosAdmin,
     MqttAuth,
     MachineIdentityEncryptionKey,
+    StateChangeWebhook,
 }
 
 impl CredentialPrefix {
@@ -444,6 +450,7 @@ impl CredentialPrefix {
             Self::SwitchNvosAdmin => "switch_nvos/",
             Self::MqttAuth => "mqtt/",
             Self::MachineIdentityEncryptionKey => "machine_identity/",
+            Self::StateChangeWebhook => "state_change_webhooks/",
         }
     }
 
@@ -465,6 +472,7 @@ impl CredentialPrefix {
             Self::SwitchNvosAdmin,
             Self::MqttAuth,
             Self::MachineIdentityEncryptionKey,
+            Self::StateChangeWebhook,
         ]
     }
 }
@@ -491,6 +499,7 @@ impl CredentialKey {
             Self::MachineIdentityEncryptionKey { .. } => {
                 CredentialPrefix::MachineIdentityEncryptionKey
             }
+            Self::StateChangeWebhook { .. } => CredentialPrefix::StateChangeWebhook,
         }
     }
 
@@ -582,6 +591,9 @@ impl CredentialKey {
             CredentialKey::MachineIdentityEncryptionKey { key_id } => {
                 Cow::from(format!("machine_identity/encryption_keys/{key_id}"))
             }
+            CredentialKey::StateChangeWebhook { endpoint } => {
+                Cow::from(format!("state_change_webhooks/{endpoint}/hmac"))
+            }
             CredentialKey::Bgp { credential_type } => match credential_type {
                 BgpCredentialType::SiteWideLeafPassword => Cow::from("bgp/leaf/site/auth"),
             },
@@ -809,6 +821,12 @@ mod tests {
                 },
                 "mqtt/",
             ),
+            (
+                CredentialKey::StateChangeWebhook {
+                    endpoint: "hook1".to_string(),
+                },
+                "state_change_webhooks/",
+            ),
         ];
 
         for (key, expected_prefix) in &cases {
@@ -880,6 +898,9 @@ mod tests {
             CredentialKey::MachineIdentityEncryptionKey {
                 key_id: "k".to_string(),
             },
+            CredentialKey::StateChangeWebhook {
+                endpoint: "e".to_string(),
+            },
         ];
 
         for key in &keys {
@@ -899,6 +920,6 @@ mod tests {
     #[test]
     fn prefix_all_is_complete() {
         let all = CredentialPrefix::all();
-        assert_eq!(all.len(), 15);
+        assert_eq!(all.len(), 16);
     }
 }
@@ -82,6 +82,8 @@ pub struct CredentialSnapshot {
     pub mqtt_auth_by_credential_type: HashMap<MqttCredentialType, UsernamePassword>,
     pub machine_identity: Option<MachineIdentityConfig>,
     pub bmc_site_wide_root: Option<UsernamePassword>,
+    /// Map of state change webhook endpoint name to its HMAC signing secret
+    pub state_change_webhook_secrets: HashMap<String, String>,
 }
 
 impl CredentialSnapshot {
@@ -139,6 +141,13 @@ impl CredentialSnapshot {
             CredentialKey::BmcCredentials {
                 credential_type: BmcCredentialType::SiteWideRoot,
             } => self.bmc_site_wide_root.clone().map(Into::into),
+            CredentialKey::StateChangeWebhook { endpoint } => self
+                .state_change_webhook_secrets
+                .get(endpoint)
+                .map(|secret| Credentials::UsernamePassword {
+                    username: endpoint.clone(),
+                    password: secret.clone(),
+                }),
             _ => None,
         }
     }
@@ -198,6 +207,7 @@ mod tests {
             )]),
             machine_identity: None,
             bmc_site_wide_root: None,
+            state_change_webhook_secrets: HashMap::new(),
         }
     }
 
@@ -395,4 +405,28 @@ mod tests {
         assert_eq!(snap.get_credentials(&v2), Some(cred("v2", "secret-2")));
         assert_eq!(snap.get_credentials(&missing), None);
     }
+
+    #[test]
+    fn snapshot_state_change_webhook_secret() {
+        let snap = CredentialSnapshot {
+            state_change_webhook_secrets: HashMap::from([(
+                "tenant-automation".to_string(),
+                "hmac-secret".to_string(),
+            )]),
+            ..Default::default()
+        };
+
+        let key = CredentialKey::StateChangeWebhook {
+            endpoint: "tenant-automation".to_string(),
+        };
+        assert_eq!(
+            snap.get_credentials(&key),
+            Some(cred("tenant-automation", "hmac-secret"))
+        );
+
+        let missing = CredentialKey::StateChangeWebhook {
+            endpoint: "other".to_string(),
+        };
+        assert_eq!(snap.get_credentials(&missing), None);
+    }
 }
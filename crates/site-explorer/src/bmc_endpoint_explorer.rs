@@ -499,6 +499,14 @@ impl EndpointExplorer for BmcEndpointExplorer {
         self.credential_client.check_preconditions(metrics).await
     }
 
+    #[tracing::instrument(skip_all, fields(object_id=%address))]
+    async fn probe_service_root(
+        &self,
+        address: SocketAddr,
+    ) -> Result<Option<String>, EndpointExplorationError> {
+        self.redfish_client.probe_service_root(address).await
+    }
+
     async fn have_credentials(&self, interface: &MachineInterfaceSnapshot) -> bool {
         self.get_bmc_root_credentials(interface.mac_address)
             .await
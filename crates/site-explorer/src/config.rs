@@ -26,6 +26,7 @@ use carbide_utils::config::{
 };
 use chrono::Duration;
 use duration_str::{deserialize_duration, deserialize_duration_chrono};
+use ipnetwork::IpNetwork;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// SiteExplorer related configuration for hardware discovery and ingestion.
@@ -186,6 +187,11 @@ pub struct SiteExplorerConfig {
     /// CompareResult for side-by-side validation).
     #[serde(default = "SiteExplorerConfig::default_explore_mode")]
     pub explore_mode: SiteExplorerExploreMode,
+
+    /// Opt-in sweep of management network ranges for BMCs which never
+    /// DHCPed through carbide.
+    #[serde(default)]
+    pub subnet_sweep: SubnetSweepConfig,
 }
 
 impl Default for SiteExplorerConfig {
@@ -213,6 +219,7 @@ impl Default for SiteExplorerConfig {
             rotate_switch_nvos_credentials: Self::default_rotate_switch_nvos_credentials(),
             force_dpu_nic_mode: Arc::new(false.into()),
             explore_mode: Self::default_explore_mode(),
+            subnet_sweep: SubnetSweepConfig::default(),
         }
     }
 }
@@ -296,6 +303,193 @@ impl SiteExplorerConfig {
     }
 }
 
+/// Configuration for the active subnet sweep.
+///
+/// When enabled, site-explorer walks the configured CIDR ranges a few addresses
+/// per sweep run and probes each address for an anonymous Redfish service root.
+/// The sweep runs in its own task, so slow probes don't delay the exploration
+/// of known endpoints.
+/// Addresses that answer but are not known to carbide are recorded as swept
+/// BMC endpoints, so operators can claim them.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SubnetSweepConfig {
+    /// Whether the sweep is enabled. Defaults to false.
+    #[serde(default)]
+    pub enabled: bool,
+    /// The CIDR ranges to sweep.
+    #[serde(default)]
+    pub ranges: Vec<IpNetwork>,
+    /// CIDR ranges that must never be probed, even if they are part of `ranges`.
+    /// Single addresses can be excluded as /32 (or /128) networks.
+    #[serde(default)]
+    pub exclude: Vec<IpNetwork>,
+    /// The port the Redfish service root is probed on. Default is 443.
+    #[serde(default = "SubnetSweepConfig::default_port")]
+    pub port: u16,
+    /// How often the sweep runs. Default is 1 minute.
+    #[serde(
+        default = "SubnetSweepConfig::default_run_interval",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub run_interval: std::time::Duration,
+    /// How many addresses are probed in a single sweep run.
+    /// The sweep resumes where it stopped in the next run. Default is 64.
+    #[serde(default = "SubnetSweepConfig::default_probes_per_run")]
+    pub probes_per_run: usize,
+    /// The maximum amount of probes that are in flight at the same time. Default is 4.
+    #[serde(default = "SubnetSweepConfig::default_concurrent_probes")]
+    pub concurrent_probes: usize,
+    /// Minimum time between starting two consecutive probes. Default is 100ms.
+    #[serde(
+        default = "SubnetSweepConfig::default_probe_interval",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub probe_interval: std::time::Duration,
+    /// How long a single probe may take before the address is treated as
+    /// not responding. Default is 5 seconds.
+    #[serde(
+        default = "SubnetSweepConfig::default_probe_timeout",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub probe_timeout: std::time::Duration,
+    /// Swept endpoints which did not answer a probe for this long are removed.
+    /// Needs to be longer than a full pass over all ranges, see
+    /// [`SubnetSweepConfig::sweep_cycle_estimate`]. Default is 7 days.
+    #[serde(
+        default = "SubnetSweepConfig::default_stale_after",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "as_std_duration"
+    )]
+    pub stale_after: std::time::Duration,
+}
+
+impl Default for SubnetSweepConfig {
+    fn default() -> Self {
+        SubnetSweepConfig {
+            enabled: false,
+            ranges: Vec::new(),
+            exclude: Vec::new(),
+            port: Self::default_port(),
+            run_interval: Self::default_run_interval(),
+            probes_per_run: Self::default_probes_per_run(),
+            concurrent_probes: Self::default_concurrent_probes(),
+            probe_interval: Self::default_probe_interval(),
+            probe_timeout: Self::default_probe_timeout(),
+            stale_after: Self::default_stale_after(),
+        }
+    }
+}
+
+impl SubnetSweepConfig {
+    /// Ranges larger than this are rejected, since sweeping them would take
+    /// far too long to be useful and is most likely a configuration mistake.
+    pub const MAX_RANGE_PREFIX_BITS: u32 = 16;
+
+    pub const fn default_port() -> u16 {
+        443
+    }
+
+    pub const fn default_run_interval() -> std::time::Duration {
+        std::time::Duration::from_secs(60)
+    }
+
+    pub const fn default_probes_per_run() -> usize {
+        64
+    }
+
+    pub const fn default_concurrent_probes() -> usize {
+        4
+    }
+
+    pub const fn default_probe_interval() -> std::time::Duration {
+        std::time::Duration::from_millis(100)
+    }
+
+    pub const fn default_probe_timeout() -> std::time::Duration {
+        std::time::Duration::from_secs(5)
+    }
+
+    pub const fn default_stale_after() -> std::time::Duration {
+        std::time::Duration::from_secs(7 * 24 * 60 * 60)
+    }
+
+    /// Checks that the sweep configuration is safe to run.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.ranges.is_empty() {
+            return Err("subnet_sweep.ranges must not be empty when the sweep is enabled".into());
+        }
+        if self.probes_per_run == 0 {
+            return Err("subnet_sweep.probes_per_run must be greater than 0".into());
+        }
+        if self.concurrent_probes == 0 {
+            return Err("subnet_sweep.concurrent_probes must be greater than 0".into());
+        }
+        for range in &self.ranges {
+            let host_bits = u32::from(max_prefix(range)) - u32::from(range.prefix());
+            if host_bits > Self::MAX_RANGE_PREFIX_BITS {
+                return Err(format!(
+                    "subnet_sweep range {range} is too large, ranges may contain at most 2^{} addresses",
+                    Self::MAX_RANGE_PREFIX_BITS
+                ));
+            }
+        }
+        let cycle = self.sweep_cycle_estimate();
+        if self.stale_after <= cycle {
+            return Err(format!(
+                "subnet_sweep.stale_after ({:?}) must be longer than a full pass over all ranges, which can take up to {cycle:?}",
+                self.stale_after
+            ));
+        }
+        Ok(())
+    }
+
+    /// Upper bound for the time it takes to probe every address of every range once.
+    ///
+    /// A run is assumed to take as long as probing `probes_per_run` addresses
+    /// which all time out, or `run_interval` if that is longer. Excluded and
+    /// known addresses are not probed, so actual passes are usually shorter.
+    pub fn sweep_cycle_estimate(&self) -> std::time::Duration {
+        let addresses: u128 = self
+            .ranges
+            .iter()
+            .map(range_size)
+            .fold(0, u128::saturating_add);
+        let probes_per_run = self.probes_per_run.max(1);
+        let runs = addresses.div_ceil(probes_per_run as u128);
+
+        let probe_rounds = probes_per_run.div_ceil(self.concurrent_probes.max(1));
+        let run_time = self
+            .probe_interval
+            .saturating_mul(u32::try_from(probes_per_run).unwrap_or(u32::MAX))
+            .max(
+                self.probe_timeout
+                    .saturating_mul(u32::try_from(probe_rounds).unwrap_or(u32::MAX)),
+            )
+            .max(self.run_interval);
+
+        run_time.saturating_mul(u32::try_from(runs).unwrap_or(u32::MAX))
+    }
+}
+
+fn max_prefix(network: &IpNetwork) -> u8 {
+    match network {
+        IpNetwork::V4(_) => 32,
+        IpNetwork::V6(_) => 128,
+    }
+}
+
+/// The number of addresses in the range
+pub(crate) fn range_size(range: &IpNetwork) -> u128 {
+    let host_bits = u32::from(max_prefix(range) - range.prefix());
+    1u128.checked_shl(host_bits).unwrap_or(u128::MAX)
+}
+
 pub fn bmc_proxy(s: Option<HostPortPair>) -> Arc<ArcSwap<Option<HostPortPair>>> {
     Arc::new(ArcSwap::new(Arc::new(s)))
 }
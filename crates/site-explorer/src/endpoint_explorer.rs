@@ -44,6 +44,15 @@ pub trait EndpointExplorer: Send + Sync + 'static {
         boot_interface_mac: Option<MacAddress>,
     ) -> Result<EndpointExplorationReport, EndpointExplorationError>;
 
+    /// Probes an address that is not known to carbide for an anonymous Redfish
+    /// service root, without attempting to authenticate.
+    ///
+    /// Returns the `Vendor` reported by the service root, if any.
+    async fn probe_service_root(
+        &self,
+        address: SocketAddr,
+    ) -> Result<Option<String>, EndpointExplorationError>;
+
     async fn check_preconditions(
         &self,
         metrics: &mut SiteExplorationMetrics,
@@ -43,11 +43,12 @@ use librms::RmsApi;
 use mac_address::MacAddress;
 use model::expected_entity::ExpectedEntity;
 use model::expected_power_shelf::ExpectedPowerShelf;
-use model::machine::MachineInterfaceSnapshot;
 use model::machine::machine_search_config::MachineSearchConfig;
+use model::machine::{InstanceState, MachineInterfaceSnapshot, ManagedHostState};
 use model::machine_interface::InterfaceType;
 use model::power_shelf::{NewPowerShelf, PowerShelfConfig};
 use model::resource_pool::common::CommonPools;
+use model::site_explorer::report_history::{ExplorationReportDiff, ReportChangeCategory};
 use model::site_explorer::{
     EndpointExplorationError, EndpointExplorationReport, EndpointType, ExploredDpu,
     ExploredEndpoint, ExploredManagedHost, ExploredManagedSwitch, MachineExpectation, NicMode,
@@ -79,6 +80,8 @@ use model::expected_machine::DpuMode;
 use model::firmware::FirmwareComponentType;
 use model::machine_interface_address::MachineInterfaceAssociation;
 use model::network_segment::NetworkSegmentType;
+mod subnet_sweep;
+use subnet_sweep::SubnetSweepTask;
 mod switch_creator;
 use carbide_uuid::rack::RackId;
 use model::rack::Rack;
@@ -223,6 +226,7 @@ pub struct SiteExplorer {
     machine_creator: MachineCreator,
     switch_creator: SwitchCreator,
     boot_order_tracker: BootOrderTracker,
+    subnet_sweep: Arc<SubnetSweepTask>,
     // rms_client: Option<Arc<dyn RmsApi>>,
 }
 
@@ -266,6 +270,13 @@ impl SiteExplorer {
                 database_connection.clone(),
                 explorer_config.clone(),
             ),
+            subnet_sweep: Arc::new(SubnetSweepTask::new(
+                database_connection.clone(),
+                explorer_config.subnet_sweep.clone(),
+                explorer_config.enabled.clone(),
+                endpoint_explorer.clone(),
+                work_lock_manager_handle.clone(),
+            )),
             database_connection,
             config: explorer_config,
             metric_holder,
@@ -276,13 +287,23 @@ impl SiteExplorer {
         }
     }
 
-    /// Start the SiteExplorer background task. The task always runs and checks
-    /// `config.enabled` each iteration, allowing runtime pause/unpause via the API.
+    /// Start the SiteExplorer background task, and the subnet sweep task if the
+    /// sweep is enabled. The tasks always run and check `config.enabled` each
+    /// iteration, allowing runtime pause/unpause via the API.
     pub fn start(
         mut self,
         join_set: &mut JoinSet<()>,
         cancel_token: CancellationToken,
     ) -> io::Result<()> {
+        if self.subnet_sweep.is_enabled() {
+            let subnet_sweep = self.subnet_sweep.clone();
+            let cancel_token = cancel_token.clone();
+            join_set
+                .build_task()
+                .name("site_explorer_subnet_sweep")
+                .spawn(async move { subnet_sweep.run(cancel_token).await })?;
+        }
+
         join_set
             .build_task()
             .name("site_explorer")
@@ -406,6 +427,12 @@ impl SiteExplorer {
             }
         }
 
+        // The subnet sweep runs in its own task, report its most recent run
+        let sweep_stats = self.subnet_sweep.last_run();
+        metrics.subnet_sweep_probes = sweep_stats.probes;
+        metrics.subnet_sweep_responses = sweep_stats.responses;
+        metrics.swept_bmc_endpoints_unclaimed = sweep_stats.unclaimed;
+
         // Cache all other metrics that have been captured in this iteration.
         // Those will be queried by OTEL on demand
         self.metric_holder.update_metrics(metrics);
@@ -413,6 +440,12 @@ impl SiteExplorer {
         res
     }
 
+    /// Runs a single iteration of the subnet sweep, independent of whether
+    /// the sweep task was started
+    pub async fn run_single_subnet_sweep(&self) -> SiteExplorerResult<()> {
+        self.subnet_sweep.run_single_iteration().await
+    }
+
     /// Audits and collects metrics of _all_ explored results vs. _all_ expected machines, not a single exploration cycle.
     /// Also updates the Site Explorer Health Report for all explored endpoints based on the last exploration data.
     ///
@@ -429,6 +462,16 @@ impl SiteExplorer {
         // not just the subset in the current run.
         let explored_endpoints = db::explored_endpoints::find_all(txn.as_pgconn()).await?;
         let explored_managed_hosts = db::explored_managed_host::find_all(txn.as_pgconn()).await?;
+        let mut unacknowledged_drift: HashMap<IpAddr, Vec<_>> = HashMap::new();
+        for record in
+            db::explored_endpoint_report_history::find_unacknowledged_unexpected(txn.as_pgconn())
+                .await?
+        {
+            unacknowledged_drift
+                .entry(record.address)
+                .or_default()
+                .push(record);
+        }
 
         txn.rollback().await?;
 
@@ -513,6 +556,40 @@ impl SiteExplorer {
                 }
             }
 
+            if let Some(records) = unacknowledged_drift.get(&ep.address) {
+                // Records are sorted from newest to oldest
+                let categories: Vec<_> = records
+                    .iter()
+                    .flat_map(|record| record.diff.categories())
+                    .sorted()
+                    .dedup()
+                    .collect();
+                // A changed boot order alone doesn't make the host unusable
+                let classifications = if categories
+                    .iter()
+                    .all(|category| *category == ReportChangeCategory::BootOrder)
+                {
+                    vec![]
+                } else {
+                    vec![health_report::HealthAlertClassification::prevent_allocations()]
+                };
+                let since = records.last().map(|record| record.time).unwrap_or_default();
+                new_health_report
+                    .alerts
+                    .push(health_report::HealthProbeAlert {
+                        id: "HardwareDrift".parse().unwrap(),
+                        target: Some(ep.address.to_string()),
+                        in_alert_since: None,
+                        message: format!(
+                            "{} unacknowledged hardware changes since {since}: {}",
+                            records.len(),
+                            categories.iter().join(", ")
+                        ),
+                        tenant_message: None,
+                        classifications,
+                    });
+            }
+
             let expected_machine = expected_endpoint_index.matched_expected_machine(&ep.address);
 
             let (machine_type, expected) = match ep.report.is_dpu() {
@@ -1716,7 +1793,7 @@ impl SiteExplorer {
                                     "Initial exploration of endpoint"
                                 );
                             }
-                            db::explored_endpoints::try_update(
+                            let updated = db::explored_endpoints::try_update(
                                 address,
                                 old_version,
                                 &report,
@@ -1724,6 +1801,16 @@ impl SiteExplorer {
                                 &mut txn,
                             )
                             .await?;
+                            if updated {
+                                record_report_history(
+                                    &mut txn,
+                                    address,
+                                    old_version.increment(),
+                                    old_report,
+                                    &report,
+                                )
+                                .await?;
+                            }
                         }
                         Err(e) => {
                             // If an endpoint can not be explored we don't delete the known information, since it's
@@ -2753,6 +2840,93 @@ fn should_alert_power_state(power_state: PowerState) -> bool {
     )
 }
 
+/// Stores `report` in the report history of the endpoint if its hardware
+/// differs from `previous`.
+///
+/// Changes on a Machine that is `Ready` or `Assigned` are flagged as unexpected,
+/// since nothing should touch the hardware or firmware of such a Machine.
+async fn record_report_history(
+    txn: &mut sqlx::PgConnection,
+    address: IpAddr,
+    report_version: config_version::ConfigVersion,
+    previous: &EndpointExplorationReport,
+    report: &EndpointExplorationReport,
+) -> SiteExplorerResult<()> {
+    let Some(diff) = ExplorationReportDiff::between(previous, report) else {
+        return Ok(());
+    };
+    if diff.is_empty() {
+        return Ok(());
+    }
+
+    let machine_id = match report.machine_id {
+        Some(machine_id) => Some(machine_id),
+        None => db::machine::find_id_by_bmc_ip(&mut *txn, &address).await?,
+    };
+    let machine = match machine_id {
+        Some(machine_id) => {
+            db::machine::find_one(
+                &mut *txn,
+                &machine_id,
+                MachineSearchConfig {
+                    include_dpus: true,
+                    include_predicted_host: true,
+                    ..Default::default()
+                },
+            )
+            .await?
+        }
+        None => None,
+    };
+    let machine_state = machine.as_ref().map(|machine| machine.current_state());
+    // Carbide itself changes firmware and boot order while it provisions, reprovisions
+    // or cleans up hosts, so only idle hosts are expected to keep their hardware.
+    // Tenants are free to change the boot order of their instances.
+    let unexpected = match machine_state {
+        Some(ManagedHostState::Ready) => true,
+        Some(ManagedHostState::Assigned {
+            instance_state: InstanceState::Ready,
+        }) => diff
+            .changes
+            .iter()
+            .any(|change| change.category != ReportChangeCategory::BootOrder),
+        _ => false,
+    };
+    let machine_state = machine_state.map(|state| state.to_string());
+
+    let changes = diff.changes.iter().map(|c| c.to_string()).join("; ");
+    if unexpected {
+        tracing::warn!(
+            %address,
+            machine_id = ?machine_id,
+            machine_state = ?machine_state,
+            %changes,
+            "Unexpected hardware drift detected"
+        );
+    } else {
+        tracing::info!(
+            %address,
+            machine_id = ?machine_id,
+            machine_state = ?machine_state,
+            %changes,
+            "Hardware of endpoint changed"
+        );
+    }
+
+    db::explored_endpoint_report_history::persist(
+        txn,
+        address,
+        report_version,
+        report,
+        &diff,
+        machine_state.as_deref(),
+        unexpected,
+    )
+    .await?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use config_version::ConfigVersion;
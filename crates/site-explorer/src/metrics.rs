@@ -125,6 +125,12 @@ pub struct SiteExplorationMetrics {
     /// These are issues that prevent a host from being paired with its dpu(s)
     /// and require manual intervention.
     pub host_dpu_pairing_blockers: HashMap<String, usize>,
+    /// Addresses probed by the subnet sweep
+    pub subnet_sweep_probes: usize,
+    /// Addresses which answered a subnet sweep probe with a Redfish service root
+    pub subnet_sweep_responses: usize,
+    /// Swept BMC endpoints that are waiting to be claimed by an operator
+    pub swept_bmc_endpoints_unclaimed: usize,
 }
 
 impl Default for SiteExplorationMetrics {
@@ -159,6 +165,9 @@ impl SiteExplorationMetrics {
             endpoint_explorations_expected_power_shelves_missing_overall_count: 0,
             expected_machines_sku_count: HashMap::new(),
             host_dpu_pairing_blockers: HashMap::new(),
+            subnet_sweep_probes: 0,
+            subnet_sweep_responses: 0,
+            swept_bmc_endpoints_unclaimed: 0,
         }
     }
 
@@ -518,6 +527,49 @@ impl SiteExplorerInstruments {
                 .build();
         }
 
+        {
+            let metrics = shared_metrics.clone();
+            meter
+                .u64_observable_gauge("carbide_site_explorer_subnet_sweep_probes_count")
+                .with_description("The amount of addresses probed in the last subnet sweep run")
+                .with_callback(move |observer| {
+                    metrics.if_available(|metrics, attrs| {
+                        observer.observe(metrics.subnet_sweep_probes as u64, attrs);
+                    })
+                })
+                .build();
+        }
+
+        {
+            let metrics = shared_metrics.clone();
+            meter
+                .u64_observable_gauge("carbide_site_explorer_subnet_sweep_responses_count")
+                .with_description(
+                    "The amount of addresses that answered with a Redfish service root in the last subnet sweep run",
+                )
+                .with_callback(move |observer| {
+                    metrics.if_available(|metrics, attrs| {
+                        observer.observe(metrics.subnet_sweep_responses as u64, attrs);
+                    })
+                })
+                .build();
+        }
+
+        {
+            let metrics = shared_metrics.clone();
+            meter
+                .u64_observable_gauge("carbide_site_explorer_swept_bmc_endpoints_unclaimed_count")
+                .with_description(
+                    "The total number of BMC endpoints found by the subnet sweep that are not known to carbide",
+                )
+                .with_callback(move |observer| {
+                    metrics.if_available(|metrics, attrs| {
+                        observer.observe(metrics.swept_bmc_endpoints_unclaimed as u64, attrs);
+                    })
+                })
+                .build();
+        }
+
         {
             let metrics = shared_metrics.clone();
             meter
@@ -136,6 +136,20 @@ impl RedfishClient {
         Ok(vendor)
     }
 
+    pub async fn probe_service_root(
+        &self,
+        bmc_ip_address: SocketAddr,
+    ) -> Result<Option<String>, EndpointExplorationError> {
+        let client = self
+            .create_anon_redfish_client(bmc_ip_address)
+            .await
+            .map_err(map_redfish_client_creation_error)?;
+
+        let service_root = client.get_service_root().await.map_err(map_redfish_error)?;
+
+        Ok(service_root.vendor)
+    }
+
     pub async fn validate_bmc_credentials(
         &self,
         bmc_ip_address: SocketAddr,
@@ -0,0 +1,520 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Active discovery of BMCs which never DHCPed through carbide.
+//!
+//! The sweep runs as its own periodic task next to the site-explorer runs.
+//! Each run walks a few addresses of the configured CIDR ranges, and remembers
+//! where it stopped so that the next run continues from there. Once all ranges
+//! have been walked it starts over.
+
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use carbide_utils::periodic_timer::PeriodicTimer;
+use chrono::Utc;
+use db::work_lock_manager::WorkLockManagerHandle;
+use futures_util::{StreamExt, TryFutureExt};
+use ipnetwork::IpNetwork;
+use model::site_explorer::EndpointExplorationError;
+use sqlx::PgPool;
+use tokio_util::sync::CancellationToken;
+
+use crate::EndpointExplorer;
+use crate::config::{SubnetSweepConfig, range_size};
+use crate::errors::{SiteExplorerError, SiteExplorerResult};
+
+/// The outcome of probing a single address
+pub type ProbeResult = (IpAddr, Result<Option<String>, EndpointExplorationError>);
+
+/// Position of the sweep within the configured ranges
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct SweepCursor {
+    range: usize,
+    offset: u128,
+}
+
+pub struct SubnetSweeper {
+    config: SubnetSweepConfig,
+    cursor: Mutex<SweepCursor>,
+}
+
+impl SubnetSweeper {
+    pub fn new(config: SubnetSweepConfig) -> Self {
+        Self {
+            config,
+            cursor: Mutex::new(SweepCursor::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.config.ranges.is_empty()
+    }
+
+    pub fn port(&self) -> u16 {
+        self.config.port
+    }
+
+    pub fn stale_after(&self) -> std::time::Duration {
+        self.config.stale_after
+    }
+
+    /// Returns up to `probes_per_run` addresses that should be probed next.
+    ///
+    /// Network and broadcast addresses, excluded addresses and addresses for
+    /// which `is_known` returns true are skipped. Every address is visited at
+    /// most once per call, so this terminates even if all addresses are skipped.
+    pub fn next_candidates(&self, is_known: impl Fn(&IpAddr) -> bool) -> Vec<IpAddr> {
+        let ranges = &self.config.ranges;
+        let mut candidates = Vec::new();
+        if ranges.is_empty() {
+            return candidates;
+        }
+
+        let total = ranges
+            .iter()
+            .map(range_size)
+            .fold(0u128, u128::saturating_add);
+        let mut visited = 0u128;
+
+        let mut cursor = self
+            .cursor
+            .lock()
+            .expect("SubnetSweeper cursor mutex poisoned");
+        while candidates.len() < self.config.probes_per_run && visited < total {
+            if cursor.range >= ranges.len() {
+                *cursor = SweepCursor::default();
+            }
+            let range = &ranges[cursor.range];
+            if cursor.offset >= range_size(range) {
+                cursor.range += 1;
+                cursor.offset = 0;
+                continue;
+            }
+
+            let address = nth_address(range, cursor.offset);
+            cursor.offset += 1;
+            visited += 1;
+
+            if is_host_address(range, &address)
+                && !self.is_excluded(&address)
+                && !is_known(&address)
+            {
+                candidates.push(address);
+            }
+        }
+
+        candidates
+    }
+
+    /// Probes all `candidates` for a Redfish service root.
+    ///
+    /// At most `concurrent_probes` probes are in flight at the same time, and
+    /// two probes are never started less than `probe_interval` apart.
+    /// Probes that take longer than `probe_timeout` fail with a connection timeout.
+    pub async fn probe(
+        &self,
+        endpoint_explorer: &dyn EndpointExplorer,
+        candidates: Vec<IpAddr>,
+    ) -> Vec<ProbeResult> {
+        let start = tokio::time::Instant::now();
+        let interval = self.config.probe_interval;
+        let timeout = self.config.probe_timeout;
+        let port = self.config.port;
+
+        futures_util::stream::iter(candidates.into_iter().enumerate())
+            .map(|(i, address)| async move {
+                let index = u32::try_from(i).unwrap_or(u32::MAX);
+                tokio::time::sleep_until(start + interval.saturating_mul(index)).await;
+
+                let target = SocketAddr::new(address, port);
+                let probe = endpoint_explorer.probe_service_root(target);
+                let result = match tokio::time::timeout(timeout, probe).await {
+                    Ok(result) => result,
+                    Err(_) => Err(EndpointExplorationError::ConnectionTimeout {
+                        details: format!("no service root response within {timeout:?}"),
+                    }),
+                };
+                (address, result)
+            })
+            .buffer_unordered(self.config.concurrent_probes.max(1))
+            .collect()
+            .await
+    }
+
+    fn is_excluded(&self, address: &IpAddr) -> bool {
+        self.config
+            .exclude
+            .iter()
+            .any(|network| network.contains(*address))
+    }
+}
+
+/// Results of the most recent sweep run, reported with the site-explorer metrics
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SubnetSweepStats {
+    /// Addresses probed in the run
+    pub probes: usize,
+    /// Addresses which answered with a Redfish service root
+    pub responses: usize,
+    /// Swept BMC endpoints that are waiting to be claimed by an operator
+    pub unclaimed: usize,
+}
+
+/// Runs the [`SubnetSweeper`] every `subnet_sweep.run_interval`
+pub struct SubnetSweepTask {
+    database_connection: PgPool,
+    enabled: Arc<AtomicBool>,
+    endpoint_explorer: Arc<dyn EndpointExplorer>,
+    work_lock_manager_handle: WorkLockManagerHandle,
+    sweeper: SubnetSweeper,
+    last_run: Mutex<SubnetSweepStats>,
+}
+
+impl SubnetSweepTask {
+    const ITERATION_WORK_KEY: &'static str = "SiteExplorer::sweep_subnets";
+
+    pub fn new(
+        database_connection: PgPool,
+        config: SubnetSweepConfig,
+        enabled: Arc<AtomicBool>,
+        endpoint_explorer: Arc<dyn EndpointExplorer>,
+        work_lock_manager_handle: WorkLockManagerHandle,
+    ) -> Self {
+        Self {
+            database_connection,
+            enabled,
+            endpoint_explorer,
+            work_lock_manager_handle,
+            sweeper: SubnetSweeper::new(config),
+            last_run: Mutex::new(SubnetSweepStats::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sweeper.is_enabled()
+    }
+
+    /// Results of the most recent sweep run
+    pub fn last_run(&self) -> SubnetSweepStats {
+        *self
+            .last_run
+            .lock()
+            .expect("SubnetSweepTask stats mutex poisoned")
+    }
+
+    /// Sweeps until `cancel_token` is cancelled. Like the exploration, the
+    /// sweep pauses while site-explorer is disabled.
+    pub async fn run(&self, cancel_token: CancellationToken) {
+        let timer = PeriodicTimer::new(self.sweeper.config.run_interval);
+        loop {
+            let tick = timer.tick();
+
+            if self.enabled.load(Ordering::Relaxed) {
+                if let Err(error) = self.run_single_iteration().await {
+                    tracing::warn!(%error, "Subnet sweep failed");
+                }
+            } else {
+                tracing::debug!("SiteExplorer is disabled, skipping subnet sweep");
+            }
+
+            tokio::select! {
+                _ = tick.sleep() => {},
+                _ = cancel_token.cancelled() => {
+                    tracing::info!("Subnet sweep stop was requested");
+                    return;
+                }
+            }
+        }
+    }
+
+    // This function can just async when
+    // https://github.com/rust-lang/rust/issues/110011 will be
+    // implemented
+    #[track_caller]
+    fn txn_begin(&self) -> impl Future<Output = SiteExplorerResult<db::Transaction<'_>>> {
+        let loc = Location::caller();
+        db::Transaction::begin_with_location(&self.database_connection, loc).map_err(Into::into)
+    }
+
+    /// Probes the next batch of addresses in the configured sweep ranges and
+    /// records BMCs which carbide doesn't know about yet.
+    ///
+    /// Swept endpoints are claimed by creating a machine interface for their
+    /// address (e.g. via static IP assignment). Once that happened, the address
+    /// is part of the regular exploration and the swept record is dropped.
+    /// Records of endpoints which stopped answering probes are dropped after
+    /// `stale_after`.
+    pub async fn run_single_iteration(&self) -> SiteExplorerResult<()> {
+        let _work_lock = self
+            .work_lock_manager_handle
+            .try_acquire_lock(Self::ITERATION_WORK_KEY.into())
+            .await
+            .map_err(|e| {
+                SiteExplorerError::internal(format!("Failed to acquire connection: {e}"))
+            })?;
+
+        let mut txn = self.txn_begin().await?;
+        let mut known: HashSet<IpAddr> = db::machine_interface::find_all(&mut txn)
+            .await?
+            .into_iter()
+            .flat_map(|iface| iface.addresses)
+            .collect();
+        known.extend(
+            db::explored_endpoints::find_all(txn.as_pgconn())
+                .await?
+                .into_iter()
+                .map(|endpoint| endpoint.address),
+        );
+        let is_known = |address: &IpAddr| known.contains(address);
+
+        let swept_endpoints = db::swept_bmc_endpoints::find_all(&mut txn).await?;
+        let (claimed, mut unclaimed): (Vec<IpAddr>, HashSet<IpAddr>) = swept_endpoints
+            .iter()
+            .map(|endpoint| endpoint.address)
+            .partition(&is_known);
+        if !claimed.is_empty() {
+            tracing::info!(
+                ?claimed,
+                "Dropping swept BMC endpoints that are now known to carbide"
+            );
+            db::swept_bmc_endpoints::delete_many(&mut txn, &claimed).await?;
+        }
+        let stale_cutoff = chrono::Duration::from_std(self.sweeper.stale_after())
+            .ok()
+            .and_then(|stale_after| Utc::now().checked_sub_signed(stale_after))
+            .unwrap_or(chrono::DateTime::<Utc>::MIN_UTC);
+        let stale = db::swept_bmc_endpoints::delete_not_seen_since(&mut txn, stale_cutoff).await?;
+        if !stale.is_empty() {
+            tracing::info!(
+                ?stale,
+                "Dropping swept BMC endpoints that stopped answering sweep probes"
+            );
+            for address in &stale {
+                unclaimed.remove(address);
+            }
+        }
+        txn.commit().await?;
+
+        let candidates = self.sweeper.next_candidates(is_known);
+        let mut stats = SubnetSweepStats {
+            probes: candidates.len(),
+            ..Default::default()
+        };
+        let results = self
+            .sweeper
+            .probe(self.endpoint_explorer.as_ref(), candidates)
+            .await;
+
+        let mut txn = self.txn_begin().await?;
+        for (address, result) in results {
+            match result {
+                Ok(vendor) => {
+                    stats.responses += 1;
+                    if unclaimed.insert(address) {
+                        tracing::info!(%address, ?vendor, "Subnet sweep found an unknown BMC endpoint");
+                    }
+                    db::swept_bmc_endpoints::upsert(
+                        &mut txn,
+                        address,
+                        self.sweeper.port(),
+                        vendor.as_deref(),
+                    )
+                    .await?;
+                }
+                Err(error) => {
+                    tracing::trace!(%address, %error, "Subnet sweep probe did not find a Redfish service root");
+                }
+            }
+        }
+        txn.commit().await?;
+
+        stats.unclaimed = unclaimed.len();
+        *self
+            .last_run
+            .lock()
+            .expect("SubnetSweepTask stats mutex poisoned") = stats;
+        Ok(())
+    }
+}
+
+fn nth_address(range: &IpNetwork, offset: u128) -> IpAddr {
+    match range {
+        IpNetwork::V4(network) => {
+            // The offset is always smaller than the size of the range
+            let base = u32::from(network.network());
+            IpAddr::V4(Ipv4Addr::from(base.wrapping_add(offset as u32)))
+        }
+        IpNetwork::V6(network) => {
+            let base = u128::from(network.network());
+            IpAddr::V6(Ipv6Addr::from(base.wrapping_add(offset)))
+        }
+    }
+}
+
+/// Whether the address can be assigned to a host, i.e. is neither the network
+/// nor the broadcast address of the range. Point-to-point ranges have neither.
+fn is_host_address(range: &IpNetwork, address: &IpAddr) -> bool {
+    match range {
+        IpNetwork::V4(network) if network.prefix() <= 30 => {
+            let address = *address;
+            address != IpAddr::V4(network.network()) && address != IpAddr::V4(network.broadcast())
+        }
+        IpNetwork::V6(network) if network.prefix() <= 126 => {
+            // The first address of an IPv6 subnet is the subnet-router anycast address
+            *address != IpAddr::V6(network.network())
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_sweeper(ranges: &[&str], exclude: &[&str], probes_per_run: usize) -> SubnetSweeper {
+        SubnetSweeper::new(SubnetSweepConfig {
+            enabled: true,
+            ranges: ranges.iter().map(|r| r.parse().unwrap()).collect(),
+            exclude: exclude.iter().map(|r| r.parse().unwrap()).collect(),
+            probes_per_run,
+            ..Default::default()
+        })
+    }
+
+    fn addrs(addresses: &[&str]) -> Vec<IpAddr> {
+        addresses.iter().map(|a| a.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn skips_network_and_broadcast_addresses() {
+        let sweeper = new_sweeper(&["10.0.0.0/30"], &[], 10);
+        assert_eq!(
+            sweeper.next_candidates(|_| false),
+            addrs(&["10.0.0.1", "10.0.0.2"])
+        );
+
+        let sweeper = new_sweeper(&["10.0.0.0/31", "10.0.1.5/32"], &[], 10);
+        assert_eq!(
+            sweeper.next_candidates(|_| false),
+            addrs(&["10.0.0.0", "10.0.0.1", "10.0.1.5"])
+        );
+    }
+
+    #[test]
+    fn skips_excluded_and_known_addresses() {
+        let sweeper = new_sweeper(&["10.0.0.0/29"], &["10.0.0.2/32", "10.0.0.4/31"], 10);
+        let known: IpAddr = "10.0.0.6".parse().unwrap();
+        assert_eq!(
+            sweeper.next_candidates(|addr| *addr == known),
+            addrs(&["10.0.0.1", "10.0.0.3"])
+        );
+    }
+
+    #[test]
+    fn continues_where_the_previous_run_stopped() {
+        let sweeper = new_sweeper(&["10.0.0.0/29", "10.0.1.0/30"], &[], 4);
+        assert_eq!(
+            sweeper.next_candidates(|_| false),
+            addrs(&["10.0.0.1", "10.0.0.2", "10.0.0.3", "10.0.0.4"])
+        );
+        assert_eq!(
+            sweeper.next_candidates(|_| false),
+            addrs(&["10.0.0.5", "10.0.0.6", "10.0.1.1", "10.0.1.2"])
+        );
+        // All ranges were walked, so the sweep starts over
+        assert_eq!(
+            sweeper.next_candidates(|_| false),
+            addrs(&["10.0.0.1", "10.0.0.2", "10.0.0.3", "10.0.0.4"])
+        );
+    }
+
+    #[test]
+    fn terminates_if_all_addresses_are_known() {
+        let sweeper = new_sweeper(&["10.0.0.0/24"], &[], 10);
+        assert!(sweeper.next_candidates(|_| true).is_empty());
+    }
+
+    #[test]
+    fn sweeps_ipv6_ranges() {
+        let sweeper = new_sweeper(&["fd00::/126"], &[], 10);
+        assert_eq!(
+            sweeper.next_candidates(|_| false),
+            addrs(&["fd00::1", "fd00::2", "fd00::3"])
+        );
+    }
+
+    #[test]
+    fn validates_config() {
+        let mut config = SubnetSweepConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.enabled = true;
+        assert!(config.validate().is_err());
+
+        config.ranges = vec!["10.0.0.0/16".parse().unwrap()];
+        assert!(config.validate().is_ok());
+
+        config.ranges = vec!["10.0.0.0/15".parse().unwrap()];
+        assert!(config.validate().is_err());
+
+        config.ranges = vec!["10.0.0.0/24".parse().unwrap()];
+        config.concurrent_probes = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_stale_after_shorter_than_a_sweep_cycle() {
+        let mut config = SubnetSweepConfig {
+            enabled: true,
+            ranges: vec!["10.0.0.0/24".parse().unwrap()],
+            probes_per_run: 64,
+            concurrent_probes: 4,
+            run_interval: std::time::Duration::from_secs(60),
+            probe_interval: std::time::Duration::from_millis(100),
+            probe_timeout: std::time::Duration::from_secs(5),
+            ..Default::default()
+        };
+        // 4 runs, each of which takes 16 rounds of probes timing out after 5s
+        assert_eq!(
+            config.sweep_cycle_estimate(),
+            std::time::Duration::from_secs(4 * 80)
+        );
+
+        config.stale_after = std::time::Duration::from_secs(4 * 80);
+        assert!(config.validate().is_err());
+        config.stale_after = std::time::Duration::from_secs(4 * 80 + 1);
+        assert!(config.validate().is_ok());
+
+        // Runs can't be shorter than the run interval
+        config.run_interval = std::time::Duration::from_secs(600);
+        assert!(config.validate().is_err());
+
+        // Each /16 takes 1024 runs, so 7 of them are the most the defaults allow
+        config.run_interval = SubnetSweepConfig::default_run_interval();
+        config.stale_after = SubnetSweepConfig::default_stale_after();
+        config.ranges = (0..7)
+            .map(|i| format!("10.{i}.0.0/16").parse().unwrap())
+            .collect();
+        assert!(config.validate().is_ok());
+        config.ranges.push("10.7.0.0/16".parse().unwrap());
+        assert!(config.validate().is_err());
+    }
+}
@@ -102,8 +102,7 @@ impl Forge for MockApiServer {
             .into_iter()
             .map(|(instance_id, mock_host)| forge::Instance {
                 id: Some(instance_id.clone()),
-                machine_id: Some(mock_host.machine_id),
-                ..Default::default()
+                ..mock_host.clone().into()
             })
             .collect::<Vec<_>>();
 
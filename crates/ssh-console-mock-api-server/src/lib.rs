@@ -36,6 +36,7 @@ use crate::generated::{common, machine_discovery};
 pub struct MockHost {
     pub machine_id: carbide_uuid::machine::MachineId,
     pub instance_id: Uuid,
+    pub tenant_organization_id: String,
     pub tenant_public_key: String,
     pub sys_vendor: &'static str,
     pub bmc_ip: IpAddr,
@@ -68,6 +69,13 @@ impl From<MockHost> for forge::Instance {
                 value: value.instance_id.to_string(),
             }),
             machine_id: Some(value.machine_id),
+            config: Some(forge::InstanceConfig {
+                tenant: Some(forge::TenantConfig {
+                    tenant_organization_id: value.tenant_organization_id,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
             ..Default::default()
         }
     }
@@ -24,6 +24,7 @@ use carbide_uuid::machine::MachineId;
 use chrono::{DateTime, Utc};
 use futures_util::FutureExt;
 use opentelemetry::KeyValue;
+use rpc::forge_api_client::ForgeApiClient;
 use russh::ChannelMsg;
 use tokio::net::TcpStream;
 use tokio::sync::{MutexGuard, broadcast, mpsc, oneshot};
@@ -32,13 +33,14 @@ use tokio::time::MissedTickBehavior;
 
 use crate::bmc::client_pool::BmcPoolMetrics;
 use crate::bmc::connection::{self, AtomicConnectionState, ConnectionDetails};
+use crate::bmc::console_sessions::ConsoleSessions;
 use crate::bmc::message_proxy::{
     ConnectionChangeMessage, ExecReply, ToBmcMessage, ToFrontendMessage,
 };
 use crate::config::Config;
-use crate::console_logger;
 use crate::shutdown_handle::ShutdownHandle;
 use crate::ssh_server::ServerMetrics;
+use crate::{console_logger, console_triggers};
 
 /// Spawn a connection to the given BMC in the background, returning a handle. Connections will
 /// be retried indefinitely, with exponential backoff, until a shutdown is signaled (ie. by dropping
@@ -46,6 +48,7 @@ use crate::ssh_server::ServerMetrics;
 pub fn spawn(
     connection_details: ConnectionDetails,
     config: Arc<Config>,
+    forge_api_client: ForgeApiClient,
     metrics: Arc<BmcPoolMetrics>,
 ) -> ClientHandle {
     // Shutdown handle for the retry loop that is retrying this connection
@@ -59,6 +62,9 @@ pub fn spawn(
     // Always consume messages from the frontend broadcast channel, even if there are no frontends.
     dev_null(broadcast_to_frontend_rx);
 
+    // Frontends attached to this console, and which of them may write to it
+    let sessions = Arc::new(ConsoleSessions::new(broadcast_to_frontend_tx.downgrade()));
+
     let connection_state = Arc::new(AtomicConnectionState::default());
     let machine_id = connection_details.machine_id();
     let kind = connection_details.kind();
@@ -72,6 +78,7 @@ pub fn spawn(
     let bmc_client = BmcClient {
         connection_details,
         config,
+        forge_api_client,
         connection_state: connection_state.clone(),
         broadcast_to_frontend_tx: broadcast_to_frontend_tx.clone(),
         shutdown_rx,
@@ -84,6 +91,7 @@ pub fn spawn(
     ClientHandle {
         to_bmc_msg_tx,
         broadcast_to_frontend_tx,
+        sessions,
         machine_id,
         shutdown_tx,
         join_handle,
@@ -95,6 +103,7 @@ pub fn spawn(
 struct BmcClient {
     connection_details: ConnectionDetails,
     config: Arc<Config>,
+    forge_api_client: ForgeApiClient,
     connection_state: Arc<AtomicConnectionState>,
     shutdown_rx: oneshot::Receiver<()>,
     broadcast_to_frontend_tx: broadcast::Sender<ToFrontendMessage>,
@@ -119,6 +128,18 @@ impl BmcClient {
             None
         };
 
+        // Spawn a task to raise health alerts from console output, if configured.
+        let triggers_handle = if self.config.console_triggers_enabled {
+            Some(console_triggers::spawn(
+                machine_id,
+                self.broadcast_to_frontend_tx.subscribe(),
+                self.config.clone(),
+                self.forge_api_client.clone(),
+            ))
+        } else {
+            None
+        };
+
         // Keep track of when we were last disconnected, for relaying status
         let last_disconnect_time: Arc<RwLock<Option<DateTime<Utc>>>> = Default::default();
 
@@ -275,11 +296,14 @@ impl BmcClient {
             }
         }
 
-        // Clean up: Shut down message relay and logger
+        // Clean up: Shut down message relay, logger and triggers
         bmc_message_relay.shutdown_and_wait().await;
         if let Some(logger_handle) = logger_handle {
             logger_handle.shutdown_and_wait().await;
         }
+        if let Some(triggers_handle) = triggers_handle {
+            triggers_handle.shutdown_and_wait().await;
+        }
     }
 }
 
@@ -470,6 +494,7 @@ pub struct ClientHandle {
     // Hold a copy of the tx for broadcasting to frontends, so that we can subscribe to it multiple
     // times.
     broadcast_to_frontend_tx: broadcast::Sender<ToFrontendMessage>,
+    sessions: Arc<ConsoleSessions>,
     shutdown_tx: oneshot::Sender<()>,
     join_handle: JoinHandle<()>,
     pub connection_state: Arc<AtomicConnectionState>, // pub for metrics gathering
@@ -494,6 +519,7 @@ impl ClientHandle {
             machine_id: self.machine_id,
             to_frontend_msg_weak_tx: self.broadcast_to_frontend_tx.downgrade(),
             to_bmc_msg_tx: self.to_bmc_msg_tx.clone(),
+            sessions: self.sessions.clone(),
             metrics,
             kind: self.kind,
         }
@@ -506,6 +532,7 @@ pub struct BmcConnectionSubscription {
     pub machine_id: MachineId,
     pub to_frontend_msg_weak_tx: broadcast::WeakSender<ToFrontendMessage>,
     pub to_bmc_msg_tx: mpsc::Sender<ToBmcMessage>,
+    pub sessions: Arc<ConsoleSessions>,
     pub kind: connection::Kind,
     // Not pub, to make sure we go through ClientHandle::subscribe() to build, so we get the
     // right metrics
@@ -412,6 +412,7 @@ impl BmcPool {
                 let bmc_session_handle = client::spawn(
                     connection_details,
                     self.config.clone(),
+                    self.forge_api_client.clone(),
                     self.metrics.clone(),
                 );
                 guard.insert(machine_id, bmc_session_handle);
@@ -0,0 +1,408 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{broadcast, oneshot};
+
+use crate::bmc::message_proxy::ToFrontendMessage;
+
+/// Arbitrates input between the frontend sessions attached to a single BMC console: At most one
+/// session holds the write lock, and every other session is a read-only observer. Changes to who
+/// is attached, or who holds the write lock, are announced to every attached session.
+pub struct ConsoleSessions {
+    inner: Mutex<Inner>,
+    to_frontend_msg_weak_tx: broadcast::WeakSender<ToFrontendMessage>,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_id: u64,
+    sessions: BTreeMap<u64, SessionEntry>,
+    writer: Option<u64>,
+}
+
+struct SessionEntry {
+    user: String,
+    peer_addr: String,
+    frontend: Frontend,
+    attached_at: DateTime<Utc>,
+    kick_tx: Option<oneshot::Sender<()>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frontend {
+    Ssh,
+    Websocket,
+}
+
+impl fmt::Display for Frontend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Frontend::Ssh => f.write_str("ssh"),
+            Frontend::Websocket => f.write_str("websocket"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub id: u64,
+    pub user: String,
+    pub peer_addr: String,
+    pub frontend: Frontend,
+    pub attached_at: DateTime<Utc>,
+    pub writer: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WriteAccess {
+    Granted,
+    ReadOnly { writer: String },
+}
+
+impl ConsoleSessions {
+    pub fn new(to_frontend_msg_weak_tx: broadcast::WeakSender<ToFrontendMessage>) -> Self {
+        Self {
+            inner: Mutex::new(Inner::default()),
+            to_frontend_msg_weak_tx,
+        }
+    }
+
+    /// Register a new session. The first session to attach gets the write lock. The returned
+    /// receiver fires if an admin disconnects the session.
+    pub fn attach(
+        self: &Arc<Self>,
+        user: Option<String>,
+        peer_addr: String,
+        frontend: Frontend,
+    ) -> (AttachedSession, oneshot::Receiver<()>) {
+        let (kick_tx, kick_rx) = oneshot::channel();
+        let mut inner = self.inner.lock().expect("lock poisoned");
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let user = user.unwrap_or_else(|| "<unknown>".to_string());
+        inner.sessions.insert(
+            id,
+            SessionEntry {
+                user: user.clone(),
+                peer_addr,
+                frontend,
+                attached_at: Utc::now(),
+                kick_tx: Some(kick_tx),
+            },
+        );
+        if inner.writer.is_none() {
+            inner.writer = Some(id);
+        }
+        self.announce(&inner, format!("{user} attached via {frontend}"));
+
+        (
+            AttachedSession {
+                id,
+                sessions: self.clone(),
+            },
+            kick_rx,
+        )
+    }
+
+    pub fn list(&self) -> Vec<SessionSummary> {
+        let inner = self.inner.lock().expect("lock poisoned");
+        inner
+            .sessions
+            .iter()
+            .map(|(id, entry)| SessionSummary {
+                id: *id,
+                user: entry.user.clone(),
+                peer_addr: entry.peer_addr.clone(),
+                frontend: entry.frontend,
+                attached_at: entry.attached_at,
+                writer: inner.writer == Some(*id),
+            })
+            .collect()
+    }
+
+    /// Force a session to disconnect. Returns false if there is no such session.
+    pub fn disconnect(&self, id: u64, by: &str) -> bool {
+        let mut inner = self.inner.lock().expect("lock poisoned");
+        let Some(entry) = inner.sessions.get_mut(&id) else {
+            return false;
+        };
+        if let Some(kick_tx) = entry.kick_tx.take() {
+            kick_tx.send(()).ok();
+        }
+        let user = entry.user.clone();
+        self.announce(&inner, format!("{user} [{id}] was disconnected by {by}"));
+        true
+    }
+
+    /// Send a notice to every attached session, along with the list of who is attached.
+    fn announce(&self, inner: &Inner, what: String) {
+        let attached = inner
+            .sessions
+            .iter()
+            .map(|(id, entry)| {
+                if inner.writer == Some(*id) {
+                    format!("{} [{id}] (writer)", entry.user)
+                } else {
+                    format!("{} [{id}]", entry.user)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Some(to_frontend_msg_tx) = self.to_frontend_msg_weak_tx.upgrade() {
+            to_frontend_msg_tx
+                .send(ToFrontendMessage::SessionsChanged(format!(
+                    "{what}. Attached: {attached}"
+                )))
+                .ok();
+        }
+    }
+}
+
+/// A session attached to a console. The session is detached when this is dropped.
+pub struct AttachedSession {
+    id: u64,
+    sessions: Arc<ConsoleSessions>,
+}
+
+impl AttachedSession {
+    /// Check whether this session may send input to the BMC. If nobody holds the write lock, this
+    /// session takes it.
+    pub fn write_access(&self) -> WriteAccess {
+        let mut inner = self.sessions.inner.lock().expect("lock poisoned");
+        match inner.writer {
+            Some(writer) if writer == self.id => WriteAccess::Granted,
+            Some(writer) => WriteAccess::ReadOnly {
+                writer: inner
+                    .sessions
+                    .get(&writer)
+                    .map(|entry| entry.user.clone())
+                    .unwrap_or_default(),
+            },
+            None if inner.sessions.contains_key(&self.id) => {
+                inner.writer = Some(self.id);
+                let user = &inner.sessions[&self.id].user;
+                self.sessions
+                    .announce(&inner, format!("{user} took the write lock"));
+                WriteAccess::Granted
+            }
+            None => WriteAccess::ReadOnly {
+                writer: String::new(),
+            },
+        }
+    }
+
+    /// Whether this session currently holds the write lock.
+    pub fn is_writer(&self) -> bool {
+        let inner = self.sessions.inner.lock().expect("lock poisoned");
+        inner.writer == Some(self.id)
+    }
+
+    /// Take the write lock, even if another session holds it. The previous writer becomes an
+    /// observer.
+    pub fn take_write_lock(&self) {
+        let mut inner = self.sessions.inner.lock().expect("lock poisoned");
+        if inner.writer == Some(self.id) || !inner.sessions.contains_key(&self.id) {
+            return;
+        }
+        let previous = inner
+            .writer
+            .replace(self.id)
+            .and_then(|previous| inner.sessions.get(&previous))
+            .map(|entry| entry.user.clone());
+        let user = &inner.sessions[&self.id].user;
+        let what = match previous {
+            Some(previous) => format!("{user} took the write lock from {previous}"),
+            None => format!("{user} took the write lock"),
+        };
+        self.sessions.announce(&inner, what);
+    }
+
+    /// Detach this session, releasing the write lock if it holds it. Safe to call more than once.
+    pub fn detach(&self) {
+        let mut inner = self.sessions.inner.lock().expect("lock poisoned");
+        let Some(entry) = inner.sessions.remove(&self.id) else {
+            return;
+        };
+        if inner.writer == Some(self.id) {
+            inner.writer = None;
+        }
+        self.sessions
+            .announce(&inner, format!("{} detached", entry.user));
+    }
+}
+
+impl Drop for AttachedSession {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SessionCommand {
+    TakeWriteLock,
+}
+
+/// Intercepts ssh-console's own escape sequences in user input, so they aren't sent to the BMC.
+/// Like SSH escapes, they're only recognized immediately after a newline:
+///
+/// - `~w`: Take the write lock
+pub struct SessionCommandFilter {
+    at_line_start: bool,
+    escape_pending: bool,
+}
+
+impl Default for SessionCommandFilter {
+    fn default() -> Self {
+        Self {
+            at_line_start: true,
+            escape_pending: false,
+        }
+    }
+}
+
+impl SessionCommandFilter {
+    /// Returns the data to forward to the BMC, and any commands found in the input.
+    pub fn filter(&mut self, data: &[u8]) -> (Vec<u8>, Vec<SessionCommand>) {
+        let mut forward = Vec::with_capacity(data.len());
+        let mut commands = Vec::new();
+        for &b in data {
+            if self.escape_pending {
+                self.escape_pending = false;
+                if b == b'w' {
+                    commands.push(SessionCommand::TakeWriteLock);
+                    self.at_line_start = false;
+                    continue;
+                }
+                forward.push(b'~');
+            } else if self.at_line_start && b == b'~' {
+                self.escape_pending = true;
+                continue;
+            }
+            forward.push(b);
+            self.at_line_start = b == b'\r' || b == b'\n';
+        }
+        (forward, commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn notices(rx: &mut broadcast::Receiver<ToFrontendMessage>) -> Vec<String> {
+        let mut notices = vec![];
+        while let Ok(msg) = rx.try_recv() {
+            if let ToFrontendMessage::SessionsChanged(notice) = msg {
+                notices.push(notice);
+            }
+        }
+        notices
+    }
+
+    #[test]
+    fn test_write_lock() {
+        let (tx, mut rx) = broadcast::channel(16);
+        let sessions = Arc::new(ConsoleSessions::new(tx.downgrade()));
+
+        let (alice, _) = sessions.attach(Some("alice".into()), "a".into(), Frontend::Ssh);
+        let (bob, _) = sessions.attach(Some("bob".into()), "b".into(), Frontend::Websocket);
+        assert_eq!(
+            notices(&mut rx),
+            vec![
+                "alice attached via ssh. Attached: alice [0] (writer)",
+                "bob attached via websocket. Attached: alice [0] (writer), bob [1]",
+            ]
+        );
+
+        assert_eq!(alice.write_access(), WriteAccess::Granted);
+        assert_eq!(
+            bob.write_access(),
+            WriteAccess::ReadOnly {
+                writer: "alice".into()
+            }
+        );
+
+        bob.take_write_lock();
+        assert_eq!(
+            notices(&mut rx),
+            vec!["bob took the write lock from alice. Attached: alice [0], bob [1] (writer)"]
+        );
+        assert_eq!(bob.write_access(), WriteAccess::Granted);
+        assert_eq!(
+            alice.write_access(),
+            WriteAccess::ReadOnly {
+                writer: "bob".into()
+            }
+        );
+
+        // When the writer leaves, the next session to type gets the lock.
+        std::mem::drop(bob);
+        assert_eq!(alice.write_access(), WriteAccess::Granted);
+        assert_eq!(
+            notices(&mut rx),
+            vec![
+                "bob detached. Attached: alice [0]",
+                "alice took the write lock. Attached: alice [0] (writer)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let (tx, _rx) = broadcast::channel(16);
+        let sessions = Arc::new(ConsoleSessions::new(tx.downgrade()));
+
+        let (_alice, _) = sessions.attach(Some("alice".into()), "a".into(), Frontend::Ssh);
+        let (bob, mut bob_kick_rx) = sessions.attach(None, "b".into(), Frontend::Ssh);
+        assert_eq!(sessions.list().len(), 2);
+        assert!(!sessions.list()[1].writer);
+
+        assert!(!sessions.disconnect(5, "admin"));
+        assert!(bob_kick_rx.try_recv().is_err());
+        assert!(sessions.disconnect(bob.id, "admin"));
+        assert!(bob_kick_rx.try_recv().is_ok());
+
+        bob.detach();
+        assert_eq!(sessions.list().len(), 1);
+        assert_eq!(sessions.list()[0].user, "alice");
+    }
+
+    #[test]
+    fn test_session_command_filter() {
+        let mut filter = SessionCommandFilter::default();
+        assert_eq!(
+            filter.filter(b"~w"),
+            (vec![], vec![SessionCommand::TakeWriteLock])
+        );
+        // Only recognized after a newline
+        assert_eq!(filter.filter(b"ls ~w\r"), (b"ls ~w\r".to_vec(), vec![]));
+        // Split across writes
+        assert_eq!(filter.filter(b"~"), (vec![], vec![]));
+        assert_eq!(
+            filter.filter(b"w"),
+            (vec![], vec![SessionCommand::TakeWriteLock])
+        );
+        // Other escapes are passed through
+        assert_eq!(filter.filter(b"\r~."), (b"\r~.".to_vec(), vec![]));
+    }
+}
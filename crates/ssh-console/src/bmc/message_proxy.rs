@@ -91,6 +91,8 @@ pub enum ToFrontendMessage {
     ConnectionChanged(ConnectionChangeMessage),
     /// A reply to the user pressing the Enter key when the BMC is disconnected
     InformDisconnectedSince(Option<DateTime<Utc>>),
+    /// A change in who is attached to the console, or who holds the write lock
+    SessionsChanged(String),
 }
 
 #[derive(Clone)]
@@ -117,6 +119,10 @@ impl From<ToFrontendMessage> for Arc<ChannelMsg> {
                 let data: Bytes = "--- Console not connected ---\r\n".into();
                 Arc::new(ChannelMsg::Data { data })
             }
+            ToFrontendMessage::SessionsChanged(notice) => {
+                let data: Bytes = format!("\r\n--- {notice} ---\r\n").into();
+                Arc::new(ChannelMsg::Data { data })
+            }
             ToFrontendMessage::Channel(msg) => msg,
         }
     }
@@ -19,6 +19,7 @@ pub mod client;
 pub mod client_pool;
 pub mod connection;
 mod connection_impl;
+pub mod console_sessions;
 pub mod message_proxy;
 mod pending_output_line;
 pub mod vendor;
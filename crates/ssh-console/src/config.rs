@@ -24,6 +24,7 @@ use std::time::Duration;
 use carbide_uuid::machine::MachineIdParseError;
 use duration_str::deserialize_duration;
 use forge_tls::client_config::ClientCert;
+use health_report::{HealthAlertClassification, HealthProbeId};
 use rpc::forge_api_client::ForgeApiClient;
 use rpc::forge_tls_client::{ApiConfig, ForgeClientConfig};
 use russh::keys::ssh_key::Fingerprint;
@@ -89,6 +90,24 @@ pub struct Config {
     pub console_logs_path: PathBuf,
     #[serde(default = "Defaults::console_logging_enabled")]
     pub console_logging_enabled: bool,
+    #[serde(default = "Defaults::session_recordings_path")]
+    pub session_recordings_path: PathBuf,
+    #[serde(default = "Defaults::session_recording_enabled")]
+    pub session_recording_enabled: bool,
+    #[serde(default = "Defaults::session_recordings_max_per_machine")]
+    pub session_recordings_max_per_machine: usize,
+    #[serde(
+        default = "Defaults::session_recordings_max_age",
+        serialize_with = "serialize_duration",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub session_recordings_max_age: Duration,
+    #[serde(default = "Defaults::recordings_address")]
+    pub recordings_address: SocketAddr,
+    #[serde(default)]
+    pub admin_root_ca_path: Option<PathBuf>,
+    #[serde(default)]
+    pub admin_cert_issuer_cns: Vec<String>,
     #[serde(default)]
     pub override_bmc_ssh_host: Option<String>,
     #[serde(
@@ -115,6 +134,95 @@ pub struct Config {
     pub log_rotate_max_rotated_files: usize,
     #[serde(default = "Defaults::cert_authorization")]
     pub openssh_certificate_authorization: CertAuthorization,
+    #[serde(default = "Defaults::console_triggers_enabled")]
+    pub console_triggers_enabled: bool,
+    #[serde(default = "Defaults::console_triggers")]
+    pub console_triggers: Vec<ConsoleTrigger>,
+    #[serde(default = "Defaults::websocket_enabled")]
+    pub websocket_enabled: bool,
+    #[serde(default = "Defaults::websocket_address")]
+    pub websocket_address: SocketAddr,
+    #[serde(default)]
+    pub websocket_token_public_key_path: Option<PathBuf>,
+    #[serde(default = "Defaults::websocket_token_audience")]
+    pub websocket_token_audience: String,
+}
+
+/// A pattern in console output which raises a health alert on the machine.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConsoleTrigger {
+    /// The health probe ID of the alert
+    pub name: HealthProbeId,
+    pub pattern: TriggerPattern,
+    #[serde(default = "Defaults::console_trigger_severity")]
+    pub severity: TriggerSeverity,
+    #[serde(
+        default = "Defaults::console_trigger_cooldown",
+        serialize_with = "serialize_duration",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub cooldown: Duration,
+    /// How long after the last match the alert is removed again
+    #[serde(
+        default = "Defaults::console_trigger_clear_after",
+        serialize_with = "serialize_duration",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub clear_after: Duration,
+    #[serde(default)]
+    pub machine_type: TriggerMachineType,
+    /// Classifications to add to the alert, in addition to the ones implied by the severity
+    #[serde(default)]
+    pub classifications: Vec<HealthAlertClassification>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerSeverity {
+    Warning,
+    // Critical alerts also prevent the machine from being allocated, if it's a host which is not
+    // assigned to an instance
+    Critical,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerMachineType {
+    #[default]
+    Any,
+    Host,
+    Dpu,
+}
+
+/// A regex which is validated when the config is loaded.
+#[derive(Debug, Clone)]
+pub struct TriggerPattern(pub regex::Regex);
+
+impl PartialEq for TriggerPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Serialize for TriggerPattern {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TriggerPattern {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        regex::Regex::new(&s)
+            .map(TriggerPattern)
+            .map_err(serde::de::Error::custom)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -216,6 +324,13 @@ impl Config {
             api_poll_interval,
             console_logs_path,
             console_logging_enabled,
+            session_recordings_path,
+            session_recording_enabled,
+            session_recordings_max_per_machine,
+            session_recordings_max_age,
+            recordings_address,
+            admin_root_ca_path: _,
+            admin_cert_issuer_cns: _,
             override_bmc_ssh_host: _,
             reconnect_interval_base,
             reconnect_interval_max,
@@ -223,6 +338,12 @@ impl Config {
             log_rotate_max_size,
             log_rotate_max_rotated_files,
             openssh_certificate_authorization,
+            console_triggers_enabled,
+            console_triggers,
+            websocket_enabled,
+            websocket_address,
+            websocket_token_public_key_path: _,
+            websocket_token_audience,
         } = self;
         let api_poll_interval = format!("{}s", api_poll_interval.as_secs());
         let reconnect_interval_base = format!("{}s", reconnect_interval_base.as_secs());
@@ -232,6 +353,9 @@ impl Config {
         let carbide_uri = carbide_uri.to_string();
         let listen_address = listen_address.to_string();
         let metrics_address = metrics_address.to_string();
+        let recordings_address = recordings_address.to_string();
+        let session_recordings_max_age = format!("{}s", session_recordings_max_age.as_secs());
+        let websocket_address = websocket_address.to_string();
         let log_rotate_max_size = log_rotate_max_size
             .format()
             .with_base(size::Base::Base2)
@@ -259,6 +383,15 @@ impl Config {
             .keyid_format
             .role_separator;
 
+        let console_triggers = {
+            #[derive(Serialize)]
+            struct ConsoleTriggers {
+                console_triggers: Vec<ConsoleTrigger>,
+            }
+            toml::to_string(&ConsoleTriggers { console_triggers })
+                .expect("BUG: console_triggers could not be serialized")
+        };
+
         format!(
             r#"
 #####
@@ -273,6 +406,26 @@ listen_address = {listen_address:?}
 ## Address to listen on for prometheus metrics requests (HTTP)
 metrics_address = {metrics_address:?}
 
+## Whether to serve consoles to browsers over WebSocket, in addition to SSH. Clients connect to
+## wss://<websocket_address>/console/<machine_id or instance_id>, passing a token from carbide-api's
+## CreateConsoleToken as either an `Authorization: Bearer <token>` header, or (for browsers) by
+## offering the `carbide-console` and `carbide-console-token.<token>` WebSocket subprotocols.
+websocket_enabled = {websocket_enabled:?}
+
+## What address to listen on for WebSocket connections (TLS), if enabled. client_cert_path is used
+## as the server identity.
+websocket_address = {websocket_address:?}
+
+## Path to the PEM-encoded public key (ES256) which carbide signs console tokens with. Tokens must
+## contain a `console` claim naming the machine or instance they grant access to. Machine consoles
+## require admin_certificate_role in the token's `roles` claim. Other tokens for instance consoles
+## are only accepted if carbide-api says the instance belongs to their `tenant_organization_id`.
+## If unset, no token is accepted (unless `insecure` is set.)
+# websocket_token_public_key_path = <path>
+
+## The audience (`aud` claim) console tokens must be issued for.
+websocket_token_audience = {websocket_token_audience:?}
+
 ## Address for carbide-api
 carbide_url = {carbide_uri:?}
 
@@ -327,6 +480,32 @@ console_logging_enabled = {console_logging_enabled:?}
 ## Where to write console logs for each machine, if enabled
 console_logs_path = {console_logs_path:?}
 
+## Whether to record each interactive session (output, input and terminal resizes) as an asciicast
+## v2 file, along with the authenticated user and start/stop times. Recordings can be listed and
+## fetched over HTTPS from recordings_address, under /recordings/<machine_id>.
+session_recording_enabled = {session_recording_enabled:?}
+
+## Where to write session recordings, if enabled
+session_recordings_path = {session_recordings_path:?}
+
+## How many recordings to keep for each machine. Older recordings are deleted when a new session
+## starts.
+session_recordings_max_per_machine = {session_recordings_max_per_machine}
+
+## Recordings older than this are deleted when a new session on the same machine starts.
+session_recordings_max_age = {session_recordings_max_age:?}
+
+## What address to serve session recordings on (HTTPS), if enabled. Clients must present an admin
+## client certificate: one issued by a CA in forge_root_ca_path or admin_root_ca_path, by an issuer
+## whose CN is listed in admin_cert_issuer_cns. client_cert_path is used as the server identity.
+recordings_address = {recordings_address:?}
+
+## Path to an additional root CA cert for admin client certificates
+# admin_root_ca_path = <path>
+
+## Issuer CN's of admin client certificates which may fetch session recordings. Defaults to none.
+# admin_cert_issuer_cns = ["<issuer CN>"]
+
 ## If set, use this host to override all BMC backends. Useful for machine-a-tron mocks where we use
 ## a single SSH server to mock all BMC SSH connections.
 # override_bmc_ssh_host = <hostname>
@@ -351,6 +530,10 @@ log_rotate_max_size = {log_rotate_max_size:?}
 ## When rotating console logs, how many old logs should we keep? (e.g. 3 means we keep .log, .log.0, .log.1, and .log.2)
 log_rotate_max_rotated_files = {log_rotate_max_rotated_files}
 
+## Whether to watch console output for the patterns in [[console_triggers]], and raise a health
+## alert on the machine through carbide-api when one matches.
+console_triggers_enabled = {console_triggers_enabled:?}
+
 ## Configure how the role is extracted from an SSH certificate
 [openssh_certificate_authorization]
 ## How should roles be extracted from SSH certs? (Currently supported: "key_id")
@@ -378,7 +561,19 @@ role_separator = {cert_authorization_keyid_format_role_separator:?}
 #
 # # [[bmcs]]
 # # ... more bmcs sections can define more than one
-"#
+
+## Patterns to watch for in console output, if console_triggers_enabled is set. Each line of output
+## (with ANSI escapes removed) is matched against each pattern. When a pattern matches, an alert
+## with the trigger's name as the probe ID is reported under the health report source
+## "ssh-console.console-trigger.<name>". The alert is removed again once the pattern hasn't matched
+## for clear_after (or earlier, by removing the health report source.) A trigger does not fire again
+## for the same machine until its cooldown has passed.
+##
+## severity: "warning", or "critical" (which also prevents allocations of hosts which are not
+##   assigned to an instance)
+## machine_type: "any", "host" or "dpu"
+## classifications: extra health alert classifications to add to the alert
+{console_triggers}"#
         )
     }
 
@@ -453,6 +648,13 @@ impl Default for Config {
             api_poll_interval: Defaults::api_poll_interval(),
             console_logs_path: Defaults::console_logs_path(),
             console_logging_enabled: Defaults::console_logging_enabled(),
+            session_recordings_path: Defaults::session_recordings_path(),
+            session_recording_enabled: Defaults::session_recording_enabled(),
+            session_recordings_max_per_machine: Defaults::session_recordings_max_per_machine(),
+            session_recordings_max_age: Defaults::session_recordings_max_age(),
+            recordings_address: Defaults::recordings_address(),
+            admin_root_ca_path: None,
+            admin_cert_issuer_cns: vec![],
             successful_connection_minimum_duration:
                 Defaults::successful_connection_minimum_duration(),
             log_rotate_max_size: Defaults::log_rotate_max_size(),
@@ -462,6 +664,12 @@ impl Default for Config {
             dpus: Defaults::dpus(),
             hosts: Defaults::hosts(),
             openssh_certificate_authorization: Defaults::cert_authorization(),
+            console_triggers_enabled: Defaults::console_triggers_enabled(),
+            console_triggers: Defaults::console_triggers(),
+            websocket_enabled: Defaults::websocket_enabled(),
+            websocket_address: Defaults::websocket_address(),
+            websocket_token_public_key_path: None,
+            websocket_token_audience: Defaults::websocket_token_audience(),
             override_bmc_ssh_port: None,
             override_ipmi_port: None,
             authorized_keys_path: None,
@@ -511,6 +719,20 @@ impl Defaults {
             .expect("BUG: default listen_address is invalid")
     }
 
+    pub fn websocket_enabled() -> bool {
+        false
+    }
+
+    pub fn websocket_address() -> SocketAddr {
+        "[::]:8081"
+            .parse()
+            .expect("BUG: default websocket_address is invalid")
+    }
+
+    pub fn websocket_token_audience() -> String {
+        "ssh-console".to_string()
+    }
+
     pub fn host_key_path() -> PathBuf {
         "/etc/ssh/ssh_host_ed25519_key".into()
     }
@@ -553,6 +775,28 @@ impl Defaults {
         true
     }
 
+    pub fn session_recordings_path() -> PathBuf {
+        "/var/log/console-recordings".into()
+    }
+
+    pub fn session_recording_enabled() -> bool {
+        false
+    }
+
+    pub fn session_recordings_max_per_machine() -> usize {
+        50
+    }
+
+    pub fn session_recordings_max_age() -> Duration {
+        Duration::from_secs(30 * 24 * 60 * 60)
+    }
+
+    pub fn recordings_address() -> SocketAddr {
+        "[::]:8082"
+            .parse()
+            .expect("BUG: default recordings_address is invalid")
+    }
+
     pub fn reconnect_interval_base() -> Duration {
         Duration::from_secs(10)
     }
@@ -604,6 +848,74 @@ impl Defaults {
     pub fn cert_authorization_strategy() -> Vec<CertAuthorizationStrategy> {
         vec![CertAuthorizationStrategy::KeyId]
     }
+
+    pub fn console_triggers_enabled() -> bool {
+        false
+    }
+
+    pub fn console_trigger_severity() -> TriggerSeverity {
+        TriggerSeverity::Warning
+    }
+
+    pub fn console_trigger_cooldown() -> Duration {
+        Duration::from_secs(600)
+    }
+
+    pub fn console_trigger_clear_after() -> Duration {
+        Duration::from_secs(3600)
+    }
+
+    pub fn console_triggers() -> Vec<ConsoleTrigger> {
+        let trigger =
+            |name: &str, pattern: &str, severity, machine_type, classifications| ConsoleTrigger {
+                name: HealthProbeId::from_str(name).expect("BUG: invalid default trigger name"),
+                pattern: TriggerPattern(
+                    regex::Regex::new(pattern).expect("BUG: invalid default trigger pattern"),
+                ),
+                severity,
+                cooldown: Self::console_trigger_cooldown(),
+                clear_after: Self::console_trigger_clear_after(),
+                machine_type,
+                classifications,
+            };
+        vec![
+            trigger(
+                "ConsoleKernelPanic",
+                r"Kernel panic - not syncing",
+                TriggerSeverity::Critical,
+                TriggerMachineType::Any,
+                vec![],
+            ),
+            trigger(
+                "ConsoleMachineCheck",
+                r"mce: \[Hardware Error\]|Machine check events logged",
+                TriggerSeverity::Critical,
+                TriggerMachineType::Any,
+                vec![HealthAlertClassification::hardware()],
+            ),
+            trigger(
+                "ConsoleOutOfMemory",
+                r"Out of memory: Kill",
+                TriggerSeverity::Warning,
+                TriggerMachineType::Any,
+                vec![],
+            ),
+            trigger(
+                "ConsoleNoBootableDevice",
+                r"(?i)no bootable device",
+                TriggerSeverity::Critical,
+                TriggerMachineType::Host,
+                vec![],
+            ),
+            trigger(
+                "ConsoleBfbInstallFailed",
+                r"(?i)bfb[- _]?install.*(fail|error|timed? ?out)",
+                TriggerSeverity::Critical,
+                TriggerMachineType::Dpu,
+                vec![],
+            ),
+        ]
+    }
 }
 
 fn serialize_duration<S>(d: &std::time::Duration, serializer: S) -> Result<S::Ok, S::Error>
@@ -0,0 +1,578 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use carbide_uuid::machine::MachineId;
+use chrono::Utc;
+use health_report::{HealthAlertClassification, HealthProbeAlert, HealthReport};
+use rpc::forge_api_client::ForgeApiClient;
+use russh::ChannelMsg;
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::bmc::message_proxy::ToFrontendMessage;
+use crate::config::{Config, ConsoleTrigger, TriggerMachineType, TriggerSeverity};
+use crate::shutdown_handle::ShutdownHandle;
+
+/// Lines longer than this are matched in pieces, so a console which never prints a newline can't
+/// grow the buffer without bound.
+const MAX_LINE_LENGTH: usize = 4096;
+
+/// How much of the matching line to include in the alert message.
+const MAX_MESSAGE_LINE_LENGTH: usize = 256;
+
+/// Prefix of the health report source of each trigger's alert.
+const SOURCE_PREFIX: &str = "ssh-console.console-trigger.";
+
+/// Spawn a background task which watches all output from a BMC for the configured
+/// [`ConsoleTrigger`]s, raising a health alert on the machine when one matches. Each alert is
+/// removed again once its trigger hasn't matched for the trigger's `clear_after`.
+pub fn spawn(
+    machine_id: MachineId,
+    message_rx: broadcast::Receiver<ToFrontendMessage>,
+    config: Arc<Config>,
+    forge_api_client: ForgeApiClient,
+) -> ConsoleTriggersHandle {
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    let matcher = TriggerMatcher::new(machine_id, &config.console_triggers);
+    let console_triggers = ConsoleTriggers {
+        machine_id,
+        matcher,
+        forge_api_client,
+    };
+
+    let join_handle = tokio::spawn(console_triggers.run(shutdown_rx, message_rx));
+
+    ConsoleTriggersHandle {
+        shutdown_tx,
+        join_handle,
+    }
+}
+
+pub struct ConsoleTriggersHandle {
+    shutdown_tx: oneshot::Sender<()>,
+    join_handle: JoinHandle<()>,
+}
+
+impl ShutdownHandle<()> for ConsoleTriggersHandle {
+    fn into_parts(self) -> (oneshot::Sender<()>, JoinHandle<()>) {
+        (self.shutdown_tx, self.join_handle)
+    }
+}
+
+struct ConsoleTriggers {
+    machine_id: MachineId,
+    matcher: TriggerMatcher,
+    forge_api_client: ForgeApiClient,
+}
+
+impl ConsoleTriggers {
+    async fn run(
+        mut self,
+        mut shutdown_rx: oneshot::Receiver<()>,
+        mut message_rx: broadcast::Receiver<ToFrontendMessage>,
+    ) {
+        if self.matcher.triggers.is_empty() {
+            tracing::debug!(machine_id=%self.machine_id, "no console triggers apply to machine");
+            return;
+        }
+
+        self.resume_alerts().await;
+
+        loop {
+            let next_expiry = self.matcher.next_expiry();
+            tokio::select! {
+                _ = &mut shutdown_rx => {
+                    break;
+                }
+
+                _ = tokio::time::sleep_until(next_expiry.unwrap_or_else(Instant::now).into()), if next_expiry.is_some() => {
+                    for trigger in self.matcher.expired(Instant::now()) {
+                        self.clear(trigger);
+                    }
+                }
+
+                res = message_rx.recv() => match res {
+                    Ok(msg) => {
+                        let msg = Arc::<ChannelMsg>::from(msg);
+                        if let ChannelMsg::Data { data } = msg.as_ref() {
+                            for trigger_match in self.matcher.feed(data.as_ref(), Instant::now()) {
+                                self.report(trigger_match);
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(count)) => {
+                        tracing::warn!(machine_id=%self.machine_id, "console triggers lagged by {count} messages, output may not have been matched");
+                    }
+                },
+            }
+        }
+
+        tracing::debug!(machine_id=%self.machine_id, "shutting down console triggers");
+    }
+
+    /// Pick up alerts raised before we were (re)started: Ones which are older than their trigger's
+    /// `clear_after`, or whose trigger no longer applies, are removed now, and the rest are removed
+    /// once they expire.
+    async fn resume_alerts(&mut self) {
+        let machine_id = self.machine_id;
+        let entries = match self
+            .forge_api_client
+            .list_machine_health_reports(machine_id)
+            .await
+        {
+            Ok(response) => response.health_report_entries,
+            Err(error) => {
+                tracing::warn!(%machine_id, %error, "could not list health reports to resume console trigger alerts");
+                return;
+            }
+        };
+
+        let now = Utc::now();
+        for report in entries.into_iter().filter_map(|entry| entry.report) {
+            let Some(name) = report.source.strip_prefix(SOURCE_PREFIX) else {
+                continue;
+            };
+            let age = HealthReport::try_from(report.clone())
+                .ok()
+                .and_then(|report| report.observed_at)
+                .and_then(|observed_at| (now - observed_at).to_std().ok())
+                .unwrap_or_default();
+            if let Some(trigger) = self.matcher.resume(name, age, Instant::now()) {
+                self.clear(trigger);
+            } else if !self
+                .matcher
+                .triggers
+                .iter()
+                .any(|t| t.name.as_str() == name)
+            {
+                self.clear_source(report.source);
+            }
+        }
+    }
+
+    /// Send the health report for a match in the background, so that a slow carbide-api doesn't
+    /// cause us to lag behind the console output.
+    fn report(&self, trigger_match: TriggerMatch) {
+        let machine_id = self.machine_id;
+        let forge_api_client = self.forge_api_client.clone();
+        tracing::info!(
+            %machine_id,
+            trigger = %trigger_match.trigger.name,
+            line = trigger_match.line,
+            "console output matched trigger, raising health alert"
+        );
+
+        tokio::spawn(async move {
+            let prevent_allocations = trigger_match.trigger.severity == TriggerSeverity::Critical
+                && is_unassigned_host(&forge_api_client, machine_id).await;
+            let request = rpc::forge::InsertMachineHealthReportRequest {
+                machine_id: Some(machine_id),
+                health_report_entry: Some(rpc::forge::HealthReportEntry {
+                    report: Some(trigger_match.health_report(prevent_allocations).into()),
+                    mode: rpc::forge::HealthReportApplyMode::Merge.into(),
+                }),
+            };
+            if let Err(error) = forge_api_client.insert_machine_health_report(request).await {
+                tracing::error!(
+                    %machine_id,
+                    trigger = %trigger_match.trigger.name,
+                    %error,
+                    "could not insert health report for console trigger"
+                );
+            }
+        });
+    }
+
+    /// Remove the alert of a trigger which hasn't matched for its `clear_after`.
+    fn clear(&self, trigger: ConsoleTrigger) {
+        tracing::info!(
+            machine_id = %self.machine_id,
+            trigger = %trigger.name,
+            "console trigger has not matched for {}s, clearing health alert",
+            trigger.clear_after.as_secs()
+        );
+        self.clear_source(format!("{SOURCE_PREFIX}{}", trigger.name));
+    }
+
+    fn clear_source(&self, source: String) {
+        let machine_id = self.machine_id;
+        let forge_api_client = self.forge_api_client.clone();
+        tokio::spawn(async move {
+            let request = rpc::forge::RemoveMachineHealthReportRequest {
+                machine_id: Some(machine_id),
+                source: source.clone(),
+            };
+            if let Err(error) = forge_api_client.remove_machine_health_report(request).await {
+                tracing::error!(
+                    %machine_id,
+                    source,
+                    %error,
+                    "could not remove health report for console trigger"
+                );
+            }
+        });
+    }
+}
+
+/// Whether the machine is a host which is not assigned to an instance, so that a critical alert
+/// may prevent it from being allocated without getting in a tenant's way. Errs on the side of not
+/// preventing allocations if carbide-api can't tell us.
+async fn is_unassigned_host(forge_api_client: &ForgeApiClient, machine_id: MachineId) -> bool {
+    let machine_type = machine_id.machine_type();
+    if !(machine_type.is_host() || machine_type.is_predicted_host()) {
+        return false;
+    }
+    match forge_api_client
+        .find_instance_by_machine_id(machine_id)
+        .await
+    {
+        Ok(instances) => instances.instances.is_empty(),
+        Err(error) => {
+            tracing::warn!(
+                %machine_id,
+                %error,
+                "could not look up instance of machine, not preventing allocations for console trigger"
+            );
+            false
+        }
+    }
+}
+
+/// Splits console output into lines and matches each line against the triggers which apply to a
+/// machine, enforcing each trigger's cooldown and tracking when its alert expires.
+struct TriggerMatcher {
+    triggers: Vec<ConsoleTrigger>,
+    last_fired: Vec<Option<Instant>>,
+    expires_at: Vec<Option<Instant>>,
+    buffer: Vec<u8>,
+}
+
+impl TriggerMatcher {
+    fn new(machine_id: MachineId, triggers: &[ConsoleTrigger]) -> Self {
+        let machine_type = machine_id.machine_type();
+        let triggers = triggers
+            .iter()
+            .filter(|trigger| match trigger.machine_type {
+                TriggerMachineType::Any => true,
+                TriggerMachineType::Host => {
+                    machine_type.is_host() || machine_type.is_predicted_host()
+                }
+                TriggerMachineType::Dpu => machine_type.is_dpu(),
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        Self {
+            last_fired: vec![None; triggers.len()],
+            expires_at: vec![None; triggers.len()],
+            triggers,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// When the next alert expires, if any are active.
+    fn next_expiry(&self) -> Option<Instant> {
+        self.expires_at.iter().flatten().min().copied()
+    }
+
+    /// Returns the triggers whose alerts have expired by `now`, which are no longer active.
+    fn expired(&mut self, now: Instant) -> Vec<ConsoleTrigger> {
+        self.triggers
+            .iter()
+            .zip(self.expires_at.iter_mut())
+            .filter(|(_, expires_at)| expires_at.is_some_and(|expires_at| expires_at <= now))
+            .map(|(trigger, expires_at)| {
+                *expires_at = None;
+                trigger.clone()
+            })
+            .collect()
+    }
+
+    /// Track an alert for trigger `name` which was raised `age` ago. Returns the trigger if the
+    /// alert has already expired.
+    fn resume(&mut self, name: &str, age: Duration, now: Instant) -> Option<ConsoleTrigger> {
+        let index = self.triggers.iter().position(|t| t.name.as_str() == name)?;
+        let trigger = &self.triggers[index];
+        match trigger.clear_after.checked_sub(age) {
+            Some(remaining) if !remaining.is_zero() => {
+                self.expires_at[index] = Some(now + remaining);
+                None
+            }
+            _ => Some(trigger.clone()),
+        }
+    }
+
+    fn feed(&mut self, data: &[u8], now: Instant) -> Vec<TriggerMatch> {
+        self.buffer.extend_from_slice(data);
+
+        let mut matches = Vec::new();
+        loop {
+            let line_bytes: Vec<u8> = match self.buffer.iter().position(|&b| b == b'\n') {
+                Some(nl) => self.buffer.drain(..=nl).collect(),
+                None if self.buffer.len() >= MAX_LINE_LENGTH => {
+                    self.buffer.drain(..MAX_LINE_LENGTH).collect()
+                }
+                None => break,
+            };
+
+            let clean = strip_ansi_escapes::strip(&line_bytes);
+            let line = String::from_utf8_lossy(&clean);
+            let line = line.trim_end_matches(['\r', '\n']);
+            matches.extend(self.match_line(line, now));
+        }
+        matches
+    }
+
+    fn match_line(&mut self, line: &str, now: Instant) -> Vec<TriggerMatch> {
+        let mut matches = Vec::new();
+        for ((trigger, last_fired), expires_at) in self
+            .triggers
+            .iter()
+            .zip(self.last_fired.iter_mut())
+            .zip(self.expires_at.iter_mut())
+        {
+            if !trigger.pattern.0.is_match(line) {
+                continue;
+            }
+            *expires_at = Some(now + trigger.clear_after);
+            if let Some(last_fired) = last_fired
+                && now.duration_since(*last_fired) < trigger.cooldown
+            {
+                continue;
+            }
+            *last_fired = Some(now);
+            matches.push(TriggerMatch {
+                trigger: trigger.clone(),
+                line: line.trim().to_string(),
+            });
+        }
+        matches
+    }
+}
+
+#[derive(Debug)]
+struct TriggerMatch {
+    trigger: ConsoleTrigger,
+    line: String,
+}
+
+impl TriggerMatch {
+    fn health_report(&self, prevent_allocations: bool) -> HealthReport {
+        let trigger = &self.trigger;
+        let severity = match trigger.severity {
+            TriggerSeverity::Warning => "Warning",
+            TriggerSeverity::Critical => "Critical",
+        };
+        let line = match self.line.char_indices().nth(MAX_MESSAGE_LINE_LENGTH) {
+            Some((idx, _)) => format!("{}...", &self.line[..idx]),
+            None => self.line.clone(),
+        };
+
+        let mut classifications = vec![HealthAlertClassification::serial_console()];
+        if prevent_allocations {
+            classifications.push(HealthAlertClassification::prevent_allocations());
+        }
+        for classification in &trigger.classifications {
+            if !classifications.contains(classification) {
+                classifications.push(classification.clone());
+            }
+        }
+
+        HealthReport {
+            source: format!("{SOURCE_PREFIX}{}", trigger.name),
+            triggered_by: None,
+            observed_at: Some(Utc::now()),
+            successes: vec![],
+            alerts: vec![HealthProbeAlert {
+                id: trigger.name.clone(),
+                target: None,
+                in_alert_since: None,
+                message: format!(
+                    "[{severity}] Serial console output matched {}: {line}",
+                    trigger.name
+                ),
+                tenant_message: None,
+                classifications,
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::config::Defaults;
+
+    static HOST_ID: &str = "fm100htjtiaehv1n5vh67tbmqq4eabcjdng40f7jupsadbedhruh6rag1l0";
+    static DPU_ID: &str = "fm100dtjtiaehv1n5vh67tbmqq4eabcjdng40f7jupsadbedhruh6rag1l0";
+
+    fn matched_names(matches: &[TriggerMatch]) -> Vec<String> {
+        matches.iter().map(|m| m.trigger.name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_matches_complete_lines_only() {
+        let machine_id = MachineId::from_str(HOST_ID).unwrap();
+        let mut matcher = TriggerMatcher::new(machine_id, &Defaults::console_triggers());
+        let now = Instant::now();
+
+        assert!(matcher.feed(b"[  12.3] Kernel panic - not", now).is_empty());
+        let matches = matcher.feed(b" syncing: Fatal exception\r\n", now);
+        assert_eq!(matched_names(&matches), vec!["ConsoleKernelPanic"]);
+        assert_eq!(
+            matches[0].line,
+            "[  12.3] Kernel panic - not syncing: Fatal exception"
+        );
+
+        // ANSI escapes are stripped before matching
+        let matches = matcher.feed(b"\x1b[1;31mNo Bootable Device\x1b[0m\n", now);
+        assert_eq!(matched_names(&matches), vec!["ConsoleNoBootableDevice"]);
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let machine_id = MachineId::from_str(HOST_ID).unwrap();
+        let mut matcher = TriggerMatcher::new(machine_id, &Defaults::console_triggers());
+        let now = Instant::now();
+        let line = b"Out of memory: Killed process 1234 (stress)\n";
+
+        assert_eq!(matcher.feed(line, now).len(), 1);
+        assert!(matcher.feed(line, now + Duration::from_secs(1)).is_empty());
+        assert_eq!(
+            matcher
+                .feed(line, now + Defaults::console_trigger_cooldown())
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_machine_type_filtering() {
+        let host_id = MachineId::from_str(HOST_ID).unwrap();
+        let dpu_id = MachineId::from_str(DPU_ID).unwrap();
+        let now = Instant::now();
+
+        let mut host_matcher = TriggerMatcher::new(host_id, &Defaults::console_triggers());
+        let mut dpu_matcher = TriggerMatcher::new(dpu_id, &Defaults::console_triggers());
+
+        assert!(host_matcher.feed(b"bfb-install: failed\n", now).is_empty());
+        assert_eq!(
+            matched_names(&dpu_matcher.feed(b"bfb-install: failed\n", now)),
+            vec!["ConsoleBfbInstallFailed"]
+        );
+        assert!(dpu_matcher.feed(b"No bootable device\n", now).is_empty());
+    }
+
+    #[test]
+    fn test_health_report() {
+        let machine_id = MachineId::from_str(HOST_ID).unwrap();
+        let mut matcher = TriggerMatcher::new(machine_id, &Defaults::console_triggers());
+        let matches = matcher.feed(
+            b"mce: [Hardware Error]: CPU 0: Machine Check\n",
+            Instant::now(),
+        );
+        let report = matches[0].health_report(true);
+
+        assert_eq!(
+            report.source,
+            "ssh-console.console-trigger.ConsoleMachineCheck"
+        );
+        assert_eq!(report.alerts.len(), 1);
+        assert_eq!(
+            report.alerts[0].message,
+            "[Critical] Serial console output matched ConsoleMachineCheck: mce: [Hardware Error]: \
+             CPU 0: Machine Check"
+        );
+        assert_eq!(
+            report.alerts[0].classifications,
+            vec![
+                HealthAlertClassification::serial_console(),
+                HealthAlertClassification::prevent_allocations(),
+                HealthAlertClassification::hardware(),
+            ]
+        );
+
+        // Critical alerts only prevent allocations of unassigned hosts
+        assert_eq!(
+            matches[0].health_report(false).alerts[0].classifications,
+            vec![
+                HealthAlertClassification::serial_console(),
+                HealthAlertClassification::hardware(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alerts_expire() {
+        let machine_id = MachineId::from_str(HOST_ID).unwrap();
+        let mut matcher = TriggerMatcher::new(machine_id, &Defaults::console_triggers());
+        let clear_after = Defaults::console_trigger_clear_after();
+        let now = Instant::now();
+        let line = b"Out of memory: Killed process 1234 (stress)\n";
+
+        assert_eq!(matcher.next_expiry(), None);
+        assert_eq!(matcher.feed(line, now).len(), 1);
+        assert_eq!(matcher.next_expiry(), Some(now + clear_after));
+
+        // Matches during the cooldown don't fire again, but keep the alert active
+        let later = now + Duration::from_secs(60);
+        assert!(matcher.feed(line, later).is_empty());
+        assert!(matcher.expired(now + clear_after).is_empty());
+        let expired = matcher.expired(later + clear_after);
+        assert_eq!(
+            expired
+                .iter()
+                .map(|t| t.name.to_string())
+                .collect::<Vec<_>>(),
+            vec!["ConsoleOutOfMemory"]
+        );
+        assert_eq!(matcher.next_expiry(), None);
+    }
+
+    #[test]
+    fn test_resume_alerts() {
+        let machine_id = MachineId::from_str(HOST_ID).unwrap();
+        let mut matcher = TriggerMatcher::new(machine_id, &Defaults::console_triggers());
+        let clear_after = Defaults::console_trigger_clear_after();
+        let now = Instant::now();
+
+        // An alert raised a minute ago expires a minute early
+        let age = Duration::from_secs(60);
+        assert!(matcher.resume("ConsoleKernelPanic", age, now).is_none());
+        assert_eq!(matcher.next_expiry(), Some(now + clear_after - age));
+
+        // An alert older than clear_after is cleared right away
+        let expired = matcher.resume("ConsoleOutOfMemory", clear_after, now);
+        assert_eq!(
+            expired.map(|trigger| trigger.name.to_string()).as_deref(),
+            Some("ConsoleOutOfMemory")
+        );
+
+        // Triggers which don't apply to the machine aren't tracked
+        assert!(
+            matcher
+                .resume("ConsoleBfbInstallFailed", Duration::ZERO, now)
+                .is_none()
+        );
+    }
+}
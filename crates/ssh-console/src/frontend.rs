@@ -24,20 +24,25 @@ use carbide_uuid::machine::MachineId;
 use lazy_static::lazy_static;
 use rpc::forge::ValidateTenantPublicKeyRequest;
 use rpc::forge_api_client::ForgeApiClient;
-use russh::keys::ssh_key::AuthorizedKeys;
+use russh::keys::ssh_key::{AuthorizedKeys, HashAlg};
 use russh::keys::{Certificate, PublicKey, PublicKeyBase64};
 use russh::server::{Auth, Msg, Session};
 use russh::{Channel, ChannelId, ChannelMsg, MethodKind, MethodSet, Pty};
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot};
 use tonic::Code;
 use uuid::Uuid;
 
 use crate::bmc::client::BmcConnectionSubscription;
 use crate::bmc::client_pool::{BmcConnectionStore, GetConnectionError};
 use crate::bmc::connection::Kind;
+use crate::bmc::console_sessions::{
+    AttachedSession, ConsoleSessions, Frontend, SessionCommand, SessionCommandFilter, WriteAccess,
+};
 use crate::bmc::message_proxy;
 use crate::bmc::message_proxy::{ExecReply, ToBmcMessage};
 use crate::config::Config;
+use crate::session_recorder;
+use crate::session_recorder::{RecorderEvent, Retention, SessionInfo};
 use crate::shutdown_handle::ShutdownHandle;
 use crate::ssh_cert_parsing::{certificate_contains_role, get_user_from_certificate};
 use crate::ssh_server::ServerMetrics;
@@ -52,6 +57,7 @@ static BANNER_SSH_BMC: &str = "\
 |      (Note that escapes are only recognized immediately after newline.)      |\r\n\
 |                               ~. | terminate session                         |\r\n\
 |                               ~? | Help                                      |\r\n\
+|                               ~w | take the write lock (if observing)        |\r\n\
 +------------------------------------------------------------------------------+\r\n\
 ";
 
@@ -63,6 +69,7 @@ static BANNER_IPMI_BMC: &str = "\
 |      (Note that escapes are only recognized immediately after newline.)      |\r\n\
 |                               ~. | terminate session                         |\r\n\
 |                               ~? | Help                                      |\r\n\
+|                               ~w | take the write lock (if observing)        |\r\n\
 |   This system supports power reset requests. To reboot this system, append   |\r\n\
 |                \"power reset\" to your original SSH command                  |\r\n\
 |                (e.g. ssh <host>@<console-ip> power reset)                    |\r\n\
@@ -85,6 +92,12 @@ pub struct Handler {
     bmc_connection_store: BmcConnectionStore,
     /// The machine_id or instance_id the user is attempting to log into. Used as the username in the ssh command line (ie. ssh machine_id@ssh-console)
     authenticated_machine_string: Option<String>,
+    /// Who logged in, for session recordings: The user from the SSH certificate, or the fingerprint
+    /// of the public key.
+    authenticated_user: Option<String>,
+    /// Whether the user authenticated as an admin (via an admin certificate or authorized_keys),
+    /// which allows them to force-disconnect other sessions.
+    is_admin: bool,
     per_client_state: HashMap<ChannelId, PerClientState>,
     metrics: Arc<ServerMetrics>,
     last_auth_failure: Option<AuthFailureReason>,
@@ -96,6 +109,22 @@ struct PerClientState {
     bmc_connection: BmcConnectionSubscription,
     // Option so that it can be taken with .take() when we get a shell_request or exec_request
     client_channel: Option<Channel<Msg>>,
+    // Set by pty_request, used for the session recording header
+    pty: Option<PtyInfo>,
+    // Set by shell_request if session recording is enabled
+    recorder_tx: Option<mpsc::UnboundedSender<RecorderEvent>>,
+    // Set by shell_request, arbitrates input with other sessions attached to the same console
+    attached: Option<Arc<AttachedSession>>,
+    command_filter: SessionCommandFilter,
+    // Whether we've told the user their input is being ignored since they don't hold the write
+    // lock, so we only tell them once.
+    read_only_notified: bool,
+}
+
+struct PtyInfo {
+    term: String,
+    col_width: u32,
+    row_height: u32,
 }
 
 impl Handler {
@@ -112,6 +141,8 @@ impl Handler {
             forge_api_client,
             bmc_connection_store,
             authenticated_machine_string: None,
+            authenticated_user: None,
+            is_admin: false,
             per_client_state: HashMap::new(),
             metrics,
             last_auth_failure: Default::default(),
@@ -209,6 +240,11 @@ impl russh::server::Handler for Handler {
             PerClientState {
                 bmc_connection,
                 client_channel: Some(channel),
+                pty: None,
+                recorder_tx: None,
+                attached: None,
+                command_filter: SessionCommandFilter::default(),
+                read_only_notified: false,
             },
         );
 
@@ -302,6 +338,8 @@ impl russh::server::Handler for Handler {
             );
         }
         self.authenticated_machine_string = Some(machine_string.to_owned());
+        self.authenticated_user = user.or_else(|| Some(certificate.key_id().to_owned()));
+        self.is_admin = true;
         Ok(Auth::Accept)
     }
 
@@ -323,6 +361,7 @@ impl russh::server::Handler for Handler {
                 machine_id: machine_string.to_owned(),
                 error,
             })? {
+            self.is_admin = true;
             true
         } else if Uuid::from_str(machine_string).is_ok() {
             // Only try tenant auth if the user is a valid-looking UUID.
@@ -353,6 +392,7 @@ impl russh::server::Handler for Handler {
 
         if success {
             self.authenticated_machine_string = Some(machine_string.to_owned());
+            self.authenticated_user = Some(public_key.fingerprint(HashAlg::Sha256).to_string());
             Ok(Auth::Accept)
         } else {
             self.last_auth_failure = Some(AuthFailureReason::PubKey {
@@ -365,7 +405,7 @@ impl russh::server::Handler for Handler {
         }
     }
 
-    /// Forward the data to the BMC
+    /// Forward the data to the BMC, if this session holds the write lock
     async fn data(
         &mut self,
         channel: ChannelId,
@@ -374,14 +414,46 @@ impl russh::server::Handler for Handler {
     ) -> Result<(), Self::Error> {
         tracing::trace!(peer_addr = self.peer_addr, "data");
         if let Some(client_state) = self.get_client_state_or_report_error(session, channel) {
+            let data = if let Some(attached) = &client_state.attached {
+                let (data, commands) = client_state.command_filter.filter(data);
+                for command in commands {
+                    match command {
+                        SessionCommand::TakeWriteLock => attached.take_write_lock(),
+                    }
+                }
+                if data.is_empty() {
+                    return Ok(());
+                }
+                match attached.write_access() {
+                    WriteAccess::Granted => client_state.read_only_notified = false,
+                    WriteAccess::ReadOnly { writer } => {
+                        if !client_state.read_only_notified {
+                            client_state.read_only_notified = true;
+                            let notice = format!(
+                                "\r\n--- Read-only: {writer} has the write lock. Type ~w after a \
+                                 newline to take it ---\r\n"
+                            );
+                            session.data(channel, notice.into_bytes()).ok();
+                        }
+                        return Ok(());
+                    }
+                }
+                data
+            } else {
+                data.to_vec()
+            };
+
             client_state
                 .bmc_connection
                 .to_bmc_msg_tx
                 .send(ToBmcMessage::ChannelMsg(ChannelMsg::Data {
-                    data: data.to_vec().into(),
+                    data: data.clone().into(),
                 }))
                 .await
                 .map_err(|_| HandlerError::WritingToChannel { what: "data" })?;
+            if let Some(recorder_tx) = &client_state.recorder_tx {
+                recorder_tx.send(RecorderEvent::Input(data.into())).ok();
+            }
         }
         Ok(())
     }
@@ -396,6 +468,13 @@ impl russh::server::Handler for Handler {
     ) -> Result<(), Self::Error> {
         tracing::trace!(peer_addr = self.peer_addr, "extended_data");
         if let Some(client_state) = self.get_client_state_or_report_error(session, channel) {
+            if client_state
+                .attached
+                .as_ref()
+                .is_some_and(|attached| attached.write_access() != WriteAccess::Granted)
+            {
+                return Ok(());
+            }
             client_state
                 .bmc_connection
                 .to_bmc_msg_tx
@@ -414,15 +493,22 @@ impl russh::server::Handler for Handler {
     async fn pty_request(
         &mut self,
         channel: ChannelId,
-        _term: &str,
-        _col_width: u32,
-        _row_height: u32,
+        term: &str,
+        col_width: u32,
+        row_height: u32,
         _pix_width: u32,
         _pix_height: u32,
         _modes: &[(Pty, u32)],
         session: &mut Session,
     ) -> Result<(), Self::Error> {
         tracing::trace!(peer_addr = self.peer_addr, "pty_request");
+        if let Some(client_state) = self.per_client_state.get_mut(&channel) {
+            client_state.pty = Some(PtyInfo {
+                term: term.to_owned(),
+                col_width,
+                row_height,
+            });
+        }
         session.channel_success(channel)?;
         Ok(())
     }
@@ -434,6 +520,8 @@ impl russh::server::Handler for Handler {
     ) -> Result<(), Self::Error> {
         tracing::trace!(peer_addr = self.peer_addr, "shell_request");
         let peer_addr = self.peer_addr.clone();
+        let config = self.config.clone();
+        let authenticated_user = self.authenticated_user.clone();
         let Some(client_state) = self.get_client_state_or_report_error(session, channel_id) else {
             return Ok(());
         };
@@ -455,14 +543,46 @@ impl russh::server::Handler for Handler {
             return Ok(());
         };
         let machine_id = client_state.bmc_connection.machine_id;
-        let Some(from_bmc_rx) = client_state
+        let Some(to_frontend_msg_tx) = client_state
             .bmc_connection
             .to_frontend_msg_weak_tx
             .upgrade()
-            .map(|tx| tx.subscribe())
         else {
             return Err(HandlerError::BmcDisconnectedBeforeSubscribe { machine_id })?;
         };
+        let from_bmc_rx = to_frontend_msg_tx.subscribe();
+
+        // Start recording before anything is sent to the user, so the recording is complete.
+        let recorder_handle = if config.session_recording_enabled {
+            let pty = client_state.pty.as_ref();
+            let (recorder_handle, recorder_tx) = session_recorder::spawn(
+                &config.session_recordings_path,
+                Retention::from(config.as_ref()),
+                SessionInfo {
+                    machine_id,
+                    user: authenticated_user.clone(),
+                    peer_addr: peer_addr.clone(),
+                    term: pty.map(|pty| pty.term.clone()),
+                    col_width: pty.map(|pty| pty.col_width).unwrap_or(80),
+                    row_height: pty.map(|pty| pty.row_height).unwrap_or(24),
+                },
+                to_frontend_msg_tx.subscribe(),
+            );
+            client_state.recorder_tx = Some(recorder_tx);
+            Some(recorder_handle)
+        } else {
+            None
+        };
+        std::mem::drop(to_frontend_msg_tx);
+
+        // Register the session, so its input is arbitrated with other sessions on this console.
+        let (attached, kick_rx) = client_state.bmc_connection.sessions.attach(
+            authenticated_user,
+            peer_addr.clone(),
+            Frontend::Ssh,
+        );
+        let attached = Arc::new(attached);
+        client_state.attached = Some(attached.clone());
 
         // Output the banner with instructions
         let banner = match client_state.bmc_connection.kind {
@@ -483,6 +603,11 @@ impl russh::server::Handler for Handler {
             .await
             .ok();
         if let Ok(pending_line) = pending_line_reply_rx.await {
+            if let Some(recorder_tx) = &client_state.recorder_tx {
+                recorder_tx
+                    .send(RecorderEvent::Output(pending_line.clone().into()))
+                    .ok();
+            }
             channel_tx.data(pending_line.as_slice()).await.ok();
         }
 
@@ -492,16 +617,28 @@ impl russh::server::Handler for Handler {
         // (taking ownership of it) and signal a shutdown of the proxy loop, then when that happens,
         // we finally close the channel. Only then is Self::channel_close() actually sent! (This is
         // IMO a design flaw in russh.)
-        let proxy_handle = message_proxy::spawn(from_bmc_rx, channel_tx, peer_addr);
+        let proxy_handle = message_proxy::spawn(from_bmc_rx, channel_tx, peer_addr.clone());
 
         tokio::spawn({
             async move {
-                loop {
-                    if channel_rx.wait().await.is_none() {
-                        break;
+                let wait_for_disconnect = async {
+                    loop {
+                        if channel_rx.wait().await.is_none() {
+                            break;
+                        }
+                    }
+                };
+                tokio::select! {
+                    _ = wait_for_disconnect => {}
+                    Ok(()) = kick_rx => {
+                        tracing::info!(peer_addr, "session disconnected by an admin");
                     }
                 }
                 proxy_handle.shutdown_and_wait().await;
+                attached.detach();
+                if let Some(recorder_handle) = recorder_handle {
+                    recorder_handle.shutdown_and_wait().await;
+                }
             }
         });
 
@@ -515,9 +652,12 @@ impl russh::server::Handler for Handler {
         session: &mut Session,
     ) -> Result<(), Self::Error> {
         tracing::trace!(peer_addr = self.peer_addr, "exec_request");
+        let is_admin = self.is_admin;
+        let authenticated_user = self.authenticated_user.clone();
         let Some(PerClientState {
             client_channel,
             bmc_connection,
+            ..
         }) = self.get_client_state_or_report_error(session, channel_id)
         else {
             return Ok(());
@@ -537,6 +677,23 @@ impl russh::server::Handler for Handler {
             return Ok(());
         };
 
+        // Commands for managing sessions are handled by ssh-console, not the BMC.
+        if let Some(ExecReply {
+            output,
+            exit_status,
+        }) = session_exec_command(
+            data,
+            &bmc_connection.sessions,
+            is_admin,
+            authenticated_user.as_deref(),
+        ) {
+            channel.data(output.as_slice()).await.ok();
+            channel.exit_status(exit_status).await.ok();
+            session.channel_success(channel_id).ok();
+            channel.close().await.ok();
+            return Ok(());
+        }
+
         let (reply_tx, reply_rx) = oneshot::channel();
         bmc_connection
             .to_bmc_msg_tx
@@ -592,19 +749,34 @@ impl russh::server::Handler for Handler {
     ) -> Result<(), Self::Error> {
         tracing::trace!(peer_addr = self.peer_addr, "window_change_request");
         if let Some(client_state) = self.get_client_state_or_report_error(session, channel) {
-            client_state
-                .bmc_connection
-                .to_bmc_msg_tx
-                .send(ToBmcMessage::ChannelMsg(ChannelMsg::WindowChange {
-                    col_width,
-                    row_height,
-                    pix_width,
-                    pix_height,
-                }))
-                .await
-                .map_err(|_| HandlerError::WritingToChannel {
-                    what: "window change request",
-                })?;
+            // Only the writer's terminal size is applied to the BMC.
+            let is_observer = client_state
+                .attached
+                .as_ref()
+                .is_some_and(|attached| !attached.is_writer());
+            if !is_observer {
+                client_state
+                    .bmc_connection
+                    .to_bmc_msg_tx
+                    .send(ToBmcMessage::ChannelMsg(ChannelMsg::WindowChange {
+                        col_width,
+                        row_height,
+                        pix_width,
+                        pix_height,
+                    }))
+                    .await
+                    .map_err(|_| HandlerError::WritingToChannel {
+                        what: "window change request",
+                    })?;
+            }
+            if let Some(recorder_tx) = &client_state.recorder_tx {
+                recorder_tx
+                    .send(RecorderEvent::Resize {
+                        col_width,
+                        row_height,
+                    })
+                    .ok();
+            }
         }
         Ok(())
     }
@@ -691,6 +863,53 @@ impl AuthFailureReason {
     }
 }
 
+/// Handle ssh-console's own exec commands, which list and manage the sessions attached to a
+/// console, returning None if the command should be sent to the BMC instead:
+///
+/// - `sessions`: List the attached sessions (peer addresses are only shown to admins)
+/// - `disconnect <id>`: Force a session to disconnect (admins only)
+fn session_exec_command(
+    command: &[u8],
+    sessions: &ConsoleSessions,
+    is_admin: bool,
+    user: Option<&str>,
+) -> Option<ExecReply> {
+    let command = std::str::from_utf8(command).ok()?;
+    let words = command.split_whitespace().collect::<Vec<_>>();
+    let (output, exit_status) = match words.as_slice() {
+        ["sessions"] => {
+            let mut output = "ID\tUSER\tFRONTEND\tPEER\tATTACHED SINCE\tROLE\r\n".to_string();
+            for s in sessions.list() {
+                output.push_str(&format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\r\n",
+                    s.id,
+                    s.user,
+                    s.frontend,
+                    if is_admin { s.peer_addr.as_str() } else { "-" },
+                    s.attached_at.to_rfc3339(),
+                    if s.writer { "writer" } else { "observer" },
+                ));
+            }
+            (output, 0)
+        }
+        ["disconnect", _] if !is_admin => (
+            "Error: only admins can disconnect sessions\r\n".to_string(),
+            1,
+        ),
+        ["disconnect", id] => match id.parse() {
+            Ok(id) if sessions.disconnect(id, user.unwrap_or("an admin")) => {
+                (format!("Disconnected session {id}\r\n"), 0)
+            }
+            _ => (format!("Error: no session with id {id}\r\n"), 1),
+        },
+        _ => return None,
+    };
+    Some(ExecReply {
+        output: output.into_bytes(),
+        exit_status,
+    })
+}
+
 /// Check if the user is in the configured authorized_keys file, which grants them admin access (can
 /// log into any host.) This is generally only used for testing: In production we should be using
 /// OpenSSH certificate auth, or no admin auth at all.
@@ -777,3 +996,33 @@ async fn pubkey_auth_tenant(
 
     Ok(authorized)
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::broadcast;
+
+    use super::*;
+
+    #[test]
+    fn test_sessions_command_hides_peer_addr_from_tenants() {
+        let (tx, _rx) = broadcast::channel(16);
+        let sessions = Arc::new(ConsoleSessions::new(tx.downgrade()));
+        let (_alice, _) =
+            sessions.attach(Some("alice".into()), "10.0.0.1:2222".into(), Frontend::Ssh);
+
+        let listing = |is_admin| {
+            let reply = session_exec_command(b"sessions", &sessions, is_admin, None).unwrap();
+            assert_eq!(reply.exit_status, 0);
+            String::from_utf8(reply.output).unwrap()
+        };
+        assert!(listing(true).contains("\talice\tssh\t10.0.0.1:2222\t"));
+        let tenant_listing = listing(false);
+        assert!(tenant_listing.contains("\talice\tssh\t-\t"));
+        assert!(!tenant_listing.contains("10.0.0.1"));
+
+        // Only admins can disconnect sessions
+        let reply = session_exec_command(b"disconnect 0", &sessions, false, None).unwrap();
+        assert_eq!(reply.exit_status, 1);
+        assert_eq!(sessions.list().len(), 1);
+    }
+}
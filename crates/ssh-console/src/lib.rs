@@ -22,7 +22,12 @@ mod ssh_cert_parsing;
 mod ssh_server;
 
 mod console_logger;
+mod console_triggers;
 mod frontend;
+mod recordings_server;
+mod session_recorder;
+mod tls;
+mod websocket_frontend;
 
 // pub mods are only ones used by main.rs and integration tests
 pub mod config;
@@ -38,6 +43,7 @@ use tokio::task::JoinHandle;
 use crate::config::Config;
 use crate::metrics::MetricsState;
 use crate::shutdown_handle::{ReadyHandle, ShutdownHandle};
+use crate::ssh_server::ServerMetrics;
 
 pub static POWER_RESET_COMMAND: &str = "power reset";
 
@@ -57,14 +63,37 @@ pub async fn spawn(config: Config) -> Result<SpawnHandle, SpawnError> {
         .map_err(|_| SpawnError::ClientPoolUnknownFailure)?;
 
     // 2) Start SSH server itself
+    let server_metrics = Arc::new(ServerMetrics::new(&metrics.meter, &config));
     let server = ssh_server::spawn(
         config.clone(),
         forge_api_client.clone(),
         bmc_client_pool.connection_store(),
-        &metrics.meter,
+        server_metrics.clone(),
     )
     .await?;
 
+    // 2b) Start the WebSocket frontend, if enabled
+    let websocket_server = if config.websocket_enabled {
+        Some(
+            websocket_frontend::spawn(
+                config.clone(),
+                forge_api_client.clone(),
+                bmc_client_pool.connection_store(),
+                server_metrics,
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    // 2c) Serve session recordings to admins, if recording is enabled
+    let recordings_server = if config.session_recording_enabled {
+        Some(recordings_server::spawn(config.clone()).await?)
+    } else {
+        None
+    };
+
     // 3) Start metrics server
     let metrics_handle = metrics::spawn(config.clone(), metrics).await?;
 
@@ -75,6 +104,12 @@ pub async fn spawn(config: Config) -> Result<SpawnHandle, SpawnError> {
         metrics_handle.shutdown_and_wait().await;
         bmc_client_pool.shutdown_and_wait().await;
         server.shutdown_and_wait().await;
+        if let Some(websocket_server) = websocket_server {
+            websocket_server.shutdown_and_wait().await;
+        }
+        if let Some(recordings_server) = recordings_server {
+            recordings_server.shutdown_and_wait().await;
+        }
     });
 
     Ok(SpawnHandle {
@@ -89,6 +124,10 @@ pub enum SpawnError {
     ClientPoolUnknownFailure,
     #[error("Error spawning SSH server: {0}")]
     SshServerSpawn(#[from] ssh_server::SpawnError),
+    #[error("Error spawning WebSocket server: {0}")]
+    WebsocketSpawn(#[from] websocket_frontend::SpawnError),
+    #[error("Error spawning session recordings server: {0}")]
+    RecordingsSpawn(#[from] recordings_server::SpawnError),
     #[error("Error spawning metrics server: {0}")]
     MetricsSpawn(#[from] metrics::SpawnError),
 }
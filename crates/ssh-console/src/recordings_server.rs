@@ -0,0 +1,454 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Serves session recordings to admins over HTTPS.
+//!
+//! Recordings contain everything typed into a console, so they are only served to clients
+//! presenting an admin certificate: one which carbide-authn maps to an external user, the same
+//! certificates carbide-api accepts from admin-cli.
+
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use carbide_authn::SpiffeContext;
+use carbide_authn::config::TrustConfig;
+use carbide_authn::middleware::{
+    CertDescriptionMiddleware, ExternalUserInfo, NoAuthorization, Principal,
+};
+use carbide_uuid::machine::MachineId;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use http::{Method, Request, Response};
+use http_body_util::Full;
+use hyper::body;
+use hyper::body::Bytes;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use rustls::RootCertStore;
+use rustls::server::WebPkiClientVerifier;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::Config;
+use crate::shutdown_handle::ShutdownHandle;
+use crate::{session_recorder, tls};
+
+pub async fn spawn(config: Arc<Config>) -> Result<RecordingsServerHandle, SpawnError> {
+    // Fail early on an invalid TLS config rather than on the first connection.
+    tls_acceptor(&config)?;
+    let cert_description = Arc::new(cert_description(&config));
+
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+    let listener = TcpListener::bind(config.recordings_address)
+        .await
+        .map_err(SpawnError::Listen)?;
+
+    tracing::info!(
+        "session recordings listening on {}",
+        config.recordings_address
+    );
+
+    let join_handle = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => {
+                    tracing::info!("session recordings service shutting down");
+                    break;
+                }
+
+                res = listener.accept() => match res {
+                    Ok((stream, addr)) => {
+                        tokio::task::spawn(serve_connection(
+                            stream,
+                            addr,
+                            config.clone(),
+                            cert_description.clone(),
+                        ));
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, "error accepting session recordings connection");
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(RecordingsServerHandle {
+        shutdown_tx,
+        join_handle,
+    })
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SpawnError {
+    #[error("error listening on recordings address: {0}")]
+    Listen(std::io::Error),
+    #[error("error loading TLS config for session recordings: {0}")]
+    Tls(String),
+}
+
+async fn serve_connection(
+    stream: TcpStream,
+    addr: SocketAddr,
+    config: Arc<Config>,
+    cert_description: Arc<CertDescriptionMiddleware<NoAuthorization>>,
+) {
+    // Load certificates for every connection, so that rotated certificates are picked up.
+    let acceptor = match tls_acceptor(&config) {
+        Ok(acceptor) => acceptor,
+        Err(error) => {
+            tracing::error!(%error, "error loading TLS config for session recordings");
+            return;
+        }
+    };
+    let stream = match acceptor.accept(stream).await {
+        Ok(stream) => stream,
+        Err(error) => {
+            tracing::info!(%error, %addr, "session recordings TLS handshake failed");
+            return;
+        }
+    };
+
+    let admin = stream
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|certificates| certificates.first())
+        .and_then(|certificate| {
+            Principal::try_from_client_certificate(certificate, &cert_description).ok()
+        })
+        .and_then(|principal| match principal {
+            Principal::ExternalUser(user) => Some(user),
+            _ => None,
+        });
+
+    let result = auto::Builder::new(TokioExecutor::new())
+        .serve_connection(
+            TokioIo::new(stream),
+            hyper::service::service_fn(move |req| {
+                let config = config.clone();
+                let admin = admin.clone();
+                async move {
+                    Ok::<_, hyper::Error>(serve(req, addr, admin.as_ref(), &config).await)
+                }
+            }),
+        )
+        .await;
+    if let Err(error) = result {
+        tracing::debug!(%error, %addr, "error serving session recordings connection");
+    }
+}
+
+async fn serve(
+    req: Request<body::Incoming>,
+    addr: SocketAddr,
+    admin: Option<&ExternalUserInfo>,
+    config: &Config,
+) -> Response<Full<Bytes>> {
+    let Some(admin) = admin else {
+        tracing::info!(
+            %addr,
+            path = req.uri().path(),
+            "denied session recordings request without an admin certificate"
+        );
+        return Response::builder()
+            .status(403)
+            .body("Session recordings require an admin client certificate".into())
+            .expect("BUG: Response::builder error");
+    };
+
+    match (req.method(), req.uri().path().strip_prefix("/recordings/")) {
+        (&Method::GET, Some(path)) => {
+            tracing::info!(
+                user = admin.user.as_deref().unwrap_or_default(),
+                group = admin.group,
+                %addr,
+                path = req.uri().path(),
+                "serving session recordings"
+            );
+            serve_recordings(path, config).await
+        }
+        _ => Response::builder()
+            .status(404)
+            .body("Invalid URL".into())
+            .expect("BUG: Response::builder error"),
+    }
+}
+
+/// Serve `/recordings/<machine_id>` (a JSON list of recordings) and
+/// `/recordings/<machine_id>/<file_name>` (a single asciicast recording.)
+async fn serve_recordings(path: &str, config: &Config) -> Response<Full<Bytes>> {
+    let (machine_id, file_name) = match path.split_once('/') {
+        Some((machine_id, file_name)) => (machine_id, Some(file_name)),
+        None => (path, None),
+    };
+    let Ok(machine_id) = MachineId::from_str(machine_id) else {
+        return Response::builder()
+            .status(400)
+            .body("Invalid machine ID".into())
+            .expect("BUG: Response::builder error");
+    };
+
+    let response = match file_name {
+        None => session_recorder::list(&config.session_recordings_path, &machine_id)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|recordings| serde_json::to_vec(&recordings).map_err(|e| e.to_string()))
+            .map(|body| {
+                Response::builder()
+                    .status(200)
+                    .header(CONTENT_TYPE, "application/json")
+                    .header(CONTENT_LENGTH, body.len())
+                    .body(body.into())
+            }),
+        Some(file_name) => {
+            session_recorder::read(&config.session_recordings_path, &machine_id, file_name)
+                .await
+                .map_err(|e| e.to_string())
+                .map(|recording| match recording {
+                    Some(body) => Response::builder()
+                        .status(200)
+                        .header(CONTENT_TYPE, "application/x-asciicast")
+                        .header(CONTENT_LENGTH, body.len())
+                        .body(body.into()),
+                    None => Response::builder()
+                        .status(404)
+                        .body("Recording not found".into()),
+                })
+        }
+    };
+
+    response
+        .unwrap_or_else(|error| {
+            tracing::error!(%machine_id, %error, "error reading session recordings");
+            Response::builder()
+                .status(500)
+                .body(format!("Error reading recordings: {error}").into())
+        })
+        .expect("BUG: Response::builder error")
+}
+
+fn cert_description(config: &Config) -> CertDescriptionMiddleware<NoAuthorization> {
+    let trust = TrustConfig {
+        spiffe_trust_domain: "forge.local".to_string(),
+        spiffe_service_base_paths: vec![],
+        spiffe_machine_base_path: "/forge-system/machine/".to_string(),
+        additional_issuer_cns: config.admin_cert_issuer_cns.clone(),
+    };
+    let spiffe_context =
+        SpiffeContext::try_from(trust).expect("BUG: default SPIFFE trust domain is invalid");
+    CertDescriptionMiddleware::new(None, spiffe_context)
+}
+
+/// Build a TLS acceptor which uses our own certificate as the server identity, and requires client
+/// certificates issued by the forge root CA or the admin root CA.
+fn tls_acceptor(config: &Config) -> Result<TlsAcceptor, SpawnError> {
+    let (certs, key) = tls::server_identity(config).map_err(SpawnError::Tls)?;
+
+    let roots = {
+        let mut roots = RootCertStore::empty();
+        roots.add_parsable_certificates(
+            tls::read_certs(&config.forge_root_ca_path).map_err(SpawnError::Tls)?,
+        );
+        if let Some(admin_root_ca_path) = &config.admin_root_ca_path {
+            roots.add_parsable_certificates(
+                tls::read_certs(admin_root_ca_path).map_err(SpawnError::Tls)?,
+            );
+        }
+        Arc::new(roots)
+    };
+
+    let crypto_provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let client_cert_verifier =
+        WebPkiClientVerifier::builder_with_provider(roots, crypto_provider.clone())
+            .allow_unknown_revocation_status()
+            .build()
+            .map_err(|e| SpawnError::Tls(format!("could not build client cert verifier: {e}")))?;
+
+    let mut tls = rustls::ServerConfig::builder_with_provider(crypto_provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| SpawnError::Tls(e.to_string()))?
+        .with_client_cert_verifier(client_cert_verifier)
+        .with_single_cert(certs, key)
+        .map_err(|e| SpawnError::Tls(format!("rustls error building server config: {e}")))?;
+    tls.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(TlsAcceptor::from(Arc::new(tls)))
+}
+
+pub struct RecordingsServerHandle {
+    shutdown_tx: oneshot::Sender<()>,
+    join_handle: JoinHandle<()>,
+}
+
+impl ShutdownHandle<()> for RecordingsServerHandle {
+    fn into_parts(self) -> (oneshot::Sender<()>, JoinHandle<()>) {
+        (self.shutdown_tx, self.join_handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener as StdTcpListener;
+
+    use rcgen::{
+        BasicConstraints, CertificateParams, CertifiedIssuer, DnType, DnValue, IsCa, KeyPair,
+    };
+    use temp_dir::TempDir;
+
+    use super::*;
+
+    const MACHINE_ID: &str = "fm100ht038bg3qsho433vkg684heguv282qaggmrsh2ugn1qk096n2c6hcg";
+
+    fn ca(common_name: &str) -> CertifiedIssuer<'static, KeyPair> {
+        let mut params = CertificateParams::new(vec![]).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.distinguished_name.push(
+            DnType::CommonName,
+            DnValue::PrintableString(common_name.try_into().unwrap()),
+        );
+        CertifiedIssuer::self_signed(params, KeyPair::generate().unwrap()).unwrap()
+    }
+
+    /// Returns a (cert, key) PEM pair issued by `issuer`.
+    fn issue(
+        issuer: &CertifiedIssuer<'static, KeyPair>,
+        subject_alt_names: Vec<String>,
+        subject: &[(DnType, &str)],
+    ) -> (String, String) {
+        let mut params = CertificateParams::new(subject_alt_names).unwrap();
+        for (ty, value) in subject {
+            params.distinguished_name.push(
+                ty.clone(),
+                DnValue::PrintableString((*value).try_into().unwrap()),
+            );
+        }
+        let key = KeyPair::generate().unwrap();
+        let cert = params.signed_by(&key, issuer).unwrap();
+        (cert.pem(), key.serialize_pem())
+    }
+
+    fn client(
+        ca: &CertifiedIssuer<'static, KeyPair>,
+        identity: &(String, String),
+        addr: SocketAddr,
+    ) -> reqwest::Client {
+        reqwest::Client::builder()
+            .tls_certs_only([reqwest::Certificate::from_pem(ca.pem().as_bytes()).unwrap()])
+            .identity(
+                reqwest::Identity::from_pem(format!("{}{}", identity.0, identity.1).as_bytes())
+                    .unwrap(),
+            )
+            .resolve("localhost", addr)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_recordings_are_only_served_to_admins() {
+        let dir = TempDir::new().unwrap();
+        let forge_ca = ca("Forge Root CA");
+        let admin_ca = ca("Admin CA");
+        let write = |name: &str, contents: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            path
+        };
+
+        let (server_cert, server_key) = issue(
+            &forge_ca,
+            vec!["localhost".to_string()],
+            &[(DnType::CommonName, "ssh-console")],
+        );
+        let addr = StdTcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let config = Arc::new(Config {
+            recordings_address: addr,
+            forge_root_ca_path: write("ca.crt", &forge_ca.pem()),
+            admin_root_ca_path: Some(write("admin_ca.crt", &admin_ca.pem())),
+            admin_cert_issuer_cns: vec!["Admin CA".to_string()],
+            client_cert_path: write("tls.crt", &server_cert),
+            client_key_path: write("tls.key", &server_key),
+            session_recordings_path: dir.path().join("recordings"),
+            session_recording_enabled: true,
+            ..Default::default()
+        });
+
+        let machine_dir = config.session_recordings_path.join(MACHINE_ID);
+        std::fs::create_dir_all(&machine_dir).unwrap();
+        std::fs::write(machine_dir.join("session.cast"), "{\"version\": 2}\n").unwrap();
+
+        let handle = spawn(config).await.unwrap();
+
+        let admin = client(
+            &forge_ca,
+            &issue(
+                &admin_ca,
+                vec![],
+                &[
+                    (DnType::OrganizationalUnitName, "admins"),
+                    (DnType::CommonName, "alice"),
+                ],
+            ),
+            addr,
+        );
+        let url = format!("https://localhost:{}/recordings/{MACHINE_ID}", addr.port());
+        let response = admin
+            .get(format!("{url}/session.cast"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "{\"version\": 2}\n");
+        let response = admin.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        // Service certificates are trusted by the TLS acceptor, but aren't admins
+        let service = client(
+            &forge_ca,
+            &issue(&forge_ca, vec![], &[(DnType::CommonName, "some-service")]),
+            addr,
+        );
+        let response = service
+            .get(format!("{url}/session.cast"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 403);
+
+        // Certificates from unknown CAs don't get past the TLS handshake
+        let untrusted = client(
+            &forge_ca,
+            &issue(
+                &ca("Admin CA"),
+                vec![],
+                &[
+                    (DnType::OrganizationalUnitName, "admins"),
+                    (DnType::CommonName, "mallory"),
+                ],
+            ),
+            addr,
+        );
+        assert!(untrusted.get(&url).send().await.is_err());
+
+        handle.shutdown_and_wait().await;
+    }
+}
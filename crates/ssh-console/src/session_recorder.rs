@@ -0,0 +1,582 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Records interactive frontend sessions in the asciicast v2 format.
+//!
+//! Each recording is written to `<session_recordings_path>/<machine_id>/<name>.cast`, next to a
+//! `<name>.json` file holding [`RecordingMetadata`] (who was attached, and when.) The metadata is
+//! written once when the session starts and rewritten with `ended_at` when it ends, so that
+//! recordings of sessions which are still in progress (or were cut short by a crash) can still be
+//! listed. Recordings beyond the configured [`Retention`] are deleted whenever a new session on the
+//! same machine starts.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use carbide_uuid::machine::MachineId;
+use chrono::{DateTime, SecondsFormat, Utc};
+use russh::ChannelMsg;
+use serde::{Deserialize, Serialize};
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::bmc::message_proxy::ToFrontendMessage;
+use crate::config::Config;
+use crate::shutdown_handle::ShutdownHandle;
+
+static RECORDING_EXTENSION: &str = "cast";
+static METADATA_EXTENSION: &str = "json";
+
+/// Metadata stored alongside each recording.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecordingMetadata {
+    pub machine_id: String,
+    /// The authenticated user: the user from the SSH certificate, or the fingerprint of the public
+    /// key used to log in.
+    pub user: Option<String>,
+    pub peer_addr: String,
+    /// RFC3339 timestamp of when the session started
+    pub started_at: String,
+    /// RFC3339 timestamp of when the session ended, if it has ended
+    pub ended_at: Option<String>,
+    /// File name of the asciicast recording, relative to the machine's recording directory
+    pub file_name: String,
+}
+
+/// Something that happened in the session which did not come from the BMC broadcast channel.
+pub enum RecorderEvent {
+    /// Data the user typed
+    Input(Bytes),
+    /// Data sent to the user outside of the BMC broadcast channel (ie. the pending output line)
+    Output(Bytes),
+    /// The user's terminal was resized
+    Resize { col_width: u32, row_height: u32 },
+}
+
+/// How many recordings to keep for each machine.
+#[derive(Debug, Clone, Copy)]
+pub struct Retention {
+    pub max_per_machine: usize,
+    pub max_age: Duration,
+}
+
+impl From<&Config> for Retention {
+    fn from(config: &Config) -> Self {
+        Self {
+            max_per_machine: config.session_recordings_max_per_machine,
+            max_age: config.session_recordings_max_age,
+        }
+    }
+}
+
+pub struct SessionInfo {
+    pub machine_id: MachineId,
+    pub user: Option<String>,
+    pub peer_addr: String,
+    pub term: Option<String>,
+    pub col_width: u32,
+    pub row_height: u32,
+}
+
+/// Spawn a background task which records a single frontend session.
+pub fn spawn(
+    recordings_path: &Path,
+    retention: Retention,
+    session: SessionInfo,
+    from_bmc_rx: broadcast::Receiver<ToFrontendMessage>,
+) -> (SessionRecorderHandle, mpsc::UnboundedSender<RecorderEvent>) {
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    let (event_tx, event_rx) = mpsc::unbounded_channel();
+    let recorder = SessionRecorder::new(recordings_path, retention, session);
+
+    let join_handle = tokio::spawn(recorder.run(shutdown_rx, from_bmc_rx, event_rx));
+
+    (
+        SessionRecorderHandle {
+            shutdown_tx,
+            join_handle,
+        },
+        event_tx,
+    )
+}
+
+pub struct SessionRecorderHandle {
+    shutdown_tx: oneshot::Sender<()>,
+    join_handle: JoinHandle<()>,
+}
+
+impl ShutdownHandle<()> for SessionRecorderHandle {
+    fn into_parts(self) -> (oneshot::Sender<()>, JoinHandle<()>) {
+        (self.shutdown_tx, self.join_handle)
+    }
+}
+
+struct SessionRecorder {
+    session: SessionInfo,
+    metadata: RecordingMetadata,
+    machine_dir: PathBuf,
+    retention: Retention,
+}
+
+impl SessionRecorder {
+    fn new(recordings_path: &Path, retention: Retention, session: SessionInfo) -> Self {
+        let now = Utc::now();
+        let file_name = format!(
+            "{}_{}.{RECORDING_EXTENSION}",
+            now.format("%Y%m%dT%H%M%S%.3fZ"),
+            uuid::Uuid::new_v4()
+        );
+        Self {
+            machine_dir: recordings_path.join(session.machine_id.to_string()),
+            metadata: RecordingMetadata {
+                machine_id: session.machine_id.to_string(),
+                user: session.user.clone(),
+                peer_addr: session.peer_addr.clone(),
+                started_at: now.to_rfc3339_opts(SecondsFormat::Millis, true),
+                ended_at: None,
+                file_name,
+            },
+            session,
+            retention,
+        }
+    }
+
+    async fn run(
+        mut self,
+        mut shutdown_rx: oneshot::Receiver<()>,
+        mut from_bmc_rx: broadcast::Receiver<ToFrontendMessage>,
+        mut event_rx: mpsc::UnboundedReceiver<RecorderEvent>,
+    ) {
+        let machine_id = self.session.machine_id;
+        let mut writer = match self.start().await {
+            Ok(writer) => writer,
+            Err(error) => {
+                tracing::error!(
+                    path = self.machine_dir.display().to_string(),
+                    %machine_id,
+                    %error,
+                    "could not start session recording"
+                );
+                return;
+            }
+        };
+
+        loop {
+            let result = tokio::select! {
+                _ = &mut shutdown_rx => {
+                    break;
+                }
+
+                res = from_bmc_rx.recv() => match res {
+                    Ok(msg) => match Arc::<ChannelMsg>::from(msg).as_ref() {
+                        ChannelMsg::Data { data } | ChannelMsg::ExtendedData { data, .. } => {
+                            writer.output(data).await
+                        }
+                        _ => Ok(()),
+                    },
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(count)) => {
+                        tracing::warn!(
+                            %machine_id,
+                            "session recorder is lagged by {count} messages, data may be missing from recording"
+                        );
+                        Ok(())
+                    }
+                },
+
+                Some(event) = event_rx.recv() => match event {
+                    RecorderEvent::Input(data) => writer.input(&data).await,
+                    RecorderEvent::Output(data) => writer.output(&data).await,
+                    RecorderEvent::Resize { col_width, row_height } => {
+                        writer.resize(col_width, row_height).await
+                    }
+                },
+            };
+            if let Err(error) = result {
+                tracing::error!(%machine_id, %error, "error writing session recording, stopping");
+                break;
+            }
+        }
+
+        tracing::debug!(%machine_id, "shutting down session recorder");
+        writer.file.flush().await.ok();
+        self.metadata.ended_at = Some(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true));
+        if let Err(error) = self.write_metadata().await {
+            tracing::error!(%machine_id, %error, "could not write session recording metadata");
+        }
+    }
+
+    async fn start(&self) -> io::Result<AsciicastWriter> {
+        tokio::fs::create_dir_all(&self.machine_dir).await?;
+        if let Err(error) = prune(&self.machine_dir, self.retention).await {
+            tracing::warn!(
+                path = self.machine_dir.display().to_string(),
+                %error,
+                "could not prune old session recordings"
+            );
+        }
+        self.write_metadata().await?;
+
+        let file = OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .open(self.machine_dir.join(&self.metadata.file_name))
+            .await?;
+        let mut writer = AsciicastWriter::new(file);
+        let title = match &self.metadata.user {
+            Some(user) => format!("{} ({user})", self.metadata.machine_id),
+            None => self.metadata.machine_id.clone(),
+        };
+        let mut header = serde_json::json!({
+            "version": 2,
+            "width": self.session.col_width,
+            "height": self.session.row_height,
+            "timestamp": Utc::now().timestamp(),
+            "title": title,
+        });
+        if let Some(term) = &self.session.term {
+            header["env"] = serde_json::json!({ "TERM": term });
+        }
+        writer.write_line(&header).await?;
+        Ok(writer)
+    }
+
+    async fn write_metadata(&self) -> io::Result<()> {
+        let path = self
+            .machine_dir
+            .join(&self.metadata.file_name)
+            .with_extension(METADATA_EXTENSION);
+        tokio::fs::write(path, serde_json::to_vec(&self.metadata)?).await
+    }
+}
+
+struct AsciicastWriter {
+    file: tokio::fs::File,
+    started: Instant,
+    // Incomplete UTF-8 sequences left over from the last chunk, since asciicast events are strings
+    // but BMC output can be split anywhere.
+    input_remainder: Vec<u8>,
+    output_remainder: Vec<u8>,
+}
+
+impl AsciicastWriter {
+    fn new(file: tokio::fs::File) -> Self {
+        Self {
+            file,
+            started: Instant::now(),
+            input_remainder: Vec::new(),
+            output_remainder: Vec::new(),
+        }
+    }
+
+    async fn output(&mut self, data: &[u8]) -> io::Result<()> {
+        let data = decode_utf8(&mut self.output_remainder, data);
+        self.event("o", data).await
+    }
+
+    async fn input(&mut self, data: &[u8]) -> io::Result<()> {
+        let data = decode_utf8(&mut self.input_remainder, data);
+        self.event("i", data).await
+    }
+
+    async fn resize(&mut self, col_width: u32, row_height: u32) -> io::Result<()> {
+        self.event("r", format!("{col_width}x{row_height}")).await
+    }
+
+    async fn event(&mut self, code: &str, data: String) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let time = self.started.elapsed().as_secs_f64();
+        self.write_line(&serde_json::json!([time, code, data]))
+            .await
+    }
+
+    async fn write_line(&mut self, value: &serde_json::Value) -> io::Result<()> {
+        let mut line = serde_json::to_vec(value)?;
+        line.push(b'\n');
+        self.file.write_all(&line).await
+    }
+}
+
+/// Decode as much of `remainder + data` as possible, keeping a trailing incomplete UTF-8 sequence
+/// in `remainder` for next time. Invalid bytes are replaced.
+fn decode_utf8(remainder: &mut Vec<u8>, data: &[u8]) -> String {
+    remainder.extend_from_slice(data);
+    let complete = match std::str::from_utf8(remainder) {
+        Ok(_) => remainder.len(),
+        Err(error) if error.error_len().is_none() => error.valid_up_to(),
+        Err(_) => remainder.len(),
+    };
+    let decoded = String::from_utf8_lossy(&remainder[..complete]).into_owned();
+    remainder.drain(..complete);
+    decoded
+}
+
+/// Delete the recordings in `machine_dir` which ended longer than `max_age` ago, then the oldest
+/// ones until there is room for one more within `max_per_machine`.
+async fn prune(machine_dir: &Path, retention: Retention) -> io::Result<()> {
+    let recordings = list_dir(machine_dir).await?;
+    let keep = retention.max_per_machine.saturating_sub(1);
+    let excess = recordings.len().saturating_sub(keep);
+    let now = Utc::now();
+    for (index, recording) in recordings.iter().enumerate() {
+        let last_active = recording
+            .ended_at
+            .as_deref()
+            .unwrap_or(&recording.started_at);
+        let expired = DateTime::parse_from_rfc3339(last_active).is_ok_and(|last_active| {
+            (now - last_active.to_utc())
+                .to_std()
+                .is_ok_and(|age| age > retention.max_age)
+        });
+        if index >= excess && !expired {
+            continue;
+        }
+        let path = machine_dir.join(&recording.file_name);
+        for path in [path.with_extension(METADATA_EXTENSION), path] {
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+                Err(error) => return Err(error),
+            }
+        }
+        tracing::debug!(
+            machine_id = recording.machine_id,
+            file_name = recording.file_name,
+            "deleted old session recording"
+        );
+    }
+    Ok(())
+}
+
+/// List the recordings for a machine, oldest first.
+pub async fn list(
+    recordings_path: &Path,
+    machine_id: &MachineId,
+) -> io::Result<Vec<RecordingMetadata>> {
+    list_dir(&recordings_path.join(machine_id.to_string())).await
+}
+
+async fn list_dir(machine_dir: &Path) -> io::Result<Vec<RecordingMetadata>> {
+    let mut read_dir = match tokio::fs::read_dir(machine_dir).await {
+        Ok(read_dir) => read_dir,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(error),
+    };
+
+    let mut recordings = Vec::new();
+    while let Some(entry) = read_dir.next_entry().await? {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != METADATA_EXTENSION) {
+            continue;
+        }
+        match serde_json::from_slice::<RecordingMetadata>(&tokio::fs::read(&path).await?) {
+            Ok(metadata) => recordings.push(metadata),
+            Err(error) => {
+                tracing::warn!(
+                    path = path.display().to_string(),
+                    %error,
+                    "invalid recording metadata"
+                );
+            }
+        }
+    }
+    recordings.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    Ok(recordings)
+}
+
+/// Read a single recording for a machine. Returns None if the recording does not exist or if
+/// `file_name` does not name a recording.
+pub async fn read(
+    recordings_path: &Path,
+    machine_id: &MachineId,
+    file_name: &str,
+) -> io::Result<Option<Vec<u8>>> {
+    let is_recording_name = Path::new(file_name)
+        .extension()
+        .is_some_and(|ext| ext == RECORDING_EXTENSION)
+        && !file_name.starts_with('.')
+        && !file_name.contains(['/', '\\']);
+    if !is_recording_name {
+        return Ok(None);
+    }
+    let path = recordings_path.join(machine_id.to_string()).join(file_name);
+    match tokio::fs::read(path).await {
+        Ok(data) => Ok(Some(data)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use temp_dir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_utf8_keeps_incomplete_sequences() {
+        let mut remainder = Vec::new();
+        let snowman = "☃".as_bytes();
+        assert_eq!(decode_utf8(&mut remainder, &[b'a', snowman[0]]), "a");
+        assert_eq!(decode_utf8(&mut remainder, &snowman[1..]), "☃");
+        assert!(remainder.is_empty());
+        assert_eq!(decode_utf8(&mut remainder, &[0xff, b'b']), "\u{fffd}b");
+    }
+
+    #[tokio::test]
+    async fn test_recording_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let machine_id =
+            MachineId::from_str("fm100ht038bg3qsho433vkg684heguv282qaggmrsh2ugn1qk096n2c6hcg")
+                .unwrap();
+        let (from_bmc_tx, from_bmc_rx) = broadcast::channel(16);
+        let (handle, event_tx) = spawn(
+            dir.path(),
+            Retention {
+                max_per_machine: 10,
+                max_age: Duration::from_secs(3600),
+            },
+            SessionInfo {
+                machine_id,
+                user: Some("alice".to_string()),
+                peer_addr: "127.0.0.1:1234".to_string(),
+                term: Some("xterm".to_string()),
+                col_width: 80,
+                row_height: 24,
+            },
+            from_bmc_rx,
+        );
+
+        event_tx
+            .send(RecorderEvent::Input(Bytes::from_static(b"root\r")))
+            .unwrap();
+        let sent = from_bmc_tx.send(ToFrontendMessage::Channel(Arc::new(ChannelMsg::Data {
+            data: Bytes::from_static(b"Password: "),
+        })));
+        assert!(sent.is_ok());
+        event_tx
+            .send(RecorderEvent::Resize {
+                col_width: 120,
+                row_height: 40,
+            })
+            .unwrap();
+        // Let the recorder drain its channels before shutting down
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        handle.shutdown_and_wait().await;
+
+        let recordings = list(dir.path(), &machine_id).await.unwrap();
+        assert_eq!(recordings.len(), 1);
+        let metadata = &recordings[0];
+        assert_eq!(metadata.user.as_deref(), Some("alice"));
+        assert_eq!(metadata.machine_id, machine_id.to_string());
+        assert!(metadata.ended_at.is_some());
+
+        let cast = read(dir.path(), &machine_id, &metadata.file_name)
+            .await
+            .unwrap()
+            .unwrap();
+        let lines = String::from_utf8(cast)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 80);
+        assert_eq!(lines[0]["env"]["TERM"], "xterm");
+        let events = lines[1..]
+            .iter()
+            .map(|event| (event[1].as_str().unwrap(), event[2].as_str().unwrap()))
+            .collect::<Vec<_>>();
+        assert!(events.contains(&("i", "root\r")));
+        assert!(events.contains(&("o", "Password: ")));
+        assert!(events.contains(&("r", "120x40")));
+
+        assert!(
+            read(dir.path(), &machine_id, "../other.cast")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_recent_recordings() {
+        let dir = TempDir::new().unwrap();
+        let now = Utc::now();
+        let timestamp =
+            |ago: chrono::Duration| (now - ago).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let minutes = chrono::Duration::minutes;
+        // (name, started, ended), in minutes ago
+        let recordings = [
+            // Deleted to make room for the new recording
+            ("long", 3 * 24 * 60, Some(1)),
+            ("expired", 3 * 24 * 60 - 1, Some(2 * 24 * 60)),
+            ("abandoned", 2 * 24 * 60, None),
+            ("recent", 5, Some(2)),
+            ("active", 1, None),
+        ];
+        for (name, started, ended) in recordings {
+            let metadata = RecordingMetadata {
+                machine_id: "machine".to_string(),
+                user: None,
+                peer_addr: "127.0.0.1:1234".to_string(),
+                started_at: timestamp(minutes(started)),
+                ended_at: ended.map(|ended| timestamp(minutes(ended))),
+                file_name: format!("{name}.{RECORDING_EXTENSION}"),
+            };
+            let path = dir.path().join(&metadata.file_name);
+            std::fs::write(&path, "").unwrap();
+            std::fs::write(
+                path.with_extension(METADATA_EXTENSION),
+                serde_json::to_vec(&metadata).unwrap(),
+            )
+            .unwrap();
+        }
+
+        prune(
+            dir.path(),
+            Retention {
+                max_per_machine: 5,
+                max_age: Duration::from_secs(24 * 60 * 60),
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut remaining = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            ["active.cast", "active.json", "recent.cast", "recent.json"]
+        );
+    }
+}
@@ -0,0 +1,56 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Loading of the certificates used by ssh-console's HTTPS services.
+
+use std::path::Path;
+
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+use crate::config::Config;
+
+/// The certificate chain and key ssh-console serves HTTPS with, which are the same ones it uses as
+/// a client of carbide-api.
+pub fn server_identity(
+    config: &Config,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), String> {
+    let certs = read_certs(&config.client_cert_path)?;
+    let key = rustls_pemfile::private_key(&mut read_pem(&config.client_key_path)?.as_slice())
+        .map_err(|e| {
+            format!(
+                "error parsing key file at {}: {e}",
+                config.client_key_path.display()
+            )
+        })?
+        .ok_or_else(|| {
+            format!(
+                "no keys found in key file at {}",
+                config.client_key_path.display()
+            )
+        })?;
+    Ok((certs, key))
+}
+
+pub fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    rustls_pemfile::certs(&mut read_pem(path)?.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("error parsing {}: {e}", path.display()))
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("error reading {}: {e}", path.display()))
+}
@@ -0,0 +1,924 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::Router;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, Path, Query, State};
+use axum::http::{HeaderMap, Request, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use carbide_uuid::instance::InstanceId;
+use futures_util::{SinkExt, StreamExt};
+use hyper::body;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use lazy_static::lazy_static;
+use rpc::forge;
+use rpc::forge_api_client::ForgeApiClient;
+use russh::ChannelMsg;
+use serde::Deserialize;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+use tokio_rustls::TlsAcceptor;
+use tower_service::Service;
+
+use crate::bmc::client::BmcConnectionSubscription;
+use crate::bmc::client_pool::BmcConnectionStore;
+use crate::bmc::console_sessions::{Frontend, WriteAccess};
+use crate::bmc::message_proxy::ToBmcMessage;
+use crate::config::Config;
+use crate::session_recorder::{RecorderEvent, Retention, SessionInfo};
+use crate::shutdown_handle::ShutdownHandle;
+use crate::ssh_server::ServerMetrics;
+use crate::{session_recorder, tls};
+
+static BANNER_WEBSOCKET: &str = "\
++------------------------------------------------------------------------------+\r\n\
+|                  NVIDIA Carbide Web Serial Console (beta)                    |\r\n\
++------------------------------------------------------------------------------+\r\n\
+|             Close this window or tab to terminate the session.               |\r\n\
++------------------------------------------------------------------------------+\r\n\
+";
+
+/// The WebSocket subprotocol the console speaks. Browsers can't set an `Authorization` header on
+/// WebSocket connections, so they offer this subprotocol along with the console token as a second
+/// subprotocol, prefixed with [`TOKEN_SUBPROTOCOL_PREFIX`]. Only this one is ever selected, so the
+/// token is not echoed back.
+const CONSOLE_SUBPROTOCOL: &str = "carbide-console";
+const TOKEN_SUBPROTOCOL_PREFIX: &str = "carbide-console-token.";
+
+lazy_static! {
+    static ref TOKEN_AUTH_FAILURE_METRIC: [opentelemetry::KeyValue; 1] =
+        [opentelemetry::KeyValue::new("auth_type", "token")];
+}
+
+/// Serve consoles over WebSocket (with TLS) at `/console/<machine_id or instance_id>`, for
+/// browser-based terminals like xterm.js.
+///
+/// Output from the BMC is sent as binary messages. Clients send raw input as binary messages, or
+/// [`ClientMessage`]s as JSON text messages.
+pub async fn spawn(
+    config: Arc<Config>,
+    forge_api_client: ForgeApiClient,
+    bmc_connection_store: BmcConnectionStore,
+    metrics: Arc<ServerMetrics>,
+) -> Result<Handle, SpawnError> {
+    use SpawnError::*;
+    let token_verifier = TokenVerifier::load(&config)?;
+    // Fail early on an invalid TLS config rather than on the first connection.
+    tls_acceptor(&config)?;
+    let websocket_address = config.websocket_address;
+
+    let state = Arc::new(WebsocketState {
+        config: config.clone(),
+        forge_api_client,
+        bmc_connection_store,
+        metrics,
+        token_verifier,
+    });
+    let router = Router::new()
+        .route("/console/{machine_string}", get(console))
+        .with_state(state);
+
+    let listener = TcpListener::bind(websocket_address)
+        .await
+        .map_err(|error| Listening {
+            addr: websocket_address,
+            error,
+        })?;
+    tracing::info!("websocket console listening on {}", websocket_address);
+
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+    let join_handle = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => {
+                    tracing::info!("websocket console shutting down");
+                    break;
+                }
+
+                res = listener.accept() => match res {
+                    Ok((stream, addr)) => {
+                        tokio::task::spawn(serve_connection(
+                            stream,
+                            addr,
+                            config.clone(),
+                            router.clone(),
+                        ));
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, "error accepting websocket console connection");
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(Handle {
+        shutdown_tx,
+        join_handle,
+    })
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SpawnError {
+    #[error("Error reading websocket token public key at {path}: {error}")]
+    ReadingTokenPublicKey { path: String, error: std::io::Error },
+    #[error("Invalid websocket token public key at {path}: {error}")]
+    InvalidTokenPublicKey {
+        path: String,
+        error: jsonwebtoken::errors::Error,
+    },
+    #[error("Error listening on {addr}: {error}")]
+    Listening {
+        addr: SocketAddr,
+        error: std::io::Error,
+    },
+    #[error("Error loading TLS config for websocket console: {0}")]
+    Tls(String),
+}
+
+async fn serve_connection(
+    stream: TcpStream,
+    addr: SocketAddr,
+    config: Arc<Config>,
+    router: Router,
+) {
+    // Load certificates for every connection, so that rotated certificates are picked up.
+    let acceptor = match tls_acceptor(&config) {
+        Ok(acceptor) => acceptor,
+        Err(error) => {
+            tracing::error!(%error, "error loading TLS config for websocket console");
+            return;
+        }
+    };
+    let stream = match acceptor.accept(stream).await {
+        Ok(stream) => stream,
+        Err(error) => {
+            tracing::info!(%error, %addr, "websocket console TLS handshake failed");
+            return;
+        }
+    };
+
+    let result = auto::Builder::new(TokioExecutor::new())
+        .serve_connection_with_upgrades(
+            TokioIo::new(stream),
+            hyper::service::service_fn(move |mut req: Request<body::Incoming>| {
+                req.extensions_mut().insert(ConnectInfo(addr));
+                router.clone().call(req)
+            }),
+        )
+        .await;
+    if let Err(error) = result {
+        tracing::debug!(%error, %addr, "error serving websocket console connection");
+    }
+}
+
+/// Browsers don't present client certificates for WebSocket connections, so clients are
+/// authenticated by their console token instead.
+fn tls_acceptor(config: &Config) -> Result<TlsAcceptor, SpawnError> {
+    let (certs, key) = tls::server_identity(config).map_err(SpawnError::Tls)?;
+    let crypto_provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let mut tls = rustls::ServerConfig::builder_with_provider(crypto_provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| SpawnError::Tls(e.to_string()))?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| SpawnError::Tls(format!("rustls error building server config: {e}")))?;
+    // WebSocket upgrades are HTTP/1.1 requests
+    tls.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    Ok(TlsAcceptor::from(Arc::new(tls)))
+}
+
+pub struct Handle {
+    shutdown_tx: oneshot::Sender<()>,
+    join_handle: JoinHandle<()>,
+}
+
+impl ShutdownHandle<()> for Handle {
+    fn into_parts(self) -> (oneshot::Sender<()>, JoinHandle<()>) {
+        (self.shutdown_tx, self.join_handle)
+    }
+}
+
+struct WebsocketState {
+    config: Arc<Config>,
+    forge_api_client: ForgeApiClient,
+    bmc_connection_store: BmcConnectionStore,
+    metrics: Arc<ServerMetrics>,
+    token_verifier: TokenVerifier,
+}
+
+#[derive(Deserialize)]
+struct ConsoleQuery {
+    #[serde(default = "default_cols")]
+    cols: u32,
+    #[serde(default = "default_rows")]
+    rows: u32,
+}
+
+fn default_cols() -> u32 {
+    80
+}
+
+fn default_rows() -> u32 {
+    24
+}
+
+/// Control messages sent by the client as JSON text messages.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    /// Input typed by the user (e.g. from xterm.js's onData)
+    Input { data: String },
+    /// The terminal was resized (e.g. from xterm.js's onResize)
+    Resize { cols: u32, rows: u32 },
+    /// Take the write lock from whoever holds it
+    TakeWriteLock,
+}
+
+async fn console(
+    State(state): State<Arc<WebsocketState>>,
+    Path(machine_string): Path<String>,
+    Query(query): Query<ConsoleQuery>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let peer_addr = peer_addr.to_string();
+    let token = request_token(&headers);
+
+    let user = match authenticate(&state, token, &machine_string).await {
+        Ok(user) => {
+            tracing::info!(
+                peer_addr,
+                user,
+                machine = machine_string,
+                "websocket token auth succeeded"
+            );
+            Some(user)
+        }
+        Err(error) if state.config.insecure => {
+            tracing::info!(
+                peer_addr,
+                %error,
+                "Overriding token rejection because we are in insecure (testing) mode"
+            );
+            None
+        }
+        Err(error) => {
+            tracing::warn!(
+                peer_addr,
+                machine = machine_string,
+                %error,
+                "websocket authentication failed"
+            );
+            state
+                .metrics
+                .client_auth_failures_total
+                .add(1, TOKEN_AUTH_FAILURE_METRIC.as_slice());
+            return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+        }
+    };
+
+    let bmc_connection = match state
+        .bmc_connection_store
+        .get_connection(
+            &machine_string,
+            &state.config,
+            &state.forge_api_client,
+            state.metrics.clone(),
+        )
+        .await
+    {
+        Ok(bmc_connection) => bmc_connection,
+        Err(error) => {
+            tracing::warn!(peer_addr, %error, "could not get BMC connection for websocket");
+            return (StatusCode::NOT_FOUND, error.to_string()).into_response();
+        }
+    };
+
+    let config = state.config.clone();
+    ws.protocols([CONSOLE_SUBPROTOCOL])
+        .on_upgrade(move |socket| async move {
+            let session_info = SessionInfo {
+                machine_id: bmc_connection.machine_id,
+                user,
+                peer_addr,
+                term: Some("xterm".to_string()),
+                col_width: query.cols,
+                row_height: query.rows,
+            };
+            run_session(socket, bmc_connection, session_info, config).await
+        })
+}
+
+/// Proxy a single websocket session to and from the BMC, until either side disconnects.
+async fn run_session(
+    socket: WebSocket,
+    bmc_connection: BmcConnectionSubscription,
+    session_info: SessionInfo,
+    config: Arc<Config>,
+) {
+    let machine_id = bmc_connection.machine_id;
+    let peer_addr = session_info.peer_addr.clone();
+    let user = session_info.user.clone();
+    let (mut ws_tx, mut ws_rx) = socket.split();
+
+    let Some(to_frontend_msg_tx) = bmc_connection.to_frontend_msg_weak_tx.upgrade() else {
+        tracing::warn!(
+            %machine_id,
+            peer_addr,
+            "BMC connection dropped before we could subscribe to messages"
+        );
+        ws_tx.close().await.ok();
+        return;
+    };
+    let mut from_bmc_rx = to_frontend_msg_tx.subscribe();
+
+    // Start recording before anything is sent to the user, so the recording is complete.
+    let (recorder_handle, recorder_tx) = if config.session_recording_enabled {
+        let (recorder_handle, recorder_tx) = session_recorder::spawn(
+            &config.session_recordings_path,
+            Retention::from(config.as_ref()),
+            session_info,
+            to_frontend_msg_tx.subscribe(),
+        );
+        (Some(recorder_handle), Some(recorder_tx))
+    } else {
+        (None, None)
+    };
+    std::mem::drop(to_frontend_msg_tx);
+
+    // Register the session, so its input is arbitrated with other sessions on this console.
+    let (attached, kick_rx) =
+        bmc_connection
+            .sessions
+            .attach(user, peer_addr.clone(), Frontend::Websocket);
+    let kicked = async move {
+        if kick_rx.await.is_err() {
+            // The session was detached without being kicked, never fire.
+            std::future::pending::<()>().await;
+        }
+    };
+    tokio::pin!(kicked);
+    let mut read_only_notified = false;
+
+    ws_tx
+        .send(Message::Binary(BANNER_WEBSOCKET.as_bytes().into()))
+        .await
+        .ok();
+
+    // Tell the backend to return any "pending line": data since the last newline
+    let (pending_line_reply_tx, pending_line_reply_rx) = oneshot::channel();
+    bmc_connection
+        .to_bmc_msg_tx
+        .send(ToBmcMessage::EchoConnectionMessage {
+            reply_tx: pending_line_reply_tx,
+        })
+        .await
+        .ok();
+    if let Ok(pending_line) = pending_line_reply_rx.await {
+        if let Some(recorder_tx) = &recorder_tx {
+            recorder_tx
+                .send(RecorderEvent::Output(pending_line.clone().into()))
+                .ok();
+        }
+        ws_tx.send(Message::Binary(pending_line.into())).await.ok();
+    }
+
+    loop {
+        tokio::select! {
+            _ = &mut kicked => {
+                tracing::info!(peer_addr, "session disconnected by an admin");
+                break;
+            }
+
+            res = from_bmc_rx.recv() => match res {
+                Ok(msg) => {
+                    let msg = Arc::<ChannelMsg>::from(msg);
+                    let data = match msg.as_ref() {
+                        ChannelMsg::Data { data } | ChannelMsg::ExtendedData { data, .. } => {
+                            data.clone()
+                        }
+                        ChannelMsg::Eof | ChannelMsg::Close => break,
+                        _ => continue,
+                    };
+                    if let Err(error) = ws_tx.send(Message::Binary(data)).await {
+                        tracing::debug!(peer_addr, %error, "error sending message to websocket, likely disconnected");
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(count)) => {
+                    tracing::warn!(%machine_id, peer_addr, "websocket client lagged by {count} messages");
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    tracing::debug!(peer_addr, "BMC connection closed");
+                    break;
+                }
+            },
+
+            res = ws_rx.next() => {
+                let channel_msg = match res {
+                    Some(Ok(Message::Binary(data))) => ChannelMsg::Data { data },
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str(text.as_str()) {
+                        Ok(ClientMessage::Input { data }) => ChannelMsg::Data { data: data.into() },
+                        Ok(ClientMessage::Resize { cols, rows }) => ChannelMsg::WindowChange {
+                            col_width: cols,
+                            row_height: rows,
+                            pix_width: 0,
+                            pix_height: 0,
+                        },
+                        Ok(ClientMessage::TakeWriteLock) => {
+                            attached.take_write_lock();
+                            continue;
+                        }
+                        Err(error) => {
+                            tracing::debug!(peer_addr, %error, "ignoring invalid websocket message");
+                            continue;
+                        }
+                    },
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Err(error)) => {
+                        tracing::debug!(peer_addr, %error, "error reading from websocket");
+                        break;
+                    }
+                };
+
+                let recorder_event = match &channel_msg {
+                    ChannelMsg::Data { data } => RecorderEvent::Input(data.clone()),
+                    ChannelMsg::WindowChange { col_width, row_height, .. } => {
+                        RecorderEvent::Resize {
+                            col_width: *col_width,
+                            row_height: *row_height,
+                        }
+                    }
+                    _ => continue,
+                };
+
+                // Only the writer's input (and terminal size) is sent to the BMC.
+                let is_writer = match &channel_msg {
+                    ChannelMsg::Data { .. } => match attached.write_access() {
+                        WriteAccess::Granted => {
+                            read_only_notified = false;
+                            true
+                        }
+                        WriteAccess::ReadOnly { writer } => {
+                            if !read_only_notified {
+                                read_only_notified = true;
+                                let notice = format!(
+                                    "\r\n--- Read-only: {writer} has the write lock ---\r\n"
+                                );
+                                ws_tx.send(Message::Binary(notice.into())).await.ok();
+                            }
+                            false
+                        }
+                    },
+                    _ => attached.is_writer(),
+                };
+                if !is_writer {
+                    if let Some(recorder_tx) = &recorder_tx
+                        && matches!(recorder_event, RecorderEvent::Resize { .. })
+                    {
+                        recorder_tx.send(recorder_event).ok();
+                    }
+                    continue;
+                }
+
+                if bmc_connection
+                    .to_bmc_msg_tx
+                    .send(ToBmcMessage::ChannelMsg(channel_msg))
+                    .await
+                    .is_err()
+                {
+                    tracing::debug!(peer_addr, "error writing to BMC, BMC disconnected?");
+                    break;
+                }
+                if let Some(recorder_tx) = &recorder_tx {
+                    recorder_tx.send(recorder_event).ok();
+                }
+            }
+        }
+    }
+
+    tracing::info!(%machine_id, peer_addr, "end websocket console session");
+    attached.detach();
+    ws_tx.close().await.ok();
+    if let Some(recorder_handle) = recorder_handle {
+        recorder_handle.shutdown_and_wait().await;
+    }
+}
+
+/// The console token of a request, from either an `Authorization: Bearer <token>` header or a
+/// [`TOKEN_SUBPROTOCOL_PREFIX`]ed WebSocket subprotocol. Tokens are not accepted in the URL, where
+/// they would end up in proxy and browser logs.
+fn request_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| {
+            headers
+                .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .find_map(|protocol| protocol.trim().strip_prefix(TOKEN_SUBPROTOCOL_PREFIX))
+        })
+}
+
+/// Authenticate a websocket session to `machine_string`, returning the user the token was issued
+/// to. Like SSH tenant auth (see `pubkey_auth_tenant`), access to instances is checked with
+/// carbide-api, unless the token has the admin role.
+async fn authenticate(
+    state: &WebsocketState,
+    token: Option<&str>,
+    machine_string: &str,
+) -> Result<String, TokenAuthError> {
+    let claims = state
+        .token_verifier
+        .verify(token, machine_string, &state.config)?;
+    if let Ok(instance_id) = InstanceId::from_str(machine_string)
+        && !claims.is_admin(&state.config)
+    {
+        check_instance_owner(instance_id, &claims, &state.forge_api_client).await?;
+    }
+    Ok(claims.sub)
+}
+
+/// Check with carbide-api that the instance belongs to the organization the token was issued to.
+/// The token says who carbide issued it to, but the instance may have been released since.
+async fn check_instance_owner(
+    instance_id: InstanceId,
+    claims: &ConsoleTokenClaims,
+    forge_api_client: &ForgeApiClient,
+) -> Result<(), TokenAuthError> {
+    use TokenAuthError::*;
+    let Some(tenant_organization_id) = claims.tenant_organization_id.as_deref() else {
+        return Err(NoTenantOrganization {
+            user: claims.sub.clone(),
+        });
+    };
+
+    let instance = forge_api_client
+        .find_instances_by_ids(forge::InstancesByIdsRequest {
+            instance_ids: vec![instance_id],
+        })
+        .await
+        .map_err(|tonic_status| CarbideApi {
+            instance_id,
+            tonic_status,
+        })?
+        .instances
+        .into_iter()
+        .next()
+        .ok_or(UnknownInstance { instance_id })?;
+
+    let owner = instance
+        .config
+        .as_ref()
+        .and_then(|config| config.tenant.as_ref())
+        .map(|tenant| tenant.tenant_organization_id.as_str());
+    if owner != Some(tenant_organization_id) {
+        return Err(NotInstanceOwner {
+            user: claims.sub.clone(),
+            tenant_organization_id: tenant_organization_id.to_string(),
+            instance_id,
+        });
+    }
+    Ok(())
+}
+
+/// Verifies console tokens issued by carbide's `CreateConsoleToken`: JWTs signed with the key in
+/// `websocket_token_public_key_path`.
+struct TokenVerifier {
+    decoding_key: Option<DecodingKey>,
+    validation: Validation,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsoleTokenClaims {
+    /// The user the token was issued to
+    sub: String,
+    /// The machine or instance ID the token grants access to
+    console: String,
+    #[serde(default)]
+    roles: Vec<String>,
+    /// The organization the token was issued to, for instance consoles
+    #[serde(default)]
+    tenant_organization_id: Option<String>,
+}
+
+impl ConsoleTokenClaims {
+    fn is_admin(&self, config: &Config) -> bool {
+        config
+            .admin_certificate_role
+            .as_ref()
+            .is_some_and(|role| self.roles.contains(role))
+    }
+}
+
+impl TokenVerifier {
+    fn load(config: &Config) -> Result<Self, SpawnError> {
+        let decoding_key = match &config.websocket_token_public_key_path {
+            Some(path) => {
+                let pem =
+                    std::fs::read(path).map_err(|error| SpawnError::ReadingTokenPublicKey {
+                        path: path.to_string_lossy().to_string(),
+                        error,
+                    })?;
+                Some(DecodingKey::from_ec_pem(&pem).map_err(|error| {
+                    SpawnError::InvalidTokenPublicKey {
+                        path: path.to_string_lossy().to_string(),
+                        error,
+                    }
+                })?)
+            }
+            None => None,
+        };
+        let mut validation = Validation::new(Algorithm::ES256);
+        validation.set_audience(&[&config.websocket_token_audience]);
+        Ok(Self {
+            decoding_key,
+            validation,
+        })
+    }
+
+    /// Verify the token and check that it was issued for `machine_string`. Tokens for machine IDs
+    /// must contain the admin role, while tokens for instance IDs still need to be checked against
+    /// the instance's owner.
+    fn verify(
+        &self,
+        token: Option<&str>,
+        machine_string: &str,
+        config: &Config,
+    ) -> Result<ConsoleTokenClaims, TokenAuthError> {
+        use TokenAuthError::*;
+        let token = token.ok_or(MissingToken)?;
+        let decoding_key = self.decoding_key.as_ref().ok_or(NoPublicKeyConfigured)?;
+        let claims =
+            jsonwebtoken::decode::<ConsoleTokenClaims>(token, decoding_key, &self.validation)
+                .map_err(InvalidToken)?
+                .claims;
+
+        if claims.console != machine_string {
+            return Err(WrongConsole {
+                user: claims.sub,
+                console: claims.console,
+            });
+        }
+
+        if InstanceId::from_str(machine_string).is_err() && !claims.is_admin(config) {
+            return Err(NotAdmin { user: claims.sub });
+        }
+
+        Ok(claims)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum TokenAuthError {
+    #[error("no token was provided")]
+    MissingToken,
+    #[error("no websocket_token_public_key_path is configured")]
+    NoPublicKeyConfigured,
+    #[error("invalid token: {0}")]
+    InvalidToken(jsonwebtoken::errors::Error),
+    #[error("token for {user} was issued for console {console}")]
+    WrongConsole { user: String, console: String },
+    #[error("token for {user} does not have the admin role, and can only access instances")]
+    NotAdmin { user: String },
+    #[error("token for {user} has neither the admin role nor a tenant_organization_id")]
+    NoTenantOrganization { user: String },
+    #[error("Error looking up instance ID {instance_id}: {tonic_status}")]
+    CarbideApi {
+        instance_id: InstanceId,
+        tonic_status: tonic::Status,
+    },
+    #[error("no instance with ID {instance_id}")]
+    UnknownInstance { instance_id: InstanceId },
+    #[error(
+        "token for {user} was issued to {tenant_organization_id}, which does not own instance {instance_id}"
+    )]
+    NotInstanceOwner {
+        user: String,
+        tenant_organization_id: String,
+        instance_id: InstanceId,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    use super::*;
+
+    static SIGNING_KEY: &str = include_str!("../tests/fixtures/console_token_signing_key.pem");
+    static INSTANCE_ID: &str = "2020eb71-7674-4a15-a05b-c7d73da747b4";
+    static MACHINE_ID: &str = "fm100htjtiaehv1n5vh67tbmqq4eabcjdng40f7jupsadbedhruh6rag1l0";
+
+    fn test_config() -> Config {
+        Config {
+            websocket_token_public_key_path: Some(
+                concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/fixtures/console_token_signing_key.pub.pem"
+                )
+                .into(),
+            ),
+            admin_certificate_role: Some("console-admin".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn make_token(claims: serde_json::Value) -> String {
+        let key = EncodingKey::from_ec_pem(SIGNING_KEY.as_bytes()).unwrap();
+        jsonwebtoken::encode(&Header::new(Algorithm::ES256), &claims, &key).unwrap()
+    }
+
+    fn exp() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 300
+    }
+
+    #[test]
+    fn test_tenant_token() {
+        let config = test_config();
+        let verifier = TokenVerifier::load(&config).unwrap();
+        let token = make_token(json!({
+            "sub": "tenant-user",
+            "aud": "ssh-console",
+            "exp": exp(),
+            "console": INSTANCE_ID,
+            "tenant_organization_id": "tenant-org",
+        }));
+
+        let claims = verifier.verify(Some(&token), INSTANCE_ID, &config).unwrap();
+        assert_eq!(claims.sub, "tenant-user");
+        assert_eq!(claims.tenant_organization_id.as_deref(), Some("tenant-org"));
+        assert!(!claims.is_admin(&config));
+        // Scoped to a single console
+        assert!(matches!(
+            verifier.verify(
+                Some(&token),
+                "00000000-0000-0000-0000-000000000000",
+                &config
+            ),
+            Err(TokenAuthError::WrongConsole { .. })
+        ));
+        assert!(matches!(
+            verifier.verify(None, INSTANCE_ID, &config),
+            Err(TokenAuthError::MissingToken)
+        ));
+    }
+
+    #[test]
+    fn test_machine_console_requires_admin_role() {
+        let config = test_config();
+        let verifier = TokenVerifier::load(&config).unwrap();
+
+        let token = make_token(json!({
+            "sub": "someone",
+            "aud": "ssh-console",
+            "exp": exp(),
+            "console": MACHINE_ID,
+        }));
+        assert!(matches!(
+            verifier.verify(Some(&token), MACHINE_ID, &config),
+            Err(TokenAuthError::NotAdmin { .. })
+        ));
+
+        let token = make_token(json!({
+            "sub": "admin",
+            "aud": "ssh-console",
+            "exp": exp(),
+            "console": MACHINE_ID,
+            "roles": ["console-admin"],
+        }));
+        assert_eq!(
+            verifier
+                .verify(Some(&token), MACHINE_ID, &config)
+                .unwrap()
+                .sub,
+            "admin"
+        );
+    }
+
+    #[test]
+    fn test_invalid_tokens() {
+        let config = test_config();
+        let verifier = TokenVerifier::load(&config).unwrap();
+
+        let wrong_audience = make_token(json!({
+            "sub": "tenant-user",
+            "aud": "something-else",
+            "exp": exp(),
+            "console": INSTANCE_ID,
+        }));
+        let expired = make_token(json!({
+            "sub": "tenant-user",
+            "aud": "ssh-console",
+            "exp": 1000,
+            "console": INSTANCE_ID,
+        }));
+        let mut tampered = make_token(json!({
+            "sub": "tenant-user",
+            "aud": "ssh-console",
+            "exp": exp(),
+            "console": INSTANCE_ID,
+        }));
+        tampered.push('A');
+
+        for token in [wrong_audience, expired, tampered] {
+            assert!(matches!(
+                verifier.verify(Some(&token), INSTANCE_ID, &config),
+                Err(TokenAuthError::InvalidToken(_))
+            ));
+        }
+
+        // Without a configured public key, nothing is accepted.
+        let config = Config {
+            websocket_token_public_key_path: None,
+            ..test_config()
+        };
+        let verifier = TokenVerifier::load(&config).unwrap();
+        assert!(matches!(
+            verifier.verify(Some("token"), INSTANCE_ID, &config),
+            Err(TokenAuthError::NoPublicKeyConfigured)
+        ));
+    }
+
+    #[test]
+    fn test_request_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_token(&headers), None);
+
+        headers.insert(
+            header::SEC_WEBSOCKET_PROTOCOL,
+            "carbide-console, carbide-console-token.abc.def"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(request_token(&headers), Some("abc.def"));
+
+        // The Authorization header takes precedence
+        headers.insert(header::AUTHORIZATION, "Bearer ghi.jkl".parse().unwrap());
+        assert_eq!(request_token(&headers), Some("ghi.jkl"));
+
+        headers.insert(header::AUTHORIZATION, "Basic Zm9vOmJhcg==".parse().unwrap());
+        headers.insert(
+            header::SEC_WEBSOCKET_PROTOCOL,
+            "carbide-console".parse().unwrap(),
+        );
+        assert_eq!(request_token(&headers), None);
+    }
+
+    #[test]
+    fn test_client_messages() {
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"input","data":"ls\r"}"#).unwrap(),
+            ClientMessage::Input {
+                data: "ls\r".to_string()
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"resize","cols":120,"rows":40}"#)
+                .unwrap(),
+            ClientMessage::Resize {
+                cols: 120,
+                rows: 40
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"take_write_lock"}"#).unwrap(),
+            ClientMessage::TakeWriteLock
+        );
+    }
+}
@@ -14,18 +14,27 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use eyre::Context;
-use futures_util::FutureExt;
+use futures_util::{FutureExt, SinkExt, StreamExt};
 use lazy_static::lazy_static;
 use russh::ChannelMsg;
 use russh::keys::PrivateKeyWithHashAlg;
+use tokio::net::TcpStream;
 use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::{mpsc, oneshot};
 use tokio::time::Instant;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::{Request, Response};
+use tokio_tungstenite::tungstenite::http::header::{AUTHORIZATION, SEC_WEBSOCKET_PROTOCOL};
+use tokio_tungstenite::tungstenite::{self, Message};
 
 mod util;
 
@@ -37,6 +46,7 @@ use crate::util::ssh_client::PermissiveSshClient;
 use crate::util::{BaselineTestAssertion, MockBmcType, run_baseline_test_environment};
 
 static TENANT_SSH_PUBKEY: &str = include_str!("fixtures/tenant_ssh_key.pub");
+static CONSOLE_TOKEN_SIGNING_KEY: &str = include_str!("fixtures/console_token_signing_key.pem");
 
 lazy_static! {
     static ref TENANT_SSH_KEY_PATH: PathBuf =
@@ -390,6 +400,224 @@ async fn test_ssh_console_log_rotation() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_websocket_console() -> eyre::Result<()> {
+    if std::env::var("REPO_ROOT").is_err() {
+        tracing::info!("Skipping running ssh-console integration tests, as REPO_ROOT is not set");
+        return Ok(());
+    }
+    let Some(env) = run_baseline_test_environment(vec![MockBmcType::Ssh]).await? else {
+        return Ok(());
+    };
+
+    // Run new ssh-console
+    let handle = ssh_console_test_helper::spawn(env.mock_api_server.addr.port(), None).await?;
+    let mock_host = &env.mock_hosts[0];
+    let instance_id = mock_host.instance_id.to_string();
+    let url = format!(
+        "wss://localhost:{}/console/{instance_id}",
+        handle.websocket_address.port()
+    );
+    let tenant_token = console_token(&instance_id, &mock_host.tenant_organization_id);
+
+    // A valid token for the instance is still rejected if carbide-api says the instance belongs to
+    // another organization.
+    let mut request = url.as_str().into_client_request()?;
+    request.headers_mut().insert(
+        AUTHORIZATION,
+        format!("Bearer {}", console_token(&instance_id, "other-org")).parse()?,
+    );
+    match connect_websocket_console(handle.websocket_address, request).await {
+        Err(tungstenite::Error::Http(response)) => assert_eq!(response.status(), 401),
+        Err(error) => panic!("Unexpected error connecting with another org's token: {error}"),
+        Ok(_) => panic!("Connecting with another org's token succeeded, it should have failed"),
+    }
+
+    // Tokens are not accepted in the URL
+    let request = format!("{url}?token={tenant_token}").into_client_request()?;
+    match connect_websocket_console(handle.websocket_address, request).await {
+        Err(tungstenite::Error::Http(response)) => assert_eq!(response.status(), 401),
+        Err(error) => panic!("Unexpected error connecting with a token in the URL: {error}"),
+        Ok(_) => panic!("Connecting with a token in the URL succeeded, it should have failed"),
+    }
+
+    // Browsers pass the token as a subprotocol
+    let mut request = url.as_str().into_client_request()?;
+    request.headers_mut().insert(
+        SEC_WEBSOCKET_PROTOCOL,
+        format!("carbide-console, carbide-console-token.{tenant_token}").parse()?,
+    );
+    let (websocket, response) = connect_websocket_console(handle.websocket_address, request)
+        .await
+        .context("Error connecting to websocket console")?;
+    assert_eq!(
+        response
+            .headers()
+            .get(SEC_WEBSOCKET_PROTOCOL)
+            .and_then(|value| value.to_str().ok()),
+        Some("carbide-console")
+    );
+    let (mut websocket_tx, mut websocket_rx) = websocket.split();
+
+    websocket_tx
+        .send(Message::text(r#"{"type":"resize","cols":120,"rows":40}"#))
+        .await?;
+
+    // Send newlines until the prompt appears
+    let prompt = format!("root@{} # ", mock_host.machine_id);
+    let mut output = Vec::new();
+    let mut newline_interval = tokio::time::interval(Duration::from_secs(1));
+    let wait_for_prompt_timeout = Instant::now() + Duration::from_secs(30);
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(wait_for_prompt_timeout) => {
+                panic!(
+                    "Timed out waiting for prompt over websocket, got: {}",
+                    String::from_utf8_lossy(&output)
+                );
+            }
+            _ = newline_interval.tick() => {
+                websocket_tx
+                    .send(Message::text(r#"{"type":"input","data":"\r"}"#))
+                    .await?;
+            }
+            res = websocket_rx.next() => match res {
+                Some(Ok(Message::Binary(data))) => {
+                    output.extend_from_slice(&data);
+                    if output.windows(prompt.len()).any(|w| w == prompt.as_bytes()) {
+                        break;
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(error)) => return Err(error.into()),
+                None => panic!("Websocket closed before the prompt appeared"),
+            },
+        }
+    }
+
+    websocket_tx.send(Message::Close(None)).await?;
+
+    // The session is recorded as the token's user, with its resize and input.
+    let recordings_dir = handle
+        .logs_dir
+        .path()
+        .join("recordings")
+        .join(mock_host.machine_id.to_string());
+    let wait_for_recording_timeout = Instant::now() + Duration::from_secs(10);
+    let metadata = loop {
+        let ended = std::fs::read_dir(&recordings_dir)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                serde_json::from_slice::<serde_json::Value>(&std::fs::read(path).ok()?).ok()
+            })
+            .find(|metadata| metadata["user"] == "tenant-user" && !metadata["ended_at"].is_null());
+        if let Some(metadata) = ended {
+            break metadata;
+        }
+        assert!(
+            Instant::now() < wait_for_recording_timeout,
+            "Recording of the websocket session never ended"
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    };
+    let recording_path = recordings_dir.join(
+        metadata["file_name"]
+            .as_str()
+            .expect("recording metadata has no file_name"),
+    );
+    let recording = std::fs::read_to_string(&recording_path)
+        .with_context(|| format!("error reading recording at {}", recording_path.display()))?;
+    let events = recording
+        .lines()
+        .skip(1) // header
+        .map(serde_json::from_str::<(f64, String, String)>)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(
+        events
+            .iter()
+            .any(|(_, code, data)| code == "r" && data == "120x40"),
+        "recording does not contain the resize:\n{recording}"
+    );
+    assert!(
+        events
+            .iter()
+            .any(|(_, code, data)| code == "i" && data == "\r"),
+        "recording does not contain the input:\n{recording}"
+    );
+
+    // Console output seen over the websocket is logged like any other.
+    handle.spawn_handle.shutdown_and_wait().await;
+    let log_path = handle
+        .logs_dir
+        .path()
+        .join(format!("{}_{}.log", mock_host.machine_id, mock_host.bmc_ip));
+    let logs = std::fs::read_to_string(&log_path)
+        .with_context(|| format!("error reading log file at {}", log_path.display()))?;
+    assert!(
+        logs.contains(&prompt),
+        "{} does not contain the prompt:\n{logs}",
+        log_path.display()
+    );
+
+    Ok(())
+}
+
+fn console_token(instance_id: &str, tenant_organization_id: &str) -> String {
+    let exp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_secs()
+        + 300;
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::ES256),
+        &serde_json::json!({
+            "sub": "tenant-user",
+            "aud": "ssh-console",
+            "exp": exp,
+            "console": instance_id,
+            "tenant_organization_id": tenant_organization_id,
+        }),
+        &jsonwebtoken::EncodingKey::from_ec_pem(CONSOLE_TOKEN_SIGNING_KEY.as_bytes())
+            .expect("invalid console token signing key fixture"),
+    )
+    .expect("error signing console token")
+}
+
+/// Connect to the websocket console at `addr` over TLS, trusting the CA which issued ssh-console's
+/// certificate.
+async fn connect_websocket_console(
+    addr: SocketAddr,
+    request: Request,
+) -> Result<(WebSocketStream<TlsStream<TcpStream>>, Response), tungstenite::Error> {
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in rustls_pemfile::certs(&mut std::fs::read(&*util::fixtures::API_CA_CERT)?.as_slice())
+    {
+        roots
+            .add(cert?)
+            .expect("invalid certificate in the API CA fixture");
+    }
+    let tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::aws_lc_rs::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .expect("default protocol versions are supported")
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+
+    let stream = TlsConnector::from(Arc::new(tls_config))
+        .connect(
+            "localhost"
+                .try_into()
+                .expect("localhost is a valid server name"),
+            TcpStream::connect(addr).await?,
+        )
+        .await?;
+    tokio_tungstenite::client_async(request, stream).await
+}
+
 #[ctor::ctor(unsafe)]
 fn setup_test_logging() {
     api_test_helper::setup_logging()
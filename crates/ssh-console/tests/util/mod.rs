@@ -78,6 +78,10 @@ pub mod fixtures {
             .join("dev/certs/localhost/client.key")
             .canonicalize()
             .unwrap();
+        pub static ref CONSOLE_TOKEN_PUBLIC_KEY: PathBuf = REPO_ROOT
+            .join("crates/ssh-console/tests/fixtures/console_token_signing_key.pub.pem")
+            .canonicalize()
+            .unwrap();
     }
 }
 
@@ -164,6 +168,7 @@ pub async fn run_baseline_test_environment(
             .map(|(bmc_handle, machine_id)| MockHost {
                 machine_id: *machine_id,
                 instance_id: Uuid::new_v4(),
+                tenant_organization_id: "tenant-org".to_string(),
                 tenant_public_key: TENANT_SSH_PUBKEY.to_string(),
                 sys_vendor: match &bmc_handle {
                     MockBmcHandle::Ssh(_) => "Dell",
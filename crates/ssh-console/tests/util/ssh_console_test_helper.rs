@@ -26,7 +26,8 @@ use ssh_console::config::Defaults;
 use temp_dir::TempDir;
 
 use crate::util::fixtures::{
-    API_CA_CERT, API_CLIENT_CERT, API_CLIENT_KEY, AUTHORIZED_KEYS_PATH, SSH_HOST_KEY,
+    API_CA_CERT, API_CLIENT_CERT, API_CLIENT_KEY, AUTHORIZED_KEYS_PATH, CONSOLE_TOKEN_PUBLIC_KEY,
+    SSH_HOST_KEY,
 };
 
 #[derive(Default)]
@@ -57,6 +58,24 @@ pub async fn spawn(
             .expect("No socket available")
     };
 
+    let recordings_address = {
+        // Pick an open port
+        let l = TcpListener::bind("127.0.0.1:0")?;
+        l.local_addr()?
+            .to_socket_addrs()?
+            .next()
+            .expect("No socket available")
+    };
+
+    let websocket_address = {
+        // Pick an open port
+        let l = TcpListener::bind("127.0.0.1:0")?;
+        l.local_addr()?
+            .to_socket_addrs()?
+            .next()
+            .expect("No socket available")
+    };
+
     let logs_dir = TempDir::new().context("error creating temp dir for console logs")?;
 
     let config = ssh_console::config::Config {
@@ -81,6 +100,13 @@ pub async fn spawn(
         api_poll_interval: Duration::from_secs(1),
         console_logging_enabled: true,
         console_logs_path: logs_dir.path().to_path_buf(),
+        session_recording_enabled: true,
+        session_recordings_path: logs_dir.path().join("recordings"),
+        session_recordings_max_per_machine: Defaults::session_recordings_max_per_machine(),
+        session_recordings_max_age: Defaults::session_recordings_max_age(),
+        recordings_address,
+        admin_root_ca_path: None,
+        admin_cert_issuer_cns: vec![],
         override_bmc_ssh_host: None,
         // Eagerly retry if the connection was only open a short while (needed for tests to avoid
         // long backoff intervals.)
@@ -100,6 +126,12 @@ pub async fn spawn(
         log_rotate_max_size: Size::from_kib(10),
         hosts: true,
         openssh_certificate_authorization: ssh_console::config::Defaults::cert_authorization(),
+        console_triggers_enabled: false,
+        console_triggers: ssh_console::config::Defaults::console_triggers(),
+        websocket_enabled: true,
+        websocket_address,
+        websocket_token_public_key_path: Some(CONSOLE_TOKEN_PUBLIC_KEY.clone()),
+        websocket_token_audience: ssh_console::config::Defaults::websocket_token_audience(),
     };
 
     let spawn_handle = ssh_console::spawn(config).await?;
@@ -107,6 +139,7 @@ pub async fn spawn(
     Ok(NewSshConsoleHandle {
         addr: listen_address,
         metrics_address,
+        websocket_address,
         // Make sure the logs dir doesn't drop.
         logs_dir,
         spawn_handle,
@@ -116,6 +149,7 @@ pub async fn spawn(
 pub struct NewSshConsoleHandle {
     pub addr: SocketAddr,
     pub metrics_address: SocketAddr,
+    pub websocket_address: SocketAddr,
     pub logs_dir: TempDir,
     pub spawn_handle: ssh_console::SpawnHandle,
 }
@@ -15,10 +15,11 @@
  * limitations under the License.
  */
 
+use std::collections::HashMap;
 use std::time::Duration;
 
 /// General settings for state controller iterations
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IterationConfig {
     /// Configures the desired duration for one state controller iteration
     ///
@@ -55,6 +56,39 @@ pub struct IterationConfig {
     /// The duration of this needs to be longer than the time between state handler
     /// invocations for the object
     pub metric_hold_time: std::time::Duration,
+
+    /// Configures the maximum time objects are expected to stay in a given state.
+    ///
+    /// Keys are the state names which are reported via `StateControllerIO::metric_state_names`,
+    /// either as `state` or as `state.substate`. If both are configured, the more
+    /// specific `state.substate` limit applies.
+    ///
+    /// Objects which stay in a state for longer than the limit get a health alert
+    /// attached, which is cleared once the object leaves the state.
+    pub state_dwell_time_limits: HashMap<String, Duration>,
+
+    /// Configures how quickly queued objects gain priority while they are waiting
+    /// to get processed.
+    ///
+    /// For every elapsed interval, the effective priority of a queued object is
+    /// raised by one priority class. This prevents objects with a low priority
+    /// from starving in case higher priority objects are constantly enqueued.
+    pub priority_aging_interval: Duration,
+}
+
+impl IterationConfig {
+    /// Returns the maximum time objects are expected to stay in the given state,
+    /// if a limit is configured for it
+    pub fn dwell_time_limit(&self, state: &str, substate: &str) -> Option<Duration> {
+        if !substate.is_empty()
+            && let Some(limit) = self
+                .state_dwell_time_limits
+                .get(&format!("{state}.{substate}"))
+        {
+            return Some(*limit);
+        }
+        self.state_dwell_time_limits.get(state).copied()
+    }
 }
 
 impl Default for IterationConfig {
@@ -71,6 +105,8 @@ impl Default for IterationConfig {
             processor_dispatch_interval: Duration::from_secs(2),
             metric_emission_interval: Duration::from_secs(60),
             metric_hold_time: Duration::from_secs(5 * 60),
+            state_dwell_time_limits: HashMap::new(),
+            priority_aging_interval: Duration::from_secs(5 * 60),
         }
     }
 }
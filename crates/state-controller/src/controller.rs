@@ -27,6 +27,7 @@ use crate::state_handler::StateHandlerError;
 
 mod builder;
 pub mod db;
+pub mod dry_run;
 mod enqueuer;
 pub use enqueuer::Enqueuer;
 
@@ -71,19 +72,74 @@ pub struct QueuedObject {
     /// Identifies the processor which is executing the state handler
     /// The value of this field will be NULL in case the object is not yet processed
     pub processed_by: Option<String>,
+    /// The priority class the object has been enqueued with
+    pub priority: QueuePriority,
+    /// The point in time until which handling of the object should have been started.
+    /// Objects with an expired deadline are dequeued ahead of all other objects.
+    pub deadline: Option<DateTime<Utc>>,
 }
 
 impl<'r> FromRow<'r, PgRow> for QueuedObject {
     fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
         let object_id = row.try_get("object_id")?;
         let processed_by: Option<String> = row.try_get("processed_by")?;
+        let priority: i16 = row.try_get("priority")?;
+        let deadline: Option<DateTime<Utc>> = row.try_get("deadline")?;
         Ok(QueuedObject {
             object_id,
             processed_by,
+            priority: QueuePriority::from_i16(priority),
+            deadline,
         })
     }
 }
 
+/// Describes how urgently a queued object should be handled by the state processor
+///
+/// Objects with a higher priority are dequeued first. To prevent starvation,
+/// the effective priority of an object increases by one class for each
+/// `IterationConfig::priority_aging_interval` it has been waiting in the queue.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QueuePriority {
+    /// Objects which are enqueued by the periodic enqueuer
+    #[default]
+    Periodic,
+    /// Objects which are enqueued due to a change in their state or configuration
+    Normal,
+    /// Objects which a user is actively waiting on, e.g. for an instance allocation
+    Urgent,
+}
+
+impl QueuePriority {
+    /// Returns the value which is stored in the `priority` column of queued object tables
+    pub fn as_i16(self) -> i16 {
+        match self {
+            QueuePriority::Periodic => 0,
+            QueuePriority::Normal => 1,
+            QueuePriority::Urgent => 2,
+        }
+    }
+
+    /// Converts the value of the `priority` column back into a priority.
+    /// Unknown values are mapped to the closest known priority class.
+    pub fn from_i16(value: i16) -> Self {
+        match value {
+            i16::MIN..=0 => QueuePriority::Periodic,
+            1 => QueuePriority::Normal,
+            _ => QueuePriority::Urgent,
+        }
+    }
+
+    /// Returns the name of the priority class which is used in metrics
+    pub fn metric_name(self) -> &'static str {
+        match self {
+            QueuePriority::Periodic => "periodic",
+            QueuePriority::Normal => "normal",
+            QueuePriority::Urgent => "urgent",
+        }
+    }
+}
+
 /// The object static controller evaluates the current state of all objects of a
 /// certain type in a Forge site, and decides which actions the system should
 /// undertake to bring the state inline with the state users requested.
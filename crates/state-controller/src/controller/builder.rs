@@ -25,12 +25,15 @@ use tokio_util::sync::CancellationToken;
 
 use crate::config::IterationConfig;
 use crate::controller::StateController;
+use crate::controller::dry_run::DryRunController;
 use crate::controller::periodic_enqueuer::{EnqueuerMetricsEmitter, PeriodicEnqueuer};
 use crate::controller::processor::{ProcessorMetricsEmitter, StateProcessor};
 use crate::io::StateControllerIO;
 use crate::metrics::MetricHolder;
 use crate::state_change_emitter::StateChangeEmitter;
-use crate::state_handler::{NoopStateHandler, StateHandler, StateHandlerContextObjects};
+use crate::state_handler::{
+    DryRunServices, NoopStateHandler, StateHandler, StateHandlerContextObjects,
+};
 
 /// The return value of `[Builder::build_internal]`
 struct BuildOrSpawn<IO: StateControllerIO> {
@@ -133,6 +136,42 @@ impl<IO: StateControllerIO> Builder<IO> {
         Ok(())
     }
 
+    /// Builds a [`DryRunController`] which evaluates the configured state handler
+    /// for all objects without applying any outcome.
+    ///
+    /// Only the database, services, IO, state handler and concurrency settings are used.
+    /// The work lock and processor ID are ignored, since the dry-run does not
+    /// take part in the object queue.
+    pub fn build_for_dry_run(mut self) -> Result<DryRunController<IO>, StateControllerBuildError>
+    where
+        <IO::ContextObjects as StateHandlerContextObjects>::Services: DryRunServices,
+    {
+        let pool = self
+            .database
+            .take()
+            .ok_or(StateControllerBuildError::MissingArgument("database"))?;
+
+        let handler_services = self
+            .services
+            .take()
+            .ok_or(StateControllerBuildError::MissingArgument("services"))?;
+
+        if self.iteration_config.max_concurrency == 0 {
+            return Err(StateControllerBuildError::MissingArgument(
+                "max_concurrency",
+            ));
+        }
+
+        Ok(DryRunController {
+            pool,
+            io: self.io.unwrap_or_default(),
+            handler_services: Arc::new(handler_services.for_dry_run()),
+            state_handler: self.state_handler,
+            max_object_handling_time: self.iteration_config.max_object_handling_time,
+            max_concurrency: self.iteration_config.max_concurrency,
+        })
+    }
+
     /// Builds a [`StateController`] with all configured options
     fn build_internal(
         mut self,
@@ -184,7 +223,7 @@ impl<IO: StateControllerIO> Builder<IO> {
             work_lock_manager_handle,
             cancel_token: cancel_token.clone(),
             metric_emitter: period_enqueuer_metric_emitter,
-            iteration_config: self.iteration_config,
+            iteration_config: self.iteration_config.clone(),
             io: self.io.clone().unwrap_or_default(),
         };
 
@@ -204,7 +243,7 @@ impl<IO: StateControllerIO> Builder<IO> {
         let processor = StateProcessor::<IO> {
             pool: database,
             cancel_token,
-            iteration_config: self.iteration_config,
+            iteration_config: Arc::new(self.iteration_config),
             handler_services: services,
             io: self.io.unwrap_or_default(),
             state_handler: self.state_handler.clone(),
@@ -17,12 +17,14 @@
 
 //! Database access methods used in the StateController framework
 
+use chrono::{DateTime, Utc};
 use db::work_lock_manager::{AcquireLockError, WorkLockManagerHandle};
 use db::{BIND_LIMIT, DatabaseError};
 use sqlx::{PgConnection, PgPool};
 
 use crate::controller::{
-    ControllerIteration, ControllerIterationId, LockedControllerIteration, QueuedObject,
+    ControllerIteration, ControllerIterationId, LockedControllerIteration, QueuePriority,
+    QueuedObject,
 };
 
 /// Inserts a new entry into the iteration table
@@ -110,10 +112,15 @@ pub enum LockIterationTableError {
 /// Enqueues object IDs for processing into the queued objects table with name `table_id`
 /// If the object is enqueued, then keep the current entry. That guarantees that the object will be processed
 /// with the oldest possible run id and that the processed_by field won't get lost.
+/// The priority and deadline of an existing entry are only ever raised or tightened.
+///
+/// Returns the amount of objects which have not been enqueued before.
 pub async fn queue_objects(
     txn: &mut PgConnection,
     table_id: &str,
     queued_objects: &[String],
+    priority: QueuePriority,
+    deadline: Option<DateTime<Utc>>,
 ) -> Result<usize, DatabaseError> {
     // Object IDs need to be sorted in order to avoid a deadlock on concurrent calls to this
     // method.
@@ -142,20 +149,30 @@ pub async fn queue_objects(
     for queued_objects in sorted.chunks(OBJECTS_PER_QUERY) {
         let mut builder = sqlx::QueryBuilder::new("INSERT INTO ");
         builder.push(table_id);
-        builder.push("(object_id)");
+        builder.push("(object_id, priority, deadline)");
 
         builder.push_values(queued_objects, |mut b, object_id| {
-            b.push_bind(object_id);
+            b.push_bind(object_id)
+                .push_bind(priority.as_i16())
+                .push_bind(deadline);
         });
 
-        builder.push("ON CONFLICT (object_id) DO NOTHING");
-        let query = builder.build();
+        builder.push(" ON CONFLICT (object_id) DO UPDATE SET ");
+        builder.push(format!(
+            "priority = GREATEST({table_id}.priority, EXCLUDED.priority), \
+             deadline = LEAST({table_id}.deadline, EXCLUDED.deadline) \
+             WHERE EXCLUDED.priority > {table_id}.priority \
+             OR (EXCLUDED.deadline IS NOT NULL AND ({table_id}.deadline IS NULL OR EXCLUDED.deadline < {table_id}.deadline))"
+        ));
+        // xmax is only 0 for rows which got freshly inserted
+        builder.push(" RETURNING (xmax = 0) AS inserted");
+        let query = builder.build_query_scalar::<bool>();
 
-        let result = query
-            .execute(&mut *txn)
+        let inserted = query
+            .fetch_all(&mut *txn)
             .await
             .map_err(|e| DatabaseError::new("StateController::queue_object", e))?;
-        num_enqueued += result.rows_affected() as usize;
+        num_enqueued += inserted.into_iter().filter(|inserted| *inserted).count();
     }
 
     Ok(num_enqueued)
@@ -187,12 +204,20 @@ pub async fn acquire_queued_objects(
     count: u32, // u32 to avoid u64 numbers getting passed that are not valid in postgres
     processor_id: &str,
     max_outdated: std::time::Duration,
+    priority_aging_interval: std::time::Duration,
 ) -> Result<Vec<QueuedObject>, DatabaseError> {
-    // Grab the oldest ones first
+    // Objects with an expired deadline are grabbed first. Afterwards objects are
+    // ordered by their priority, which is raised by one class for every `priority_aging_interval`
+    // the object is waiting. That prevents low priority objects from starving.
+    // Within the same effective priority, the oldest ones are grabbed first.
     let query = format!(
         "WITH dequeued_ids AS (
             SELECT object_id FROM {table_id} WHERE (processed_by IS NULL OR processing_started_at + $1::interval < now())
-            ORDER BY processing_started_at ASC
+            ORDER BY
+                (deadline IS NOT NULL AND deadline <= now()) DESC,
+                priority + FLOOR(EXTRACT(EPOCH FROM (now() - processing_started_at)) / $3::float8) DESC,
+                deadline ASC NULLS LAST,
+                processing_started_at ASC
             FOR UPDATE SKIP LOCKED
             LIMIT {count}
         )
@@ -202,6 +227,7 @@ pub async fn acquire_queued_objects(
     let result = sqlx::query_as(&query)
         .bind(max_outdated)
         .bind(processor_id)
+        .bind(priority_aging_interval.as_secs_f64().max(1.0))
         .fetch_all(txn)
         .await
         .map_err(|e| DatabaseError::new("StateController::acquire_queued_objects", e))?;
@@ -0,0 +1,317 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (c) 2026 NVIDIA CORPORATION & AFFILIATES. All rights reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Read-only ("what-if") evaluation of state handlers
+//!
+//! A [`DryRunController`] runs the configured state handler for every object
+//! that the state controller manages, and reports which outcome the handler
+//! would have produced. Nothing that the handler returns is applied:
+//! - Transactions returned by the handler are rolled back
+//! - Writes enqueued in the [`DbWriteBatch`] and the proposed state transition are
+//!   applied to a transaction which is always rolled back, so that they are still
+//!   validated against the database
+//! - Outcomes are not persisted
+//! - No state change events are emitted and no metrics are recorded
+//!
+//! Handlers are passed the services returned by
+//! [`DryRunServices::for_dry_run`](crate::state_handler::DryRunServices::for_dry_run),
+//! which stub out all external side effects.
+
+use std::panic::Location;
+use std::sync::Arc;
+
+use ::db::DatabaseError;
+use futures::StreamExt;
+
+use crate::db_write_batch::DbWriteBatch;
+use crate::io::StateControllerIO;
+use crate::state_handler::{
+    StateHandler, StateHandlerContext, StateHandlerContextObjects, StateHandlerError,
+    StateHandlerOutcome,
+};
+
+/// The outcome a state handler would have produced for a single object
+#[derive(Debug)]
+pub enum DryRunOutcome<S> {
+    Wait {
+        /// The reason the handler is waiting
+        reason: String,
+        source_ref: &'static Location<'static>,
+    },
+    Transition {
+        /// The state the object would transition to
+        next_state: S,
+        source_ref: &'static Location<'static>,
+    },
+    DoNothing {
+        source_ref: &'static Location<'static>,
+    },
+    Deleted {
+        source_ref: &'static Location<'static>,
+    },
+    /// The state handler (or loading the object state) failed
+    Error(StateHandlerError),
+}
+
+impl<S> From<StateHandlerOutcome<S>> for DryRunOutcome<S> {
+    fn from(outcome: StateHandlerOutcome<S>) -> Self {
+        match outcome {
+            StateHandlerOutcome::Wait {
+                reason, source_ref, ..
+            } => DryRunOutcome::Wait { reason, source_ref },
+            StateHandlerOutcome::Transition {
+                next_state,
+                source_ref,
+                ..
+            } => DryRunOutcome::Transition {
+                next_state,
+                source_ref,
+            },
+            StateHandlerOutcome::DoNothing { source_ref, .. } => {
+                DryRunOutcome::DoNothing { source_ref }
+            }
+            StateHandlerOutcome::Deleted { _source_ref, .. } => DryRunOutcome::Deleted {
+                source_ref: _source_ref,
+            },
+        }
+    }
+}
+
+impl<S: std::fmt::Debug> std::fmt::Display for DryRunOutcome<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DryRunOutcome::Wait { reason, source_ref } => {
+                write!(f, "Wait(\"{reason}\") at {source_ref}")
+            }
+            DryRunOutcome::Transition {
+                next_state,
+                source_ref,
+            } => write!(f, "Transition({next_state:?}) at {source_ref}"),
+            DryRunOutcome::DoNothing { source_ref } => write!(f, "DoNothing at {source_ref}"),
+            DryRunOutcome::Deleted { source_ref } => write!(f, "Deleted at {source_ref}"),
+            DryRunOutcome::Error(err) => write!(f, "Error({err})"),
+        }
+    }
+}
+
+impl<S> DryRunOutcome<S> {
+    /// Returns where in the state handler the outcome was produced.
+    /// Returns `None` for errors.
+    pub fn source_ref(&self) -> Option<&'static Location<'static>> {
+        match self {
+            DryRunOutcome::Wait { source_ref, .. }
+            | DryRunOutcome::Transition { source_ref, .. }
+            | DryRunOutcome::DoNothing { source_ref }
+            | DryRunOutcome::Deleted { source_ref } => Some(source_ref),
+            DryRunOutcome::Error(_) => None,
+        }
+    }
+}
+
+/// The result of evaluating the state handler for a single object
+#[derive(Debug)]
+pub struct DryRunObjectReport<IO: StateControllerIO> {
+    /// The ID of the object
+    pub object_id: IO::ObjectId,
+    /// The controller state the object is currently in.
+    /// This is `None` if loading the state failed.
+    pub current_state: Option<IO::ControllerState>,
+    /// The outcome the state handler would have produced
+    pub outcome: DryRunOutcome<IO::ControllerState>,
+    /// The amount of database writes the handler enqueued, and which have been rolled back
+    pub discarded_db_writes: usize,
+}
+
+impl<IO: StateControllerIO> std::fmt::Display for DryRunObjectReport<IO> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.current_state {
+            Some(state) => write!(f, "{}: {state:?}: {}", self.object_id, self.outcome),
+            None => write!(f, "{}: {}", self.object_id, self.outcome),
+        }
+    }
+}
+
+impl<IO: StateControllerIO> DryRunObjectReport<IO> {
+    /// Returns the state the object would transition to, if the handler
+    /// requested a transition into a different state
+    pub fn proposed_state_change(&self) -> Option<&IO::ControllerState> {
+        match &self.outcome {
+            DryRunOutcome::Transition { next_state, .. }
+                if self.current_state.as_ref() != Some(next_state) =>
+            {
+                Some(next_state)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The result of a dry-run over all objects managed by a state controller
+#[derive(Debug)]
+pub struct DryRunReport<IO: StateControllerIO> {
+    /// Per-object results, in the order returned by [`StateControllerIO::list_objects`]
+    pub objects: Vec<DryRunObjectReport<IO>>,
+}
+
+impl<IO: StateControllerIO> DryRunReport<IO> {
+    /// Returns the reports of all objects which would change their state
+    pub fn state_changes(&self) -> impl Iterator<Item = &DryRunObjectReport<IO>> {
+        self.objects
+            .iter()
+            .filter(|report| report.proposed_state_change().is_some())
+    }
+
+    /// Returns the reports of all objects where state handling would fail
+    pub fn errors(&self) -> impl Iterator<Item = &DryRunObjectReport<IO>> {
+        self.objects
+            .iter()
+            .filter(|report| matches!(report.outcome, DryRunOutcome::Error(_)))
+    }
+}
+
+/// Evaluates the state handler for all objects without applying any outcome.
+///
+/// Built via `StateController::builder().build_for_dry_run()`.
+pub struct DryRunController<IO: StateControllerIO> {
+    pub(super) pool: sqlx::PgPool,
+    pub(super) io: Arc<IO>,
+    /// Services with all external side effects stubbed out
+    pub(super) handler_services: Arc<<IO::ContextObjects as StateHandlerContextObjects>::Services>,
+    pub(super) state_handler: Arc<
+        dyn StateHandler<
+                State = IO::State,
+                ControllerState = IO::ControllerState,
+                ContextObjects = IO::ContextObjects,
+                ObjectId = IO::ObjectId,
+            >,
+    >,
+    pub(super) max_object_handling_time: std::time::Duration,
+    pub(super) max_concurrency: usize,
+}
+
+impl<IO: StateControllerIO> DryRunController<IO> {
+    /// Evaluates the state handler for all objects that the state controller manages
+    pub async fn run(&self) -> Result<DryRunReport<IO>, DatabaseError> {
+        let object_ids = {
+            let mut conn = self.pool.acquire().await.map_err(DatabaseError::acquire)?;
+            self.io.list_objects(&mut conn).await?
+        };
+
+        Ok(self.run_for_objects(object_ids).await)
+    }
+
+    /// Evaluates the state handler for the given set of objects
+    pub async fn run_for_objects(&self, object_ids: Vec<IO::ObjectId>) -> DryRunReport<IO> {
+        let objects = futures::stream::iter(object_ids)
+            .map(|object_id| self.evaluate_object(object_id))
+            .buffered(self.max_concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        DryRunReport { objects }
+    }
+
+    async fn evaluate_object(&self, object_id: IO::ObjectId) -> DryRunObjectReport<IO> {
+        let mut services = self.handler_services.as_ref().clone();
+        let mut metrics =
+            <IO::ContextObjects as StateHandlerContextObjects>::ObjectMetrics::default();
+        let mut current_state = None;
+        let mut discarded_db_writes = 0;
+
+        let result: Result<
+            Result<StateHandlerOutcome<_>, StateHandlerError>,
+            tokio::time::error::Elapsed,
+        > = tokio::time::timeout(self.max_object_handling_time, async {
+            // Everything the handler would write ends up in this transaction,
+            // which is rolled back once the outcome is known
+            let mut txn = self.pool.begin().await?;
+            let mut snapshot = self
+                .io
+                .load_object_state(&mut txn, &object_id)
+                .await?
+                .ok_or_else(|| StateHandlerError::MissingData {
+                    object_id: object_id.to_string(),
+                    missing: "object_state",
+                })?;
+            let controller_state = self
+                .io
+                .load_controller_state(&mut txn, &object_id, &snapshot)
+                .await?;
+            current_state = Some(controller_state.value.clone());
+
+            let mut pending_db_writes = DbWriteBatch::new();
+            let mut ctx = StateHandlerContext {
+                services: &mut services,
+                metrics: &mut metrics,
+                pending_db_writes: &mut pending_db_writes,
+            };
+
+            let handler_outcome = match self
+                .state_handler
+                .handle_object_state(&object_id, &mut snapshot, &controller_state.value, &mut ctx)
+                .await
+            {
+                Ok(mut outcome) => {
+                    if let Some(handler_txn) = outcome.take_transaction() {
+                        handler_txn.rollback().await?;
+                    }
+                    discarded_db_writes = pending_db_writes.len();
+                    pending_db_writes
+                        .apply_all(&mut txn)
+                        .await
+                        .map(|()| outcome)
+                }
+                Err(e) => Err(e),
+            };
+
+            if let Ok(StateHandlerOutcome::Transition { next_state, .. }) = &handler_outcome {
+                self.io
+                    .persist_controller_state(
+                        &mut txn,
+                        &object_id,
+                        controller_state.version,
+                        controller_state.version.increment(),
+                        next_state,
+                    )
+                    .await?;
+            }
+            txn.rollback().await?;
+
+            handler_outcome
+        })
+        .await;
+
+        let outcome = match result {
+            Ok(Ok(outcome)) => DryRunOutcome::from(outcome),
+            Ok(Err(err)) => DryRunOutcome::Error(err),
+            Err(_timeout) => DryRunOutcome::Error(StateHandlerError::Timeout {
+                object_id: object_id.to_string(),
+                state: current_state
+                    .as_ref()
+                    .map(|state| format!("{state:?}"))
+                    .unwrap_or_default(),
+            }),
+        };
+
+        DryRunObjectReport {
+            object_id,
+            current_state,
+            outcome,
+            discarded_db_writes,
+        }
+    }
+}
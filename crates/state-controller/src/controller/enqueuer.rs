@@ -16,8 +16,9 @@
  */
 
 use ::db::DatabaseError;
+use chrono::{DateTime, Utc};
 
-use super::db;
+use super::{QueuePriority, db};
 use crate::io::StateControllerIO;
 
 /// Allows to request state handling for objects of a certain type
@@ -37,12 +38,32 @@ impl<IO: StateControllerIO> Enqueuer<IO> {
 
     /// Requests state handling for the given object
     pub async fn enqueue_object(&self, object_id: &IO::ObjectId) -> Result<bool, DatabaseError> {
+        self.enqueue_object_with_priority(object_id, QueuePriority::Normal, None)
+            .await
+    }
+
+    /// Requests state handling for the given object with the given priority.
+    ///
+    /// If a `deadline` is provided and expires before the object got picked up
+    /// by a state processor, the object will be handled ahead of all other objects.
+    /// If the object is already enqueued, its priority and deadline are only
+    /// raised or tightened - but never lowered.
+    ///
+    /// Returns `true` if the object had not been enqueued before.
+    pub async fn enqueue_object_with_priority(
+        &self,
+        object_id: &IO::ObjectId,
+        priority: QueuePriority,
+        deadline: Option<DateTime<Utc>>,
+    ) -> Result<bool, DatabaseError> {
         let mut conn = self.pool.acquire().await.map_err(DatabaseError::acquire)?;
 
         let num_enqueued = db::queue_objects(
             &mut conn,
             IO::DB_QUEUED_OBJECTS_TABLE_NAME,
             &[object_id.to_string()],
+            priority,
+            deadline,
         )
         .await?;
 
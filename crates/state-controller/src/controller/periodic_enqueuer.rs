@@ -25,7 +25,9 @@ use tokio_util::sync::CancellationToken;
 use tracing::Instrument;
 
 use crate::config::IterationConfig;
-use crate::controller::{ControllerIteration, ControllerIterationId, IterationError, db};
+use crate::controller::{
+    ControllerIteration, ControllerIterationId, IterationError, QueuePriority, db,
+};
 use crate::io::StateControllerIO;
 
 /// Periodically enqueues state handling tasks for all objects that are managed by the
@@ -216,8 +218,14 @@ impl<IO: StateControllerIO> PeriodicEnqueuer<IO> {
         // The transactions for listing and enqueuing are decoupled to avoid
         // any locking side-effects
         let mut txn = self.pool.begin().await?;
-        iteration_metrics.num_enqueued_objects =
-            db::queue_objects(&mut txn, IO::DB_QUEUED_OBJECTS_TABLE_NAME, &queued_objects).await?;
+        iteration_metrics.num_enqueued_objects = db::queue_objects(
+            &mut txn,
+            IO::DB_QUEUED_OBJECTS_TABLE_NAME,
+            &queued_objects,
+            QueuePriority::Periodic,
+            None,
+        )
+        .await?;
 
         txn.commit().await?;
 
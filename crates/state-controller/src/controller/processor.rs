@@ -20,6 +20,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use ::db::DatabaseError;
+use health_report::HealthReport;
 use model::controller_outcome::PersistentStateHandlerOutcome;
 use opentelemetry::KeyValue;
 use opentelemetry::metrics::{Counter, Histogram, Meter};
@@ -27,7 +28,7 @@ use sqlx_query_tracing::SqlxQueryDataAggregation;
 use tokio_util::sync::CancellationToken;
 use tracing::Instrument;
 
-use super::db;
+use super::{QueuePriority, db};
 use crate::config::IterationConfig;
 use crate::db_write_batch::DbWriteBatch;
 use crate::io::StateControllerIO;
@@ -60,7 +61,7 @@ pub(super) struct StateProcessor<IO: StateControllerIO> {
 
     pub(super) object_metrics: HashMap<IO::ObjectId, CollectedMetrics<IO>>,
     pub(super) cancel_token: CancellationToken,
-    pub(super) iteration_config: IterationConfig,
+    pub(super) iteration_config: Arc<IterationConfig>,
     /// IDs of objects where the task handler is currently executed
     pub(super) in_flight: HashSet<IO::ObjectId>,
     /// Objects where the state handling task was finished but where the entry
@@ -400,6 +401,7 @@ impl<IO: StateControllerIO> StateProcessor<IO> {
                 capacity,
                 &self.processor_id,
                 self.iteration_config.max_object_handling_time * 3,
+                self.iteration_config.priority_aging_interval,
             )
             .await?;
             txn.commit().await?;
@@ -408,10 +410,20 @@ impl<IO: StateControllerIO> StateProcessor<IO> {
             Vec::new()
         };
 
+        let now = chrono::Utc::now();
+        let mut dispatched_per_priority: HashMap<QueuePriority, (u64, u64)> = HashMap::new();
         let objects: Vec<IO::ObjectId> = objects
             .into_iter()
             .filter_map(|object| match IO::ObjectId::from_str(&object.object_id) {
-                Ok(id) => Some(id),
+                Ok(id) => {
+                    let (dispatched, deadline_missed) =
+                        dispatched_per_priority.entry(object.priority).or_default();
+                    *dispatched += 1;
+                    if object.deadline.is_some_and(|deadline| deadline < now) {
+                        *deadline_missed += 1;
+                    }
+                    Some(id)
+                }
                 Err(_) => {
                     tracing::error!(
                         controller = IO::LOG_SPAN_CONTROLLER_NAME,
@@ -432,12 +444,16 @@ impl<IO: StateControllerIO> StateProcessor<IO> {
             self.in_flight.insert(object_id);
         }
 
-        if let Some(emitter) = &self.metric_emitter
-            && num_dispatched_tasks > 0
-        {
-            emitter
-                .dispatched_tasks_counter
-                .add(num_dispatched_tasks as u64, &[]);
+        if let Some(emitter) = &self.metric_emitter {
+            for (priority, (dispatched, deadline_missed)) in dispatched_per_priority {
+                let attrs = [KeyValue::new("priority", priority.metric_name())];
+                emitter.dispatched_tasks_counter.add(dispatched, &attrs);
+                if deadline_missed > 0 {
+                    emitter
+                        .deadline_missed_tasks_counter
+                        .add(deadline_missed, &attrs);
+                }
+            }
         }
 
         Ok(num_dispatched_tasks)
@@ -450,7 +466,7 @@ impl<IO: StateControllerIO> StateProcessor<IO> {
         let services = self.handler_services.as_ref().clone();
         let io = self.io.clone();
         let handler = self.state_handler.clone();
-        let max_object_handling_time = self.iteration_config.max_object_handling_time;
+        let iteration_config = self.iteration_config.clone();
         let metrics_emitter = self.metric_holder.emitter.clone();
         let state_change_emitter = self.state_change_emitter.clone();
         let result_sender = self.task_sender.clone();
@@ -465,7 +481,7 @@ impl<IO: StateControllerIO> StateProcessor<IO> {
                         services,
                         io,
                         handler,
-                        max_object_handling_time,
+                        iteration_config,
                         metrics_emitter,
                         state_change_emitter,
                     )
@@ -527,8 +543,16 @@ impl<IO: StateControllerIO> StateProcessor<IO> {
             .map(|id| id.to_string())
             .collect();
         let mut txn = self.pool.begin().await?;
-        let num_requeued =
-            db::queue_objects(&mut txn, IO::DB_QUEUED_OBJECTS_TABLE_NAME, &queue_objects).await?;
+        // Objects which just transitioned into a new state should advance quickly, and
+        // are therefore handled ahead of objects which are only periodically checked
+        let num_requeued = db::queue_objects(
+            &mut txn,
+            IO::DB_QUEUED_OBJECTS_TABLE_NAME,
+            &queue_objects,
+            QueuePriority::Normal,
+            None,
+        )
+        .await?;
         txn.commit().await?;
 
         self.stats_since_last_log.num_requeued_objects += num_requeued;
@@ -597,7 +621,7 @@ async fn process_object<IO: StateControllerIO>(
                 ObjectId = IO::ObjectId,
             >,
     >,
-    max_object_handling_time: std::time::Duration,
+    iteration_config: Arc<IterationConfig>,
     metrics_emitter: Option<Arc<StateProcessorMetricEmitter<IO>>>,
     state_change_emitter: Arc<StateChangeEmitter<IO::ObjectId, IO::ControllerState>>,
 ) -> ObjectHandlerMetrics<IO> {
@@ -611,7 +635,7 @@ async fn process_object<IO: StateControllerIO>(
     let result: Result<
         Result<StateHandlerOutcome<_>, StateHandlerError>,
         tokio::time::error::Elapsed,
-    > = tokio::time::timeout(max_object_handling_time, async {
+    > = tokio::time::timeout(iteration_config.max_object_handling_time, async {
         let mut txn = pool.begin().await?;
         let mut snapshot = io
             .load_object_state(&mut txn, &object_id)
@@ -633,6 +657,13 @@ async fn process_object<IO: StateControllerIO>(
         let state_sla = io.state_sla(&controller_state, &snapshot);
         metrics.common.time_in_state_above_sla = state_sla.time_in_state_above_sla;
 
+        let (state_name, substate_name) = IO::metric_state_names(&controller_state.value);
+        let exceeded_dwell_time_limit = iteration_config
+            .dwell_time_limit(state_name, substate_name)
+            .filter(|limit| metrics.common.time_in_state > *limit);
+
+        let current_dwell_time_report = io.dwell_time_health_report(&snapshot).cloned();
+
         let mut pending_db_writes = DbWriteBatch::new();
         let mut ctx = StateHandlerContext {
             services: &mut services,
@@ -696,6 +727,35 @@ async fn process_object<IO: StateControllerIO>(
 
         let is_success = handler_outcome.is_ok();
 
+        // If the object stays in its state for longer than the configured dwell
+        // time limit, attach a health alert which describes what it is waiting for
+        let dwell_time_alert = exceeded_dwell_time_limit.and_then(|limit| {
+            let (reason_label, reason) = dwell_time_exceeded_reason(&handler_outcome)?;
+            let full_state_name = if substate_name.is_empty() {
+                state_name.to_string()
+            } else {
+                format!("{state_name}.{substate_name}")
+            };
+            let report = HealthReport::state_dwell_time_exceeded(
+                full_state_name.clone(),
+                format!(
+                    "Object has been in state {full_state_name} since {}, which exceeds the limit of {}s. Handler outcome: {reason}",
+                    controller_state.version.timestamp().to_rfc3339(),
+                    limit.as_secs()
+                ),
+                controller_state.version.timestamp()
+                    + chrono::Duration::from_std(limit).unwrap_or_default(),
+            );
+            Some((reason_label, report))
+        });
+        // The report is cleared whenever the object no longer exceeds a limit - no matter
+        // whether it left the state, or the limit changed. It is only written on changes.
+        let dwell_time_report_update = dwell_time_report_changed(
+            current_dwell_time_report.as_ref(),
+            dwell_time_alert.as_ref().map(|(_, report)| report),
+        )
+        .then(|| dwell_time_alert.as_ref().map(|(_, report)| report));
+
         // If the state handler neither transitioned nor returned no error,
         // but the object is stuck in the state for longer than the defined SLA,
         // then transform the outcome into an error
@@ -719,6 +779,11 @@ async fn process_object<IO: StateControllerIO>(
                 let db_outcome =
                     PersistentStateHandlerOutcome::from_result(handler_outcome.as_ref());
                 io.persist_outcome(&mut txn, &object_id, db_outcome).await?;
+
+                if let Some(report) = dwell_time_report_update {
+                    io.persist_dwell_time_health_report(&mut txn, &object_id, report)
+                        .await?;
+                }
             }
 
             txn.commit().await.map_err(StateHandlerError::from)?;
@@ -728,12 +793,18 @@ async fn process_object<IO: StateControllerIO>(
             let mut txn = pool.begin().await?;
             let db_outcome = PersistentStateHandlerOutcome::from_result(handler_outcome.as_ref());
             io.persist_outcome(&mut txn, &object_id, db_outcome).await?;
+            if let Some(report) = dwell_time_report_update {
+                io.persist_dwell_time_health_report(&mut txn, &object_id, report)
+                    .await?;
+            }
             txn.commit().await.map_err(StateHandlerError::from)?;
         }
 
         // Only emit the next state as metric if the transaction was actually
         // committed and we are sure we reached the next state
         metrics.common.next_state = next_state;
+        metrics.common.dwell_time_limit_exceeded_reason =
+            dwell_time_alert.map(|(reason_label, _)| reason_label);
 
         handler_outcome
     })
@@ -744,7 +815,6 @@ async fn process_object<IO: StateControllerIO>(
     if let Some(next_state) = &metrics.common.next_state {
         state_change_emitter.emit(StateChangeEvent {
             object_id: &object_id,
-            #[cfg(any(test, feature = "test-support"))]
             previous_state: metrics.common.initial_state.as_ref(),
             new_state: next_state,
             timestamp: chrono::Utc::now(),
@@ -777,10 +847,46 @@ async fn process_object<IO: StateControllerIO>(
     metrics
 }
 
+/// Describes why an object which exceeded its dwell time limit did not leave its state.
+///
+/// Returns a label with bounded cardinality which is used for metrics, and a
+/// human readable description. Returns `None` if the object is leaving the state.
+fn dwell_time_exceeded_reason<S>(
+    handler_outcome: &Result<StateHandlerOutcome<S>, StateHandlerError>,
+) -> Option<(String, String)> {
+    match handler_outcome {
+        Ok(StateHandlerOutcome::Wait {
+            reason, source_ref, ..
+        }) => Some((
+            format!("{}:{}", source_ref.file(), source_ref.line()),
+            format!("Wait(\"{reason}\")"),
+        )),
+        Ok(StateHandlerOutcome::DoNothing { .. }) => {
+            Some(("do_nothing".to_string(), "DoNothing".to_string()))
+        }
+        Ok(StateHandlerOutcome::Transition { .. } | StateHandlerOutcome::Deleted { .. }) => None,
+        Err(e) => Some((e.metric_label().to_string(), e.to_string())),
+    }
+}
+
+/// Returns whether the dwell time health report attached to an object needs to be
+/// replaced by `desired`
+fn dwell_time_report_changed(
+    current: Option<&HealthReport>,
+    desired: Option<&HealthReport>,
+) -> bool {
+    match (current, desired) {
+        (None, None) => false,
+        (Some(current), Some(desired)) => current.alerts != desired.alerts,
+        _ => true,
+    }
+}
+
 #[derive(Debug)]
 pub(super) struct ProcessorMetricsEmitter {
     iteration_latency: Histogram<f64>,
     dispatched_tasks_counter: Counter<u64>,
+    deadline_missed_tasks_counter: Counter<u64>,
     completed_tasks_counter: Counter<u64>,
     requeued_tasks_counter: Counter<u64>,
     db: sqlx_query_tracing::DatabaseMetricEmitters,
@@ -805,6 +911,13 @@ impl ProcessorMetricsEmitter {
             ))
             .build();
 
+        let deadline_missed_tasks_counter = meter
+            .u64_counter(format!("{object_type}_object_tasks_deadline_missed"))
+            .with_description(format!(
+                "The amount of object handling tasks that have been dispatched after their scheduling deadline expired for objects of type {object_type}"
+            ))
+            .build();
+
         let completed_tasks_counter = meter
             .u64_counter(format!("{object_type}_object_tasks_completed"))
             .with_description(format!(
@@ -823,6 +936,7 @@ impl ProcessorMetricsEmitter {
             iteration_latency,
             db,
             dispatched_tasks_counter,
+            deadline_missed_tasks_counter,
             completed_tasks_counter,
             requeued_tasks_counter,
         }
@@ -852,6 +966,7 @@ fn emit_object_metrics_as_log<IO: StateControllerIO>(iteration_metrics: &Iterati
     let mut total_objects = 0;
     let mut states: HashMap<String, usize> = HashMap::new();
     let mut states_above_sla: HashMap<String, usize> = HashMap::new();
+    let mut states_above_dwell_time_limit: HashMap<String, usize> = HashMap::new();
     let mut error_types: HashMap<String, HashMap<String, usize>> = HashMap::new();
     for (full_state, state_metrics) in iteration_metrics.common.state_metrics.iter() {
         total_objects += state_metrics.num_objects;
@@ -874,12 +989,22 @@ fn emit_object_metrics_as_log<IO: StateControllerIO>(iteration_metrics: &Iterati
         if state_metrics.num_objects_above_sla > 0 {
             states_above_sla.insert(full_state_name.clone(), state_metrics.num_objects_above_sla);
         }
+        let num_objects_above_dwell_time_limit: usize = state_metrics
+            .num_objects_above_dwell_time_limit_per_reason
+            .values()
+            .sum();
+        if num_objects_above_dwell_time_limit > 0 {
+            states_above_dwell_time_limit
+                .insert(full_state_name.clone(), num_objects_above_dwell_time_limit);
+        }
     }
 
     let states = serde_json::to_string(&states).unwrap_or_else(|_| "{}".to_string());
     let states_above_sla =
         serde_json::to_string(&states_above_sla).unwrap_or_else(|_| "{}".to_string());
+    let states_above_dwell_time_limit =
+        serde_json::to_string(&states_above_dwell_time_limit).unwrap_or_else(|_| "{}".to_string());
     let error_types = serde_json::to_string(&error_types).unwrap_or_else(|_| "{}".to_string());
 
-    tracing::info!(name: "state_controller_object_metrics", controller = IO::LOG_SPAN_CONTROLLER_NAME, %total_objects, %states, %states_above_sla, %error_types);
+    tracing::info!(name: "state_controller_object_metrics", controller = IO::LOG_SPAN_CONTROLLER_NAME, %total_objects, %states, %states_above_sla, %states_above_dwell_time_limit, %error_types);
 }
@@ -90,6 +90,15 @@ impl DbWriteBatch {
         self.writes.push(Box::new(op));
     }
 
+    /// Returns the amount of enqueued write operations
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
     pub async fn apply_all(self, txn: &mut PgTransaction<'_>) -> Result<(), StateHandlerError> {
         for w in self.writes {
             w.apply(txn).await?;
@@ -16,6 +16,7 @@
  */
 use config_version::{ConfigVersion, Versioned};
 use db::DatabaseError;
+use health_report::HealthReport;
 use model::StateSla;
 use model::controller_outcome::PersistentStateHandlerOutcome;
 use sqlx::PgConnection;
@@ -128,6 +129,36 @@ pub trait StateControllerIO: Send + Sync + std::fmt::Debug + 'static + Default {
         outcome: PersistentStateHandlerOutcome,
     ) -> Result<(), DatabaseError>;
 
+    /// Attaches or clears the health report which signals that an object stayed in
+    /// its current state for longer than the configured dwell time limit.
+    ///
+    /// `report` is `Some` if the limit is exceeded, and `None` if the object no
+    /// longer exceeds a limit and a previously attached report should be removed.
+    ///
+    /// The default implementation does nothing, for objects which don't carry
+    /// health reports.
+    async fn persist_dwell_time_health_report(
+        &self,
+        _txn: &mut PgConnection,
+        _object_id: &Self::ObjectId,
+        _report: Option<&HealthReport>,
+    ) -> Result<(), DatabaseError> {
+        Ok(())
+    }
+
+    /// Returns the dwell time health report which is currently attached to the object,
+    /// i.e. the last report stored via `persist_dwell_time_health_report`.
+    ///
+    /// The state controller uses this to only update the report when it changes.
+    /// The default implementation returns `None`, for objects which don't carry
+    /// health reports.
+    fn dwell_time_health_report<'a>(
+        &self,
+        _object_state: &'a Self::State,
+    ) -> Option<&'a HealthReport> {
+        None
+    }
+
     /// Returns the names that should be used in metrics for a given object state
     /// The first returned value is the value that will be used for the main `state`
     /// attribute on each metric. The 2nd value - if not empty - will be used for
@@ -47,6 +47,11 @@ pub struct CommonObjectHandlerMetrics<IO: StateControllerIO> {
     pub time_in_state: Duration,
     /// Whether the object was in `initial_state` for longer than allowed by the SLA
     pub time_in_state_above_sla: bool,
+    /// If the object was in `initial_state` for longer than the configured dwell time
+    /// limit and did not leave the state, this contains the reason it was stuck for.
+    /// The reason is either the location where the state handler returned `Wait`,
+    /// `"do_nothing"`, or the metric label of the state handling error.
+    pub dwell_time_limit_exceeded_reason: Option<String>,
     /// How long we took to execute the state handler
     pub handler_latency: Duration,
     /// If state handling fails, this contains the error
@@ -61,6 +66,7 @@ impl<IO: StateControllerIO> Default for CommonObjectHandlerMetrics<IO> {
             handler_latency: Duration::from_secs(0),
             time_in_state: Duration::from_secs(0),
             time_in_state_above_sla: false,
+            dwell_time_limit_exceeded_reason: None,
             error: None,
         }
     }
@@ -129,6 +135,12 @@ impl CommonIterationMetrics {
             if object_metrics.time_in_state_above_sla {
                 state_metrics.num_objects_above_sla += 1;
             }
+            if let Some(reason) = &object_metrics.dwell_time_limit_exceeded_reason {
+                *state_metrics
+                    .num_objects_above_dwell_time_limit_per_reason
+                    .entry(reason.clone())
+                    .or_default() += 1;
+            }
         }
 
         // If a follow-up state is defined, we exited the state and entered the next state
@@ -164,6 +176,9 @@ pub struct StateMetrics {
     pub num_objects: usize,
     /// Amount of objects that have been in the state for more than the SLA allows
     pub num_objects_above_sla: usize,
+    /// Amount of objects that have been in the state for longer than the configured
+    /// dwell time limit, per reason the objects are waiting for
+    pub num_objects_above_dwell_time_limit_per_reason: HashMap<String, usize>,
     /// Counts the errors per error type in this state
     pub handling_errors_per_type: HashMap<&'static str, usize>,
 }
@@ -278,6 +293,7 @@ impl MetricsEmitter for NoopMetricsEmitter {
 pub struct CommonMetricsEmitter<IO> {
     state_entered_counter: Counter<u64>,
     state_exited_counter: Counter<u64>,
+    state_transition_counter: Counter<u64>,
     time_in_state_histogram: Histogram<f64>,
     handler_latency_in_state_histogram: Histogram<f64>,
     _phantom_io: PhantomData<IO>,
@@ -365,6 +381,42 @@ impl<IO: StateControllerIO> MetricsEmitter for CommonMetricsEmitter<IO> {
                 .build()
         };
 
+        {
+            let metrics = shared_metrics_holder.clone();
+            meter
+                .u64_observable_gauge(format!("{object_type}_per_state_above_dwell_time_limit"))
+                .with_description(format!(
+                    "The number of {object_type} in the system which had been longer in a state than the configured dwell time limit"
+                ))
+                .with_callback(move |observer| {
+                    metrics.if_available(|metrics, attrs| {
+                        for (full_state, state_metrics) in metrics.state_metrics.iter() {
+                            for (reason, &count) in state_metrics
+                                .num_objects_above_dwell_time_limit_per_reason
+                                .iter()
+                            {
+                                observer.observe(
+                                    count as u64,
+                                    &[
+                                        attrs,
+                                        &[
+                                            KeyValue::new("state", full_state.state.to_string()),
+                                            KeyValue::new(
+                                                "substate",
+                                                full_state.substate.to_string(),
+                                            ),
+                                            KeyValue::new("reason", reason.clone()),
+                                        ],
+                                    ]
+                                    .concat(),
+                                );
+                            }
+                        }
+                    })
+                })
+                .build()
+        };
+
         {
             let metrics = shared_metrics_holder;
             meter
@@ -424,6 +476,12 @@ impl<IO: StateControllerIO> MetricsEmitter for CommonMetricsEmitter<IO> {
                 "The amount of types that objects of type {object_type} have exited a certain state"
             ))
             .build();
+        let state_transition_counter = meter
+            .u64_counter(format!("{object_type}_state_transitions"))
+            .with_description(format!(
+                "The amount of times that objects of type {object_type} have transitioned from a certain state into another state"
+            ))
+            .build();
         let time_in_state_histogram = meter
             .f64_histogram(format!("{object_type}_time_in_state"))
             .with_description(format!(
@@ -442,6 +500,7 @@ impl<IO: StateControllerIO> MetricsEmitter for CommonMetricsEmitter<IO> {
         Self {
             state_entered_counter,
             state_exited_counter,
+            state_transition_counter,
             handler_latency_in_state_histogram,
             time_in_state_histogram,
             _phantom_io: PhantomData,
@@ -475,6 +534,15 @@ impl<IO: StateControllerIO> MetricsEmitter for CommonMetricsEmitter<IO> {
             let next_substate_attr = KeyValue::new("substate", next_substate_name.to_string());
             let attrs = &[next_state_attr, next_substate_attr];
             self.state_entered_counter.add(1, attrs);
+            self.state_transition_counter.add(
+                1,
+                &[
+                    KeyValue::new("from_state", initial_state_name.to_string()),
+                    KeyValue::new("from_substate", initial_substate_name.to_string()),
+                    KeyValue::new("to_state", next_state_name.to_string()),
+                    KeyValue::new("to_substate", next_substate_name.to_string()),
+                ],
+            );
 
             let transition_record = StateTransitionRecord {
                 time_in_state: object_metrics.time_in_state,
@@ -49,6 +49,19 @@ pub struct StateHandlerContext<'a, T: StateHandlerContextObjects> {
     pub pending_db_writes: &'a mut DbWriteBatch,
 }
 
+/// Services which can be used to evaluate state handlers in dry-run mode
+/// (see [`crate::controller::dry_run`])
+pub trait DryRunServices: Sized {
+    /// Returns a copy of the services in which every operation with side effects outside
+    /// of the state controller's transaction is stubbed out, e.g. calls to BMCs, switches
+    /// or secret stores, and writes through a database pool of their own.
+    fn for_dry_run(&self) -> Self;
+}
+
+impl DryRunServices for () {
+    fn for_dry_run(&self) -> Self {}
+}
+
 /// Defines a function that will be called to determine the next step in
 /// an objects lifecycle.
 ///
@@ -24,6 +24,7 @@ use carbide_utils::test_support::test_meter::TestMeter;
 use config_version::{ConfigVersion, Versioned};
 use db::DatabaseError;
 use futures::StreamExt;
+use health_report::{HealthProbeId, HealthReport};
 use model::StateSla;
 use model::controller_outcome::PersistentStateHandlerOutcome;
 use serde::{self, Deserialize, Serialize};
@@ -33,7 +34,8 @@ use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 
 use crate::config::IterationConfig;
-use crate::controller::{self, Enqueuer, QueuedObject, StateController};
+use crate::controller::{self, Enqueuer, QueuePriority, QueuedObject, StateController};
+use crate::db_write_batch::WriteOp;
 use crate::io::StateControllerIO;
 use crate::metrics::NoopMetricsEmitter;
 use crate::state_change_emitter::{StateChangeEmitterBuilder, StateChangeEvent, StateChangeHook};
@@ -170,6 +172,8 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
         &mut txn,
         TestStateControllerIO::DB_QUEUED_OBJECTS_TABLE_NAME,
         &["0".to_string()],
+        QueuePriority::Periodic,
+        None,
     )
     .await
     .unwrap();
@@ -178,6 +182,8 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
         &mut txn,
         TestStateControllerIO::DB_QUEUED_OBJECTS_TABLE_NAME,
         &["1".to_string(), "2".to_string()],
+        QueuePriority::Periodic,
+        None,
     )
     .await
     .unwrap();
@@ -196,14 +202,20 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
             QueuedObject {
                 object_id: "0".to_string(),
                 processed_by: None,
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
             QueuedObject {
                 object_id: "1".to_string(),
                 processed_by: None,
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
             QueuedObject {
                 object_id: "2".to_string(),
                 processed_by: None,
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
         ]
     );
@@ -217,6 +229,8 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
         &mut txn,
         TestStateControllerIO::DB_QUEUED_OBJECTS_TABLE_NAME,
         &["0".to_string()],
+        QueuePriority::Periodic,
+        None,
     )
     .await
     .unwrap();
@@ -225,6 +239,8 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
         &mut txn,
         TestStateControllerIO::DB_QUEUED_OBJECTS_TABLE_NAME,
         &["3".to_string(), "2".to_string()],
+        QueuePriority::Periodic,
+        None,
     )
     .await
     .unwrap();
@@ -242,18 +258,26 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
             QueuedObject {
                 object_id: "0".to_string(),
                 processed_by: None,
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
             QueuedObject {
                 object_id: "1".to_string(),
                 processed_by: None,
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
             QueuedObject {
                 object_id: "2".to_string(),
                 processed_by: None,
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
             QueuedObject {
                 object_id: "3".to_string(),
                 processed_by: None,
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
         ]
     );
@@ -270,6 +294,7 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
         2,
         &processor_id1,
         std::time::Duration::from_secs(60),
+        std::time::Duration::from_secs(5 * 60),
     )
     .await
     .unwrap();
@@ -280,10 +305,14 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
             QueuedObject {
                 object_id: "0".to_string(),
                 processed_by: Some(processor_id1.clone()),
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
             QueuedObject {
                 object_id: "1".to_string(),
                 processed_by: Some(processor_id1.clone()),
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
         ]
     );
@@ -293,6 +322,7 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
         1,
         &processor_id2,
         std::time::Duration::from_secs(60),
+        std::time::Duration::from_secs(5 * 60),
     )
     .await
     .unwrap();
@@ -302,6 +332,8 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
         vec![QueuedObject {
             object_id: "2".to_string(),
             processed_by: Some(processor_id2.clone()),
+            priority: QueuePriority::Periodic,
+            deadline: None,
         },]
     );
 
@@ -344,14 +376,20 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
             QueuedObject {
                 object_id: "0".to_string(),
                 processed_by: Some(processor_id1.clone()),
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
             QueuedObject {
                 object_id: "2".to_string(),
                 processed_by: Some(processor_id2.clone()),
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
             QueuedObject {
                 object_id: "3".to_string(),
                 processed_by: None,
+                priority: QueuePriority::Periodic,
+                deadline: None,
             },
         ]
     );
@@ -367,6 +405,7 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
         2,
         &processor_id1,
         std::time::Duration::from_millis(500),
+        std::time::Duration::from_secs(5 * 60),
     )
     .await
     .unwrap();
@@ -391,6 +430,121 @@ async fn test_queue_objects(pool: sqlx::PgPool) -> sqlx::Result<()> {
     Ok(())
 }
 
+#[carbide_macros::sqlx_test]
+async fn test_acquire_queued_objects_by_priority(pool: sqlx::PgPool) -> sqlx::Result<()> {
+    create_test_state_controller_tables(&pool).await;
+    const TABLE: &str = TestStateControllerIO::DB_QUEUED_OBJECTS_TABLE_NAME;
+    let processor_id = "000000000001".to_string();
+    let max_outdated = std::time::Duration::from_secs(60);
+    let aging_interval = std::time::Duration::from_secs(5 * 60);
+
+    let mut txn = pool.begin().await.unwrap();
+    for (object_id, priority, deadline) in [
+        ("0", QueuePriority::Periodic, None),
+        ("1", QueuePriority::Periodic, None),
+        ("2", QueuePriority::Normal, None),
+        ("3", QueuePriority::Urgent, None),
+        (
+            "4",
+            QueuePriority::Periodic,
+            Some(chrono::Utc::now() - chrono::Duration::minutes(1)),
+        ),
+    ] {
+        let num_enqueued = controller::db::queue_objects(
+            &mut txn,
+            TABLE,
+            &[object_id.to_string()],
+            priority,
+            deadline,
+        )
+        .await
+        .unwrap();
+        assert_eq!(num_enqueued, 1);
+    }
+
+    // Enqueuing an object again can raise its priority, but never lower it
+    let num_enqueued = controller::db::queue_objects(
+        &mut txn,
+        TABLE,
+        &["0".to_string()],
+        QueuePriority::Normal,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(num_enqueued, 0);
+    let num_enqueued = controller::db::queue_objects(
+        &mut txn,
+        TABLE,
+        &["2".to_string()],
+        QueuePriority::Periodic,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(num_enqueued, 0);
+    let mut queued = controller::db::fetch_queued_objects(&mut txn, TABLE)
+        .await
+        .unwrap();
+    queued.sort_by(|a, b| a.object_id.cmp(&b.object_id));
+    let priorities: Vec<_> = queued.iter().map(|object| object.priority).collect();
+    assert_eq!(
+        priorities,
+        vec![
+            QueuePriority::Normal,
+            QueuePriority::Periodic,
+            QueuePriority::Normal,
+            QueuePriority::Urgent,
+            QueuePriority::Periodic
+        ]
+    );
+    txn.commit().await.unwrap();
+
+    // Objects with an expired deadline come first, followed by the remaining
+    // objects in order of priority
+    let mut txn = pool.begin().await.unwrap();
+    let queued = controller::db::acquire_queued_objects(
+        &mut txn,
+        TABLE,
+        2,
+        &processor_id,
+        max_outdated,
+        aging_interval,
+    )
+    .await
+    .unwrap();
+    let mut acquired: Vec<_> = queued.into_iter().map(|object| object.object_id).collect();
+    acquired.sort();
+    assert_eq!(acquired, vec!["3".to_string(), "4".to_string()]);
+    txn.commit().await.unwrap();
+
+    // Objects which have been waiting for a long time gain priority,
+    // and are acquired ahead of newer objects with a higher priority
+    let mut txn = pool.begin().await.unwrap();
+    sqlx::query(&format!(
+        "UPDATE {TABLE} SET processing_started_at = now() - interval '1 hour' WHERE object_id = '1'"
+    ))
+    .execute(&mut *txn)
+    .await
+    .unwrap();
+    let queued = controller::db::acquire_queued_objects(
+        &mut txn,
+        TABLE,
+        1,
+        &processor_id,
+        max_outdated,
+        aging_interval,
+    )
+    .await
+    .unwrap();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].object_id, "1");
+    assert_eq!(queued[0].priority, QueuePriority::Periodic);
+    txn.commit().await.unwrap();
+
+    Ok(())
+}
+
 #[derive(Debug, Default)]
 struct TestStateControllerIO {}
 
@@ -400,6 +554,7 @@ pub struct TestObject {
     pub controller_state: Versioned<TestObjectControllerState>,
     #[allow(dead_code)]
     pub controller_state_outcome: Option<PersistentStateHandlerOutcome>,
+    pub dwell_time_health_report: Option<HealthReport>,
 }
 
 impl<'r> FromRow<'r, PgRow> for TestObject {
@@ -408,6 +563,8 @@ impl<'r> FromRow<'r, PgRow> for TestObject {
             row.try_get("controller_state")?;
         let state_outcome: Option<sqlx::types::Json<PersistentStateHandlerOutcome>> =
             row.try_get("controller_state_outcome")?;
+        let dwell_time_health_report: Option<sqlx::types::Json<HealthReport>> =
+            row.try_get("dwell_time_health_report")?;
 
         Ok(TestObject {
             id: row.try_get("id")?,
@@ -416,6 +573,7 @@ impl<'r> FromRow<'r, PgRow> for TestObject {
                 row.try_get("controller_state_version")?,
             ),
             controller_state_outcome: state_outcome.map(|x| x.0),
+            dwell_time_health_report: dwell_time_health_report.map(|x| x.0),
         })
     }
 }
@@ -447,7 +605,8 @@ async fn create_test_state_controller_tables(pool: &sqlx::PgPool) {
         id             varchar NOT NULL,
         controller_state         jsonb       NOT NULL,
         controller_state_version VARCHAR(64) NOT NULL,
-        controller_state_outcome JSONB
+        controller_state_outcome JSONB,
+        dwell_time_health_report JSONB
     );",
     )
     .execute(&mut *txn)
@@ -477,7 +636,9 @@ async fn create_test_state_controller_tables(pool: &sqlx::PgPool) {
         "CREATE TABLE test_state_controller_queued_objects(
         object_id VARCHAR PRIMARY KEY,
         processed_by TEXT NULL,
-        processing_started_at timestamptz NOT NULL DEFAULT NOW()
+        processing_started_at timestamptz NOT NULL DEFAULT NOW(),
+        priority SMALLINT NOT NULL DEFAULT 0,
+        deadline timestamptz NULL DEFAULT NULL
     );",
     )
     .execute(&mut *txn)
@@ -605,6 +766,29 @@ impl StateControllerIO for TestStateControllerIO {
         Ok(())
     }
 
+    async fn persist_dwell_time_health_report(
+        &self,
+        txn: &mut PgConnection,
+        object_id: &Self::ObjectId,
+        report: Option<&HealthReport>,
+    ) -> Result<(), DatabaseError> {
+        let query = "UPDATE test_objects SET dwell_time_health_report=$1::json WHERE id=$2";
+        sqlx::query(query)
+            .bind(report.map(sqlx::types::Json))
+            .bind(object_id)
+            .execute(txn)
+            .await
+            .map_err(|e| DatabaseError::query(query, e))?;
+        Ok(())
+    }
+
+    fn dwell_time_health_report<'a>(
+        &self,
+        object_state: &'a Self::State,
+    ) -> Option<&'a HealthReport> {
+        object_state.dwell_time_health_report.as_ref()
+    }
+
     fn metric_state_names(state: &TestObjectControllerState) -> (&'static str, &'static str) {
         match state {
             TestObjectControllerState::A => ("a", ""),
@@ -1019,6 +1203,8 @@ async fn test_state_change_emitter_emits_events_on_transitions(
     let obj = create_test_object("test-obj-1".to_string(), &mut txn).await;
     txn.commit().await?;
 
+    let test_meter = TestMeter::default();
+
     // Create a channel hook to receive events deterministically
     let (hook, mut receiver) = ChannelHook::new();
 
@@ -1033,6 +1219,7 @@ async fn test_state_change_emitter_emits_events_on_transitions(
             iteration_time: Duration::from_millis(50),
             ..Default::default()
         })
+        .meter("test_objects", test_meter.meter())
         .database(pool.clone(), work_lock_manager_handle.clone())
         .processor_id(uuid::Uuid::new_v4().to_string())
         .services(Arc::new(()))
@@ -1068,6 +1255,20 @@ async fn test_state_change_emitter_emits_events_on_transitions(
         "Expected no event for do_nothing outcome"
     );
 
+    // Every transition is counted once with its source and target state
+    let transitions = test_meter.parsed_metrics("test_objects_state_transitions_total");
+    assert_eq!(transitions.len(), 2, "Unexpected metrics: {transitions:?}");
+    for (from, to) in [("a", "b"), ("b", "c")] {
+        let (_, count) = transitions
+            .iter()
+            .find(|(attrs, _)| {
+                attrs.contains(&format!(r#"from_state="{from}""#))
+                    && attrs.contains(&format!(r#"to_state="{to}""#))
+            })
+            .unwrap_or_else(|| panic!("Expected a {from} -> {to} transition: {transitions:?}"));
+        assert_eq!(count, "1");
+    }
+
     Ok(())
 }
 
@@ -1124,9 +1325,302 @@ async fn test_state_controller_manual_enqueuing(pool: sqlx::PgPool) -> eyre::Res
         vec![QueuedObject {
             object_id: "test-obj-1".to_string(),
             processed_by: None,
+            priority: QueuePriority::Normal,
+            deadline: None,
         },]
     );
     txn.commit().await.unwrap();
 
     Ok(())
 }
+
+#[carbide_macros::sqlx_test]
+async fn test_dwell_time_limit_raises_and_clears_health_alert(
+    pool: sqlx::PgPool,
+) -> eyre::Result<()> {
+    let test_meter = TestMeter::default();
+
+    create_test_state_controller_tables(&pool).await;
+    let mut join_set = JoinSet::new();
+    let cancel_token = CancellationToken::new();
+    let work_lock_manager_handle =
+        db::work_lock_manager::start(&mut join_set, pool.clone(), Default::default()).await?;
+
+    let mut txn = pool.begin().await?;
+    let obj = create_test_object("test-obj-1".to_string(), &mut txn).await;
+    txn.commit().await?;
+
+    // Objects exceed the limit for states A and C immediately
+    let mut controller = StateController::<TestStateControllerIO>::builder()
+        .iteration_config(IterationConfig {
+            iteration_time: Duration::from_millis(50),
+            processor_dispatch_interval: Duration::from_millis(50),
+            state_dwell_time_limits: HashMap::from([
+                ("a".to_string(), Duration::ZERO),
+                ("c".to_string(), Duration::ZERO),
+            ]),
+            ..Default::default()
+        })
+        .meter("test_objects", test_meter.meter())
+        .database(pool.clone(), work_lock_manager_handle.clone())
+        .processor_id(uuid::Uuid::new_v4().to_string())
+        .services(Arc::new(()))
+        .state_handler(Arc::new(TestTransitionStateHandler))
+        .build_for_manual_iterations(cancel_token)?;
+
+    let io = TestStateControllerIO::default();
+
+    // A -> B -> C. Objects which leave a state don't get an alert
+    controller.run_single_iteration().await;
+    controller.run_single_iteration().await;
+    let object = load_test_object(&pool, &obj.id).await?;
+    assert_eq!(object.controller_state.value, TestObjectControllerState::C);
+    assert!(object.dwell_time_health_report.is_none());
+
+    // The handler returns `DoNothing` in state C
+    controller.run_single_iteration().await;
+    let object = load_test_object(&pool, &obj.id).await?;
+    let report = object
+        .dwell_time_health_report
+        .expect("Expected a health report for exceeding the dwell time limit");
+    assert_eq!(report.source, HealthReport::STATE_CONTROLLER_SOURCE);
+    assert_eq!(report.alerts.len(), 1);
+    assert_eq!(
+        report.alerts[0].id,
+        HealthProbeId::state_dwell_time_exceeded()
+    );
+    assert_eq!(report.alerts[0].target.as_deref(), Some("c"));
+    assert_eq!(
+        report.alerts[0].in_alert_since,
+        Some(object.controller_state.version.timestamp())
+    );
+
+    let metrics = test_meter.parsed_metrics("test_objects_per_state_above_dwell_time_limit");
+    assert_eq!(metrics.len(), 1, "Unexpected metrics: {metrics:?}");
+    assert!(metrics[0].0.contains(r#"state="c""#));
+    assert!(metrics[0].0.contains(r#"reason="do_nothing""#));
+    assert_eq!(metrics[0].1, "1");
+
+    // The report is not rewritten while the object stays stuck
+    controller.run_single_iteration().await;
+    let object = load_test_object(&pool, &obj.id).await?;
+    assert_eq!(
+        object.dwell_time_health_report.unwrap().observed_at,
+        report.observed_at
+    );
+
+    // The alert is cleared if the state is changed outside of the handler
+    // into a state without a limit
+    let mut txn = pool.begin().await?;
+    io.persist_controller_state(
+        &mut txn,
+        &obj.id,
+        object.controller_state.version,
+        object.controller_state.version.increment(),
+        &TestObjectControllerState::B,
+    )
+    .await?;
+    txn.commit().await?;
+
+    controller.run_single_iteration().await;
+    let object = load_test_object(&pool, &obj.id).await?;
+    assert_eq!(object.controller_state.value, TestObjectControllerState::C);
+    assert!(object.dwell_time_health_report.is_none());
+
+    // Get stuck in C again
+    controller.run_single_iteration().await;
+    let object = load_test_object(&pool, &obj.id).await?;
+    assert!(object.dwell_time_health_report.is_some());
+
+    // Once the object leaves the state, the alert is cleared
+    let mut txn = pool.begin().await?;
+    io.persist_controller_state(
+        &mut txn,
+        &obj.id,
+        object.controller_state.version,
+        object.controller_state.version.increment(),
+        &TestObjectControllerState::A,
+    )
+    .await?;
+    txn.commit().await?;
+
+    controller.run_single_iteration().await;
+    let object = load_test_object(&pool, &obj.id).await?;
+    assert_eq!(object.controller_state.value, TestObjectControllerState::B);
+    assert!(object.dwell_time_health_report.is_none());
+    assert!(
+        test_meter
+            .parsed_metrics("test_objects_per_state_above_dwell_time_limit")
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+/// A write which records the outcome of an object, to observe whether enqueued
+/// writes are applied
+struct SetOutcomeWrite {
+    object_id: String,
+    query: &'static str,
+}
+
+#[async_trait::async_trait]
+impl WriteOp for SetOutcomeWrite {
+    async fn apply<'a, 't: 'a>(
+        self: Box<Self>,
+        txn: &'a mut sqlx::PgTransaction<'t>,
+    ) -> Result<(), StateHandlerError> {
+        sqlx::query(self.query)
+            .bind(&self.object_id)
+            .execute(&mut **txn)
+            .await?;
+        Ok(())
+    }
+}
+
+/// A state handler which enqueues a write running `query` and transitions from A -> B
+#[derive(Debug)]
+struct DryRunTestStateHandler {
+    query: &'static str,
+}
+
+#[async_trait::async_trait]
+impl StateHandler for DryRunTestStateHandler {
+    type State = TestObject;
+    type ControllerState = TestObjectControllerState;
+    type ObjectId = String;
+    type ContextObjects = TestStateControllerContextObjects;
+
+    async fn handle_object_state(
+        &self,
+        object_id: &String,
+        _state: &mut TestObject,
+        controller_state: &Self::ControllerState,
+        ctx: &mut StateHandlerContext<Self::ContextObjects>,
+    ) -> Result<StateHandlerOutcome<Self::ControllerState>, StateHandlerError> {
+        ctx.pending_db_writes.push(SetOutcomeWrite {
+            object_id: object_id.clone(),
+            query: self.query,
+        });
+        match controller_state {
+            TestObjectControllerState::A => Ok(StateHandlerOutcome::transition(
+                TestObjectControllerState::B,
+            )),
+            _ => Ok(StateHandlerOutcome::wait("Waiting in B or C".to_string())),
+        }
+    }
+}
+
+#[carbide_macros::sqlx_test]
+async fn test_dry_run_does_not_apply_outcomes(pool: sqlx::PgPool) -> eyre::Result<()> {
+    create_test_state_controller_tables(&pool).await;
+    let mut join_set = JoinSet::new();
+    let work_lock_manager_handle =
+        db::work_lock_manager::start(&mut join_set, pool.clone(), Default::default()).await?;
+
+    let mut txn = pool.begin().await?;
+    let obj1 = create_test_object("test-obj-1".to_string(), &mut txn).await;
+    let obj2 = create_test_object("test-obj-2".to_string(), &mut txn).await;
+    txn.commit().await?;
+
+    let (hook, mut receiver) = ChannelHook::new();
+    let emitter = StateChangeEmitterBuilder::default()
+        .hook(Box::new(hook))
+        .build();
+
+    let dry_run_controller = StateController::<TestStateControllerIO>::builder()
+        .database(pool.clone(), work_lock_manager_handle.clone())
+        .services(Arc::new(()))
+        .state_handler(Arc::new(DryRunTestStateHandler {
+            query: r#"UPDATE test_objects SET controller_state_outcome='{"outcome": "success"}' WHERE id=$1"#,
+        }))
+        .state_change_emitter(emitter)
+        .build_for_dry_run()?;
+
+    // Running the dry-run multiple times always yields the same proposal,
+    // since nothing is persisted
+    for _ in 0..2 {
+        let report = dry_run_controller.run().await?;
+        assert_eq!(report.objects.len(), 2);
+        assert_eq!(report.state_changes().count(), 2);
+        assert_eq!(report.errors().count(), 0);
+        for object in report.objects.iter() {
+            assert_eq!(object.current_state, Some(TestObjectControllerState::A));
+            assert_eq!(
+                object.proposed_state_change(),
+                Some(&TestObjectControllerState::B)
+            );
+            assert_eq!(object.discarded_db_writes, 1);
+            let source_ref = object.outcome.source_ref().unwrap();
+            assert_eq!(source_ref.file(), file!());
+            assert!(
+                object.to_string().contains(": A: Transition(B) at "),
+                "Unexpected report: {object}"
+            );
+        }
+    }
+
+    for expected in [&obj1, &obj2] {
+        let object = load_test_object(&pool, &expected.id).await?;
+        assert_eq!(object.controller_state.value, TestObjectControllerState::A);
+        assert_eq!(
+            object.controller_state.version,
+            expected.controller_state.version
+        );
+        assert!(object.controller_state_outcome.is_none());
+    }
+    let mut txn = pool.begin().await?;
+    let queued = controller::db::fetch_queued_objects(
+        &mut txn,
+        TestStateControllerIO::DB_QUEUED_OBJECTS_TABLE_NAME,
+    )
+    .await?;
+    assert!(queued.is_empty());
+    txn.commit().await?;
+
+    assert!(
+        receiver.try_recv().is_err(),
+        "Expected no state change events during a dry-run"
+    );
+
+    Ok(())
+}
+
+#[carbide_macros::sqlx_test]
+async fn test_dry_run_reports_failing_writes(pool: sqlx::PgPool) -> eyre::Result<()> {
+    create_test_state_controller_tables(&pool).await;
+    let mut join_set = JoinSet::new();
+    let work_lock_manager_handle =
+        db::work_lock_manager::start(&mut join_set, pool.clone(), Default::default()).await?;
+
+    let mut txn = pool.begin().await?;
+    let obj = create_test_object("test-obj-1".to_string(), &mut txn).await;
+    txn.commit().await?;
+
+    let dry_run_controller = StateController::<TestStateControllerIO>::builder()
+        .database(pool.clone(), work_lock_manager_handle.clone())
+        .services(Arc::new(()))
+        .state_handler(Arc::new(DryRunTestStateHandler {
+            query: "UPDATE missing_table SET id=$1",
+        }))
+        .build_for_dry_run()?;
+
+    let report = dry_run_controller
+        .run_for_objects(vec![obj.id.clone()])
+        .await;
+    assert_eq!(report.errors().count(), 1);
+    assert_eq!(report.state_changes().count(), 0);
+    assert!(report.objects[0].outcome.source_ref().is_none());
+
+    Ok(())
+}
+
+async fn load_test_object(pool: &sqlx::PgPool, object_id: &str) -> eyre::Result<TestObject> {
+    let mut txn = pool.begin().await?;
+    let object = TestStateControllerIO::default()
+        .load_object_state(&mut txn, &object_id.to_string())
+        .await?
+        .unwrap();
+    txn.commit().await?;
+    Ok(object)
+}